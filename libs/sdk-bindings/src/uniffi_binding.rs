@@ -1,35 +1,47 @@
+use std::future::Future;
 use std::sync::Arc;
 
 use anyhow::Result;
 use breez_sdk_core::lnurl::pay::{LnUrlPayResult, LnUrlPaySuccessData};
+use breez_sdk_core::lnurl::server::{LnUrlPayServerConfig, LnUrlPayServerResponse};
+use breez_sdk_core::wallets::{log_to_wallet_stream, with_wallet_context, WalletManager};
 use breez_sdk_core::{
     error::*, mnemonic_to_seed as sdk_mnemonic_to_seed, parse as sdk_parse_input,
-    parse_invoice as sdk_parse_invoice, AesSuccessActionDataDecrypted, AesSuccessActionDataResult,
-    BackupFailedData, BackupStatus, BitcoinAddressData, BreezEvent, BreezServices,
-    BuyBitcoinProvider, BuyBitcoinRequest, BuyBitcoinResponse, ChannelState, CheckMessageRequest,
+    parse_invoice as sdk_parse_invoice, AccelerateSwapDepositRequest,
+    AccelerateSwapDepositResponse, AesSuccessActionDataDecrypted, AesSuccessActionDataResult,
+    Amount, BackupFailedData, BackupStatus, BitcoinAddressData, Bolt12PaymentDetails, BreezEvent,
+    BreezServices, BudgetPeriod, BumpFeeRequest, BumpFeeResponse, BuyBitcoinProvider,
+    BuyBitcoinRequest, BuyBitcoinResponse, ChainBackend, ChannelState, CheckMessageRequest,
     CheckMessageResponse, ClosedChannelPaymentDetails, Config, ConfigureNodeRequest,
-    ConnectRequest, CurrencyInfo, EnvironmentType, EventListener, FeeratePreset, FiatCurrency,
-    GreenlightCredentials, GreenlightDeviceCredentials, GreenlightNodeConfig, HealthCheckStatus,
-    InputType, InvoicePaidDetails, LNInvoice, ListPaymentsRequest, LnPaymentDetails,
+    ConnectRequest, CreateOfferRequest, CreateOfferResponse, CurrencyInfo, CustomLsp,
+    EnvironmentType, EventListener, ExportFormat, ExportPaymentsRequest, ExportPaymentsResponse,
+    FeeratePreset, FiatAmount, FiatCurrency, GreenlightCredentials, GreenlightDeviceCredentials,
+    GreenlightNodeConfig, HealthCheckStatus, InputType, InvoicePaidDetails, LNInvoice, LNOffer,
+    LightningSweepPolicy, ListPaymentRequestsRequest, ListPaymentsRequest, LnPaymentDetails,
     LnUrlAuthError, LnUrlAuthRequestData, LnUrlCallbackStatus, LnUrlErrorData, LnUrlPayError,
-    LnUrlPayErrorData, LnUrlPayRequest, LnUrlPayRequestData, LnUrlWithdrawError,
+    LnUrlPayErrorData, LnUrlPayRequest, LnUrlPayRequestData, LnUrlVerifyStatus, LnUrlWithdrawError,
     LnUrlWithdrawRequest, LnUrlWithdrawRequestData, LnUrlWithdrawResult, LnUrlWithdrawSuccessData,
-    LocaleOverrides, LocalizedName, LogEntry, LogStream, LspInformation,
+    LocaleOverrides, LocalizedName, LockedFiatRate, LogEntry, LogStream, LspInformation,
     MaxReverseSwapAmountResponse, MessageSuccessActionData, MetadataFilter, MetadataItem, Network,
-    NodeConfig, NodeCredentials, NodeState, OnchainPaymentLimitsResponse, OpenChannelFeeRequest,
-    OpenChannelFeeResponse, OpeningFeeParams, OpeningFeeParamsMenu, PayOnchainRequest,
-    PayOnchainResponse, Payment, PaymentDetails, PaymentFailedData, PaymentStatus, PaymentType,
-    PaymentTypeFilter, PrepareOnchainPaymentRequest, PrepareOnchainPaymentResponse,
-    PrepareRedeemOnchainFundsRequest, PrepareRedeemOnchainFundsResponse, PrepareRefundRequest,
-    PrepareRefundResponse, Rate, ReceiveOnchainRequest, ReceivePaymentRequest,
-    ReceivePaymentResponse, RecommendedFees, RedeemOnchainFundsRequest, RedeemOnchainFundsResponse,
-    RefundRequest, RefundResponse, ReportIssueRequest, ReportPaymentFailureDetails,
-    ReverseSwapFeesRequest, ReverseSwapInfo, ReverseSwapPairInfo, ReverseSwapStatus, RouteHint,
-    RouteHintHop, SendOnchainRequest, SendOnchainResponse, SendPaymentRequest, SendPaymentResponse,
-    SendSpontaneousPaymentRequest, ServiceHealthCheckResponse, SignMessageRequest,
-    SignMessageResponse, StaticBackupRequest, StaticBackupResponse, SuccessActionProcessed,
-    SwapAmountType, SwapInfo, SwapStatus, Symbol, TlvEntry, UnspentTransactionOutput,
-    UrlSuccessActionData,
+    NodeConfig, NodeCredentials, NodeState, OnchainPaymentLimitsResponse, OnchainSweepPolicy,
+    OpenChannelFeeRequest, OpenChannelFeeResponse, OpeningFeeParams, OpeningFeeParamsMenu,
+    PayOfferRequest, PayOnchainRequest, PayOnchainResponse, Payment, PaymentApprovalRequest,
+    PaymentApprover, PaymentDetails, PaymentFailedData, PaymentRequest, PaymentRequestStatus,
+    PaymentSortOrder, PaymentStatus, PaymentType, PaymentTypeFilter, PendingSpendingBudget,
+    PrepareAccelerateSwapDepositRequest, PrepareAccelerateSwapDepositResponse,
+    PrepareOnchainPaymentRequest, PrepareOnchainPaymentResponse, PrepareRedeemOnchainFundsRequest,
+    PrepareRedeemOnchainFundsResponse, PrepareRefundAllRequest, PrepareRefundRequest,
+    PrepareRefundResponse, PrepareSendPaymentRequest, PrepareSendPaymentResponse, Rate,
+    ReceiveOnchainRequest, ReceivePaymentRequest, ReceivePaymentResponse, RecommendedFees,
+    RedeemOnchainFundsRequest, RedeemOnchainFundsResponse, RefundAllRequest, RefundRequest,
+    RefundResponse, ReportIssueRequest, ReportPaymentFailureDetails, ReverseSwapFeesRequest,
+    ReverseSwapInfo, ReverseSwapPairInfo, ReverseSwapStatus, RouteHint, RouteHintHop,
+    SendOnchainRequest, SendOnchainResponse, SendPaymentRequest, SendPaymentResponse,
+    SendSpontaneousPaymentRequest, ServiceHealthCheckResponse, SetPaymentAnnotationsRequest,
+    SignMessageRequest, SignMessageResponse, SpendingBudget, SpendingLimit, StaticBackupRequest,
+    StaticBackupResponse, SuccessActionProcessed, SwapAmountType, SwapDepositAccelerationSource,
+    SwapInfo, SwapScriptVersion, SwapStatus, SweepDetails, SweepFailedData, SweepKind, SweepPolicy,
+    Symbol, TlvEntry, UnspentTransactionOutput, UrlSuccessActionData,
 };
 use log::{Level, LevelFilter, Metadata, Record};
 use once_cell::sync::{Lazy, OnceCell};
@@ -60,6 +72,7 @@ impl log::Log for BindingLogger {
             line: record.args().to_string(),
             level: record.level().as_str().to_string(),
         });
+        log_to_wallet_stream(record);
     }
     fn flush(&self) {}
 }
@@ -104,7 +117,71 @@ pub fn connect(
     rt().block_on(async move {
         let breez_services = BreezServices::connect(req, event_listener).await?;
 
-        Ok(Arc::new(BlockingBreezServices { breez_services }))
+        Ok(Arc::new(BlockingBreezServices {
+            breez_services,
+            wallet_id: None,
+        }))
+    })
+}
+
+/// Same as [connect], with the approver of the payments above the approval threshold of the
+/// spending budget. The approver can't be replaced once connected.
+pub fn connect_with_payment_approver(
+    req: ConnectRequest,
+    event_listener: Box<dyn EventListener>,
+    payment_approver: Box<dyn PaymentApprover>,
+) -> Result<Arc<BlockingBreezServices>, ConnectError> {
+    rt().block_on(async move {
+        let breez_services =
+            BreezServices::connect_with_payment_approver(req, event_listener, payment_approver)
+                .await?;
+
+        Ok(Arc::new(BlockingBreezServices {
+            breez_services,
+            wallet_id: None,
+        }))
+    })
+}
+
+/// Same as [connect], for processes hosting several wallets. The log statements of the wallet
+/// are also sent to its log stream, set with [set_wallet_log_stream].
+pub fn connect_wallet(
+    wallet_id: String,
+    req: ConnectRequest,
+    event_listener: Box<dyn EventListener>,
+) -> Result<Arc<BlockingBreezServices>, ConnectError> {
+    rt().block_on(async move {
+        let breez_services = with_wallet_context(
+            wallet_id.clone(),
+            BreezServices::connect(req, event_listener),
+        )
+        .await?;
+
+        Ok(Arc::new(BlockingBreezServices {
+            breez_services,
+            wallet_id: Some(wallet_id),
+        }))
+    })
+}
+
+/// Same as [connect_wallet], with the approver of the payments of the wallet
+pub fn connect_wallet_with_payment_approver(
+    wallet_id: String,
+    req: ConnectRequest,
+    event_listener: Box<dyn EventListener>,
+    payment_approver: Box<dyn PaymentApprover>,
+) -> Result<Arc<BlockingBreezServices>, ConnectError> {
+    rt().block_on(async move {
+        let breez_services = with_wallet_context(
+            wallet_id.clone(),
+            BreezServices::connect_with_payment_approver(req, event_listener, payment_approver),
+        )
+        .await?;
+
+        Ok(Arc::new(BlockingBreezServices {
+            breez_services,
+            wallet_id: Some(wallet_id),
+        }))
     })
 }
 
@@ -117,38 +194,79 @@ pub fn set_log_stream(log_stream: Box<dyn LogStream>) -> SdkResult<()> {
     Ok(())
 }
 
+/// If used, this must be called after [set_log_stream], and before [connect_wallet] to include
+/// the statements logged while connecting
+pub fn set_wallet_log_stream(wallet_id: String, log_stream: Box<dyn LogStream>) {
+    WalletManager::set_log_stream(&wallet_id, log_stream);
+}
+
 pub struct BlockingBreezServices {
     breez_services: Arc<BreezServices>,
+    wallet_id: Option<String>,
 }
 
 impl BlockingBreezServices {
+    /// Runs the future in the context of the wallet, if connected with [connect_wallet]
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        match self.wallet_id.clone() {
+            Some(wallet_id) => rt().block_on(with_wallet_context(wallet_id, future)),
+            None => rt().block_on(future),
+        }
+    }
+
     pub fn disconnect(&self) -> SdkResult<()> {
-        rt().block_on(self.breez_services.disconnect())
+        self.block_on(self.breez_services.disconnect())
     }
 
     pub fn configure_node(&self, req: ConfigureNodeRequest) -> SdkResult<()> {
-        rt().block_on(self.breez_services.configure_node(req))
+        self.block_on(self.breez_services.configure_node(req))
     }
 
     pub fn send_payment(
         &self,
         req: SendPaymentRequest,
     ) -> Result<SendPaymentResponse, SendPaymentError> {
-        rt().block_on(self.breez_services.send_payment(req))
+        self.block_on(self.breez_services.send_payment(req))
+    }
+
+    pub fn prepare_send_payment(
+        &self,
+        req: PrepareSendPaymentRequest,
+    ) -> Result<PrepareSendPaymentResponse, SendPaymentError> {
+        self.block_on(self.breez_services.prepare_send_payment(req))
     }
 
     pub fn send_spontaneous_payment(
         &self,
         req: SendSpontaneousPaymentRequest,
     ) -> Result<SendPaymentResponse, SendPaymentError> {
-        rt().block_on(self.breez_services.send_spontaneous_payment(req))
+        self.block_on(self.breez_services.send_spontaneous_payment(req))
+    }
+
+    pub fn pay_offer(&self, req: PayOfferRequest) -> Result<SendPaymentResponse, SendPaymentError> {
+        self.block_on(self.breez_services.pay_offer(req))
+    }
+
+    pub fn create_offer(&self, req: CreateOfferRequest) -> SdkResult<CreateOfferResponse> {
+        self.block_on(self.breez_services.create_offer(req))
     }
 
     pub fn receive_payment(
         &self,
         req: ReceivePaymentRequest,
     ) -> Result<ReceivePaymentResponse, ReceivePaymentError> {
-        rt().block_on(self.breez_services.receive_payment(req))
+        self.block_on(self.breez_services.receive_payment(req))
+    }
+
+    pub fn list_payment_requests(
+        &self,
+        req: ListPaymentRequestsRequest,
+    ) -> SdkResult<Vec<PaymentRequest>> {
+        self.block_on(self.breez_services.list_payment_requests(req))
+    }
+
+    pub fn cancel_payment_request(&self, payment_hash: String) -> SdkResult<()> {
+        self.block_on(self.breez_services.cancel_payment_request(payment_hash))
     }
 
     pub fn node_credentials(&self) -> SdkResult<Option<NodeCredentials>> {
@@ -160,11 +278,11 @@ impl BlockingBreezServices {
     }
 
     pub fn sign_message(&self, req: SignMessageRequest) -> SdkResult<SignMessageResponse> {
-        rt().block_on(self.breez_services.sign_message(req))
+        self.block_on(self.breez_services.sign_message(req))
     }
 
     pub fn check_message(&self, req: CheckMessageRequest) -> SdkResult<CheckMessageResponse> {
-        rt().block_on(self.breez_services.check_message(req))
+        self.block_on(self.breez_services.check_message(req))
     }
 
     pub fn backup_status(&self) -> SdkResult<BackupStatus> {
@@ -172,98 +290,136 @@ impl BlockingBreezServices {
     }
 
     pub fn backup(&self) -> SdkResult<()> {
-        rt().block_on(self.breez_services.backup())
+        self.block_on(self.breez_services.backup())
     }
 
     pub fn list_payments(&self, req: ListPaymentsRequest) -> SdkResult<Vec<Payment>> {
-        rt().block_on(self.breez_services.list_payments(req))
+        self.block_on(self.breez_services.list_payments(req))
+    }
+
+    pub fn count_payments(&self, req: ListPaymentsRequest) -> SdkResult<u64> {
+        self.block_on(self.breez_services.count_payments(req))
+    }
+
+    pub fn export_payments(&self, req: ExportPaymentsRequest) -> SdkResult<ExportPaymentsResponse> {
+        self.block_on(self.breez_services.export_payments(req))
     }
 
     pub fn payment_by_hash(&self, hash: String) -> SdkResult<Option<Payment>> {
-        rt().block_on(self.breez_services.payment_by_hash(hash))
+        self.block_on(self.breez_services.payment_by_hash(hash))
     }
 
     pub fn set_payment_metadata(&self, hash: String, metadata: String) -> SdkResult<()> {
-        rt().block_on(self.breez_services.set_payment_metadata(hash, metadata))
+        self.block_on(self.breez_services.set_payment_metadata(hash, metadata))
+    }
+
+    pub fn set_payment_annotations(&self, req: SetPaymentAnnotationsRequest) -> SdkResult<()> {
+        self.block_on(self.breez_services.set_payment_annotations(req))
+    }
+
+    pub fn list_tags(&self) -> SdkResult<Vec<String>> {
+        self.block_on(self.breez_services.list_tags())
     }
 
     pub fn pay_lnurl(&self, req: LnUrlPayRequest) -> Result<LnUrlPayResult, LnUrlPayError> {
-        rt().block_on(self.breez_services.lnurl_pay(req))
+        self.block_on(self.breez_services.lnurl_pay(req))
+    }
+
+    pub fn handle_lnurl_pay_server_request(
+        &self,
+        config: LnUrlPayServerConfig,
+        url: String,
+    ) -> LnUrlPayServerResponse {
+        self.block_on(
+            self.breez_services
+                .handle_lnurl_pay_server_request(config, url),
+        )
+    }
+
+    pub fn verify_lnurl_payment(
+        &self,
+        payment_hash: String,
+    ) -> Result<LnUrlVerifyStatus, LnUrlPayError> {
+        self.block_on(self.breez_services.verify_lnurl_payment(payment_hash))
     }
 
     pub fn withdraw_lnurl(
         &self,
         req: LnUrlWithdrawRequest,
     ) -> Result<LnUrlWithdrawResult, LnUrlWithdrawError> {
-        rt().block_on(self.breez_services.lnurl_withdraw(req))
+        self.block_on(self.breez_services.lnurl_withdraw(req))
     }
 
     pub fn lnurl_auth(
         &self,
         req_data: LnUrlAuthRequestData,
     ) -> Result<LnUrlCallbackStatus, LnUrlAuthError> {
-        rt().block_on(self.breez_services.lnurl_auth(req_data))
+        self.block_on(self.breez_services.lnurl_auth(req_data))
     }
 
     pub fn report_issue(&self, req: ReportIssueRequest) -> SdkResult<()> {
-        rt().block_on(self.breez_services.report_issue(req))
+        self.block_on(self.breez_services.report_issue(req))
     }
 
     pub fn redeem_onchain_funds(
         &self,
         req: RedeemOnchainFundsRequest,
     ) -> RedeemOnchainResult<RedeemOnchainFundsResponse> {
-        rt().block_on(self.breez_services.redeem_onchain_funds(req))
+        self.block_on(self.breez_services.redeem_onchain_funds(req))
     }
 
     pub fn fetch_fiat_rates(&self) -> SdkResult<Vec<Rate>> {
-        rt().block_on(self.breez_services.fetch_fiat_rates())
+        self.block_on(self.breez_services.fetch_fiat_rates())
+    }
+
+    pub fn lock_fiat_rate(&self, currency: String) -> SdkResult<LockedFiatRate> {
+        self.block_on(self.breez_services.lock_fiat_rate(currency))
     }
 
     pub fn list_fiat_currencies(&self) -> SdkResult<Vec<FiatCurrency>> {
-        rt().block_on(self.breez_services.list_fiat_currencies())
+        self.block_on(self.breez_services.list_fiat_currencies())
     }
 
     pub fn list_lsps(&self) -> SdkResult<Vec<LspInformation>> {
-        rt().block_on(self.breez_services.list_lsps())
+        self.block_on(self.breez_services.list_lsps())
     }
 
     pub fn connect_lsp(&self, lsp_id: String) -> SdkResult<()> {
-        rt().block_on(self.breez_services.connect_lsp(lsp_id))
+        self.block_on(self.breez_services.connect_lsp(lsp_id))
     }
 
     pub fn fetch_lsp_info(&self, lsp_id: String) -> SdkResult<Option<LspInformation>> {
-        rt().block_on(self.breez_services.fetch_lsp_info(lsp_id))
+        self.block_on(self.breez_services.fetch_lsp_info(lsp_id))
     }
 
     pub fn lsp_id(&self) -> SdkResult<Option<String>> {
-        rt().block_on(self.breez_services.lsp_id())
+        self.block_on(self.breez_services.lsp_id())
     }
 
     pub fn lsp_info(&self) -> SdkResult<LspInformation> {
-        rt().block_on(self.breez_services.lsp_info())
+        self.block_on(self.breez_services.lsp_info())
     }
 
     pub fn open_channel_fee(
         &self,
         req: OpenChannelFeeRequest,
     ) -> SdkResult<OpenChannelFeeResponse> {
-        rt().block_on(self.breez_services.open_channel_fee(req))
+        self.block_on(self.breez_services.open_channel_fee(req))
     }
 
     pub fn close_lsp_channels(&self) -> SdkResult<()> {
-        rt().block_on(async {
+        self.block_on(async {
             _ = self.breez_services.close_lsp_channels().await?;
             Ok(())
         })
     }
 
     pub fn register_webhook(&self, webhook_url: String) -> SdkResult<()> {
-        rt().block_on(async { self.breez_services.register_webhook(webhook_url).await })
+        self.block_on(async { self.breez_services.register_webhook(webhook_url).await })
     }
 
     pub fn unregister_webhook(&self, webhook_url: String) -> SdkResult<()> {
-        rt().block_on(async { self.breez_services.unregister_webhook(webhook_url).await })
+        self.block_on(async { self.breez_services.unregister_webhook(webhook_url).await })
     }
 
     /// Onchain receive swap API
@@ -271,74 +427,111 @@ impl BlockingBreezServices {
         &self,
         req: ReceiveOnchainRequest,
     ) -> Result<SwapInfo, ReceiveOnchainError> {
-        rt().block_on(self.breez_services.receive_onchain(req))
+        self.block_on(self.breez_services.receive_onchain(req))
     }
 
     /// Onchain receive swap API
     pub fn in_progress_swap(&self) -> SdkResult<Option<SwapInfo>> {
-        rt().block_on(self.breez_services.in_progress_swap())
+        self.block_on(self.breez_services.in_progress_swap())
     }
 
     /// Onchain rescan_swaps API
     pub fn rescan_swaps(&self) -> SdkResult<()> {
-        rt().block_on(self.breez_services.rescan_swaps())
+        self.block_on(self.breez_services.rescan_swaps())
     }
 
     /// Redeem an individual swap
     pub fn redeem_swap(&self, swap_address: String) -> SdkResult<()> {
-        rt().block_on(self.breez_services.redeem_swap(swap_address))
+        self.block_on(self.breez_services.redeem_swap(swap_address))
     }
 
     /// list non-completed expired swaps that should be refunded by calling [BreezServices::refund]
     pub fn list_refundables(&self) -> SdkResult<Vec<SwapInfo>> {
-        rt().block_on(self.breez_services.list_refundables())
+        self.block_on(self.breez_services.list_refundables())
     }
 
     // prepare a refund transaction for a failed/expired swap
     // optionally used to know fees before calling `refund()`
     pub fn prepare_refund(&self, req: PrepareRefundRequest) -> SdkResult<PrepareRefundResponse> {
-        rt().block_on(self.breez_services.prepare_refund(req))
+        self.block_on(self.breez_services.prepare_refund(req))
     }
 
     // construct and broadcast a refund transaction for a faile/expired swap
     pub fn refund(&self, req: RefundRequest) -> SdkResult<RefundResponse> {
-        rt().block_on(self.breez_services.refund(req))
+        self.block_on(self.breez_services.refund(req))
+    }
+
+    pub fn request_cooperative_refund(&self, req: RefundRequest) -> SdkResult<RefundResponse> {
+        self.block_on(self.breez_services.request_cooperative_refund(req))
+    }
+
+    // prepare a single refund transaction for several failed/expired swaps
+    pub fn prepare_refund_all(
+        &self,
+        req: PrepareRefundAllRequest,
+    ) -> SdkResult<PrepareRefundResponse> {
+        self.block_on(self.breez_services.prepare_refund_all(req))
+    }
+
+    // construct and broadcast a single refund transaction for several failed/expired swaps
+    pub fn refund_all(&self, req: RefundAllRequest) -> SdkResult<RefundResponse> {
+        self.block_on(self.breez_services.refund_all(req))
+    }
+
+    // replace an unconfirmed refund, claim or redeem transaction with a higher fee one
+    pub fn bump_fee(&self, req: BumpFeeRequest) -> SdkResult<BumpFeeResponse> {
+        self.block_on(self.breez_services.bump_fee(req))
+    }
+
+    // accelerate the unconfirmed deposits of a swap with a child transaction paying for them
+    pub fn prepare_accelerate_swap_deposit(
+        &self,
+        req: PrepareAccelerateSwapDepositRequest,
+    ) -> SdkResult<PrepareAccelerateSwapDepositResponse> {
+        self.block_on(self.breez_services.prepare_accelerate_swap_deposit(req))
+    }
+
+    pub fn accelerate_swap_deposit(
+        &self,
+        req: AccelerateSwapDepositRequest,
+    ) -> SdkResult<AccelerateSwapDepositResponse> {
+        self.block_on(self.breez_services.accelerate_swap_deposit(req))
     }
 
     pub fn fetch_reverse_swap_fees(
         &self,
         req: ReverseSwapFeesRequest,
     ) -> SdkResult<ReverseSwapPairInfo> {
-        rt().block_on(self.breez_services.fetch_reverse_swap_fees(req))
+        self.block_on(self.breez_services.fetch_reverse_swap_fees(req))
     }
 
     pub fn onchain_payment_limits(&self) -> SdkResult<OnchainPaymentLimitsResponse> {
-        rt().block_on(self.breez_services.onchain_payment_limits())
+        self.block_on(self.breez_services.onchain_payment_limits())
     }
 
     pub fn prepare_onchain_payment(
         &self,
         req: PrepareOnchainPaymentRequest,
     ) -> Result<PrepareOnchainPaymentResponse, SendOnchainError> {
-        rt().block_on(self.breez_services.prepare_onchain_payment(req))
+        self.block_on(self.breez_services.prepare_onchain_payment(req))
     }
 
     pub fn in_progress_onchain_payments(&self) -> SdkResult<Vec<ReverseSwapInfo>> {
-        rt().block_on(self.breez_services.in_progress_onchain_payments())
+        self.block_on(self.breez_services.in_progress_onchain_payments())
     }
 
     pub fn in_progress_reverse_swaps(&self) -> SdkResult<Vec<ReverseSwapInfo>> {
         #[allow(deprecated)]
-        rt().block_on(self.breez_services.in_progress_reverse_swaps())
+        self.block_on(self.breez_services.in_progress_reverse_swaps())
     }
 
     pub fn claim_reverse_swap(&self, lockup_address: String) -> SdkResult<()> {
-        rt().block_on(self.breez_services.claim_reverse_swap(lockup_address))
+        self.block_on(self.breez_services.claim_reverse_swap(lockup_address))
     }
 
     pub fn max_reverse_swap_amount(&self) -> SdkResult<MaxReverseSwapAmountResponse> {
         #[allow(deprecated)]
-        rt().block_on(self.breez_services.max_reverse_swap_amount())
+        self.block_on(self.breez_services.max_reverse_swap_amount())
     }
 
     pub fn send_onchain(
@@ -346,44 +539,64 @@ impl BlockingBreezServices {
         req: SendOnchainRequest,
     ) -> Result<SendOnchainResponse, SendOnchainError> {
         #[allow(deprecated)]
-        rt().block_on(self.breez_services.send_onchain(req))
+        self.block_on(self.breez_services.send_onchain(req))
     }
 
     pub fn pay_onchain(
         &self,
         req: PayOnchainRequest,
     ) -> Result<PayOnchainResponse, SendOnchainError> {
-        rt().block_on(self.breez_services.pay_onchain(req))
+        self.block_on(self.breez_services.pay_onchain(req))
     }
 
     pub fn execute_dev_command(&self, command: String) -> SdkResult<String> {
-        rt().block_on(self.breez_services.execute_dev_command(command))
+        self.block_on(self.breez_services.execute_dev_command(command))
     }
 
     pub fn generate_diagnostic_data(&self) -> SdkResult<String> {
-        rt().block_on(self.breez_services.generate_diagnostic_data())
+        self.block_on(self.breez_services.generate_diagnostic_data())
     }
 
     pub fn sync(&self) -> SdkResult<()> {
-        rt().block_on(self.breez_services.sync())
+        self.block_on(self.breez_services.sync())
     }
 
     pub fn recommended_fees(&self) -> SdkResult<RecommendedFees> {
-        rt().block_on(self.breez_services.recommended_fees())
+        self.block_on(self.breez_services.recommended_fees())
+    }
+
+    pub fn set_sweep_policy(&self, policy: SweepPolicy) -> SdkResult<()> {
+        self.block_on(self.breez_services.set_sweep_policy(policy))
+    }
+
+    pub fn sweep_policy(&self) -> SdkResult<SweepPolicy> {
+        self.block_on(self.breez_services.sweep_policy())
+    }
+
+    pub fn set_spending_budget(&self, budget: SpendingBudget) -> SdkResult<()> {
+        self.block_on(self.breez_services.set_spending_budget(budget))
+    }
+
+    pub fn spending_budget(&self) -> SdkResult<SpendingBudget> {
+        self.block_on(self.breez_services.spending_budget())
+    }
+
+    pub fn pending_spending_budget(&self) -> SdkResult<Option<PendingSpendingBudget>> {
+        self.block_on(self.breez_services.pending_spending_budget())
     }
 
     pub fn buy_bitcoin(
         &self,
         req: BuyBitcoinRequest,
     ) -> Result<BuyBitcoinResponse, ReceiveOnchainError> {
-        rt().block_on(self.breez_services.buy_bitcoin(req))
+        self.block_on(self.breez_services.buy_bitcoin(req))
     }
 
     pub fn prepare_redeem_onchain_funds(
         &self,
         req: PrepareRedeemOnchainFundsRequest,
     ) -> RedeemOnchainResult<PrepareRedeemOnchainFundsResponse> {
-        rt().block_on(self.breez_services.prepare_redeem_onchain_funds(req))
+        self.block_on(self.breez_services.prepare_redeem_onchain_funds(req))
     }
 }
 
@@ -5,7 +5,10 @@ use serde::{Deserialize, Serialize};
 use tonic::Request;
 
 use crate::grpc::RatesRequest;
-use crate::prelude::BreezServer;
+use crate::prelude::{get_parse_and_log_response, BreezServer};
+
+const COINGECKO_MARKET_CHART_ENDPOINT: &str =
+    "https://api.coingecko.com/api/v3/coins/bitcoin/market_chart/range";
 
 /// Trait covering fiat-related functionality
 #[tonic::async_trait]
@@ -15,6 +18,15 @@ pub trait FiatAPI: Send + Sync {
 
     /// Get the live rates from the server.
     async fn fetch_fiat_rates(&self) -> Result<Vec<Rate>>;
+
+    /// Get the historical BTC prices in the given currency over the given range of epoch
+    /// seconds, sorted by time. Unlike the live rates, they may come from a third party.
+    async fn fetch_historical_rates(
+        &self,
+        currency: &str,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<HistoricalRate>>;
 }
 
 /// Settings for the symbol representation of a currency
@@ -70,6 +82,20 @@ pub struct Rate {
     pub value: f64,
 }
 
+/// The BTC price in a fiat currency at a point in time
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoricalRate {
+    /// Epoch time, in seconds
+    pub timestamp: i64,
+    pub value: f64,
+}
+
+#[derive(Deserialize)]
+struct MarketChart {
+    /// Pairs of epoch time in milliseconds and price
+    prices: Vec<(f64, f64)>,
+}
+
 fn convert_to_fiat_currency_with_id(id: String, info: CurrencyInfo) -> FiatCurrency {
     FiatCurrency { id, info }
 }
@@ -114,4 +140,30 @@ impl FiatAPI for BreezServer {
             })
             .collect())
     }
+
+    /// The Breez server only serves live rates, so the historical ones are fetched from CoinGecko
+    async fn fetch_historical_rates(
+        &self,
+        currency: &str,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<HistoricalRate>> {
+        let url = format!(
+            "{COINGECKO_MARKET_CHART_ENDPOINT}?vs_currency={}&from={from}&to={to}",
+            currency.to_lowercase(),
+        );
+        let chart: MarketChart = get_parse_and_log_response(&url, true)
+            .await
+            .map_err(|e| anyhow!("Fetch historical rates request failed: {e}"))?;
+        let mut rates: Vec<HistoricalRate> = chart
+            .prices
+            .into_iter()
+            .map(|(time_ms, value)| HistoricalRate {
+                timestamp: (time_ms / 1000.0) as i64,
+                value,
+            })
+            .collect();
+        rates.sort_by_key(|r| r.timestamp);
+        Ok(rates)
+    }
 }
@@ -188,6 +188,10 @@ pub async fn parse(input: &str) -> Result<InputType> {
         return Ok(InputType::Bolt11 { invoice });
     }
 
+    if let Ok(offer) = parse_offer(input) {
+        return Ok(InputType::Bolt12Offer { offer });
+    }
+
     // Public key serialized in compressed form (66 hex chars)
     if let Ok(_node_id) = bitcoin::secp256k1::PublicKey::from_str(input) {
         return Ok(InputType::NodeId {
@@ -414,6 +418,13 @@ pub enum InputType {
     Bolt11 {
         invoice: LNInvoice,
     },
+
+    /// # Supported standards
+    ///
+    /// - BOLT12 offers (`lno1...`), with or without the `lightning:` prefix
+    Bolt12Offer {
+        offer: LNOffer,
+    },
     NodeId {
         node_id: String,
     },
@@ -435,11 +446,8 @@ pub enum InputType {
     ///
     /// - LUD-01 LNURL bech32 encoding
     /// - LUD-03 `withdrawRequest` spec
-    /// - LUD-17 Support for lnurlw prefix with non-bech32-encoded LNURL URLs
-    ///
-    /// # Not supported (yet)
-    ///
     /// - LUD-14 `balanceCheck`: reusable `withdrawRequest`s
+    /// - LUD-17 Support for lnurlw prefix with non-bech32-encoded LNURL URLs
     /// - LUD-19 Pay link discoverable from withdraw link
     LnUrlWithdraw {
         data: LnUrlWithdrawRequestData,
@@ -850,6 +858,34 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_bolt12_offer() -> Result<()> {
+        let secp = Secp256k1::new();
+        let signing_pubkey =
+            PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&[42; 32]).unwrap());
+        let offer =
+            lightning::offers::offer::OfferBuilder::new("test offer".to_string(), signing_pubkey)
+                .amount_msats(1_000)
+                .build()
+                .map_err(|e| anyhow!("{e:?}"))?
+                .to_string();
+
+        // Offer without prefix
+        assert!(matches!(
+            parse(&offer).await?,
+            InputType::Bolt12Offer { offer: _offer }
+        ));
+
+        // Offer with prefix
+        let offer_with_prefix = format!("lightning:{offer}");
+        assert!(matches!(
+            parse(&offer_with_prefix).await?,
+            InputType::Bolt12Offer { offer: _offer }
+        ));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_capitalized_bolt11() -> Result<()> {
         let bolt11 = "LNBC110N1P38Q3GTPP5YPZ09JRD8P993SNJWNM68CPH4FTWP22LE34XD4R8FTSPWSHXHMNSDQQXQYJW5QCQPXSP5HTLG8YDPYWVSA7H3U4HDN77EHS4Z4E844EM0APJYVMQFKZQHHD2Q9QGSQQQYSSQSZPXZXT9UUQZYMR7ZXCDCCJ5G69S8Q7ZZJS7SGXN9EJHNVDH6GQJCY22MSS2YEXUNAGM5R2GQCZH8K24CWRQML3NJSKM548ARUHPWSSQ9NVRVZ";
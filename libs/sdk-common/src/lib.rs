@@ -9,6 +9,7 @@ pub mod invoice;
 pub mod liquid;
 mod lnurl;
 mod model;
+pub mod offer;
 mod utils;
 
 // Re-export commonly used crates, to make it easy for callers to use the specific versions we're using.
@@ -41,5 +42,6 @@ pub mod prelude {
     pub use crate::lnurl::specs::withdraw::*;
     pub use crate::lnurl::*;
     pub use crate::model::*;
+    pub use crate::offer::*;
     pub use crate::utils::rest_client::*;
 }
@@ -1,5 +1,7 @@
 use std::str::FromStr;
 
+use bitcoin::hashes::{hex::ToHex, sha256, Hash};
+
 use crate::prelude::*;
 
 pub type Aes256CbcEnc = cbc::Encryptor<aes::Aes256>;
@@ -52,6 +54,44 @@ pub async fn validate_lnurl_pay(
     }
 }
 
+/// Queries the LUD-21 verify URL returned in the LNURL-pay callback response, as per
+/// <https://github.com/lnurl/luds/blob/luds/21.md>
+///
+/// When the invoice is reported as settled, the returned preimage is checked against `payment_hash`,
+/// so the result doesn't depend on trusting the LNURL service or the payer.
+pub async fn verify_lnurl_pay(
+    verify_url: &str,
+    payment_hash: &str,
+) -> LnUrlResult<LnUrlVerifyStatus> {
+    let (verify_resp_text, _) = get_and_log_response(verify_url)
+        .await
+        .map_err(|e| LnUrlError::ServiceConnectivity(e.to_string()))?;
+
+    if let Ok(err) = serde_json::from_str::<LnUrlErrorData>(&verify_resp_text) {
+        return Err(LnUrlError::Generic(err.reason));
+    }
+
+    let verify_resp: LnUrlVerifyResponse = serde_json::from_str(&verify_resp_text)?;
+    ensure_sdk!(
+        parse_invoice(&verify_resp.pr)?.payment_hash == payment_hash,
+        LnUrlError::generic("Verify URL returned a different invoice")
+    );
+    if !verify_resp.settled {
+        return Ok(LnUrlVerifyStatus::Pending);
+    }
+
+    let preimage = verify_resp.preimage.ok_or(LnUrlError::generic(
+        "Verify URL reported a settled invoice without preimage",
+    ))?;
+    let preimage_bytes = hex::decode(&preimage)
+        .map_err(|e| LnUrlError::Generic(format!("Invalid preimage: {e}")))?;
+    ensure_sdk!(
+        sha256::Hash::hash(&preimage_bytes).to_hex() == payment_hash,
+        LnUrlError::generic("Verify URL returned a preimage that doesn't match the payment hash")
+    );
+    Ok(LnUrlVerifyStatus::Settled { preimage })
+}
+
 pub fn build_pay_callback_url(
     user_amount_msat: u64,
     user_comment: &Option<String>,
@@ -162,6 +202,25 @@ pub mod model {
     pub struct CallbackResponse {
         pub pr: String,
         pub success_action: Option<SuccessAction>,
+        /// LUD-21: URL that can be queried to check whether the invoice was paid
+        pub verify: Option<String>,
+    }
+
+    /// Payload of a LUD-21 verify endpoint, as received from the LNURL service
+    #[derive(Deserialize, Debug)]
+    pub struct LnUrlVerifyResponse {
+        pub settled: bool,
+        pub preimage: Option<String>,
+        pub pr: String,
+    }
+
+    /// Status of a LNURL-pay invoice, as reported by its LUD-21 verify URL
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub enum LnUrlVerifyStatus {
+        /// The invoice was paid. The preimage was checked against the payment hash.
+        Settled { preimage: String },
+        /// The invoice was not paid yet
+        Pending,
     }
 
     /// Payload of the AES success action, as received from the LNURL endpoint
@@ -416,6 +475,10 @@ pub mod model {
         /// This error is raised when a connection to an external service fails.
         #[error("Service connectivity: {err}")]
         ServiceConnectivity { err: String },
+
+        /// This error is raised when the payment exceeds the spending budget of the wallet.
+        #[error("Budget exceeded: {err}")]
+        BudgetExceeded { err: String },
     }
 
     impl From<anyhow::Error> for LnUrlPayError {
@@ -463,7 +526,13 @@ pub(crate) mod tests {
     use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
     use anyhow::Result;
     use bitcoin::hashes::{sha256, Hash};
+    use bitcoin::secp256k1::{Secp256k1, SecretKey};
+    use lightning::ln::PaymentSecret;
+    use lightning_invoice::{Currency, InvoiceBuilder};
+    use mockito::Mock;
+    use serde_json::json;
 
+    use crate::input_parser::tests::MOCK_HTTP_SERVER;
     use crate::lnurl::specs::pay::*;
     use crate::lnurl::tests::rand_string;
 
@@ -669,4 +738,66 @@ pub(crate) mod tests {
 
         Ok(())
     }
+
+    fn create_test_invoice(preimage: &[u8]) -> Result<String> {
+        let private_key = SecretKey::from_slice(&[42; 32])?;
+        let invoice = InvoiceBuilder::new(Currency::Bitcoin)
+            .description("test".into())
+            .payment_hash(sha256::Hash::hash(preimage))
+            .payment_secret(PaymentSecret([0; 32]))
+            .current_timestamp()
+            .min_final_cltv_expiry_delta(144)
+            .amount_milli_satoshis(1_000)
+            .build_signed(|hash| Secp256k1::new().sign_ecdsa_recoverable(hash, &private_key))?;
+        Ok(invoice.to_string())
+    }
+
+    fn mock_lnurl_verify_endpoint(path: &str, response_body: String) -> Mock {
+        let mut server = MOCK_HTTP_SERVER.lock().unwrap();
+        server.mock("GET", path).with_body(response_body).create()
+    }
+
+    #[tokio::test]
+    async fn test_lnurl_pay_verify() -> Result<()> {
+        let preimage = [7; 32];
+        let payment_hash = sha256::Hash::hash(&preimage).to_string();
+        let pr = create_test_invoice(&preimage)?;
+
+        let path = format!("/verify/{}", rand_string(10));
+        let verify_url = format!("http://127.0.0.1:8080{path}");
+        let _m = mock_lnurl_verify_endpoint(
+            &path,
+            json!({"status": "OK", "settled": false, "preimage": null, "pr": pr}).to_string(),
+        );
+        assert_eq!(
+            verify_lnurl_pay(&verify_url, &payment_hash).await?,
+            LnUrlVerifyStatus::Pending
+        );
+
+        let path = format!("/verify/{}", rand_string(10));
+        let verify_url = format!("http://127.0.0.1:8080{path}");
+        let _m = mock_lnurl_verify_endpoint(
+            &path,
+            json!({"status": "OK", "settled": true, "preimage": hex::encode(preimage), "pr": pr})
+                .to_string(),
+        );
+        assert_eq!(
+            verify_lnurl_pay(&verify_url, &payment_hash).await?,
+            LnUrlVerifyStatus::Settled {
+                preimage: hex::encode(preimage)
+            }
+        );
+
+        // A preimage that doesn't match the payment hash is rejected
+        let path = format!("/verify/{}", rand_string(10));
+        let verify_url = format!("http://127.0.0.1:8080{path}");
+        let _m = mock_lnurl_verify_endpoint(
+            &path,
+            json!({"status": "OK", "settled": true, "preimage": hex::encode([8; 32]), "pr": pr})
+                .to_string(),
+        );
+        assert!(verify_lnurl_pay(&verify_url, &payment_hash).await.is_err());
+
+        Ok(())
+    }
 }
@@ -1,5 +1,7 @@
 use std::str::FromStr;
 
+use log::warn;
+
 use crate::error::{ServiceConnectivityError, ServiceConnectivityErrorKind};
 use crate::prelude::*;
 
@@ -11,6 +13,9 @@ use crate::prelude::*;
 /// Note that the invoice amount has to respect two separate min/max limits:
 /// * those in the [LnUrlWithdrawRequestData] showing the limits of the LNURL endpoint, and
 /// * those of the current node, depending on the LSP settings and LN channel conditions
+///
+/// If the endpoint is a reusable withdraw link (LUD-14), its `balanceCheck` URL is queried after a
+/// successful withdraw and the refreshed request is returned in the [LnUrlWithdrawSuccessData].
 pub async fn validate_lnurl_withdraw(
     req_data: LnUrlWithdrawRequestData,
     invoice: LNInvoice,
@@ -36,14 +41,23 @@ pub async fn validate_lnurl_withdraw(
     let callback_url = build_withdraw_callback_url(&req_data, &invoice)?;
     let withdraw_status = match get_parse_and_log_response(&callback_url, false).await {
         Ok(LnUrlCallbackStatus::Ok) => LnUrlWithdrawResult::Ok {
-            data: LnUrlWithdrawSuccessData { invoice },
+            data: LnUrlWithdrawSuccessData {
+                invoice,
+                refreshed_request: match &req_data.balance_check {
+                    Some(balance_check) => fetch_refreshed_withdraw_request(balance_check).await,
+                    None => None,
+                },
+            },
         },
         Ok(LnUrlCallbackStatus::ErrorStatus { data }) => LnUrlWithdrawResult::ErrorStatus { data },
         Err(ServiceConnectivityError {
             kind: ServiceConnectivityErrorKind::Timeout,
             err: _,
         }) => LnUrlWithdrawResult::Timeout {
-            data: LnUrlWithdrawSuccessData { invoice },
+            data: LnUrlWithdrawSuccessData {
+                invoice,
+                refreshed_request: None,
+            },
         },
         Err(e) => return Err(LnUrlError::ServiceConnectivity(e.to_string())),
     };
@@ -51,6 +65,23 @@ pub async fn validate_lnurl_withdraw(
     Ok(withdraw_status)
 }
 
+/// Queries the LUD-14 `balanceCheck` URL of a reusable withdraw link.
+///
+/// A failure here doesn't affect the outcome of the withdraw, so errors are only logged.
+async fn fetch_refreshed_withdraw_request(balance_check: &str) -> Option<LnUrlWithdrawRequestData> {
+    match get_parse_and_log_response::<LnUrlRequestData>(balance_check, true).await {
+        Ok(LnUrlRequestData::WithdrawRequest { data }) => Some(data),
+        Ok(_) => {
+            warn!("Balance check endpoint did not return a withdraw request");
+            None
+        }
+        Err(e) => {
+            warn!("Failed to query the balance check endpoint: {e}");
+            None
+        }
+    }
+}
+
 pub fn build_withdraw_callback_url(
     req_data: &LnUrlWithdrawRequestData,
     invoice: &LNInvoice,
@@ -101,6 +132,12 @@ pub mod model {
         pub min_withdrawable: u64,
         /// The maximum amount, in millisats, that this LNURL-withdraw endpoint accepts
         pub max_withdrawable: u64,
+        /// LUD-14: URL that returns a refreshed withdraw request for this reusable withdraw link.
+        /// Queried automatically after a successful withdraw.
+        pub balance_check: Option<String>,
+        /// LUD-19: LNURL-pay link of the same service, which can be used to top up the balance
+        /// of this withdraw link. Pass it to [parse] to get the LNURL-pay request data.
+        pub pay_link: Option<String>,
     }
 
     /// [LnUrlCallbackStatus] specific to LNURL-withdraw, where the success case contains the invoice.
@@ -114,6 +151,9 @@ pub mod model {
     #[derive(Clone, Deserialize, Debug, Serialize)]
     pub struct LnUrlWithdrawSuccessData {
         pub invoice: LNInvoice,
+        /// Only set for reusable withdraw links (LUD-14). The withdraw request returned by the
+        /// `balanceCheck` URL after the withdraw, reflecting the updated balance.
+        pub refreshed_request: Option<LnUrlWithdrawRequestData>,
     }
 
     #[derive(Debug, Error)]
@@ -228,6 +268,8 @@ mod tests {
             k1: rand_string(10),
             default_description: "test description".into(),
             callback: "http://127.0.0.1:8080/callback".into(),
+            balance_check: None,
+            pay_link: None,
         }
     }
 
@@ -241,12 +283,62 @@ mod tests {
 
         assert!(matches!(
             validate_lnurl_withdraw(withdraw_req, req_invoice.clone()).await?,
-            LnUrlWithdrawResult::Ok { data: LnUrlWithdrawSuccessData { invoice } } if invoice == req_invoice
+            LnUrlWithdrawResult::Ok { data: LnUrlWithdrawSuccessData { invoice, refreshed_request: None } } if invoice == req_invoice
         ));
 
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_lnurl_withdraw_success_with_balance_check() -> Result<()> {
+        let invoice_str = "lnbc110n1p38q3gtpp5ypz09jrd8p993snjwnm68cph4ftwp22le34xd4r8ftspwshxhmnsdqqxqyjw5qcqpxsp5htlg8ydpywvsa7h3u4hdn77ehs4z4e844em0apjyvmqfkzqhhd2q9qgsqqqyssqszpxzxt9uuqzymr7zxcdccj5g69s8q7zzjs7sgxn9ejhnvdh6gqjcy22mss2yexunagm5r2gqczh8k24cwrqml3njskm548aruhpwssq9nvrvz";
+        let req_invoice = crate::invoice::parse_invoice(invoice_str)?;
+        let balance_check_path = format!("/balance-check/{}", rand_string(10));
+        let withdraw_req = LnUrlWithdrawRequestData {
+            balance_check: Some(format!("http://127.0.0.1:8080{balance_check_path}")),
+            ..get_test_withdraw_req_data(0, 100)
+        };
+
+        let _m = mock_lnurl_withdraw_callback(&withdraw_req, &req_invoice, None)?;
+        let refreshed_body = r#"
+{
+    "tag":"withdrawRequest",
+    "callback":"http://127.0.0.1:8080/callback",
+    "k1":"refreshed-k1",
+    "minWithdrawable":1000,
+    "maxWithdrawable":89000,
+    "defaultDescription":"test description",
+    "balanceCheck":"http://127.0.0.1:8080/balance-check",
+    "payLink":"lnurlp://127.0.0.1:8080/top-up"
+}
+        "#
+        .replace('\n', "");
+        let _m2 = MOCK_HTTP_SERVER
+            .lock()
+            .unwrap()
+            .mock("GET", balance_check_path.as_str())
+            .with_body(refreshed_body)
+            .create();
+
+        let LnUrlWithdrawResult::Ok { data } =
+            validate_lnurl_withdraw(withdraw_req, req_invoice.clone()).await?
+        else {
+            panic!("Expected a successful withdraw");
+        };
+        assert_eq!(data.invoice, req_invoice);
+        let refreshed = data
+            .refreshed_request
+            .expect("Expected a refreshed request");
+        assert_eq!(refreshed.k1, "refreshed-k1");
+        assert_eq!(refreshed.max_withdrawable, 89000);
+        assert_eq!(
+            refreshed.pay_link,
+            Some("lnurlp://127.0.0.1:8080/top-up".to_string())
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_lnurl_withdraw_endpoint_failure() -> Result<()> {
         let invoice_str = "lnbc110n1p38q3gtpp5ypz09jrd8p993snjwnm68cph4ftwp22le34xd4r8ftspwshxhmnsdqqxqyjw5qcqpxsp5htlg8ydpywvsa7h3u4hdn77ehs4z4e844em0apjyvmqfkzqhhd2q9qgsqqqyssqszpxzxt9uuqzymr7zxcdccj5g69s8q7zzjs7sgxn9ejhnvdh6gqjcy22mss2yexunagm5r2gqczh8k24cwrqml3njskm548aruhpwssq9nvrvz";
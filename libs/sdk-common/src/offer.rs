@@ -0,0 +1,138 @@
+use std::str::FromStr;
+
+use hex::ToHex;
+use lightning::offers::offer::{self, Offer};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+/// Amount of a BOLT12 offer
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Amount {
+    Bitcoin {
+        amount_msat: u64,
+    },
+
+    /// An amount of currency specified using ISO 4712.
+    Currency {
+        /// The currency that the amount is denominated in.
+        iso4217_code: String,
+        /// The amount in the currency unit adjusted by the ISO 4712 exponent (e.g., USD cents).
+        fractional_amount: u64,
+    },
+}
+
+/// Wrapper for a BOLT12 offer
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LNOffer {
+    /// String representation of the Bolt12 offer
+    pub offer: String,
+    /// The chain hashes, as hex, the offer is valid for
+    pub chains: Vec<String>,
+    /// If set, it represents the minimum amount that an invoice must have to be valid for this offer
+    pub min_amount: Option<Amount>,
+    pub description: String,
+    /// Epoch time from which an invoice should no longer be requested. If None, the offer does not expire.
+    pub absolute_expiry: Option<u64>,
+    pub issuer: Option<String>,
+    /// The public key used by the recipient to sign invoices.
+    pub signing_pubkey: String,
+    /// The number of blinded paths through which the offer can be reached
+    pub paths_count: u32,
+}
+
+impl LNOffer {
+    /// Whether the offer can be paid on the given network
+    pub fn supports_network(&self, network: Network) -> bool {
+        let network: bitcoin::Network = network.into();
+        let chain_hash =
+            bitcoin::blockdata::constants::ChainHash::using_genesis_block(network).to_string();
+        match self.chains.is_empty() {
+            // Offers without chains are implicitly for Bitcoin mainnet
+            true => network == bitcoin::Network::Bitcoin,
+            false => self.chains.contains(&chain_hash),
+        }
+    }
+}
+
+/// Parses a BOLT12 offer (`lno1...`), with or without the `lightning:` prefix
+pub fn parse_offer(offer: &str) -> InvoiceResult<LNOffer> {
+    if offer.trim().is_empty() {
+        return Err(InvoiceError::validation("Offer is an empty string"));
+    }
+    let re = Regex::new(r"(?i)^lightning:")?;
+    let offer = re.replace_all(offer.trim(), "");
+    let parsed = Offer::from_str(&offer)
+        .map_err(|e| InvoiceError::Validation(format!("Invalid offer: {e:?}")))?;
+
+    Ok(LNOffer {
+        offer: parsed.to_string(),
+        chains: parsed
+            .chains()
+            .iter()
+            .map(|chain| chain.to_string())
+            .collect(),
+        min_amount: parsed.amount().map(|amount| match amount {
+            offer::Amount::Bitcoin { amount_msats } => Amount::Bitcoin {
+                amount_msat: *amount_msats,
+            },
+            offer::Amount::Currency {
+                iso4217_code,
+                amount,
+            } => Amount::Currency {
+                iso4217_code: String::from_utf8_lossy(iso4217_code).to_string(),
+                fractional_amount: *amount,
+            },
+        }),
+        description: parsed.description().to_string(),
+        absolute_expiry: parsed.absolute_expiry().map(|expiry| expiry.as_secs()),
+        issuer: parsed.issuer().map(|issuer| issuer.to_string()),
+        signing_pubkey: parsed.signing_pubkey().serialize().encode_hex::<String>(),
+        paths_count: parsed.paths().len() as u32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+    use lightning::offers::offer::OfferBuilder;
+
+    use crate::offer::*;
+
+    #[test]
+    fn test_parse_offer() {
+        let secp = Secp256k1::new();
+        let signing_pubkey =
+            PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&[42; 32]).unwrap());
+        let offer = OfferBuilder::new("test offer".to_string(), signing_pubkey)
+            .amount_msats(50_000)
+            .issuer("breez".to_string())
+            .build()
+            .unwrap()
+            .to_string();
+
+        let parsed = parse_offer(&offer).unwrap();
+        assert_eq!(parsed.offer, offer);
+        assert_eq!(
+            parsed.min_amount,
+            Some(Amount::Bitcoin {
+                amount_msat: 50_000
+            })
+        );
+        assert_eq!(parsed.description, "test offer");
+        assert_eq!(parsed.issuer, Some("breez".to_string()));
+        assert_eq!(parsed.signing_pubkey, signing_pubkey.to_string());
+        assert!(parsed.supports_network(Network::Bitcoin));
+        assert!(!parsed.supports_network(Network::Testnet));
+
+        let prefixed = parse_offer(&format!("lightning:{offer}")).unwrap();
+        assert_eq!(prefixed, parsed);
+    }
+
+    #[test]
+    fn test_parse_offer_invalid() {
+        assert!(parse_offer("").is_err());
+        assert!(parse_offer("lno1invalid").is_err());
+    }
+}
@@ -1,4 +1,5 @@
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tonic_build::compile_protos("src/cln/proto/offers.proto")?;
     set_git_revision_hash();
     Ok(())
 }
@@ -2,7 +2,7 @@ use crate::{
     breez_services::BackupFailedData,
     error::SdkResult,
     persist::db::{HookEvent, SqliteStorage},
-    BreezEvent, Config,
+    wallets, BreezEvent, Config,
 };
 
 use anyhow::{anyhow, Result};
@@ -121,66 +121,70 @@ impl BackupWatcher {
         self.set_request_sender(backup_request_sender.clone()).await;
 
         let rt = Builder::new_current_thread().enable_all().build()?;
+        let wallet_id = wallets::current_wallet_id();
         std::thread::spawn(move || {
-            rt.block_on(async move {
-                loop {
-                    tokio::select! {
-
-                     // We listen to manual backup requests from the user
-                     req = backup_request_receiver.recv() => {
-                      match req {
-                       Some(req) => {
-                        match worker.sync(req.force).await {
-                         Ok(_) => {
-                          if let Some(callback) = req.on_complete {
-                           _ = callback.send(Ok(())).await;
-                          }
-                         }
-                         Err(e) => {
-                          error!("Sync worker returned with error {e}");
-                          if let Some(callback) = req.on_complete {
-                           _ = callback.send(Err(e)).await;
-                          }
-                         }
-                        };
-                       }
-                       None => {
-                        return
-                       }
-                      }
-                     }
-
-                      // We spin the backup worker on every new entry to the sync_requests table.
-                      event = hooks_subscription.recv() => {
-                        match event {
-                            Ok(HookEvent::Insert{table}) => {
-                             if table == "sync_requests"{
-                              // we do want to wait a bit to allow for multiple sync requests to be inserted
-                              tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-                              if let Err(e) = worker.sync(false).await {
-                               error!("Sync worker returned with error {e}");
+            rt.block_on(wallets::with_optional_wallet_context(
+                wallet_id,
+                async move {
+                    loop {
+                        tokio::select! {
+
+                         // We listen to manual backup requests from the user
+                         req = backup_request_receiver.recv() => {
+                          match req {
+                           Some(req) => {
+                            match worker.sync(req.force).await {
+                             Ok(_) => {
+                              if let Some(callback) = req.on_complete {
+                               _ = callback.send(Ok(())).await;
                               }
                              }
-                            }
-                            // If we are lagging we want to trigger sync
-                            Err(RecvError::Lagged(_)) => {
-                             if let Err(e) = worker.sync(false).await {
+                             Err(e) => {
                               error!("Sync worker returned with error {e}");
+                              if let Some(callback) = req.on_complete {
+                               _ = callback.send(Err(e)).await;
+                              }
                              }
+                            };
+                           }
+                           None => {
+                            return
+                           }
+                          }
+                         }
+
+                          // We spin the backup worker on every new entry to the sync_requests table.
+                          event = hooks_subscription.recv() => {
+                            match event {
+                                Ok(HookEvent::Insert{table}) => {
+                                 if table == "sync_requests"{
+                                  // we do want to wait a bit to allow for multiple sync requests to be inserted
+                                  tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                                  if let Err(e) = worker.sync(false).await {
+                                   error!("Sync worker returned with error {e}");
+                                  }
+                                 }
+                                }
+                                // If we are lagging we want to trigger sync
+                                Err(RecvError::Lagged(_)) => {
+                                 if let Err(e) = worker.sync(false).await {
+                                  error!("Sync worker returned with error {e}");
+                                 }
+                                }
+                                // If the channel is closed we exit
+                                Err(_) => {
+                                 return
+                                }
                             }
-                            // If the channel is closed we exit
-                            Err(_) => {
-                             return
-                            }
+                          },
+                          // We also want to exit if we receive a quit signal
+                          _ = quit_receiver.changed() => {
+                            return
+                          }
                         }
-                      },
-                      // We also want to exit if we receive a quit signal
-                      _ = quit_receiver.changed() => {
-                        return
-                      }
                     }
-                }
-            });
+                },
+            ));
         });
 
         Ok(())
@@ -449,7 +453,7 @@ mod tests {
         backup::BackupRequest,
         persist::db::SqliteStorage,
         test_utils::{create_test_config, create_test_persister, MockBackupTransport},
-        BreezEvent, SwapInfo,
+        BreezEvent, SwapInfo, SwapScriptVersion,
     };
     use std::{sync::Arc, vec};
     use tokio::sync::{broadcast::Receiver, watch};
@@ -769,6 +773,7 @@ mod tests {
             public_key: vec![4],
             swapper_public_key: vec![5],
             script: vec![5],
+            script_version: SwapScriptVersion::P2wsh,
             bolt11: None,
             paid_msat: 0,
             unconfirmed_sats: 0,
@@ -10,8 +10,11 @@
 //! The first option loses the `SdkError` type. The second option keeps the type, which we can retrieve
 //! with `anyhow::Error::downcast_ref` (or equivalent Dart method). We therefore use the second approach.
 
+use std::collections::HashMap;
 use std::future::Future;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use flutter_rust_bridge::StreamSink;
@@ -19,16 +22,18 @@ use log::{Level, LevelFilter, Metadata, Record};
 use once_cell::sync::{Lazy, OnceCell};
 use sdk_common::invoice;
 pub use sdk_common::prelude::{
-    parse, AesSuccessActionDataDecrypted, AesSuccessActionDataResult, BitcoinAddressData,
-    CurrencyInfo, FiatCurrency, InputType, LNInvoice, LnUrlAuthRequestData, LnUrlCallbackStatus,
-    LnUrlError, LnUrlErrorData, LnUrlPayErrorData, LnUrlPayRequest, LnUrlPayRequestData,
-    LnUrlWithdrawRequest, LnUrlWithdrawRequestData, LnUrlWithdrawResult, LnUrlWithdrawSuccessData,
-    LocaleOverrides, LocalizedName, MessageSuccessActionData, Network, Rate, RouteHint,
-    RouteHintHop, SuccessActionProcessed, Symbol, UrlSuccessActionData,
+    parse, AesSuccessActionDataDecrypted, AesSuccessActionDataResult, Amount, BitcoinAddressData,
+    CurrencyInfo, FiatCurrency, InputType, LNInvoice, LNOffer, LnUrlAuthRequestData,
+    LnUrlCallbackStatus, LnUrlError, LnUrlErrorData, LnUrlPayErrorData, LnUrlPayRequest,
+    LnUrlPayRequestData, LnUrlVerifyStatus, LnUrlWithdrawRequest, LnUrlWithdrawRequestData,
+    LnUrlWithdrawResult, LnUrlWithdrawSuccessData, LocaleOverrides, LocalizedName,
+    MessageSuccessActionData, Network, Rate, RouteHint, RouteHintHop, SuccessActionProcessed,
+    Symbol, UrlSuccessActionData,
 };
-use tokio::sync::Mutex;
 
-use crate::breez_services::{self, BreezEvent, BreezServices, EventListener};
+use crate::breez_services::{
+    self, BreezEvent, BreezServices, EventListener, LogStream, PaymentApprover,
+};
 use crate::chain::RecommendedFees;
 use crate::error::{
     ConnectError, ReceiveOnchainError, ReceivePaymentError, RedeemOnchainError, SdkError,
@@ -36,19 +41,27 @@ use crate::error::{
 };
 use crate::lsp::LspInformation;
 use crate::models::{Config, LogEntry, NodeState, Payment, SwapInfo};
+use crate::wallets::{self, WalletEvent, WalletManager};
 use crate::{
-    BackupStatus, BuyBitcoinRequest, BuyBitcoinResponse, CheckMessageRequest, CheckMessageResponse,
-    ConfigureNodeRequest, ConnectRequest, EnvironmentType, ListPaymentsRequest, LnUrlAuthError,
-    MaxReverseSwapAmountResponse, NodeConfig, NodeCredentials, OnchainPaymentLimitsResponse,
-    OpenChannelFeeRequest, OpenChannelFeeResponse, PayOnchainRequest, PayOnchainResponse,
-    PrepareOnchainPaymentRequest, PrepareOnchainPaymentResponse, PrepareRedeemOnchainFundsRequest,
-    PrepareRedeemOnchainFundsResponse, PrepareRefundRequest, PrepareRefundResponse,
+    AccelerateSwapDepositRequest, AccelerateSwapDepositResponse, BackupStatus, BumpFeeRequest,
+    BumpFeeResponse, BuyBitcoinRequest, BuyBitcoinResponse, CheckMessageRequest,
+    CheckMessageResponse, ConfigureNodeRequest, ConnectRequest, CreateOfferRequest,
+    CreateOfferResponse, EnvironmentType, ExportPaymentsRequest, ExportPaymentsResponse,
+    ListPaymentsRequest, LnUrlAuthError, LockedFiatRate, MaxReverseSwapAmountResponse, NodeConfig,
+    NodeCredentials, OnchainPaymentLimitsResponse, OpenChannelFeeRequest, OpenChannelFeeResponse,
+    PayOfferRequest, PayOnchainRequest, PayOnchainResponse, PaymentApprovalEvent,
+    PaymentApprovalRequest, PendingSpendingBudget, PrepareAccelerateSwapDepositRequest,
+    PrepareAccelerateSwapDepositResponse, PrepareOnchainPaymentRequest,
+    PrepareOnchainPaymentResponse, PrepareRedeemOnchainFundsRequest,
+    PrepareRedeemOnchainFundsResponse, PrepareRefundAllRequest, PrepareRefundRequest,
+    PrepareRefundResponse, PrepareSendPaymentRequest, PrepareSendPaymentResponse,
     ReceiveOnchainRequest, ReceivePaymentRequest, ReceivePaymentResponse,
-    RedeemOnchainFundsRequest, RedeemOnchainFundsResponse, RefundRequest, RefundResponse,
-    ReportIssueRequest, ReverseSwapFeesRequest, ReverseSwapInfo, ReverseSwapPairInfo,
-    SendOnchainRequest, SendOnchainResponse, SendPaymentRequest, SendPaymentResponse,
-    SendSpontaneousPaymentRequest, ServiceHealthCheckResponse, SignMessageRequest,
-    SignMessageResponse, StaticBackupRequest, StaticBackupResponse,
+    RedeemOnchainFundsRequest, RedeemOnchainFundsResponse, RefundAllRequest, RefundRequest,
+    RefundResponse, ReportIssueRequest, ReverseSwapFeesRequest, ReverseSwapInfo,
+    ReverseSwapPairInfo, SendOnchainRequest, SendOnchainResponse, SendPaymentRequest,
+    SendPaymentResponse, SendSpontaneousPaymentRequest, ServiceHealthCheckResponse,
+    SetPaymentAnnotationsRequest, SignMessageRequest, SignMessageResponse, SpendingBudget,
+    StaticBackupRequest, StaticBackupResponse, SweepPolicy,
 };
 
 // === FRB mirroring
@@ -155,12 +168,15 @@ pub struct _LnUrlWithdrawRequestData {
     pub default_description: String,
     pub min_withdrawable: u64,
     pub max_withdrawable: u64,
+    pub balance_check: Option<String>,
+    pub pay_link: Option<String>,
 }
 
 #[frb(mirror(InputType))]
 pub enum _InputType {
     BitcoinAddress { address: BitcoinAddressData },
     Bolt11 { invoice: LNInvoice },
+    Bolt12Offer { offer: LNOffer },
     NodeId { node_id: String },
     Url { url: String },
     LnUrlPay { data: LnUrlPayRequestData },
@@ -169,6 +185,29 @@ pub enum _InputType {
     LnUrlError { data: LnUrlErrorData },
 }
 
+#[frb(mirror(LNOffer))]
+pub struct _LNOffer {
+    pub offer: String,
+    pub chains: Vec<String>,
+    pub min_amount: Option<Amount>,
+    pub description: String,
+    pub absolute_expiry: Option<u64>,
+    pub issuer: Option<String>,
+    pub signing_pubkey: String,
+    pub paths_count: u32,
+}
+
+#[frb(mirror(Amount))]
+pub enum _Amount {
+    Bitcoin {
+        amount_msat: u64,
+    },
+    Currency {
+        iso4217_code: String,
+        fractional_amount: u64,
+    },
+}
+
 #[frb(mirror(BitcoinAddressData))]
 pub struct _BitcoinAddressData {
     pub address: String,
@@ -208,6 +247,12 @@ pub struct _UrlSuccessActionData {
     pub url: String,
 }
 
+#[frb(mirror(LnUrlVerifyStatus))]
+pub enum _LnUrlVerifyStatus {
+    Settled { preimage: String },
+    Pending,
+}
+
 #[frb(mirror(LnUrlPayErrorData))]
 pub struct _LnUrlPayErrorData {
     pub payment_hash: String,
@@ -228,6 +273,7 @@ pub enum _LnUrlPayError {
     RouteNotFound { err: String },
     RouteTooExpensive { err: String },
     ServiceConnectivity { err: String },
+    BudgetExceeded { err: String },
 }
 
 #[frb(mirror(LnUrlWithdrawResult))]
@@ -240,6 +286,7 @@ pub enum _LnUrlWithdrawResult {
 #[frb(mirror(LnUrlWithdrawSuccessData))]
 pub struct _LnUrlWithdrawSuccessData {
     pub invoice: LNInvoice,
+    pub refreshed_request: Option<LnUrlWithdrawRequestData>,
 }
 
 #[frb(mirror(Rate))]
@@ -287,52 +334,60 @@ pub struct _Symbol {
 }
 
 /*
-The format Lazy<Mutex<Option<...>>> for the following variables allows them to be instance-global,
-meaning they can be set only once per instance, but calling disconnect() will unset them.
+The connected wallets, by the app-defined wallet id that is passed to each API call.
  */
-static BREEZ_SERVICES_INSTANCE: Lazy<Mutex<Option<Arc<BreezServices>>>> =
-    Lazy::new(|| Mutex::new(None));
+static WALLETS: Lazy<WalletManager> = Lazy::new(WalletManager::new);
 static NOTIFICATION_STREAM: OnceCell<StreamSink<BreezEvent>> = OnceCell::new();
+static WALLET_EVENTS_STREAM: OnceCell<StreamSink<WalletEvent>> = OnceCell::new();
 static RT: Lazy<tokio::runtime::Runtime> = Lazy::new(|| tokio::runtime::Runtime::new().unwrap());
 static LOG_INIT: OnceCell<bool> = OnceCell::new();
+static PAYMENT_APPROVAL_STREAM: OnceCell<StreamSink<PaymentApprovalEvent>> = OnceCell::new();
+/// The payment approvals waiting for the app to respond, by id
+static PAYMENT_APPROVALS: Lazy<Mutex<HashMap<u64, mpsc::Sender<bool>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+static NEXT_PAYMENT_APPROVAL_ID: AtomicU64 = AtomicU64::new(0);
+/// How long a payment waits for the app to approve it, before being rejected
+const PAYMENT_APPROVAL_TIMEOUT: Duration = Duration::from_secs(300);
 
 /*  Breez Services API's */
 
-/// Wrapper around [BreezServices::connect] which also initializes SDK logging
-pub fn connect(req: ConnectRequest) -> Result<()> {
+/// Connects the wallet with the app-defined `wallet_id`, which the other calls are made with.
+/// See [WalletManager::connect].
+pub fn connect(wallet_id: String, req: ConnectRequest) -> Result<()> {
     block_on(async move {
-        let mut locked = BREEZ_SERVICES_INSTANCE.lock().await;
-        match *locked {
-            None => {
-                let breez_services =
-                    BreezServices::connect(req, Box::new(BindingEventListener {})).await?;
-
-                *locked = Some(breez_services);
-                Ok(())
-            }
-            Some(_) => Err(ConnectError::Generic {
-                err: "Static node services already set, please call disconnect() first".into(),
-            }),
-        }
+        let event_listener = BindingEventListener {
+            wallet_id: wallet_id.clone(),
+        };
+        let payment_approver = PAYMENT_APPROVAL_STREAM.get().map(|_| {
+            Box::new(BindingPaymentApprover {
+                wallet_id: wallet_id.clone(),
+            }) as Box<dyn PaymentApprover>
+        });
+        WALLETS
+            .connect(wallet_id, req, Box::new(event_listener), payment_approver)
+            .await?;
+        Ok(())
     })
     .map_err(anyhow::Error::new::<ConnectError>)
 }
 
-/// Check whether node service is initialized or not
-pub fn is_initialized() -> bool {
-    block_on(async { get_breez_services().await.is_ok() })
+/// Check whether the wallet is connected or not
+pub fn is_initialized(wallet_id: String) -> bool {
+    WALLETS.get_wallet(&wallet_id).is_ok()
 }
 
 /// See [BreezServices::sync]
-pub fn sync() -> Result<()> {
-    block_on(async { get_breez_services().await?.sync().await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn sync(wallet_id: String) -> Result<()> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id).await?.sync().await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::node_credentials]
-pub fn node_credentials() -> Result<Option<NodeCredentials>> {
-    block_on(async {
-        get_breez_services()
+pub fn node_credentials(wallet_id: String) -> Result<Option<NodeCredentials>> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
             .await?
             .node_credentials()
             .map_err(anyhow::Error::new::<SdkError>)
@@ -340,9 +395,9 @@ pub fn node_credentials() -> Result<Option<NodeCredentials>> {
 }
 
 /// See [BreezServices::node_info]
-pub fn node_info() -> Result<NodeState> {
-    block_on(async {
-        get_breez_services()
+pub fn node_info(wallet_id: String) -> Result<NodeState> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
             .await?
             .node_info()
             .map_err(anyhow::Error::new::<SdkError>)
@@ -350,34 +405,49 @@ pub fn node_info() -> Result<NodeState> {
 }
 
 /// See [BreezServices::configure_node]
-pub fn configure_node(req: ConfigureNodeRequest) -> Result<()> {
-    block_on(async { get_breez_services().await?.configure_node(req).await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn configure_node(wallet_id: String, req: ConfigureNodeRequest) -> Result<()> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .configure_node(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
-/// Cleanup node resources and stop the signer.
-pub fn disconnect() -> Result<()> {
-    block_on(async {
-        // To avoid deadlock: first disconnect SDK, then acquire lock and unset global instance
-        get_breez_services().await?.disconnect().await?;
-        let mut locked_sdk_instance = BREEZ_SERVICES_INSTANCE.lock().await;
-        *locked_sdk_instance = None;
+/// Cleanup node resources and stop the signer of the wallet. See [WalletManager::disconnect].
+pub fn disconnect(wallet_id: String) -> Result<()> {
+    rt().block_on(WALLETS.disconnect(&wallet_id))
+        .map_err(anyhow::Error::new::<SdkError>)
+}
 
-        Ok(())
+/// See [BreezServices::sign_message]
+pub fn sign_message(wallet_id: String, req: SignMessageRequest) -> Result<SignMessageResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .sign_message(req)
+            .await
     })
     .map_err(anyhow::Error::new::<SdkError>)
 }
 
-/// See [BreezServices::sign_message]
-pub fn sign_message(req: SignMessageRequest) -> Result<SignMessageResponse> {
-    block_on(async { get_breez_services().await?.sign_message(req).await })
-        .map_err(anyhow::Error::new::<SdkError>)
+/// See [BreezServices::check_message]
+pub fn check_message(wallet_id: String, req: CheckMessageRequest) -> Result<CheckMessageResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .check_message(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
-/// See [BreezServices::check_message]
-pub fn check_message(req: CheckMessageRequest) -> Result<CheckMessageResponse> {
-    block_on(async { get_breez_services().await?.check_message(req).await })
-        .map_err(anyhow::Error::new::<SdkError>)
+/*  Wallet API's */
+
+/// See [WalletManager::list_wallets]
+pub fn list_wallets() -> Vec<String> {
+    WALLETS.list_wallets()
 }
 
 /*  Breez Services Helper API's */
@@ -417,6 +487,39 @@ pub fn breez_events_stream(s: StreamSink<BreezEvent>) -> Result<()> {
     Ok(())
 }
 
+/// Streams the events of all the connected wallets, tagged with the id of their wallet.
+///
+/// If used, this must be called before `connect`. It can only be called once.
+pub fn wallet_events_stream(s: StreamSink<WalletEvent>) -> Result<()> {
+    WALLET_EVENTS_STREAM
+        .set(s)
+        .map_err(|_| anyhow!("Wallet events stream already created"))?;
+    Ok(())
+}
+
+/// Streams the payments above the [SpendingBudget] approval threshold, to be answered with
+/// [respond_payment_approval]. Without it, these payments are rejected.
+///
+/// If used, this must be called before `connect`. It can only be called once.
+pub fn payment_approval_stream(s: StreamSink<PaymentApprovalEvent>) -> Result<()> {
+    PAYMENT_APPROVAL_STREAM
+        .set(s)
+        .map_err(|_| anyhow!("Payment approval stream already created"))?;
+    Ok(())
+}
+
+/// Approves or rejects the payment of a [PaymentApprovalEvent]
+pub fn respond_payment_approval(id: u64, approved: bool) -> Result<()> {
+    let sender = PAYMENT_APPROVALS
+        .lock()
+        .unwrap()
+        .remove(&id)
+        .ok_or(anyhow!("Payment approval {id} is not pending"))?;
+    sender
+        .send(approved)
+        .map_err(|_| anyhow!("Payment approval {id} is not pending"))
+}
+
 /// If used, this must be called before `connect`. It can only be called once.
 pub fn breez_log_stream(s: StreamSink<LogEntry>) -> Result<()> {
     LOG_INIT
@@ -426,49 +529,75 @@ pub fn breez_log_stream(s: StreamSink<LogEntry>) -> Result<()> {
     Ok(())
 }
 
+/// Sets the stream receiving the log statements of the wallet. It requires the log stream to be
+/// set with [breez_log_stream].
+pub fn breez_wallet_log_stream(wallet_id: String, s: StreamSink<LogEntry>) -> Result<()> {
+    WalletManager::set_log_stream(&wallet_id, Box::new(BindingLogStream { log_stream: s }));
+    Ok(())
+}
+
 /*  LSP API's */
 
 /// See [BreezServices::list_lsps]
-pub fn list_lsps() -> Result<Vec<LspInformation>> {
-    block_on(async { get_breez_services().await?.list_lsps().await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn list_lsps(wallet_id: String) -> Result<Vec<LspInformation>> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id).await?.list_lsps().await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::connect_lsp]
-pub fn connect_lsp(lsp_id: String) -> Result<()> {
-    block_on(async { get_breez_services().await?.connect_lsp(lsp_id).await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn connect_lsp(wallet_id: String, lsp_id: String) -> Result<()> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .connect_lsp(lsp_id)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::lsp_id]
-pub fn lsp_id() -> Result<Option<String>> {
-    block_on(async { get_breez_services().await?.lsp_id().await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn lsp_id(wallet_id: String) -> Result<Option<String>> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id).await?.lsp_id().await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::fetch_lsp_info]
-pub fn fetch_lsp_info(id: String) -> Result<Option<LspInformation>> {
-    block_on(async { get_breez_services().await?.fetch_lsp_info(id).await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn fetch_lsp_info(wallet_id: String, id: String) -> Result<Option<LspInformation>> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .fetch_lsp_info(id)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::lsp_info]
-pub fn lsp_info() -> Result<LspInformation> {
-    block_on(async { get_breez_services().await?.lsp_info().await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn lsp_info(wallet_id: String) -> Result<LspInformation> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id).await?.lsp_info().await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::close_lsp_channels]
-pub fn close_lsp_channels() -> Result<()> {
-    block_on(async {
-        _ = get_breez_services().await?.close_lsp_channels().await?;
+pub fn close_lsp_channels(wallet_id: String) -> Result<()> {
+    block_on_wallet(&wallet_id, async {
+        _ = get_breez_services(&wallet_id)
+            .await?
+            .close_lsp_channels()
+            .await?;
         Ok(())
     })
 }
 
-pub fn register_webhook(webhook_url: String) -> Result<()> {
-    block_on(async {
-        get_breez_services()
+pub fn register_webhook(wallet_id: String, webhook_url: String) -> Result<()> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
             .await?
             .register_webhook(webhook_url)
             .await
@@ -476,9 +605,9 @@ pub fn register_webhook(webhook_url: String) -> Result<()> {
     .map_err(anyhow::Error::new::<SdkError>)
 }
 
-pub fn unregister_webhook(webhook_url: String) -> Result<()> {
-    block_on(async {
-        get_breez_services()
+pub fn unregister_webhook(wallet_id: String, webhook_url: String) -> Result<()> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
             .await?
             .unregister_webhook(webhook_url)
             .await
@@ -489,15 +618,19 @@ pub fn unregister_webhook(webhook_url: String) -> Result<()> {
 /*  Backup API's */
 
 /// See [BreezServices::backup]
-pub fn backup() -> Result<()> {
-    block_on(async { get_breez_services().await?.backup().await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn backup(wallet_id: String) -> Result<()> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id).await?.backup().await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::backup_status]
-pub fn backup_status() -> Result<BackupStatus> {
-    block_on(async { get_breez_services().await?.backup_status() })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn backup_status(wallet_id: String) -> Result<BackupStatus> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id).await?.backup_status()
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /*  Parse API's */
@@ -513,21 +646,56 @@ pub fn parse_input(input: String) -> Result<InputType> {
 /*  Payment API's */
 
 /// See [BreezServices::list_payments]
-pub fn list_payments(req: ListPaymentsRequest) -> Result<Vec<Payment>> {
-    block_on(async { get_breez_services().await?.list_payments(req).await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn list_payments(wallet_id: String, req: ListPaymentsRequest) -> Result<Vec<Payment>> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .list_payments(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::count_payments]
+pub fn count_payments(wallet_id: String, req: ListPaymentsRequest) -> Result<u64> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .count_payments(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::export_payments]
+pub fn export_payments(
+    wallet_id: String,
+    req: ExportPaymentsRequest,
+) -> Result<ExportPaymentsResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .export_payments(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::list_payments]
-pub fn payment_by_hash(hash: String) -> Result<Option<Payment>> {
-    block_on(async { get_breez_services().await?.payment_by_hash(hash).await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn payment_by_hash(wallet_id: String, hash: String) -> Result<Option<Payment>> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .payment_by_hash(hash)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::set_payment_metadata]
-pub fn set_payment_metadata(hash: String, metadata: String) -> Result<()> {
-    block_on(async {
-        get_breez_services()
+pub fn set_payment_metadata(wallet_id: String, hash: String, metadata: String) -> Result<()> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
             .await?
             .set_payment_metadata(hash, metadata)
             .await
@@ -535,18 +703,59 @@ pub fn set_payment_metadata(hash: String, metadata: String) -> Result<()> {
     .map_err(anyhow::Error::new::<SdkError>)
 }
 
+/// See [BreezServices::set_payment_annotations]
+pub fn set_payment_annotations(wallet_id: String, req: SetPaymentAnnotationsRequest) -> Result<()> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .set_payment_annotations(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::list_tags]
+pub fn list_tags(wallet_id: String) -> Result<Vec<String>> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id).await?.list_tags().await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
 /*  Lightning Payment API's */
 
+/// See [BreezServices::prepare_send_payment]
+pub fn prepare_send_payment(
+    wallet_id: String,
+    req: PrepareSendPaymentRequest,
+) -> Result<PrepareSendPaymentResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .prepare_send_payment(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SendPaymentError>)
+}
+
 /// See [BreezServices::send_payment]
-pub fn send_payment(req: SendPaymentRequest) -> Result<SendPaymentResponse> {
-    block_on(async { get_breez_services().await?.send_payment(req).await })
-        .map_err(anyhow::Error::new::<SendPaymentError>)
+pub fn send_payment(wallet_id: String, req: SendPaymentRequest) -> Result<SendPaymentResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .send_payment(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SendPaymentError>)
 }
 
 /// See [BreezServices::send_spontaneous_payment]
-pub fn send_spontaneous_payment(req: SendSpontaneousPaymentRequest) -> Result<SendPaymentResponse> {
-    block_on(async {
-        get_breez_services()
+pub fn send_spontaneous_payment(
+    wallet_id: String,
+    req: SendSpontaneousPaymentRequest,
+) -> Result<SendPaymentResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
             .await?
             .send_spontaneous_payment(req)
             .await
@@ -554,100 +763,250 @@ pub fn send_spontaneous_payment(req: SendSpontaneousPaymentRequest) -> Result<Se
     .map_err(anyhow::Error::new::<SendPaymentError>)
 }
 
+/// See [BreezServices::pay_offer]
+pub fn pay_offer(wallet_id: String, req: PayOfferRequest) -> Result<SendPaymentResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id).await?.pay_offer(req).await
+    })
+    .map_err(anyhow::Error::new::<SendPaymentError>)
+}
+
+/// See [BreezServices::create_offer]
+pub fn create_offer(wallet_id: String, req: CreateOfferRequest) -> Result<CreateOfferResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .create_offer(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
 /// See [BreezServices::receive_payment]
-pub fn receive_payment(req: ReceivePaymentRequest) -> Result<ReceivePaymentResponse> {
-    block_on(async { get_breez_services().await?.receive_payment(req).await })
-        .map_err(anyhow::Error::new::<ReceivePaymentError>)
+pub fn receive_payment(
+    wallet_id: String,
+    req: ReceivePaymentRequest,
+) -> Result<ReceivePaymentResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .receive_payment(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<ReceivePaymentError>)
+}
+
+/// See [BreezServices::list_payment_requests]
+pub fn list_payment_requests(
+    wallet_id: String,
+    req: ListPaymentRequestsRequest,
+) -> Result<Vec<PaymentRequest>> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .list_payment_requests(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::cancel_payment_request]
+pub fn cancel_payment_request(wallet_id: String, payment_hash: String) -> Result<()> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .cancel_payment_request(payment_hash)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /*  LNURL API's */
 
 /// See [BreezServices::lnurl_pay]
-pub fn lnurl_pay(req: LnUrlPayRequest) -> Result<crate::lnurl::pay::LnUrlPayResult> {
-    block_on(async { get_breez_services().await?.lnurl_pay(req).await })
-        .map_err(anyhow::Error::new::<crate::LnUrlPayError>)
+pub fn lnurl_pay(
+    wallet_id: String,
+    req: LnUrlPayRequest,
+) -> Result<crate::lnurl::pay::LnUrlPayResult> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id).await?.lnurl_pay(req).await
+    })
+    .map_err(anyhow::Error::new::<crate::LnUrlPayError>)
+}
+
+/// See [BreezServices::handle_lnurl_pay_server_request]
+pub fn handle_lnurl_pay_server_request(
+    wallet_id: String,
+    config: crate::lnurl::server::LnUrlPayServerConfig,
+    url: String,
+) -> Result<crate::lnurl::server::LnUrlPayServerResponse> {
+    block_on_wallet(&wallet_id, async {
+        Ok(get_breez_services(&wallet_id)
+            .await?
+            .handle_lnurl_pay_server_request(config, url)
+            .await)
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::verify_lnurl_payment]
+pub fn verify_lnurl_payment(wallet_id: String, payment_hash: String) -> Result<LnUrlVerifyStatus> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .verify_lnurl_payment(payment_hash)
+            .await
+    })
+    .map_err(anyhow::Error::new::<crate::LnUrlPayError>)
 }
 
 /// See [BreezServices::lnurl_withdraw]
-pub fn lnurl_withdraw(req: LnUrlWithdrawRequest) -> Result<LnUrlWithdrawResult> {
-    block_on(async { get_breez_services().await?.lnurl_withdraw(req).await })
-        .map_err(anyhow::Error::new::<crate::LnUrlWithdrawError>)
+pub fn lnurl_withdraw(wallet_id: String, req: LnUrlWithdrawRequest) -> Result<LnUrlWithdrawResult> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .lnurl_withdraw(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<crate::LnUrlWithdrawError>)
 }
 
 /// See [BreezServices::lnurl_auth]
-pub fn lnurl_auth(req_data: crate::LnUrlAuthRequestData) -> Result<LnUrlCallbackStatus> {
-    block_on(async { get_breez_services().await?.lnurl_auth(req_data).await })
-        .map_err(anyhow::Error::new::<LnUrlAuthError>)
+pub fn lnurl_auth(
+    wallet_id: String,
+    req_data: crate::LnUrlAuthRequestData,
+) -> Result<LnUrlCallbackStatus> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .lnurl_auth(req_data)
+            .await
+    })
+    .map_err(anyhow::Error::new::<LnUrlAuthError>)
 }
 
 /*  Support API */
 
 /// See [BreezServices::report_issue]
-pub fn report_issue(req: ReportIssueRequest) -> Result<()> {
-    block_on(async { get_breez_services().await?.report_issue(req).await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn report_issue(wallet_id: String, req: ReportIssueRequest) -> Result<()> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .report_issue(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /*  Fiat Currency API's */
 
 /// See [BreezServices::fetch_fiat_rates]
-pub fn fetch_fiat_rates() -> Result<Vec<Rate>> {
-    block_on(async { get_breez_services().await?.fetch_fiat_rates().await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn fetch_fiat_rates(wallet_id: String) -> Result<Vec<Rate>> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .fetch_fiat_rates()
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::lock_fiat_rate]
+pub fn lock_fiat_rate(wallet_id: String, currency: String) -> Result<LockedFiatRate> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .lock_fiat_rate(currency)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::list_fiat_currencies]
-pub fn list_fiat_currencies() -> Result<Vec<FiatCurrency>> {
-    block_on(async { get_breez_services().await?.list_fiat_currencies().await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn list_fiat_currencies(wallet_id: String) -> Result<Vec<FiatCurrency>> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .list_fiat_currencies()
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /*  On-Chain Swap API's */
 
 /// See [BreezServices::max_reverse_swap_amount]
-pub fn max_reverse_swap_amount() -> Result<MaxReverseSwapAmountResponse> {
+pub fn max_reverse_swap_amount(wallet_id: String) -> Result<MaxReverseSwapAmountResponse> {
     #[allow(deprecated)]
-    block_on(async { get_breez_services().await?.max_reverse_swap_amount().await })
-        .map_err(anyhow::Error::new::<SdkError>)
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .max_reverse_swap_amount()
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::send_onchain]
-pub fn send_onchain(req: SendOnchainRequest) -> Result<SendOnchainResponse> {
+pub fn send_onchain(wallet_id: String, req: SendOnchainRequest) -> Result<SendOnchainResponse> {
     #[allow(deprecated)]
-    block_on(async { get_breez_services().await?.send_onchain(req).await })
-        .map_err(anyhow::Error::new::<SendOnchainError>)
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .send_onchain(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SendOnchainError>)
 }
 
 /// See [BreezServices::pay_onchain]
-pub fn pay_onchain(req: PayOnchainRequest) -> Result<PayOnchainResponse> {
-    block_on(async { get_breez_services().await?.pay_onchain(req).await })
-        .map_err(anyhow::Error::new::<SendOnchainError>)
+pub fn pay_onchain(wallet_id: String, req: PayOnchainRequest) -> Result<PayOnchainResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id).await?.pay_onchain(req).await
+    })
+    .map_err(anyhow::Error::new::<SendOnchainError>)
 }
 
 /// See [BreezServices::receive_onchain]
-pub fn receive_onchain(req: ReceiveOnchainRequest) -> Result<SwapInfo> {
-    block_on(async { get_breez_services().await?.receive_onchain(req).await })
-        .map_err(anyhow::Error::new::<ReceiveOnchainError>)
+pub fn receive_onchain(wallet_id: String, req: ReceiveOnchainRequest) -> Result<SwapInfo> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .receive_onchain(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<ReceiveOnchainError>)
 }
 
 /// See [BreezServices::buy_bitcoin]
-pub fn buy_bitcoin(req: BuyBitcoinRequest) -> Result<BuyBitcoinResponse> {
-    block_on(async { get_breez_services().await?.buy_bitcoin(req).await })
-        .map_err(anyhow::Error::new::<ReceiveOnchainError>)
+pub fn buy_bitcoin(wallet_id: String, req: BuyBitcoinRequest) -> Result<BuyBitcoinResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id).await?.buy_bitcoin(req).await
+    })
+    .map_err(anyhow::Error::new::<ReceiveOnchainError>)
 }
 
 /// See [BreezServices::redeem_onchain_funds]
-pub fn redeem_onchain_funds(req: RedeemOnchainFundsRequest) -> Result<RedeemOnchainFundsResponse> {
-    block_on(async { get_breez_services().await?.redeem_onchain_funds(req).await })
-        .map_err(anyhow::Error::new::<RedeemOnchainError>)
+pub fn redeem_onchain_funds(
+    wallet_id: String,
+    req: RedeemOnchainFundsRequest,
+) -> Result<RedeemOnchainFundsResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .redeem_onchain_funds(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<RedeemOnchainError>)
 }
 
 /// See [BreezServices::prepare_redeem_onchain_funds]
 pub fn prepare_redeem_onchain_funds(
+    wallet_id: String,
     req: PrepareRedeemOnchainFundsRequest,
 ) -> Result<PrepareRedeemOnchainFundsResponse> {
-    block_on(async {
-        get_breez_services()
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
             .await?
             .prepare_redeem_onchain_funds(req)
             .await
@@ -658,48 +1017,144 @@ pub fn prepare_redeem_onchain_funds(
 /*  Refundables API's */
 
 /// See [BreezServices::list_refundables]
-pub fn list_refundables() -> Result<Vec<SwapInfo>> {
-    block_on(async { get_breez_services().await?.list_refundables().await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn list_refundables(wallet_id: String) -> Result<Vec<SwapInfo>> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .list_refundables()
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::prepare_refund]
-pub fn prepare_refund(req: PrepareRefundRequest) -> Result<PrepareRefundResponse> {
-    block_on(async { get_breez_services().await?.prepare_refund(req).await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn prepare_refund(
+    wallet_id: String,
+    req: PrepareRefundRequest,
+) -> Result<PrepareRefundResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .prepare_refund(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::refund]
-pub fn refund(req: RefundRequest) -> Result<RefundResponse> {
-    block_on(async { get_breez_services().await?.refund(req).await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn refund(wallet_id: String, req: RefundRequest) -> Result<RefundResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id).await?.refund(req).await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::request_cooperative_refund]
+pub fn request_cooperative_refund(wallet_id: String, req: RefundRequest) -> Result<RefundResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .request_cooperative_refund(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::prepare_refund_all]
+pub fn prepare_refund_all(
+    wallet_id: String,
+    req: PrepareRefundAllRequest,
+) -> Result<PrepareRefundResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .prepare_refund_all(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::refund_all]
+pub fn refund_all(wallet_id: String, req: RefundAllRequest) -> Result<RefundResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id).await?.refund_all(req).await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::bump_fee]
+pub fn bump_fee(wallet_id: String, req: BumpFeeRequest) -> Result<BumpFeeResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id).await?.bump_fee(req).await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::prepare_accelerate_swap_deposit]
+pub fn prepare_accelerate_swap_deposit(
+    wallet_id: String,
+    req: PrepareAccelerateSwapDepositRequest,
+) -> Result<PrepareAccelerateSwapDepositResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .prepare_accelerate_swap_deposit(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::accelerate_swap_deposit]
+pub fn accelerate_swap_deposit(
+    wallet_id: String,
+    req: AccelerateSwapDepositRequest,
+) -> Result<AccelerateSwapDepositResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .accelerate_swap_deposit(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::rescan_swaps]
-pub fn rescan_swaps() -> Result<()> {
-    block_on(async { get_breez_services().await?.rescan_swaps().await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn rescan_swaps(wallet_id: String) -> Result<()> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id).await?.rescan_swaps().await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::redeem_swap]
-pub fn redeem_swap(swap_address: String) -> Result<()> {
-    block_on(async { get_breez_services().await?.redeem_swap(swap_address).await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn redeem_swap(wallet_id: String, swap_address: String) -> Result<()> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .redeem_swap(swap_address)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /*  In Progress Swap API's */
 
 /// See [BreezServices::in_progress_swap]
-pub fn in_progress_swap() -> Result<Option<SwapInfo>> {
-    block_on(async { get_breez_services().await?.in_progress_swap().await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn in_progress_swap(wallet_id: String) -> Result<Option<SwapInfo>> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .in_progress_swap()
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::in_progress_reverse_swaps]
-pub fn in_progress_reverse_swaps() -> Result<Vec<ReverseSwapInfo>> {
+pub fn in_progress_reverse_swaps(wallet_id: String) -> Result<Vec<ReverseSwapInfo>> {
     #[allow(deprecated)]
-    block_on(async {
-        get_breez_services()
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
             .await?
             .in_progress_reverse_swaps()
             .await
@@ -708,9 +1163,9 @@ pub fn in_progress_reverse_swaps() -> Result<Vec<ReverseSwapInfo>> {
 }
 
 /// See [BreezServices::claim_reverse_swap]
-pub fn claim_reverse_swap(lockup_address: String) -> Result<()> {
-    block_on(async {
-        get_breez_services()
+pub fn claim_reverse_swap(wallet_id: String, lockup_address: String) -> Result<()> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
             .await?
             .claim_reverse_swap(lockup_address)
             .await
@@ -721,15 +1176,26 @@ pub fn claim_reverse_swap(lockup_address: String) -> Result<()> {
 /*  Swap Fee API's */
 
 /// See [BreezServices::open_channel_fee]
-pub fn open_channel_fee(req: OpenChannelFeeRequest) -> Result<OpenChannelFeeResponse> {
-    block_on(async { get_breez_services().await?.open_channel_fee(req).await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn open_channel_fee(
+    wallet_id: String,
+    req: OpenChannelFeeRequest,
+) -> Result<OpenChannelFeeResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .open_channel_fee(req)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::fetch_reverse_swap_fees]
-pub fn fetch_reverse_swap_fees(req: ReverseSwapFeesRequest) -> Result<ReverseSwapPairInfo> {
-    block_on(async {
-        get_breez_services()
+pub fn fetch_reverse_swap_fees(
+    wallet_id: String,
+    req: ReverseSwapFeesRequest,
+) -> Result<ReverseSwapPairInfo> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
             .await?
             .fetch_reverse_swap_fees(req)
             .await
@@ -738,17 +1204,23 @@ pub fn fetch_reverse_swap_fees(req: ReverseSwapFeesRequest) -> Result<ReverseSwa
 }
 
 /// See [BreezServices::onchain_payment_limits]
-pub fn onchain_payment_limits() -> Result<OnchainPaymentLimitsResponse> {
-    block_on(async { get_breez_services().await?.onchain_payment_limits().await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn onchain_payment_limits(wallet_id: String) -> Result<OnchainPaymentLimitsResponse> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .onchain_payment_limits()
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /// See [BreezServices::prepare_onchain_payment]
 pub fn prepare_onchain_payment(
+    wallet_id: String,
     req: PrepareOnchainPaymentRequest,
 ) -> Result<PrepareOnchainPaymentResponse> {
-    block_on(async {
-        get_breez_services()
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
             .await?
             .prepare_onchain_payment(req)
             .await
@@ -757,9 +1229,9 @@ pub fn prepare_onchain_payment(
 }
 
 /// See [BreezServices::in_progress_onchain_payments]
-pub fn in_progress_onchain_payments() -> Result<Vec<ReverseSwapInfo>> {
-    block_on(async {
-        get_breez_services()
+pub fn in_progress_onchain_payments(wallet_id: String) -> Result<Vec<ReverseSwapInfo>> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
             .await?
             .in_progress_onchain_payments()
             .await
@@ -768,17 +1240,74 @@ pub fn in_progress_onchain_payments() -> Result<Vec<ReverseSwapInfo>> {
 }
 
 /// See [BreezServices::recommended_fees]
-pub fn recommended_fees() -> Result<RecommendedFees> {
-    block_on(async { get_breez_services().await?.recommended_fees().await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn recommended_fees(wallet_id: String) -> Result<RecommendedFees> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .recommended_fees()
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::set_sweep_policy]
+pub fn set_sweep_policy(wallet_id: String, policy: SweepPolicy) -> Result<()> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .set_sweep_policy(policy)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::sweep_policy]
+pub fn sweep_policy(wallet_id: String) -> Result<SweepPolicy> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id).await?.sweep_policy().await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::set_spending_budget]
+pub fn set_spending_budget(wallet_id: String, budget: SpendingBudget) -> Result<()> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .set_spending_budget(budget)
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::spending_budget]
+pub fn spending_budget(wallet_id: String) -> Result<SpendingBudget> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .spending_budget()
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
+}
+
+/// See [BreezServices::pending_spending_budget]
+pub fn pending_spending_budget(wallet_id: String) -> Result<Option<PendingSpendingBudget>> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .pending_spending_budget()
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /*  CLI API's */
 
 /// See [BreezServices::execute_dev_command]
-pub fn execute_command(command: String) -> Result<String> {
-    block_on(async {
-        get_breez_services()
+pub fn execute_command(wallet_id: String, command: String) -> Result<String> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
             .await?
             .execute_dev_command(command)
             .await
@@ -787,20 +1316,69 @@ pub fn execute_command(command: String) -> Result<String> {
 }
 
 /// See [BreezServices::generate_diagnostic_data]
-pub fn generate_diagnostic_data() -> Result<String> {
-    block_on(async { get_breez_services().await?.generate_diagnostic_data().await })
-        .map_err(anyhow::Error::new::<SdkError>)
+pub fn generate_diagnostic_data(wallet_id: String) -> Result<String> {
+    block_on_wallet(&wallet_id, async {
+        get_breez_services(&wallet_id)
+            .await?
+            .generate_diagnostic_data()
+            .await
+    })
+    .map_err(anyhow::Error::new::<SdkError>)
 }
 
 /*  Binding Related Logic */
 
-struct BindingEventListener;
+struct BindingEventListener {
+    wallet_id: String,
+}
 
 impl EventListener for BindingEventListener {
     fn on_event(&self, e: BreezEvent) {
         if let Some(stream) = NOTIFICATION_STREAM.get() {
-            stream.add(e);
+            stream.add(e.clone());
         }
+        if let Some(stream) = WALLET_EVENTS_STREAM.get() {
+            stream.add(WalletEvent {
+                wallet_id: self.wallet_id.clone(),
+                event: e,
+            });
+        }
+    }
+}
+
+/// Sends the payments to approve to the approval stream, and waits for the app to respond
+struct BindingPaymentApprover {
+    wallet_id: String,
+}
+
+impl PaymentApprover for BindingPaymentApprover {
+    fn approve_payment(&self, req: PaymentApprovalRequest) -> bool {
+        let Some(stream) = PAYMENT_APPROVAL_STREAM.get() else {
+            return false;
+        };
+        let id = NEXT_PAYMENT_APPROVAL_ID.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = mpsc::channel();
+        PAYMENT_APPROVALS.lock().unwrap().insert(id, sender);
+        stream.add(PaymentApprovalEvent {
+            id,
+            wallet_id: self.wallet_id.clone(),
+            request: req,
+        });
+        let approved = receiver
+            .recv_timeout(PAYMENT_APPROVAL_TIMEOUT)
+            .unwrap_or(false);
+        PAYMENT_APPROVALS.lock().unwrap().remove(&id);
+        approved
+    }
+}
+
+struct BindingLogStream {
+    log_stream: StreamSink<LogEntry>,
+}
+
+impl LogStream for BindingLogStream {
+    fn log(&self, l: LogEntry) {
+        self.log_stream.add(l);
     }
 }
 
@@ -827,24 +1405,25 @@ impl log::Log for BindingLogger {
                 line: record.args().to_string(),
                 level: record.level().as_str().to_string(),
             });
+            wallets::log_to_wallet_stream(record);
         }
     }
     fn flush(&self) {}
 }
 
-async fn get_breez_services() -> Result<Arc<BreezServices>, SdkError> {
-    match BREEZ_SERVICES_INSTANCE.lock().await.as_ref() {
-        None => Err(SdkError::Generic {
-            err: "Node service was not initialized".into(),
-        }),
-        Some(sdk) => Ok(sdk.clone()),
-    }
+async fn get_breez_services(wallet_id: &str) -> Result<Arc<BreezServices>, SdkError> {
+    WALLETS.get_wallet(wallet_id)
 }
 
 fn block_on<F: Future>(future: F) -> F::Output {
     rt().block_on(future)
 }
 
+/// Runs the future in the context of the wallet, so its log statements go to its log stream
+fn block_on_wallet<F: Future>(wallet_id: &str, future: F) -> F::Output {
+    rt().block_on(wallets::with_wallet_context(wallet_id.to_string(), future))
+}
+
 pub(crate) fn rt() -> &'static tokio::runtime::Runtime {
     &RT
 }
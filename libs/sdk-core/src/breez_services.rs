@@ -1,16 +1,18 @@
+use std::collections::HashMap;
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, ensure, Result};
 use bip39::*;
 use bitcoin::hashes::hex::ToHex;
 use bitcoin::hashes::{sha256, Hash};
 use bitcoin::util::bip32::ChildNumber;
-use chrono::Local;
-use futures::TryFutureExt;
+use bitcoin::{OutPoint, Txid};
+use chrono::{Local, Utc};
+use futures::{StreamExt, TryFutureExt};
 use gl_client::bitcoin::secp256k1::Secp256k1;
 use log::{LevelFilter, Metadata, Record};
 use reqwest::{header::CONTENT_TYPE, Body, Url};
@@ -19,41 +21,71 @@ use sdk_common::prelude::*;
 use serde::Serialize;
 use serde_json::json;
 use strum_macros::EnumString;
-use tokio::sync::{mpsc, watch, Mutex};
+use tokio::sync::{mpsc, watch, Mutex, Notify};
 use tokio::time::{sleep, MissedTickBehavior};
 
 use crate::backup::{BackupRequest, BackupTransport, BackupWatcher};
 use crate::buy::{BuyBitcoinApi, BuyBitcoinService};
 use crate::chain::{
-    ChainService, Outspend, RecommendedFees, RedundantChainService, RedundantChainServiceTrait,
-    DEFAULT_MEMPOOL_SPACE_URL,
+    validate_replacement, ChainService, Outspend, RecommendedFees, RedundantChainService,
+    RedundantChainServiceTrait, DEFAULT_MEMPOOL_SPACE_URL,
 };
+use crate::cln::{Cln, ClnBackupTransport};
 use crate::error::{
     ConnectError, ReceiveOnchainError, ReceiveOnchainResult, ReceivePaymentError,
     RedeemOnchainResult, SdkError, SdkResult, SendOnchainError, SendPaymentError,
 };
+use crate::export::{format_payments, HistoricalRates};
+use crate::greenlight::node_api::default_fee_limit_msat;
 use crate::greenlight::{GLBackupTransport, Greenlight};
 use crate::lnurl::pay::*;
-use crate::lsp::LspInformation;
+use crate::lnurl::server::{LnUrlPayServerConfig, LnUrlPayServerRequest, LnUrlPayServerResponse};
+use crate::lsp::{LspInformation, LspRegistry};
+use crate::lsps0;
+use crate::lsps2;
 use crate::models::{
-    parse_short_channel_id, ChannelState, ClosedChannelPaymentDetails, Config, EnvironmentType,
-    LspAPI, NodeState, Payment, PaymentDetails, PaymentType, ReverseSwapPairInfo,
+    parse_short_channel_id, split_payment, ChannelState, ClosedChannelPaymentDetails, Config,
+    EnvironmentType, LspAPI, NodeState, Payment, PaymentDetails, PaymentType, ReverseSwapPairInfo,
     ReverseSwapServiceAPI, SwapInfo, SwapperAPI, INVOICE_PAYMENT_FEE_EXPIRY_SECONDS,
 };
-use crate::node_api::{CreateInvoiceRequest, NodeAPI};
+use crate::node_api::{CreateInvoiceRequest, NodeAPI, NodeError, NodeFeature, NodeResult};
+use crate::nwc::relay::NostrRelay;
+use crate::nwc::{
+    self, CreateNwcConnectionRequest, CreateNwcConnectionResponse, NwcConnection, NwcService,
+};
 use crate::persist::db::SqliteStorage;
+use crate::persist::error::PersistResult;
+use crate::persist::replacements::TxReplacement;
 use crate::swap_in::swap::BTCReceiveSwap;
 use crate::swap_out::boltzswap::BoltzApi;
 use crate::swap_out::reverseswap::{BTCSendSwap, CreateReverseSwapArg};
+use crate::wallets;
 use crate::*;
 
 pub type BreezServicesResult<T, E = ConnectError> = Result<T, E>;
 
+/// The max route length considered when quoting a payment, as in CLN `pay`
+const PAYMENT_QUOTE_MAX_HOPS: u32 = 20;
+
+/// How long after expiring a payment request is still reconciled with the received payments,
+/// as a payment settled right before the expiry can be synced after it
+const PAYMENT_REQUEST_RECONCILE_WINDOW_SECONDS: i64 = 3600;
+
+/// How long a [SpendingBudget] that loosens the current one waits before applying, so that whoever
+/// gets hold of the app can't drain the node right away
+const BUDGET_LOOSENING_DELAY_SECONDS: i64 = 24 * 3600;
+
 /// Trait that can be used to react to various [BreezEvent]s emitted by the SDK.
 pub trait EventListener: Send + Sync {
     fn on_event(&self, e: BreezEvent);
 }
 
+/// Trait that can be used to approve the payments above the
+/// [SpendingBudget::approval_threshold_msat], for example by asking the user.
+pub trait PaymentApprover: Send + Sync {
+    fn approve_payment(&self, req: PaymentApprovalRequest) -> bool;
+}
+
 /// Event emitted by the SDK. To listen for and react to these events, use an [EventListener] when
 /// initializing the [BreezServices].
 #[derive(Clone, Debug, PartialEq)]
@@ -81,6 +113,13 @@ pub enum BreezEvent {
     /// Indicates that a swap has been updated which may also
     /// include a status change
     SwapUpdated { details: SwapInfo },
+    /// Indicates that a sweep of the [SweepPolicy] has just been made
+    SweepSucceeded { details: SweepDetails },
+    /// Indicates that a sweep of the [SweepPolicy] has just failed
+    SweepFailed { details: SweepFailedData },
+    /// Indicates that an invoice created with [BreezServices::receive_payment] has expired
+    /// without being paid
+    InvoiceExpired { details: PaymentRequest },
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -96,6 +135,23 @@ pub struct PaymentFailedData {
     pub label: Option<String>,
 }
 
+/// Details of a sweep made by the [SweepPolicy], included as payload in an emitted [BreezEvent]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SweepDetails {
+    pub kind: SweepKind,
+    /// The amount sent to the sweep address, after the fees
+    pub amount_sat: u64,
+    pub sat_per_vbyte: u32,
+    /// The txid of an onchain sweep, or the reverse swap id of a lightning sweep
+    pub id: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SweepFailedData {
+    pub kind: SweepKind,
+    pub error: String,
+}
+
 /// Details of an invoice that has been paid, included as payload in an emitted [BreezEvent]
 #[derive(Clone, Debug, PartialEq)]
 pub struct InvoicePaidDetails {
@@ -165,6 +221,10 @@ pub struct BreezServices {
     btc_send_swapper: Arc<BTCSendSwap>,
     event_listener: Option<Box<dyn EventListener>>,
     backup_watcher: Arc<BackupWatcher>,
+    sweep_policy_notifier: Notify,
+    payment_requests_notifier: Notify,
+    payment_approver: Option<Arc<dyn PaymentApprover>>,
+    budget_lock: Mutex<()>,
     shutdown_sender: watch::Sender<()>,
     shutdown_receiver: watch::Receiver<()>,
 }
@@ -184,13 +244,37 @@ impl BreezServices {
     pub async fn connect(
         req: ConnectRequest,
         event_listener: Box<dyn EventListener>,
+    ) -> BreezServicesResult<Arc<BreezServices>> {
+        Self::connect_with(req, event_listener, None).await
+    }
+
+    /// Same as [BreezServices::connect], with the approver of the payments above the
+    /// [SpendingBudget::approval_threshold_msat]. Without an approver, these payments are rejected.
+    ///
+    /// The approver can't be replaced once connected.
+    pub async fn connect_with_payment_approver(
+        req: ConnectRequest,
+        event_listener: Box<dyn EventListener>,
+        payment_approver: Box<dyn PaymentApprover>,
+    ) -> BreezServicesResult<Arc<BreezServices>> {
+        Self::connect_with(req, event_listener, Some(Arc::from(payment_approver))).await
+    }
+
+    async fn connect_with(
+        req: ConnectRequest,
+        event_listener: Box<dyn EventListener>,
+        payment_approver: Option<Arc<dyn PaymentApprover>>,
     ) -> BreezServicesResult<Arc<BreezServices>> {
         let sdk_version = option_env!("CARGO_PKG_VERSION").unwrap_or_default();
         let sdk_git_hash = option_env!("SDK_GIT_HASH").unwrap_or_default();
         info!("SDK v{sdk_version} ({sdk_git_hash})");
         let start = Instant::now();
-        let services = BreezServicesBuilder::new(req.config)
-            .seed(req.seed)
+        let mut builder = BreezServicesBuilder::new(req.config);
+        builder.seed(req.seed);
+        if let Some(payment_approver) = payment_approver {
+            builder.payment_approver(payment_approver);
+        }
+        let services = builder
             .build(req.restore_only, Some(event_listener))
             .await?;
         services.start().await?;
@@ -251,16 +335,13 @@ impl BreezServices {
         Ok(self.node_api.configure_node(req.close_to_address).await?)
     }
 
-    /// Pay a bolt11 invoice
-    ///
-    /// Calling `send_payment` ensures that the payment is not already completed, if so it will result in an error.
-    /// If the invoice doesn't specify an amount, the amount is taken from the `amount_msat` arg.
-    pub async fn send_payment(
+    /// Parses the invoice to pay and returns it with the amount to pay, checking it can be paid
+    fn validate_invoice_to_pay(
         &self,
-        req: SendPaymentRequest,
-    ) -> Result<SendPaymentResponse, SendPaymentError> {
-        self.start_node().await?;
-        let parsed_invoice = parse_invoice(req.bolt11.as_str())?;
+        bolt11: &str,
+        amount_msat: Option<u64>,
+    ) -> Result<(LNInvoice, u64), SendPaymentError> {
+        let parsed_invoice = parse_invoice(bolt11)?;
         let invoice_expiration = parsed_invoice.timestamp + parsed_invoice.expiry;
         let current_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
         if invoice_expiration < current_time {
@@ -269,7 +350,7 @@ impl BreezServices {
             });
         }
         let invoice_amount_msat = parsed_invoice.amount_msat.unwrap_or_default();
-        let provided_amount_msat = req.amount_msat.unwrap_or_default();
+        let provided_amount_msat = amount_msat.unwrap_or_default();
 
         // Valid the invoice network against the config network
         validate_network(parsed_invoice.clone(), self.config.network)?;
@@ -288,6 +369,143 @@ impl BreezServices {
                 })
             }
         };
+        Ok((parsed_invoice, amount_msat))
+    }
+
+    /// Quote the payment of a bolt11 invoice before sending it with [BreezServices::send_payment].
+    ///
+    /// The cheapest route to the recipient is probed with an HTLC it can't claim, as its payment
+    /// hash is random, so this takes as long as the probe. If the probe reaches the recipient,
+    /// the quote is the fee of this route for the whole amount.
+    ///
+    /// Otherwise the quote is estimated from the network graph of the node. The channels are
+    /// split over the routes found to the recipient as a manual payment would, which gives the
+    /// expected number of parts and a fee estimate, and the cheapest route gives the other end of
+    /// the fee range.
+    ///
+    /// The actual fee depends on the route the node finds when paying, and the payment can still
+    /// fail if the liquidity on the way changes in the meantime.
+    pub async fn prepare_send_payment(
+        &self,
+        req: PrepareSendPaymentRequest,
+    ) -> Result<PrepareSendPaymentResponse, SendPaymentError> {
+        self.start_node().await?;
+        let (parsed_invoice, amount_msat) =
+            self.validate_invoice_to_pay(&req.bolt11, req.amount_msat)?;
+        if self
+            .persister
+            .get_completed_payment_by_hash(&parsed_invoice.payment_hash)?
+            .is_some()
+        {
+            return Err(SendPaymentError::AlreadyPaid);
+        }
+
+        let payee_node_id = hex::decode(&parsed_invoice.payee_pubkey).map_err(|e| {
+            SendPaymentError::InvalidInvoice {
+                err: format!("Invalid payee pubkey: {e}"),
+            }
+        })?;
+        let last_hop = parsed_invoice
+            .routing_hints
+            .first()
+            .and_then(|rh| rh.hops.first());
+        let max_amounts = self
+            .node_api
+            .max_sendable_amount(
+                Some(payee_node_id.clone()),
+                PAYMENT_QUOTE_MAX_HOPS,
+                last_hop,
+            )
+            .await?;
+        let max_sendable_msat = max_amounts.iter().map(|m| m.amount_msat).sum();
+        let fee_limit_msat = default_fee_limit_msat(&self.config, amount_msat);
+
+        let probe = self
+            .node_api
+            .probe_route(
+                payee_node_id,
+                amount_msat,
+                parsed_invoice.min_final_cltv_expiry_delta,
+                PAYMENT_QUOTE_MAX_HOPS,
+                last_hop,
+            )
+            .await?;
+        if let Some(probe) = probe.as_ref().filter(|probe| probe.reached_payee) {
+            return Ok(PrepareSendPaymentResponse {
+                amount_msat,
+                payable: true,
+                probed: true,
+                max_sendable_msat,
+                parts: 1,
+                min_fee_msat: probe.fee_msat,
+                max_fee_msat: probe.fee_msat,
+                fee_limit_msat,
+            });
+        }
+
+        let parts = match split_payment(max_amounts, amount_msat) {
+            Some(parts) => parts,
+            None => {
+                return Ok(PrepareSendPaymentResponse {
+                    amount_msat,
+                    payable: false,
+                    probed: false,
+                    max_sendable_msat,
+                    parts: 0,
+                    min_fee_msat: 0,
+                    max_fee_msat: 0,
+                    fee_limit_msat,
+                })
+            }
+        };
+        let split_fee_msat: u64 = parts
+            .iter()
+            .map(|(max, to_pay_msat)| max.path.first_hop_amount(*to_pay_msat) - to_pay_msat)
+            .sum();
+        let route_fee_msat = probe.map_or(split_fee_msat, |probe| probe.fee_msat);
+
+        Ok(PrepareSendPaymentResponse {
+            amount_msat,
+            payable: true,
+            probed: false,
+            max_sendable_msat,
+            parts: parts.len() as u32,
+            min_fee_msat: split_fee_msat.min(route_fee_msat),
+            max_fee_msat: split_fee_msat.max(route_fee_msat),
+            fee_limit_msat,
+        })
+    }
+
+    /// Pay a bolt11 invoice
+    ///
+    /// Calling `send_payment` ensures that the payment is not already completed, if so it will result in an error.
+    /// If the invoice doesn't specify an amount, the amount is taken from the `amount_msat` arg.
+    /// To know the fee before paying, see [BreezServices::prepare_send_payment].
+    pub async fn send_payment(
+        &self,
+        req: SendPaymentRequest,
+    ) -> Result<SendPaymentResponse, SendPaymentError> {
+        self.start_node().await?;
+        let req = match req.fiat_amount.clone() {
+            Some(fiat_amount) => {
+                ensure_sdk!(
+                    req.amount_msat.is_none(),
+                    SendPaymentError::InvalidAmount {
+                        err: "Amount should not be provided along with a fiat amount".into()
+                    }
+                );
+                let amount_msat = self
+                    .fiat_amount_msat(&fiat_amount)
+                    .map_err(|e| SendPaymentError::InvalidAmount { err: e.to_string() })?;
+                SendPaymentRequest {
+                    amount_msat: Some(amount_msat),
+                    ..req
+                }
+            }
+            None => req,
+        };
+        let (parsed_invoice, amount_msat) =
+            self.validate_invoice_to_pay(&req.bolt11, req.amount_msat)?;
 
         match self
             .persister
@@ -295,16 +513,34 @@ impl BreezServices {
         {
             Some(_) => Err(SendPaymentError::AlreadyPaid),
             None => {
-                self.persist_pending_payment(&parsed_invoice, amount_msat, req.label.clone())?;
+                // Written first, so a failure doesn't leave a budget spend or a pending payment
+                if let Some(fiat_amount) = &req.fiat_amount {
+                    self.persister
+                        .insert_payment_fiat_amount(&parsed_invoice.payment_hash, fiat_amount)?;
+                }
+                let fee_limit_msat = req
+                    .max_fee_msat
+                    .unwrap_or(default_fee_limit_msat(&self.config, amount_msat));
+                let spend_id = self
+                    .spend_budget(amount_msat + fee_limit_msat, &parsed_invoice.payee_pubkey)
+                    .await?;
+                if let Err(e) =
+                    self.persist_pending_payment(&parsed_invoice, amount_msat, req.label.clone())
+                {
+                    self.persister.delete_budget_spend(spend_id)?;
+                    return Err(e);
+                }
                 let payment_res = self
                     .node_api
                     .send_payment(
                         parsed_invoice.bolt11.clone(),
                         req.amount_msat,
                         req.label.clone(),
+                        req.max_fee_msat,
                     )
                     .map_err(Into::into)
                     .await;
+                self.settle_budget_spend(spend_id, &payment_res)?;
                 let payment = self
                     .on_payment_completed(
                         parsed_invoice.payee_pubkey.clone(),
@@ -324,6 +560,12 @@ impl BreezServices {
         req: SendSpontaneousPaymentRequest,
     ) -> Result<SendPaymentResponse, SendPaymentError> {
         self.start_node().await?;
+        let spend_id = self
+            .spend_budget(
+                req.amount_msat + default_fee_limit_msat(&self.config, req.amount_msat),
+                &req.node_id,
+            )
+            .await?;
         let payment_res = self
             .node_api
             .send_spontaneous_payment(
@@ -334,12 +576,118 @@ impl BreezServices {
             )
             .map_err(Into::into)
             .await;
+        self.settle_budget_spend(spend_id, &payment_res)?;
         let payment = self
             .on_payment_completed(req.node_id, None, req.label, payment_res)
             .await?;
         Ok(SendPaymentResponse { payment })
     }
 
+    /// Pay a BOLT12 offer. The first step is `parse()`, which decodes the offer.
+    ///
+    /// This fetches an invoice for the offer from the offer issuer and pays it. The resulting
+    /// [Payment] has [PaymentDetails::Bolt12] details.
+    ///
+    /// Only CLN nodes support offers, Greenlight nodes fail with [SendPaymentError::Generic].
+    pub async fn pay_offer(
+        &self,
+        req: PayOfferRequest,
+    ) -> Result<SendPaymentResponse, SendPaymentError> {
+        self.ensure_node_supports(NodeFeature::Offers)?;
+        self.start_node().await?;
+        let offer = parse_offer(&req.offer)?;
+        ensure_sdk!(
+            offer.supports_network(self.config.network),
+            SendPaymentError::InvalidNetwork {
+                err: format!("Offer is not valid for network {}", self.config.network)
+            }
+        );
+        if let Some(absolute_expiry) = offer.absolute_expiry {
+            let current_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            ensure_sdk!(
+                absolute_expiry >= current_time,
+                SendPaymentError::InvoiceExpired {
+                    err: format!("Offer expired at {absolute_expiry}")
+                }
+            );
+        }
+        match (&offer.min_amount, req.amount_msat) {
+            (None, None) => {
+                return Err(SendPaymentError::InvalidAmount {
+                    err: "Amount must be provided when paying an offer without amount".into(),
+                })
+            }
+            (Some(Amount::Bitcoin { amount_msat }), Some(provided_amount_msat))
+                if provided_amount_msat < *amount_msat =>
+            {
+                return Err(SendPaymentError::InvalidAmount {
+                    err: format!(
+                        "Amount should be at least the offer amount of {amount_msat} msat"
+                    ),
+                })
+            }
+            _ => {}
+        }
+        let amount_msat = match (req.amount_msat, &offer.min_amount) {
+            (Some(amount_msat), _) => amount_msat,
+            (None, Some(Amount::Bitcoin { amount_msat })) => *amount_msat,
+            // Without a bitcoin amount, the payment can't be checked against the budget
+            _ => {
+                return Err(SendPaymentError::InvalidAmount {
+                    err: "Amount must be provided when paying an offer in a fiat currency".into(),
+                })
+            }
+        };
+        let spend_id = self
+            .spend_budget(
+                amount_msat + default_fee_limit_msat(&self.config, amount_msat),
+                &offer.signing_pubkey,
+            )
+            .await?;
+
+        let payment_res = self
+            .node_api
+            .pay_offer(
+                offer.offer.clone(),
+                req.amount_msat,
+                req.payer_note.clone(),
+                req.label.clone(),
+            )
+            .map_err(Into::into)
+            .await;
+        self.settle_budget_spend(spend_id, &payment_res)?;
+        if let Ok(payment) = &payment_res {
+            self.persister.insert_payment_external_info(
+                &payment.id,
+                PaymentExternalInfo {
+                    bolt12_offer: Some(offer.offer.clone()),
+                    bolt12_payer_note: req.payer_note,
+                    ..Default::default()
+                },
+            )?;
+        }
+        let payment = self
+            .on_payment_completed(offer.signing_pubkey, None, req.label, payment_res)
+            .await?;
+        let payment = self
+            .persister
+            .get_payment_by_hash(&payment.id)?
+            .unwrap_or(payment);
+        Ok(SendPaymentResponse { payment })
+    }
+
+    /// Creates a reusable BOLT12 offer that can be paid multiple times.
+    ///
+    /// Like [BreezServices::pay_offer], this is only supported by CLN nodes.
+    pub async fn create_offer(&self, req: CreateOfferRequest) -> SdkResult<CreateOfferResponse> {
+        self.ensure_node_supports(NodeFeature::Offers)?;
+        self.start_node().await?;
+        let offer = self.node_api.create_offer(req).await?;
+        Ok(CreateOfferResponse {
+            offer: parse_offer(&offer)?,
+        })
+    }
+
     /// Second step of LNURL-pay. The first step is `parse()`, which also validates the LNURL destination
     /// and generates the `LnUrlPayRequest` payload needed here.
     ///
@@ -366,6 +714,8 @@ impl BreezServices {
                     bolt11: cb.pr.clone(),
                     amount_msat: None,
                     label: req.payment_label,
+                    max_fee_msat: None,
+                    fiat_amount: None,
                 };
                 let invoice = parse_invoice(cb.pr.as_str())?;
 
@@ -373,7 +723,8 @@ impl BreezServices {
                     Ok(p) => Ok(p),
                     e @ Err(
                         SendPaymentError::InvalidInvoice { .. }
-                        | SendPaymentError::ServiceConnectivity { .. },
+                        | SendPaymentError::ServiceConnectivity { .. }
+                        | SendPaymentError::BudgetExceeded { .. },
                     ) => e,
                     Err(e) => {
                         return Ok(LnUrlPayResult::PayError {
@@ -386,7 +737,7 @@ impl BreezServices {
                 }?
                 .payment;
                 let details = match &payment.details {
-                    PaymentDetails::ClosedChannel { .. } => {
+                    PaymentDetails::ClosedChannel { .. } | PaymentDetails::Bolt12 { .. } => {
                         return Err(LnUrlPayError::Generic {
                             err: "Payment lookup found unexpected payment type".into(),
                         });
@@ -435,6 +786,9 @@ impl BreezServices {
                         lnurl_withdraw_endpoint: None,
                         attempted_amount_msat: invoice.amount_msat,
                         attempted_error: None,
+                        bolt12_offer: None,
+                        bolt12_payer_note: None,
+                        lnurl_verify_url: cb.verify,
                     },
                 )?;
 
@@ -454,6 +808,9 @@ impl BreezServices {
     /// This call will validate the given `amount_msat` against the parameters
     /// of the LNURL endpoint (`data`). If they match the endpoint requirements, the LNURL withdraw
     /// request is made. A successful result here means the endpoint started the payment.
+    ///
+    /// For reusable withdraw links (LUD-14), the successful result also carries the refreshed
+    /// withdraw request, which reflects the remaining balance and can be used for the next withdraw.
     pub async fn lnurl_withdraw(
         &self,
         req: LnUrlWithdrawRequest,
@@ -484,6 +841,9 @@ impl BreezServices {
                     lnurl_withdraw_endpoint: Some(lnurl_w_endpoint),
                     attempted_amount_msat: None,
                     attempted_error: None,
+                    bolt12_offer: None,
+                    bolt12_payer_note: None,
+                    lnurl_verify_url: None,
                 },
             )?;
         }
@@ -491,6 +851,133 @@ impl BreezServices {
         Ok(res)
     }
 
+    /// Checks whether a LNURL-pay payment was settled, using the LUD-21 verify URL returned by
+    /// the LNURL endpoint when the invoice was requested.
+    ///
+    /// This only relies on the LNURL service, so it can be used to confirm a payment to a third-party
+    /// Lightning Address without trusting the payer. A reported preimage is checked against the payment hash.
+    pub async fn verify_lnurl_payment(
+        &self,
+        payment_hash: String,
+    ) -> Result<LnUrlVerifyStatus, LnUrlPayError> {
+        let payment =
+            self.persister
+                .get_payment_by_hash(&payment_hash)?
+                .ok_or(LnUrlPayError::Generic {
+                    err: "Payment not found".into(),
+                })?;
+        let verify_url = match payment.details {
+            PaymentDetails::Ln { data } => data.lnurl_verify_url,
+            _ => None,
+        }
+        .ok_or(LnUrlPayError::Generic {
+            err: "Payment has no LNURL verify URL".into(),
+        })?;
+
+        Ok(verify_lnurl_pay(&verify_url, &payment_hash).await?)
+    }
+
+    /// Handles a request made to a LNURL-pay endpoint (LUD-06) or Lightning Address (LUD-16)
+    /// backed by this node.
+    ///
+    /// The handler is transport-agnostic: the app forwards the full URL of each request made to the
+    /// endpoint or its callback, and returns the resulting status code and JSON body to the caller.
+    /// For callbacks, an invoice committing to the endpoint metadata is created with [BreezServices::receive_payment].
+    pub async fn handle_lnurl_pay_server_request(
+        &self,
+        config: LnUrlPayServerConfig,
+        url: String,
+    ) -> LnUrlPayServerResponse {
+        let (amount_msat, comment) = match LnUrlPayServerRequest::parse(&url) {
+            Ok(LnUrlPayServerRequest::PayRequest) => {
+                return LnUrlPayServerResponse::ok(config.pay_request())
+            }
+            Ok(LnUrlPayServerRequest::Callback {
+                amount_msat,
+                comment,
+            }) => (amount_msat, comment),
+            Err(e) => return LnUrlPayServerResponse::error(400, &e),
+        };
+        if let Err(e) = validate_user_input(
+            amount_msat,
+            &comment,
+            config.min_sendable,
+            config.max_sendable,
+            config.comment_allowed,
+        ) {
+            return LnUrlPayServerResponse::error(400, &e.to_string());
+        }
+
+        let metadata = config.metadata_str();
+        let invoice = match self
+            .receive_payment(ReceivePaymentRequest {
+                amount_msat,
+                description: metadata.clone(),
+                use_description_hash: Some(true),
+                ..Default::default()
+            })
+            .await
+        {
+            Ok(res) => res.ln_invoice,
+            Err(e) => {
+                error!("Failed to create invoice for LNURL-pay request: {e}");
+                return LnUrlPayServerResponse::error(500, "Failed to create invoice");
+            }
+        };
+
+        // Keep the payer comment with the received payment
+        if let Err(e) = self.persister.insert_payment_external_info(
+            &invoice.payment_hash,
+            PaymentExternalInfo {
+                lnurl_pay_comment: comment,
+                lnurl_metadata: Some(metadata),
+                ln_address: config.ln_address,
+                ..Default::default()
+            },
+        ) {
+            warn!("Failed to store LNURL-pay info for received payment: {e}");
+        }
+
+        LnUrlPayServerResponse::ok(json!({ "pr": invoice.bolt11, "routes": [] }))
+    }
+
+    /// Starts the Nostr Wallet Connect (NIP-47) wallet service, which handles the requests of the
+    /// connected clients received through the given relay until the SDK is disconnected.
+    ///
+    /// Clients are connected with [BreezServices::create_nwc_connection].
+    pub async fn start_nwc_service(
+        self: &Arc<BreezServices>,
+        relay: Arc<dyn NostrRelay>,
+    ) -> SdkResult<()> {
+        NwcService::new(
+            self.clone(),
+            self.persister.clone(),
+            relay,
+            self.config.clone(),
+        )?
+        .start(self.shutdown_receiver.clone())
+        .await
+    }
+
+    /// Creates a Nostr Wallet Connect connection, granting a client the given permissions and
+    /// spending budget. The returned connection URI should be passed to the client.
+    pub async fn create_nwc_connection(
+        &self,
+        req: CreateNwcConnectionRequest,
+    ) -> SdkResult<CreateNwcConnectionResponse> {
+        nwc::create_connection(&self.persister, req)
+    }
+
+    /// Lists the Nostr Wallet Connect connections, along with their current budget
+    pub async fn list_nwc_connections(&self) -> SdkResult<Vec<NwcConnection>> {
+        Ok(self.persister.list_nwc_connections()?)
+    }
+
+    /// Deletes a Nostr Wallet Connect connection. Further requests of the client are ignored.
+    pub async fn delete_nwc_connection(&self, client_pubkey: String) -> SdkResult<()> {
+        Ok(self.persister.delete_nwc_connection(&client_pubkey)?)
+    }
+
     /// Third and last step of LNURL-auth. The first step is `parse()`, which also validates the LNURL destination
     /// and generates the `LnUrlAuthRequestData` payload needed here. The second step is user approval of auth action.
     ///
@@ -524,7 +1011,80 @@ impl BreezServices {
         &self,
         req: ReceivePaymentRequest,
     ) -> Result<ReceivePaymentResponse, ReceivePaymentError> {
-        self.payment_receiver.receive_payment(req).await
+        let fiat_amount = req.fiat_amount.clone();
+        let req = match &fiat_amount {
+            Some(fiat_amount) => {
+                ensure_sdk!(
+                    req.amount_msat == 0,
+                    ReceivePaymentError::InvalidAmount {
+                        err: "Amount should not be provided along with a fiat amount".into()
+                    }
+                );
+                let amount_msat = self
+                    .fiat_amount_msat(fiat_amount)
+                    .map_err(|e| ReceivePaymentError::InvalidAmount { err: e.to_string() })?;
+                ReceivePaymentRequest { amount_msat, ..req }
+            }
+            None => req,
+        };
+
+        let res = self.payment_receiver.receive_payment(req).await?;
+        if let Some(fiat_amount) = fiat_amount {
+            let payment_hash = &res.ln_invoice.payment_hash;
+            if let Err(e) = self
+                .persister
+                .insert_payment_fiat_amount(payment_hash, &fiat_amount)
+            {
+                // The invoice can't be withdrawn, but it isn't left open without its fiat amount
+                self.persister.update_payment_request_status(
+                    payment_hash,
+                    PaymentRequestStatus::Cancelled,
+                    None,
+                )?;
+                return Err(e.into());
+            }
+        }
+        self.payment_requests_notifier.notify_one();
+        Ok(res)
+    }
+
+    /// List the invoices created with [BreezServices::receive_payment], with their status.
+    ///
+    /// The status is updated on sync, when the received payments are reconciled with them, and
+    /// when the open requests expire.
+    pub async fn list_payment_requests(
+        &self,
+        req: ListPaymentRequestsRequest,
+    ) -> SdkResult<Vec<PaymentRequest>> {
+        Ok(self.persister.list_payment_requests(&req)?)
+    }
+
+    /// Cancels an open payment request, so it is no longer listed as pending.
+    ///
+    /// The invoice can't be withdrawn from payers, so the request is still marked as paid if it
+    /// is paid before it expires.
+    pub async fn cancel_payment_request(&self, payment_hash: String) -> SdkResult<()> {
+        let request =
+            self.persister
+                .get_payment_request(&payment_hash)?
+                .ok_or(SdkError::Generic {
+                    err: format!("No payment request found for payment hash {payment_hash}"),
+                })?;
+        ensure_sdk!(
+            request.status == PaymentRequestStatus::Open,
+            SdkError::Generic {
+                err: format!(
+                    "A payment request can't be cancelled in status {}",
+                    request.status
+                )
+            }
+        );
+        self.persister.update_payment_request_status(
+            &payment_hash,
+            PaymentRequestStatus::Cancelled,
+            None,
+        )?;
+        Ok(())
     }
 
     /// Report an issue.
@@ -617,6 +1177,41 @@ impl BreezServices {
         Ok(self.persister.list_payments(req)?)
     }
 
+    /// Count the payments matching the given filters, e.g. to paginate [BreezServices::list_payments].
+    ///
+    /// The offset and limit of the request are ignored.
+    pub async fn count_payments(&self, req: ListPaymentsRequest) -> SdkResult<u64> {
+        Ok(self.persister.count_payments(&req)?)
+    }
+
+    /// Export the payments matching the given filters, e.g. to import them in accounting software.
+    ///
+    /// If a fiat currency is set, the fiat value of each payment at the time it was made is
+    /// fetched from CoinGecko. As this shares the currency and the time range of the payments
+    /// with a third party, it needs [ExportPaymentsRequest::use_coingecko_rates] to be set.
+    pub async fn export_payments(
+        &self,
+        req: ExportPaymentsRequest,
+    ) -> SdkResult<ExportPaymentsResponse> {
+        ensure_sdk!(
+            req.fiat_currency.is_none() || req.use_coingecko_rates,
+            SdkError::Generic {
+                err: "The fiat value of the payments needs the rates from CoinGecko".into()
+            }
+        );
+        let payments = self.persister.list_payments(req.list_payments_request)?;
+        let times = payments.iter().map(|p| p.payment_time);
+        let rates = match (req.fiat_currency, times.clone().min(), times.max()) {
+            (Some(currency), Some(from), Some(to)) => {
+                Some(HistoricalRates::fetch(self.fiat_api.as_ref(), &currency, from, to).await?)
+            }
+            _ => None,
+        };
+        Ok(ExportPaymentsResponse {
+            content: format_payments(&payments, req.format, rates.as_ref()),
+        })
+    }
+
     /// Fetch a specific payment by its hash.
     pub async fn payment_by_hash(&self, hash: String) -> SdkResult<Option<Payment>> {
         Ok(self.persister.get_payment_by_hash(&hash)?)
@@ -629,16 +1224,55 @@ impl BreezServices {
             .set_payment_external_metadata(hash, metadata)?)
     }
 
+    /// Set the note, tags and category of a payment, replacing any previously set.
+    ///
+    /// These are synced to the other devices of the wallet and can be used to filter
+    /// [BreezServices::list_payments].
+    pub async fn set_payment_annotations(
+        &self,
+        req: SetPaymentAnnotationsRequest,
+    ) -> SdkResult<()> {
+        Ok(self.persister.set_payment_annotations(&req)?)
+    }
+
+    /// List all the distinct tags set on payments
+    pub async fn list_tags(&self) -> SdkResult<Vec<String>> {
+        Ok(self.persister.list_payment_tags()?)
+    }
+
     /// Redeem on-chain funds from closed channels to the specified on-chain address, with the given feerate
     pub async fn redeem_onchain_funds(
         &self,
         req: RedeemOnchainFundsRequest,
     ) -> RedeemOnchainResult<RedeemOnchainFundsResponse> {
         self.start_node().await?;
-        let txid = self
-            .node_api
-            .redeem_onchain_funds(req.to_address, req.sat_per_vbyte)
+        let onchain_balance_msat = self
+            .persister
+            .get_node_state()?
+            .map(|s| s.onchain_balance_msat)
+            .unwrap_or_default();
+        let spend_id = self
+            .spend_budget(onchain_balance_msat, &req.to_address)
             .await?;
+        let txid = match self
+            .node_api
+            .redeem_onchain_funds(req.to_address.clone(), req.sat_per_vbyte)
+            .await
+        {
+            Ok(txid) => txid,
+            Err(e) => {
+                self.persister.delete_budget_spend(spend_id)?;
+                return Err(e.into());
+            }
+        };
+        // Keep the destination, so the fee of the transaction can be bumped later
+        let created_at = Utc::now().timestamp();
+        if let Err(e) =
+            self.persister
+                .insert_redeem_tx(&hex::encode(&txid), &req.to_address, created_at)
+        {
+            warn!("Failed to persist redeem tx: {e}");
+        }
         self.sync().await?;
         Ok(RedeemOnchainFundsResponse { txid })
     }
@@ -657,6 +1291,46 @@ impl BreezServices {
         self.fiat_api.fetch_fiat_rates().await.map_err(Into::into)
     }
 
+    /// Locks the live rate of a fiat currency, to convert the [FiatAmount] of a
+    /// [ReceivePaymentRequest] or [SendPaymentRequest] at the rate shown to the user.
+    ///
+    /// A locked rate can be used for [FIAT_RATE_MAX_AGE_SECONDS]. The locked rates are kept by
+    /// the SDK, so a request can only use a rate it locked.
+    pub async fn lock_fiat_rate(&self, currency: String) -> SdkResult<LockedFiatRate> {
+        let rate = self
+            .fetch_fiat_rates()
+            .await?
+            .into_iter()
+            .find(|r| r.coin.eq_ignore_ascii_case(&currency))
+            .ok_or(SdkError::Generic {
+                err: format!("No rate found for currency {currency}"),
+            })?;
+        let now = Utc::now().timestamp();
+        let locked_rate = LockedFiatRate {
+            id: hex::encode(rand::random::<[u8; 16]>()),
+            currency: rate.coin,
+            value: rate.value.to_string(),
+            locked_at: now,
+        };
+        self.persister
+            .delete_fiat_rate_locks_before(now - FIAT_RATE_MAX_AGE_SECONDS)?;
+        self.persister.insert_fiat_rate_lock(&locked_rate)?;
+        Ok(locked_rate)
+    }
+
+    /// Converts a fiat amount at its rate, checking the rate was locked with
+    /// [BreezServices::lock_fiat_rate] and is still usable
+    fn fiat_amount_msat(&self, fiat_amount: &FiatAmount) -> Result<u64> {
+        let locked_rate = self.persister.get_fiat_rate_lock(&fiat_amount.rate.id)?;
+        ensure!(
+            locked_rate.as_ref() == Some(&fiat_amount.rate),
+            "The {} rate was not locked with lock_fiat_rate",
+            fiat_amount.rate.currency
+        );
+        fiat_amount.validate(Utc::now().timestamp())?;
+        fiat_amount.amount_msat()
+    }
+
     /// List all supported fiat currencies for which there is a known exchange rate.
     /// List is sorted by the canonical name of the currency
     pub async fn list_fiat_currencies(&self) -> SdkResult<Vec<FiatCurrency>> {
@@ -745,6 +1419,8 @@ impl BreezServices {
     /// a swap waiting for confirmation to be redeemed and by that complete the swap.
     /// In such case the [BreezServices::in_progress_swap] can be used to query the live swap status.
     ///
+    /// A Taproot swap address can be requested with [ReceiveOnchainRequest::script_version].
+    ///
     /// The returned [SwapInfo] contains the created swap details. The channel opening fees are
     /// available at [SwapInfo::channel_opening_fees].
     pub async fn receive_onchain(
@@ -766,7 +1442,7 @@ impl BreezServices {
 
         let swap_info = self
             .btc_receive_swapper
-            .create_swap_address(channel_opening_fees)
+            .create_swap_address(channel_opening_fees, req.script_version.unwrap_or_default())
             .await?;
         if let Some(webhook_url) = self.persister.get_webhook_url()? {
             let address = &swap_info.bitcoin_address;
@@ -898,9 +1574,20 @@ impl BreezServices {
         &self,
         req: SendOnchainRequest,
     ) -> Result<SendOnchainResponse, SendOnchainError> {
-        let reverse_swap_info = self
-            .pay_onchain_common(CreateReverseSwapArg::V1(req))
+        let amount_msat = req.amount_sat * 1_000;
+        let spend_id = self
+            .spend_budget(
+                amount_msat + default_fee_limit_msat(&self.config, amount_msat),
+                &req.onchain_recipient_address,
+            )
             .await?;
+        let reverse_swap_info = self
+            .pay_onchain_common(CreateReverseSwapArg::V1(req), spend_id)
+            .await
+            .or_else(|e| {
+                self.persister.delete_budget_spend(spend_id)?;
+                Err(e)
+            })?;
         Ok(SendOnchainResponse { reverse_swap_info })
     }
 
@@ -944,6 +1631,316 @@ impl BreezServices {
         Ok(self.btc_receive_swapper.refund_swap(req).await?)
     }
 
+    /// Refunds a failed Taproot swap without waiting for its lock height, by asking the swapper
+    /// to co-sign a key-path refund transaction.
+    ///
+    /// P2WSH swaps, or swaps the swapper doesn't co-sign, fall back to the timelocked refund of
+    /// [BreezServices::refund] once they are refundable.
+    ///
+    /// Returns the txid of the refund transaction.
+    pub async fn request_cooperative_refund(
+        &self,
+        req: RefundRequest,
+    ) -> SdkResult<RefundResponse> {
+        Ok(self
+            .btc_receive_swapper
+            .request_cooperative_refund(req)
+            .await?)
+    }
+
+    /// Prepares a single refund transaction for several failed/expired swaps.
+    ///
+    /// Can optionally be used before [BreezServices::refund_all] to know how much fees will be
+    /// paid to perform the refund.
+    pub async fn prepare_refund_all(
+        &self,
+        req: PrepareRefundAllRequest,
+    ) -> SdkResult<PrepareRefundResponse> {
+        Ok(self
+            .btc_receive_swapper
+            .prepare_refund_all_swaps(req)
+            .await?)
+    }
+
+    /// Construct and broadcast a single refund transaction spending the funds of several
+    /// failed/expired swaps, or of all of them if no swap addresses are given.
+    ///
+    /// Returns the txid of the refund transaction.
+    pub async fn refund_all(&self, req: RefundAllRequest) -> SdkResult<RefundResponse> {
+        Ok(self.btc_receive_swapper.refund_all_swaps(req).await?)
+    }
+
+    /// Replaces an unconfirmed transaction broadcast by the SDK with one paying the given fee rate
+    /// (RBF).
+    ///
+    /// This can be a refund tx of [BreezServices::refund] or [BreezServices::refund_all], the claim
+    /// tx of a reverse swap, or the tx of [BreezServices::redeem_onchain_funds]. The related swap
+    /// or reverse swap is updated with the txid of the replacement.
+    pub async fn bump_fee(&self, req: BumpFeeRequest) -> SdkResult<BumpFeeResponse> {
+        if let Some(replacing_txid) = self.persister.get_replacing_txid(&req.txid)? {
+            return Err(SdkError::Generic {
+                err: format!(
+                    "Transaction {} was already replaced by {replacing_txid}",
+                    req.txid
+                ),
+            });
+        }
+
+        let txid = match self
+            .btc_receive_swapper
+            .bump_refund_tx(&req.txid, req.sat_per_vbyte)
+            .await?
+        {
+            Some(txid) => txid,
+            None => match self
+                .btc_send_swapper
+                .bump_claim_tx(&req.txid, req.sat_per_vbyte)
+                .await?
+            {
+                Some(txid) => txid,
+                None => self.bump_redeem_tx(&req.txid, req.sat_per_vbyte).await?,
+            },
+        };
+        Ok(BumpFeeResponse { txid })
+    }
+
+    /// Replaces an unconfirmed tx redeeming the onchain funds of the node
+    async fn bump_redeem_tx(&self, txid: &str, sat_per_vbyte: u32) -> SdkResult<String> {
+        let to_address =
+            self.persister
+                .get_redeem_tx_address(txid)?
+                .ok_or_else(|| SdkError::Generic {
+                    err: format!("Transaction {txid} is not a refund, claim or redeem transaction"),
+                })?;
+        let replaced_tx = self
+            .chain_service
+            .address_transactions(to_address.clone())
+            .await?
+            .into_iter()
+            .find(|tx| tx.txid == txid)
+            .ok_or_else(|| SdkError::Generic {
+                err: format!("Redeem tx {txid} was not found"),
+            })?;
+
+        let utxos = replaced_tx
+            .vin
+            .iter()
+            .map(|vin| {
+                Ok(OutPoint {
+                    txid: Txid::from_str(&vin.txid)?,
+                    vout: vin.vout,
+                })
+            })
+            .collect::<SdkResult<Vec<OutPoint>>>()?;
+        self.start_node().await?;
+        let replacement = self
+            .node_api
+            .sign_replacement_redeem(utxos, to_address.clone(), sat_per_vbyte)
+            .await?;
+        validate_replacement(&replaced_tx, replacement.fee_sat, replacement.weight)?;
+
+        let new_txid = hex::encode(self.node_api.send_signed_psbt(replacement.psbt).await?);
+        self.persister.insert_tx_replacement(&TxReplacement::new(
+            new_txid.clone(),
+            txid,
+            sat_per_vbyte,
+        ))?;
+        let created_at = Utc::now().timestamp();
+        self.persister
+            .insert_redeem_tx(&new_txid, &to_address, created_at)?;
+        Ok(new_txid)
+    }
+
+    /// Prepares a transaction accelerating the unconfirmed deposits of a swap by paying for them
+    /// (CPFP), so the deposits and the child transaction together pay the target fee rate.
+    ///
+    /// The child transaction spends the node outputs of the deposits, as listed in
+    /// [NodeState::utxos]. If the node has no funds in them, the deposits of a Taproot swap are
+    /// refunded to the refund address instead.
+    pub async fn prepare_accelerate_swap_deposit(
+        &self,
+        req: PrepareAccelerateSwapDepositRequest,
+    ) -> SdkResult<PrepareAccelerateSwapDepositResponse> {
+        Ok(self
+            .btc_receive_swapper
+            .prepare_accelerate_swap_deposit(req)
+            .await?)
+    }
+
+    /// Broadcasts the transaction accelerating the unconfirmed deposits of a swap, as prepared by
+    /// [BreezServices::prepare_accelerate_swap_deposit].
+    ///
+    /// Returns the txid of the child transaction.
+    pub async fn accelerate_swap_deposit(
+        &self,
+        req: AccelerateSwapDepositRequest,
+    ) -> SdkResult<AccelerateSwapDepositResponse> {
+        self.start_node().await?;
+        Ok(self
+            .btc_receive_swapper
+            .accelerate_swap_deposit(req)
+            .await?)
+    }
+
+    /// Sets the policy of the automatic sweeps of the node funds, replacing the current one.
+    ///
+    /// The policy is applied on every new block and sync, emitting [BreezEvent::SweepSucceeded]
+    /// or [BreezEvent::SweepFailed] for each sweep it makes. Use [SweepPolicy::default] to turn
+    /// the sweeps off.
+    pub async fn set_sweep_policy(&self, policy: SweepPolicy) -> SdkResult<()> {
+        policy.validate(self.config.network)?;
+        self.persister.set_sweep_policy(&policy)?;
+        self.sweep_policy_notifier.notify_one();
+        Ok(())
+    }
+
+    /// The policy of the automatic sweeps of the node funds, see [BreezServices::set_sweep_policy]
+    pub async fn sweep_policy(&self) -> SdkResult<SweepPolicy> {
+        Ok(self.persister.get_sweep_policy()?.unwrap_or_default())
+    }
+
+    /// Sets the budget the outgoing payments are checked against, replacing the current one.
+    ///
+    /// Lightning payments, LNURL payments, reverse swaps and redeems of the onchain funds,
+    /// including the automatic sweeps, fail with a `BudgetExceeded` error when they exceed it.
+    /// The limits apply to what was spent in the rolling periods, before the budget was set too.
+    /// Use [SpendingBudget::default] to remove the limits.
+    ///
+    /// A budget that raises or removes any of the current caps only applies after a delay of a
+    /// day, see [BreezServices::pending_spending_budget]. Other budgets apply right away,
+    /// replacing the pending one.
+    pub async fn set_spending_budget(&self, budget: SpendingBudget) -> SdkResult<()> {
+        budget.validate()?;
+        let _budget_guard = self.budget_lock.lock().await;
+        let current = self.current_spending_budget()?;
+        match budget.loosens(&current) {
+            true => {
+                let applies_at = Utc::now().timestamp() + BUDGET_LOOSENING_DELAY_SECONDS;
+                info!("The spending budget loosens the current one, it applies at {applies_at}");
+                self.persister
+                    .set_pending_spending_budget(&PendingSpendingBudget { budget, applies_at })?;
+            }
+            false => {
+                self.persister.set_spending_budget(&budget)?;
+                self.persister.delete_pending_spending_budget()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The budget of the outgoing payments, see [BreezServices::set_spending_budget]
+    pub async fn spending_budget(&self) -> SdkResult<SpendingBudget> {
+        Ok(self.current_spending_budget()?)
+    }
+
+    /// The budget waiting for its delay to pass before replacing the current one, if any
+    pub async fn pending_spending_budget(&self) -> SdkResult<Option<PendingSpendingBudget>> {
+        self.current_spending_budget()?;
+        Ok(self.persister.get_pending_spending_budget()?)
+    }
+
+    /// The budget in force, applying the pending one if its delay has passed
+    fn current_spending_budget(&self) -> PersistResult<SpendingBudget> {
+        if let Some(pending) = self.persister.get_pending_spending_budget()? {
+            if pending.applies_at <= Utc::now().timestamp() {
+                self.persister.set_spending_budget(&pending.budget)?;
+                self.persister.delete_pending_spending_budget()?;
+                return Ok(pending.budget);
+            }
+        }
+        Ok(self.persister.get_spending_budget()?.unwrap_or_default())
+    }
+
+    /// Checks a payment against the [SpendingBudget] and records it as spent, asking the
+    /// [PaymentApprover] if it is above the approval threshold. The payments are recorded
+    /// without a budget too, so the limits of a new budget include them.
+    ///
+    /// Returns the id of the spend, to settle it with [BreezServices::settle_budget_spend].
+    async fn spend_budget(
+        &self,
+        amount_msat: u64,
+        destination: &str,
+    ) -> Result<i64, SendPaymentError> {
+        let (budget, spend_id) = {
+            // The budget is read under the lock too, so it can't change before the spend is recorded
+            let _budget_guard = self.budget_lock.lock().await;
+            let budget = self.current_spending_budget()?;
+            let now = Utc::now().timestamp();
+            let spent_msat = budget
+                .limits
+                .iter()
+                .map(|l| {
+                    self.persister
+                        .get_budget_spent_since(now - l.period.seconds())
+                })
+                .collect::<PersistResult<Vec<u64>>>()?;
+            if let Some(err) = budget.exceeded_by(amount_msat, &spent_msat) {
+                return Err(SendPaymentError::BudgetExceeded { err });
+            }
+            let spend_id = self
+                .persister
+                .insert_budget_spend(amount_msat, destination, now)?;
+            (budget, spend_id)
+        };
+
+        if budget.needs_approval(amount_msat) {
+            let approver = self.payment_approver.clone();
+            let req = PaymentApprovalRequest {
+                amount_msat,
+                destination: destination.to_string(),
+            };
+            let approved = match approver {
+                Some(approver) => {
+                    tokio::task::spawn_blocking(move || approver.approve_payment(req))
+                        .await
+                        .unwrap_or(false)
+                }
+                None => false,
+            };
+            if !approved {
+                self.persister.delete_budget_spend(spend_id)?;
+                return Err(SendPaymentError::BudgetExceeded {
+                    err: format!("The payment of {amount_msat} msat was not approved"),
+                });
+            }
+        }
+        Ok(spend_id)
+    }
+
+    /// Settles the spends of the payments that completed after the call that made them, like
+    /// the payments of the reverse swaps
+    fn settle_pending_budget_spends(&self) -> PersistResult<()> {
+        for (spend_id, payment_hash) in self.persister.list_pending_budget_spends()? {
+            match self.persister.get_payment_by_hash(&payment_hash)? {
+                Some(payment) if payment.status == PaymentStatus::Complete => self
+                    .persister
+                    .update_budget_spend(spend_id, payment.amount_msat + payment.fee_msat)?,
+                Some(payment) if payment.status == PaymentStatus::Failed => {
+                    self.persister.delete_budget_spend(spend_id)?
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Settles a spend with the result of its payment. A failed payment releases the spend and
+    /// a completed one sets it to the amount paid, fees included. A timed out payment may still
+    /// complete, so its spend is kept.
+    fn settle_budget_spend(
+        &self,
+        spend_id: i64,
+        payment_res: &Result<Payment, SendPaymentError>,
+    ) -> PersistResult<()> {
+        match payment_res {
+            Ok(payment) => self
+                .persister
+                .update_budget_spend(spend_id, payment.amount_msat + payment.fee_msat),
+            Err(SendPaymentError::PaymentTimeout { .. }) => Ok(()),
+            Err(_) => self.persister.delete_budget_spend(spend_id),
+        }
+    }
+
     pub async fn onchain_payment_limits(&self) -> SdkResult<OnchainPaymentLimitsResponse> {
         let fee_info = self.btc_send_swapper.fetch_reverse_swap_fees().await?;
         debug!("Reverse swap pair info: {fee_info:?}");
@@ -1027,19 +2024,42 @@ impl BreezServices {
             SendOnchainError::generic("Send amount must be bigger than receive amount")
         );
 
-        let reverse_swap_info = self
-            .pay_onchain_common(CreateReverseSwapArg::V2(req))
+        let amount_msat = req.prepare_res.sender_amount_sat * 1_000;
+        let spend_id = self
+            .spend_budget(
+                amount_msat + default_fee_limit_msat(&self.config, amount_msat),
+                &req.recipient_address,
+            )
             .await?;
+        let reverse_swap_info = self
+            .pay_onchain_common(CreateReverseSwapArg::V2(req), spend_id)
+            .await
+            .or_else(|e| {
+                self.persister.delete_budget_spend(spend_id)?;
+                Err(e)
+            })?;
         Ok(PayOnchainResponse { reverse_swap_info })
     }
 
-    async fn pay_onchain_common(&self, req: CreateReverseSwapArg) -> SdkResult<ReverseSwapInfo> {
+    /// Creates the reverse swap, whose payment settles the budget spend once it completes
+    async fn pay_onchain_common(
+        &self,
+        req: CreateReverseSwapArg,
+        spend_id: i64,
+    ) -> SdkResult<ReverseSwapInfo> {
         ensure_sdk!(self.in_progress_onchain_payments().await?.is_empty(), SdkError::Generic { err:
             "You can only start a new one after after the ongoing ones finish. \
             Use the in_progress_reverse_swaps method to get an overview of currently ongoing reverse swaps".into(),
         });
 
         let full_rsi = self.btc_send_swapper.create_reverse_swap(req).await?;
+        // The swap is being paid, so the spend is kept in full if it can't track the payment
+        if let Err(e) = self
+            .persister
+            .set_budget_spend_pending_payment(spend_id, &full_rsi.get_preimage_hash().to_hex())
+        {
+            warn!("Failed to track the payment of the budget spend: {e}");
+        }
         let reverse_swap_info = self
             .btc_send_swapper
             .convert_reverse_swap_info(full_rsi.clone())
@@ -1154,6 +2174,12 @@ impl BreezServices {
         let mut payments = closed_channel_payments;
         payments.extend(new_data.payments.clone());
         self.persister.insert_or_update_payments(&payments, true)?;
+        if let Err(e) = self.update_payment_requests().await {
+            error!("Failed to update the payment requests: {e}");
+        }
+        if let Err(e) = self.settle_pending_budget_spends() {
+            error!("Failed to settle the budget spends: {e}");
+        }
         let duration = start.elapsed();
         info!("Sync duration: {:?}", duration);
 
@@ -1166,6 +2192,49 @@ impl BreezServices {
         Ok(())
     }
 
+    /// Reconciles the tracked payment requests with the received payments, notifying the ones
+    /// that have just expired
+    async fn update_payment_requests(&self) -> Result<()> {
+        let now = Utc::now().timestamp();
+        let requests = self
+            .persister
+            .list_unsettled_payment_requests(now - PAYMENT_REQUEST_RECONCILE_WINDOW_SECONDS)?;
+        for request in requests {
+            let payment = self
+                .persister
+                .get_completed_payment_by_hash(&request.payment_hash)?
+                .filter(|p| p.payment_type == PaymentType::Received);
+            match payment {
+                Some(payment) => {
+                    info!("Payment request {} was paid", request.payment_hash);
+                    self.persister.update_payment_request_status(
+                        &request.payment_hash,
+                        PaymentRequestStatus::Paid,
+                        Some(payment.payment_time),
+                    )?;
+                }
+                // The request may be expired concurrently by the expiry task and the sync
+                None if request.status == PaymentRequestStatus::Open
+                    && request.expires_at <= now
+                    && self
+                        .persister
+                        .expire_payment_request(&request.payment_hash)? =>
+                {
+                    info!("Payment request {} has expired", request.payment_hash);
+                    self.notify_event_listeners(BreezEvent::InvoiceExpired {
+                        details: PaymentRequest {
+                            status: PaymentRequestStatus::Expired,
+                            ..request
+                        },
+                    })
+                    .await?;
+                }
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
     /// Connects to the selected LSP peer.
     /// This validates if the selected LSP is still in [`list_lsps`].
     /// If not or no LSP is selected, it selects the first LSP in [`list_lsps`].
@@ -1208,35 +2277,36 @@ impl BreezServices {
     ) -> Result<(), SendPaymentError> {
         self.persister.insert_or_update_payments(
             &[Payment {
-                id: invoice.payment_hash.clone(),
-                payment_type: PaymentType::Sent,
-                payment_time: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
-                amount_msat,
-                fee_msat: 0,
-                status: PaymentStatus::Pending,
-                error: None,
                 description: invoice.description.clone(),
-                details: PaymentDetails::Ln {
-                    data: LnPaymentDetails {
-                        payment_hash: invoice.payment_hash.clone(),
-                        label: label.unwrap_or_default(),
-                        destination_pubkey: invoice.payee_pubkey.clone(),
-                        payment_preimage: String::new(),
-                        keysend: false,
-                        bolt11: invoice.bolt11.clone(),
-                        lnurl_success_action: None,
-                        lnurl_pay_domain: None,
-                        lnurl_pay_comment: None,
-                        ln_address: None,
-                        lnurl_metadata: None,
-                        lnurl_withdraw_endpoint: None,
-                        swap_info: None,
-                        reverse_swap_info: None,
-                        pending_expiration_block: None,
-                        open_channel_bolt11: None,
+                ..Payment::new(
+                    invoice.payment_hash.clone(),
+                    PaymentType::Sent,
+                    SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+                    amount_msat,
+                    0,
+                    PaymentStatus::Pending,
+                    PaymentDetails::Ln {
+                        data: LnPaymentDetails {
+                            payment_hash: invoice.payment_hash.clone(),
+                            label: label.unwrap_or_default(),
+                            destination_pubkey: invoice.payee_pubkey.clone(),
+                            payment_preimage: String::new(),
+                            keysend: false,
+                            bolt11: invoice.bolt11.clone(),
+                            lnurl_success_action: None,
+                            lnurl_pay_domain: None,
+                            lnurl_pay_comment: None,
+                            ln_address: None,
+                            lnurl_metadata: None,
+                            lnurl_withdraw_endpoint: None,
+                            lnurl_verify_url: None,
+                            swap_info: None,
+                            reverse_swap_info: None,
+                            pending_expiration_block: None,
+                            open_channel_bolt11: None,
+                        },
                     },
-                },
-                metadata: None,
+                )
             }],
             false,
         )?;
@@ -1252,6 +2322,9 @@ impl BreezServices {
                 lnurl_withdraw_endpoint: None,
                 attempted_amount_msat: invoice.amount_msat.map_or(Some(amount_msat), |_| None),
                 attempted_error: None,
+                bolt12_offer: None,
+                bolt12_payer_note: None,
+                lnurl_verify_url: None,
             },
         )?;
         Ok(())
@@ -1313,6 +2386,11 @@ impl BreezServices {
             )
         };
 
+        // The sweep policy is applied in its own task, as it may trigger new events
+        if matches!(e, BreezEvent::NewBlock { .. } | BreezEvent::Synced) {
+            self.sweep_policy_notifier.notify_one();
+        }
+
         if self.event_listener.is_some() {
             self.event_listener.as_ref().unwrap().on_event(e.clone())
         }
@@ -1324,6 +2402,16 @@ impl BreezServices {
         get_lsp(self.persister.clone(), self.lsp_api.clone()).await
     }
 
+    /// Fails if the node backend can't provide the feature
+    fn ensure_node_supports(&self, feature: NodeFeature) -> NodeResult<()> {
+        match self.node_api.unsupported_features().contains(&feature) {
+            true => Err(NodeError::generic(&format!(
+                "{feature:?} are not supported by the node backend"
+            ))),
+            false => Ok(()),
+        }
+    }
+
     pub(crate) async fn start_node(&self) -> Result<()> {
         self.node_api.start().await?;
         Ok(())
@@ -1377,6 +2465,7 @@ impl BreezServices {
         let swap_info = self
             .receive_onchain(ReceiveOnchainRequest {
                 opening_fee_params: req.opening_fee_params,
+                script_version: None,
             })
             .await?;
         let url = self
@@ -1429,12 +2518,27 @@ impl BreezServices {
         // track new blocks
         self.track_new_blocks().await;
 
+        // apply the sweep policy
+        self.track_sweep_policy().await;
+
+        // expire the payment requests as they reach their expiry
+        self.track_payment_requests_expiry().await;
+
         // track logs
-        self.track_logs().await;
+        if !self
+            .node_api
+            .unsupported_features()
+            .contains(&NodeFeature::LogStream)
+        {
+            self.track_logs().await;
+        }
+
+        // handle LSPS0 messages from the LSP
+        self.payment_receiver.start(self.shutdown_receiver.clone());
 
         // Stop signer on shutdown
         let mut shutdown_receiver = self.shutdown_receiver.clone();
-        tokio::spawn(async move {
+        wallets::spawn(async move {
             // start the backup watcher
             _ = shutdown_receiver.changed().await;
             _ = shutdown_signer_sender.send(()).await;
@@ -1448,7 +2552,7 @@ impl BreezServices {
 
     async fn start_signer(self: &Arc<BreezServices>, shutdown_receiver: mpsc::Receiver<()>) {
         let signer_api = self.clone();
-        tokio::spawn(async move {
+        wallets::spawn(async move {
             tokio::time::sleep(std::time::Duration::from_secs(2)).await;
             signer_api.node_api.start_signer(shutdown_receiver).await;
         });
@@ -1459,7 +2563,7 @@ impl BreezServices {
         shutdown_receiver: watch::Receiver<()>,
     ) {
         let cloned = self.clone();
-        tokio::spawn(async move {
+        wallets::spawn(async move {
             cloned.node_api.start_keep_alive(shutdown_receiver).await;
         });
     }
@@ -1484,7 +2588,7 @@ impl BreezServices {
 
     async fn track_backup_events(self: &Arc<BreezServices>) {
         let cloned = self.clone();
-        tokio::spawn(async move {
+        wallets::spawn(async move {
             let mut events_stream = cloned.backup_watcher.subscribe_events();
             let mut shutdown_receiver = cloned.shutdown_receiver.clone();
             loop {
@@ -1509,7 +2613,7 @@ impl BreezServices {
 
     async fn track_swap_events(self: &Arc<BreezServices>) {
         let cloned = self.clone();
-        tokio::spawn(async move {
+        wallets::spawn(async move {
             let mut swap_events_stream = cloned.btc_receive_swapper.subscribe_status_changes();
             let mut rev_swap_events_stream = cloned.btc_send_swapper.subscribe_status_changes();
             let mut shutdown_receiver = cloned.shutdown_receiver.clone();
@@ -1540,7 +2644,7 @@ impl BreezServices {
 
     async fn track_invoices(self: &Arc<BreezServices>) {
         let cloned = self.clone();
-        tokio::spawn(async move {
+        wallets::spawn(async move {
             let mut shutdown_receiver = cloned.shutdown_receiver.clone();
             loop {
                 if shutdown_receiver.has_changed().unwrap_or(true) {
@@ -1550,9 +2654,9 @@ impl BreezServices {
                 if let Ok(mut invoice_stream) = invoice_stream_res {
                     loop {
                         tokio::select! {
-                                paid_invoice_res = invoice_stream.message() => {
+                                paid_invoice_res = invoice_stream.next() => {
                                       match paid_invoice_res {
-                                          Ok(Some(i)) => {
+                                          Some(Ok(i)) => {
                                               debug!("invoice stream got new invoice");
                                               if let Some(gl_client::signer::model::greenlight::incoming_payment::Details::Offchain(p)) = i.details {
                                                   let mut payment: Option<crate::models::Payment> = p.clone().try_into().ok();
@@ -1580,11 +2684,11 @@ impl BreezServices {
                                                   }
                                               }
                                           }
-                                          Ok(None) => {
+                                          None => {
                                               debug!("invoice stream got None");
                                               break;
                                           }
-                                          Err(err) => {
+                                          Some(Err(err)) => {
                                               debug!("invoice stream got error: {:?}", err);
                                               break;
                                           }
@@ -1605,7 +2709,7 @@ impl BreezServices {
 
     async fn track_logs(self: &Arc<BreezServices>) {
         let cloned = self.clone();
-        tokio::spawn(async move {
+        wallets::spawn(async move {
             let mut shutdown_receiver = cloned.shutdown_receiver.clone();
             loop {
                 if shutdown_receiver.has_changed().unwrap_or(true) {
@@ -1645,7 +2749,7 @@ impl BreezServices {
 
     async fn track_new_blocks(self: &Arc<BreezServices>) {
         let cloned = self.clone();
-        tokio::spawn(async move {
+        wallets::spawn(async move {
             let mut current_block: u32 = 0;
             let mut shutdown_receiver = cloned.shutdown_receiver.clone();
             let mut interval = tokio::time::interval(Duration::from_secs(30));
@@ -1675,7 +2779,217 @@ impl BreezServices {
                     }
                 }
             }
-        });
+        });
+    }
+
+    async fn track_sweep_policy(self: &Arc<BreezServices>) {
+        let cloned = self.clone();
+        wallets::spawn(async move {
+            let mut shutdown_receiver = cloned.shutdown_receiver.clone();
+            loop {
+                tokio::select! {
+                    _ = cloned.sweep_policy_notifier.notified() => {
+                        if let Err(e) = cloned.apply_sweep_policy().await {
+                            error!("Failed to apply the sweep policy: {e}");
+                        }
+                    }
+
+                    _ = shutdown_receiver.changed() => {
+                        debug!("Sweep policy task has completed");
+                        return;
+                    }
+                }
+            }
+        });
+    }
+
+    async fn track_payment_requests_expiry(self: &Arc<BreezServices>) {
+        let cloned = self.clone();
+        wallets::spawn(async move {
+            let mut shutdown_receiver = cloned.shutdown_receiver.clone();
+            loop {
+                let next_expiry = match cloned.persister.get_next_payment_request_expiry() {
+                    Ok(next_expiry) => next_expiry,
+                    Err(e) => {
+                        error!("Failed to get the next payment request expiry: {e}");
+                        None
+                    }
+                };
+                let expiry = async {
+                    match next_expiry {
+                        Some(expires_at) => {
+                            let secs = (expires_at - Utc::now().timestamp()).max(0) as u64;
+                            sleep(Duration::from_secs(secs)).await
+                        }
+                        None => std::future::pending().await,
+                    }
+                };
+
+                tokio::select! {
+                    _ = expiry => {
+                        if let Err(e) = cloned.update_payment_requests().await {
+                            error!("Failed to update the payment requests: {e}");
+                            sleep(Duration::from_secs(1)).await;
+                        }
+                    }
+
+                    // a new request may expire before the one awaited
+                    _ = cloned.payment_requests_notifier.notified() => {}
+
+                    _ = shutdown_receiver.changed() => {
+                        debug!("Payment requests expiry task has completed");
+                        return;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Makes the sweeps of the [SweepPolicy] that are due with the current balances and fees
+    async fn apply_sweep_policy(&self) -> Result<()> {
+        let policy = match self.persister.get_sweep_policy()? {
+            Some(policy) => policy,
+            None => return Ok(()),
+        };
+        let node_state = match self.persister.get_node_state()? {
+            Some(node_state) => node_state,
+            None => return Ok(()),
+        };
+        let sat_per_vbyte = self.chain_service.recommended_fees().await?.half_hour_fee as u32;
+
+        for kind in policy.due_sweeps(&node_state, sat_per_vbyte) {
+            match self.sweep(kind, &policy, &node_state, sat_per_vbyte).await {
+                Ok(Some(details)) => {
+                    info!("Swept {} sat with the sweep policy", details.amount_sat);
+                    self.notify_event_listeners(BreezEvent::SweepSucceeded { details })
+                        .await?
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    self.notify_event_listeners(BreezEvent::SweepFailed {
+                        details: SweepFailedData {
+                            kind,
+                            error: e.to_string(),
+                        },
+                    })
+                    .await?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes a sweep of the policy, unless it has to wait for a previous one or a bigger amount
+    async fn sweep(
+        &self,
+        kind: SweepKind,
+        policy: &SweepPolicy,
+        node_state: &NodeState,
+        sat_per_vbyte: u32,
+    ) -> Result<Option<SweepDetails>> {
+        match kind {
+            SweepKind::Onchain => {
+                let onchain = policy
+                    .onchain
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("No onchain sweep policy"))?;
+                if self.onchain_sweep_in_flight().await? {
+                    return Ok(None);
+                }
+                let prepare_res = self
+                    .prepare_redeem_onchain_funds(PrepareRedeemOnchainFundsRequest {
+                        to_address: onchain.to_address.clone(),
+                        sat_per_vbyte,
+                    })
+                    .await?;
+                let amount_sat = (node_state.onchain_balance_msat / 1_000)
+                    .checked_sub(prepare_res.tx_fee_sat)
+                    .ok_or_else(|| anyhow!("The onchain balance doesn't cover the sweep fee"))?;
+
+                let res = self
+                    .redeem_onchain_funds(RedeemOnchainFundsRequest {
+                        to_address: onchain.to_address.clone(),
+                        sat_per_vbyte,
+                    })
+                    .await?;
+                let txid = hex::encode(res.txid);
+                self.persister.set_onchain_sweep_txid(&txid)?;
+                Ok(Some(SweepDetails {
+                    kind,
+                    amount_sat,
+                    sat_per_vbyte,
+                    id: txid,
+                }))
+            }
+            SweepKind::Lightning => {
+                let lightning = policy
+                    .lightning
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("No lightning sweep policy"))?;
+                if !self.in_progress_onchain_payments().await?.is_empty() {
+                    return Ok(None);
+                }
+                let limits = self.onchain_payment_limits().await?;
+                let amount_sat = (node_state.channels_balance_msat / 1_000
+                    - lightning.max_channels_balance_sat)
+                    .min(limits.max_sat)
+                    .min(limits.max_payable_sat);
+                if amount_sat < limits.min_sat {
+                    return Ok(None);
+                }
+
+                let prepare_res = self
+                    .prepare_onchain_payment(PrepareOnchainPaymentRequest {
+                        amount_sat,
+                        amount_type: SwapAmountType::Send,
+                        claim_tx_feerate: sat_per_vbyte,
+                    })
+                    .await?;
+                let recipient_amount_sat = prepare_res.recipient_amount_sat;
+                let res = self
+                    .pay_onchain(PayOnchainRequest {
+                        recipient_address: lightning.to_address.clone(),
+                        prepare_res,
+                    })
+                    .await?;
+                Ok(Some(SweepDetails {
+                    kind,
+                    amount_sat: recipient_amount_sat,
+                    sat_per_vbyte,
+                    id: res.reverse_swap_info.id,
+                }))
+            }
+        }
+    }
+
+    /// Whether the redeem tx of the last onchain sweep, or the tx replacing it, is still
+    /// unconfirmed. The node balance doesn't reflect it until it confirms.
+    async fn onchain_sweep_in_flight(&self) -> Result<bool> {
+        let mut txid = match self.persister.get_onchain_sweep_txid()? {
+            Some(txid) => txid,
+            None => return Ok(false),
+        };
+        while let Some(replacing_txid) = self.persister.get_replacing_txid(&txid)? {
+            txid = replacing_txid;
+        }
+        let to_address = match self.persister.get_redeem_tx_address(&txid)? {
+            Some(to_address) => to_address,
+            None => {
+                self.persister.delete_onchain_sweep_txid()?;
+                return Ok(false);
+            }
+        };
+        let in_flight = self
+            .chain_service
+            .address_transactions(to_address)
+            .await?
+            .into_iter()
+            .any(|tx| tx.txid == txid && !tx.status.confirmed);
+        // Once confirmed, or dropped from the mempool, the sweep isn't waited for anymore
+        if !in_flight {
+            self.persister.delete_onchain_sweep_txid()?;
+        }
+        Ok(in_flight)
     }
 
     async fn init_chainservice_urls(&self) -> Result<()> {
@@ -1687,7 +3001,7 @@ impl BreezServices {
 
         let cloned_breez_server = breez_server.clone();
         let cloned_persister = persister.clone();
-        tokio::spawn(async move {
+        wallets::spawn(async move {
             match cloned_breez_server.fetch_mempoolspace_urls().await {
                 Ok(fresh_urls) => {
                     if let Err(e) = cloned_persister.set_mempoolspace_base_urls(fresh_urls) {
@@ -1719,6 +3033,9 @@ impl BreezServices {
     /// log output to a file in the configured `log_dir`, then do not register the
     /// app-specific logger as a global logger and instead call this method with the app logger as an arg.
     ///
+    /// When hosting several wallets with a [crate::wallets::WalletManager], the log statements
+    /// are prefixed with the wallet id and also sent to the wallet log stream, if any.
+    ///
     /// ### Logging Configuration
     ///
     /// Setting `breez_sdk_core::input_parser=debug` will include in the logs the raw payloads received
@@ -1757,11 +3074,15 @@ impl BreezServices {
             "#,
             )
             .format(|buf, record| {
+                let wallet = wallets::current_wallet_id()
+                    .map(|wallet_id| format!(" {wallet_id}"))
+                    .unwrap_or_default();
                 writeln!(
                     buf,
-                    "[{} {} {}:{}] {}",
+                    "[{} {}{} {}:{}] {}",
                     Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
                     record.level(),
+                    wallet,
                     record.module_path().unwrap_or("unknown"),
                     record.line().unwrap_or(0),
                     record.args()
@@ -1856,26 +3177,26 @@ impl BreezServices {
         };
 
         Ok(Payment {
-            id: channel.funding_txid.clone(),
-            payment_type: PaymentType::ClosedChannel,
-            payment_time,
-            amount_msat: channel.local_balance_msat,
-            fee_msat: 0,
-            status: match channel.state {
-                ChannelState::PendingClose => PaymentStatus::Pending,
-                _ => PaymentStatus::Complete,
-            },
             description: Some("Closed Channel".to_string()),
-            details: PaymentDetails::ClosedChannel {
-                data: ClosedChannelPaymentDetails {
-                    short_channel_id: channel.short_channel_id,
-                    state: channel.state,
-                    funding_txid: channel.funding_txid,
-                    closing_txid,
+            ..Payment::new(
+                channel.funding_txid.clone(),
+                PaymentType::ClosedChannel,
+                payment_time,
+                channel.local_balance_msat,
+                0,
+                match channel.state {
+                    ChannelState::PendingClose => PaymentStatus::Pending,
+                    _ => PaymentStatus::Complete,
                 },
-            },
-            error: None,
-            metadata: None,
+                PaymentDetails::ClosedChannel {
+                    data: ClosedChannelPaymentDetails {
+                        short_channel_id: channel.short_channel_id,
+                        state: channel.state,
+                        funding_txid: channel.funding_txid,
+                        closing_txid,
+                    },
+                },
+            )
         })
     }
 
@@ -2133,6 +3454,7 @@ impl log::Log for GlobalSdkLogger {
                     s.log(record);
                 }
             }
+            wallets::log_to_wallet_stream(record);
         }
     }
 
@@ -2155,6 +3477,7 @@ struct BreezServicesBuilder {
     /// Reverse swap functionality on the 3rd party reverse swap service
     reverse_swap_service_api: Option<Arc<dyn ReverseSwapServiceAPI>>,
     buy_bitcoin_api: Option<Arc<dyn BuyBitcoinApi>>,
+    payment_approver: Option<Arc<dyn PaymentApprover>>,
 }
 
 #[allow(dead_code)]
@@ -2173,6 +3496,7 @@ impl BreezServicesBuilder {
             reverse_swap_service_api: None,
             buy_bitcoin_api: None,
             backup_transport: None,
+            payment_approver: None,
         }
     }
 
@@ -2237,6 +3561,11 @@ impl BreezServicesBuilder {
         self
     }
 
+    pub fn payment_approver(&mut self, payment_approver: Arc<dyn PaymentApprover>) -> &mut Self {
+        self.payment_approver = Some(payment_approver);
+        self
+    }
+
     pub async fn build(
         &self,
         restore_only: Option<bool>,
@@ -2258,17 +3587,34 @@ impl BreezServicesBuilder {
         let mut node_api = self.node_api.clone();
         let mut backup_transport = self.backup_transport.clone();
         if node_api.is_none() {
-            let greenlight = Greenlight::connect(
-                self.config.clone(),
-                self.seed.clone().unwrap(),
-                restore_only,
-                persister.clone(),
-            )
-            .await?;
-            let gl_arc = Arc::new(greenlight);
-            node_api = Some(gl_arc.clone());
-            if backup_transport.is_none() {
-                backup_transport = Some(Arc::new(GLBackupTransport { inner: gl_arc }));
+            match self.config.node_config {
+                NodeConfig::Greenlight { .. } => {
+                    let greenlight = Greenlight::connect(
+                        self.config.clone(),
+                        self.seed.clone().unwrap(),
+                        restore_only,
+                        persister.clone(),
+                    )
+                    .await?;
+                    let gl_arc = Arc::new(greenlight);
+                    node_api = Some(gl_arc.clone());
+                    if backup_transport.is_none() {
+                        backup_transport = Some(Arc::new(GLBackupTransport { inner: gl_arc }));
+                    }
+                }
+                NodeConfig::Cln { .. } => {
+                    let cln = Cln::connect(
+                        self.config.clone(),
+                        self.seed.clone().unwrap(),
+                        persister.clone(),
+                    )
+                    .await?;
+                    let cln_arc = Arc::new(cln);
+                    node_api = Some(cln_arc.clone());
+                    if backup_transport.is_none() {
+                        backup_transport = Some(Arc::new(ClnBackupTransport { inner: cln_arc }));
+                    }
+                }
             }
         }
 
@@ -2311,7 +3657,7 @@ impl BreezServicesBuilder {
 
         // Ensure breez server connection is established in the background
         let cloned_breez_server = breez_server.clone();
-        tokio::spawn(async move {
+        wallets::spawn(async move {
             if let Err(e) = cloned_breez_server.ping().await {
                 error!("Failed to ping breez server: {e}");
             }
@@ -2322,34 +3668,61 @@ impl BreezServicesBuilder {
             persister.set_lsp_id(self.config.default_lsp_id.clone().unwrap())?;
         }
 
+        // merge the LSPs registered in the config with the ones from the LSP API
+        // LSPS0 needs custom messages, without them only the legacy LSP flow is used
+        let lsps0_transport = match unwrapped_node_api
+            .unsupported_features()
+            .contains(&NodeFeature::CustomMessages)
+        {
+            true => None,
+            false => Some(Arc::new(lsps0::Transport::new(unwrapped_node_api.clone()))),
+        };
+        let lsp_api: Arc<dyn LspAPI> = Arc::new(LspRegistry::new(
+            self.lsp_api.clone().unwrap_or_else(|| breez_server.clone()),
+            self.config.custom_lsps.clone(),
+            unwrapped_node_api.clone(),
+            lsps0_transport.clone(),
+            persister.clone(),
+        ));
+
         let payment_receiver = Arc::new(PaymentReceiver {
             config: self.config.clone(),
             node_api: unwrapped_node_api.clone(),
-            lsp: breez_server.clone(),
+            lsp: lsp_api.clone(),
             persister: persister.clone(),
+            lsps0_transport,
+            lsps_protocols: Default::default(),
         });
 
-        // mempool space is used to monitor the chain
-        let mempoolspace_urls = match self.config.mempoolspace_url.clone() {
-            None => {
-                let cached = persister.get_mempoolspace_base_urls()?;
-                match cached.len() {
-                    // If we have no cached values, or we cached an empty list, fetch new ones
-                    0 => {
-                        let fresh_urls = breez_server
-                            .fetch_mempoolspace_urls()
-                            .await
-                            .unwrap_or(vec![DEFAULT_MEMPOOL_SPACE_URL.into()]);
-                        persister.set_mempoolspace_base_urls(fresh_urls.clone())?;
-                        fresh_urls
+        // mempool space is used to monitor the chain, unless other backends are configured
+        let chain_service = Arc::new(match self.config.chain_backends.is_empty() {
+            true => {
+                let mempoolspace_urls = match self.config.mempoolspace_url.clone() {
+                    None => {
+                        let cached = persister.get_mempoolspace_base_urls()?;
+                        match cached.len() {
+                            // If we have no cached values, or we cached an empty list, fetch new ones
+                            0 => {
+                                let fresh_urls = breez_server
+                                    .fetch_mempoolspace_urls()
+                                    .await
+                                    .unwrap_or(vec![DEFAULT_MEMPOOL_SPACE_URL.into()]);
+                                persister.set_mempoolspace_base_urls(fresh_urls.clone())?;
+                                fresh_urls
+                            }
+                            // If we already have cached values, return those
+                            _ => cached,
+                        }
                     }
-                    // If we already have cached values, return those
-                    _ => cached,
-                }
+                    Some(mempoolspace_url_from_config) => vec![mempoolspace_url_from_config],
+                };
+                RedundantChainService::from_base_urls(mempoolspace_urls)
             }
-            Some(mempoolspace_url_from_config) => vec![mempoolspace_url_from_config],
-        };
-        let chain_service = Arc::new(RedundantChainService::from_base_urls(mempoolspace_urls));
+            false => RedundantChainService::from_backends(
+                self.config.chain_backends.clone(),
+                self.config.network.into(),
+            ),
+        });
 
         let btc_receive_swapper = Arc::new(BTCReceiveSwap::new(
             self.config.network.into(),
@@ -2388,7 +3761,7 @@ impl BreezServicesBuilder {
             config: self.config.clone(),
             started: Mutex::new(false),
             node_api: unwrapped_node_api.clone(),
-            lsp_api: self.lsp_api.clone().unwrap_or_else(|| breez_server.clone()),
+            lsp_api,
             fiat_api: self
                 .fiat_api
                 .clone()
@@ -2405,6 +3778,10 @@ impl BreezServicesBuilder {
             payment_receiver,
             event_listener,
             backup_watcher: Arc::new(backup_watcher),
+            sweep_policy_notifier: Notify::new(),
+            payment_requests_notifier: Notify::new(),
+            payment_approver: self.payment_approver.clone(),
+            budget_lock: Mutex::new(()),
             shutdown_sender,
             shutdown_receiver,
         });
@@ -2446,6 +3823,11 @@ pub(crate) struct PaymentReceiver {
     node_api: Arc<dyn NodeAPI>,
     lsp: Arc<dyn LspAPI>,
     persister: Arc<SqliteStorage>,
+    /// Used to talk LSPS0 to the LSP. When set, channel opens are bought through LSPS2 if the
+    /// LSP supports it, otherwise the legacy [LspAPI::register_payment] flow is used.
+    lsps0_transport: Option<Arc<lsps0::Transport>>,
+    /// The LSPS protocols supported by each LSP, so an LSP is only probed once
+    lsps_protocols: std::sync::Mutex<HashMap<String, Vec<u32>>>,
 }
 
 #[tonic::async_trait]
@@ -2474,6 +3856,7 @@ impl Receiver for PaymentReceiver {
         let mut destination_invoice_amount_msat = req.amount_msat;
         let mut channel_opening_fee_params = None;
         let mut channel_fees_msat = None;
+        let mut lsps2_client = None;
 
         // check if we need to open channel
         let open_channel_needed =
@@ -2481,12 +3864,54 @@ impl Receiver for PaymentReceiver {
         if open_channel_needed {
             info!("We need to open a channel");
 
+            // prefer the standard LSPS2 flow if the LSP supports it
+            lsps2_client = self.lsps2_client(&lsp_info).await;
+            let lsps2_info = match &lsps2_client {
+                Some(client) => Some(
+                    client
+                        .get_info(lsps2::client::GetInfoRequest {
+                            version: lsps2::LSPS2_VERSION,
+                            token: match self.custom_lsp(&lsp_info.id) {
+                                Some(custom_lsp) => custom_lsp.token.clone(),
+                                None => self.config.api_key.clone(),
+                            },
+                        })
+                        .await?,
+                ),
+                None => None,
+            };
+
             // we need to open channel so we are calculating the fees for the LSP (coming either from the user, or from the LSP)
-            let ofp = match req.opening_fee_params {
-                Some(fee_params) => fee_params,
-                None => lsp_info.cheapest_open_channel_fee(expiry)?.clone(),
+            let ofp = match (req.opening_fee_params, &lsps2_info) {
+                (Some(fee_params), _) => fee_params,
+                (None, Some(info)) => LspInformation {
+                    opening_fee_params_list: OpeningFeeParamsMenu::try_from(
+                        info.opening_fee_params_menu
+                            .iter()
+                            .cloned()
+                            .map(Into::into)
+                            .collect(),
+                    )?,
+                    ..lsp_info.clone()
+                }
+                .cheapest_open_channel_fee(expiry)?
+                .clone(),
+                (None, None) => lsp_info.cheapest_open_channel_fee(expiry)?.clone(),
             };
 
+            if let Some(info) = &lsps2_info {
+                ensure_sdk!(
+                    req.amount_msat >= info.min_payment_size_msat
+                        && req.amount_msat <= info.max_payment_size_msat,
+                    ReceivePaymentError::InvalidAmount {
+                        err: format!(
+                            "Amount should be between {} and {} msat to open a channel, but is {} msat",
+                            info.min_payment_size_msat, info.max_payment_size_msat, req.amount_msat
+                        )
+                    }
+                );
+            }
+
             channel_opening_fee_params = Some(ofp.clone());
             channel_fees_msat = Some(ofp.get_channel_fees_msat_for(req.amount_msat));
             if let Some(channel_fees_msat) = channel_fees_msat {
@@ -2511,7 +3936,7 @@ impl Receiver for PaymentReceiver {
             .node_api
             .create_invoice(CreateInvoiceRequest {
                 amount_msat: destination_invoice_amount_msat,
-                description: req.description,
+                description: req.description.clone(),
                 payer_amount_msat: match open_channel_needed {
                     true => Some(req.amount_msat),
                     false => None,
@@ -2537,11 +3962,34 @@ impl Receiver for PaymentReceiver {
             false => None,
         };
 
-        let invoice = self
-            .wrap_node_invoice(&invoice, open_channel_params, Some(lsp_info))
-            .await?;
+        let invoice = match (lsps2_client, open_channel_params) {
+            (Some(client), Some(params)) => {
+                self.wrap_lsps2_invoice(&client, &invoice, params, &lsp_info)
+                    .await?
+            }
+            (_, params) => {
+                self.wrap_node_invoice(&invoice, params, Some(lsp_info))
+                    .await?
+            }
+        };
         let parsed_invoice = parse_invoice(&invoice)?;
 
+        // track the invoice until it is paid or expires, the invoice is valid regardless
+        if let Err(e) = self.persister.insert_payment_request(&PaymentRequest {
+            payment_hash: parsed_invoice.payment_hash.clone(),
+            bolt11: invoice,
+            description: req.description,
+            amount_msat: req.amount_msat,
+            opening_fee_params: channel_opening_fee_params.clone(),
+            opening_fee_msat: channel_fees_msat,
+            status: PaymentRequestStatus::Open,
+            created_at: parsed_invoice.timestamp as i64,
+            expires_at: (parsed_invoice.timestamp + parsed_invoice.expiry) as i64,
+            paid_at: None,
+        }) {
+            error!("Failed to track the payment request: {e}");
+        }
+
         // return the signed, converted invoice with hints
         Ok(ReceivePaymentResponse {
             ln_invoice: parsed_invoice,
@@ -2572,6 +4020,124 @@ impl Receiver for PaymentReceiver {
 }
 
 impl PaymentReceiver {
+    /// Starts listening for LSPS0 messages from the LSP, if LSPS0 is enabled.
+    fn start(&self, shutdown: watch::Receiver<()>) {
+        if let Some(transport) = &self.lsps0_transport {
+            transport.start(shutdown);
+        }
+    }
+
+    /// Returns an LSPS2 client for the given LSP, if the LSP advertises support for LSPS2.
+    ///
+    /// Any error while probing the LSP is logged and treated as no LSPS2 support, so the caller
+    /// can fall back to the legacy flow.
+    async fn lsps2_client(&self, lsp_info: &LspInformation) -> Option<lsps2::Client> {
+        let transport = self.lsps0_transport.clone()?;
+        let peer_id = match hex::decode(&lsp_info.pubkey) {
+            Ok(peer_id) => peer_id,
+            Err(e) => {
+                warn!("Failed to decode LSP pubkey {}: {e}", lsp_info.pubkey);
+                return None;
+            }
+        };
+        let client = lsps0::Client::new(transport, peer_id, lsps0::LSPS0_REQUEST_TIMEOUT);
+        let cached_protocols = self
+            .lsps_protocols
+            .lock()
+            .unwrap()
+            .get(&lsp_info.pubkey)
+            .cloned();
+        let protocols = match (self.custom_lsp(&lsp_info.id), cached_protocols) {
+            (Some(custom_lsp), _) if !custom_lsp.protocols.is_empty() => {
+                custom_lsp.protocols.clone()
+            }
+            (_, Some(protocols)) => protocols,
+            _ => match client.list_protocols().await {
+                Ok(res) => {
+                    self.lsps_protocols
+                        .lock()
+                        .unwrap()
+                        .insert(lsp_info.pubkey.clone(), res.protocols.clone());
+                    res.protocols
+                }
+                Err(e) => {
+                    debug!("Failed to list LSPS protocols of LSP {}: {e}", lsp_info.id);
+                    return None;
+                }
+            },
+        };
+        match protocols.contains(&2) {
+            true => {
+                info!("LSP {} supports LSPS2", lsp_info.id);
+                Some(lsps2::Client::new(client))
+            }
+            false => {
+                debug!(
+                    "LSP {} does not support LSPS2, supported protocols: {:?}",
+                    lsp_info.id, protocols
+                );
+                None
+            }
+        }
+    }
+
+    /// Returns the [CustomLsp] registered in the [Config] with the given ID, if any.
+    fn custom_lsp(&self, lsp_id: &str) -> Option<&CustomLsp> {
+        self.config
+            .custom_lsps
+            .iter()
+            .find(|custom_lsp| custom_lsp.id == lsp_id)
+    }
+
+    /// Buys a JIT channel through LSPS2 and adds the resulting route hint to the invoice.
+    async fn wrap_lsps2_invoice(
+        &self,
+        client: &lsps2::Client,
+        invoice: &str,
+        params: OpenChannelParams,
+        lsp_info: &LspInformation,
+    ) -> Result<String, ReceivePaymentError> {
+        let parsed_invoice = parse_invoice(invoice)?;
+
+        info!("Buying JIT channel from LSP using LSPS2");
+        let buy_res = client
+            .buy(lsps2::client::BuyRequest {
+                version: lsps2::LSPS2_VERSION,
+                opening_fee_params: params.opening_fee_params.into(),
+                payment_size_msat: Some(params.payer_amount_msat),
+            })
+            .await?;
+
+        let jit_channel_hint = RouteHint {
+            hops: vec![RouteHintHop {
+                src_node_id: lsp_info.pubkey.clone(),
+                short_channel_id: parse_short_channel_id(&buy_res.jit_channel_scid)?,
+                fees_base_msat: 0,
+                fees_proportional_millionths: 0,
+                cltv_expiry_delta: buy_res.lsp_cltv_expiry_delta as u64,
+                htlc_minimum_msat: None,
+                htlc_maximum_msat: None,
+            }],
+        };
+        info!("Adding JIT channel hint: {:?}", jit_channel_hint);
+        let invoice_with_hint = add_routing_hints(
+            invoice,
+            false,
+            &vec![jit_channel_hint],
+            Some(params.payer_amount_msat),
+        )?;
+        let signed_invoice = self.node_api.sign_invoice(invoice_with_hint)?;
+
+        // Make sure we save the large amount so we can deduce the fees later.
+        self.persister.insert_open_channel_payment_info(
+            &parsed_invoice.payment_hash,
+            params.payer_amount_msat,
+            invoice,
+        )?;
+
+        Ok(signed_invoice)
+    }
+
     async fn ensure_hint(
         &self,
         invoice: &str,
@@ -2771,17 +4337,20 @@ pub(crate) mod tests {
     use std::sync::Arc;
 
     use anyhow::{anyhow, Result};
+    use chrono::Utc;
     use regex::Regex;
     use reqwest::Url;
     use sdk_common::prelude::Rate;
+    use tokio::sync::watch;
 
     use crate::breez_services::{BreezServices, BreezServicesBuilder};
+    use crate::lsps0;
     use crate::models::{LnPaymentDetails, NodeState, Payment, PaymentDetails, PaymentTypeFilter};
     use crate::node_api::NodeAPI;
     use crate::test_utils::*;
     use crate::*;
 
-    use super::{PaymentReceiver, Receiver};
+    use super::{PaymentReceiver, Receiver, BUDGET_LOOSENING_DELAY_SECONDS};
 
     #[tokio::test]
     async fn test_node_state() -> Result<()> {
@@ -2812,6 +4381,7 @@ pub(crate) mod tests {
             public_key: vec![],
             swapper_public_key: vec![],
             script: vec![],
+            script_version: SwapScriptVersion::P2wsh,
             bolt11: Some("312".into()),
             paid_msat: 1000,
             confirmed_sats: 1,
@@ -2865,159 +4435,164 @@ pub(crate) mod tests {
         };
         let dummy_transactions = vec![
             Payment {
-                id: "1111".to_string(),
-                payment_type: PaymentType::Received,
-                payment_time: 100000,
-                amount_msat: 10,
-                fee_msat: 0,
-                status: PaymentStatus::Complete,
-                error: None,
                 description: Some("test receive".to_string()),
-                details: PaymentDetails::Ln {
-                    data: LnPaymentDetails {
-                        payment_hash: "1111".to_string(),
-                        label: "".to_string(),
-                        destination_pubkey: "1111".to_string(),
-                        payment_preimage: "2222".to_string(),
-                        keysend: false,
-                        bolt11: "1111".to_string(),
-                        lnurl_success_action: None,
-                        lnurl_pay_domain: None,
-                        lnurl_pay_comment: None,
-                        lnurl_metadata: None,
-                        ln_address: None,
-                        lnurl_withdraw_endpoint: None,
-                        swap_info: None,
-                        reverse_swap_info: None,
-                        pending_expiration_block: None,
-                        open_channel_bolt11: None,
+                ..Payment::new(
+                    "1111".to_string(),
+                    PaymentType::Received,
+                    100000,
+                    10,
+                    0,
+                    PaymentStatus::Complete,
+                    PaymentDetails::Ln {
+                        data: LnPaymentDetails {
+                            payment_hash: "1111".to_string(),
+                            label: "".to_string(),
+                            destination_pubkey: "1111".to_string(),
+                            payment_preimage: "2222".to_string(),
+                            keysend: false,
+                            bolt11: "1111".to_string(),
+                            lnurl_success_action: None,
+                            lnurl_pay_domain: None,
+                            lnurl_pay_comment: None,
+                            lnurl_metadata: None,
+                            ln_address: None,
+                            lnurl_withdraw_endpoint: None,
+                            lnurl_verify_url: None,
+                            swap_info: None,
+                            reverse_swap_info: None,
+                            pending_expiration_block: None,
+                            open_channel_bolt11: None,
+                        },
                     },
-                },
-                metadata: None,
+                )
             },
             Payment {
-                id: payment_hash_lnurl_withdraw.to_string(),
-                payment_type: PaymentType::Received,
-                payment_time: 150000,
-                amount_msat: 10,
-                fee_msat: 0,
-                status: PaymentStatus::Complete,
-                error: None,
                 description: Some("test lnurl-withdraw receive".to_string()),
-                details: PaymentDetails::Ln {
-                    data: LnPaymentDetails {
-                        payment_hash: payment_hash_lnurl_withdraw.to_string(),
-                        label: "".to_string(),
-                        destination_pubkey: "1111".to_string(),
-                        payment_preimage: "3333".to_string(),
-                        keysend: false,
-                        bolt11: "1111".to_string(),
-                        lnurl_success_action: None,
-                        lnurl_pay_domain: None,
-                        lnurl_pay_comment: None,
-                        lnurl_metadata: None,
-                        ln_address: None,
-                        lnurl_withdraw_endpoint: Some(test_lnurl_withdraw_endpoint.to_string()),
-                        swap_info: None,
-                        reverse_swap_info: None,
-                        pending_expiration_block: None,
-                        open_channel_bolt11: None,
+                ..Payment::new(
+                    payment_hash_lnurl_withdraw.to_string(),
+                    PaymentType::Received,
+                    150000,
+                    10,
+                    0,
+                    PaymentStatus::Complete,
+                    PaymentDetails::Ln {
+                        data: LnPaymentDetails {
+                            payment_hash: payment_hash_lnurl_withdraw.to_string(),
+                            label: "".to_string(),
+                            destination_pubkey: "1111".to_string(),
+                            payment_preimage: "3333".to_string(),
+                            keysend: false,
+                            bolt11: "1111".to_string(),
+                            lnurl_success_action: None,
+                            lnurl_pay_domain: None,
+                            lnurl_pay_comment: None,
+                            lnurl_metadata: None,
+                            ln_address: None,
+                            lnurl_withdraw_endpoint: Some(test_lnurl_withdraw_endpoint.to_string()),
+                            lnurl_verify_url: None,
+                            swap_info: None,
+                            reverse_swap_info: None,
+                            pending_expiration_block: None,
+                            open_channel_bolt11: None,
+                        },
                     },
-                },
-                metadata: None,
+                )
             },
             Payment {
-                id: payment_hash_with_lnurl_success_action.to_string(),
-                payment_type: PaymentType::Sent,
-                payment_time: 200000,
-                amount_msat: 8,
-                fee_msat: 2,
-                status: PaymentStatus::Complete,
-                error: None,
                 description: Some("test payment".to_string()),
-                details: PaymentDetails::Ln {
-                    data: LnPaymentDetails {
-                        payment_hash: payment_hash_with_lnurl_success_action.to_string(),
-                        label: "".to_string(),
-                        destination_pubkey: "123".to_string(),
-                        payment_preimage: "4444".to_string(),
-                        keysend: false,
-                        bolt11: "123".to_string(),
-                        lnurl_success_action: Some(sa.clone()),
-                        lnurl_pay_domain: None,
-                        lnurl_pay_comment: None,
-                        lnurl_metadata: Some(lnurl_metadata.to_string()),
-                        ln_address: Some(test_ln_address.to_string()),
-                        lnurl_withdraw_endpoint: None,
-                        swap_info: None,
-                        reverse_swap_info: None,
-                        pending_expiration_block: None,
-                        open_channel_bolt11: None,
+                ..Payment::new(
+                    payment_hash_with_lnurl_success_action.to_string(),
+                    PaymentType::Sent,
+                    200000,
+                    8,
+                    2,
+                    PaymentStatus::Complete,
+                    PaymentDetails::Ln {
+                        data: LnPaymentDetails {
+                            payment_hash: payment_hash_with_lnurl_success_action.to_string(),
+                            label: "".to_string(),
+                            destination_pubkey: "123".to_string(),
+                            payment_preimage: "4444".to_string(),
+                            keysend: false,
+                            bolt11: "123".to_string(),
+                            lnurl_success_action: Some(sa.clone()),
+                            lnurl_pay_domain: None,
+                            lnurl_pay_comment: None,
+                            lnurl_metadata: Some(lnurl_metadata.to_string()),
+                            ln_address: Some(test_ln_address.to_string()),
+                            lnurl_withdraw_endpoint: None,
+                            lnurl_verify_url: None,
+                            swap_info: None,
+                            reverse_swap_info: None,
+                            pending_expiration_block: None,
+                            open_channel_bolt11: None,
+                        },
                     },
-                },
-                metadata: None,
+                )
             },
             Payment {
-                id: hex::encode(payment_hash_swap.clone()),
-                payment_type: PaymentType::Received,
-                payment_time: 250000,
-                amount_msat: 1_000,
-                fee_msat: 0,
-                status: PaymentStatus::Complete,
-                error: None,
                 description: Some("test receive".to_string()),
-                details: PaymentDetails::Ln {
-                    data: LnPaymentDetails {
-                        payment_hash: hex::encode(payment_hash_swap),
-                        label: "".to_string(),
-                        destination_pubkey: "321".to_string(),
-                        payment_preimage: "5555".to_string(),
-                        keysend: false,
-                        bolt11: "312".to_string(),
-                        lnurl_success_action: None,
-                        lnurl_pay_domain: None,
-                        lnurl_pay_comment: None,
-                        lnurl_metadata: None,
-                        ln_address: None,
-                        lnurl_withdraw_endpoint: None,
-                        swap_info: Some(swap_info.clone()),
-                        reverse_swap_info: None,
-                        pending_expiration_block: None,
-                        open_channel_bolt11: None,
+                ..Payment::new(
+                    hex::encode(payment_hash_swap.clone()),
+                    PaymentType::Received,
+                    250000,
+                    1_000,
+                    0,
+                    PaymentStatus::Complete,
+                    PaymentDetails::Ln {
+                        data: LnPaymentDetails {
+                            payment_hash: hex::encode(payment_hash_swap),
+                            label: "".to_string(),
+                            destination_pubkey: "321".to_string(),
+                            payment_preimage: "5555".to_string(),
+                            keysend: false,
+                            bolt11: "312".to_string(),
+                            lnurl_success_action: None,
+                            lnurl_pay_domain: None,
+                            lnurl_pay_comment: None,
+                            lnurl_metadata: None,
+                            ln_address: None,
+                            lnurl_withdraw_endpoint: None,
+                            lnurl_verify_url: None,
+                            swap_info: Some(swap_info.clone()),
+                            reverse_swap_info: None,
+                            pending_expiration_block: None,
+                            open_channel_bolt11: None,
+                        },
                     },
-                },
-                metadata: None,
+                )
             },
             Payment {
-                id: hex::encode(payment_hash_rev_swap.clone()),
-                payment_type: PaymentType::Sent,
-                payment_time: 300000,
-                amount_msat: 50_000_000,
-                fee_msat: 2_000,
-                status: PaymentStatus::Complete,
-                error: None,
                 description: Some("test send onchain".to_string()),
-                details: PaymentDetails::Ln {
-                    data: LnPaymentDetails {
-                        payment_hash: hex::encode(payment_hash_rev_swap),
-                        label: "".to_string(),
-                        destination_pubkey: "321".to_string(),
-                        payment_preimage: hex::encode(preimage_rev_swap),
-                        keysend: false,
-                        bolt11: "312".to_string(),
-                        lnurl_success_action: None,
-                        lnurl_metadata: None,
-                        lnurl_pay_domain: None,
-                        lnurl_pay_comment: None,
-                        ln_address: None,
-                        lnurl_withdraw_endpoint: None,
-                        swap_info: None,
-                        reverse_swap_info: Some(rev_swap_info.clone()),
-                        pending_expiration_block: None,
-                        open_channel_bolt11: None,
+                ..Payment::new(
+                    hex::encode(payment_hash_rev_swap.clone()),
+                    PaymentType::Sent,
+                    300000,
+                    50_000_000,
+                    2_000,
+                    PaymentStatus::Complete,
+                    PaymentDetails::Ln {
+                        data: LnPaymentDetails {
+                            payment_hash: hex::encode(payment_hash_rev_swap),
+                            label: "".to_string(),
+                            destination_pubkey: "321".to_string(),
+                            payment_preimage: hex::encode(preimage_rev_swap),
+                            keysend: false,
+                            bolt11: "312".to_string(),
+                            lnurl_success_action: None,
+                            lnurl_metadata: None,
+                            lnurl_pay_domain: None,
+                            lnurl_pay_comment: None,
+                            ln_address: None,
+                            lnurl_withdraw_endpoint: None,
+                            lnurl_verify_url: None,
+                            swap_info: None,
+                            reverse_swap_info: Some(rev_swap_info.clone()),
+                            pending_expiration_block: None,
+                            open_channel_bolt11: None,
+                        },
                     },
-                },
-                metadata: None,
+                )
             },
         ];
         let node_api = Arc::new(MockNodeAPI::new(dummy_node_state.clone()));
@@ -3037,6 +4612,9 @@ pub(crate) mod tests {
                 lnurl_withdraw_endpoint: None,
                 attempted_amount_msat: None,
                 attempted_error: None,
+                bolt12_offer: None,
+                bolt12_payer_note: None,
+                lnurl_verify_url: None,
             },
         )?;
         persister.insert_payment_external_info(
@@ -3050,6 +4628,9 @@ pub(crate) mod tests {
                 lnurl_withdraw_endpoint: Some(test_lnurl_withdraw_endpoint.to_string()),
                 attempted_amount_msat: None,
                 attempted_error: None,
+                bolt12_offer: None,
+                bolt12_payer_note: None,
+                lnurl_verify_url: None,
             },
         )?;
         persister.insert_swap(swap_info.clone())?;
@@ -3149,18 +4730,20 @@ pub(crate) mod tests {
         let receiver: Arc<dyn Receiver> = Arc::new(PaymentReceiver {
             config,
             node_api,
-            persister,
+            persister: persister.clone(),
             lsp: breez_server.clone(),
+            lsps0_transport: None,
+            lsps_protocols: Default::default(),
         });
-        let ln_invoice = receiver
+        let res = receiver
             .receive_payment(ReceivePaymentRequest {
                 amount_msat: 3_000_000,
                 description: "should populate lsp hints".to_string(),
                 use_description_hash: Some(false),
                 ..Default::default()
             })
-            .await?
-            .ln_invoice;
+            .await?;
+        let ln_invoice = res.ln_invoice;
         assert_eq!(ln_invoice.routing_hints[0].hops.len(), 1);
         let lsp_hop = &ln_invoice.routing_hints[0].hops[0];
         assert_eq!(lsp_hop.src_node_id, breez_server.clone().lsp_pub_key());
@@ -3168,6 +4751,72 @@ pub(crate) mod tests {
             lsp_hop.short_channel_id,
             parse_short_channel_id("1x0x0").unwrap()
         );
+
+        // The invoice is tracked until it is paid
+        let request = persister
+            .get_payment_request(&ln_invoice.payment_hash)?
+            .unwrap();
+        assert_eq!(request.status, PaymentRequestStatus::Open);
+        assert_eq!(request.bolt11, ln_invoice.bolt11);
+        assert_eq!(request.amount_msat, 3_000_000);
+        assert_eq!(request.opening_fee_params, res.opening_fee_params);
+        assert_eq!(request.opening_fee_msat, res.opening_fee_msat);
+        assert_eq!(
+            request.expires_at,
+            (ln_invoice.timestamp + ln_invoice.expiry) as i64
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_receive_with_open_channel_lsps2() -> Result<()> {
+        let config = create_test_config();
+        let persister = Arc::new(create_test_persister(config.clone()));
+        persister.init().unwrap();
+
+        let dummy_node_state = get_dummy_node_state();
+        let breez_server = Arc::new(MockBreezServer {});
+        persister.set_lsp_id(breez_server.lsp_id()).unwrap();
+        persister.set_node_state(&dummy_node_state).unwrap();
+
+        // Simulate an LSP that supports LSPS2
+        let mut node_api = MockNodeAPI::new(dummy_node_state.clone());
+        let mut ofp = get_test_ofp_48h(10, 12);
+        ofp.promise = "promise".to_string();
+        node_api.set_lsps2_responses(ofp, "123x1x0").await;
+        let node_api = Arc::new(node_api);
+
+        let (stop, shutdown) = watch::channel(());
+        let receiver = Arc::new(PaymentReceiver {
+            config,
+            node_api: node_api.clone(),
+            persister,
+            lsp: breez_server.clone(),
+            lsps0_transport: Some(Arc::new(lsps0::Transport::new(node_api))),
+            lsps_protocols: Default::default(),
+        });
+        receiver.start(shutdown);
+        let res = receiver
+            .receive_payment(ReceivePaymentRequest {
+                amount_msat: 3_000_000,
+                description: "should populate jit channel hint".to_string(),
+                use_description_hash: Some(false),
+                ..Default::default()
+            })
+            .await?;
+        let _ = stop.send(());
+
+        assert_eq!(res.opening_fee_params.unwrap().promise, "promise");
+        let ln_invoice = res.ln_invoice;
+        assert_eq!(ln_invoice.amount_msat, Some(3_000_000));
+        assert_eq!(ln_invoice.routing_hints[0].hops.len(), 1);
+        let lsp_hop = &ln_invoice.routing_hints[0].hops[0];
+        assert_eq!(lsp_hop.src_node_id, breez_server.lsp_pub_key());
+        assert_eq!(
+            lsp_hop.short_channel_id,
+            parse_short_channel_id("123x1x0").unwrap()
+        );
+        assert_eq!(lsp_hop.cltv_expiry_delta, 144);
         Ok(())
     }
 
@@ -3233,6 +4882,184 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_create_and_pay_offer() -> Result<(), Box<dyn std::error::Error>> {
+        let breez_services = breez_services().await?;
+        breez_services.sync().await?;
+
+        let offer = breez_services
+            .create_offer(CreateOfferRequest {
+                description: "test offer".into(),
+                amount_msat: Some(1_000),
+            })
+            .await?
+            .offer;
+        assert_eq!(offer.description, "test offer");
+        assert_eq!(
+            offer.min_amount,
+            Some(Amount::Bitcoin { amount_msat: 1_000 })
+        );
+
+        let res = breez_services
+            .pay_offer(PayOfferRequest {
+                offer: offer.offer.clone(),
+                amount_msat: Some(999),
+                payer_note: None,
+                label: None,
+            })
+            .await;
+        assert!(matches!(res, Err(SendPaymentError::InvalidAmount { .. })));
+
+        let payment = breez_services
+            .pay_offer(PayOfferRequest {
+                offer: offer.offer.clone(),
+                amount_msat: None,
+                payer_note: Some("thanks".into()),
+                label: None,
+            })
+            .await?
+            .payment;
+        match payment.details {
+            PaymentDetails::Bolt12 { data } => {
+                assert_eq!(data.offer, offer.offer);
+                assert_eq!(data.payer_note, Some("thanks".into()));
+            }
+            _ => panic!("Expected BOLT12 payment details"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spending_budget() -> Result<(), Box<dyn std::error::Error>> {
+        struct ApproveAll {}
+        impl PaymentApprover for ApproveAll {
+            fn approve_payment(&self, _req: PaymentApprovalRequest) -> bool {
+                true
+            }
+        }
+
+        let budget = SpendingBudget {
+            max_payment_msat: Some(100_000),
+            limits: vec![],
+            approval_threshold_msat: Some(50_000),
+        };
+        let req = SendSpontaneousPaymentRequest {
+            node_id: "02".repeat(33),
+            amount_msat: 40_000,
+            extra_tlvs: None,
+            label: None,
+        };
+
+        // Without an approver, the payments above the threshold are rejected
+        let breez_services = breez_services().await?;
+        breez_services.sync().await?;
+        breez_services.set_spending_budget(budget.clone()).await?;
+        let res = breez_services.send_spontaneous_payment(req.clone()).await;
+        assert!(matches!(res, Err(SendPaymentError::BudgetExceeded { .. })));
+        assert_eq!(breez_services.persister.get_budget_spent_since(0)?, 0);
+
+        let breez_services = breez_services_with_approver(Arc::new(ApproveAll {})).await?;
+        breez_services.sync().await?;
+        breez_services.set_spending_budget(budget.clone()).await?;
+        // The amount checked includes the fee limit of 20 sat
+        let res = breez_services
+            .send_spontaneous_payment(SendSpontaneousPaymentRequest {
+                amount_msat: 90_000,
+                ..req.clone()
+            })
+            .await;
+        assert!(matches!(res, Err(SendPaymentError::BudgetExceeded { .. })));
+        let payment = breez_services
+            .send_spontaneous_payment(req.clone())
+            .await?
+            .payment;
+        assert_eq!(
+            breez_services.persister.get_budget_spent_since(0)?,
+            payment.amount_msat + payment.fee_msat
+        );
+
+        // Adding a limit applies right away
+        let limited_budget = SpendingBudget {
+            limits: vec![SpendingLimit {
+                period: BudgetPeriod::Daily,
+                max_amount_msat: payment.amount_msat + payment.fee_msat + 20_000,
+            }],
+            ..budget
+        };
+        breez_services
+            .set_spending_budget(limited_budget.clone())
+            .await?;
+        assert_eq!(breez_services.spending_budget().await?, limited_budget);
+        let res = breez_services
+            .send_spontaneous_payment(SendSpontaneousPaymentRequest {
+                amount_msat: 1,
+                ..req
+            })
+            .await;
+        assert!(matches!(res, Err(SendPaymentError::BudgetExceeded { .. })));
+
+        // Removing the limits only applies after the delay
+        breez_services
+            .set_spending_budget(SpendingBudget::default())
+            .await?;
+        assert_eq!(breez_services.spending_budget().await?, limited_budget);
+        let pending = breez_services.pending_spending_budget().await?.unwrap();
+        assert_eq!(pending.budget, SpendingBudget::default());
+        assert!(pending.applies_at >= Utc::now().timestamp() + BUDGET_LOOSENING_DELAY_SECONDS - 60);
+
+        // Tightening the budget again discards the pending one
+        breez_services
+            .set_spending_budget(limited_budget.clone())
+            .await?;
+        assert_eq!(breez_services.pending_spending_budget().await?, None);
+
+        breez_services
+            .persister
+            .set_pending_spending_budget(&PendingSpendingBudget {
+                budget: SpendingBudget::default(),
+                applies_at: Utc::now().timestamp() - 1,
+            })?;
+        assert_eq!(
+            breez_services.spending_budget().await?,
+            SpendingBudget::default()
+        );
+        assert_eq!(breez_services.pending_spending_budget().await?, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_lock_fiat_rate() -> Result<()> {
+        let breez_services = breez_services().await?;
+        let rate = breez_services.lock_fiat_rate("usd".into()).await?;
+        assert_eq!(rate.currency, "USD");
+        assert_eq!(rate.value, "20000");
+
+        let fiat_amount = FiatAmount {
+            amount: "10".into(),
+            rate: rate.clone(),
+        };
+        assert_eq!(breez_services.fiat_amount_msat(&fiat_amount)?, 50_000_000);
+        // Only the rates locked by the SDK are accepted, as they were locked
+        let forged_rate = FiatAmount {
+            rate: LockedFiatRate {
+                value: "40000".into(),
+                ..rate.clone()
+            },
+            ..fiat_amount.clone()
+        };
+        assert!(breez_services.fiat_amount_msat(&forged_rate).is_err());
+        let unknown_lock = FiatAmount {
+            rate: LockedFiatRate {
+                id: "00".repeat(16),
+                ..rate
+            },
+            ..fiat_amount
+        };
+        assert!(breez_services.fiat_amount_msat(&unknown_lock).is_err());
+        Ok(())
+    }
+
     /// Build node service for tests
     pub(crate) async fn breez_services() -> Result<Arc<BreezServices>> {
         breez_services_with(None, vec![]).await
@@ -3242,6 +5069,21 @@ pub(crate) mod tests {
     pub(crate) async fn breez_services_with(
         node_api: Option<Arc<dyn NodeAPI>>,
         known_payments: Vec<Payment>,
+    ) -> Result<Arc<BreezServices>> {
+        build_test_services(node_api, known_payments, None).await
+    }
+
+    /// Build node service for tests with the approver of the payments
+    async fn breez_services_with_approver(
+        payment_approver: Arc<dyn PaymentApprover>,
+    ) -> Result<Arc<BreezServices>> {
+        build_test_services(None, vec![], Some(payment_approver)).await
+    }
+
+    async fn build_test_services(
+        node_api: Option<Arc<dyn NodeAPI>>,
+        known_payments: Vec<Payment>,
+        payment_approver: Option<Arc<dyn PaymentApprover>>,
     ) -> Result<Arc<BreezServices>> {
         let node_api =
             node_api.unwrap_or_else(|| Arc::new(MockNodeAPI::new(get_dummy_node_state())));
@@ -3253,6 +5095,9 @@ pub(crate) mod tests {
         persister.set_lsp_id(MockBreezServer {}.lsp_id())?;
 
         let mut builder = BreezServicesBuilder::new(test_config.clone());
+        if let Some(payment_approver) = payment_approver {
+            builder.payment_approver(payment_approver);
+        }
         let breez_services = builder
             .lsp_api(Arc::new(MockBreezServer {}))
             .fiat_api(Arc::new(MockBreezServer {}))
@@ -2,48 +2,69 @@ use super::*;
 // Section: wire functions
 
 #[no_mangle]
-pub extern "C" fn wire_connect(port_: i64, req: *mut wire_ConnectRequest) {
-    wire_connect_impl(port_, req)
+pub extern "C" fn wire_connect(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_ConnectRequest,
+) {
+    wire_connect_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_is_initialized(port_: i64) {
-    wire_is_initialized_impl(port_)
+pub extern "C" fn wire_is_initialized(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_is_initialized_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_sync(port_: i64) {
-    wire_sync_impl(port_)
+pub extern "C" fn wire_sync(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_sync_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_node_credentials(port_: i64) {
-    wire_node_credentials_impl(port_)
+pub extern "C" fn wire_node_credentials(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_node_credentials_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_node_info(port_: i64) {
-    wire_node_info_impl(port_)
+pub extern "C" fn wire_node_info(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_node_info_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_configure_node(port_: i64, req: *mut wire_ConfigureNodeRequest) {
-    wire_configure_node_impl(port_, req)
+pub extern "C" fn wire_configure_node(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_ConfigureNodeRequest,
+) {
+    wire_configure_node_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_disconnect(port_: i64) {
-    wire_disconnect_impl(port_)
+pub extern "C" fn wire_disconnect(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_disconnect_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_sign_message(port_: i64, req: *mut wire_SignMessageRequest) {
-    wire_sign_message_impl(port_, req)
+pub extern "C" fn wire_sign_message(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_SignMessageRequest,
+) {
+    wire_sign_message_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_check_message(port_: i64, req: *mut wire_CheckMessageRequest) {
-    wire_check_message_impl(port_, req)
+pub extern "C" fn wire_check_message(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_CheckMessageRequest,
+) {
+    wire_check_message_impl(port_, wallet_id, req)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_list_wallets(port_: i64) {
+    wire_list_wallets_impl(port_)
 }
 
 #[no_mangle]
@@ -76,59 +97,95 @@ pub extern "C" fn wire_breez_events_stream(port_: i64) {
     wire_breez_events_stream_impl(port_)
 }
 
+#[no_mangle]
+pub extern "C" fn wire_wallet_events_stream(port_: i64) {
+    wire_wallet_events_stream_impl(port_)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_payment_approval_stream(port_: i64) {
+    wire_payment_approval_stream_impl(port_)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_respond_payment_approval(port_: i64, id: u64, approved: bool) {
+    wire_respond_payment_approval_impl(port_, id, approved)
+}
+
 #[no_mangle]
 pub extern "C" fn wire_breez_log_stream(port_: i64) {
     wire_breez_log_stream_impl(port_)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_list_lsps(port_: i64) {
-    wire_list_lsps_impl(port_)
+pub extern "C" fn wire_breez_wallet_log_stream(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_breez_wallet_log_stream_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_connect_lsp(port_: i64, lsp_id: *mut wire_uint_8_list) {
-    wire_connect_lsp_impl(port_, lsp_id)
+pub extern "C" fn wire_list_lsps(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_list_lsps_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_lsp_id(port_: i64) {
-    wire_lsp_id_impl(port_)
+pub extern "C" fn wire_connect_lsp(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    lsp_id: *mut wire_uint_8_list,
+) {
+    wire_connect_lsp_impl(port_, wallet_id, lsp_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_fetch_lsp_info(port_: i64, id: *mut wire_uint_8_list) {
-    wire_fetch_lsp_info_impl(port_, id)
+pub extern "C" fn wire_lsp_id(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_lsp_id_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_lsp_info(port_: i64) {
-    wire_lsp_info_impl(port_)
+pub extern "C" fn wire_fetch_lsp_info(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    id: *mut wire_uint_8_list,
+) {
+    wire_fetch_lsp_info_impl(port_, wallet_id, id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_close_lsp_channels(port_: i64) {
-    wire_close_lsp_channels_impl(port_)
+pub extern "C" fn wire_lsp_info(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_lsp_info_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_register_webhook(port_: i64, webhook_url: *mut wire_uint_8_list) {
-    wire_register_webhook_impl(port_, webhook_url)
+pub extern "C" fn wire_close_lsp_channels(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_close_lsp_channels_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_unregister_webhook(port_: i64, webhook_url: *mut wire_uint_8_list) {
-    wire_unregister_webhook_impl(port_, webhook_url)
+pub extern "C" fn wire_register_webhook(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    webhook_url: *mut wire_uint_8_list,
+) {
+    wire_register_webhook_impl(port_, wallet_id, webhook_url)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_unregister_webhook(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    webhook_url: *mut wire_uint_8_list,
+) {
+    wire_unregister_webhook_impl(port_, wallet_id, webhook_url)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_backup(port_: i64) {
-    wire_backup_impl(port_)
+pub extern "C" fn wire_backup(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_backup_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_backup_status(port_: i64) {
-    wire_backup_status_impl(port_)
+pub extern "C" fn wire_backup_status(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_backup_status_impl(port_, wallet_id)
 }
 
 #[no_mangle]
@@ -142,200 +199,496 @@ pub extern "C" fn wire_parse_input(port_: i64, input: *mut wire_uint_8_list) {
 }
 
 #[no_mangle]
-pub extern "C" fn wire_list_payments(port_: i64, req: *mut wire_ListPaymentsRequest) {
-    wire_list_payments_impl(port_, req)
+pub extern "C" fn wire_list_payments(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_ListPaymentsRequest,
+) {
+    wire_list_payments_impl(port_, wallet_id, req)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_count_payments(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_ListPaymentsRequest,
+) {
+    wire_count_payments_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_payment_by_hash(port_: i64, hash: *mut wire_uint_8_list) {
-    wire_payment_by_hash_impl(port_, hash)
+pub extern "C" fn wire_export_payments(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_ExportPaymentsRequest,
+) {
+    wire_export_payments_impl(port_, wallet_id, req)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_payment_by_hash(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    hash: *mut wire_uint_8_list,
+) {
+    wire_payment_by_hash_impl(port_, wallet_id, hash)
 }
 
 #[no_mangle]
 pub extern "C" fn wire_set_payment_metadata(
     port_: i64,
+    wallet_id: *mut wire_uint_8_list,
     hash: *mut wire_uint_8_list,
     metadata: *mut wire_uint_8_list,
 ) {
-    wire_set_payment_metadata_impl(port_, hash, metadata)
+    wire_set_payment_metadata_impl(port_, wallet_id, hash, metadata)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_set_payment_annotations(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_SetPaymentAnnotationsRequest,
+) {
+    wire_set_payment_annotations_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_send_payment(port_: i64, req: *mut wire_SendPaymentRequest) {
-    wire_send_payment_impl(port_, req)
+pub extern "C" fn wire_list_tags(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_list_tags_impl(port_, wallet_id)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_prepare_send_payment(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_PrepareSendPaymentRequest,
+) {
+    wire_prepare_send_payment_impl(port_, wallet_id, req)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_send_payment(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_SendPaymentRequest,
+) {
+    wire_send_payment_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
 pub extern "C" fn wire_send_spontaneous_payment(
     port_: i64,
+    wallet_id: *mut wire_uint_8_list,
     req: *mut wire_SendSpontaneousPaymentRequest,
 ) {
-    wire_send_spontaneous_payment_impl(port_, req)
+    wire_send_spontaneous_payment_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_receive_payment(port_: i64, req: *mut wire_ReceivePaymentRequest) {
-    wire_receive_payment_impl(port_, req)
+pub extern "C" fn wire_pay_offer(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_PayOfferRequest,
+) {
+    wire_pay_offer_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_lnurl_pay(port_: i64, req: *mut wire_LnUrlPayRequest) {
-    wire_lnurl_pay_impl(port_, req)
+pub extern "C" fn wire_create_offer(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_CreateOfferRequest,
+) {
+    wire_create_offer_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_lnurl_withdraw(port_: i64, req: *mut wire_LnUrlWithdrawRequest) {
-    wire_lnurl_withdraw_impl(port_, req)
+pub extern "C" fn wire_receive_payment(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_ReceivePaymentRequest,
+) {
+    wire_receive_payment_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_lnurl_auth(port_: i64, req_data: *mut wire_LnUrlAuthRequestData) {
-    wire_lnurl_auth_impl(port_, req_data)
+pub extern "C" fn wire_list_payment_requests(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_ListPaymentRequestsRequest,
+) {
+    wire_list_payment_requests_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_report_issue(port_: i64, req: *mut wire_ReportIssueRequest) {
-    wire_report_issue_impl(port_, req)
+pub extern "C" fn wire_cancel_payment_request(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    payment_hash: *mut wire_uint_8_list,
+) {
+    wire_cancel_payment_request_impl(port_, wallet_id, payment_hash)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_fetch_fiat_rates(port_: i64) {
-    wire_fetch_fiat_rates_impl(port_)
+pub extern "C" fn wire_lnurl_pay(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_LnUrlPayRequest,
+) {
+    wire_lnurl_pay_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_list_fiat_currencies(port_: i64) {
-    wire_list_fiat_currencies_impl(port_)
+pub extern "C" fn wire_handle_lnurl_pay_server_request(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    config: *mut wire_LnUrlPayServerConfig,
+    url: *mut wire_uint_8_list,
+) {
+    wire_handle_lnurl_pay_server_request_impl(port_, wallet_id, config, url)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_max_reverse_swap_amount(port_: i64) {
-    wire_max_reverse_swap_amount_impl(port_)
+pub extern "C" fn wire_verify_lnurl_payment(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    payment_hash: *mut wire_uint_8_list,
+) {
+    wire_verify_lnurl_payment_impl(port_, wallet_id, payment_hash)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_send_onchain(port_: i64, req: *mut wire_SendOnchainRequest) {
-    wire_send_onchain_impl(port_, req)
+pub extern "C" fn wire_lnurl_withdraw(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_LnUrlWithdrawRequest,
+) {
+    wire_lnurl_withdraw_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_pay_onchain(port_: i64, req: *mut wire_PayOnchainRequest) {
-    wire_pay_onchain_impl(port_, req)
+pub extern "C" fn wire_lnurl_auth(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req_data: *mut wire_LnUrlAuthRequestData,
+) {
+    wire_lnurl_auth_impl(port_, wallet_id, req_data)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_receive_onchain(port_: i64, req: *mut wire_ReceiveOnchainRequest) {
-    wire_receive_onchain_impl(port_, req)
+pub extern "C" fn wire_report_issue(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_ReportIssueRequest,
+) {
+    wire_report_issue_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_buy_bitcoin(port_: i64, req: *mut wire_BuyBitcoinRequest) {
-    wire_buy_bitcoin_impl(port_, req)
+pub extern "C" fn wire_fetch_fiat_rates(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_fetch_fiat_rates_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_redeem_onchain_funds(port_: i64, req: *mut wire_RedeemOnchainFundsRequest) {
-    wire_redeem_onchain_funds_impl(port_, req)
+pub extern "C" fn wire_lock_fiat_rate(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    currency: *mut wire_uint_8_list,
+) {
+    wire_lock_fiat_rate_impl(port_, wallet_id, currency)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_list_fiat_currencies(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_list_fiat_currencies_impl(port_, wallet_id)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_max_reverse_swap_amount(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_max_reverse_swap_amount_impl(port_, wallet_id)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_send_onchain(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_SendOnchainRequest,
+) {
+    wire_send_onchain_impl(port_, wallet_id, req)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_pay_onchain(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_PayOnchainRequest,
+) {
+    wire_pay_onchain_impl(port_, wallet_id, req)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_receive_onchain(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_ReceiveOnchainRequest,
+) {
+    wire_receive_onchain_impl(port_, wallet_id, req)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_buy_bitcoin(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_BuyBitcoinRequest,
+) {
+    wire_buy_bitcoin_impl(port_, wallet_id, req)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_redeem_onchain_funds(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_RedeemOnchainFundsRequest,
+) {
+    wire_redeem_onchain_funds_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
 pub extern "C" fn wire_prepare_redeem_onchain_funds(
     port_: i64,
+    wallet_id: *mut wire_uint_8_list,
     req: *mut wire_PrepareRedeemOnchainFundsRequest,
 ) {
-    wire_prepare_redeem_onchain_funds_impl(port_, req)
+    wire_prepare_redeem_onchain_funds_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_list_refundables(port_: i64) {
-    wire_list_refundables_impl(port_)
+pub extern "C" fn wire_list_refundables(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_list_refundables_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_prepare_refund(port_: i64, req: *mut wire_PrepareRefundRequest) {
-    wire_prepare_refund_impl(port_, req)
+pub extern "C" fn wire_prepare_refund(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_PrepareRefundRequest,
+) {
+    wire_prepare_refund_impl(port_, wallet_id, req)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_refund(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_RefundRequest,
+) {
+    wire_refund_impl(port_, wallet_id, req)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_request_cooperative_refund(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_RefundRequest,
+) {
+    wire_request_cooperative_refund_impl(port_, wallet_id, req)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_prepare_refund_all(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_PrepareRefundAllRequest,
+) {
+    wire_prepare_refund_all_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_refund(port_: i64, req: *mut wire_RefundRequest) {
-    wire_refund_impl(port_, req)
+pub extern "C" fn wire_refund_all(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_RefundAllRequest,
+) {
+    wire_refund_all_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_rescan_swaps(port_: i64) {
-    wire_rescan_swaps_impl(port_)
+pub extern "C" fn wire_bump_fee(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_BumpFeeRequest,
+) {
+    wire_bump_fee_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_redeem_swap(port_: i64, swap_address: *mut wire_uint_8_list) {
-    wire_redeem_swap_impl(port_, swap_address)
+pub extern "C" fn wire_prepare_accelerate_swap_deposit(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_PrepareAccelerateSwapDepositRequest,
+) {
+    wire_prepare_accelerate_swap_deposit_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_in_progress_swap(port_: i64) {
-    wire_in_progress_swap_impl(port_)
+pub extern "C" fn wire_accelerate_swap_deposit(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_AccelerateSwapDepositRequest,
+) {
+    wire_accelerate_swap_deposit_impl(port_, wallet_id, req)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_in_progress_reverse_swaps(port_: i64) {
-    wire_in_progress_reverse_swaps_impl(port_)
+pub extern "C" fn wire_rescan_swaps(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_rescan_swaps_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_claim_reverse_swap(port_: i64, lockup_address: *mut wire_uint_8_list) {
-    wire_claim_reverse_swap_impl(port_, lockup_address)
+pub extern "C" fn wire_redeem_swap(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    swap_address: *mut wire_uint_8_list,
+) {
+    wire_redeem_swap_impl(port_, wallet_id, swap_address)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_open_channel_fee(port_: i64, req: *mut wire_OpenChannelFeeRequest) {
-    wire_open_channel_fee_impl(port_, req)
+pub extern "C" fn wire_in_progress_swap(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_in_progress_swap_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_fetch_reverse_swap_fees(port_: i64, req: *mut wire_ReverseSwapFeesRequest) {
-    wire_fetch_reverse_swap_fees_impl(port_, req)
+pub extern "C" fn wire_in_progress_reverse_swaps(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_in_progress_reverse_swaps_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_onchain_payment_limits(port_: i64) {
-    wire_onchain_payment_limits_impl(port_)
+pub extern "C" fn wire_claim_reverse_swap(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    lockup_address: *mut wire_uint_8_list,
+) {
+    wire_claim_reverse_swap_impl(port_, wallet_id, lockup_address)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_open_channel_fee(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_OpenChannelFeeRequest,
+) {
+    wire_open_channel_fee_impl(port_, wallet_id, req)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_fetch_reverse_swap_fees(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    req: *mut wire_ReverseSwapFeesRequest,
+) {
+    wire_fetch_reverse_swap_fees_impl(port_, wallet_id, req)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_onchain_payment_limits(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_onchain_payment_limits_impl(port_, wallet_id)
 }
 
 #[no_mangle]
 pub extern "C" fn wire_prepare_onchain_payment(
     port_: i64,
+    wallet_id: *mut wire_uint_8_list,
     req: *mut wire_PrepareOnchainPaymentRequest,
 ) {
-    wire_prepare_onchain_payment_impl(port_, req)
+    wire_prepare_onchain_payment_impl(port_, wallet_id, req)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_in_progress_onchain_payments(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_in_progress_onchain_payments_impl(port_, wallet_id)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_recommended_fees(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_recommended_fees_impl(port_, wallet_id)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_set_sweep_policy(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    policy: *mut wire_SweepPolicy,
+) {
+    wire_set_sweep_policy_impl(port_, wallet_id, policy)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_sweep_policy(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_sweep_policy_impl(port_, wallet_id)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_set_spending_budget(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    budget: *mut wire_SpendingBudget,
+) {
+    wire_set_spending_budget_impl(port_, wallet_id, budget)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_in_progress_onchain_payments(port_: i64) {
-    wire_in_progress_onchain_payments_impl(port_)
+pub extern "C" fn wire_spending_budget(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_spending_budget_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_recommended_fees(port_: i64) {
-    wire_recommended_fees_impl(port_)
+pub extern "C" fn wire_pending_spending_budget(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_pending_spending_budget_impl(port_, wallet_id)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_execute_command(port_: i64, command: *mut wire_uint_8_list) {
-    wire_execute_command_impl(port_, command)
+pub extern "C" fn wire_execute_command(
+    port_: i64,
+    wallet_id: *mut wire_uint_8_list,
+    command: *mut wire_uint_8_list,
+) {
+    wire_execute_command_impl(port_, wallet_id, command)
 }
 
 #[no_mangle]
-pub extern "C" fn wire_generate_diagnostic_data(port_: i64) {
-    wire_generate_diagnostic_data_impl(port_)
+pub extern "C" fn wire_generate_diagnostic_data(port_: i64, wallet_id: *mut wire_uint_8_list) {
+    wire_generate_diagnostic_data_impl(port_, wallet_id)
 }
 
 // Section: allocate functions
 
+#[no_mangle]
+pub extern "C" fn new_StringList_0(len: i32) -> *mut wire_StringList {
+    let wrap = wire_StringList {
+        ptr: support::new_leak_vec_ptr(<*mut wire_uint_8_list>::new_with_null_ptr(), len),
+        len,
+    };
+    support::new_leak_box_ptr(wrap)
+}
+
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_accelerate_swap_deposit_request_0(
+) -> *mut wire_AccelerateSwapDepositRequest {
+    support::new_leak_box_ptr(wire_AccelerateSwapDepositRequest::new_with_null_ptr())
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_autoadd_bool_0(value: bool) -> *mut bool {
     support::new_leak_box_ptr(value)
 }
 
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_bump_fee_request_0() -> *mut wire_BumpFeeRequest {
+    support::new_leak_box_ptr(wire_BumpFeeRequest::new_with_null_ptr())
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_autoadd_buy_bitcoin_request_0() -> *mut wire_BuyBitcoinRequest {
     support::new_leak_box_ptr(wire_BuyBitcoinRequest::new_with_null_ptr())
@@ -356,6 +709,21 @@ pub extern "C" fn new_box_autoadd_connect_request_0() -> *mut wire_ConnectReques
     support::new_leak_box_ptr(wire_ConnectRequest::new_with_null_ptr())
 }
 
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_create_offer_request_0() -> *mut wire_CreateOfferRequest {
+    support::new_leak_box_ptr(wire_CreateOfferRequest::new_with_null_ptr())
+}
+
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_export_payments_request_0() -> *mut wire_ExportPaymentsRequest {
+    support::new_leak_box_ptr(wire_ExportPaymentsRequest::new_with_null_ptr())
+}
+
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_fiat_amount_0() -> *mut wire_FiatAmount {
+    support::new_leak_box_ptr(wire_FiatAmount::new_with_null_ptr())
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_autoadd_greenlight_credentials_0() -> *mut wire_GreenlightCredentials {
     support::new_leak_box_ptr(wire_GreenlightCredentials::new_with_null_ptr())
@@ -371,6 +739,17 @@ pub extern "C" fn new_box_autoadd_i64_0(value: i64) -> *mut i64 {
     support::new_leak_box_ptr(value)
 }
 
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_lightning_sweep_policy_0() -> *mut wire_LightningSweepPolicy {
+    support::new_leak_box_ptr(wire_LightningSweepPolicy::new_with_null_ptr())
+}
+
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_list_payment_requests_request_0(
+) -> *mut wire_ListPaymentRequestsRequest {
+    support::new_leak_box_ptr(wire_ListPaymentRequestsRequest::new_with_null_ptr())
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_autoadd_list_payments_request_0() -> *mut wire_ListPaymentsRequest {
     support::new_leak_box_ptr(wire_ListPaymentsRequest::new_with_null_ptr())
@@ -386,6 +765,11 @@ pub extern "C" fn new_box_autoadd_ln_url_pay_request_0() -> *mut wire_LnUrlPayRe
     support::new_leak_box_ptr(wire_LnUrlPayRequest::new_with_null_ptr())
 }
 
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_ln_url_pay_server_config_0() -> *mut wire_LnUrlPayServerConfig {
+    support::new_leak_box_ptr(wire_LnUrlPayServerConfig::new_with_null_ptr())
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_autoadd_ln_url_withdraw_request_0() -> *mut wire_LnUrlWithdrawRequest {
     support::new_leak_box_ptr(wire_LnUrlWithdrawRequest::new_with_null_ptr())
@@ -396,6 +780,11 @@ pub extern "C" fn new_box_autoadd_node_config_0() -> *mut wire_NodeConfig {
     support::new_leak_box_ptr(wire_NodeConfig::new_with_null_ptr())
 }
 
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_onchain_sweep_policy_0() -> *mut wire_OnchainSweepPolicy {
+    support::new_leak_box_ptr(wire_OnchainSweepPolicy::new_with_null_ptr())
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_autoadd_open_channel_fee_request_0() -> *mut wire_OpenChannelFeeRequest {
     support::new_leak_box_ptr(wire_OpenChannelFeeRequest::new_with_null_ptr())
@@ -406,11 +795,27 @@ pub extern "C" fn new_box_autoadd_opening_fee_params_0() -> *mut wire_OpeningFee
     support::new_leak_box_ptr(wire_OpeningFeeParams::new_with_null_ptr())
 }
 
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_pay_offer_request_0() -> *mut wire_PayOfferRequest {
+    support::new_leak_box_ptr(wire_PayOfferRequest::new_with_null_ptr())
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_autoadd_pay_onchain_request_0() -> *mut wire_PayOnchainRequest {
     support::new_leak_box_ptr(wire_PayOnchainRequest::new_with_null_ptr())
 }
 
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_payment_sort_order_0(value: i32) -> *mut i32 {
+    support::new_leak_box_ptr(value)
+}
+
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_prepare_accelerate_swap_deposit_request_0(
+) -> *mut wire_PrepareAccelerateSwapDepositRequest {
+    support::new_leak_box_ptr(wire_PrepareAccelerateSwapDepositRequest::new_with_null_ptr())
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_autoadd_prepare_onchain_payment_request_0(
 ) -> *mut wire_PrepareOnchainPaymentRequest {
@@ -423,11 +828,23 @@ pub extern "C" fn new_box_autoadd_prepare_redeem_onchain_funds_request_0(
     support::new_leak_box_ptr(wire_PrepareRedeemOnchainFundsRequest::new_with_null_ptr())
 }
 
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_prepare_refund_all_request_0() -> *mut wire_PrepareRefundAllRequest
+{
+    support::new_leak_box_ptr(wire_PrepareRefundAllRequest::new_with_null_ptr())
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_autoadd_prepare_refund_request_0() -> *mut wire_PrepareRefundRequest {
     support::new_leak_box_ptr(wire_PrepareRefundRequest::new_with_null_ptr())
 }
 
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_prepare_send_payment_request_0(
+) -> *mut wire_PrepareSendPaymentRequest {
+    support::new_leak_box_ptr(wire_PrepareSendPaymentRequest::new_with_null_ptr())
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_autoadd_receive_onchain_request_0() -> *mut wire_ReceiveOnchainRequest {
     support::new_leak_box_ptr(wire_ReceiveOnchainRequest::new_with_null_ptr())
@@ -444,6 +861,11 @@ pub extern "C" fn new_box_autoadd_redeem_onchain_funds_request_0(
     support::new_leak_box_ptr(wire_RedeemOnchainFundsRequest::new_with_null_ptr())
 }
 
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_refund_all_request_0() -> *mut wire_RefundAllRequest {
+    support::new_leak_box_ptr(wire_RefundAllRequest::new_with_null_ptr())
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_autoadd_refund_request_0() -> *mut wire_RefundRequest {
     support::new_leak_box_ptr(wire_RefundRequest::new_with_null_ptr())
@@ -482,16 +904,37 @@ pub extern "C" fn new_box_autoadd_send_spontaneous_payment_request_0(
     support::new_leak_box_ptr(wire_SendSpontaneousPaymentRequest::new_with_null_ptr())
 }
 
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_set_payment_annotations_request_0(
+) -> *mut wire_SetPaymentAnnotationsRequest {
+    support::new_leak_box_ptr(wire_SetPaymentAnnotationsRequest::new_with_null_ptr())
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_autoadd_sign_message_request_0() -> *mut wire_SignMessageRequest {
     support::new_leak_box_ptr(wire_SignMessageRequest::new_with_null_ptr())
 }
 
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_spending_budget_0() -> *mut wire_SpendingBudget {
+    support::new_leak_box_ptr(wire_SpendingBudget::new_with_null_ptr())
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_autoadd_static_backup_request_0() -> *mut wire_StaticBackupRequest {
     support::new_leak_box_ptr(wire_StaticBackupRequest::new_with_null_ptr())
 }
 
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_swap_script_version_0(value: i32) -> *mut i32 {
+    support::new_leak_box_ptr(value)
+}
+
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_sweep_policy_0() -> *mut wire_SweepPolicy {
+    support::new_leak_box_ptr(wire_SweepPolicy::new_with_null_ptr())
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_autoadd_u32_0(value: u32) -> *mut u32 {
     support::new_leak_box_ptr(value)
@@ -502,6 +945,33 @@ pub extern "C" fn new_box_autoadd_u64_0(value: u64) -> *mut u64 {
     support::new_leak_box_ptr(value)
 }
 
+#[no_mangle]
+pub extern "C" fn new_int_32_list_0(len: i32) -> *mut wire_int_32_list {
+    let ans = wire_int_32_list {
+        ptr: support::new_leak_vec_ptr(Default::default(), len),
+        len,
+    };
+    support::new_leak_box_ptr(ans)
+}
+
+#[no_mangle]
+pub extern "C" fn new_list_chain_backend_0(len: i32) -> *mut wire_list_chain_backend {
+    let wrap = wire_list_chain_backend {
+        ptr: support::new_leak_vec_ptr(<wire_ChainBackend>::new_with_null_ptr(), len),
+        len,
+    };
+    support::new_leak_box_ptr(wrap)
+}
+
+#[no_mangle]
+pub extern "C" fn new_list_custom_lsp_0(len: i32) -> *mut wire_list_custom_lsp {
+    let wrap = wire_list_custom_lsp {
+        ptr: support::new_leak_vec_ptr(<wire_CustomLsp>::new_with_null_ptr(), len),
+        len,
+    };
+    support::new_leak_box_ptr(wrap)
+}
+
 #[no_mangle]
 pub extern "C" fn new_list_metadata_filter_0(len: i32) -> *mut wire_list_metadata_filter {
     let wrap = wire_list_metadata_filter {
@@ -511,6 +981,26 @@ pub extern "C" fn new_list_metadata_filter_0(len: i32) -> *mut wire_list_metadat
     support::new_leak_box_ptr(wrap)
 }
 
+#[no_mangle]
+pub extern "C" fn new_list_payment_request_status_0(
+    len: i32,
+) -> *mut wire_list_payment_request_status {
+    let wrap = wire_list_payment_request_status {
+        ptr: support::new_leak_vec_ptr(Default::default(), len),
+        len,
+    };
+    support::new_leak_box_ptr(wrap)
+}
+
+#[no_mangle]
+pub extern "C" fn new_list_payment_status_0(len: i32) -> *mut wire_list_payment_status {
+    let wrap = wire_list_payment_status {
+        ptr: support::new_leak_vec_ptr(Default::default(), len),
+        len,
+    };
+    support::new_leak_box_ptr(wrap)
+}
+
 #[no_mangle]
 pub extern "C" fn new_list_payment_type_filter_0(len: i32) -> *mut wire_list_payment_type_filter {
     let wrap = wire_list_payment_type_filter {
@@ -520,6 +1010,15 @@ pub extern "C" fn new_list_payment_type_filter_0(len: i32) -> *mut wire_list_pay
     support::new_leak_box_ptr(wrap)
 }
 
+#[no_mangle]
+pub extern "C" fn new_list_spending_limit_0(len: i32) -> *mut wire_list_spending_limit {
+    let wrap = wire_list_spending_limit {
+        ptr: support::new_leak_vec_ptr(<wire_SpendingLimit>::new_with_null_ptr(), len),
+        len,
+    };
+    support::new_leak_box_ptr(wrap)
+}
+
 #[no_mangle]
 pub extern "C" fn new_list_tlv_entry_0(len: i32) -> *mut wire_list_tlv_entry {
     let wrap = wire_list_tlv_entry {
@@ -548,12 +1047,42 @@ impl Wire2Api<String> for *mut wire_uint_8_list {
         String::from_utf8_lossy(&vec).into_owned()
     }
 }
+impl Wire2Api<Vec<String>> for *mut wire_StringList {
+    fn wire2api(self) -> Vec<String> {
+        let vec = unsafe {
+            let wrap = support::box_from_leak_ptr(self);
+            support::vec_from_leak_ptr(wrap.ptr, wrap.len)
+        };
+        vec.into_iter().map(Wire2Api::wire2api).collect()
+    }
+}
+impl Wire2Api<AccelerateSwapDepositRequest> for wire_AccelerateSwapDepositRequest {
+    fn wire2api(self) -> AccelerateSwapDepositRequest {
+        AccelerateSwapDepositRequest {
+            swap_address: self.swap_address.wire2api(),
+            target_sat_per_vbyte: self.target_sat_per_vbyte.wire2api(),
+            refund_address: self.refund_address.wire2api(),
+        }
+    }
+}
 
+impl Wire2Api<AccelerateSwapDepositRequest> for *mut wire_AccelerateSwapDepositRequest {
+    fn wire2api(self) -> AccelerateSwapDepositRequest {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<AccelerateSwapDepositRequest>::wire2api(*wrap).into()
+    }
+}
 impl Wire2Api<bool> for *mut bool {
     fn wire2api(self) -> bool {
         unsafe { *support::box_from_leak_ptr(self) }
     }
 }
+impl Wire2Api<BumpFeeRequest> for *mut wire_BumpFeeRequest {
+    fn wire2api(self) -> BumpFeeRequest {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<BumpFeeRequest>::wire2api(*wrap).into()
+    }
+}
 impl Wire2Api<BuyBitcoinRequest> for *mut wire_BuyBitcoinRequest {
     fn wire2api(self) -> BuyBitcoinRequest {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
@@ -578,6 +1107,24 @@ impl Wire2Api<ConnectRequest> for *mut wire_ConnectRequest {
         Wire2Api::<ConnectRequest>::wire2api(*wrap).into()
     }
 }
+impl Wire2Api<CreateOfferRequest> for *mut wire_CreateOfferRequest {
+    fn wire2api(self) -> CreateOfferRequest {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<CreateOfferRequest>::wire2api(*wrap).into()
+    }
+}
+impl Wire2Api<ExportPaymentsRequest> for *mut wire_ExportPaymentsRequest {
+    fn wire2api(self) -> ExportPaymentsRequest {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<ExportPaymentsRequest>::wire2api(*wrap).into()
+    }
+}
+impl Wire2Api<FiatAmount> for *mut wire_FiatAmount {
+    fn wire2api(self) -> FiatAmount {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<FiatAmount>::wire2api(*wrap).into()
+    }
+}
 impl Wire2Api<GreenlightCredentials> for *mut wire_GreenlightCredentials {
     fn wire2api(self) -> GreenlightCredentials {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
@@ -595,6 +1142,18 @@ impl Wire2Api<i64> for *mut i64 {
         unsafe { *support::box_from_leak_ptr(self) }
     }
 }
+impl Wire2Api<LightningSweepPolicy> for *mut wire_LightningSweepPolicy {
+    fn wire2api(self) -> LightningSweepPolicy {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<LightningSweepPolicy>::wire2api(*wrap).into()
+    }
+}
+impl Wire2Api<ListPaymentRequestsRequest> for *mut wire_ListPaymentRequestsRequest {
+    fn wire2api(self) -> ListPaymentRequestsRequest {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<ListPaymentRequestsRequest>::wire2api(*wrap).into()
+    }
+}
 impl Wire2Api<ListPaymentsRequest> for *mut wire_ListPaymentsRequest {
     fn wire2api(self) -> ListPaymentsRequest {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
@@ -613,6 +1172,12 @@ impl Wire2Api<LnUrlPayRequest> for *mut wire_LnUrlPayRequest {
         Wire2Api::<LnUrlPayRequest>::wire2api(*wrap).into()
     }
 }
+impl Wire2Api<LnUrlPayServerConfig> for *mut wire_LnUrlPayServerConfig {
+    fn wire2api(self) -> LnUrlPayServerConfig {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<LnUrlPayServerConfig>::wire2api(*wrap).into()
+    }
+}
 impl Wire2Api<LnUrlWithdrawRequest> for *mut wire_LnUrlWithdrawRequest {
     fn wire2api(self) -> LnUrlWithdrawRequest {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
@@ -625,6 +1190,12 @@ impl Wire2Api<NodeConfig> for *mut wire_NodeConfig {
         Wire2Api::<NodeConfig>::wire2api(*wrap).into()
     }
 }
+impl Wire2Api<OnchainSweepPolicy> for *mut wire_OnchainSweepPolicy {
+    fn wire2api(self) -> OnchainSweepPolicy {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<OnchainSweepPolicy>::wire2api(*wrap).into()
+    }
+}
 impl Wire2Api<OpenChannelFeeRequest> for *mut wire_OpenChannelFeeRequest {
     fn wire2api(self) -> OpenChannelFeeRequest {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
@@ -637,12 +1208,32 @@ impl Wire2Api<OpeningFeeParams> for *mut wire_OpeningFeeParams {
         Wire2Api::<OpeningFeeParams>::wire2api(*wrap).into()
     }
 }
+impl Wire2Api<PayOfferRequest> for *mut wire_PayOfferRequest {
+    fn wire2api(self) -> PayOfferRequest {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<PayOfferRequest>::wire2api(*wrap).into()
+    }
+}
 impl Wire2Api<PayOnchainRequest> for *mut wire_PayOnchainRequest {
     fn wire2api(self) -> PayOnchainRequest {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
         Wire2Api::<PayOnchainRequest>::wire2api(*wrap).into()
     }
 }
+impl Wire2Api<PaymentSortOrder> for *mut i32 {
+    fn wire2api(self) -> PaymentSortOrder {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<PaymentSortOrder>::wire2api(*wrap).into()
+    }
+}
+impl Wire2Api<PrepareAccelerateSwapDepositRequest>
+    for *mut wire_PrepareAccelerateSwapDepositRequest
+{
+    fn wire2api(self) -> PrepareAccelerateSwapDepositRequest {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<PrepareAccelerateSwapDepositRequest>::wire2api(*wrap).into()
+    }
+}
 impl Wire2Api<PrepareOnchainPaymentRequest> for *mut wire_PrepareOnchainPaymentRequest {
     fn wire2api(self) -> PrepareOnchainPaymentRequest {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
@@ -655,12 +1246,24 @@ impl Wire2Api<PrepareRedeemOnchainFundsRequest> for *mut wire_PrepareRedeemOncha
         Wire2Api::<PrepareRedeemOnchainFundsRequest>::wire2api(*wrap).into()
     }
 }
+impl Wire2Api<PrepareRefundAllRequest> for *mut wire_PrepareRefundAllRequest {
+    fn wire2api(self) -> PrepareRefundAllRequest {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<PrepareRefundAllRequest>::wire2api(*wrap).into()
+    }
+}
 impl Wire2Api<PrepareRefundRequest> for *mut wire_PrepareRefundRequest {
     fn wire2api(self) -> PrepareRefundRequest {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
         Wire2Api::<PrepareRefundRequest>::wire2api(*wrap).into()
     }
 }
+impl Wire2Api<PrepareSendPaymentRequest> for *mut wire_PrepareSendPaymentRequest {
+    fn wire2api(self) -> PrepareSendPaymentRequest {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<PrepareSendPaymentRequest>::wire2api(*wrap).into()
+    }
+}
 impl Wire2Api<ReceiveOnchainRequest> for *mut wire_ReceiveOnchainRequest {
     fn wire2api(self) -> ReceiveOnchainRequest {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
@@ -679,6 +1282,12 @@ impl Wire2Api<RedeemOnchainFundsRequest> for *mut wire_RedeemOnchainFundsRequest
         Wire2Api::<RedeemOnchainFundsRequest>::wire2api(*wrap).into()
     }
 }
+impl Wire2Api<RefundAllRequest> for *mut wire_RefundAllRequest {
+    fn wire2api(self) -> RefundAllRequest {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<RefundAllRequest>::wire2api(*wrap).into()
+    }
+}
 impl Wire2Api<RefundRequest> for *mut wire_RefundRequest {
     fn wire2api(self) -> RefundRequest {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
@@ -721,18 +1330,42 @@ impl Wire2Api<SendSpontaneousPaymentRequest> for *mut wire_SendSpontaneousPaymen
         Wire2Api::<SendSpontaneousPaymentRequest>::wire2api(*wrap).into()
     }
 }
+impl Wire2Api<SetPaymentAnnotationsRequest> for *mut wire_SetPaymentAnnotationsRequest {
+    fn wire2api(self) -> SetPaymentAnnotationsRequest {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<SetPaymentAnnotationsRequest>::wire2api(*wrap).into()
+    }
+}
 impl Wire2Api<SignMessageRequest> for *mut wire_SignMessageRequest {
     fn wire2api(self) -> SignMessageRequest {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
         Wire2Api::<SignMessageRequest>::wire2api(*wrap).into()
     }
 }
+impl Wire2Api<SpendingBudget> for *mut wire_SpendingBudget {
+    fn wire2api(self) -> SpendingBudget {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<SpendingBudget>::wire2api(*wrap).into()
+    }
+}
 impl Wire2Api<StaticBackupRequest> for *mut wire_StaticBackupRequest {
     fn wire2api(self) -> StaticBackupRequest {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
         Wire2Api::<StaticBackupRequest>::wire2api(*wrap).into()
     }
 }
+impl Wire2Api<SwapScriptVersion> for *mut i32 {
+    fn wire2api(self) -> SwapScriptVersion {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<SwapScriptVersion>::wire2api(*wrap).into()
+    }
+}
+impl Wire2Api<SweepPolicy> for *mut wire_SweepPolicy {
+    fn wire2api(self) -> SweepPolicy {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<SweepPolicy>::wire2api(*wrap).into()
+    }
+}
 impl Wire2Api<u32> for *mut u32 {
     fn wire2api(self) -> u32 {
         unsafe { *support::box_from_leak_ptr(self) }
@@ -744,6 +1377,15 @@ impl Wire2Api<u64> for *mut u64 {
     }
 }
 
+impl Wire2Api<BumpFeeRequest> for wire_BumpFeeRequest {
+    fn wire2api(self) -> BumpFeeRequest {
+        BumpFeeRequest {
+            txid: self.txid.wire2api(),
+            sat_per_vbyte: self.sat_per_vbyte.wire2api(),
+        }
+    }
+}
+
 impl Wire2Api<BuyBitcoinRequest> for wire_BuyBitcoinRequest {
     fn wire2api(self) -> BuyBitcoinRequest {
         BuyBitcoinRequest {
@@ -753,6 +1395,43 @@ impl Wire2Api<BuyBitcoinRequest> for wire_BuyBitcoinRequest {
         }
     }
 }
+impl Wire2Api<ChainBackend> for wire_ChainBackend {
+    fn wire2api(self) -> ChainBackend {
+        match self.tag {
+            0 => unsafe {
+                let ans = support::box_from_leak_ptr(self.kind);
+                let ans = support::box_from_leak_ptr(ans.MempoolSpace);
+                ChainBackend::MempoolSpace {
+                    url: ans.url.wire2api(),
+                }
+            },
+            1 => unsafe {
+                let ans = support::box_from_leak_ptr(self.kind);
+                let ans = support::box_from_leak_ptr(ans.Esplora);
+                ChainBackend::Esplora {
+                    url: ans.url.wire2api(),
+                }
+            },
+            2 => unsafe {
+                let ans = support::box_from_leak_ptr(self.kind);
+                let ans = support::box_from_leak_ptr(ans.Electrum);
+                ChainBackend::Electrum {
+                    url: ans.url.wire2api(),
+                }
+            },
+            3 => unsafe {
+                let ans = support::box_from_leak_ptr(self.kind);
+                let ans = support::box_from_leak_ptr(ans.Bitcoind);
+                ChainBackend::Bitcoind {
+                    url: ans.url.wire2api(),
+                    rpc_user: ans.rpc_user.wire2api(),
+                    rpc_password: ans.rpc_password.wire2api(),
+                }
+            },
+            _ => unreachable!(),
+        }
+    }
+}
 impl Wire2Api<CheckMessageRequest> for wire_CheckMessageRequest {
     fn wire2api(self) -> CheckMessageRequest {
         CheckMessageRequest {
@@ -768,6 +1447,7 @@ impl Wire2Api<Config> for wire_Config {
             breezserver: self.breezserver.wire2api(),
             chainnotifier_url: self.chainnotifier_url.wire2api(),
             mempoolspace_url: self.mempoolspace_url.wire2api(),
+            chain_backends: self.chain_backends.wire2api(),
             working_dir: self.working_dir.wire2api(),
             network: self.network.wire2api(),
             payment_timeout_sec: self.payment_timeout_sec.wire2api(),
@@ -776,6 +1456,7 @@ impl Wire2Api<Config> for wire_Config {
             maxfee_percent: self.maxfee_percent.wire2api(),
             exemptfee_msat: self.exemptfee_msat.wire2api(),
             node_config: self.node_config.wire2api(),
+            custom_lsps: self.custom_lsps.wire2api(),
         }
     }
 }
@@ -795,7 +1476,46 @@ impl Wire2Api<ConnectRequest> for wire_ConnectRequest {
         }
     }
 }
+impl Wire2Api<CreateOfferRequest> for wire_CreateOfferRequest {
+    fn wire2api(self) -> CreateOfferRequest {
+        CreateOfferRequest {
+            description: self.description.wire2api(),
+            amount_msat: self.amount_msat.wire2api(),
+        }
+    }
+}
+impl Wire2Api<CustomLsp> for wire_CustomLsp {
+    fn wire2api(self) -> CustomLsp {
+        CustomLsp {
+            id: self.id.wire2api(),
+            name: self.name.wire2api(),
+            pubkey: self.pubkey.wire2api(),
+            host: self.host.wire2api(),
+            protocols: self.protocols.wire2api(),
+            token: self.token.wire2api(),
+        }
+    }
+}
 
+impl Wire2Api<ExportPaymentsRequest> for wire_ExportPaymentsRequest {
+    fn wire2api(self) -> ExportPaymentsRequest {
+        ExportPaymentsRequest {
+            format: self.format.wire2api(),
+            list_payments_request: self.list_payments_request.wire2api(),
+            fiat_currency: self.fiat_currency.wire2api(),
+            use_coingecko_rates: self.use_coingecko_rates.wire2api(),
+        }
+    }
+}
+
+impl Wire2Api<FiatAmount> for wire_FiatAmount {
+    fn wire2api(self) -> FiatAmount {
+        FiatAmount {
+            amount: self.amount.wire2api(),
+            rate: self.rate.wire2api(),
+        }
+    }
+}
 impl Wire2Api<GreenlightCredentials> for wire_GreenlightCredentials {
     fn wire2api(self) -> GreenlightCredentials {
         GreenlightCredentials {
@@ -813,6 +1533,41 @@ impl Wire2Api<GreenlightNodeConfig> for wire_GreenlightNodeConfig {
     }
 }
 
+impl Wire2Api<Vec<i32>> for *mut wire_int_32_list {
+    fn wire2api(self) -> Vec<i32> {
+        unsafe {
+            let wrap = support::box_from_leak_ptr(self);
+            support::vec_from_leak_ptr(wrap.ptr, wrap.len)
+        }
+    }
+}
+impl Wire2Api<LightningSweepPolicy> for wire_LightningSweepPolicy {
+    fn wire2api(self) -> LightningSweepPolicy {
+        LightningSweepPolicy {
+            to_address: self.to_address.wire2api(),
+            max_channels_balance_sat: self.max_channels_balance_sat.wire2api(),
+            max_sat_per_vbyte: self.max_sat_per_vbyte.wire2api(),
+        }
+    }
+}
+impl Wire2Api<Vec<ChainBackend>> for *mut wire_list_chain_backend {
+    fn wire2api(self) -> Vec<ChainBackend> {
+        let vec = unsafe {
+            let wrap = support::box_from_leak_ptr(self);
+            support::vec_from_leak_ptr(wrap.ptr, wrap.len)
+        };
+        vec.into_iter().map(Wire2Api::wire2api).collect()
+    }
+}
+impl Wire2Api<Vec<CustomLsp>> for *mut wire_list_custom_lsp {
+    fn wire2api(self) -> Vec<CustomLsp> {
+        let vec = unsafe {
+            let wrap = support::box_from_leak_ptr(self);
+            support::vec_from_leak_ptr(wrap.ptr, wrap.len)
+        };
+        vec.into_iter().map(Wire2Api::wire2api).collect()
+    }
+}
 impl Wire2Api<Vec<MetadataFilter>> for *mut wire_list_metadata_filter {
     fn wire2api(self) -> Vec<MetadataFilter> {
         let vec = unsafe {
@@ -822,6 +1577,35 @@ impl Wire2Api<Vec<MetadataFilter>> for *mut wire_list_metadata_filter {
         vec.into_iter().map(Wire2Api::wire2api).collect()
     }
 }
+impl Wire2Api<Vec<PaymentRequestStatus>> for *mut wire_list_payment_request_status {
+    fn wire2api(self) -> Vec<PaymentRequestStatus> {
+        let vec = unsafe {
+            let wrap = support::box_from_leak_ptr(self);
+            support::vec_from_leak_ptr(wrap.ptr, wrap.len)
+        };
+        vec.into_iter().map(Wire2Api::wire2api).collect()
+    }
+}
+impl Wire2Api<ListPaymentRequestsRequest> for wire_ListPaymentRequestsRequest {
+    fn wire2api(self) -> ListPaymentRequestsRequest {
+        ListPaymentRequestsRequest {
+            statuses: self.statuses.wire2api(),
+            from_timestamp: self.from_timestamp.wire2api(),
+            to_timestamp: self.to_timestamp.wire2api(),
+            offset: self.offset.wire2api(),
+            limit: self.limit.wire2api(),
+        }
+    }
+}
+impl Wire2Api<Vec<PaymentStatus>> for *mut wire_list_payment_status {
+    fn wire2api(self) -> Vec<PaymentStatus> {
+        let vec = unsafe {
+            let wrap = support::box_from_leak_ptr(self);
+            support::vec_from_leak_ptr(wrap.ptr, wrap.len)
+        };
+        vec.into_iter().map(Wire2Api::wire2api).collect()
+    }
+}
 impl Wire2Api<Vec<PaymentTypeFilter>> for *mut wire_list_payment_type_filter {
     fn wire2api(self) -> Vec<PaymentTypeFilter> {
         let vec = unsafe {
@@ -839,11 +1623,28 @@ impl Wire2Api<ListPaymentsRequest> for wire_ListPaymentsRequest {
             from_timestamp: self.from_timestamp.wire2api(),
             to_timestamp: self.to_timestamp.wire2api(),
             include_failures: self.include_failures.wire2api(),
+            statuses: self.statuses.wire2api(),
+            min_amount_msat: self.min_amount_msat.wire2api(),
+            max_amount_msat: self.max_amount_msat.wire2api(),
+            counterparty_node_id: self.counterparty_node_id.wire2api(),
+            tags: self.tags.wire2api(),
+            category: self.category.wire2api(),
+            search: self.search.wire2api(),
+            sort_order: self.sort_order.wire2api(),
             offset: self.offset.wire2api(),
             limit: self.limit.wire2api(),
         }
     }
 }
+impl Wire2Api<Vec<SpendingLimit>> for *mut wire_list_spending_limit {
+    fn wire2api(self) -> Vec<SpendingLimit> {
+        let vec = unsafe {
+            let wrap = support::box_from_leak_ptr(self);
+            support::vec_from_leak_ptr(wrap.ptr, wrap.len)
+        };
+        vec.into_iter().map(Wire2Api::wire2api).collect()
+    }
+}
 impl Wire2Api<Vec<TlvEntry>> for *mut wire_list_tlv_entry {
     fn wire2api(self) -> Vec<TlvEntry> {
         let vec = unsafe {
@@ -889,6 +1690,18 @@ impl Wire2Api<LnUrlPayRequestData> for wire_LnUrlPayRequestData {
         }
     }
 }
+impl Wire2Api<LnUrlPayServerConfig> for wire_LnUrlPayServerConfig {
+    fn wire2api(self) -> LnUrlPayServerConfig {
+        LnUrlPayServerConfig {
+            callback: self.callback.wire2api(),
+            min_sendable: self.min_sendable.wire2api(),
+            max_sendable: self.max_sendable.wire2api(),
+            description: self.description.wire2api(),
+            comment_allowed: self.comment_allowed.wire2api(),
+            ln_address: self.ln_address.wire2api(),
+        }
+    }
+}
 impl Wire2Api<LnUrlWithdrawRequest> for wire_LnUrlWithdrawRequest {
     fn wire2api(self) -> LnUrlWithdrawRequest {
         LnUrlWithdrawRequest {
@@ -906,6 +1719,18 @@ impl Wire2Api<LnUrlWithdrawRequestData> for wire_LnUrlWithdrawRequestData {
             default_description: self.default_description.wire2api(),
             min_withdrawable: self.min_withdrawable.wire2api(),
             max_withdrawable: self.max_withdrawable.wire2api(),
+            balance_check: self.balance_check.wire2api(),
+            pay_link: self.pay_link.wire2api(),
+        }
+    }
+}
+impl Wire2Api<LockedFiatRate> for wire_LockedFiatRate {
+    fn wire2api(self) -> LockedFiatRate {
+        LockedFiatRate {
+            id: self.id.wire2api(),
+            currency: self.currency.wire2api(),
+            value: self.value.wire2api(),
+            locked_at: self.locked_at.wire2api(),
         }
     }
 }
@@ -928,10 +1753,29 @@ impl Wire2Api<NodeConfig> for wire_NodeConfig {
                     config: ans.config.wire2api(),
                 }
             },
+            1 => unsafe {
+                let ans = support::box_from_leak_ptr(self.kind);
+                let ans = support::box_from_leak_ptr(ans.Cln);
+                NodeConfig::Cln {
+                    grpc_url: ans.grpc_url.wire2api(),
+                    ca_cert: ans.ca_cert.wire2api(),
+                    client_cert: ans.client_cert.wire2api(),
+                    client_key: ans.client_key.wire2api(),
+                }
+            },
             _ => unreachable!(),
         }
     }
 }
+impl Wire2Api<OnchainSweepPolicy> for wire_OnchainSweepPolicy {
+    fn wire2api(self) -> OnchainSweepPolicy {
+        OnchainSweepPolicy {
+            to_address: self.to_address.wire2api(),
+            max_onchain_balance_sat: self.max_onchain_balance_sat.wire2api(),
+            max_sat_per_vbyte: self.max_sat_per_vbyte.wire2api(),
+        }
+    }
+}
 impl Wire2Api<OpenChannelFeeRequest> for wire_OpenChannelFeeRequest {
     fn wire2api(self) -> OpenChannelFeeRequest {
         OpenChannelFeeRequest {
@@ -952,7 +1796,17 @@ impl Wire2Api<OpeningFeeParams> for wire_OpeningFeeParams {
         }
     }
 }
-
+
+impl Wire2Api<PayOfferRequest> for wire_PayOfferRequest {
+    fn wire2api(self) -> PayOfferRequest {
+        PayOfferRequest {
+            offer: self.offer.wire2api(),
+            amount_msat: self.amount_msat.wire2api(),
+            payer_note: self.payer_note.wire2api(),
+            label: self.label.wire2api(),
+        }
+    }
+}
 impl Wire2Api<PayOnchainRequest> for wire_PayOnchainRequest {
     fn wire2api(self) -> PayOnchainRequest {
         PayOnchainRequest {
@@ -962,6 +1816,15 @@ impl Wire2Api<PayOnchainRequest> for wire_PayOnchainRequest {
     }
 }
 
+impl Wire2Api<PrepareAccelerateSwapDepositRequest> for wire_PrepareAccelerateSwapDepositRequest {
+    fn wire2api(self) -> PrepareAccelerateSwapDepositRequest {
+        PrepareAccelerateSwapDepositRequest {
+            swap_address: self.swap_address.wire2api(),
+            target_sat_per_vbyte: self.target_sat_per_vbyte.wire2api(),
+            refund_address: self.refund_address.wire2api(),
+        }
+    }
+}
 impl Wire2Api<PrepareOnchainPaymentRequest> for wire_PrepareOnchainPaymentRequest {
     fn wire2api(self) -> PrepareOnchainPaymentRequest {
         PrepareOnchainPaymentRequest {
@@ -992,6 +1855,15 @@ impl Wire2Api<PrepareRedeemOnchainFundsRequest> for wire_PrepareRedeemOnchainFun
         }
     }
 }
+impl Wire2Api<PrepareRefundAllRequest> for wire_PrepareRefundAllRequest {
+    fn wire2api(self) -> PrepareRefundAllRequest {
+        PrepareRefundAllRequest {
+            to_address: self.to_address.wire2api(),
+            sat_per_vbyte: self.sat_per_vbyte.wire2api(),
+            swap_addresses: self.swap_addresses.wire2api(),
+        }
+    }
+}
 impl Wire2Api<PrepareRefundRequest> for wire_PrepareRefundRequest {
     fn wire2api(self) -> PrepareRefundRequest {
         PrepareRefundRequest {
@@ -1001,10 +1873,19 @@ impl Wire2Api<PrepareRefundRequest> for wire_PrepareRefundRequest {
         }
     }
 }
+impl Wire2Api<PrepareSendPaymentRequest> for wire_PrepareSendPaymentRequest {
+    fn wire2api(self) -> PrepareSendPaymentRequest {
+        PrepareSendPaymentRequest {
+            bolt11: self.bolt11.wire2api(),
+            amount_msat: self.amount_msat.wire2api(),
+        }
+    }
+}
 impl Wire2Api<ReceiveOnchainRequest> for wire_ReceiveOnchainRequest {
     fn wire2api(self) -> ReceiveOnchainRequest {
         ReceiveOnchainRequest {
             opening_fee_params: self.opening_fee_params.wire2api(),
+            script_version: self.script_version.wire2api(),
         }
     }
 }
@@ -1018,6 +1899,7 @@ impl Wire2Api<ReceivePaymentRequest> for wire_ReceivePaymentRequest {
             use_description_hash: self.use_description_hash.wire2api(),
             expiry: self.expiry.wire2api(),
             cltv: self.cltv.wire2api(),
+            fiat_amount: self.fiat_amount.wire2api(),
         }
     }
 }
@@ -1029,6 +1911,15 @@ impl Wire2Api<RedeemOnchainFundsRequest> for wire_RedeemOnchainFundsRequest {
         }
     }
 }
+impl Wire2Api<RefundAllRequest> for wire_RefundAllRequest {
+    fn wire2api(self) -> RefundAllRequest {
+        RefundAllRequest {
+            to_address: self.to_address.wire2api(),
+            sat_per_vbyte: self.sat_per_vbyte.wire2api(),
+            swap_addresses: self.swap_addresses.wire2api(),
+        }
+    }
+}
 impl Wire2Api<RefundRequest> for wire_RefundRequest {
     fn wire2api(self) -> RefundRequest {
         RefundRequest {
@@ -1084,6 +1975,8 @@ impl Wire2Api<SendPaymentRequest> for wire_SendPaymentRequest {
             bolt11: self.bolt11.wire2api(),
             amount_msat: self.amount_msat.wire2api(),
             label: self.label.wire2api(),
+            max_fee_msat: self.max_fee_msat.wire2api(),
+            fiat_amount: self.fiat_amount.wire2api(),
         }
     }
 }
@@ -1097,6 +1990,16 @@ impl Wire2Api<SendSpontaneousPaymentRequest> for wire_SendSpontaneousPaymentRequ
         }
     }
 }
+impl Wire2Api<SetPaymentAnnotationsRequest> for wire_SetPaymentAnnotationsRequest {
+    fn wire2api(self) -> SetPaymentAnnotationsRequest {
+        SetPaymentAnnotationsRequest {
+            payment_hash: self.payment_hash.wire2api(),
+            note: self.note.wire2api(),
+            tags: self.tags.wire2api(),
+            category: self.category.wire2api(),
+        }
+    }
+}
 impl Wire2Api<SignMessageRequest> for wire_SignMessageRequest {
     fn wire2api(self) -> SignMessageRequest {
         SignMessageRequest {
@@ -1104,6 +2007,23 @@ impl Wire2Api<SignMessageRequest> for wire_SignMessageRequest {
         }
     }
 }
+impl Wire2Api<SpendingBudget> for wire_SpendingBudget {
+    fn wire2api(self) -> SpendingBudget {
+        SpendingBudget {
+            max_payment_msat: self.max_payment_msat.wire2api(),
+            limits: self.limits.wire2api(),
+            approval_threshold_msat: self.approval_threshold_msat.wire2api(),
+        }
+    }
+}
+impl Wire2Api<SpendingLimit> for wire_SpendingLimit {
+    fn wire2api(self) -> SpendingLimit {
+        SpendingLimit {
+            period: self.period.wire2api(),
+            max_amount_msat: self.max_amount_msat.wire2api(),
+        }
+    }
+}
 impl Wire2Api<StaticBackupRequest> for wire_StaticBackupRequest {
     fn wire2api(self) -> StaticBackupRequest {
         StaticBackupRequest {
@@ -1112,6 +2032,14 @@ impl Wire2Api<StaticBackupRequest> for wire_StaticBackupRequest {
     }
 }
 
+impl Wire2Api<SweepPolicy> for wire_SweepPolicy {
+    fn wire2api(self) -> SweepPolicy {
+        SweepPolicy {
+            onchain: self.onchain.wire2api(),
+            lightning: self.lightning.wire2api(),
+        }
+    }
+}
 impl Wire2Api<TlvEntry> for wire_TlvEntry {
     fn wire2api(self) -> TlvEntry {
         TlvEntry {
@@ -1131,6 +2059,28 @@ impl Wire2Api<Vec<u8>> for *mut wire_uint_8_list {
 }
 // Section: wire structs
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_StringList {
+    ptr: *mut *mut wire_uint_8_list,
+    len: i32,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_AccelerateSwapDepositRequest {
+    swap_address: *mut wire_uint_8_list,
+    target_sat_per_vbyte: u32,
+    refund_address: *mut wire_uint_8_list,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_BumpFeeRequest {
+    txid: *mut wire_uint_8_list,
+    sat_per_vbyte: u32,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_BuyBitcoinRequest {
@@ -1153,6 +2103,7 @@ pub struct wire_Config {
     breezserver: *mut wire_uint_8_list,
     chainnotifier_url: *mut wire_uint_8_list,
     mempoolspace_url: *mut wire_uint_8_list,
+    chain_backends: *mut wire_list_chain_backend,
     working_dir: *mut wire_uint_8_list,
     network: i32,
     payment_timeout_sec: u32,
@@ -1161,6 +2112,7 @@ pub struct wire_Config {
     maxfee_percent: f64,
     exemptfee_msat: u64,
     node_config: wire_NodeConfig,
+    custom_lsps: *mut wire_list_custom_lsp,
 }
 
 #[repr(C)]
@@ -1177,6 +2129,40 @@ pub struct wire_ConnectRequest {
     restore_only: *mut bool,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_CreateOfferRequest {
+    description: *mut wire_uint_8_list,
+    amount_msat: *mut u64,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_CustomLsp {
+    id: *mut wire_uint_8_list,
+    name: *mut wire_uint_8_list,
+    pubkey: *mut wire_uint_8_list,
+    host: *mut wire_uint_8_list,
+    protocols: *mut wire_int_32_list,
+    token: *mut wire_uint_8_list,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_ExportPaymentsRequest {
+    format: i32,
+    list_payments_request: wire_ListPaymentsRequest,
+    fiat_currency: *mut wire_uint_8_list,
+    use_coingecko_rates: bool,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_FiatAmount {
+    amount: *mut wire_uint_8_list,
+    rate: wire_LockedFiatRate,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_GreenlightCredentials {
@@ -1191,6 +2177,35 @@ pub struct wire_GreenlightNodeConfig {
     invite_code: *mut wire_uint_8_list,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_int_32_list {
+    ptr: *mut i32,
+    len: i32,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_LightningSweepPolicy {
+    to_address: *mut wire_uint_8_list,
+    max_channels_balance_sat: u64,
+    max_sat_per_vbyte: u32,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_list_chain_backend {
+    ptr: *mut wire_ChainBackend,
+    len: i32,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_list_custom_lsp {
+    ptr: *mut wire_CustomLsp,
+    len: i32,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_list_metadata_filter {
@@ -1198,6 +2213,30 @@ pub struct wire_list_metadata_filter {
     len: i32,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_list_payment_request_status {
+    ptr: *mut i32,
+    len: i32,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_ListPaymentRequestsRequest {
+    statuses: *mut wire_list_payment_request_status,
+    from_timestamp: *mut i64,
+    to_timestamp: *mut i64,
+    offset: *mut u32,
+    limit: *mut u32,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_list_payment_status {
+    ptr: *mut i32,
+    len: i32,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_list_payment_type_filter {
@@ -1213,10 +2252,25 @@ pub struct wire_ListPaymentsRequest {
     from_timestamp: *mut i64,
     to_timestamp: *mut i64,
     include_failures: *mut bool,
+    statuses: *mut wire_list_payment_status,
+    min_amount_msat: *mut u64,
+    max_amount_msat: *mut u64,
+    counterparty_node_id: *mut wire_uint_8_list,
+    tags: *mut wire_StringList,
+    category: *mut wire_uint_8_list,
+    search: *mut wire_uint_8_list,
+    sort_order: *mut i32,
     offset: *mut u32,
     limit: *mut u32,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_list_spending_limit {
+    ptr: *mut wire_SpendingLimit,
+    len: i32,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_list_tlv_entry {
@@ -1257,6 +2311,17 @@ pub struct wire_LnUrlPayRequestData {
     ln_address: *mut wire_uint_8_list,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_LnUrlPayServerConfig {
+    callback: *mut wire_uint_8_list,
+    min_sendable: u64,
+    max_sendable: u64,
+    description: *mut wire_uint_8_list,
+    comment_allowed: u16,
+    ln_address: *mut wire_uint_8_list,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_LnUrlWithdrawRequest {
@@ -1273,6 +2338,17 @@ pub struct wire_LnUrlWithdrawRequestData {
     default_description: *mut wire_uint_8_list,
     min_withdrawable: u64,
     max_withdrawable: u64,
+    balance_check: *mut wire_uint_8_list,
+    pay_link: *mut wire_uint_8_list,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_LockedFiatRate {
+    id: *mut wire_uint_8_list,
+    currency: *mut wire_uint_8_list,
+    value: *mut wire_uint_8_list,
+    locked_at: i64,
 }
 
 #[repr(C)]
@@ -1282,6 +2358,14 @@ pub struct wire_MetadataFilter {
     json_value: *mut wire_uint_8_list,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_OnchainSweepPolicy {
+    to_address: *mut wire_uint_8_list,
+    max_onchain_balance_sat: u64,
+    max_sat_per_vbyte: u32,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_OpenChannelFeeRequest {
@@ -1300,6 +2384,15 @@ pub struct wire_OpeningFeeParams {
     promise: *mut wire_uint_8_list,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_PayOfferRequest {
+    offer: *mut wire_uint_8_list,
+    amount_msat: *mut u64,
+    payer_note: *mut wire_uint_8_list,
+    label: *mut wire_uint_8_list,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_PayOnchainRequest {
@@ -1307,6 +2400,14 @@ pub struct wire_PayOnchainRequest {
     prepare_res: wire_PrepareOnchainPaymentResponse,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_PrepareAccelerateSwapDepositRequest {
+    swap_address: *mut wire_uint_8_list,
+    target_sat_per_vbyte: u32,
+    refund_address: *mut wire_uint_8_list,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_PrepareOnchainPaymentRequest {
@@ -1334,6 +2435,14 @@ pub struct wire_PrepareRedeemOnchainFundsRequest {
     sat_per_vbyte: u32,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_PrepareRefundAllRequest {
+    to_address: *mut wire_uint_8_list,
+    sat_per_vbyte: u32,
+    swap_addresses: *mut wire_StringList,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_PrepareRefundRequest {
@@ -1342,10 +2451,18 @@ pub struct wire_PrepareRefundRequest {
     sat_per_vbyte: u32,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_PrepareSendPaymentRequest {
+    bolt11: *mut wire_uint_8_list,
+    amount_msat: *mut u64,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_ReceiveOnchainRequest {
     opening_fee_params: *mut wire_OpeningFeeParams,
+    script_version: *mut i32,
 }
 
 #[repr(C)]
@@ -1358,6 +2475,7 @@ pub struct wire_ReceivePaymentRequest {
     use_description_hash: *mut bool,
     expiry: *mut u32,
     cltv: *mut u32,
+    fiat_amount: *mut wire_FiatAmount,
 }
 
 #[repr(C)]
@@ -1367,6 +2485,14 @@ pub struct wire_RedeemOnchainFundsRequest {
     sat_per_vbyte: u32,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_RefundAllRequest {
+    to_address: *mut wire_uint_8_list,
+    sat_per_vbyte: u32,
+    swap_addresses: *mut wire_StringList,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_RefundRequest {
@@ -1404,6 +2530,8 @@ pub struct wire_SendPaymentRequest {
     bolt11: *mut wire_uint_8_list,
     amount_msat: *mut u64,
     label: *mut wire_uint_8_list,
+    max_fee_msat: *mut u64,
+    fiat_amount: *mut wire_FiatAmount,
 }
 
 #[repr(C)]
@@ -1415,18 +2543,49 @@ pub struct wire_SendSpontaneousPaymentRequest {
     label: *mut wire_uint_8_list,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_SetPaymentAnnotationsRequest {
+    payment_hash: *mut wire_uint_8_list,
+    note: *mut wire_uint_8_list,
+    tags: *mut wire_StringList,
+    category: *mut wire_uint_8_list,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_SignMessageRequest {
     message: *mut wire_uint_8_list,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_SpendingBudget {
+    max_payment_msat: *mut u64,
+    limits: *mut wire_list_spending_limit,
+    approval_threshold_msat: *mut u64,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_SpendingLimit {
+    period: i32,
+    max_amount_msat: u64,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_StaticBackupRequest {
     working_dir: *mut wire_uint_8_list,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_SweepPolicy {
+    onchain: *mut wire_OnchainSweepPolicy,
+    lightning: *mut wire_LightningSweepPolicy,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_TlvEntry {
@@ -1441,6 +2600,47 @@ pub struct wire_uint_8_list {
     len: i32,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_ChainBackend {
+    tag: i32,
+    kind: *mut ChainBackendKind,
+}
+
+#[repr(C)]
+pub union ChainBackendKind {
+    MempoolSpace: *mut wire_ChainBackend_MempoolSpace,
+    Esplora: *mut wire_ChainBackend_Esplora,
+    Electrum: *mut wire_ChainBackend_Electrum,
+    Bitcoind: *mut wire_ChainBackend_Bitcoind,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_ChainBackend_MempoolSpace {
+    url: *mut wire_uint_8_list,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_ChainBackend_Esplora {
+    url: *mut wire_uint_8_list,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_ChainBackend_Electrum {
+    url: *mut wire_uint_8_list,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_ChainBackend_Bitcoind {
+    url: *mut wire_uint_8_list,
+    rpc_user: *mut wire_uint_8_list,
+    rpc_password: *mut wire_uint_8_list,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_NodeConfig {
@@ -1451,6 +2651,7 @@ pub struct wire_NodeConfig {
 #[repr(C)]
 pub union NodeConfigKind {
     Greenlight: *mut wire_NodeConfig_Greenlight,
+    Cln: *mut wire_NodeConfig_Cln,
 }
 
 #[repr(C)]
@@ -1459,6 +2660,15 @@ pub struct wire_NodeConfig_Greenlight {
     config: *mut wire_GreenlightNodeConfig,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_NodeConfig_Cln {
+    grpc_url: *mut wire_uint_8_list,
+    ca_cert: *mut wire_uint_8_list,
+    client_cert: *mut wire_uint_8_list,
+    client_key: *mut wire_uint_8_list,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_ReportIssueRequest {
@@ -1489,6 +2699,37 @@ impl<T> NewWithNullPtr for *mut T {
     }
 }
 
+impl NewWithNullPtr for wire_AccelerateSwapDepositRequest {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            swap_address: core::ptr::null_mut(),
+            target_sat_per_vbyte: Default::default(),
+            refund_address: core::ptr::null_mut(),
+        }
+    }
+}
+
+impl Default for wire_AccelerateSwapDepositRequest {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
+impl NewWithNullPtr for wire_BumpFeeRequest {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            txid: core::ptr::null_mut(),
+            sat_per_vbyte: Default::default(),
+        }
+    }
+}
+
+impl Default for wire_BumpFeeRequest {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
 impl NewWithNullPtr for wire_BuyBitcoinRequest {
     fn new_with_null_ptr() -> Self {
         Self {
@@ -1499,10 +2740,63 @@ impl NewWithNullPtr for wire_BuyBitcoinRequest {
     }
 }
 
-impl Default for wire_BuyBitcoinRequest {
-    fn default() -> Self {
-        Self::new_with_null_ptr()
-    }
+impl Default for wire_BuyBitcoinRequest {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
+impl Default for wire_ChainBackend {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
+impl NewWithNullPtr for wire_ChainBackend {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            tag: -1,
+            kind: core::ptr::null_mut(),
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn inflate_ChainBackend_MempoolSpace() -> *mut ChainBackendKind {
+    support::new_leak_box_ptr(ChainBackendKind {
+        MempoolSpace: support::new_leak_box_ptr(wire_ChainBackend_MempoolSpace {
+            url: core::ptr::null_mut(),
+        }),
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn inflate_ChainBackend_Esplora() -> *mut ChainBackendKind {
+    support::new_leak_box_ptr(ChainBackendKind {
+        Esplora: support::new_leak_box_ptr(wire_ChainBackend_Esplora {
+            url: core::ptr::null_mut(),
+        }),
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn inflate_ChainBackend_Electrum() -> *mut ChainBackendKind {
+    support::new_leak_box_ptr(ChainBackendKind {
+        Electrum: support::new_leak_box_ptr(wire_ChainBackend_Electrum {
+            url: core::ptr::null_mut(),
+        }),
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn inflate_ChainBackend_Bitcoind() -> *mut ChainBackendKind {
+    support::new_leak_box_ptr(ChainBackendKind {
+        Bitcoind: support::new_leak_box_ptr(wire_ChainBackend_Bitcoind {
+            url: core::ptr::null_mut(),
+            rpc_user: core::ptr::null_mut(),
+            rpc_password: core::ptr::null_mut(),
+        }),
+    })
 }
 
 impl NewWithNullPtr for wire_CheckMessageRequest {
@@ -1527,6 +2821,7 @@ impl NewWithNullPtr for wire_Config {
             breezserver: core::ptr::null_mut(),
             chainnotifier_url: core::ptr::null_mut(),
             mempoolspace_url: core::ptr::null_mut(),
+            chain_backends: core::ptr::null_mut(),
             working_dir: core::ptr::null_mut(),
             network: Default::default(),
             payment_timeout_sec: Default::default(),
@@ -1535,6 +2830,7 @@ impl NewWithNullPtr for wire_Config {
             maxfee_percent: Default::default(),
             exemptfee_msat: Default::default(),
             node_config: Default::default(),
+            custom_lsps: core::ptr::null_mut(),
         }
     }
 }
@@ -1575,6 +2871,72 @@ impl Default for wire_ConnectRequest {
     }
 }
 
+impl NewWithNullPtr for wire_CreateOfferRequest {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            description: core::ptr::null_mut(),
+            amount_msat: core::ptr::null_mut(),
+        }
+    }
+}
+
+impl Default for wire_CreateOfferRequest {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
+impl NewWithNullPtr for wire_CustomLsp {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            id: core::ptr::null_mut(),
+            name: core::ptr::null_mut(),
+            pubkey: core::ptr::null_mut(),
+            host: core::ptr::null_mut(),
+            protocols: core::ptr::null_mut(),
+            token: core::ptr::null_mut(),
+        }
+    }
+}
+
+impl Default for wire_CustomLsp {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
+impl NewWithNullPtr for wire_ExportPaymentsRequest {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            format: Default::default(),
+            list_payments_request: Default::default(),
+            fiat_currency: core::ptr::null_mut(),
+            use_coingecko_rates: Default::default(),
+        }
+    }
+}
+
+impl Default for wire_ExportPaymentsRequest {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
+impl NewWithNullPtr for wire_FiatAmount {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            amount: core::ptr::null_mut(),
+            rate: Default::default(),
+        }
+    }
+}
+
+impl Default for wire_FiatAmount {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
 impl NewWithNullPtr for wire_GreenlightCredentials {
     fn new_with_null_ptr() -> Self {
         Self {
@@ -1605,6 +2967,40 @@ impl Default for wire_GreenlightNodeConfig {
     }
 }
 
+impl NewWithNullPtr for wire_LightningSweepPolicy {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            to_address: core::ptr::null_mut(),
+            max_channels_balance_sat: Default::default(),
+            max_sat_per_vbyte: Default::default(),
+        }
+    }
+}
+
+impl Default for wire_LightningSweepPolicy {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
+impl NewWithNullPtr for wire_ListPaymentRequestsRequest {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            statuses: core::ptr::null_mut(),
+            from_timestamp: core::ptr::null_mut(),
+            to_timestamp: core::ptr::null_mut(),
+            offset: core::ptr::null_mut(),
+            limit: core::ptr::null_mut(),
+        }
+    }
+}
+
+impl Default for wire_ListPaymentRequestsRequest {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
 impl NewWithNullPtr for wire_ListPaymentsRequest {
     fn new_with_null_ptr() -> Self {
         Self {
@@ -1613,6 +3009,14 @@ impl NewWithNullPtr for wire_ListPaymentsRequest {
             from_timestamp: core::ptr::null_mut(),
             to_timestamp: core::ptr::null_mut(),
             include_failures: core::ptr::null_mut(),
+            statuses: core::ptr::null_mut(),
+            min_amount_msat: core::ptr::null_mut(),
+            max_amount_msat: core::ptr::null_mut(),
+            counterparty_node_id: core::ptr::null_mut(),
+            tags: core::ptr::null_mut(),
+            category: core::ptr::null_mut(),
+            search: core::ptr::null_mut(),
+            sort_order: core::ptr::null_mut(),
             offset: core::ptr::null_mut(),
             limit: core::ptr::null_mut(),
         }
@@ -1682,6 +3086,25 @@ impl Default for wire_LnUrlPayRequestData {
     }
 }
 
+impl NewWithNullPtr for wire_LnUrlPayServerConfig {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            callback: core::ptr::null_mut(),
+            min_sendable: Default::default(),
+            max_sendable: Default::default(),
+            description: core::ptr::null_mut(),
+            comment_allowed: Default::default(),
+            ln_address: core::ptr::null_mut(),
+        }
+    }
+}
+
+impl Default for wire_LnUrlPayServerConfig {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
 impl NewWithNullPtr for wire_LnUrlWithdrawRequest {
     fn new_with_null_ptr() -> Self {
         Self {
@@ -1706,6 +3129,8 @@ impl NewWithNullPtr for wire_LnUrlWithdrawRequestData {
             default_description: core::ptr::null_mut(),
             min_withdrawable: Default::default(),
             max_withdrawable: Default::default(),
+            balance_check: core::ptr::null_mut(),
+            pay_link: core::ptr::null_mut(),
         }
     }
 }
@@ -1716,6 +3141,23 @@ impl Default for wire_LnUrlWithdrawRequestData {
     }
 }
 
+impl NewWithNullPtr for wire_LockedFiatRate {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            id: core::ptr::null_mut(),
+            currency: core::ptr::null_mut(),
+            value: core::ptr::null_mut(),
+            locked_at: Default::default(),
+        }
+    }
+}
+
+impl Default for wire_LockedFiatRate {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
 impl NewWithNullPtr for wire_MetadataFilter {
     fn new_with_null_ptr() -> Self {
         Self {
@@ -1755,6 +3197,34 @@ pub extern "C" fn inflate_NodeConfig_Greenlight() -> *mut NodeConfigKind {
     })
 }
 
+#[no_mangle]
+pub extern "C" fn inflate_NodeConfig_Cln() -> *mut NodeConfigKind {
+    support::new_leak_box_ptr(NodeConfigKind {
+        Cln: support::new_leak_box_ptr(wire_NodeConfig_Cln {
+            grpc_url: core::ptr::null_mut(),
+            ca_cert: core::ptr::null_mut(),
+            client_cert: core::ptr::null_mut(),
+            client_key: core::ptr::null_mut(),
+        }),
+    })
+}
+
+impl NewWithNullPtr for wire_OnchainSweepPolicy {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            to_address: core::ptr::null_mut(),
+            max_onchain_balance_sat: Default::default(),
+            max_sat_per_vbyte: Default::default(),
+        }
+    }
+}
+
+impl Default for wire_OnchainSweepPolicy {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
 impl NewWithNullPtr for wire_OpenChannelFeeRequest {
     fn new_with_null_ptr() -> Self {
         Self {
@@ -1789,6 +3259,23 @@ impl Default for wire_OpeningFeeParams {
     }
 }
 
+impl NewWithNullPtr for wire_PayOfferRequest {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            offer: core::ptr::null_mut(),
+            amount_msat: core::ptr::null_mut(),
+            payer_note: core::ptr::null_mut(),
+            label: core::ptr::null_mut(),
+        }
+    }
+}
+
+impl Default for wire_PayOfferRequest {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
 impl NewWithNullPtr for wire_PayOnchainRequest {
     fn new_with_null_ptr() -> Self {
         Self {
@@ -1804,6 +3291,22 @@ impl Default for wire_PayOnchainRequest {
     }
 }
 
+impl NewWithNullPtr for wire_PrepareAccelerateSwapDepositRequest {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            swap_address: core::ptr::null_mut(),
+            target_sat_per_vbyte: Default::default(),
+            refund_address: core::ptr::null_mut(),
+        }
+    }
+}
+
+impl Default for wire_PrepareAccelerateSwapDepositRequest {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
 impl NewWithNullPtr for wire_PrepareOnchainPaymentRequest {
     fn new_with_null_ptr() -> Self {
         Self {
@@ -1855,6 +3358,22 @@ impl Default for wire_PrepareRedeemOnchainFundsRequest {
     }
 }
 
+impl NewWithNullPtr for wire_PrepareRefundAllRequest {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            to_address: core::ptr::null_mut(),
+            sat_per_vbyte: Default::default(),
+            swap_addresses: core::ptr::null_mut(),
+        }
+    }
+}
+
+impl Default for wire_PrepareRefundAllRequest {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
 impl NewWithNullPtr for wire_PrepareRefundRequest {
     fn new_with_null_ptr() -> Self {
         Self {
@@ -1871,10 +3390,26 @@ impl Default for wire_PrepareRefundRequest {
     }
 }
 
+impl NewWithNullPtr for wire_PrepareSendPaymentRequest {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            bolt11: core::ptr::null_mut(),
+            amount_msat: core::ptr::null_mut(),
+        }
+    }
+}
+
+impl Default for wire_PrepareSendPaymentRequest {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
 impl NewWithNullPtr for wire_ReceiveOnchainRequest {
     fn new_with_null_ptr() -> Self {
         Self {
             opening_fee_params: core::ptr::null_mut(),
+            script_version: core::ptr::null_mut(),
         }
     }
 }
@@ -1895,6 +3430,7 @@ impl NewWithNullPtr for wire_ReceivePaymentRequest {
             use_description_hash: core::ptr::null_mut(),
             expiry: core::ptr::null_mut(),
             cltv: core::ptr::null_mut(),
+            fiat_amount: core::ptr::null_mut(),
         }
     }
 }
@@ -1920,6 +3456,22 @@ impl Default for wire_RedeemOnchainFundsRequest {
     }
 }
 
+impl NewWithNullPtr for wire_RefundAllRequest {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            to_address: core::ptr::null_mut(),
+            sat_per_vbyte: Default::default(),
+            swap_addresses: core::ptr::null_mut(),
+        }
+    }
+}
+
+impl Default for wire_RefundAllRequest {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
 impl NewWithNullPtr for wire_RefundRequest {
     fn new_with_null_ptr() -> Self {
         Self {
@@ -2013,6 +3565,8 @@ impl NewWithNullPtr for wire_SendPaymentRequest {
             bolt11: core::ptr::null_mut(),
             amount_msat: core::ptr::null_mut(),
             label: core::ptr::null_mut(),
+            max_fee_msat: core::ptr::null_mut(),
+            fiat_amount: core::ptr::null_mut(),
         }
     }
 }
@@ -2040,6 +3594,23 @@ impl Default for wire_SendSpontaneousPaymentRequest {
     }
 }
 
+impl NewWithNullPtr for wire_SetPaymentAnnotationsRequest {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            payment_hash: core::ptr::null_mut(),
+            note: core::ptr::null_mut(),
+            tags: core::ptr::null_mut(),
+            category: core::ptr::null_mut(),
+        }
+    }
+}
+
+impl Default for wire_SetPaymentAnnotationsRequest {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
 impl NewWithNullPtr for wire_SignMessageRequest {
     fn new_with_null_ptr() -> Self {
         Self {
@@ -2054,6 +3625,37 @@ impl Default for wire_SignMessageRequest {
     }
 }
 
+impl NewWithNullPtr for wire_SpendingBudget {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            max_payment_msat: core::ptr::null_mut(),
+            limits: core::ptr::null_mut(),
+            approval_threshold_msat: core::ptr::null_mut(),
+        }
+    }
+}
+
+impl Default for wire_SpendingBudget {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
+impl NewWithNullPtr for wire_SpendingLimit {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            period: Default::default(),
+            max_amount_msat: Default::default(),
+        }
+    }
+}
+
+impl Default for wire_SpendingLimit {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
 impl NewWithNullPtr for wire_StaticBackupRequest {
     fn new_with_null_ptr() -> Self {
         Self {
@@ -2068,6 +3670,21 @@ impl Default for wire_StaticBackupRequest {
     }
 }
 
+impl NewWithNullPtr for wire_SweepPolicy {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            onchain: core::ptr::null_mut(),
+            lightning: core::ptr::null_mut(),
+        }
+    }
+}
+
+impl Default for wire_SweepPolicy {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
 impl NewWithNullPtr for wire_TlvEntry {
     fn new_with_null_ptr() -> Self {
         Self {
@@ -28,26 +28,47 @@ use crate::breez_services::InvoicePaidDetails;
 use crate::breez_services::PaymentFailedData;
 use crate::breez_services::SignMessageRequest;
 use crate::breez_services::SignMessageResponse;
+use crate::breez_services::SweepDetails;
+use crate::breez_services::SweepFailedData;
 use crate::chain::RecommendedFees;
 use crate::lnurl::pay::LnUrlPayResult;
 use crate::lnurl::pay::LnUrlPaySuccessData;
+use crate::lnurl::server::LnUrlPayServerConfig;
+use crate::lnurl::server::LnUrlPayServerResponse;
 use crate::lsp::LspInformation;
+use crate::models::AccelerateSwapDepositRequest;
+use crate::models::AccelerateSwapDepositResponse;
 use crate::models::BackupStatus;
+use crate::models::Bolt12PaymentDetails;
+use crate::models::BudgetPeriod;
+use crate::models::BumpFeeRequest;
+use crate::models::BumpFeeResponse;
 use crate::models::BuyBitcoinProvider;
 use crate::models::BuyBitcoinRequest;
 use crate::models::BuyBitcoinResponse;
+use crate::models::ChainBackend;
 use crate::models::ChannelState;
 use crate::models::ClosedChannelPaymentDetails;
 use crate::models::Config;
 use crate::models::ConfigureNodeRequest;
 use crate::models::ConnectRequest;
+use crate::models::CreateOfferRequest;
+use crate::models::CreateOfferResponse;
+use crate::models::CustomLsp;
 use crate::models::EnvironmentType;
+use crate::models::ExportFormat;
+use crate::models::ExportPaymentsRequest;
+use crate::models::ExportPaymentsResponse;
+use crate::models::FiatAmount;
 use crate::models::GreenlightCredentials;
 use crate::models::GreenlightDeviceCredentials;
 use crate::models::GreenlightNodeConfig;
 use crate::models::HealthCheckStatus;
+use crate::models::LightningSweepPolicy;
+use crate::models::ListPaymentRequestsRequest;
 use crate::models::ListPaymentsRequest;
 use crate::models::LnPaymentDetails;
+use crate::models::LockedFiatRate;
 use crate::models::LogEntry;
 use crate::models::MaxReverseSwapAmountResponse;
 use crate::models::MetadataFilter;
@@ -55,28 +76,42 @@ use crate::models::NodeConfig;
 use crate::models::NodeCredentials;
 use crate::models::NodeState;
 use crate::models::OnchainPaymentLimitsResponse;
+use crate::models::OnchainSweepPolicy;
 use crate::models::OpenChannelFeeRequest;
 use crate::models::OpenChannelFeeResponse;
 use crate::models::OpeningFeeParams;
 use crate::models::OpeningFeeParamsMenu;
+use crate::models::PayOfferRequest;
 use crate::models::PayOnchainRequest;
 use crate::models::PayOnchainResponse;
 use crate::models::Payment;
+use crate::models::PaymentApprovalEvent;
+use crate::models::PaymentApprovalRequest;
 use crate::models::PaymentDetails;
+use crate::models::PaymentRequest;
+use crate::models::PaymentRequestStatus;
+use crate::models::PaymentSortOrder;
 use crate::models::PaymentStatus;
 use crate::models::PaymentType;
 use crate::models::PaymentTypeFilter;
+use crate::models::PendingSpendingBudget;
+use crate::models::PrepareAccelerateSwapDepositRequest;
+use crate::models::PrepareAccelerateSwapDepositResponse;
 use crate::models::PrepareOnchainPaymentRequest;
 use crate::models::PrepareOnchainPaymentResponse;
 use crate::models::PrepareRedeemOnchainFundsRequest;
 use crate::models::PrepareRedeemOnchainFundsResponse;
+use crate::models::PrepareRefundAllRequest;
 use crate::models::PrepareRefundRequest;
 use crate::models::PrepareRefundResponse;
+use crate::models::PrepareSendPaymentRequest;
+use crate::models::PrepareSendPaymentResponse;
 use crate::models::ReceiveOnchainRequest;
 use crate::models::ReceivePaymentRequest;
 use crate::models::ReceivePaymentResponse;
 use crate::models::RedeemOnchainFundsRequest;
 use crate::models::RedeemOnchainFundsResponse;
+use crate::models::RefundAllRequest;
 use crate::models::RefundRequest;
 use crate::models::RefundResponse;
 use crate::models::ReportIssueRequest;
@@ -91,17 +126,29 @@ use crate::models::SendPaymentRequest;
 use crate::models::SendPaymentResponse;
 use crate::models::SendSpontaneousPaymentRequest;
 use crate::models::ServiceHealthCheckResponse;
+use crate::models::SetPaymentAnnotationsRequest;
+use crate::models::SpendingBudget;
+use crate::models::SpendingLimit;
 use crate::models::StaticBackupRequest;
 use crate::models::StaticBackupResponse;
 use crate::models::SwapAmountType;
+use crate::models::SwapDepositAccelerationSource;
 use crate::models::SwapInfo;
+use crate::models::SwapScriptVersion;
 use crate::models::SwapStatus;
+use crate::models::SweepKind;
+use crate::models::SweepPolicy;
 use crate::models::TlvEntry;
 use crate::models::UnspentTransactionOutput;
+use crate::wallets::WalletEvent;
 
 // Section: wire functions
 
-fn wire_connect_impl(port_: MessagePort, req: impl Wire2Api<ConnectRequest> + UnwindSafe) {
+fn wire_connect_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<ConnectRequest> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
         WrapInfo {
             debug_name: "connect",
@@ -109,53 +156,67 @@ fn wire_connect_impl(port_: MessagePort, req: impl Wire2Api<ConnectRequest> + Un
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| connect(api_req)
+            move |task_callback| connect(api_wallet_id, api_req)
         },
     )
 }
-fn wire_is_initialized_impl(port_: MessagePort) {
+fn wire_is_initialized_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, bool, _>(
         WrapInfo {
             debug_name: "is_initialized",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| Result::<_, ()>::Ok(is_initialized()),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| Result::<_, ()>::Ok(is_initialized(api_wallet_id))
+        },
     )
 }
-fn wire_sync_impl(port_: MessagePort) {
+fn wire_sync_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
         WrapInfo {
             debug_name: "sync",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| sync(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| sync(api_wallet_id)
+        },
     )
 }
-fn wire_node_credentials_impl(port_: MessagePort) {
+fn wire_node_credentials_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Option<NodeCredentials>, _>(
         WrapInfo {
             debug_name: "node_credentials",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| node_credentials(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| node_credentials(api_wallet_id)
+        },
     )
 }
-fn wire_node_info_impl(port_: MessagePort) {
+fn wire_node_info_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, NodeState, _>(
         WrapInfo {
             debug_name: "node_info",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| node_info(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| node_info(api_wallet_id)
+        },
     )
 }
 fn wire_configure_node_impl(
     port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
     req: impl Wire2Api<ConfigureNodeRequest> + UnwindSafe,
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
@@ -165,22 +226,30 @@ fn wire_configure_node_impl(
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| configure_node(api_req)
+            move |task_callback| configure_node(api_wallet_id, api_req)
         },
     )
 }
-fn wire_disconnect_impl(port_: MessagePort) {
+fn wire_disconnect_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
         WrapInfo {
             debug_name: "disconnect",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| disconnect(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| disconnect(api_wallet_id)
+        },
     )
 }
-fn wire_sign_message_impl(port_: MessagePort, req: impl Wire2Api<SignMessageRequest> + UnwindSafe) {
+fn wire_sign_message_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<SignMessageRequest> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, SignMessageResponse, _>(
         WrapInfo {
             debug_name: "sign_message",
@@ -188,13 +257,15 @@ fn wire_sign_message_impl(port_: MessagePort, req: impl Wire2Api<SignMessageRequ
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| sign_message(api_req)
+            move |task_callback| sign_message(api_wallet_id, api_req)
         },
     )
 }
 fn wire_check_message_impl(
     port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
     req: impl Wire2Api<CheckMessageRequest> + UnwindSafe,
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, CheckMessageResponse, _>(
@@ -204,9 +275,20 @@ fn wire_check_message_impl(
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| check_message(api_req)
+            move |task_callback| check_message(api_wallet_id, api_req)
+        },
+    )
+}
+fn wire_list_wallets_impl(port_: MessagePort) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Vec<String>, _>(
+        WrapInfo {
+            debug_name: "list_wallets",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
         },
+        move || move |task_callback| Result::<_, ()>::Ok(list_wallets()),
     )
 }
 fn wire_mnemonic_to_seed_impl(port_: MessagePort, phrase: impl Wire2Api<String> + UnwindSafe) {
@@ -285,6 +367,50 @@ fn wire_breez_events_stream_impl(port_: MessagePort) {
         },
     )
 }
+fn wire_wallet_events_stream_impl(port_: MessagePort) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
+        WrapInfo {
+            debug_name: "wallet_events_stream",
+            port: Some(port_),
+            mode: FfiCallMode::Stream,
+        },
+        move || {
+            move |task_callback| wallet_events_stream(task_callback.stream_sink::<_, WalletEvent>())
+        },
+    )
+}
+fn wire_payment_approval_stream_impl(port_: MessagePort) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
+        WrapInfo {
+            debug_name: "payment_approval_stream",
+            port: Some(port_),
+            mode: FfiCallMode::Stream,
+        },
+        move || {
+            move |task_callback| {
+                payment_approval_stream(task_callback.stream_sink::<_, PaymentApprovalEvent>())
+            }
+        },
+    )
+}
+fn wire_respond_payment_approval_impl(
+    port_: MessagePort,
+    id: impl Wire2Api<u64> + UnwindSafe,
+    approved: impl Wire2Api<bool> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
+        WrapInfo {
+            debug_name: "respond_payment_approval",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_id = id.wire2api();
+            let api_approved = approved.wire2api();
+            move |task_callback| respond_payment_approval(api_id, api_approved)
+        },
+    )
+}
 fn wire_breez_log_stream_impl(port_: MessagePort) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
         WrapInfo {
@@ -295,17 +421,42 @@ fn wire_breez_log_stream_impl(port_: MessagePort) {
         move || move |task_callback| breez_log_stream(task_callback.stream_sink::<_, LogEntry>()),
     )
 }
-fn wire_list_lsps_impl(port_: MessagePort) {
+fn wire_breez_wallet_log_stream_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
+        WrapInfo {
+            debug_name: "breez_wallet_log_stream",
+            port: Some(port_),
+            mode: FfiCallMode::Stream,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| {
+                breez_wallet_log_stream(api_wallet_id, task_callback.stream_sink::<_, LogEntry>())
+            }
+        },
+    )
+}
+fn wire_list_lsps_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Vec<LspInformation>, _>(
         WrapInfo {
             debug_name: "list_lsps",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| list_lsps(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| list_lsps(api_wallet_id)
+        },
     )
 }
-fn wire_connect_lsp_impl(port_: MessagePort, lsp_id: impl Wire2Api<String> + UnwindSafe) {
+fn wire_connect_lsp_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    lsp_id: impl Wire2Api<String> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
         WrapInfo {
             debug_name: "connect_lsp",
@@ -313,22 +464,30 @@ fn wire_connect_lsp_impl(port_: MessagePort, lsp_id: impl Wire2Api<String> + Unw
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_lsp_id = lsp_id.wire2api();
-            move |task_callback| connect_lsp(api_lsp_id)
+            move |task_callback| connect_lsp(api_wallet_id, api_lsp_id)
         },
     )
 }
-fn wire_lsp_id_impl(port_: MessagePort) {
+fn wire_lsp_id_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Option<String>, _>(
         WrapInfo {
             debug_name: "lsp_id",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| lsp_id(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| lsp_id(api_wallet_id)
+        },
     )
 }
-fn wire_fetch_lsp_info_impl(port_: MessagePort, id: impl Wire2Api<String> + UnwindSafe) {
+fn wire_fetch_lsp_info_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    id: impl Wire2Api<String> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Option<LspInformation>, _>(
         WrapInfo {
             debug_name: "fetch_lsp_info",
@@ -336,32 +495,43 @@ fn wire_fetch_lsp_info_impl(port_: MessagePort, id: impl Wire2Api<String> + Unwi
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_id = id.wire2api();
-            move |task_callback| fetch_lsp_info(api_id)
+            move |task_callback| fetch_lsp_info(api_wallet_id, api_id)
         },
     )
 }
-fn wire_lsp_info_impl(port_: MessagePort) {
+fn wire_lsp_info_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, LspInformation, _>(
         WrapInfo {
             debug_name: "lsp_info",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| lsp_info(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| lsp_info(api_wallet_id)
+        },
     )
 }
-fn wire_close_lsp_channels_impl(port_: MessagePort) {
+fn wire_close_lsp_channels_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
         WrapInfo {
             debug_name: "close_lsp_channels",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| close_lsp_channels(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| close_lsp_channels(api_wallet_id)
+        },
     )
 }
-fn wire_register_webhook_impl(port_: MessagePort, webhook_url: impl Wire2Api<String> + UnwindSafe) {
+fn wire_register_webhook_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    webhook_url: impl Wire2Api<String> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
         WrapInfo {
             debug_name: "register_webhook",
@@ -369,13 +539,15 @@ fn wire_register_webhook_impl(port_: MessagePort, webhook_url: impl Wire2Api<Str
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_webhook_url = webhook_url.wire2api();
-            move |task_callback| register_webhook(api_webhook_url)
+            move |task_callback| register_webhook(api_wallet_id, api_webhook_url)
         },
     )
 }
 fn wire_unregister_webhook_impl(
     port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
     webhook_url: impl Wire2Api<String> + UnwindSafe,
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
@@ -385,29 +557,36 @@ fn wire_unregister_webhook_impl(
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_webhook_url = webhook_url.wire2api();
-            move |task_callback| unregister_webhook(api_webhook_url)
+            move |task_callback| unregister_webhook(api_wallet_id, api_webhook_url)
         },
     )
 }
-fn wire_backup_impl(port_: MessagePort) {
+fn wire_backup_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
         WrapInfo {
             debug_name: "backup",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| backup(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| backup(api_wallet_id)
+        },
     )
 }
-fn wire_backup_status_impl(port_: MessagePort) {
+fn wire_backup_status_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, BackupStatus, _>(
         WrapInfo {
             debug_name: "backup_status",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| backup_status(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| backup_status(api_wallet_id)
+        },
     )
 }
 fn wire_parse_invoice_impl(port_: MessagePort, invoice: impl Wire2Api<String> + UnwindSafe) {
@@ -438,6 +617,7 @@ fn wire_parse_input_impl(port_: MessagePort, input: impl Wire2Api<String> + Unwi
 }
 fn wire_list_payments_impl(
     port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
     req: impl Wire2Api<ListPaymentsRequest> + UnwindSafe,
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Vec<Payment>, _>(
@@ -447,12 +627,53 @@ fn wire_list_payments_impl(
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_req = req.wire2api();
+            move |task_callback| list_payments(api_wallet_id, api_req)
+        },
+    )
+}
+fn wire_count_payments_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<ListPaymentsRequest> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, u64, _>(
+        WrapInfo {
+            debug_name: "count_payments",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_req = req.wire2api();
+            move |task_callback| count_payments(api_wallet_id, api_req)
+        },
+    )
+}
+fn wire_export_payments_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<ExportPaymentsRequest> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, ExportPaymentsResponse, _>(
+        WrapInfo {
+            debug_name: "export_payments",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| list_payments(api_req)
+            move |task_callback| export_payments(api_wallet_id, api_req)
         },
     )
 }
-fn wire_payment_by_hash_impl(port_: MessagePort, hash: impl Wire2Api<String> + UnwindSafe) {
+fn wire_payment_by_hash_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    hash: impl Wire2Api<String> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Option<Payment>, _>(
         WrapInfo {
             debug_name: "payment_by_hash",
@@ -460,13 +681,15 @@ fn wire_payment_by_hash_impl(port_: MessagePort, hash: impl Wire2Api<String> + U
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_hash = hash.wire2api();
-            move |task_callback| payment_by_hash(api_hash)
+            move |task_callback| payment_by_hash(api_wallet_id, api_hash)
         },
     )
 }
 fn wire_set_payment_metadata_impl(
     port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
     hash: impl Wire2Api<String> + UnwindSafe,
     metadata: impl Wire2Api<String> + UnwindSafe,
 ) {
@@ -477,13 +700,67 @@ fn wire_set_payment_metadata_impl(
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_hash = hash.wire2api();
             let api_metadata = metadata.wire2api();
-            move |task_callback| set_payment_metadata(api_hash, api_metadata)
+            move |task_callback| set_payment_metadata(api_wallet_id, api_hash, api_metadata)
         },
     )
 }
-fn wire_send_payment_impl(port_: MessagePort, req: impl Wire2Api<SendPaymentRequest> + UnwindSafe) {
+fn wire_set_payment_annotations_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<SetPaymentAnnotationsRequest> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
+        WrapInfo {
+            debug_name: "set_payment_annotations",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_req = req.wire2api();
+            move |task_callback| set_payment_annotations(api_wallet_id, api_req)
+        },
+    )
+}
+fn wire_list_tags_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Vec<String>, _>(
+        WrapInfo {
+            debug_name: "list_tags",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| list_tags(api_wallet_id)
+        },
+    )
+}
+fn wire_prepare_send_payment_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<PrepareSendPaymentRequest> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, PrepareSendPaymentResponse, _>(
+        WrapInfo {
+            debug_name: "prepare_send_payment",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_req = req.wire2api();
+            move |task_callback| prepare_send_payment(api_wallet_id, api_req)
+        },
+    )
+}
+fn wire_send_payment_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<SendPaymentRequest> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, SendPaymentResponse, _>(
         WrapInfo {
             debug_name: "send_payment",
@@ -491,13 +768,15 @@ fn wire_send_payment_impl(port_: MessagePort, req: impl Wire2Api<SendPaymentRequ
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| send_payment(api_req)
+            move |task_callback| send_payment(api_wallet_id, api_req)
         },
     )
 }
 fn wire_send_spontaneous_payment_impl(
     port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
     req: impl Wire2Api<SendSpontaneousPaymentRequest> + UnwindSafe,
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, SendPaymentResponse, _>(
@@ -507,13 +786,51 @@ fn wire_send_spontaneous_payment_impl(
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| send_spontaneous_payment(api_req)
+            move |task_callback| send_spontaneous_payment(api_wallet_id, api_req)
+        },
+    )
+}
+fn wire_pay_offer_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<PayOfferRequest> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, SendPaymentResponse, _>(
+        WrapInfo {
+            debug_name: "pay_offer",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_req = req.wire2api();
+            move |task_callback| pay_offer(api_wallet_id, api_req)
+        },
+    )
+}
+fn wire_create_offer_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<CreateOfferRequest> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, CreateOfferResponse, _>(
+        WrapInfo {
+            debug_name: "create_offer",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_req = req.wire2api();
+            move |task_callback| create_offer(api_wallet_id, api_req)
         },
     )
 }
 fn wire_receive_payment_impl(
     port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
     req: impl Wire2Api<ReceivePaymentRequest> + UnwindSafe,
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, ReceivePaymentResponse, _>(
@@ -523,12 +840,53 @@ fn wire_receive_payment_impl(
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_req = req.wire2api();
+            move |task_callback| receive_payment(api_wallet_id, api_req)
+        },
+    )
+}
+fn wire_list_payment_requests_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<ListPaymentRequestsRequest> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Vec<PaymentRequest>, _>(
+        WrapInfo {
+            debug_name: "list_payment_requests",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| receive_payment(api_req)
+            move |task_callback| list_payment_requests(api_wallet_id, api_req)
+        },
+    )
+}
+fn wire_cancel_payment_request_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    payment_hash: impl Wire2Api<String> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
+        WrapInfo {
+            debug_name: "cancel_payment_request",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_payment_hash = payment_hash.wire2api();
+            move |task_callback| cancel_payment_request(api_wallet_id, api_payment_hash)
         },
     )
 }
-fn wire_lnurl_pay_impl(port_: MessagePort, req: impl Wire2Api<LnUrlPayRequest> + UnwindSafe) {
+fn wire_lnurl_pay_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<LnUrlPayRequest> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, LnUrlPayResult, _>(
         WrapInfo {
             debug_name: "lnurl_pay",
@@ -536,13 +894,53 @@ fn wire_lnurl_pay_impl(port_: MessagePort, req: impl Wire2Api<LnUrlPayRequest> +
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| lnurl_pay(api_req)
+            move |task_callback| lnurl_pay(api_wallet_id, api_req)
+        },
+    )
+}
+fn wire_handle_lnurl_pay_server_request_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    config: impl Wire2Api<LnUrlPayServerConfig> + UnwindSafe,
+    url: impl Wire2Api<String> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, LnUrlPayServerResponse, _>(
+        WrapInfo {
+            debug_name: "handle_lnurl_pay_server_request",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_config = config.wire2api();
+            let api_url = url.wire2api();
+            move |task_callback| handle_lnurl_pay_server_request(api_wallet_id, api_config, api_url)
+        },
+    )
+}
+fn wire_verify_lnurl_payment_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    payment_hash: impl Wire2Api<String> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, mirror_LnUrlVerifyStatus, _>(
+        WrapInfo {
+            debug_name: "verify_lnurl_payment",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_payment_hash = payment_hash.wire2api();
+            move |task_callback| verify_lnurl_payment(api_wallet_id, api_payment_hash)
         },
     )
 }
 fn wire_lnurl_withdraw_impl(
     port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
     req: impl Wire2Api<LnUrlWithdrawRequest> + UnwindSafe,
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, mirror_LnUrlWithdrawResult, _>(
@@ -552,13 +950,15 @@ fn wire_lnurl_withdraw_impl(
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| lnurl_withdraw(api_req)
+            move |task_callback| lnurl_withdraw(api_wallet_id, api_req)
         },
     )
 }
 fn wire_lnurl_auth_impl(
     port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
     req_data: impl Wire2Api<LnUrlAuthRequestData> + UnwindSafe,
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, mirror_LnUrlCallbackStatus, _>(
@@ -568,12 +968,17 @@ fn wire_lnurl_auth_impl(
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req_data = req_data.wire2api();
-            move |task_callback| lnurl_auth(api_req_data)
+            move |task_callback| lnurl_auth(api_wallet_id, api_req_data)
         },
     )
 }
-fn wire_report_issue_impl(port_: MessagePort, req: impl Wire2Api<ReportIssueRequest> + UnwindSafe) {
+fn wire_report_issue_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<ReportIssueRequest> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
         WrapInfo {
             debug_name: "report_issue",
@@ -581,42 +986,80 @@ fn wire_report_issue_impl(port_: MessagePort, req: impl Wire2Api<ReportIssueRequ
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| report_issue(api_req)
+            move |task_callback| report_issue(api_wallet_id, api_req)
         },
     )
 }
-fn wire_fetch_fiat_rates_impl(port_: MessagePort) {
+fn wire_fetch_fiat_rates_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Vec<mirror_Rate>, _>(
         WrapInfo {
             debug_name: "fetch_fiat_rates",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| fetch_fiat_rates(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| fetch_fiat_rates(api_wallet_id)
+        },
     )
 }
-fn wire_list_fiat_currencies_impl(port_: MessagePort) {
+fn wire_lock_fiat_rate_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    currency: impl Wire2Api<String> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, LockedFiatRate, _>(
+        WrapInfo {
+            debug_name: "lock_fiat_rate",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_currency = currency.wire2api();
+            move |task_callback| lock_fiat_rate(api_wallet_id, api_currency)
+        },
+    )
+}
+fn wire_list_fiat_currencies_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Vec<mirror_FiatCurrency>, _>(
         WrapInfo {
             debug_name: "list_fiat_currencies",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| list_fiat_currencies(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| list_fiat_currencies(api_wallet_id)
+        },
     )
 }
-fn wire_max_reverse_swap_amount_impl(port_: MessagePort) {
+fn wire_max_reverse_swap_amount_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, MaxReverseSwapAmountResponse, _>(
         WrapInfo {
             debug_name: "max_reverse_swap_amount",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| max_reverse_swap_amount(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| max_reverse_swap_amount(api_wallet_id)
+        },
     )
 }
-fn wire_send_onchain_impl(port_: MessagePort, req: impl Wire2Api<SendOnchainRequest> + UnwindSafe) {
+fn wire_send_onchain_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<SendOnchainRequest> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, SendOnchainResponse, _>(
         WrapInfo {
             debug_name: "send_onchain",
@@ -624,12 +1067,17 @@ fn wire_send_onchain_impl(port_: MessagePort, req: impl Wire2Api<SendOnchainRequ
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| send_onchain(api_req)
+            move |task_callback| send_onchain(api_wallet_id, api_req)
         },
     )
 }
-fn wire_pay_onchain_impl(port_: MessagePort, req: impl Wire2Api<PayOnchainRequest> + UnwindSafe) {
+fn wire_pay_onchain_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<PayOnchainRequest> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, PayOnchainResponse, _>(
         WrapInfo {
             debug_name: "pay_onchain",
@@ -637,13 +1085,15 @@ fn wire_pay_onchain_impl(port_: MessagePort, req: impl Wire2Api<PayOnchainReques
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| pay_onchain(api_req)
+            move |task_callback| pay_onchain(api_wallet_id, api_req)
         },
     )
 }
 fn wire_receive_onchain_impl(
     port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
     req: impl Wire2Api<ReceiveOnchainRequest> + UnwindSafe,
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, SwapInfo, _>(
@@ -653,106 +1103,241 @@ fn wire_receive_onchain_impl(
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_req = req.wire2api();
+            move |task_callback| receive_onchain(api_wallet_id, api_req)
+        },
+    )
+}
+fn wire_buy_bitcoin_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<BuyBitcoinRequest> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, BuyBitcoinResponse, _>(
+        WrapInfo {
+            debug_name: "buy_bitcoin",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_req = req.wire2api();
+            move |task_callback| buy_bitcoin(api_wallet_id, api_req)
+        },
+    )
+}
+fn wire_redeem_onchain_funds_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<RedeemOnchainFundsRequest> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, RedeemOnchainFundsResponse, _>(
+        WrapInfo {
+            debug_name: "redeem_onchain_funds",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_req = req.wire2api();
+            move |task_callback| redeem_onchain_funds(api_wallet_id, api_req)
+        },
+    )
+}
+fn wire_prepare_redeem_onchain_funds_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<PrepareRedeemOnchainFundsRequest> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, PrepareRedeemOnchainFundsResponse, _>(
+        WrapInfo {
+            debug_name: "prepare_redeem_onchain_funds",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_req = req.wire2api();
+            move |task_callback| prepare_redeem_onchain_funds(api_wallet_id, api_req)
+        },
+    )
+}
+fn wire_list_refundables_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Vec<SwapInfo>, _>(
+        WrapInfo {
+            debug_name: "list_refundables",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| list_refundables(api_wallet_id)
+        },
+    )
+}
+fn wire_prepare_refund_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<PrepareRefundRequest> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, PrepareRefundResponse, _>(
+        WrapInfo {
+            debug_name: "prepare_refund",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_req = req.wire2api();
+            move |task_callback| prepare_refund(api_wallet_id, api_req)
+        },
+    )
+}
+fn wire_refund_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<RefundRequest> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, RefundResponse, _>(
+        WrapInfo {
+            debug_name: "refund",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| receive_onchain(api_req)
+            move |task_callback| refund(api_wallet_id, api_req)
         },
     )
 }
-fn wire_buy_bitcoin_impl(port_: MessagePort, req: impl Wire2Api<BuyBitcoinRequest> + UnwindSafe) {
-    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, BuyBitcoinResponse, _>(
+fn wire_request_cooperative_refund_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<RefundRequest> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, RefundResponse, _>(
         WrapInfo {
-            debug_name: "buy_bitcoin",
+            debug_name: "request_cooperative_refund",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| buy_bitcoin(api_req)
+            move |task_callback| request_cooperative_refund(api_wallet_id, api_req)
         },
     )
 }
-fn wire_redeem_onchain_funds_impl(
+fn wire_prepare_refund_all_impl(
     port_: MessagePort,
-    req: impl Wire2Api<RedeemOnchainFundsRequest> + UnwindSafe,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<PrepareRefundAllRequest> + UnwindSafe,
 ) {
-    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, RedeemOnchainFundsResponse, _>(
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, PrepareRefundResponse, _>(
         WrapInfo {
-            debug_name: "redeem_onchain_funds",
+            debug_name: "prepare_refund_all",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| redeem_onchain_funds(api_req)
+            move |task_callback| prepare_refund_all(api_wallet_id, api_req)
         },
     )
 }
-fn wire_prepare_redeem_onchain_funds_impl(
+fn wire_refund_all_impl(
     port_: MessagePort,
-    req: impl Wire2Api<PrepareRedeemOnchainFundsRequest> + UnwindSafe,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<RefundAllRequest> + UnwindSafe,
 ) {
-    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, PrepareRedeemOnchainFundsResponse, _>(
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, RefundResponse, _>(
         WrapInfo {
-            debug_name: "prepare_redeem_onchain_funds",
+            debug_name: "refund_all",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| prepare_redeem_onchain_funds(api_req)
+            move |task_callback| refund_all(api_wallet_id, api_req)
         },
     )
 }
-fn wire_list_refundables_impl(port_: MessagePort) {
-    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Vec<SwapInfo>, _>(
+fn wire_bump_fee_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<BumpFeeRequest> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, BumpFeeResponse, _>(
         WrapInfo {
-            debug_name: "list_refundables",
+            debug_name: "bump_fee",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| list_refundables(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_req = req.wire2api();
+            move |task_callback| bump_fee(api_wallet_id, api_req)
+        },
     )
 }
-fn wire_prepare_refund_impl(
+fn wire_prepare_accelerate_swap_deposit_impl(
     port_: MessagePort,
-    req: impl Wire2Api<PrepareRefundRequest> + UnwindSafe,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<PrepareAccelerateSwapDepositRequest> + UnwindSafe,
 ) {
-    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, PrepareRefundResponse, _>(
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, PrepareAccelerateSwapDepositResponse, _>(
         WrapInfo {
-            debug_name: "prepare_refund",
+            debug_name: "prepare_accelerate_swap_deposit",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| prepare_refund(api_req)
+            move |task_callback| prepare_accelerate_swap_deposit(api_wallet_id, api_req)
         },
     )
 }
-fn wire_refund_impl(port_: MessagePort, req: impl Wire2Api<RefundRequest> + UnwindSafe) {
-    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, RefundResponse, _>(
+fn wire_accelerate_swap_deposit_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    req: impl Wire2Api<AccelerateSwapDepositRequest> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, AccelerateSwapDepositResponse, _>(
         WrapInfo {
-            debug_name: "refund",
+            debug_name: "accelerate_swap_deposit",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| refund(api_req)
+            move |task_callback| accelerate_swap_deposit(api_wallet_id, api_req)
         },
     )
 }
-fn wire_rescan_swaps_impl(port_: MessagePort) {
+fn wire_rescan_swaps_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
         WrapInfo {
             debug_name: "rescan_swaps",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| rescan_swaps(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| rescan_swaps(api_wallet_id)
+        },
     )
 }
-fn wire_redeem_swap_impl(port_: MessagePort, swap_address: impl Wire2Api<String> + UnwindSafe) {
+fn wire_redeem_swap_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    swap_address: impl Wire2Api<String> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
         WrapInfo {
             debug_name: "redeem_swap",
@@ -760,33 +1345,44 @@ fn wire_redeem_swap_impl(port_: MessagePort, swap_address: impl Wire2Api<String>
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_swap_address = swap_address.wire2api();
-            move |task_callback| redeem_swap(api_swap_address)
+            move |task_callback| redeem_swap(api_wallet_id, api_swap_address)
         },
     )
 }
-fn wire_in_progress_swap_impl(port_: MessagePort) {
+fn wire_in_progress_swap_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Option<SwapInfo>, _>(
         WrapInfo {
             debug_name: "in_progress_swap",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| in_progress_swap(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| in_progress_swap(api_wallet_id)
+        },
     )
 }
-fn wire_in_progress_reverse_swaps_impl(port_: MessagePort) {
+fn wire_in_progress_reverse_swaps_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Vec<ReverseSwapInfo>, _>(
         WrapInfo {
             debug_name: "in_progress_reverse_swaps",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| in_progress_reverse_swaps(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| in_progress_reverse_swaps(api_wallet_id)
+        },
     )
 }
 fn wire_claim_reverse_swap_impl(
     port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
     lockup_address: impl Wire2Api<String> + UnwindSafe,
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
@@ -796,13 +1392,15 @@ fn wire_claim_reverse_swap_impl(
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_lockup_address = lockup_address.wire2api();
-            move |task_callback| claim_reverse_swap(api_lockup_address)
+            move |task_callback| claim_reverse_swap(api_wallet_id, api_lockup_address)
         },
     )
 }
 fn wire_open_channel_fee_impl(
     port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
     req: impl Wire2Api<OpenChannelFeeRequest> + UnwindSafe,
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, OpenChannelFeeResponse, _>(
@@ -812,13 +1410,15 @@ fn wire_open_channel_fee_impl(
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| open_channel_fee(api_req)
+            move |task_callback| open_channel_fee(api_wallet_id, api_req)
         },
     )
 }
 fn wire_fetch_reverse_swap_fees_impl(
     port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
     req: impl Wire2Api<ReverseSwapFeesRequest> + UnwindSafe,
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, ReverseSwapPairInfo, _>(
@@ -828,23 +1428,31 @@ fn wire_fetch_reverse_swap_fees_impl(
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| fetch_reverse_swap_fees(api_req)
+            move |task_callback| fetch_reverse_swap_fees(api_wallet_id, api_req)
         },
     )
 }
-fn wire_onchain_payment_limits_impl(port_: MessagePort) {
+fn wire_onchain_payment_limits_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, OnchainPaymentLimitsResponse, _>(
         WrapInfo {
             debug_name: "onchain_payment_limits",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| onchain_payment_limits(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| onchain_payment_limits(api_wallet_id)
+        },
     )
 }
 fn wire_prepare_onchain_payment_impl(
     port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
     req: impl Wire2Api<PrepareOnchainPaymentRequest> + UnwindSafe,
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, PrepareOnchainPaymentResponse, _>(
@@ -854,32 +1462,124 @@ fn wire_prepare_onchain_payment_impl(
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_req = req.wire2api();
-            move |task_callback| prepare_onchain_payment(api_req)
+            move |task_callback| prepare_onchain_payment(api_wallet_id, api_req)
         },
     )
 }
-fn wire_in_progress_onchain_payments_impl(port_: MessagePort) {
+fn wire_in_progress_onchain_payments_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Vec<ReverseSwapInfo>, _>(
         WrapInfo {
             debug_name: "in_progress_onchain_payments",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| in_progress_onchain_payments(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| in_progress_onchain_payments(api_wallet_id)
+        },
     )
 }
-fn wire_recommended_fees_impl(port_: MessagePort) {
+fn wire_recommended_fees_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, RecommendedFees, _>(
         WrapInfo {
             debug_name: "recommended_fees",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| recommended_fees(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| recommended_fees(api_wallet_id)
+        },
+    )
+}
+fn wire_set_sweep_policy_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    policy: impl Wire2Api<SweepPolicy> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
+        WrapInfo {
+            debug_name: "set_sweep_policy",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_policy = policy.wire2api();
+            move |task_callback| set_sweep_policy(api_wallet_id, api_policy)
+        },
+    )
+}
+fn wire_sweep_policy_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, SweepPolicy, _>(
+        WrapInfo {
+            debug_name: "sweep_policy",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| sweep_policy(api_wallet_id)
+        },
+    )
+}
+fn wire_set_spending_budget_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    budget: impl Wire2Api<SpendingBudget> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
+        WrapInfo {
+            debug_name: "set_spending_budget",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            let api_budget = budget.wire2api();
+            move |task_callback| set_spending_budget(api_wallet_id, api_budget)
+        },
+    )
+}
+fn wire_spending_budget_impl(port_: MessagePort, wallet_id: impl Wire2Api<String> + UnwindSafe) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, SpendingBudget, _>(
+        WrapInfo {
+            debug_name: "spending_budget",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| spending_budget(api_wallet_id)
+        },
+    )
+}
+fn wire_pending_spending_budget_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Option<PendingSpendingBudget>, _>(
+        WrapInfo {
+            debug_name: "pending_spending_budget",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| pending_spending_budget(api_wallet_id)
+        },
     )
 }
-fn wire_execute_command_impl(port_: MessagePort, command: impl Wire2Api<String> + UnwindSafe) {
+fn wire_execute_command_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+    command: impl Wire2Api<String> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, String, _>(
         WrapInfo {
             debug_name: "execute_command",
@@ -887,19 +1587,26 @@ fn wire_execute_command_impl(port_: MessagePort, command: impl Wire2Api<String>
             mode: FfiCallMode::Normal,
         },
         move || {
+            let api_wallet_id = wallet_id.wire2api();
             let api_command = command.wire2api();
-            move |task_callback| execute_command(api_command)
+            move |task_callback| execute_command(api_wallet_id, api_command)
         },
     )
 }
-fn wire_generate_diagnostic_data_impl(port_: MessagePort) {
+fn wire_generate_diagnostic_data_impl(
+    port_: MessagePort,
+    wallet_id: impl Wire2Api<String> + UnwindSafe,
+) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, String, _>(
         WrapInfo {
             debug_name: "generate_diagnostic_data",
             port: Some(port_),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| generate_diagnostic_data(),
+        move || {
+            let api_wallet_id = wallet_id.wire2api();
+            move |task_callback| generate_diagnostic_data(api_wallet_id)
+        },
     )
 }
 // Section: wrapper structs
@@ -910,6 +1617,9 @@ pub struct mirror_AesSuccessActionDataDecrypted(AesSuccessActionDataDecrypted);
 #[derive(Clone)]
 pub struct mirror_AesSuccessActionDataResult(AesSuccessActionDataResult);
 
+#[derive(Clone)]
+pub struct mirror_Amount(Amount);
+
 #[derive(Clone)]
 pub struct mirror_BitcoinAddressData(BitcoinAddressData);
 
@@ -925,6 +1635,9 @@ pub struct mirror_InputType(InputType);
 #[derive(Clone)]
 pub struct mirror_LNInvoice(LNInvoice);
 
+#[derive(Clone)]
+pub struct mirror_LNOffer(LNOffer);
+
 #[derive(Clone)]
 pub struct mirror_LnUrlAuthRequestData(LnUrlAuthRequestData);
 
@@ -940,6 +1653,9 @@ pub struct mirror_LnUrlPayErrorData(LnUrlPayErrorData);
 #[derive(Clone)]
 pub struct mirror_LnUrlPayRequestData(LnUrlPayRequestData);
 
+#[derive(Clone)]
+pub struct mirror_LnUrlVerifyStatus(LnUrlVerifyStatus);
+
 #[derive(Clone)]
 pub struct mirror_LnUrlWithdrawRequestData(LnUrlWithdrawRequestData);
 
@@ -995,6 +1711,18 @@ const _: fn() = || {
             let _: String = reason;
         }
     }
+    match None::<Amount>.unwrap() {
+        Amount::Bitcoin { amount_msat } => {
+            let _: u64 = amount_msat;
+        }
+        Amount::Currency {
+            iso4217_code,
+            fractional_amount,
+        } => {
+            let _: String = iso4217_code;
+            let _: u64 = fractional_amount;
+        }
+    }
     {
         let BitcoinAddressData = None::<BitcoinAddressData>.unwrap();
         let _: String = BitcoinAddressData.address;
@@ -1025,6 +1753,9 @@ const _: fn() = || {
         InputType::Bolt11 { invoice } => {
             let _: LNInvoice = invoice;
         }
+        InputType::Bolt12Offer { offer } => {
+            let _: LNOffer = offer;
+        }
         InputType::NodeId { node_id } => {
             let _: String = node_id;
         }
@@ -1059,6 +1790,17 @@ const _: fn() = || {
         let _: Vec<u8> = LNInvoice.payment_secret;
         let _: u64 = LNInvoice.min_final_cltv_expiry_delta;
     }
+    {
+        let LNOffer = None::<LNOffer>.unwrap();
+        let _: String = LNOffer.offer;
+        let _: Vec<String> = LNOffer.chains;
+        let _: Option<Amount> = LNOffer.min_amount;
+        let _: String = LNOffer.description;
+        let _: Option<u64> = LNOffer.absolute_expiry;
+        let _: Option<String> = LNOffer.issuer;
+        let _: String = LNOffer.signing_pubkey;
+        let _: u32 = LNOffer.paths_count;
+    }
     {
         let LnUrlAuthRequestData = None::<LnUrlAuthRequestData>.unwrap();
         let _: String = LnUrlAuthRequestData.k1;
@@ -1093,6 +1835,12 @@ const _: fn() = || {
         let _: Option<String> = LnUrlPayRequestData.nostr_pubkey;
         let _: Option<String> = LnUrlPayRequestData.ln_address;
     }
+    match None::<LnUrlVerifyStatus>.unwrap() {
+        LnUrlVerifyStatus::Settled { preimage } => {
+            let _: String = preimage;
+        }
+        LnUrlVerifyStatus::Pending => {}
+    }
     {
         let LnUrlWithdrawRequestData = None::<LnUrlWithdrawRequestData>.unwrap();
         let _: String = LnUrlWithdrawRequestData.callback;
@@ -1100,6 +1848,8 @@ const _: fn() = || {
         let _: String = LnUrlWithdrawRequestData.default_description;
         let _: u64 = LnUrlWithdrawRequestData.min_withdrawable;
         let _: u64 = LnUrlWithdrawRequestData.max_withdrawable;
+        let _: Option<String> = LnUrlWithdrawRequestData.balance_check;
+        let _: Option<String> = LnUrlWithdrawRequestData.pay_link;
     }
     match None::<LnUrlWithdrawResult>.unwrap() {
         LnUrlWithdrawResult::Ok { data } => {
@@ -1115,6 +1865,7 @@ const _: fn() = || {
     {
         let LnUrlWithdrawSuccessData = None::<LnUrlWithdrawSuccessData>.unwrap();
         let _: LNInvoice = LnUrlWithdrawSuccessData.invoice;
+        let _: Option<LnUrlWithdrawRequestData> = LnUrlWithdrawSuccessData.refreshed_request;
     }
     {
         let LocaleOverrides = None::<LocaleOverrides>.unwrap();
@@ -1205,6 +1956,17 @@ impl Wire2Api<bool> for bool {
     }
 }
 
+impl Wire2Api<BudgetPeriod> for i32 {
+    fn wire2api(self) -> BudgetPeriod {
+        match self {
+            0 => BudgetPeriod::Daily,
+            1 => BudgetPeriod::Weekly,
+            2 => BudgetPeriod::Monthly,
+            _ => unreachable!("Invalid variant for BudgetPeriod: {}", self),
+        }
+    }
+}
+
 impl Wire2Api<BuyBitcoinProvider> for i32 {
     fn wire2api(self) -> BuyBitcoinProvider {
         match self {
@@ -1223,6 +1985,17 @@ impl Wire2Api<EnvironmentType> for i32 {
         }
     }
 }
+impl Wire2Api<ExportFormat> for i32 {
+    fn wire2api(self) -> ExportFormat {
+        match self {
+            0 => ExportFormat::Csv,
+            1 => ExportFormat::Koinly,
+            2 => ExportFormat::CoinTracking,
+            _ => unreachable!("Invalid variant for ExportFormat: {}", self),
+        }
+    }
+}
+
 impl Wire2Api<f64> for f64 {
     fn wire2api(self) -> f64 {
         self
@@ -1252,6 +2025,36 @@ impl Wire2Api<Network> for i32 {
     }
 }
 
+impl Wire2Api<PaymentRequestStatus> for i32 {
+    fn wire2api(self) -> PaymentRequestStatus {
+        match self {
+            0 => PaymentRequestStatus::Open,
+            1 => PaymentRequestStatus::Paid,
+            2 => PaymentRequestStatus::Expired,
+            3 => PaymentRequestStatus::Cancelled,
+            _ => unreachable!("Invalid variant for PaymentRequestStatus: {}", self),
+        }
+    }
+}
+impl Wire2Api<PaymentSortOrder> for i32 {
+    fn wire2api(self) -> PaymentSortOrder {
+        match self {
+            0 => PaymentSortOrder::Descending,
+            1 => PaymentSortOrder::Ascending,
+            _ => unreachable!("Invalid variant for PaymentSortOrder: {}", self),
+        }
+    }
+}
+impl Wire2Api<PaymentStatus> for i32 {
+    fn wire2api(self) -> PaymentStatus {
+        match self {
+            0 => PaymentStatus::Pending,
+            1 => PaymentStatus::Complete,
+            2 => PaymentStatus::Failed,
+            _ => unreachable!("Invalid variant for PaymentStatus: {}", self),
+        }
+    }
+}
 impl Wire2Api<PaymentTypeFilter> for i32 {
     fn wire2api(self) -> PaymentTypeFilter {
         match self {
@@ -1272,6 +2075,15 @@ impl Wire2Api<SwapAmountType> for i32 {
         }
     }
 }
+impl Wire2Api<SwapScriptVersion> for i32 {
+    fn wire2api(self) -> SwapScriptVersion {
+        match self {
+            0 => SwapScriptVersion::P2wsh,
+            1 => SwapScriptVersion::Taproot,
+            _ => unreachable!("Invalid variant for SwapScriptVersion: {}", self),
+        }
+    }
+}
 
 impl Wire2Api<u16> for u16 {
     fn wire2api(self) -> u16 {
@@ -1296,6 +2108,18 @@ impl Wire2Api<u8> for u8 {
 
 // Section: impl IntoDart
 
+impl support::IntoDart for AccelerateSwapDepositResponse {
+    fn into_dart(self) -> support::DartAbi {
+        vec![self.txid.into_into_dart().into_dart()].into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for AccelerateSwapDepositResponse {}
+impl rust2dart::IntoIntoDart<AccelerateSwapDepositResponse> for AccelerateSwapDepositResponse {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for mirror_AesSuccessActionDataDecrypted {
     fn into_dart(self) -> support::DartAbi {
         vec![
@@ -1334,6 +2158,31 @@ impl rust2dart::IntoIntoDart<mirror_AesSuccessActionDataResult> for AesSuccessAc
     }
 }
 
+impl support::IntoDart for mirror_Amount {
+    fn into_dart(self) -> support::DartAbi {
+        match self.0 {
+            Amount::Bitcoin { amount_msat } => {
+                vec![0.into_dart(), amount_msat.into_into_dart().into_dart()]
+            }
+            Amount::Currency {
+                iso4217_code,
+                fractional_amount,
+            } => vec![
+                1.into_dart(),
+                iso4217_code.into_into_dart().into_dart(),
+                fractional_amount.into_into_dart().into_dart(),
+            ],
+        }
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for mirror_Amount {}
+impl rust2dart::IntoIntoDart<mirror_Amount> for Amount {
+    fn into_into_dart(self) -> mirror_Amount {
+        mirror_Amount(self)
+    }
+}
+
 impl support::IntoDart for BackupFailedData {
     fn into_dart(self) -> support::DartAbi {
         vec![self.error.into_into_dart().into_dart()].into_dart()
@@ -1381,6 +2230,28 @@ impl rust2dart::IntoIntoDart<mirror_BitcoinAddressData> for BitcoinAddressData {
     }
 }
 
+impl support::IntoDart for Bolt12PaymentDetails {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.payment_hash.into_into_dart().into_dart(),
+            self.label.into_into_dart().into_dart(),
+            self.destination_pubkey.into_into_dart().into_dart(),
+            self.payment_preimage.into_into_dart().into_dart(),
+            self.offer.into_into_dart().into_dart(),
+            self.invoice.into_into_dart().into_dart(),
+            self.payer_note.into_dart(),
+            self.pending_expiration_block.into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for Bolt12PaymentDetails {}
+impl rust2dart::IntoIntoDart<Bolt12PaymentDetails> for Bolt12PaymentDetails {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for BreezEvent {
     fn into_dart(self) -> support::DartAbi {
         match self {
@@ -1406,6 +2277,15 @@ impl support::IntoDart for BreezEvent {
             Self::SwapUpdated { details } => {
                 vec![9.into_dart(), details.into_into_dart().into_dart()]
             }
+            Self::SweepSucceeded { details } => {
+                vec![10.into_dart(), details.into_into_dart().into_dart()]
+            }
+            Self::SweepFailed { details } => {
+                vec![11.into_dart(), details.into_into_dart().into_dart()]
+            }
+            Self::InvoiceExpired { details } => {
+                vec![12.into_dart(), details.into_into_dart().into_dart()]
+            }
         }
         .into_dart()
     }
@@ -1417,6 +2297,35 @@ impl rust2dart::IntoIntoDart<BreezEvent> for BreezEvent {
     }
 }
 
+impl support::IntoDart for BudgetPeriod {
+    fn into_dart(self) -> support::DartAbi {
+        match self {
+            Self::Daily => 0,
+            Self::Weekly => 1,
+            Self::Monthly => 2,
+        }
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for BudgetPeriod {}
+impl rust2dart::IntoIntoDart<BudgetPeriod> for BudgetPeriod {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
+impl support::IntoDart for BumpFeeResponse {
+    fn into_dart(self) -> support::DartAbi {
+        vec![self.txid.into_into_dart().into_dart()].into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for BumpFeeResponse {}
+impl rust2dart::IntoIntoDart<BumpFeeResponse> for BumpFeeResponse {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for BuyBitcoinResponse {
     fn into_dart(self) -> support::DartAbi {
         vec![
@@ -1433,6 +2342,33 @@ impl rust2dart::IntoIntoDart<BuyBitcoinResponse> for BuyBitcoinResponse {
     }
 }
 
+impl support::IntoDart for ChainBackend {
+    fn into_dart(self) -> support::DartAbi {
+        match self {
+            Self::MempoolSpace { url } => vec![0.into_dart(), url.into_into_dart().into_dart()],
+            Self::Esplora { url } => vec![1.into_dart(), url.into_into_dart().into_dart()],
+            Self::Electrum { url } => vec![2.into_dart(), url.into_into_dart().into_dart()],
+            Self::Bitcoind {
+                url,
+                rpc_user,
+                rpc_password,
+            } => vec![
+                3.into_dart(),
+                url.into_into_dart().into_dart(),
+                rpc_user.into_into_dart().into_dart(),
+                rpc_password.into_into_dart().into_dart(),
+            ],
+        }
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for ChainBackend {}
+impl rust2dart::IntoIntoDart<ChainBackend> for ChainBackend {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for ChannelState {
     fn into_dart(self) -> support::DartAbi {
         match self {
@@ -1487,6 +2423,7 @@ impl support::IntoDart for Config {
             self.breezserver.into_into_dart().into_dart(),
             self.chainnotifier_url.into_into_dart().into_dart(),
             self.mempoolspace_url.into_dart(),
+            self.chain_backends.into_into_dart().into_dart(),
             self.working_dir.into_into_dart().into_dart(),
             self.network.into_into_dart().into_dart(),
             self.payment_timeout_sec.into_into_dart().into_dart(),
@@ -1495,6 +2432,7 @@ impl support::IntoDart for Config {
             self.maxfee_percent.into_into_dart().into_dart(),
             self.exemptfee_msat.into_into_dart().into_dart(),
             self.node_config.into_into_dart().into_dart(),
+            self.custom_lsps.into_into_dart().into_dart(),
         ]
         .into_dart()
     }
@@ -1506,6 +2444,18 @@ impl rust2dart::IntoIntoDart<Config> for Config {
     }
 }
 
+impl support::IntoDart for CreateOfferResponse {
+    fn into_dart(self) -> support::DartAbi {
+        vec![self.offer.into_into_dart().into_dart()].into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for CreateOfferResponse {}
+impl rust2dart::IntoIntoDart<CreateOfferResponse> for CreateOfferResponse {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for mirror_CurrencyInfo {
     fn into_dart(self) -> support::DartAbi {
         vec![
@@ -1527,6 +2477,54 @@ impl rust2dart::IntoIntoDart<mirror_CurrencyInfo> for CurrencyInfo {
     }
 }
 
+impl support::IntoDart for CustomLsp {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.id.into_into_dart().into_dart(),
+            self.name.into_into_dart().into_dart(),
+            self.pubkey.into_into_dart().into_dart(),
+            self.host.into_into_dart().into_dart(),
+            self.protocols.into_into_dart().into_dart(),
+            self.token.into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for CustomLsp {}
+impl rust2dart::IntoIntoDart<CustomLsp> for CustomLsp {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
+impl support::IntoDart for ExportPaymentsResponse {
+    fn into_dart(self) -> support::DartAbi {
+        vec![self.content.into_into_dart().into_dart()].into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for ExportPaymentsResponse {}
+impl rust2dart::IntoIntoDart<ExportPaymentsResponse> for ExportPaymentsResponse {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
+impl support::IntoDart for FiatAmount {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.amount.into_into_dart().into_dart(),
+            self.rate.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for FiatAmount {}
+impl rust2dart::IntoIntoDart<FiatAmount> for FiatAmount {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for mirror_FiatCurrency {
     fn into_dart(self) -> support::DartAbi {
         vec![
@@ -1613,17 +2611,20 @@ impl support::IntoDart for mirror_InputType {
             InputType::Bolt11 { invoice } => {
                 vec![1.into_dart(), invoice.into_into_dart().into_dart()]
             }
+            InputType::Bolt12Offer { offer } => {
+                vec![2.into_dart(), offer.into_into_dart().into_dart()]
+            }
             InputType::NodeId { node_id } => {
-                vec![2.into_dart(), node_id.into_into_dart().into_dart()]
+                vec![3.into_dart(), node_id.into_into_dart().into_dart()]
             }
-            InputType::Url { url } => vec![3.into_dart(), url.into_into_dart().into_dart()],
-            InputType::LnUrlPay { data } => vec![4.into_dart(), data.into_into_dart().into_dart()],
+            InputType::Url { url } => vec![4.into_dart(), url.into_into_dart().into_dart()],
+            InputType::LnUrlPay { data } => vec![5.into_dart(), data.into_into_dart().into_dart()],
             InputType::LnUrlWithdraw { data } => {
-                vec![5.into_dart(), data.into_into_dart().into_dart()]
+                vec![6.into_dart(), data.into_into_dart().into_dart()]
             }
-            InputType::LnUrlAuth { data } => vec![6.into_dart(), data.into_into_dart().into_dart()],
+            InputType::LnUrlAuth { data } => vec![7.into_dart(), data.into_into_dart().into_dart()],
             InputType::LnUrlError { data } => {
-                vec![7.into_dart(), data.into_into_dart().into_dart()]
+                vec![8.into_dart(), data.into_into_dart().into_dart()]
             }
         }
         .into_dart()
@@ -1653,6 +2654,23 @@ impl rust2dart::IntoIntoDart<InvoicePaidDetails> for InvoicePaidDetails {
     }
 }
 
+impl support::IntoDart for LightningSweepPolicy {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.to_address.into_into_dart().into_dart(),
+            self.max_channels_balance_sat.into_into_dart().into_dart(),
+            self.max_sat_per_vbyte.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for LightningSweepPolicy {}
+impl rust2dart::IntoIntoDart<LightningSweepPolicy> for LightningSweepPolicy {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for mirror_LNInvoice {
     fn into_dart(self) -> support::DartAbi {
         vec![
@@ -1682,6 +2700,28 @@ impl rust2dart::IntoIntoDart<mirror_LNInvoice> for LNInvoice {
     }
 }
 
+impl support::IntoDart for mirror_LNOffer {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.0.offer.into_into_dart().into_dart(),
+            self.0.chains.into_into_dart().into_dart(),
+            self.0.min_amount.map(|v| mirror_Amount(v)).into_dart(),
+            self.0.description.into_into_dart().into_dart(),
+            self.0.absolute_expiry.into_dart(),
+            self.0.issuer.into_dart(),
+            self.0.signing_pubkey.into_into_dart().into_dart(),
+            self.0.paths_count.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for mirror_LNOffer {}
+impl rust2dart::IntoIntoDart<mirror_LNOffer> for LNOffer {
+    fn into_into_dart(self) -> mirror_LNOffer {
+        mirror_LNOffer(self)
+    }
+}
+
 impl support::IntoDart for LnPaymentDetails {
     fn into_dart(self) -> support::DartAbi {
         vec![
@@ -1700,6 +2740,7 @@ impl support::IntoDart for LnPaymentDetails {
             self.ln_address.into_dart(),
             self.lnurl_metadata.into_dart(),
             self.lnurl_withdraw_endpoint.into_dart(),
+            self.lnurl_verify_url.into_dart(),
             self.swap_info.into_dart(),
             self.reverse_swap_info.into_dart(),
             self.pending_expiration_block.into_dart(),
@@ -1820,6 +2861,22 @@ impl rust2dart::IntoIntoDart<LnUrlPayResult> for LnUrlPayResult {
     }
 }
 
+impl support::IntoDart for LnUrlPayServerResponse {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.status_code.into_into_dart().into_dart(),
+            self.body.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for LnUrlPayServerResponse {}
+impl rust2dart::IntoIntoDart<LnUrlPayServerResponse> for LnUrlPayServerResponse {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for LnUrlPaySuccessData {
     fn into_dart(self) -> support::DartAbi {
         vec![
@@ -1838,6 +2895,24 @@ impl rust2dart::IntoIntoDart<LnUrlPaySuccessData> for LnUrlPaySuccessData {
     }
 }
 
+impl support::IntoDart for mirror_LnUrlVerifyStatus {
+    fn into_dart(self) -> support::DartAbi {
+        match self.0 {
+            LnUrlVerifyStatus::Settled { preimage } => {
+                vec![0.into_dart(), preimage.into_into_dart().into_dart()]
+            }
+            LnUrlVerifyStatus::Pending => vec![1.into_dart()],
+        }
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for mirror_LnUrlVerifyStatus {}
+impl rust2dart::IntoIntoDart<mirror_LnUrlVerifyStatus> for LnUrlVerifyStatus {
+    fn into_into_dart(self) -> mirror_LnUrlVerifyStatus {
+        mirror_LnUrlVerifyStatus(self)
+    }
+}
+
 impl support::IntoDart for mirror_LnUrlWithdrawRequestData {
     fn into_dart(self) -> support::DartAbi {
         vec![
@@ -1846,6 +2921,8 @@ impl support::IntoDart for mirror_LnUrlWithdrawRequestData {
             self.0.default_description.into_into_dart().into_dart(),
             self.0.min_withdrawable.into_into_dart().into_dart(),
             self.0.max_withdrawable.into_into_dart().into_dart(),
+            self.0.balance_check.into_dart(),
+            self.0.pay_link.into_dart(),
         ]
         .into_dart()
     }
@@ -1882,7 +2959,14 @@ impl rust2dart::IntoIntoDart<mirror_LnUrlWithdrawResult> for LnUrlWithdrawResult
 
 impl support::IntoDart for mirror_LnUrlWithdrawSuccessData {
     fn into_dart(self) -> support::DartAbi {
-        vec![self.0.invoice.into_into_dart().into_dart()].into_dart()
+        vec![
+            self.0.invoice.into_into_dart().into_dart(),
+            self.0
+                .refreshed_request
+                .map(|v| mirror_LnUrlWithdrawRequestData(v))
+                .into_dart(),
+        ]
+        .into_dart()
     }
 }
 impl support::IntoDartExceptPrimitive for mirror_LnUrlWithdrawSuccessData {}
@@ -1925,6 +3009,24 @@ impl rust2dart::IntoIntoDart<mirror_LocalizedName> for LocalizedName {
     }
 }
 
+impl support::IntoDart for LockedFiatRate {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.id.into_into_dart().into_dart(),
+            self.currency.into_into_dart().into_dart(),
+            self.value.into_into_dart().into_dart(),
+            self.locked_at.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for LockedFiatRate {}
+impl rust2dart::IntoIntoDart<LockedFiatRate> for LockedFiatRate {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for LogEntry {
     fn into_dart(self) -> support::DartAbi {
         vec![
@@ -2012,6 +3114,18 @@ impl support::IntoDart for NodeConfig {
     fn into_dart(self) -> support::DartAbi {
         match self {
             Self::Greenlight { config } => vec![0.into_dart(), config.into_into_dart().into_dart()],
+            Self::Cln {
+                grpc_url,
+                ca_cert,
+                client_cert,
+                client_key,
+            } => vec![
+                1.into_dart(),
+                grpc_url.into_into_dart().into_dart(),
+                ca_cert.into_into_dart().into_dart(),
+                client_cert.into_into_dart().into_dart(),
+                client_key.into_into_dart().into_dart(),
+            ],
         }
         .into_dart()
     }
@@ -2092,6 +3206,23 @@ impl rust2dart::IntoIntoDart<OnchainPaymentLimitsResponse> for OnchainPaymentLim
     }
 }
 
+impl support::IntoDart for OnchainSweepPolicy {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.to_address.into_into_dart().into_dart(),
+            self.max_onchain_balance_sat.into_into_dart().into_dart(),
+            self.max_sat_per_vbyte.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for OnchainSweepPolicy {}
+impl rust2dart::IntoIntoDart<OnchainSweepPolicy> for OnchainSweepPolicy {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for OpenChannelFeeResponse {
     fn into_dart(self) -> support::DartAbi {
         vec![
@@ -2165,6 +3296,10 @@ impl support::IntoDart for Payment {
             self.description.into_dart(),
             self.details.into_into_dart().into_dart(),
             self.metadata.into_dart(),
+            self.note.into_dart(),
+            self.tags.into_into_dart().into_dart(),
+            self.category.into_dart(),
+            self.fiat_amount.into_dart(),
         ]
         .into_dart()
     }
@@ -2176,11 +3311,45 @@ impl rust2dart::IntoIntoDart<Payment> for Payment {
     }
 }
 
+impl support::IntoDart for PaymentApprovalEvent {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.id.into_into_dart().into_dart(),
+            self.wallet_id.into_into_dart().into_dart(),
+            self.request.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for PaymentApprovalEvent {}
+impl rust2dart::IntoIntoDart<PaymentApprovalEvent> for PaymentApprovalEvent {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
+impl support::IntoDart for PaymentApprovalRequest {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.amount_msat.into_into_dart().into_dart(),
+            self.destination.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for PaymentApprovalRequest {}
+impl rust2dart::IntoIntoDart<PaymentApprovalRequest> for PaymentApprovalRequest {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for PaymentDetails {
     fn into_dart(self) -> support::DartAbi {
         match self {
             Self::Ln { data } => vec![0.into_dart(), data.into_into_dart().into_dart()],
             Self::ClosedChannel { data } => vec![1.into_dart(), data.into_into_dart().into_dart()],
+            Self::Bolt12 { data } => vec![2.into_dart(), data.into_into_dart().into_dart()],
         }
         .into_dart()
     }
@@ -2210,6 +3379,48 @@ impl rust2dart::IntoIntoDart<PaymentFailedData> for PaymentFailedData {
     }
 }
 
+impl support::IntoDart for PaymentRequest {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.payment_hash.into_into_dart().into_dart(),
+            self.bolt11.into_into_dart().into_dart(),
+            self.description.into_into_dart().into_dart(),
+            self.amount_msat.into_into_dart().into_dart(),
+            self.opening_fee_params.into_dart(),
+            self.opening_fee_msat.into_dart(),
+            self.status.into_into_dart().into_dart(),
+            self.created_at.into_into_dart().into_dart(),
+            self.expires_at.into_into_dart().into_dart(),
+            self.paid_at.into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for PaymentRequest {}
+impl rust2dart::IntoIntoDart<PaymentRequest> for PaymentRequest {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
+impl support::IntoDart for PaymentRequestStatus {
+    fn into_dart(self) -> support::DartAbi {
+        match self {
+            Self::Open => 0,
+            Self::Paid => 1,
+            Self::Expired => 2,
+            Self::Cancelled => 3,
+        }
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for PaymentRequestStatus {}
+impl rust2dart::IntoIntoDart<PaymentRequestStatus> for PaymentRequestStatus {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for PaymentStatus {
     fn into_dart(self) -> support::DartAbi {
         match self {
@@ -2244,6 +3455,41 @@ impl rust2dart::IntoIntoDart<PaymentType> for PaymentType {
     }
 }
 
+impl support::IntoDart for PendingSpendingBudget {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.budget.into_into_dart().into_dart(),
+            self.applies_at.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for PendingSpendingBudget {}
+impl rust2dart::IntoIntoDart<PendingSpendingBudget> for PendingSpendingBudget {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
+impl support::IntoDart for PrepareAccelerateSwapDepositResponse {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.source.into_into_dart().into_dart(),
+            self.child_tx_weight.into_into_dart().into_dart(),
+            self.child_tx_fee_sat.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for PrepareAccelerateSwapDepositResponse {}
+impl rust2dart::IntoIntoDart<PrepareAccelerateSwapDepositResponse>
+    for PrepareAccelerateSwapDepositResponse
+{
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for PrepareOnchainPaymentResponse {
     fn into_dart(self) -> support::DartAbi {
         vec![
@@ -2299,6 +3545,28 @@ impl rust2dart::IntoIntoDart<PrepareRefundResponse> for PrepareRefundResponse {
     }
 }
 
+impl support::IntoDart for PrepareSendPaymentResponse {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.amount_msat.into_into_dart().into_dart(),
+            self.payable.into_into_dart().into_dart(),
+            self.probed.into_into_dart().into_dart(),
+            self.max_sendable_msat.into_into_dart().into_dart(),
+            self.parts.into_into_dart().into_dart(),
+            self.min_fee_msat.into_into_dart().into_dart(),
+            self.max_fee_msat.into_into_dart().into_dart(),
+            self.fee_limit_msat.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for PrepareSendPaymentResponse {}
+impl rust2dart::IntoIntoDart<PrepareSendPaymentResponse> for PrepareSendPaymentResponse {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for mirror_Rate {
     fn into_dart(self) -> support::DartAbi {
         vec![
@@ -2519,6 +3787,39 @@ impl rust2dart::IntoIntoDart<SignMessageResponse> for SignMessageResponse {
     }
 }
 
+impl support::IntoDart for SpendingBudget {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.max_payment_msat.into_dart(),
+            self.limits.into_into_dart().into_dart(),
+            self.approval_threshold_msat.into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for SpendingBudget {}
+impl rust2dart::IntoIntoDart<SpendingBudget> for SpendingBudget {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
+impl support::IntoDart for SpendingLimit {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.period.into_into_dart().into_dart(),
+            self.max_amount_msat.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for SpendingLimit {}
+impl rust2dart::IntoIntoDart<SpendingLimit> for SpendingLimit {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for StaticBackupResponse {
     fn into_dart(self) -> support::DartAbi {
         vec![self.backup.into_dart()].into_dart()
@@ -2554,6 +3855,22 @@ impl rust2dart::IntoIntoDart<mirror_SuccessActionProcessed> for SuccessActionPro
     }
 }
 
+impl support::IntoDart for SwapDepositAccelerationSource {
+    fn into_dart(self) -> support::DartAbi {
+        match self {
+            Self::NodeFunds => 0,
+            Self::SwapOutput => 1,
+        }
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for SwapDepositAccelerationSource {}
+impl rust2dart::IntoIntoDart<SwapDepositAccelerationSource> for SwapDepositAccelerationSource {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for SwapInfo {
     fn into_dart(self) -> support::DartAbi {
         vec![
@@ -2566,6 +3883,7 @@ impl support::IntoDart for SwapInfo {
             self.public_key.into_into_dart().into_dart(),
             self.swapper_public_key.into_into_dart().into_dart(),
             self.script.into_into_dart().into_dart(),
+            self.script_version.into_into_dart().into_dart(),
             self.bolt11.into_dart(),
             self.paid_msat.into_into_dart().into_dart(),
             self.total_incoming_txs.into_into_dart().into_dart(),
@@ -2592,6 +3910,22 @@ impl rust2dart::IntoIntoDart<SwapInfo> for SwapInfo {
     }
 }
 
+impl support::IntoDart for SwapScriptVersion {
+    fn into_dart(self) -> support::DartAbi {
+        match self {
+            Self::P2wsh => 0,
+            Self::Taproot => 1,
+        }
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for SwapScriptVersion {}
+impl rust2dart::IntoIntoDart<SwapScriptVersion> for SwapScriptVersion {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for SwapStatus {
     fn into_dart(self) -> support::DartAbi {
         match self {
@@ -2612,6 +3946,68 @@ impl rust2dart::IntoIntoDart<SwapStatus> for SwapStatus {
     }
 }
 
+impl support::IntoDart for SweepDetails {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.kind.into_into_dart().into_dart(),
+            self.amount_sat.into_into_dart().into_dart(),
+            self.sat_per_vbyte.into_into_dart().into_dart(),
+            self.id.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for SweepDetails {}
+impl rust2dart::IntoIntoDart<SweepDetails> for SweepDetails {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
+impl support::IntoDart for SweepFailedData {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.kind.into_into_dart().into_dart(),
+            self.error.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for SweepFailedData {}
+impl rust2dart::IntoIntoDart<SweepFailedData> for SweepFailedData {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
+impl support::IntoDart for SweepKind {
+    fn into_dart(self) -> support::DartAbi {
+        match self {
+            Self::Onchain => 0,
+            Self::Lightning => 1,
+        }
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for SweepKind {}
+impl rust2dart::IntoIntoDart<SweepKind> for SweepKind {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
+impl support::IntoDart for SweepPolicy {
+    fn into_dart(self) -> support::DartAbi {
+        vec![self.onchain.into_dart(), self.lightning.into_dart()].into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for SweepPolicy {}
+impl rust2dart::IntoIntoDart<SweepPolicy> for SweepPolicy {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for mirror_Symbol {
     fn into_dart(self) -> support::DartAbi {
         vec![
@@ -2665,6 +4061,22 @@ impl rust2dart::IntoIntoDart<mirror_UrlSuccessActionData> for UrlSuccessActionDa
     }
 }
 
+impl support::IntoDart for WalletEvent {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.wallet_id.into_into_dart().into_dart(),
+            self.event.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for WalletEvent {}
+impl rust2dart::IntoIntoDart<WalletEvent> for WalletEvent {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 // Section: executor
 
 support::lazy_static! {
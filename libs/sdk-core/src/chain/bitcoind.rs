@@ -0,0 +1,569 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use sdk_common::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::sync::OnceCell;
+
+use super::electrum::to_onchain_tx;
+use super::{
+    recommended_fees_from_estimates, ChainService, OnchainTx, Outspend, RecommendedFees, TxStatus,
+};
+use crate::bitcoin::consensus::deserialize;
+use crate::bitcoin::{Address, Network, OutPoint, Transaction, Txid};
+use crate::error::{SdkError, SdkResult};
+
+/// The watch-only wallet in which the addresses queried by the SDK are imported
+const WATCH_WALLET_NAME: &str = "breez-sdk-watch";
+const LIST_TRANSACTIONS_COUNT: u32 = 10_000;
+/// The confirmation targets of the recommended fees
+const FEE_TARGETS: [u16; 5] = [1, 3, 6, 144, 1008];
+const RPC_WALLET_NOT_FOUND: i64 = -18;
+const RPC_WALLET_ALREADY_LOADED: i64 = -35;
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    result: Value,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct AddressInfo {
+    ismine: bool,
+    #[serde(default)]
+    iswatchonly: bool,
+    #[serde(default)]
+    labels: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct DescriptorInfo {
+    descriptor: String,
+}
+
+#[derive(Deserialize)]
+struct ImportResult {
+    success: bool,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct ListTransactionsEntry {
+    txid: String,
+    category: String,
+    confirmations: i64,
+    blockhash: Option<String>,
+    blockheight: Option<u32>,
+    blocktime: Option<u64>,
+}
+
+impl ListTransactionsEntry {
+    fn status(&self) -> TxStatus {
+        match self.confirmations > 0 {
+            true => TxStatus {
+                confirmed: true,
+                block_height: self.blockheight,
+                block_hash: self.blockhash.clone(),
+                block_time: self.blocktime,
+            },
+            false => TxStatus {
+                confirmed: false,
+                block_height: None,
+                block_hash: None,
+                block_time: None,
+            },
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct WalletTransaction {
+    hex: String,
+}
+
+#[derive(Deserialize)]
+struct FeeEstimate {
+    /// In BTC/kvB, not set if there isn't enough data to estimate the fee
+    feerate: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct NetworkInfo {
+    /// In BTC/kvB
+    relayfee: f64,
+}
+
+/// A Bitcoin Core node, reached through its JSON-RPC interface.
+///
+/// Bitcoin Core has no address index, so the addresses queried by the SDK are imported in a
+/// dedicated watch-only descriptor wallet, which is created if needed. Each address is labelled
+/// with itself, so that its transactions can be listed by label. Importing an address rescans the
+/// chain, which is only practical on regtest or small chains. The node needs `-txindex` to look up
+/// transactions that aren't in the wallet.
+///
+/// The spending transactions of outputs aren't indexed either: they are only found for the
+/// outputs of the watched addresses, as the wallet tracks their spends.
+pub(crate) struct Bitcoind {
+    url: String,
+    rpc_user: String,
+    rpc_password: String,
+    network: Network,
+    watch_wallet: OnceCell<()>,
+    watched_addresses: Mutex<HashSet<String>>,
+    /// The wallet transactions already fetched, by txid
+    wallet_txs: Mutex<HashMap<Txid, Transaction>>,
+}
+
+impl Bitcoind {
+    pub(crate) fn new(url: &str, rpc_user: String, rpc_password: String, network: Network) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            rpc_user,
+            rpc_password,
+            network,
+            watch_wallet: OnceCell::new(),
+            watched_addresses: Mutex::new(HashSet::new()),
+            wallet_txs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn call(
+        &self,
+        path: &str,
+        method: &str,
+        params: Value,
+    ) -> SdkResult<Result<Value, RpcError>> {
+        let res = get_reqwest_client()?
+            .post(format!("{}{path}", self.url))
+            .basic_auth(&self.rpc_user, Some(&self.rpc_password))
+            .json(&json!({
+                "jsonrpc": "1.0",
+                "id": "breez-sdk",
+                "method": method,
+                "params": params,
+            }))
+            .send()
+            .await
+            .map_err(|e| SdkError::ServiceConnectivity {
+                err: format!("bitcoind call {method} failed: {e}"),
+            })?;
+        // Bitcoin Core replies to failed calls with an error status and the error in the body
+        let body = res
+            .text()
+            .await
+            .map_err(|e| SdkError::ServiceConnectivity {
+                err: format!("bitcoind call {method} failed: {e}"),
+            })?;
+        trace!("bitcoind call {method} response: {body}");
+        let res: RpcResponse =
+            serde_json::from_str(&body).map_err(|e| SdkError::ServiceConnectivity {
+                err: format!("Failed to parse bitcoind {method} response: {e}"),
+            })?;
+        Ok(match res.error {
+            Some(error) => Err(error),
+            None => Ok(res.result),
+        })
+    }
+
+    /// Calls a node RPC
+    async fn rpc<T: DeserializeOwned>(&self, method: &str, params: Value) -> SdkResult<T> {
+        parse_result(method, self.call("", method, params).await?)
+    }
+
+    /// Calls an RPC of the watch-only wallet
+    async fn wallet_rpc<T: DeserializeOwned>(&self, method: &str, params: Value) -> SdkResult<T> {
+        self.watch_wallet
+            .get_or_try_init(|| self.load_watch_wallet())
+            .await?;
+        let path = format!("/wallet/{WATCH_WALLET_NAME}");
+        parse_result(method, self.call(&path, method, params).await?)
+    }
+
+    async fn load_watch_wallet(&self) -> SdkResult<()> {
+        match self
+            .call("", "loadwallet", json!([WATCH_WALLET_NAME]))
+            .await?
+        {
+            Ok(_) => Ok(()),
+            Err(e) if e.code == RPC_WALLET_ALREADY_LOADED => Ok(()),
+            Err(e) if e.code == RPC_WALLET_NOT_FOUND => {
+                info!("Creating the bitcoind watch-only wallet {WATCH_WALLET_NAME}");
+                // Arguments: name, disable_private_keys, blank, passphrase, avoid_reuse,
+                // descriptors, load_on_startup
+                self.rpc::<Value>(
+                    "createwallet",
+                    json!([WATCH_WALLET_NAME, true, true, "", false, true, true]),
+                )
+                .await?;
+                Ok(())
+            }
+            Err(e) => parse_result("loadwallet", Err(e)),
+        }
+    }
+
+    /// Imports the address in the watch-only wallet, labelled with itself, if it isn't already
+    async fn watch_address(&self, address: &str) -> SdkResult<()> {
+        if self.watched_addresses.lock().unwrap().contains(address) {
+            return Ok(());
+        }
+
+        let info: AddressInfo = self.wallet_rpc("getaddressinfo", json!([address])).await?;
+        if !info.ismine && !info.iswatchonly {
+            debug!("Importing {address} in the bitcoind watch-only wallet");
+            let descriptor: DescriptorInfo = self
+                .rpc("getdescriptorinfo", json!([format!("addr({address})")]))
+                .await?;
+            let results: Vec<ImportResult> = self
+                .wallet_rpc(
+                    "importdescriptors",
+                    json!([[{
+                        "desc": descriptor.descriptor,
+                        "timestamp": 0,
+                        "label": address,
+                    }]]),
+                )
+                .await?;
+            for result in results {
+                if !result.success {
+                    return Err(SdkError::Generic {
+                        err: format!(
+                            "Failed to import {address} in bitcoind: {}",
+                            result.error.map(|e| e.message).unwrap_or_default()
+                        ),
+                    });
+                }
+            }
+        } else if !info.labels.iter().any(|label| label == address) {
+            // The address was imported without its label, e.g. by hand
+            self.wallet_rpc::<Value>("setlabel", json!([address, address]))
+                .await?;
+        }
+        self.watched_addresses
+            .lock()
+            .unwrap()
+            .insert(address.to_string());
+        Ok(())
+    }
+
+    /// The wallet transactions listed with the label, or all of them with `*`
+    async fn list_transactions(&self, label: &str) -> SdkResult<Vec<ListTransactionsEntry>> {
+        self.wallet_rpc(
+            "listtransactions",
+            json!([label, LIST_TRANSACTIONS_COUNT, 0, true]),
+        )
+        .await
+    }
+
+    /// Gets a wallet transaction. Transactions don't change once fetched, so they are cached.
+    async fn wallet_transaction(&self, txid: &str) -> SdkResult<Transaction> {
+        let tx_id = parse_txid(txid)?;
+        if let Some(tx) = self.wallet_txs.lock().unwrap().get(&tx_id) {
+            return Ok(tx.clone());
+        }
+        let wallet_tx: WalletTransaction = self
+            .wallet_rpc("gettransaction", json!([txid, true]))
+            .await?;
+        let tx = decode_transaction(&wallet_tx.hex)?;
+        self.wallet_txs.lock().unwrap().insert(tx_id, tx.clone());
+        Ok(tx)
+    }
+
+    /// The wallet transactions spending outputs of the watched addresses
+    async fn spending_transactions(&self) -> SdkResult<Vec<(Transaction, TxStatus)>> {
+        let mut txs = vec![];
+        let mut seen_txids = HashSet::new();
+        for entry in self.list_transactions("*").await? {
+            if entry.category != "send" || !seen_txids.insert(entry.txid.clone()) {
+                continue;
+            }
+            txs.push((self.wallet_transaction(&entry.txid).await?, entry.status()));
+        }
+        Ok(txs)
+    }
+
+    async fn raw_transaction(&self, txid: &Txid) -> SdkResult<Transaction> {
+        let hex: String = self
+            .rpc("getrawtransaction", json!([txid.to_string(), false]))
+            .await?;
+        decode_transaction(&hex)
+    }
+}
+
+#[tonic::async_trait]
+impl ChainService for Bitcoind {
+    async fn recommended_fees(&self) -> SdkResult<RecommendedFees> {
+        let mut estimates = HashMap::new();
+        for target in FEE_TARGETS {
+            let estimate: FeeEstimate = self.rpc("estimatesmartfee", json!([target])).await?;
+            if let Some(feerate) = estimate.feerate {
+                estimates.insert(target, feerate * 100_000.0);
+            }
+        }
+        let network_info: NetworkInfo = self.rpc("getnetworkinfo", json!([])).await?;
+        Ok(recommended_fees(
+            &estimates,
+            network_info.relayfee * 100_000.0,
+        ))
+    }
+
+    async fn address_transactions(&self, address: String) -> SdkResult<Vec<OnchainTx>> {
+        self.watch_address(&address).await?;
+        let script = Address::from_str(&address)
+            .map_err(|e| SdkError::Generic {
+                err: format!("Invalid address {address}: {e}"),
+            })?
+            .script_pubkey();
+
+        // Listing by label only returns the transactions received by the address
+        let mut txs: Vec<(Transaction, TxStatus)> = vec![];
+        let mut seen_txids = HashSet::new();
+        for entry in self.list_transactions(&address).await? {
+            if entry.category == "send" || !seen_txids.insert(entry.txid.clone()) {
+                continue;
+            }
+            txs.push((self.wallet_transaction(&entry.txid).await?, entry.status()));
+        }
+
+        // The transactions sent from the address are the ones spending its outputs
+        let outpoints: HashSet<OutPoint> = txs
+            .iter()
+            .flat_map(|(tx, _)| {
+                let txid = tx.txid();
+                tx.output
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, output)| output.script_pubkey == script)
+                    .map(move |(vout, _)| OutPoint::new(txid, vout as u32))
+            })
+            .collect();
+        if !outpoints.is_empty() {
+            for (tx, status) in self.spending_transactions().await? {
+                let spends_address = tx
+                    .input
+                    .iter()
+                    .any(|input| outpoints.contains(&input.previous_output));
+                if spends_address && seen_txids.insert(tx.txid().to_string()) {
+                    txs.push((tx, status));
+                }
+            }
+        }
+
+        // The outputs spent by the wallet transactions are either in the wallet, or only needed
+        // for the fees
+        let mut prev_txs: HashMap<Txid, Transaction> =
+            txs.iter().map(|(tx, _)| (tx.txid(), tx.clone())).collect();
+        let missing_prev_txids: HashSet<Txid> = txs
+            .iter()
+            .filter(|(tx, _)| !tx.is_coin_base())
+            .flat_map(|(tx, _)| tx.input.iter().map(|input| input.previous_output.txid))
+            .filter(|txid| !prev_txs.contains_key(txid))
+            .collect();
+        for txid in missing_prev_txids {
+            match self.raw_transaction(&txid).await {
+                Ok(tx) => {
+                    prev_txs.insert(txid, tx);
+                }
+                Err(e) => debug!("Could not fetch transaction {txid}: {e}"),
+            }
+        }
+
+        let mut onchain_txs: Vec<OnchainTx> = txs
+            .into_iter()
+            .map(|(tx, status)| to_onchain_tx(&tx, status, &prev_txs, self.network))
+            .collect();
+        // Same order as mempool.space: mempool transactions first, then newest first
+        onchain_txs.sort_by_key(|tx| match tx.status.block_height {
+            Some(height) => -(height as i64),
+            None => i64::MIN,
+        });
+        Ok(onchain_txs)
+    }
+
+    async fn current_tip(&self) -> SdkResult<u32> {
+        self.rpc("getblockcount", json!([])).await
+    }
+
+    /// Fails if an output is spent by a transaction that isn't in the watch-only wallet, as
+    /// bitcoind can't find it
+    async fn transaction_outspends(&self, txid: String) -> SdkResult<Vec<Outspend>> {
+        let tx_id = parse_txid(&txid)?;
+        let tx = self.raw_transaction(&tx_id).await?;
+        let mut spent_outputs = Vec::new();
+        for vout in 0..tx.output.len() {
+            // Including the mempool, so that outputs spent by unconfirmed transactions are spent
+            let txout: Option<Value> = self.rpc("gettxout", json!([txid, vout, true])).await?;
+            spent_outputs.push(txout.is_none());
+        }
+        let spending_txs = match spent_outputs.contains(&true) {
+            true => self.spending_transactions().await?,
+            false => vec![],
+        };
+
+        let mut outspends = Vec::new();
+        for (vout, spent) in spent_outputs.into_iter().enumerate() {
+            if !spent {
+                outspends.push(Outspend {
+                    spent: false,
+                    txid: None,
+                    vin: None,
+                    status: None,
+                });
+                continue;
+            }
+
+            let outpoint = OutPoint::new(tx_id, vout as u32);
+            let (spending_tx, vin, status) = spending_txs
+                .iter()
+                .find_map(|(tx, status)| {
+                    tx.input
+                        .iter()
+                        .position(|input| input.previous_output == outpoint)
+                        .map(|vin| (tx, vin, status))
+                })
+                .ok_or(SdkError::Generic {
+                    err: format!("The transaction spending {outpoint} isn't known to bitcoind"),
+                })?;
+            outspends.push(Outspend {
+                spent: true,
+                txid: Some(spending_tx.txid().to_string()),
+                vin: Some(vin as u32),
+                status: Some(status.clone()),
+            });
+        }
+        Ok(outspends)
+    }
+
+    async fn broadcast_transaction(&self, tx: Vec<u8>) -> SdkResult<String> {
+        self.rpc("sendrawtransaction", json!([hex::encode(tx)]))
+            .await
+    }
+}
+
+fn parse_result<T: DeserializeOwned>(method: &str, res: Result<Value, RpcError>) -> SdkResult<T> {
+    match res {
+        Ok(result) => serde_json::from_value(result).map_err(|e| SdkError::ServiceConnectivity {
+            err: format!("Failed to parse bitcoind {method} result: {e}"),
+        }),
+        Err(e) => Err(SdkError::Generic {
+            err: format!(
+                "bitcoind call {method} failed with code {}: {}",
+                e.code, e.message
+            ),
+        }),
+    }
+}
+
+fn parse_txid(txid: &str) -> SdkResult<Txid> {
+    txid.parse().map_err(|e| SdkError::Generic {
+        err: format!("Invalid txid {txid}: {e}"),
+    })
+}
+
+fn decode_transaction(hex: &str) -> SdkResult<Transaction> {
+    hex::decode(hex)
+        .ok()
+        .and_then(|bytes| deserialize(&bytes).ok())
+        .ok_or(SdkError::Generic {
+            err: format!("Invalid transaction returned by bitcoind: {hex}"),
+        })
+}
+
+/// The recommended fees from the estimates, in sat/vByte. Regtest nodes usually don't have enough
+/// transactions to estimate fees, in which case the relay fee is used.
+fn recommended_fees(estimates: &HashMap<u16, f64>, relay_fee: f64) -> RecommendedFees {
+    let minimum_fee = (relay_fee.ceil() as u64).max(1);
+    match recommended_fees_from_estimates(estimates) {
+        Ok(fees) => RecommendedFees {
+            minimum_fee,
+            ..fees
+        },
+        Err(_) => RecommendedFees {
+            fastest_fee: minimum_fee,
+            half_hour_fee: minimum_fee,
+            hour_fee: minimum_fee,
+            economy_fee: minimum_fee,
+            minimum_fee,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use crate::chain::bitcoind::*;
+
+    #[test]
+    fn test_recommended_fees() {
+        let fees = recommended_fees(&HashMap::new(), 1.0);
+        assert_eq!(fees.fastest_fee, 1);
+        assert_eq!(fees.economy_fee, 1);
+
+        let fees = recommended_fees(&HashMap::from([(1, 12.3), (6, 4.0)]), 1.0);
+        assert_eq!(fees.fastest_fee, 13);
+        assert_eq!(fees.half_hour_fee, 13);
+        assert_eq!(fees.hour_fee, 4);
+        assert_eq!(fees.minimum_fee, 1);
+    }
+
+    #[test]
+    fn test_entry_status() {
+        let entries: Vec<ListTransactionsEntry> = parse_result(
+            "listtransactions",
+            Ok(json!([
+                {
+                    "address": "bcrt1qkd9hm2qwvck3mvlul035kl6v4nz04s6dh0hpv4",
+                    "category": "receive",
+                    "amount": 0.001,
+                    "label": "bcrt1qkd9hm2qwvck3mvlul035kl6v4nz04s6dh0hpv4",
+                    "confirmations": 2,
+                    "blockhash": "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206",
+                    "blockheight": 101,
+                    "blocktime": 1_700_000_000,
+                    "txid": "ec901bcab07df7d475d98fff2933dcb56d57bbdaa029c4142aed93462b6928fe",
+                },
+                {
+                    "category": "send",
+                    "confirmations": 0,
+                    "txid": "a2ea4c3df4b4e8b9d7e0c0e7b1f7ee0d6f35c2fbd6e7e0d1f0e1e2e3e4e5e6e7",
+                },
+            ])),
+        )
+        .unwrap();
+        let status = entries[0].status();
+        assert!(status.confirmed);
+        assert_eq!(status.block_height, Some(101));
+        assert_eq!(entries[1].category, "send");
+        assert!(!entries[1].status().confirmed);
+        assert_eq!(entries[1].status().block_height, None);
+    }
+
+    #[test]
+    fn test_parse_result() {
+        let height: u32 = parse_result("getblockcount", Ok(json!(101))).unwrap();
+        assert_eq!(height, 101);
+
+        let txout: Option<Value> = parse_result("gettxout", Ok(Value::Null)).unwrap();
+        assert!(txout.is_none());
+
+        let res: SdkResult<u32> = parse_result(
+            "getblockcount",
+            Err(RpcError {
+                code: -28,
+                message: "Loading block index...".into(),
+            }),
+        );
+        assert!(res.is_err());
+    }
+}
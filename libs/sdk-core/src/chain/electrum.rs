@@ -0,0 +1,382 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use electrum_client::{Client, ConfigBuilder, ElectrumApi, GetHistoryRes};
+
+use super::{
+    recommended_fees_from_estimates, ChainService, OnchainTx, Outspend, RecommendedFees, TxStatus,
+    Vin, Vout,
+};
+use crate::bitcoin::{Address, BlockHeader, Network, OutPoint, Script, Transaction, TxOut, Txid};
+use crate::error::{SdkError, SdkResult};
+
+const ELECTRUM_TIMEOUT_SEC: u8 = 30;
+const ELECTRUM_RETRIES: u8 = 2;
+/// The confirmation targets of the recommended fees
+const FEE_TARGETS: [usize; 5] = [1, 3, 6, 144, 1008];
+
+type ElectrumResult<T> = Result<T, electrum_client::Error>;
+
+/// An Electrum server, reached over TCP (`tcp://`) or TLS (`ssl://`).
+///
+/// Unlike mempool.space, Electrum servers index transactions by script and don't track the
+/// spending transactions of outputs, so results are assembled from several calls.
+pub(crate) struct Electrum {
+    url: String,
+    network: Network,
+    client: Arc<Mutex<Option<Arc<Client>>>>,
+}
+
+impl Electrum {
+    pub(crate) fn new(url: String, network: Network) -> Self {
+        Self {
+            url,
+            network,
+            client: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Runs the calls on a blocking thread, as the Electrum client is synchronous. The connection
+    /// is opened on first use and reused afterwards.
+    async fn call<T, F>(&self, f: F) -> SdkResult<T>
+    where
+        F: FnOnce(&Client, Network) -> ElectrumResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let url = self.url.clone();
+        let network = self.network;
+        let cached_client = self.client.clone();
+        let res = tokio::task::spawn_blocking(move || {
+            let client = {
+                let mut cached_client = cached_client.lock().unwrap();
+                match cached_client.as_ref() {
+                    Some(client) => client.clone(),
+                    None => {
+                        let config = ConfigBuilder::new()
+                            .timeout(Some(ELECTRUM_TIMEOUT_SEC))?
+                            .retry(ELECTRUM_RETRIES)
+                            .build();
+                        let client = Arc::new(Client::from_config(&url, config)?);
+                        *cached_client = Some(client.clone());
+                        client
+                    }
+                }
+            };
+            f(&client, network)
+        })
+        .await
+        .map_err(|e| SdkError::Generic {
+            err: format!("Electrum call failed to complete: {e}"),
+        })?;
+        res.map_err(|e| SdkError::ServiceConnectivity {
+            err: format!("Electrum call to {} failed: {e}", self.url),
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl ChainService for Electrum {
+    async fn recommended_fees(&self) -> SdkResult<RecommendedFees> {
+        let (estimates, relay_fee) = self
+            .call(|client, _| {
+                let rates = client.batch_estimate_fee(FEE_TARGETS)?;
+                Ok((rates, client.relay_fee()?))
+            })
+            .await?;
+
+        // Electrum returns the fee rates in BTC/kvB, and -1 if it can't estimate them
+        let estimates: HashMap<u16, f64> = FEE_TARGETS
+            .iter()
+            .zip(estimates)
+            .filter(|(_, rate)| *rate > 0.0)
+            .map(|(target, rate)| (*target as u16, rate * 100_000.0))
+            .collect();
+        let mut fees = recommended_fees_from_estimates(&estimates)?;
+        if relay_fee > 0.0 {
+            fees.minimum_fee = (relay_fee * 100_000.0).ceil() as u64;
+        }
+        Ok(fees)
+    }
+
+    async fn address_transactions(&self, address: String) -> SdkResult<Vec<OnchainTx>> {
+        let script = Address::from_str(&address)
+            .map_err(|e| SdkError::Generic {
+                err: format!("Invalid address {address}: {e}"),
+            })?
+            .script_pubkey();
+        self.call(move |client, network| {
+            let mut history = client.script_get_history(&script)?;
+            // Same order as mempool.space: mempool transactions first, then newest first
+            history.sort_by_key(|h| match h.height {
+                height if height > 0 => -height,
+                _ => i32::MIN,
+            });
+            fetch_transactions(client, network, history)
+        })
+        .await
+    }
+
+    async fn current_tip(&self) -> SdkResult<u32> {
+        self.call(|client, _| Ok(client.block_headers_subscribe()?.height as u32))
+            .await
+    }
+
+    async fn transaction_outspends(&self, txid: String) -> SdkResult<Vec<Outspend>> {
+        let txid = Txid::from_str(&txid).map_err(|e| SdkError::Generic {
+            err: format!("Invalid txid {txid}: {e}"),
+        })?;
+        self.call(move |client, _| transaction_outspends(client, txid))
+            .await
+    }
+
+    async fn broadcast_transaction(&self, tx: Vec<u8>) -> SdkResult<String> {
+        self.call(move |client, _| Ok(client.transaction_broadcast_raw(&tx)?.to_string()))
+            .await
+    }
+}
+
+/// Fetches the transactions of the history, with the outputs they spend and their blocks
+fn fetch_transactions(
+    client: &Client,
+    network: Network,
+    history: Vec<GetHistoryRes>,
+) -> ElectrumResult<Vec<OnchainTx>> {
+    if history.is_empty() {
+        return Ok(vec![]);
+    }
+    let txs = client.batch_transaction_get(history.iter().map(|h| &h.tx_hash))?;
+
+    let prev_txids: HashSet<Txid> = txs
+        .iter()
+        .filter(|tx| !tx.is_coin_base())
+        .flat_map(|tx| tx.input.iter().map(|input| input.previous_output.txid))
+        .collect();
+    let prev_txs: HashMap<Txid, Transaction> = match prev_txids.is_empty() {
+        true => HashMap::new(),
+        false => client
+            .batch_transaction_get(&prev_txids)?
+            .into_iter()
+            .map(|tx| (tx.txid(), tx))
+            .collect(),
+    };
+
+    let heights: Vec<u32> = history
+        .iter()
+        .filter(|h| h.height > 0)
+        .map(|h| h.height as u32)
+        .collect::<HashSet<u32>>()
+        .into_iter()
+        .collect();
+    let headers: HashMap<u32, BlockHeader> = match heights.is_empty() {
+        true => HashMap::new(),
+        false => heights
+            .clone()
+            .into_iter()
+            .zip(client.batch_block_header(heights)?)
+            .collect(),
+    };
+
+    Ok(history
+        .iter()
+        .zip(txs)
+        .map(|(h, tx)| {
+            let header = headers.get(&(h.height.max(0) as u32));
+            to_onchain_tx(&tx, tx_status(h.height, header), &prev_txs, network)
+        })
+        .collect())
+}
+
+/// Finds the spending transaction of each output among the transactions of its script
+fn transaction_outspends(client: &Client, txid: Txid) -> ElectrumResult<Vec<Outspend>> {
+    let tx = client.transaction_get(&txid)?;
+    let mut outspends = Vec::new();
+    for (vout, output) in tx.output.iter().enumerate() {
+        let outpoint = OutPoint {
+            txid,
+            vout: vout as u32,
+        };
+        let history: Vec<GetHistoryRes> = client
+            .script_get_history(&output.script_pubkey)?
+            .into_iter()
+            .filter(|h| h.tx_hash != txid)
+            .collect();
+        let candidates = match history.is_empty() {
+            true => vec![],
+            false => client.batch_transaction_get(history.iter().map(|h| &h.tx_hash))?,
+        };
+
+        let mut outspend = Outspend {
+            spent: false,
+            txid: None,
+            vin: None,
+            status: None,
+        };
+        for (h, candidate) in history.iter().zip(candidates) {
+            if let Some(vin) = candidate
+                .input
+                .iter()
+                .position(|input| input.previous_output == outpoint)
+            {
+                let header = match h.height > 0 {
+                    true => Some(client.block_header(h.height as usize)?),
+                    false => None,
+                };
+                outspend = Outspend {
+                    spent: true,
+                    txid: Some(candidate.txid().to_string()),
+                    vin: Some(vin as u32),
+                    status: Some(tx_status(h.height, header.as_ref())),
+                };
+                break;
+            }
+        }
+        outspends.push(outspend);
+    }
+    Ok(outspends)
+}
+
+/// The status of a transaction at the given height. Electrum uses a height of 0 or -1 for
+/// mempool transactions.
+fn tx_status(height: i32, header: Option<&BlockHeader>) -> TxStatus {
+    match (height > 0, header) {
+        (true, Some(header)) => TxStatus {
+            confirmed: true,
+            block_height: Some(height as u32),
+            block_hash: Some(header.block_hash().to_string()),
+            block_time: Some(header.time as u64),
+        },
+        _ => TxStatus {
+            confirmed: false,
+            block_height: None,
+            block_hash: None,
+            block_time: None,
+        },
+    }
+}
+
+/// Converts the transaction to the mempool.space format used by the chain services
+pub(super) fn to_onchain_tx(
+    tx: &Transaction,
+    status: TxStatus,
+    prev_txs: &HashMap<Txid, Transaction>,
+    network: Network,
+) -> OnchainTx {
+    let prevouts: Vec<Option<&TxOut>> = tx
+        .input
+        .iter()
+        .map(|input| {
+            prev_txs
+                .get(&input.previous_output.txid)
+                .and_then(|prev_tx| prev_tx.output.get(input.previous_output.vout as usize))
+        })
+        .collect();
+    let fee = match prevouts.iter().all(Option::is_some) && !tx.is_coin_base() {
+        true => {
+            let input_value: u64 = prevouts.iter().flatten().map(|o| o.value).sum();
+            let output_value: u64 = tx.output.iter().map(|o| o.value).sum();
+            input_value.saturating_sub(output_value)
+        }
+        false => 0,
+    };
+
+    OnchainTx {
+        txid: tx.txid().to_string(),
+        version: tx.version as u32,
+        locktime: tx.lock_time.0,
+        vin: tx
+            .input
+            .iter()
+            .zip(prevouts)
+            .map(|(input, prevout)| Vin {
+                txid: input.previous_output.txid.to_string(),
+                vout: input.previous_output.vout,
+                prevout: match prevout {
+                    Some(prevout) => to_vout(prevout, network),
+                    None => to_vout(
+                        &TxOut {
+                            value: 0,
+                            script_pubkey: Script::new(),
+                        },
+                        network,
+                    ),
+                },
+                scriptsig: hex::encode(input.script_sig.as_bytes()),
+                scriptsig_asm: input.script_sig.asm(),
+                witness: match input.witness.is_empty() {
+                    true => None,
+                    false => Some(input.witness.iter().map(hex::encode).collect()),
+                },
+                is_coinbase: tx.is_coin_base(),
+                sequence: input.sequence.0,
+            })
+            .collect(),
+        vout: tx.output.iter().map(|o| to_vout(o, network)).collect(),
+        size: tx.size() as u32,
+        weight: tx.weight() as u32,
+        fee: fee as u32,
+        status,
+    }
+}
+
+fn to_vout(output: &TxOut, network: Network) -> Vout {
+    Vout {
+        scriptpubkey: hex::encode(output.script_pubkey.as_bytes()),
+        scriptpubkey_asm: output.script_pubkey.asm(),
+        scriptpubkey_type: script_type(&output.script_pubkey).to_string(),
+        scriptpubkey_address: Address::from_script(&output.script_pubkey, network)
+            .map(|address| address.to_string())
+            .unwrap_or_default(),
+        value: output.value,
+    }
+}
+
+/// The script type, as named by mempool.space
+fn script_type(script: &Script) -> &'static str {
+    if script.is_p2pkh() {
+        "p2pkh"
+    } else if script.is_p2sh() {
+        "p2sh"
+    } else if script.is_v0_p2wpkh() {
+        "v0_p2wpkh"
+    } else if script.is_v0_p2wsh() {
+        "v0_p2wsh"
+    } else if script.is_v1_p2tr() {
+        "v1_p2tr"
+    } else if script.is_op_return() {
+        "op_return"
+    } else {
+        "unknown"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bitcoin::hashes::hex::FromHex;
+    use crate::chain::electrum::*;
+
+    #[test]
+    fn test_to_vout() {
+        let output = TxOut {
+            value: 197_497_253,
+            script_pubkey: Script::from_hex("0014b34b7da80e662d1db3fcfbe34b7f4cacc4fac34d")
+                .unwrap(),
+        };
+        let vout = to_vout(&output, Network::Bitcoin);
+        assert_eq!(vout.scriptpubkey_type, "v0_p2wpkh");
+        assert_eq!(
+            vout.scriptpubkey_address,
+            "bc1qkd9hm2qwvck3mvlul035kl6v4nz04s6dmryeq5"
+        );
+        assert_eq!(
+            vout.scriptpubkey_asm,
+            "OP_0 OP_PUSHBYTES_20 b34b7da80e662d1db3fcfbe34b7f4cacc4fac34d"
+        );
+    }
+
+    #[test]
+    fn test_tx_status() {
+        assert!(!tx_status(0, None).confirmed);
+        assert!(!tx_status(-1, None).confirmed);
+    }
+}
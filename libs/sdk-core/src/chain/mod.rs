@@ -1,12 +1,24 @@
-use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
 use sdk_common::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use crate::bitcoin::hashes::hex::FromHex;
-use crate::bitcoin::{OutPoint, Txid};
+use crate::bitcoin::{Network, OutPoint, Transaction, Txid};
 use crate::error::{SdkError, SdkResult};
+use crate::models::ChainBackend;
+
+mod bitcoind;
+mod electrum;
+
+use bitcoind::Bitcoind;
+use electrum::Electrum;
 
 pub const DEFAULT_MEMPOOL_SPACE_URL: &str = "https://mempool.space/api";
+/// The fee rate the replacement of a transaction pays at least for its own relay (BIP 125)
+const INCREMENTAL_RELAY_FEE_SAT_PER_VBYTE: u64 = 1;
 
 #[tonic::async_trait]
 pub trait ChainService: Send + Sync {
@@ -30,29 +42,70 @@ pub trait RedundantChainServiceTrait: ChainService {
 
 #[derive(Clone)]
 pub struct RedundantChainService {
-    instances: Vec<MempoolSpace>,
+    instances: Vec<ChainServiceInstance>,
 }
-impl RedundantChainServiceTrait for RedundantChainService {
-    fn from_base_urls(base_urls: Vec<String>) -> Self {
+
+#[derive(Clone)]
+struct ChainServiceInstance {
+    url: String,
+    service: Arc<dyn ChainService>,
+}
+
+impl RedundantChainService {
+    pub(crate) fn from_backends(backends: Vec<ChainBackend>, network: Network) -> Self {
         Self {
-            instances: base_urls
-                .iter()
-                .map(|url: &String| url.trim_end_matches('/'))
-                .map(MempoolSpace::from_base_url)
+            instances: backends
+                .into_iter()
+                .map(|backend| {
+                    let (url, service): (String, Arc<dyn ChainService>) = match backend {
+                        ChainBackend::MempoolSpace { url } => {
+                            let url = url.trim_end_matches('/').to_string();
+                            (url.clone(), Arc::new(MempoolSpace::from_base_url(&url)))
+                        }
+                        ChainBackend::Esplora { url } => {
+                            let url = url.trim_end_matches('/').to_string();
+                            (url.clone(), Arc::new(Esplora::from_base_url(&url)))
+                        }
+                        ChainBackend::Electrum { url } => {
+                            (url.clone(), Arc::new(Electrum::new(url, network)))
+                        }
+                        ChainBackend::Bitcoind {
+                            url,
+                            rpc_user,
+                            rpc_password,
+                        } => (
+                            url.clone(),
+                            Arc::new(Bitcoind::new(&url, rpc_user, rpc_password, network)),
+                        ),
+                    };
+                    ChainServiceInstance { url, service }
+                })
                 .collect(),
         }
     }
 }
 
+impl RedundantChainServiceTrait for RedundantChainService {
+    fn from_base_urls(base_urls: Vec<String>) -> Self {
+        Self::from_backends(
+            base_urls
+                .into_iter()
+                .map(|url| ChainBackend::MempoolSpace { url })
+                .collect(),
+            Network::Bitcoin,
+        )
+    }
+}
+
 #[tonic::async_trait]
 impl ChainService for RedundantChainService {
     async fn recommended_fees(&self) -> SdkResult<RecommendedFees> {
         for inst in &self.instances {
-            match inst.recommended_fees().await {
+            match inst.service.recommended_fees().await {
                 Ok(res) => {
                     return Ok(res);
                 }
-                Err(e) => error!("Call to chain service {} failed: {e}", inst.base_url),
+                Err(e) => error!("Call to chain service {} failed: {e}", inst.url),
             }
         }
         Err(SdkError::service_connectivity(
@@ -62,11 +115,11 @@ impl ChainService for RedundantChainService {
 
     async fn address_transactions(&self, address: String) -> SdkResult<Vec<OnchainTx>> {
         for inst in &self.instances {
-            match inst.address_transactions(address.clone()).await {
+            match inst.service.address_transactions(address.clone()).await {
                 Ok(res) => {
                     return Ok(res);
                 }
-                Err(e) => error!("Call to chain service {} failed: {e}", inst.base_url),
+                Err(e) => error!("Call to chain service {} failed: {e}", inst.url),
             }
         }
         Err(SdkError::service_connectivity(
@@ -76,11 +129,11 @@ impl ChainService for RedundantChainService {
 
     async fn current_tip(&self) -> SdkResult<u32> {
         for inst in &self.instances {
-            match inst.current_tip().await {
+            match inst.service.current_tip().await {
                 Ok(res) => {
                     return Ok(res);
                 }
-                Err(e) => error!("Call to chain service {} failed: {e}", inst.base_url),
+                Err(e) => error!("Call to chain service {} failed: {e}", inst.url),
             }
         }
         Err(SdkError::service_connectivity(
@@ -90,11 +143,11 @@ impl ChainService for RedundantChainService {
 
     async fn transaction_outspends(&self, txid: String) -> SdkResult<Vec<Outspend>> {
         for inst in &self.instances {
-            match inst.transaction_outspends(txid.clone()).await {
+            match inst.service.transaction_outspends(txid.clone()).await {
                 Ok(res) => {
                     return Ok(res);
                 }
-                Err(e) => error!("Call to chain service {} failed: {e}", inst.base_url),
+                Err(e) => error!("Call to chain service {} failed: {e}", inst.url),
             }
         }
         Err(SdkError::service_connectivity(
@@ -104,11 +157,11 @@ impl ChainService for RedundantChainService {
 
     async fn broadcast_transaction(&self, tx: Vec<u8>) -> SdkResult<String> {
         for inst in &self.instances {
-            match inst.broadcast_transaction(tx.clone()).await {
+            match inst.service.broadcast_transaction(tx.clone()).await {
                 Ok(res) => {
                     return Ok(res);
                 }
-                Err(e) => error!("Call to chain service {} failed: {e}", inst.base_url),
+                Err(e) => error!("Call to chain service {} failed: {e}", inst.url),
             }
         }
         Err(SdkError::service_connectivity(
@@ -170,6 +223,86 @@ impl AddressUtxos {
     }
 }
 
+/// Checks that an unconfirmed transaction can be replaced by one paying the given fee, of the given
+/// weight (BIP 125). The replacement has to pay a higher fee rate than the transaction it replaces,
+/// and its fee has to cover the fee of the replaced transaction plus the relay of the replacement
+/// at the incremental relay fee rate.
+pub(crate) fn validate_replacement(
+    replaced_tx: &OnchainTx,
+    replacement_fee: u64,
+    replacement_weight: u64,
+) -> Result<()> {
+    if replaced_tx.status.confirmed {
+        return Err(anyhow!(
+            "Transaction {} is already confirmed",
+            replaced_tx.txid
+        ));
+    }
+    let replaced_vsize = (replaced_tx.weight as u64 + 3) / 4;
+    let replacement_vsize = (replacement_weight + 3) / 4;
+    if replaced_vsize > 0
+        && replacement_fee * replaced_vsize <= replaced_tx.fee as u64 * replacement_vsize
+    {
+        return Err(anyhow!(
+            "The fee rate has to be higher than the {} sat/vbyte of transaction {}",
+            replaced_tx.fee as u64 / replaced_vsize,
+            replaced_tx.txid
+        ));
+    }
+    let min_fee = replaced_tx.fee as u64 + INCREMENTAL_RELAY_FEE_SAT_PER_VBYTE * replacement_vsize;
+    if replacement_fee < min_fee {
+        return Err(anyhow!(
+            "The replacement of transaction {} has to pay at least {min_fee} sat of fees, \
+            it pays {replacement_fee} sat",
+            replaced_tx.txid
+        ));
+    }
+    Ok(())
+}
+
+/// The fee paid by a transaction replacing the given one, which spends outputs it spends
+pub(crate) fn replacement_fee(replaced_tx: &OnchainTx, replacement: &Transaction) -> Result<u64> {
+    let mut input_value = 0;
+    for input in &replacement.input {
+        let outpoint = input.previous_output;
+        input_value += replaced_tx
+            .vin
+            .iter()
+            .find(|vin| vin.txid == outpoint.txid.to_string() && vin.vout == outpoint.vout)
+            .map(|vin| vin.prevout.value)
+            .ok_or(anyhow!(
+                "The replacement spends {outpoint}, which transaction {} doesn't spend",
+                replaced_tx.txid
+            ))?;
+    }
+    let output_value: u64 = replacement.output.iter().map(|output| output.value).sum();
+    input_value.checked_sub(output_value).ok_or(anyhow!(
+        "The replacement of transaction {} spends more than its inputs",
+        replaced_tx.txid
+    ))
+}
+
+/// The fee rate a child transaction has to pay for it and its unconfirmed parents to reach the
+/// target fee rate together (CPFP)
+pub(crate) fn cpfp_child_fee_rate(
+    parents: &[OnchainTx],
+    child_tx_weight: u32,
+    target_sat_per_vbyte: u32,
+) -> Result<u32> {
+    let target = target_sat_per_vbyte as u64;
+    let parents_vsize: u64 = parents.iter().map(|tx| (tx.weight as u64 + 3) / 4).sum();
+    let parents_fee: u64 = parents.iter().map(|tx| tx.fee as u64).sum();
+    if parents_fee >= target * parents_vsize {
+        return Err(anyhow!(
+            "The transactions already pay at least {target_sat_per_vbyte} sat/vbyte"
+        ));
+    }
+
+    let child_vsize = (child_tx_weight as u64 + 3) / 4;
+    let child_fee = target * (parents_vsize + child_vsize) - parents_fee;
+    Ok(((child_fee + child_vsize - 1) / child_vsize) as u32)
+}
+
 /// Gets unspent tx outputs. Specifically filters out inbound utxos that have been spent.
 /// If include_unconfirmed_spends is true, then the result won't include utxos that were spent
 /// in unconfirmed transactions.
@@ -372,17 +505,156 @@ impl ChainService for MempoolSpace {
         }
     }
 }
+/// A generic Esplora instance.
+///
+/// mempool.space is based on Esplora, so all endpoints but the fee estimates are shared.
+#[derive(Clone)]
+pub(crate) struct Esplora {
+    api: MempoolSpace,
+}
+
+impl Esplora {
+    pub fn from_base_url(base_url: &str) -> Esplora {
+        Esplora {
+            api: MempoolSpace::from_base_url(base_url),
+        }
+    }
+}
+
+/// Maps the fee estimates, in sat/vByte by confirmation target, to the recommended fees
+pub(crate) fn recommended_fees_from_estimates(
+    estimates: &HashMap<u16, f64>,
+) -> SdkResult<RecommendedFees> {
+    let fee = |target: u16| -> Option<u64> {
+        estimates
+            .iter()
+            .filter(|(t, rate)| **t <= target && **rate > 0.0)
+            .max_by_key(|(t, _)| **t)
+            .map(|(_, rate)| rate.ceil() as u64)
+    };
+    let fastest_fee = fee(1).ok_or(SdkError::service_connectivity("No fee estimates"))?;
+    let half_hour_fee = fee(3).unwrap_or(fastest_fee);
+    let hour_fee = fee(6).unwrap_or(half_hour_fee);
+    let economy_fee = fee(144).unwrap_or(hour_fee);
+    let minimum_fee = fee(1008).unwrap_or(economy_fee);
+    Ok(RecommendedFees {
+        fastest_fee,
+        half_hour_fee,
+        hour_fee,
+        economy_fee,
+        minimum_fee,
+    })
+}
+
+#[tonic::async_trait]
+impl ChainService for Esplora {
+    async fn recommended_fees(&self) -> SdkResult<RecommendedFees> {
+        let estimates: HashMap<u16, f64> =
+            get_parse_and_log_response(&format!("{}/fee-estimates", self.api.base_url), true)
+                .await?;
+        recommended_fees_from_estimates(&estimates)
+    }
+
+    async fn address_transactions(&self, address: String) -> SdkResult<Vec<OnchainTx>> {
+        self.api.address_transactions(address).await
+    }
+
+    async fn current_tip(&self) -> SdkResult<u32> {
+        self.api.current_tip().await
+    }
+
+    async fn transaction_outspends(&self, txid: String) -> SdkResult<Vec<Outspend>> {
+        self.api.transaction_outspends(txid).await
+    }
+
+    async fn broadcast_transaction(&self, tx: Vec<u8>) -> SdkResult<String> {
+        self.api.broadcast_transaction(tx).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        chain::{MempoolSpace, OnchainTx, RedundantChainService, RedundantChainServiceTrait},
+        chain::{
+            cpfp_child_fee_rate, recommended_fees_from_estimates, validate_replacement,
+            MempoolSpace, OnchainTx, RedundantChainService, RedundantChainServiceTrait, TxStatus,
+        },
         error::SdkError,
     };
     use anyhow::Result;
+    use std::collections::HashMap;
     use tokio::test;
 
     use super::ChainService;
 
+    #[test]
+    async fn test_validate_replacement() -> Result<()> {
+        let mut tx = OnchainTx {
+            txid: "ec901bcab07df7d475d98fff2933dcb56d57bbdaa029c4142aed93462b6928fe".into(),
+            version: 2,
+            locktime: 0,
+            vin: vec![],
+            vout: vec![],
+            size: 222,
+            weight: 561,
+            fee: 1410,
+            status: TxStatus {
+                confirmed: false,
+                block_height: None,
+                block_hash: None,
+                block_time: None,
+            },
+        };
+
+        // The transaction pays 10 sat/vbyte
+        assert!(validate_replacement(&tx, 1_269, 561).is_err());
+        assert!(validate_replacement(&tx, 1_410, 561).is_err());
+        // The replacement also pays 1 sat/vbyte for its own relay
+        assert!(validate_replacement(&tx, 1_550, 561).is_err());
+        assert!(validate_replacement(&tx, 1_551, 561).is_ok());
+        // A smaller replacement pays a higher fee rate, but still at least the replaced fee
+        assert!(validate_replacement(&tx, 1_400, 401).is_err());
+        assert!(validate_replacement(&tx, 1_511, 401).is_ok());
+
+        tx.status.confirmed = true;
+        assert!(validate_replacement(&tx, 2_820, 561).is_err());
+        Ok(())
+    }
+
+    #[test]
+    async fn test_cpfp_child_fee_rate() -> Result<()> {
+        let parent = OnchainTx {
+            txid: "ec901bcab07df7d475d98fff2933dcb56d57bbdaa029c4142aed93462b6928fe".into(),
+            version: 2,
+            locktime: 0,
+            vin: vec![],
+            vout: vec![],
+            size: 222,
+            weight: 561,
+            fee: 282,
+            status: TxStatus {
+                confirmed: false,
+                block_height: None,
+                block_hash: None,
+                block_time: None,
+            },
+        };
+
+        // The parent pays 2 sat/vbyte for 141 vbytes, the child has 110 vbytes
+        assert_eq!(cpfp_child_fee_rate(&[parent.clone()], 440, 10)?, 21);
+        assert_eq!(cpfp_child_fee_rate(&[parent.clone()], 440, 3)?, 5);
+        assert!(cpfp_child_fee_rate(&[parent.clone()], 440, 2).is_err());
+
+        // Both parents are paid for
+        let second_parent = OnchainTx {
+            weight: 440,
+            fee: 110,
+            ..parent.clone()
+        };
+        assert_eq!(cpfp_child_fee_rate(&[parent, second_parent], 440, 10)?, 30);
+        Ok(())
+    }
+
     #[test]
     async fn test_recommended_fees() -> Result<()> {
         let ms = MempoolSpace::default();
@@ -464,6 +736,20 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    async fn test_recommended_fees_from_estimates() -> Result<()> {
+        let estimates = HashMap::from([(1, 20.5), (2, 18.0), (4, 10.2), (144, 3.0), (1008, 1.1)]);
+        let fees = recommended_fees_from_estimates(&estimates)?;
+        assert_eq!(fees.fastest_fee, 21);
+        assert_eq!(fees.half_hour_fee, 18);
+        assert_eq!(fees.hour_fee, 11);
+        assert_eq!(fees.economy_fee, 3);
+        assert_eq!(fees.minimum_fee, 2);
+
+        assert!(recommended_fees_from_estimates(&HashMap::new()).is_err());
+        Ok(())
+    }
+
     // #[test]
     // async fn test_address_transactions_mempool() {
     //     let ms = MempoolSpace::default();
@@ -0,0 +1,71 @@
+use crate::{
+    backup::{BackupState, BackupTransport},
+    error::{SdkError, SdkResult},
+};
+
+use super::node_api::Cln;
+use crate::greenlight::BREEZ_SDK_DATASTORE_PATH;
+use gl_client::pb::cln;
+use std::sync::Arc;
+
+/// Stores the backup in the node datastore, under the same key as [crate::greenlight::GLBackupTransport]
+pub(crate) struct ClnBackupTransport {
+    pub(crate) inner: Arc<Cln>,
+}
+
+impl ClnBackupTransport {
+    fn datastore_key(&self) -> Vec<String> {
+        BREEZ_SDK_DATASTORE_PATH.map(|s| s.into()).to_vec()
+    }
+}
+
+#[tonic::async_trait]
+impl BackupTransport for ClnBackupTransport {
+    async fn pull(&self) -> SdkResult<Option<BackupState>> {
+        let key = self.datastore_key();
+        let mut c = self.inner.get_node_client().await?;
+        let response: cln::ListdatastoreResponse = c
+            .list_datastore(cln::ListdatastoreRequest { key })
+            .await?
+            .into_inner();
+        let store = response.datastore;
+        match store.len() {
+            0 => Ok(None),
+            1 => Ok(Some(BackupState {
+                generation: store[0]
+                    .generation
+                    .ok_or_else(|| SdkError::generic("Backup has no generation"))?,
+                data: store[0]
+                    .clone()
+                    .hex
+                    .ok_or_else(|| SdkError::generic("Backup has no data"))?,
+            })),
+            _ => Err(SdkError::Generic {
+                err: "Get returned multiple values".into(),
+            }),
+        }
+    }
+
+    async fn push(&self, version: Option<u64>, hex: Vec<u8>) -> SdkResult<u64> {
+        let key = self.datastore_key();
+        info!("set_value key = {:?} data length={:?}", key, hex.len());
+        let mut c = self.inner.get_node_client().await?;
+        let mut mode = cln::datastore_request::DatastoreMode::MustCreate;
+        if version.is_some() {
+            mode = cln::datastore_request::DatastoreMode::MustReplace;
+        }
+        let response = c
+            .datastore(cln::DatastoreRequest {
+                key,
+                string: None,
+                hex: Some(hex),
+                generation: version,
+                mode: Some(mode.into()),
+            })
+            .await?
+            .into_inner();
+        response
+            .generation
+            .ok_or_else(|| SdkError::generic("Datastore returned no generation"))
+    }
+}
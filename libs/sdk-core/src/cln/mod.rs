@@ -0,0 +1,8 @@
+mod backup_transport;
+mod node_api;
+/// The BOLT12 offer RPCs of the `cln-grpc` plugin, see `proto/offers.proto`
+mod offers {
+    tonic::include_proto!("cln");
+}
+pub(crate) use backup_transport::ClnBackupTransport;
+pub(crate) use node_api::Cln;
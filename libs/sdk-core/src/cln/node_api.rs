@@ -0,0 +1,977 @@
+use std::collections::{HashMap, HashSet};
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use gl_client::pb::cln::{self, node_client::NodeClient};
+use sdk_common::prelude::*;
+use tokio::sync::{mpsc, watch, Mutex};
+use tokio::time::sleep;
+use tokio_stream::Stream;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+use tonic::Streaming;
+
+use crate::bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+use crate::bitcoin::util::bip32::{ChildNumber, ExtendedPrivKey};
+use crate::bitcoin::{Address, OutPoint};
+use crate::greenlight::node_api::{
+    channels_and_balance, cln_invoice_request, cln_keysend_request, cln_pay_request,
+    cpfp_withdraw_request, fetch_bolt11_result, is_closeable, node_state_and_channels,
+    open_peer_channels, prepare_redeem_onchain_funds, probe_reached_payee, probe_request,
+    replacement_utxo_psbt_request, route_fee_msat, route_request, routing_hints, signed_onchain_tx,
+    transactions_since, utxos, with_redeem_output, NodeCommand,
+};
+use crate::lightning::util::message_signing::verify;
+use crate::lightning_invoice::RawBolt11Invoice;
+use crate::node_api::{
+    CreateInvoiceRequest, FetchBolt11Result, IncomingPaymentStream, NodeAPI, NodeError,
+    NodeFeature, NodeResult, RouteProbe, SignedOnchainTx,
+};
+use crate::persist::db::SqliteStorage;
+use crate::wallets;
+use crate::{
+    Config, CreateOfferRequest, CustomMessage, Htlc, LspInformation, MaxChannelAmount, NodeConfig,
+    NodeCredentials, Payment, PaymentPath, PaymentPathEdge, PaymentResponse,
+    PrepareRedeemOnchainFundsRequest, PrepareRedeemOnchainFundsResponse, RouteHint, RouteHintHop,
+    SyncResponse, TlvEntry,
+};
+
+use super::offers;
+
+/// The server name in the certificates generated by the `cln-grpc` plugin
+const CLN_GRPC_DOMAIN_NAME: &str = "cln";
+
+/// The cache key of the address set with [NodeAPI::configure_node]
+const CLOSE_TO_ADDRESS_CACHE_KEY: &str = "cln_close_to_address";
+
+type ClnGrpcClient = NodeClient<Channel>;
+type ClnOffersClient = offers::node_client::NodeClient<Channel>;
+
+/// A self-hosted Core Lightning node, reached through its `cln-grpc` plugin.
+///
+/// Unlike [crate::greenlight::Greenlight], the node keys are held by the node itself, so signing
+/// is delegated to the node RPCs and there is no signer to run.
+pub(crate) struct Cln {
+    sdk_config: Config,
+    seed: Vec<u8>,
+    persister: Arc<SqliteStorage>,
+    endpoint: Endpoint,
+    channel: Mutex<Option<Channel>>,
+}
+
+impl Cln {
+    /// Connects to the node configured in [NodeConfig::Cln] and verifies it runs on the
+    /// configured network
+    pub async fn connect(
+        config: Config,
+        seed: Vec<u8>,
+        persister: Arc<SqliteStorage>,
+    ) -> NodeResult<Self> {
+        let (grpc_url, ca_cert, client_cert, client_key) = match config.node_config.clone() {
+            NodeConfig::Cln {
+                grpc_url,
+                ca_cert,
+                client_cert,
+                client_key,
+            } => (grpc_url, ca_cert, client_cert, client_key),
+            NodeConfig::Greenlight { .. } => {
+                return Err(NodeError::generic(
+                    "CLN can't be used with a Greenlight node config",
+                ))
+            }
+        };
+
+        let tls_config = ClientTlsConfig::new()
+            .domain_name(CLN_GRPC_DOMAIN_NAME)
+            .ca_certificate(Certificate::from_pem(ca_cert))
+            .identity(Identity::from_pem(client_cert, client_key));
+        let endpoint = Endpoint::from_shared(grpc_url)
+            .and_then(|endpoint| endpoint.tls_config(tls_config))
+            .map_err(|e| NodeError::ServiceConnectivity(format!("Invalid CLN endpoint: {e}")))?;
+
+        let cln = Cln {
+            sdk_config: config,
+            seed,
+            persister,
+            endpoint,
+            channel: Mutex::new(None),
+        };
+
+        let node_info = cln
+            .get_node_client()
+            .await?
+            .getinfo(cln::GetinfoRequest::default())
+            .await?
+            .into_inner();
+        let node_network: Network = crate::bitcoin::Network::from_str(&node_info.network)
+            .map_err(|_| anyhow!("Unknown node network: {}", node_info.network))?
+            .into();
+        if node_network != cln.sdk_config.network {
+            return Err(NodeError::generic(&format!(
+                "Node network {node_network:?} does not match config network {:?}",
+                cln.sdk_config.network
+            )));
+        }
+        Ok(cln)
+    }
+
+    async fn get_channel(&self) -> NodeResult<Channel> {
+        let mut channel = self.channel.lock().await;
+        if channel.is_none() {
+            *channel = Some(
+                self.endpoint
+                    .connect()
+                    .await
+                    .map_err(|e| NodeError::ServiceConnectivity(e.to_string()))?,
+            );
+        }
+        Ok(channel.clone().unwrap())
+    }
+
+    pub(crate) async fn get_node_client(&self) -> NodeResult<ClnGrpcClient> {
+        Ok(NodeClient::new(self.get_channel().await?))
+    }
+
+    async fn get_offers_client(&self) -> NodeResult<ClnOffersClient> {
+        Ok(offers::node_client::NodeClient::new(
+            self.get_channel().await?,
+        ))
+    }
+
+    async fn list_funds(&self) -> NodeResult<cln::ListfundsResponse> {
+        Ok(self
+            .get_node_client()
+            .await?
+            .list_funds(cln::ListfundsRequest::default())
+            .await?
+            .into_inner())
+    }
+
+    async fn list_peer_channels(&self) -> NodeResult<Vec<cln::ListpeerchannelsChannels>> {
+        Ok(self
+            .get_node_client()
+            .await?
+            .list_peer_channels(cln::ListpeerchannelsRequest::default())
+            .await?
+            .into_inner()
+            .channels)
+    }
+
+    async fn fetch_channels_and_balance_with_retry(
+        &self,
+        match_local_balance: bool,
+    ) -> NodeResult<(
+        Vec<cln::ListpeerchannelsChannels>,
+        Vec<cln::ListpeerchannelsChannels>,
+        Vec<String>,
+        u64,
+    )> {
+        let mut result = channels_and_balance(self.list_peer_channels().await?);
+        if match_local_balance {
+            if let Some(state) = self.persister.get_node_state()? {
+                let mut retry_count = 0;
+                while state.channels_balance_msat != result.3 && retry_count < 10 {
+                    warn!("balance matching local state is required and not yet satisfied, retrying in 100ms...");
+                    sleep(Duration::from_millis(100)).await;
+                    result = channels_and_balance(self.list_peer_channels().await?);
+                    retry_count += 1;
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    async fn fetch_outgoing_payment_with_retry(
+        &self,
+        payment_hash: Vec<u8>,
+    ) -> NodeResult<cln::ListpaysPays> {
+        let mut client = self.get_node_client().await?;
+        for _ in 0..20 {
+            // CLN also returns failed ListpaysPays along with the complete one
+            let pay = client
+                .list_pays(cln::ListpaysRequest {
+                    payment_hash: Some(payment_hash.clone()),
+                    status: Some(cln::listpays_request::ListpaysStatus::Complete.into()),
+                    ..Default::default()
+                })
+                .await?
+                .into_inner()
+                .pays
+                .into_iter()
+                .find(|pay| pay.status() == cln::listpays_pays::ListpaysPaysStatus::Complete);
+            if let Some(pay) = pay {
+                return Ok(pay);
+            }
+            debug!("fetch outgoing payment failed, retrying in 100ms...");
+            sleep(Duration::from_millis(100)).await;
+        }
+        Err(NodeError::generic("Payment not found"))
+    }
+
+    async fn pull_transactions(
+        &self,
+        since_timestamp: u64,
+        htlc_list: Vec<Htlc>,
+    ) -> NodeResult<Vec<Payment>> {
+        let mut client = self.get_node_client().await?;
+        let invoices = client
+            .list_invoices(cln::ListinvoicesRequest::default())
+            .await?
+            .into_inner()
+            .invoices;
+        let pays = client
+            .list_pays(cln::ListpaysRequest::default())
+            .await?
+            .into_inner()
+            .pays;
+        transactions_since(since_timestamp, invoices, pays, htlc_list)
+    }
+
+    /// Signs the invoice with the node key, through the `signinvoice` RPC. As this is called from
+    /// sync code, the RPC is made from a dedicated thread with its own runtime.
+    fn sign_invoice_blocking(&self, invstring: String) -> NodeResult<String> {
+        let endpoint = self.endpoint.clone();
+        let wallet_id = wallets::current_wallet_id();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|e| NodeError::Generic(format!("Failed to create runtime: {e}")))?;
+            rt.block_on(wallets::with_optional_wallet_context(
+                wallet_id,
+                async move {
+                    let channel = endpoint
+                        .connect()
+                        .await
+                        .map_err(|e| NodeError::ServiceConnectivity(e.to_string()))?;
+                    let res = NodeClient::new(channel)
+                        .sign_invoice(cln::SigninvoiceRequest { invstring })
+                        .await?
+                        .into_inner();
+                    Ok(res.bolt11)
+                },
+            ))
+        })
+        .join()
+        .map_err(|_| NodeError::generic("Invoice signing thread panicked"))?
+    }
+}
+
+/// Converts a paid invoice to the incoming payment streamed by Greenlight nodes
+fn incoming_payment(
+    invoice: cln::WaitanyinvoiceResponse,
+) -> gl_client::signer::model::greenlight::IncomingPayment {
+    let amount_msat = invoice
+        .amount_received_msat
+        .or(invoice.amount_msat)
+        .map(|amount| amount.msat)
+        .unwrap_or_default();
+    gl_client::signer::model::greenlight::IncomingPayment {
+        details: Some(
+            gl_client::signer::model::greenlight::incoming_payment::Details::Offchain(
+                gl_client::pb::OffChainPayment {
+                    label: invoice.label,
+                    preimage: invoice.payment_preimage.unwrap_or_default(),
+                    amount: Some(gl_client::pb::greenlight::Amount {
+                        unit: Some(
+                            gl_client::signer::model::greenlight::amount::Unit::Millisatoshi(
+                                amount_msat,
+                            ),
+                        ),
+                    }),
+                    payment_hash: invoice.payment_hash,
+                    bolt11: invoice.bolt11.unwrap_or_default(),
+                    ..Default::default()
+                },
+            ),
+        ),
+    }
+}
+
+#[tonic::async_trait]
+impl NodeAPI for Cln {
+    /// `cln-grpc` has no way to subscribe to custom messages or logs
+    fn unsupported_features(&self) -> Vec<NodeFeature> {
+        vec![NodeFeature::CustomMessages, NodeFeature::LogStream]
+    }
+
+    /// The node is accessed with the TLS credentials from the config, there are no credentials
+    /// to store
+    fn node_credentials(&self) -> NodeResult<Option<NodeCredentials>> {
+        Ok(None)
+    }
+
+    /// CLN has no global close to address, so the address is stored and used as the destination
+    /// of the channel closes initiated by the SDK
+    async fn configure_node(&self, close_to_address: Option<String>) -> NodeResult<()> {
+        match close_to_address {
+            Some(address) => {
+                Address::from_str(&address)
+                    .map_err(|e| NodeError::generic(&format!("Invalid close to address: {e}")))?;
+                self.persister
+                    .update_cached_item(CLOSE_TO_ADDRESS_CACHE_KEY, address)?;
+            }
+            None => self
+                .persister
+                .delete_cached_item(CLOSE_TO_ADDRESS_CACHE_KEY)?,
+        }
+        Ok(())
+    }
+
+    async fn create_invoice(&self, request: CreateInvoiceRequest) -> NodeResult<String> {
+        let res = self
+            .get_node_client()
+            .await?
+            .invoice(cln_invoice_request(request)?)
+            .await?
+            .into_inner();
+        Ok(res.bolt11)
+    }
+
+    async fn fetch_bolt11(&self, payment_hash: Vec<u8>) -> NodeResult<Option<FetchBolt11Result>> {
+        let request = cln::ListinvoicesRequest {
+            payment_hash: Some(payment_hash),
+            ..Default::default()
+        };
+        Ok(self
+            .get_node_client()
+            .await?
+            .list_invoices(request)
+            .await?
+            .into_inner()
+            .invoices
+            .first()
+            .cloned()
+            .and_then(fetch_bolt11_result))
+    }
+
+    async fn pull_changed(
+        &self,
+        since_timestamp: u64,
+        match_local_balance: bool,
+    ) -> NodeResult<SyncResponse> {
+        info!("pull changed since {}", since_timestamp);
+        let mut client = self.get_node_client().await?;
+        let node_info = client
+            .getinfo(cln::GetinfoRequest::default())
+            .await?
+            .into_inner();
+        let funds = self.list_funds().await?;
+        let closed_channels = client
+            .list_closed_channels(cln::ListclosedchannelsRequest { id: None })
+            .await?
+            .into_inner()
+            .closedchannels;
+        let balance = self
+            .fetch_channels_and_balance_with_retry(match_local_balance)
+            .await?;
+
+        let (node_state, all_channel_models) =
+            node_state_and_channels(node_info, funds, closed_channels, balance)?;
+        let htlc_list: Vec<Htlc> = all_channel_models
+            .iter()
+            .flat_map(|c| c.htlcs.clone())
+            .collect();
+
+        Ok(SyncResponse {
+            node_state,
+            payments: self.pull_transactions(since_timestamp, htlc_list).await?,
+            channels: all_channel_models,
+        })
+    }
+
+    async fn send_payment(
+        &self,
+        bolt11: String,
+        amount_msat: Option<u64>,
+        label: Option<String>,
+        max_fee_msat: Option<u64>,
+    ) -> NodeResult<Payment> {
+        let request = cln_pay_request(&self.sdk_config, bolt11, amount_msat, label, max_fee_msat)?;
+        let result = self
+            .get_node_client()
+            .await?
+            .pay(request)
+            .await?
+            .into_inner();
+
+        // Make sure the payment is persisted in the node before returning it
+        self.fetch_outgoing_payment_with_retry(result.payment_hash)
+            .await?
+            .try_into()
+    }
+
+    async fn send_spontaneous_payment(
+        &self,
+        node_id: String,
+        amount_msat: u64,
+        extra_tlvs: Option<Vec<TlvEntry>>,
+        label: Option<String>,
+    ) -> NodeResult<Payment> {
+        let request =
+            cln_keysend_request(&self.sdk_config, node_id, amount_msat, extra_tlvs, label)?;
+        let result = self
+            .get_node_client()
+            .await?
+            .key_send(request)
+            .await?
+            .into_inner();
+
+        // Make sure the payment is persisted in the node before returning it
+        self.fetch_outgoing_payment_with_retry(result.payment_hash)
+            .await?
+            .try_into()
+    }
+
+    async fn create_offer(&self, req: CreateOfferRequest) -> NodeResult<String> {
+        let request = offers::OfferRequest {
+            amount: req
+                .amount_msat
+                .map_or("any".to_string(), |msat| format!("{msat}msat")),
+            description: Some(req.description),
+            label: None,
+        };
+        Ok(self
+            .get_offers_client()
+            .await?
+            .offer(request)
+            .await?
+            .into_inner()
+            .bolt12)
+    }
+
+    async fn pay_offer(
+        &self,
+        offer: String,
+        amount_msat: Option<u64>,
+        payer_note: Option<String>,
+        label: Option<String>,
+    ) -> NodeResult<Payment> {
+        // The offer issuer is asked for an invoice over the onion, which is then paid like a
+        // bolt11 one
+        let invoice = self
+            .get_offers_client()
+            .await?
+            .fetch_invoice(offers::FetchinvoiceRequest {
+                offer,
+                amount_msat: amount_msat.map(|msat| offers::Amount { msat }),
+                payer_note,
+            })
+            .await?
+            .into_inner()
+            .invoice;
+        let result = self
+            .get_node_client()
+            .await?
+            .pay(cln::PayRequest {
+                bolt11: invoice,
+                label,
+                maxfeepercent: Some(self.sdk_config.maxfee_percent),
+                exemptfee: Some(cln::Amount {
+                    msat: self.sdk_config.exemptfee_msat,
+                }),
+                retry_for: Some(self.sdk_config.payment_timeout_sec),
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+
+        // Make sure the payment is persisted in the node before returning it
+        self.fetch_outgoing_payment_with_retry(result.payment_hash)
+            .await?
+            .try_into()
+    }
+
+    async fn start(&self) -> NodeResult<String> {
+        let node_info = self
+            .get_node_client()
+            .await?
+            .getinfo(cln::GetinfoRequest {})
+            .await
+            .map_err(|e| NodeError::ServiceConnectivity(e.to_string()))?
+            .into_inner();
+        Ok(hex::encode(node_info.id))
+    }
+
+    /// The node splits the payment across its channels on its own, so the invoice is paid in
+    /// full with `pay`
+    async fn send_pay(&self, bolt11: String, _max_hops: u32) -> NodeResult<PaymentResponse> {
+        let invoice = parse_invoice(&bolt11)?;
+        validate_network(invoice.clone(), self.sdk_config.network)?;
+        if invoice.amount_msat.is_none() {
+            return Err(NodeError::generic("Invoice has no amount"));
+        }
+
+        let request = cln_pay_request(&self.sdk_config, bolt11, None, None, None)?;
+        self.get_node_client()
+            .await?
+            .pay(request)
+            .await?
+            .into_inner()
+            .try_into()
+    }
+
+    /// Without access to the node's network graph, the max amount is the spendable balance of
+    /// each opened channel
+    async fn max_sendable_amount(
+        &self,
+        _payee_node_id: Option<Vec<u8>>,
+        _max_hops: u32,
+        _last_hop: Option<&RouteHintHop>,
+    ) -> NodeResult<Vec<MaxChannelAmount>> {
+        let (_, opened_channels, _, _) = channels_and_balance(self.list_peer_channels().await?);
+        Ok(opened_channels
+            .into_iter()
+            .filter_map(|c| {
+                Some(MaxChannelAmount {
+                    channel_id: hex::encode(c.channel_id.clone()?),
+                    amount_msat: c.spendable_msat.clone().unwrap_or_default().msat,
+                    path: PaymentPath {
+                        edges: vec![PaymentPathEdge {
+                            base_fee_msat: 0,
+                            fee_per_millionth: 0,
+                            node_id: c.peer_id.clone().unwrap_or_default(),
+                            short_channel_id: c.short_channel_id.unwrap_or_default(),
+                            channel_delay: 0,
+                        }],
+                    },
+                })
+            })
+            .collect())
+    }
+
+    async fn probe_route(
+        &self,
+        payee_node_id: Vec<u8>,
+        amount_msat: u64,
+        final_cltv_delta: u64,
+        max_hops: u32,
+        last_hop: Option<&RouteHintHop>,
+    ) -> NodeResult<Option<RouteProbe>> {
+        let request = route_request(
+            payee_node_id.clone(),
+            amount_msat,
+            final_cltv_delta,
+            max_hops,
+            last_hop,
+        )?;
+        let mut client = self.get_node_client().await?;
+        let route = match client.get_route(request).await {
+            Ok(response) => response.into_inner().route,
+            Err(e) => {
+                info!("No route found to probe the payment: {e}");
+                return Ok(None);
+            }
+        };
+        let fee_msat = route_fee_msat(&route, amount_msat);
+
+        let request = probe_request(
+            route,
+            payee_node_id,
+            amount_msat,
+            final_cltv_delta,
+            last_hop,
+        );
+        let payment_hash = request.payment_hash.clone();
+        if let Err(e) = client.send_pay(request).await {
+            info!("Failed to send the payment probe: {e}");
+            return Ok(Some(RouteProbe {
+                fee_msat,
+                reached_payee: false,
+            }));
+        }
+        let res = client
+            .wait_send_pay(cln::WaitsendpayRequest {
+                payment_hash,
+                partid: None,
+                timeout: Some(self.sdk_config.payment_timeout_sec),
+                groupid: None,
+            })
+            .await;
+        Ok(Some(RouteProbe {
+            fee_msat,
+            reached_payee: res.err().is_some_and(probe_reached_payee),
+        }))
+    }
+
+    async fn redeem_onchain_funds(
+        &self,
+        to_address: String,
+        sat_per_vbyte: u32,
+    ) -> NodeResult<Vec<u8>> {
+        let request = cln::WithdrawRequest {
+            feerate: Some(cln::Feerate {
+                style: Some(cln::feerate::Style::Perkw(sat_per_vbyte * 250)),
+            }),
+            satoshi: Some(cln::AmountOrAll {
+                value: Some(cln::amount_or_all::Value::All(true)),
+            }),
+            destination: to_address,
+            minconf: None,
+            utxos: vec![],
+        };
+        Ok(self
+            .get_node_client()
+            .await?
+            .withdraw(request)
+            .await?
+            .into_inner()
+            .txid)
+    }
+
+    async fn prepare_redeem_onchain_funds(
+        &self,
+        req: PrepareRedeemOnchainFundsRequest,
+    ) -> NodeResult<PrepareRedeemOnchainFundsResponse> {
+        let funds = self.list_funds().await?;
+        prepare_redeem_onchain_funds(utxos(funds), req)
+    }
+
+    async fn sign_replacement_redeem(
+        &self,
+        utxos: Vec<OutPoint>,
+        to_address: String,
+        sat_per_vbyte: u32,
+    ) -> NodeResult<SignedOnchainTx> {
+        let mut client = self.get_node_client().await?;
+        let script_pubkey = Address::from_str(&to_address)?.script_pubkey();
+
+        let utxo_psbt = client
+            .utxo_psbt(replacement_utxo_psbt_request(
+                &utxos,
+                &script_pubkey,
+                sat_per_vbyte,
+            ))
+            .await?
+            .into_inner();
+        let weight = utxo_psbt.estimated_final_weight as u64;
+        let psbt = with_redeem_output(utxo_psbt, script_pubkey)?;
+        let signed_psbt = client
+            .sign_psbt(cln::SignpsbtRequest {
+                psbt,
+                signonly: vec![],
+            })
+            .await?
+            .into_inner()
+            .signed_psbt;
+        signed_onchain_tx(signed_psbt, weight)
+    }
+
+    async fn send_signed_psbt(&self, psbt: String) -> NodeResult<Vec<u8>> {
+        let mut client = self.get_node_client().await?;
+        Ok(client
+            .send_psbt(cln::SendpsbtRequest {
+                psbt,
+                reserve: None,
+            })
+            .await?
+            .into_inner()
+            .txid)
+    }
+
+    async fn cpfp_onchain_funds(
+        &self,
+        utxos: Vec<OutPoint>,
+        to_address: String,
+        sat_per_vbyte: u32,
+    ) -> NodeResult<Vec<u8>> {
+        let request = cpfp_withdraw_request(&utxos, to_address, sat_per_vbyte);
+        Ok(self
+            .get_node_client()
+            .await?
+            .withdraw(request)
+            .await?
+            .into_inner()
+            .txid)
+    }
+
+    /// The node signs with its own keys
+    async fn start_signer(&self, _shutdown: mpsc::Receiver<()>) {}
+
+    /// The node is always on
+    async fn start_keep_alive(&self, _shutdown: watch::Receiver<()>) {}
+
+    async fn connect_peer(&self, id: String, addr: String) -> NodeResult<()> {
+        let connect_req = cln::ConnectRequest {
+            id: format!("{id}@{addr}"),
+            host: None,
+            port: None,
+        };
+        self.get_node_client()
+            .await?
+            .connect_peer(connect_req)
+            .await?;
+        Ok(())
+    }
+
+    async fn sign_message(&self, message: &str) -> NodeResult<String> {
+        let res = self
+            .get_node_client()
+            .await?
+            .sign_message(cln::SignmessageRequest {
+                message: message.to_string(),
+            })
+            .await?
+            .into_inner();
+        Ok(res.zbase)
+    }
+
+    async fn check_message(
+        &self,
+        message: &str,
+        pubkey: &str,
+        signature: &str,
+    ) -> NodeResult<bool> {
+        let pk = PublicKey::from_str(pubkey)?;
+        Ok(verify(message.as_bytes(), signature, &pk))
+    }
+
+    /// `signinvoice` ignores the signature of the invoice it's given, so the invoice is encoded
+    /// with a throwaway signature for the node to replace it
+    fn sign_invoice(&self, invoice: RawBolt11Invoice) -> NodeResult<String> {
+        let secp = Secp256k1::new();
+        let throwaway_key = SecretKey::from_slice(&[1; 32])?;
+        let unsigned_invoice: Result<_> =
+            invoice.sign(|hash| Ok(secp.sign_ecdsa_recoverable(hash, &throwaway_key)));
+        self.sign_invoice_blocking(unsigned_invoice?.to_string())
+    }
+
+    async fn close_peer_channels(&self, node_id: String) -> NodeResult<Vec<String>> {
+        let mut client = self.get_node_client().await?;
+        let channels = client
+            .list_peer_channels(cln::ListpeerchannelsRequest {
+                id: Some(hex::decode(node_id)?),
+            })
+            .await?
+            .into_inner()
+            .channels;
+        let close_to_address = self.persister.get_cached_item(CLOSE_TO_ADDRESS_CACHE_KEY)?;
+        let mut tx_ids = vec![];
+        for channel in channels.into_iter().filter(is_closeable) {
+            let chan_id = channel.channel_id.ok_or(anyhow!("Empty channel id"))?;
+            let res = client
+                .close(cln::CloseRequest {
+                    id: hex::encode(chan_id),
+                    unilateraltimeout: None,
+                    destination: close_to_address.clone(),
+                    fee_negotiation_step: None,
+                    wrong_funding: None,
+                    force_lease_closed: None,
+                    feerange: vec![],
+                })
+                .await
+                .map_err(|e| anyhow!("Empty closing channel: {e}"))?;
+            tx_ids.push(hex::encode(
+                res.into_inner()
+                    .txid
+                    .ok_or(anyhow!("Empty txid in close response"))?,
+            ));
+        }
+        Ok(tx_ids)
+    }
+
+    /// Streams the invoices paid from now on, by waiting for them with `waitanyinvoice`
+    async fn stream_incoming_payments(&self) -> NodeResult<IncomingPaymentStream> {
+        let mut client = self.get_node_client().await?;
+        let lastpay_index = client
+            .list_invoices(cln::ListinvoicesRequest::default())
+            .await?
+            .into_inner()
+            .invoices
+            .iter()
+            .filter_map(|invoice| invoice.pay_index)
+            .max();
+        let stream = futures::stream::unfold(Some((client, lastpay_index)), |state| async move {
+            let (mut client, lastpay_index) = state?;
+            let res = client
+                .wait_any_invoice(cln::WaitanyinvoiceRequest {
+                    lastpay_index,
+                    timeout: None,
+                })
+                .await;
+            match res {
+                Ok(res) => {
+                    let invoice = res.into_inner();
+                    let lastpay_index = invoice.pay_index.or(lastpay_index);
+                    Some((Ok(incoming_payment(invoice)), Some((client, lastpay_index))))
+                }
+                // end the stream after the error, so the caller subscribes again
+                Err(e) => Some((Err(anyhow!("Failed to wait for invoices: {e}")), None)),
+            }
+        });
+        Ok(Box::pin(stream))
+    }
+
+    async fn stream_log_messages(
+        &self,
+    ) -> NodeResult<Streaming<gl_client::signer::model::greenlight::LogEntry>> {
+        Err(NodeError::generic(
+            "Streaming log messages is not supported for CLN nodes",
+        ))
+    }
+
+    async fn static_backup(&self) -> NodeResult<Vec<String>> {
+        let res = self
+            .get_node_client()
+            .await?
+            .static_backup(cln::StaticbackupRequest {})
+            .await?
+            .into_inner();
+        Ok(res.scb.into_iter().map(hex::encode).collect())
+    }
+
+    async fn execute_command(&self, command: String) -> NodeResult<String> {
+        let node_cmd =
+            NodeCommand::from_str(&command).map_err(|_| anyhow!("Command not found: {command}"))?;
+        let mut client = self.get_node_client().await?;
+        match node_cmd {
+            NodeCommand::ListPeers => {
+                let resp = client
+                    .list_peers(cln::ListpeersRequest::default())
+                    .await?
+                    .into_inner();
+                Ok(serde_json::to_string_pretty(&resp)?)
+            }
+            NodeCommand::ListPeerChannels => {
+                let resp = client
+                    .list_peer_channels(cln::ListpeerchannelsRequest::default())
+                    .await?
+                    .into_inner();
+                Ok(serde_json::to_string_pretty(&resp)?)
+            }
+            NodeCommand::ListFunds => {
+                let resp = client
+                    .list_funds(cln::ListfundsRequest::default())
+                    .await?
+                    .into_inner();
+                Ok(serde_json::to_string_pretty(&resp)?)
+            }
+            NodeCommand::ListPayments => {
+                let resp = client
+                    .list_pays(cln::ListpaysRequest::default())
+                    .await?
+                    .into_inner();
+                Ok(serde_json::to_string_pretty(&resp)?)
+            }
+            NodeCommand::ListInvoices => {
+                let resp = client
+                    .list_invoices(cln::ListinvoicesRequest::default())
+                    .await?
+                    .into_inner();
+                Ok(serde_json::to_string_pretty(&resp)?)
+            }
+            NodeCommand::CloseAllChannels => {
+                let peers_res = client
+                    .list_peers(cln::ListpeersRequest::default())
+                    .await?
+                    .into_inner();
+                for p in peers_res.peers {
+                    self.close_peer_channels(hex::encode(p.id)).await?;
+                }
+                Ok("All channels were closed".to_string())
+            }
+            NodeCommand::GetInfo => {
+                let resp = client
+                    .getinfo(cln::GetinfoRequest::default())
+                    .await?
+                    .into_inner();
+                Ok(serde_json::to_string_pretty(&resp)?)
+            }
+            NodeCommand::Stop => {
+                let resp = client.stop(cln::StopRequest::default()).await?.into_inner();
+                Ok(serde_json::to_string_pretty(&resp)?)
+            }
+        }
+    }
+
+    async fn generate_diagnostic_data(&self) -> NodeResult<String> {
+        let all_commands = vec![
+            NodeCommand::GetInfo,
+            NodeCommand::ListPeerChannels,
+            NodeCommand::ListFunds,
+            NodeCommand::ListPayments,
+            NodeCommand::ListInvoices,
+        ];
+
+        let mut result = String::new();
+        for command in all_commands {
+            let res = self
+                .execute_command(command.to_string())
+                .await
+                .unwrap_or_else(|e| e.to_string());
+            result += &format!("***{command}:***\n\n {res}\n\n");
+        }
+        Ok(result)
+    }
+
+    async fn send_custom_message(&self, message: CustomMessage) -> NodeResult<()> {
+        let mut msg = message.message_type.to_be_bytes().to_vec();
+        msg.extend(message.payload);
+        let resp = self
+            .get_node_client()
+            .await?
+            .send_custom_msg(cln::SendcustommsgRequest {
+                msg,
+                node_id: message.peer_id,
+            })
+            .await?
+            .into_inner();
+        debug!("send_custom_message returned status {:?}", resp.status);
+        Ok(())
+    }
+
+    async fn stream_custom_messages(
+        &self,
+    ) -> NodeResult<Pin<Box<dyn Stream<Item = Result<CustomMessage>> + Send>>> {
+        Err(NodeError::generic(
+            "Streaming custom messages is not supported for CLN nodes",
+        ))
+    }
+
+    /// The keys used by the SDK (e.g. for backups and swaps) are derived from the seed, as the
+    /// node keys never leave the node
+    fn derive_bip32_key(&self, path: Vec<ChildNumber>) -> NodeResult<ExtendedPrivKey> {
+        Ok(
+            ExtendedPrivKey::new_master(self.sdk_config.network.into(), &self.seed)?
+                .derive_priv(&crate::bitcoin::secp256k1::Secp256k1::new(), &path)?,
+        )
+    }
+
+    fn legacy_derive_bip32_key(&self, path: Vec<ChildNumber>) -> NodeResult<ExtendedPrivKey> {
+        self.derive_bip32_key(path)
+    }
+
+    async fn get_routing_hints(
+        &self,
+        lsp_info: &LspInformation,
+    ) -> NodeResult<(Vec<RouteHint>, bool)> {
+        let open_peer_channels = open_peer_channels(self.list_peer_channels().await?);
+
+        // Get channels where our node is the destination
+        let pubkey = self
+            .persister
+            .get_node_state()?
+            .map(|n| n.id)
+            .ok_or(NodeError::generic("Node info not found"))?;
+        let channels = self
+            .get_node_client()
+            .await?
+            .list_channels(cln::ListchannelsRequest {
+                destination: Some(hex::decode(pubkey)?),
+                ..Default::default()
+            })
+            .await?
+            .into_inner()
+            .channels;
+
+        routing_hints(open_peer_channels, channels, lsp_info)
+    }
+
+    async fn get_open_peers(&self) -> NodeResult<HashSet<Vec<u8>>> {
+        let open_peer_channels: HashMap<Vec<u8>, cln::ListpeerchannelsChannels> =
+            open_peer_channels(self.list_peer_channels().await?);
+        Ok(open_peer_channels.into_keys().collect())
+    }
+}
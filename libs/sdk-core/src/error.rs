@@ -5,8 +5,13 @@ use sdk_common::prelude::*;
 use thiserror::Error;
 
 use crate::{
-    bitcoin::util::bip32, node_api::NodeError, persist::error::PersistError,
-    swap_in::error::SwapError, swap_out::error::ReverseSwapError,
+    bitcoin::util::bip32,
+    lsps0,
+    lsps2::client::{BuyError, GetInfoError},
+    node_api::NodeError,
+    persist::error::PersistError,
+    swap_in::error::SwapError,
+    swap_out::error::ReverseSwapError,
 };
 
 pub type SdkResult<T, E = SdkError> = Result<T, E>;
@@ -106,6 +111,7 @@ impl From<SendPaymentError> for LnUrlPayError {
             SendPaymentError::RouteNotFound { err } => Self::RouteNotFound { err },
             SendPaymentError::RouteTooExpensive { err } => Self::RouteTooExpensive { err },
             SendPaymentError::ServiceConnectivity { err } => Self::ServiceConnectivity { err },
+            SendPaymentError::BudgetExceeded { err } => Self::BudgetExceeded { err },
         }
     }
 }
@@ -217,6 +223,10 @@ pub enum RedeemOnchainError {
     /// This error is raised when the node does not have enough funds to redeem the onchain balance.
     #[error("{err}")]
     InsufficientFunds { err: String },
+
+    /// This error is raised when the redeem exceeds the [crate::models::SpendingBudget].
+    #[error("Budget exceeded: {err}")]
+    BudgetExceeded { err: String },
 }
 
 impl From<NodeError> for RedeemOnchainError {
@@ -248,6 +258,26 @@ impl From<SdkError> for RedeemOnchainError {
     }
 }
 
+impl From<PersistError> for RedeemOnchainError {
+    fn from(err: PersistError) -> Self {
+        Self::Generic {
+            err: err.to_string(),
+        }
+    }
+}
+
+impl From<SendPaymentError> for RedeemOnchainError {
+    fn from(value: SendPaymentError) -> Self {
+        match value {
+            SendPaymentError::BudgetExceeded { err } => Self::BudgetExceeded { err },
+            SendPaymentError::ServiceConnectivity { err } => Self::ServiceConnectivity { err },
+            _ => Self::Generic {
+                err: value.to_string(),
+            },
+        }
+    }
+}
+
 /// Error returned by [crate::breez_services::BreezServices::receive_payment]
 #[derive(Debug, Error)]
 pub enum ReceivePaymentError {
@@ -340,6 +370,40 @@ impl From<SdkError> for ReceivePaymentError {
     }
 }
 
+impl From<GetInfoError> for ReceivePaymentError {
+    fn from(value: GetInfoError) -> Self {
+        match value {
+            GetInfoError::Lsps0(lsps0::Error::Timeout)
+            | GetInfoError::Lsps0(lsps0::Error::Node(_)) => Self::ServiceConnectivity {
+                err: value.to_string(),
+            },
+            _ => Self::Generic {
+                err: value.to_string(),
+            },
+        }
+    }
+}
+
+impl From<BuyError> for ReceivePaymentError {
+    fn from(value: BuyError) -> Self {
+        match value {
+            BuyError::PaymentSizeTooSmall(_) | BuyError::PaymentSizeTooLarge(_) => {
+                Self::InvalidAmount {
+                    err: value.to_string(),
+                }
+            }
+            BuyError::Lsps0(lsps0::Error::Timeout) | BuyError::Lsps0(lsps0::Error::Node(_)) => {
+                Self::ServiceConnectivity {
+                    err: value.to_string(),
+                }
+            }
+            _ => Self::Generic {
+                err: value.to_string(),
+            },
+        }
+    }
+}
+
 /// General error returned by the SDK
 #[derive(Debug, Error)]
 pub enum SdkError {
@@ -473,7 +537,8 @@ impl From<SendPaymentError> for SdkError {
             | SendPaymentError::PaymentFailed { err }
             | SendPaymentError::PaymentTimeout { err }
             | SendPaymentError::RouteNotFound { err }
-            | SendPaymentError::RouteTooExpensive { err } => Self::Generic { err },
+            | SendPaymentError::RouteTooExpensive { err }
+            | SendPaymentError::BudgetExceeded { err } => Self::Generic { err },
             SendPaymentError::ServiceConnectivity { err } => Self::ServiceConnectivity { err },
         }
     }
@@ -508,6 +573,10 @@ pub enum SendOnchainError {
     /// This error is raised when a connection to an external service fails.
     #[error("Service connectivity: {err}")]
     ServiceConnectivity { err: String },
+
+    /// This error is raised when the payment exceeds the [crate::models::SpendingBudget].
+    #[error("Budget exceeded: {err}")]
+    BudgetExceeded { err: String },
 }
 impl SendOnchainError {
     pub(crate) fn generic(err: &str) -> Self {
@@ -547,6 +616,18 @@ impl From<SdkError> for SendOnchainError {
     }
 }
 
+impl From<SendPaymentError> for SendOnchainError {
+    fn from(value: SendPaymentError) -> Self {
+        match value {
+            SendPaymentError::BudgetExceeded { err } => Self::BudgetExceeded { err },
+            SendPaymentError::ServiceConnectivity { err } => Self::ServiceConnectivity { err },
+            _ => Self::Generic {
+                err: value.to_string(),
+            },
+        }
+    }
+}
+
 impl From<ReverseSwapError> for SendOnchainError {
     fn from(value: ReverseSwapError) -> Self {
         match value {
@@ -612,6 +693,11 @@ pub enum SendPaymentError {
     /// This error is raised when a connection to an external service fails.
     #[error("Service connectivity: {err}")]
     ServiceConnectivity { err: String },
+
+    /// This error is raised when the payment exceeds the [crate::models::SpendingBudget] or
+    /// wasn't approved by the [crate::PaymentApprover].
+    #[error("Budget exceeded: {err}")]
+    BudgetExceeded { err: String },
 }
 
 impl From<anyhow::Error> for SendPaymentError {
@@ -0,0 +1,509 @@
+use chrono::{DateTime, TimeZone, Utc};
+use sdk_common::prelude::*;
+
+use crate::error::{SdkError, SdkResult};
+use crate::models::{ExportFormat, Payment, PaymentDetails, PaymentStatus, PaymentType};
+
+const MSAT_PER_BTC: u64 = 100_000_000_000;
+/// Historical rates may have at most daily data points, so the range is extended by a day to get
+/// a rate for the earliest payments
+const RATES_RANGE_MARGIN_SEC: i64 = 24 * 60 * 60;
+/// The exchange name under which the payments are imported in CoinTracking
+const COINTRACKING_EXCHANGE: &str = "Breez";
+
+/// Historical BTC exchange rates of a fiat currency
+pub(crate) struct HistoricalRates {
+    pub(crate) currency: String,
+    /// Pairs of epoch time in seconds and BTC price, sorted by time
+    pub(crate) prices: Vec<(i64, f64)>,
+}
+
+impl HistoricalRates {
+    /// Fetches the rates covering the given time range, in epoch seconds
+    pub(crate) async fn fetch(
+        fiat_api: &dyn FiatAPI,
+        currency: &str,
+        from: i64,
+        to: i64,
+    ) -> SdkResult<Self> {
+        let prices: Vec<(i64, f64)> = fiat_api
+            .fetch_historical_rates(currency, from - RATES_RANGE_MARGIN_SEC, to)
+            .await?
+            .into_iter()
+            .map(|rate| (rate.timestamp, rate.value))
+            .collect();
+        if prices.is_empty() {
+            return Err(SdkError::Generic {
+                err: format!("No historical rates found for {currency}"),
+            });
+        }
+
+        Ok(Self {
+            currency: currency.to_uppercase(),
+            prices,
+        })
+    }
+
+    /// The latest rate known at the given time, or the earliest rate if there is none
+    fn rate_at(&self, time: i64) -> Option<f64> {
+        let idx = self.prices.partition_point(|(t, _)| *t <= time);
+        self.prices
+            .get(idx.saturating_sub(1))
+            .map(|(_, price)| *price)
+    }
+
+    /// The fiat value of the amount at the given time, formatted with 2 decimals
+    fn value_at(&self, amount_msat: u64, time: i64) -> String {
+        self.rate_at(time)
+            .map(|rate| format!("{:.2}", amount_msat as f64 / MSAT_PER_BTC as f64 * rate))
+            .unwrap_or_default()
+    }
+}
+
+/// Formats the payments in the given format. If rates are given, the fiat value of each payment
+/// is included.
+///
+/// The accounting formats only include the completed Lightning payments: closed channel payments
+/// move funds between wallets of the same user and aren't taxable events.
+pub(crate) fn format_payments(
+    payments: &[Payment],
+    format: ExportFormat,
+    rates: Option<&HistoricalRates>,
+) -> String {
+    let (header, rows): (Vec<String>, Vec<Vec<String>>) = match format {
+        ExportFormat::Csv => (
+            csv_header(rates),
+            payments.iter().map(|p| csv_row(p, rates)).collect(),
+        ),
+        ExportFormat::Koinly => (
+            koinly_header(),
+            accounting_payments(payments)
+                .map(|p| koinly_row(p, rates))
+                .collect(),
+        ),
+        ExportFormat::CoinTracking => (
+            cointracking_header(),
+            accounting_payments(payments)
+                .map(|p| cointracking_row(p, rates))
+                .collect(),
+        ),
+    };
+
+    let mut content = String::new();
+    for line in std::iter::once(header).chain(rows) {
+        let fields: Vec<String> = line.iter().map(|f| csv_field(f)).collect();
+        content.push_str(&fields.join(","));
+        content.push('\n');
+    }
+    content
+}
+
+fn accounting_payments(payments: &[Payment]) -> impl Iterator<Item = &Payment> {
+    payments.iter().filter(|p| {
+        p.status == PaymentStatus::Complete && p.payment_type != PaymentType::ClosedChannel
+    })
+}
+
+fn csv_header(rates: Option<&HistoricalRates>) -> Vec<String> {
+    let mut header: Vec<String> = [
+        "id",
+        "date",
+        "type",
+        "status",
+        "amount_msat",
+        "fee_msat",
+        "description",
+        "payment_hash",
+        "destination_pubkey",
+        "invoice",
+        "swap_address",
+        "swap_txids",
+        "reverse_swap_id",
+        "reverse_swap_lockup_txid",
+        "reverse_swap_claim_txid",
+        "closed_channel_funding_txid",
+        "closed_channel_short_channel_id",
+        "closed_channel_closing_txid",
+        "error",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+    if let Some(rates) = rates {
+        header.push(format!("amount_{}", rates.currency.to_lowercase()));
+    }
+    header
+}
+
+fn csv_row(payment: &Payment, rates: Option<&HistoricalRates>) -> Vec<String> {
+    let mut lightning = vec![String::new(); 8];
+    let mut closed_channel = vec![String::new(); 3];
+    match &payment.details {
+        PaymentDetails::Ln { data } => {
+            let swap = data.swap_info.as_ref();
+            let reverse_swap = data.reverse_swap_info.as_ref();
+            lightning = vec![
+                data.payment_hash.clone(),
+                data.destination_pubkey.clone(),
+                data.bolt11.clone(),
+                swap.map(|s| s.bitcoin_address.clone()).unwrap_or_default(),
+                swap.map(|s| {
+                    [s.confirmed_tx_ids.as_slice(), s.refund_tx_ids.as_slice()]
+                        .concat()
+                        .join(" ")
+                })
+                .unwrap_or_default(),
+                reverse_swap.map(|rs| rs.id.clone()).unwrap_or_default(),
+                reverse_swap
+                    .and_then(|rs| rs.lockup_txid.clone())
+                    .unwrap_or_default(),
+                reverse_swap
+                    .and_then(|rs| rs.claim_txid.clone())
+                    .unwrap_or_default(),
+            ];
+        }
+        PaymentDetails::Bolt12 { data } => {
+            lightning[0] = data.payment_hash.clone();
+            lightning[1] = data.destination_pubkey.clone();
+            lightning[2] = data.invoice.clone();
+        }
+        PaymentDetails::ClosedChannel { data } => {
+            closed_channel = vec![
+                data.funding_txid.clone(),
+                data.short_channel_id.clone().unwrap_or_default(),
+                data.closing_txid.clone().unwrap_or_default(),
+            ];
+        }
+    }
+
+    let mut row = vec![
+        payment.id.clone(),
+        format_date(payment.payment_time, "%Y-%m-%dT%H:%M:%SZ"),
+        payment.payment_type.to_string(),
+        format!("{:?}", payment.status),
+        payment.amount_msat.to_string(),
+        payment.fee_msat.to_string(),
+        payment.description.clone().unwrap_or_default(),
+    ];
+    row.extend(lightning);
+    row.extend(closed_channel);
+    row.push(payment.error.clone().unwrap_or_default());
+    if let Some(rates) = rates {
+        row.push(rates.value_at(payment.amount_msat, payment.payment_time));
+    }
+    row
+}
+
+fn koinly_header() -> Vec<String> {
+    [
+        "Date",
+        "Sent Amount",
+        "Sent Currency",
+        "Received Amount",
+        "Received Currency",
+        "Fee Amount",
+        "Fee Currency",
+        "Net Worth Amount",
+        "Net Worth Currency",
+        "Label",
+        "Description",
+        "TxHash",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn koinly_row(payment: &Payment, rates: Option<&HistoricalRates>) -> Vec<String> {
+    let amount = format_btc(payment.amount_msat);
+    let (sent, received) = match payment.payment_type {
+        PaymentType::Sent => ((amount, "BTC".to_string()), Default::default()),
+        _ => (Default::default(), (amount, "BTC".to_string())),
+    };
+    let fee = match payment.fee_msat {
+        0 => Default::default(),
+        fee_msat => (format_btc(fee_msat), "BTC".to_string()),
+    };
+    let net_worth = match rates {
+        Some(rates) => (
+            rates.value_at(payment.amount_msat, payment.payment_time),
+            rates.currency.clone(),
+        ),
+        None => Default::default(),
+    };
+
+    vec![
+        format_date(payment.payment_time, "%Y-%m-%d %H:%M UTC"),
+        sent.0,
+        sent.1,
+        received.0,
+        received.1,
+        fee.0,
+        fee.1,
+        net_worth.0,
+        net_worth.1,
+        String::new(),
+        payment.description.clone().unwrap_or_default(),
+        payment.id.clone(),
+    ]
+}
+
+fn cointracking_header() -> Vec<String> {
+    [
+        "Type",
+        "Buy Amount",
+        "Buy Currency",
+        "Sell Amount",
+        "Sell Currency",
+        "Fee",
+        "Fee Currency",
+        "Exchange",
+        "Trade-Group",
+        "Comment",
+        "Date",
+        "Tx-ID",
+        "Buy Value in Account Currency",
+        "Sell Value in Account Currency",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn cointracking_row(payment: &Payment, rates: Option<&HistoricalRates>) -> Vec<String> {
+    let amount = format_btc(payment.amount_msat);
+    let value = rates
+        .map(|rates| rates.value_at(payment.amount_msat, payment.payment_time))
+        .unwrap_or_default();
+    let (kind, buy, sell, buy_value, sell_value) = match payment.payment_type {
+        PaymentType::Sent => (
+            "Withdrawal",
+            Default::default(),
+            (amount, "BTC".to_string()),
+            String::new(),
+            value,
+        ),
+        _ => (
+            "Deposit",
+            (amount, "BTC".to_string()),
+            Default::default(),
+            value,
+            String::new(),
+        ),
+    };
+    let fee = match payment.fee_msat {
+        0 => Default::default(),
+        fee_msat => (format_btc(fee_msat), "BTC".to_string()),
+    };
+
+    vec![
+        kind.to_string(),
+        buy.0,
+        buy.1,
+        sell.0,
+        sell.1,
+        fee.0,
+        fee.1,
+        COINTRACKING_EXCHANGE.to_string(),
+        String::new(),
+        payment.description.clone().unwrap_or_default(),
+        format_date(payment.payment_time, "%d.%m.%Y %H:%M:%S"),
+        payment.id.clone(),
+        buy_value,
+        sell_value,
+    ]
+}
+
+/// Formats the amount in BTC, without trailing zeros
+fn format_btc(amount_msat: u64) -> String {
+    let fraction = format!("{:011}", amount_msat % MSAT_PER_BTC);
+    let fraction = fraction.trim_end_matches('0');
+    match fraction.is_empty() {
+        true => (amount_msat / MSAT_PER_BTC).to_string(),
+        false => format!("{}.{fraction}", amount_msat / MSAT_PER_BTC),
+    }
+}
+
+fn format_date(epoch_seconds: i64, fmt: &str) -> String {
+    let date: Option<DateTime<Utc>> = Utc.timestamp_opt(epoch_seconds, 0).single();
+    date.map(|d| d.format(fmt).to_string()).unwrap_or_default()
+}
+
+/// Quotes the field if it contains a character with a special meaning in CSV.
+///
+/// Fields starting like a formula, e.g. a payment description set by the payer, are prefixed
+/// with `'` so spreadsheets don't evaluate them.
+fn csv_field(field: &str) -> String {
+    let field = match field.starts_with(['=', '+', '-', '@']) {
+        true => format!("'{field}"),
+        false => field.to_string(),
+    };
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::*;
+    use crate::models::{ClosedChannelPaymentDetails, LnPaymentDetails};
+    use crate::test_utils::MockBreezServer;
+    use crate::ChannelState;
+
+    fn ln_payment(id: &str, payment_type: PaymentType, amount_msat: u64, fee_msat: u64) -> Payment {
+        Payment {
+            description: Some("coffee, \"large\"".into()),
+            ..Payment::new(
+                id.into(),
+                payment_type,
+                1_700_000_000,
+                amount_msat,
+                fee_msat,
+                PaymentStatus::Complete,
+                PaymentDetails::Ln {
+                    data: LnPaymentDetails {
+                        payment_hash: id.into(),
+                        label: String::new(),
+                        destination_pubkey: String::new(),
+                        payment_preimage: String::new(),
+                        keysend: false,
+                        bolt11: "lnbc1".into(),
+                        open_channel_bolt11: None,
+                        lnurl_success_action: None,
+                        lnurl_pay_domain: None,
+                        lnurl_pay_comment: None,
+                        ln_address: None,
+                        lnurl_metadata: None,
+                        lnurl_withdraw_endpoint: None,
+                        lnurl_verify_url: None,
+                        swap_info: None,
+                        reverse_swap_info: None,
+                        pending_expiration_block: None,
+                    },
+                },
+            )
+        }
+    }
+
+    fn closed_channel_payment() -> Payment {
+        Payment {
+            description: Some("Closed Channel".into()),
+            ..Payment::new(
+                "closing".into(),
+                PaymentType::ClosedChannel,
+                1_700_000_100,
+                50_000_000,
+                0,
+                PaymentStatus::Complete,
+                PaymentDetails::ClosedChannel {
+                    data: ClosedChannelPaymentDetails {
+                        state: ChannelState::Closed,
+                        funding_txid: "funding".into(),
+                        short_channel_id: Some("1x2x3".into()),
+                        closing_txid: Some("closing".into()),
+                    },
+                },
+            )
+        }
+    }
+
+    fn rates() -> HistoricalRates {
+        HistoricalRates {
+            currency: "USD".into(),
+            prices: vec![(1_699_900_000, 30_000.0), (1_700_000_000, 40_000.0)],
+        }
+    }
+
+    #[test]
+    fn test_format_btc() {
+        assert_eq!(format_btc(0), "0");
+        assert_eq!(format_btc(1), "0.00000000001");
+        assert_eq!(format_btc(1_000), "0.00000001");
+        assert_eq!(format_btc(150_000_000_000), "1.5");
+    }
+
+    #[test]
+    fn test_rate_at() {
+        let rates = rates();
+        assert_eq!(rates.rate_at(1_600_000_000), Some(30_000.0));
+        assert_eq!(rates.rate_at(1_699_999_999), Some(30_000.0));
+        assert_eq!(rates.rate_at(1_700_000_000), Some(40_000.0));
+        assert_eq!(rates.value_at(100_000_000, 1_700_000_000), "40.00");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_rates() -> SdkResult<()> {
+        let rates =
+            HistoricalRates::fetch(&MockBreezServer {}, "usd", 1_700_000_000, 1_700_100_000)
+                .await?;
+        assert_eq!(rates.currency, "USD");
+        assert_eq!(
+            rates.prices,
+            vec![
+                (1_700_000_000 - RATES_RANGE_MARGIN_SEC, 20_000.0),
+                (1_700_100_000, 30_000.0)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_csv() {
+        let payments = vec![
+            ln_payment("hash1", PaymentType::Received, 100_000_000, 0),
+            closed_channel_payment(),
+        ];
+        let content = format_payments(&payments, ExportFormat::Csv, Some(&rates()));
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("id,date,type,status,amount_msat,fee_msat,"));
+        assert!(lines[0].ends_with(",error,amount_usd"));
+        assert_eq!(
+            lines[1],
+            "hash1,2023-11-14T22:13:20Z,Received,Complete,100000000,0,\"coffee, \"\"large\"\"\",hash1,,lnbc1,,,,,,,,,,40.00"
+        );
+        assert!(lines[2].contains(",funding,1x2x3,closing,"));
+    }
+
+    #[test]
+    fn test_csv_field_formula() {
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_field("+1"), "'+1");
+        assert_eq!(csv_field("-1"), "'-1");
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_field("a=b"), "a=b");
+    }
+
+    #[test]
+    fn test_format_accounting() {
+        let mut failed = ln_payment("failed", PaymentType::Sent, 1_000, 0);
+        failed.status = PaymentStatus::Failed;
+        let payments = vec![
+            ln_payment("hash1", PaymentType::Received, 100_000_000, 0),
+            ln_payment("hash2", PaymentType::Sent, 50_000_000, 1_000),
+            failed,
+            closed_channel_payment(),
+        ];
+
+        let koinly = format_payments(&payments, ExportFormat::Koinly, None);
+        let lines: Vec<&str> = koinly.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            "2023-11-14 22:13 UTC,,,0.001,BTC,,,,,,\"coffee, \"\"large\"\"\",hash1"
+        );
+        assert_eq!(
+            lines[2],
+            "2023-11-14 22:13 UTC,0.0005,BTC,,,0.00000001,BTC,,,,\"coffee, \"\"large\"\"\",hash2"
+        );
+
+        let cointracking = format_payments(&payments, ExportFormat::CoinTracking, Some(&rates()));
+        let lines: Vec<&str> = cointracking.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[2],
+            "Withdrawal,,,0.0005,BTC,0.00000001,BTC,Breez,,\"coffee, \"\"large\"\"\",14.11.2023 22:13:20,hash2,,20.00"
+        );
+    }
+}
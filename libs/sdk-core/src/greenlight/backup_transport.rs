@@ -7,7 +7,7 @@ use super::node_api::Greenlight;
 use gl_client::{node, pb::cln};
 use std::sync::Arc;
 
-const BREEZ_SDK_DATASTORE_PATH: [&str; 2] = ["breez-sdk", "backup"];
+pub(crate) const BREEZ_SDK_DATASTORE_PATH: [&str; 2] = ["breez-sdk", "backup"];
 
 pub(crate) struct GLBackupTransport {
     pub(crate) inner: Arc<Greenlight>,
@@ -1,5 +1,5 @@
 mod backup_transport;
 pub(crate) mod error;
-mod node_api;
-pub(crate) use backup_transport::GLBackupTransport;
+pub(crate) mod node_api;
+pub(crate) use backup_transport::{GLBackupTransport, BREEZ_SDK_DATASTORE_PATH};
 pub(crate) use node_api::Greenlight;
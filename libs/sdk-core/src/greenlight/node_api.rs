@@ -1,4 +1,4 @@
-use std::cmp::{min, Reverse};
+use std::cmp::{max, min};
 use std::collections::{HashMap, HashSet};
 use std::iter::Iterator;
 use std::pin::Pin;
@@ -39,6 +39,8 @@ use tonic::{Code, Streaming};
 use crate::bitcoin::bech32::{u5, ToBase32};
 use crate::bitcoin::blockdata::constants::WITNESS_SCALE_FACTOR;
 use crate::bitcoin::hashes::Hash;
+use crate::bitcoin::psbt::serialize::{Deserialize as PsbtDeserialize, Serialize as PsbtSerialize};
+use crate::bitcoin::psbt::PartiallySignedTransaction;
 use crate::bitcoin::secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
 use crate::bitcoin::secp256k1::PublicKey;
 use crate::bitcoin::secp256k1::Secp256k1;
@@ -46,9 +48,13 @@ use crate::bitcoin::util::bip32::{ChildNumber, ExtendedPrivKey};
 use crate::bitcoin::{
     Address, OutPoint, Script, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
 };
+use crate::greenlight::error::{parse_cln_error, JsonRpcErrCode};
 use crate::lightning::util::message_signing::verify;
 use crate::lightning_invoice::{RawBolt11Invoice, SignedRawBolt11Invoice};
-use crate::node_api::{CreateInvoiceRequest, FetchBolt11Result, NodeAPI, NodeError, NodeResult};
+use crate::node_api::{
+    CreateInvoiceRequest, FetchBolt11Result, IncomingPaymentStream, NodeAPI, NodeError,
+    NodeFeature, NodeResult, RouteProbe, SignedOnchainTx,
+};
 use crate::persist::db::SqliteStorage;
 use crate::{models::*, LspInformation};
 use crate::{NodeConfig, PrepareRedeemOnchainFundsRequest, PrepareRedeemOnchainFundsResponse};
@@ -98,6 +104,11 @@ impl Greenlight {
 
         let register_credentials = match config.node_config.clone() {
             NodeConfig::Greenlight { config } => config,
+            NodeConfig::Cln { .. } => {
+                return Err(NodeError::generic(
+                    "Greenlight can't be used with a CLN node config",
+                ))
+            }
         };
 
         // Query for the existing credentials
@@ -499,38 +510,7 @@ impl Greenlight {
             .list_peer_channels(cln::ListpeerchannelsRequest::default())
             .await?
             .into_inner();
-
-        // filter only connected peers
-        let connected_peers: Vec<String> = peerchannels
-            .channels
-            .iter()
-            .filter(|channel| channel.peer_connected())
-            .filter_map(|channel| channel.peer_id.clone())
-            .map(hex::encode)
-            .collect::<HashSet<_>>()
-            .into_iter()
-            .collect();
-
-        // filter only opened channels
-        let opened_channels: Vec<cln::ListpeerchannelsChannels> = peerchannels
-            .channels
-            .iter()
-            .filter(|c| c.state() == ChanneldNormal)
-            .cloned()
-            .collect();
-
-        // calculate channels balance only from opened channels
-        let channels_balance = opened_channels
-            .iter()
-            .map(|c| Channel::from(c.clone()))
-            .map(|c| c.spendable_msat)
-            .sum::<u64>();
-        Ok((
-            peerchannels.channels,
-            opened_channels,
-            connected_peers,
-            channels_balance,
-        ))
+        Ok(channels_and_balance(peerchannels.channels))
     }
 
     async fn list_funds(&self) -> Result<cln::ListfundsResponse> {
@@ -542,65 +522,6 @@ impl Greenlight {
         Ok(funds)
     }
 
-    async fn on_chain_balance(&self, funds: &cln::ListfundsResponse) -> Result<u64> {
-        let on_chain_balance = funds.outputs.iter().fold(0, |a, b| {
-            if b.reserved {
-                return a;
-            }
-            a + b.amount_msat.clone().unwrap_or_default().msat
-        });
-        Ok(on_chain_balance)
-    }
-
-    async fn pending_onchain_balance(
-        &self,
-        peer_channels: &[cln::ListpeerchannelsChannels],
-    ) -> Result<u64> {
-        let pending_onchain_balance = peer_channels.iter().fold(0, |a, b| match b.state() {
-            ChanneldShuttingDown | ClosingdSigexchange | ClosingdComplete | AwaitingUnilateral
-            | FundingSpendSeen => a + b.to_us_msat.clone().unwrap_or_default().msat,
-
-            // When we  unilaterally close the channel it will get status as `AwaitingUnilateral`
-            // first, but when the closing transaction is confirmed onchain the funds receive status
-            // as `Onchain`. Though if we closed the channel we'll have to wait for the timelock to
-            // pass before the funds can be spent.
-            Onchain => {
-                if b.closer() == cln::ChannelSide::Local
-                    && b.status
-                        .last()
-                        .is_some_and(|status| status.contains("DELAYED_OUTPUT_TO_US"))
-                {
-                    a + b.to_us_msat.clone().unwrap_or_default().msat
-                } else {
-                    a
-                }
-            }
-            _ => a,
-        });
-        info!("pending_onchain_balance is {}", pending_onchain_balance);
-        Ok(pending_onchain_balance)
-    }
-
-    // Collect utxos from onchain funds
-    async fn utxos(&self, funds: cln::ListfundsResponse) -> Result<Vec<UnspentTransactionOutput>> {
-        let utxos: Vec<UnspentTransactionOutput> = funds
-            .outputs
-            .iter()
-            .map(|output| UnspentTransactionOutput {
-                txid: output.txid.clone(),
-                outnum: output.output,
-                amount_millisatoshi: output
-                    .amount_msat
-                    .as_ref()
-                    .map(|a| a.msat)
-                    .unwrap_or_default(),
-                address: output.address.clone().unwrap_or_default(),
-                reserved: output.reserved,
-            })
-            .collect();
-        Ok(utxos)
-    }
-
     async fn build_payment_path(
         &self,
         route: &Vec<GetrouteRoute>,
@@ -765,15 +686,7 @@ impl Greenlight {
             .await?
             .into_inner();
 
-        let open_peer_channels: HashMap<Vec<u8>, cln::ListpeerchannelsChannels> = peer_channels
-            .channels
-            .into_iter()
-            .filter(|c| {
-                c.state == Some(cln::ChannelState::ChanneldNormal as i32) && c.peer_id.is_some()
-            })
-            .map(|c| (c.peer_id.clone().unwrap(), c))
-            .collect();
-        Ok(open_peer_channels)
+        Ok(open_peer_channels(peer_channels.channels))
     }
 
     async fn with_keep_alive<T, F>(&self, f: F) -> T
@@ -789,6 +702,10 @@ impl Greenlight {
 
 #[tonic::async_trait]
 impl NodeAPI for Greenlight {
+    fn unsupported_features(&self) -> Vec<NodeFeature> {
+        vec![NodeFeature::Offers]
+    }
+
     fn node_credentials(&self) -> NodeResult<Option<NodeCredentials>> {
         Ok(Self::get_node_credentials(
             self.sdk_config.network,
@@ -829,26 +746,10 @@ impl NodeAPI for Greenlight {
 
     async fn create_invoice(&self, request: CreateInvoiceRequest) -> NodeResult<String> {
         let mut client = self.get_node_client().await?;
-        let label = serde_json::to_string(&InvoiceLabel {
-            unix_milli: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis(),
-            payer_amount_msat: request.payer_amount_msat,
-        })?;
-        let cln_request = cln::InvoiceRequest {
-            amount_msat: Some(cln::AmountOrAny {
-                value: Some(cln::amount_or_any::Value::Amount(cln::Amount {
-                    msat: request.amount_msat,
-                })),
-            }),
-            label,
-            description: request.description,
-            preimage: request.preimage,
-            deschashonly: request.use_description_hash,
-            expiry: request.expiry.map(|e| e as u64),
-            fallbacks: vec![],
-            cltv: request.cltv,
-        };
-
-        let res = client.invoice(cln_request).await?.into_inner();
+        let res = client
+            .invoice(cln_invoice_request(request)?)
+            .await?
+            .into_inner();
         Ok(res.bolt11)
     }
 
@@ -867,15 +768,7 @@ impl NodeAPI for Greenlight {
             .invoices
             .first()
             .cloned()
-            .and_then(|invoice| {
-                invoice.bolt11.map(|bolt11| FetchBolt11Result {
-                    bolt11,
-                    payer_amount_msat: serde_json::from_str::<InvoiceLabel>(&invoice.label)
-                        .map(|label| label.payer_amount_msat)
-                        .ok()
-                        .flatten(),
-                })
-            });
+            .and_then(fetch_bolt11_result);
 
         Ok(result)
     }
@@ -916,71 +809,12 @@ impl NodeAPI for Greenlight {
             balance_future
         );
 
-        let node_info = node_info_res?.into_inner();
-        let funds = funds_res?;
-        let closed_channels = closed_channels_res?.into_inner().closedchannels;
-        let (all_channels, opened_channels, connected_peers, channels_balance) = balance_res?;
-        let forgotten_closed_channels: NodeResult<Vec<Channel>> = closed_channels
-            .into_iter()
-            .filter(|cc| {
-                all_channels
-                    .iter()
-                    .all(|ac| ac.funding_txid != Some(cc.funding_txid.clone()))
-            })
-            .map(TryInto::try_into)
-            .collect();
-        info!("forgotten_closed_channels {:?}", forgotten_closed_channels);
-
-        let mut all_channel_models: Vec<Channel> =
-            all_channels.clone().into_iter().map(|c| c.into()).collect();
-        all_channel_models.extend(forgotten_closed_channels?);
-
-        // calculate onchain balance
-        let onchain_balance = self.on_chain_balance(&funds).await?;
-        let pending_onchain_balance = self.pending_onchain_balance(&all_channels).await?;
-        let utxos: Vec<UnspentTransactionOutput> = self.utxos(funds).await?;
-
-        // calculate payment limits and inbound liquidity
-        let mut max_payable: u64 = 0;
-        let mut max_receivable_single_channel: u64 = 0;
-        let mut total_inbound_liquidity_msats: u64 = 0;
-        opened_channels.iter().try_for_each(|c| -> Result<()> {
-            max_payable += c
-                .spendable_msat
-                .as_ref()
-                .map(|a| a.msat)
-                .unwrap_or_default();
-            let receivable_amount = c
-                .receivable_msat
-                .as_ref()
-                .map(|a| a.msat)
-                .unwrap_or_default();
-            total_inbound_liquidity_msats += receivable_amount;
-            if receivable_amount > max_receivable_single_channel {
-                max_receivable_single_channel = receivable_amount;
-            }
-            Ok(())
-        })?;
-
-        let max_allowed_to_receive_msats =
-            MAX_INBOUND_LIQUIDITY_MSAT.saturating_sub(channels_balance);
-        let node_pubkey = hex::encode(node_info.id);
-        // construct the node state
-        let node_state = NodeState {
-            id: node_pubkey.clone(),
-            block_height: node_info.blockheight,
-            channels_balance_msat: channels_balance,
-            onchain_balance_msat: onchain_balance,
-            pending_onchain_balance_msat: pending_onchain_balance,
-            utxos,
-            max_payable_msat: max_payable,
-            max_receivable_msat: max_allowed_to_receive_msats,
-            max_single_payment_amount_msat: MAX_PAYMENT_AMOUNT_MSAT,
-            max_chan_reserve_msats: channels_balance - min(max_payable, channels_balance),
-            connected_peers,
-            max_receivable_single_payment_amount_msat: max_receivable_single_channel,
-            total_inbound_liquidity_msats,
-        };
+        let (node_state, all_channel_models) = node_state_and_channels(
+            node_info_res?.into_inner(),
+            funds_res?,
+            closed_channels_res?.into_inner().closedchannels,
+            balance_res?,
+        )?;
         let mut htlc_list: Vec<Htlc> = Vec::new();
         for channel in all_channel_models.clone() {
             htlc_list.extend(channel.htlcs);
@@ -1002,7 +836,7 @@ impl NodeAPI for Greenlight {
         validate_network(invoice.clone(), self.sdk_config.network)?;
 
         // We first calculate for each channel the max amount to pay (at the receiver)
-        let mut max_amount_per_channel = self
+        let max_amount_per_channel = self
             .max_sendable_amount(Some(hex::decode(invoice.payee_pubkey)?), max_hops, last_hop)
             .await?;
         info!("send_pay: routes: {:?}", max_amount_per_channel);
@@ -1010,21 +844,18 @@ impl NodeAPI for Greenlight {
         // Calculate the total amount to pay
         let total_msat: u64 = max_amount_per_channel.iter().map(|m| m.amount_msat).sum();
 
-        // Sort the channels by max amount descending so we can build the route in a way that it
-        // drains the largest channels first
-        max_amount_per_channel.sort_by_key(|m| Reverse(m.amount_msat));
-
         let amount_to_pay_msat = match invoice.amount_msat {
             Some(amount) => Ok(amount),
             None => Err(NodeError::generic("Invoice has no amount")),
         }?;
 
-        if amount_to_pay_msat > total_msat {
-            return Err(NodeError::RouteNotFound(format!(
+        // Split the amount in a way that drains the largest channels first
+        let parts = split_payment(max_amount_per_channel, amount_to_pay_msat).ok_or_else(|| {
+            NodeError::RouteNotFound(format!(
                 "Amount too high, max amount is {} msat",
                 total_msat
-            )));
-        }
+            ))
+        })?;
 
         // This is needed in greenlight for the signer to recognize this invoice.
         client
@@ -1042,14 +873,8 @@ impl NodeAPI for Greenlight {
         // Generate a random group_id for the payment
         let group_id = rand::random::<u64>();
 
-        // The algorithm goes over each channel and drains it until the received amount
-        // equals to the amount to pay defined in the bolt11 invoice.
-        for max in max_amount_per_channel {
-            // calculating the incoming amount for the remaining amount to pay.
-            let left_to_pay_msat = amount_to_pay_msat - amount_received_msat;
-            // Whether we draining the whole channel balance or only what is left to pay
-            let to_pay_msat = std::cmp::min(left_to_pay_msat, max.amount_msat);
-
+        // Each part either drains the channel balance or pays what is left to pay
+        for (max, to_pay_msat) in parts {
             // We convert our payment path to an actual route that can be sent to the node.
             // This requires calculating the right fees and cltv delta in each hop.
             let (route, sent_msat) = convert_to_send_pay_route(
@@ -1081,9 +906,6 @@ impl NodeAPI for Greenlight {
             part_id += 1;
             amount_sent_msat += sent_msat;
             amount_received_msat += to_pay_msat;
-            if amount_received_msat == amount_to_pay_msat {
-                break;
-            }
         }
 
         // Now we wait for the first part to be completed as a way to wait for the payment
@@ -1111,31 +933,10 @@ impl NodeAPI for Greenlight {
         bolt11: String,
         amount_msat: Option<u64>,
         label: Option<String>,
+        max_fee_msat: Option<u64>,
     ) -> NodeResult<Payment> {
-        let mut description = None;
-        if !bolt11.is_empty() {
-            let invoice = parse_invoice(&bolt11)?;
-            validate_network(invoice.clone(), self.sdk_config.network)?;
-            description = invoice.description;
-        }
-
+        let request = cln_pay_request(&self.sdk_config, bolt11, amount_msat, label, max_fee_msat)?;
         let mut client: node::ClnClient = self.get_node_client().await?;
-        let request = cln::PayRequest {
-            bolt11,
-            amount_msat: amount_msat.map(|amt| cln::Amount { msat: amt }),
-            maxfeepercent: Some(self.sdk_config.maxfee_percent),
-            retry_for: Some(self.sdk_config.payment_timeout_sec),
-            label,
-            maxdelay: None,
-            riskfactor: None,
-            localinvreqid: None,
-            exclude: vec![],
-            maxfee: None,
-            description,
-            exemptfee: Some(cln::Amount {
-                msat: self.sdk_config.exemptfee_msat,
-            }),
-        };
         let result: cln::PayResponse = self
             .with_keep_alive(client.pay(request))
             .await?
@@ -1155,28 +956,8 @@ impl NodeAPI for Greenlight {
         label: Option<String>,
     ) -> NodeResult<Payment> {
         let mut client: node::ClnClient = self.get_node_client().await?;
-        let request = cln::KeysendRequest {
-            destination: hex::decode(node_id)?,
-            amount_msat: Some(cln::Amount { msat: amount_msat }),
-            label: label.or(Some(format!(
-                "breez-{}",
-                SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis()
-            ))),
-            extratlvs: extra_tlvs.map(|tlvs| cln::TlvStream {
-                entries: tlvs
-                    .into_iter()
-                    .map(|tlv| cln::TlvEntry {
-                        r#type: tlv.field_number,
-                        value: tlv.value,
-                    })
-                    .collect(),
-            }),
-            routehints: None,
-            maxfeepercent: Some(self.sdk_config.maxfee_percent),
-            exemptfee: None,
-            retry_for: Some(self.sdk_config.payment_timeout_sec),
-            maxdelay: None,
-        };
+        let request =
+            cln_keysend_request(&self.sdk_config, node_id, amount_msat, extra_tlvs, label)?;
         let result = self
             .with_keep_alive(client.key_send(request))
             .await?
@@ -1188,6 +969,24 @@ impl NodeAPI for Greenlight {
         payment.try_into()
     }
 
+    async fn create_offer(&self, _req: CreateOfferRequest) -> NodeResult<String> {
+        Err(NodeError::generic(
+            "BOLT12 offers are not supported by Greenlight nodes",
+        ))
+    }
+
+    async fn pay_offer(
+        &self,
+        _offer: String,
+        _amount_msat: Option<u64>,
+        _payer_note: Option<String>,
+        _label: Option<String>,
+    ) -> NodeResult<Payment> {
+        Err(NodeError::generic(
+            "BOLT12 offers are not supported by Greenlight nodes",
+        ))
+    }
+
     async fn start(&self) -> NodeResult<String> {
         let node_info = self
             .get_node_client()
@@ -1226,52 +1025,60 @@ impl NodeAPI for Greenlight {
         req: PrepareRedeemOnchainFundsRequest,
     ) -> NodeResult<PrepareRedeemOnchainFundsResponse> {
         let funds = self.list_funds().await?;
-        let utxos = self.utxos(funds).await?;
+        prepare_redeem_onchain_funds(utxos(funds), req)
+    }
 
-        let mut amount_msat: u64 = 0;
-        let txins: Vec<TxIn> = utxos
-            .iter()
-            .map(|utxo| {
-                amount_msat += utxo.amount_millisatoshi;
-                TxIn {
-                    previous_output: OutPoint {
-                        txid: Txid::from_slice(&utxo.txid).unwrap(),
-                        vout: 0,
-                    },
-                    script_sig: Script::new(),
-                    sequence: Sequence(0),
-                    witness: Witness::default(),
-                }
+    async fn sign_replacement_redeem(
+        &self,
+        utxos: Vec<OutPoint>,
+        to_address: String,
+        sat_per_vbyte: u32,
+    ) -> NodeResult<SignedOnchainTx> {
+        let mut client = self.get_node_client().await?;
+        let script_pubkey = Address::from_str(&to_address)?.script_pubkey();
+
+        let utxo_psbt = client
+            .utxo_psbt(replacement_utxo_psbt_request(
+                &utxos,
+                &script_pubkey,
+                sat_per_vbyte,
+            ))
+            .await?
+            .into_inner();
+        let weight = utxo_psbt.estimated_final_weight as u64;
+        let psbt = with_redeem_output(utxo_psbt, script_pubkey)?;
+        let signed_psbt = client
+            .sign_psbt(cln::SignpsbtRequest {
+                psbt,
+                signonly: vec![],
             })
-            .collect();
-
-        let amount_sat = amount_msat / 1_000;
-        let btc_address = Address::from_str(&req.to_address)?;
-        let tx_out: Vec<TxOut> = vec![TxOut {
-            value: amount_sat,
-            script_pubkey: btc_address.payload.script_pubkey(),
-        }];
-        let tx = Transaction {
-            version: 2,
-            lock_time: crate::bitcoin::PackedLockTime(0),
-            input: txins.clone(),
-            output: tx_out,
-        };
+            .await?
+            .into_inner()
+            .signed_psbt;
+        signed_onchain_tx(signed_psbt, weight)
+    }
 
-        let witness_input_size: u64 = 110;
-        let tx_weight = tx.strippedsize() as u64 * WITNESS_SCALE_FACTOR as u64
-            + witness_input_size * txins.len() as u64;
-        let fee: u64 = tx_weight * req.sat_per_vbyte as u64 / WITNESS_SCALE_FACTOR as u64;
-        if fee >= amount_sat {
-            return Err(NodeError::InsufficientFunds(
-                "Insufficient funds to pay fees".to_string(),
-            ));
-        }
+    async fn send_signed_psbt(&self, psbt: String) -> NodeResult<Vec<u8>> {
+        let mut client = self.get_node_client().await?;
+        Ok(client
+            .send_psbt(cln::SendpsbtRequest {
+                psbt,
+                reserve: None,
+            })
+            .await?
+            .into_inner()
+            .txid)
+    }
 
-        return Ok(PrepareRedeemOnchainFundsResponse {
-            tx_weight,
-            tx_fee_sat: fee,
-        });
+    async fn cpfp_onchain_funds(
+        &self,
+        utxos: Vec<OutPoint>,
+        to_address: String,
+        sat_per_vbyte: u32,
+    ) -> NodeResult<Vec<u8>> {
+        let mut client = self.get_node_client().await?;
+        let request = cpfp_withdraw_request(&utxos, to_address, sat_per_vbyte);
+        Ok(client.withdraw(request).await?.into_inner().txid)
     }
 
     /// Starts the signer that listens in a loop until the shutdown signal is received
@@ -1391,22 +1198,7 @@ impl NodeAPI for Greenlight {
             .into_inner();
         let mut tx_ids = vec![];
         for channel in closed_channels.channels {
-            let mut should_close = false;
-            if let Some(state) = channel.state {
-                match cln::ChannelState::from_i32(state) {
-                    Some(cln::ChannelState::Openingd) => should_close = true,
-                    Some(cln::ChannelState::ChanneldAwaitingLockin) => should_close = true,
-                    Some(cln::ChannelState::ChanneldNormal) => should_close = true,
-                    Some(cln::ChannelState::ChanneldShuttingDown) => should_close = true,
-                    Some(cln::ChannelState::FundingSpendSeen) => should_close = true,
-                    Some(cln::ChannelState::DualopendOpenInit) => should_close = true,
-                    Some(cln::ChannelState::DualopendAwaitingLockin) => should_close = true,
-                    Some(_) => should_close = false,
-                    None => should_close = false,
-                }
-            }
-
-            if should_close {
+            if is_closeable(&channel) {
                 let chan_id = channel.channel_id.ok_or(anyhow!("Empty channel id"))?;
                 let response = client
                     .close(cln::CloseRequest {
@@ -1434,15 +1226,15 @@ impl NodeAPI for Greenlight {
         Ok(tx_ids)
     }
 
-    async fn stream_incoming_payments(
-        &self,
-    ) -> NodeResult<Streaming<gl_client::signer::model::greenlight::IncomingPayment>> {
+    async fn stream_incoming_payments(&self) -> NodeResult<IncomingPaymentStream> {
         let mut client = self.get_client().await?;
         let stream = client
             .stream_incoming(gl_client::signer::model::greenlight::StreamIncomingFilter {})
             .await?
             .into_inner();
-        Ok(stream)
+        Ok(Box::pin(stream.map(|payment| {
+            payment.map_err(|e| anyhow!("failed to receive incoming payment: {e}"))
+        })))
     }
 
     async fn stream_log_messages(
@@ -1609,6 +1401,60 @@ impl NodeAPI for Greenlight {
         Ok(max_channel_amounts)
     }
 
+    async fn probe_route(
+        &self,
+        payee_node_id: Vec<u8>,
+        amount_msat: u64,
+        final_cltv_delta: u64,
+        max_hops: u32,
+        last_hop: Option<&RouteHintHop>,
+    ) -> NodeResult<Option<RouteProbe>> {
+        let request = route_request(
+            payee_node_id.clone(),
+            amount_msat,
+            final_cltv_delta,
+            max_hops,
+            last_hop,
+        )?;
+        let mut client = self.get_node_client().await?;
+        let route = match client.get_route(request).await {
+            Ok(response) => response.into_inner().route,
+            Err(e) => {
+                info!("No route found to probe the payment: {e}");
+                return Ok(None);
+            }
+        };
+        let fee_msat = route_fee_msat(&route, amount_msat);
+
+        let request = probe_request(
+            route,
+            payee_node_id,
+            amount_msat,
+            final_cltv_delta,
+            last_hop,
+        );
+        let payment_hash = request.payment_hash.clone();
+        if let Err(e) = client.send_pay(request).await {
+            info!("Failed to send the payment probe: {e}");
+            return Ok(Some(RouteProbe {
+                fee_msat,
+                reached_payee: false,
+            }));
+        }
+        let res = self
+            .with_keep_alive(client.wait_send_pay(WaitsendpayRequest {
+                payment_hash,
+                partid: None,
+                timeout: Some(self.sdk_config.payment_timeout_sec),
+                groupid: None,
+            }))
+            .await;
+        Ok(Some(RouteProbe {
+            fee_msat,
+            reached_payee: res.err().is_some_and(probe_reached_payee),
+        }))
+    }
+
     fn derive_bip32_key(&self, path: Vec<ChildNumber>) -> NodeResult<ExtendedPrivKey> {
         Self::derive_bip32_key(self.sdk_config.network, &self.signer, path)
     }
@@ -1684,15 +1530,6 @@ impl NodeAPI for Greenlight {
         let mut node_client = self.get_node_client().await?;
 
         let open_peer_channels = self.get_open_peer_channels_pb().await?;
-        let (open_peer_channels_private, open_peer_channels_public): (
-            HashMap<Vec<u8>, ListpeerchannelsChannels>,
-            HashMap<Vec<u8>, ListpeerchannelsChannels>,
-        ) = open_peer_channels
-            .into_iter()
-            .partition(|(_, c)| c.private.unwrap_or_default());
-        let has_public_channel = !open_peer_channels_public.is_empty();
-
-        let mut hints: Vec<RouteHint> = vec![];
 
         // Get channels where our node is the destination
         let pubkey = self
@@ -1700,73 +1537,16 @@ impl NodeAPI for Greenlight {
             .get_node_state()?
             .map(|n| n.id)
             .ok_or(NodeError::generic("Node info not found"))?;
-        let channels: HashMap<Vec<u8>, cln::ListchannelsChannels> = node_client
+        let channels = node_client
             .list_channels(cln::ListchannelsRequest {
                 destination: Some(hex::decode(pubkey)?),
                 ..Default::default()
             })
             .await?
             .into_inner()
-            .channels
-            .into_iter()
-            .map(|c| (c.source.clone(), c))
-            .collect();
+            .channels;
 
-        // Create a routing hint from each private channel.
-        for (peer_id, peer_channel) in open_peer_channels_private {
-            let peer_id_str = hex::encode(&peer_id);
-            let optional_channel_id = peer_channel
-                .alias
-                .and_then(|a| a.remote)
-                .or(peer_channel.short_channel_id);
-
-            if let Some(channel_id) = optional_channel_id {
-                // The remote fee policy
-                let maybe_policy = match channels.get(&peer_id) {
-                    Some(channel) => Some((
-                        channel.base_fee_millisatoshi,
-                        channel.fee_per_millionth,
-                        channel.delay,
-                    )),
-                    None if peer_id_str == lsp_info.pubkey => Some((
-                        lsp_info.base_fee_msat as u32,
-                        (lsp_info.fee_rate * 1000000.0) as u32,
-                        lsp_info.time_lock_delta,
-                    )),
-                    _ => None,
-                };
-                match maybe_policy {
-                    Some((fees_base_msat, fees_proportional_millionths, cltv_delta)) => {
-                        debug!(
-                            "For peer {}: remote base {} proportional {} cltv_delta {}",
-                            peer_id_str, fees_base_msat, fees_proportional_millionths, cltv_delta,
-                        );
-                        let scid = parse_short_channel_id(&channel_id)?;
-                        let hint = RouteHint {
-                            hops: vec![RouteHintHop {
-                                src_node_id: peer_id_str,
-                                short_channel_id: scid,
-                                fees_base_msat,
-                                fees_proportional_millionths,
-                                cltv_expiry_delta: cltv_delta as u64,
-                                htlc_minimum_msat: Some(
-                                    peer_channel
-                                        .minimum_htlc_in_msat
-                                        .clone()
-                                        .unwrap_or_default()
-                                        .msat,
-                                ),
-                                htlc_maximum_msat: None,
-                            }],
-                        };
-                        info!("Generating hint hop as routing hint: {:?}", hint);
-                        hints.push(hint);
-                    }
-                    _ => debug!("No source channel found for peer: {:?}", peer_id_str),
-                };
-            }
-        }
-        Ok((hints, has_public_channel))
+        routing_hints(open_peer_channels, channels, lsp_info)
     }
 
     async fn get_open_peers(&self) -> NodeResult<HashSet<Vec<u8>>> {
@@ -1777,7 +1557,7 @@ impl NodeAPI for Greenlight {
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, EnumString, Display, Deserialize, Serialize)]
-enum NodeCommand {
+pub(crate) enum NodeCommand {
     /// Closes all channels of all peers.
     #[strum(serialize = "closeallchannels")]
     CloseAllChannels,
@@ -1815,6 +1595,633 @@ enum NodeCommand {
     Stop,
 }
 
+/// Builds the node state and the channels from the responses of the node
+pub(crate) fn node_state_and_channels(
+    node_info: cln::GetinfoResponse,
+    funds: cln::ListfundsResponse,
+    closed_channels: Vec<ListclosedchannelsClosedchannels>,
+    (all_channels, opened_channels, connected_peers, channels_balance): (
+        Vec<cln::ListpeerchannelsChannels>,
+        Vec<cln::ListpeerchannelsChannels>,
+        Vec<String>,
+        u64,
+    ),
+) -> NodeResult<(NodeState, Vec<Channel>)> {
+    let forgotten_closed_channels: NodeResult<Vec<Channel>> = closed_channels
+        .into_iter()
+        .filter(|cc| {
+            all_channels
+                .iter()
+                .all(|ac| ac.funding_txid != Some(cc.funding_txid.clone()))
+        })
+        .map(TryInto::try_into)
+        .collect();
+    info!("forgotten_closed_channels {:?}", forgotten_closed_channels);
+
+    let mut all_channel_models: Vec<Channel> =
+        all_channels.clone().into_iter().map(|c| c.into()).collect();
+    all_channel_models.extend(forgotten_closed_channels?);
+
+    // calculate onchain balance
+    let onchain_balance = on_chain_balance(&funds);
+    let pending_onchain_balance = pending_onchain_balance(&all_channels);
+    let utxos: Vec<UnspentTransactionOutput> = utxos(funds);
+
+    // calculate payment limits and inbound liquidity
+    let mut max_payable: u64 = 0;
+    let mut max_receivable_single_channel: u64 = 0;
+    let mut total_inbound_liquidity_msats: u64 = 0;
+    opened_channels.iter().try_for_each(|c| -> Result<()> {
+        max_payable += c
+            .spendable_msat
+            .as_ref()
+            .map(|a| a.msat)
+            .unwrap_or_default();
+        let receivable_amount = c
+            .receivable_msat
+            .as_ref()
+            .map(|a| a.msat)
+            .unwrap_or_default();
+        total_inbound_liquidity_msats += receivable_amount;
+        if receivable_amount > max_receivable_single_channel {
+            max_receivable_single_channel = receivable_amount;
+        }
+        Ok(())
+    })?;
+
+    let max_allowed_to_receive_msats = MAX_INBOUND_LIQUIDITY_MSAT.saturating_sub(channels_balance);
+    let node_pubkey = hex::encode(node_info.id);
+    // construct the node state
+    let node_state = NodeState {
+        id: node_pubkey.clone(),
+        block_height: node_info.blockheight,
+        channels_balance_msat: channels_balance,
+        onchain_balance_msat: onchain_balance,
+        pending_onchain_balance_msat: pending_onchain_balance,
+        utxos,
+        max_payable_msat: max_payable,
+        max_receivable_msat: max_allowed_to_receive_msats,
+        max_single_payment_amount_msat: MAX_PAYMENT_AMOUNT_MSAT,
+        max_chan_reserve_msats: channels_balance - min(max_payable, channels_balance),
+        connected_peers,
+        max_receivable_single_payment_amount_msat: max_receivable_single_channel,
+        total_inbound_liquidity_msats,
+    };
+    Ok((node_state, all_channel_models))
+}
+
+/// Splits the peer channels into all and opened channels, along with the connected peers and the
+/// channels balance
+pub(crate) fn channels_and_balance(
+    channels: Vec<cln::ListpeerchannelsChannels>,
+) -> (
+    Vec<cln::ListpeerchannelsChannels>,
+    Vec<cln::ListpeerchannelsChannels>,
+    Vec<String>,
+    u64,
+) {
+    // filter only connected peers
+    let connected_peers: Vec<String> = channels
+        .iter()
+        .filter(|channel| channel.peer_connected())
+        .filter_map(|channel| channel.peer_id.clone())
+        .map(hex::encode)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    // filter only opened channels
+    let opened_channels: Vec<cln::ListpeerchannelsChannels> = channels
+        .iter()
+        .filter(|c| c.state() == ChanneldNormal)
+        .cloned()
+        .collect();
+
+    // calculate channels balance only from opened channels
+    let channels_balance = opened_channels
+        .iter()
+        .map(|c| Channel::from(c.clone()))
+        .map(|c| c.spendable_msat)
+        .sum::<u64>();
+    (channels, opened_channels, connected_peers, channels_balance)
+}
+
+pub(crate) fn on_chain_balance(funds: &cln::ListfundsResponse) -> u64 {
+    funds.outputs.iter().fold(0, |a, b| {
+        if b.reserved {
+            return a;
+        }
+        a + b.amount_msat.clone().unwrap_or_default().msat
+    })
+}
+
+pub(crate) fn pending_onchain_balance(peer_channels: &[cln::ListpeerchannelsChannels]) -> u64 {
+    let pending_onchain_balance = peer_channels.iter().fold(0, |a, b| match b.state() {
+        ChanneldShuttingDown | ClosingdSigexchange | ClosingdComplete | AwaitingUnilateral
+        | FundingSpendSeen => a + b.to_us_msat.clone().unwrap_or_default().msat,
+
+        // When we  unilaterally close the channel it will get status as `AwaitingUnilateral`
+        // first, but when the closing transaction is confirmed onchain the funds receive status
+        // as `Onchain`. Though if we closed the channel we'll have to wait for the timelock to
+        // pass before the funds can be spent.
+        Onchain => {
+            if b.closer() == cln::ChannelSide::Local
+                && b.status
+                    .last()
+                    .is_some_and(|status| status.contains("DELAYED_OUTPUT_TO_US"))
+            {
+                a + b.to_us_msat.clone().unwrap_or_default().msat
+            } else {
+                a
+            }
+        }
+        _ => a,
+    });
+    info!("pending_onchain_balance is {}", pending_onchain_balance);
+    pending_onchain_balance
+}
+
+// Collect utxos from onchain funds
+pub(crate) fn utxos(funds: cln::ListfundsResponse) -> Vec<UnspentTransactionOutput> {
+    funds
+        .outputs
+        .iter()
+        .map(|output| UnspentTransactionOutput {
+            txid: output.txid.clone(),
+            outnum: output.output,
+            amount_millisatoshi: output
+                .amount_msat
+                .as_ref()
+                .map(|a| a.msat)
+                .unwrap_or_default(),
+            address: output.address.clone().unwrap_or_default(),
+            reserved: output.reserved,
+        })
+        .collect()
+}
+
+pub(crate) fn prepare_redeem_onchain_funds(
+    utxos: Vec<UnspentTransactionOutput>,
+    req: PrepareRedeemOnchainFundsRequest,
+) -> NodeResult<PrepareRedeemOnchainFundsResponse> {
+    let mut amount_msat: u64 = 0;
+    let txins: Vec<TxIn> = utxos
+        .iter()
+        .map(|utxo| {
+            amount_msat += utxo.amount_millisatoshi;
+            TxIn {
+                previous_output: OutPoint {
+                    txid: Txid::from_slice(&utxo.txid).unwrap(),
+                    vout: 0,
+                },
+                script_sig: Script::new(),
+                sequence: Sequence(0),
+                witness: Witness::default(),
+            }
+        })
+        .collect();
+
+    let amount_sat = amount_msat / 1_000;
+    let btc_address = Address::from_str(&req.to_address)?;
+    let tx_out: Vec<TxOut> = vec![TxOut {
+        value: amount_sat,
+        script_pubkey: btc_address.payload.script_pubkey(),
+    }];
+    let tx = Transaction {
+        version: 2,
+        lock_time: crate::bitcoin::PackedLockTime(0),
+        input: txins.clone(),
+        output: tx_out,
+    };
+
+    let witness_input_size: u64 = 110;
+    let tx_weight = tx.strippedsize() as u64 * WITNESS_SCALE_FACTOR as u64
+        + witness_input_size * txins.len() as u64;
+    let fee: u64 = tx_weight * req.sat_per_vbyte as u64 / WITNESS_SCALE_FACTOR as u64;
+    if fee >= amount_sat {
+        return Err(NodeError::InsufficientFunds(
+            "Insufficient funds to pay fees".to_string(),
+        ));
+    }
+
+    Ok(PrepareRedeemOnchainFundsResponse {
+        tx_weight,
+        tx_fee_sat: fee,
+    })
+}
+
+/// The request funding a transaction that replaces the one spending the given outputs
+pub(crate) fn replacement_utxo_psbt_request(
+    utxos: &[OutPoint],
+    script_pubkey: &Script,
+    sat_per_vbyte: u32,
+) -> cln::UtxopsbtRequest {
+    let output_weight = (8 + 1 + script_pubkey.len() as u32) * WITNESS_SCALE_FACTOR as u32;
+    cln::UtxopsbtRequest {
+        satoshi: Some(cln::AmountOrAll {
+            value: Some(cln::amount_or_all::Value::All(true)),
+        }),
+        feerate: Some(cln::Feerate {
+            style: Some(cln::feerate::Style::Perkw(sat_per_vbyte * 250)),
+        }),
+        startweight: output_weight,
+        utxos: utxos.iter().map(cln_outpoint).collect(),
+        // The outputs are still reserved by the transaction being replaced
+        reservedok: Some(true),
+        ..Default::default()
+    }
+}
+
+/// Spends the given outputs, even if unconfirmed, so the transaction pays for their parents (CPFP)
+pub(crate) fn cpfp_withdraw_request(
+    utxos: &[OutPoint],
+    to_address: String,
+    sat_per_vbyte: u32,
+) -> cln::WithdrawRequest {
+    cln::WithdrawRequest {
+        feerate: Some(cln::Feerate {
+            style: Some(cln::feerate::Style::Perkw(sat_per_vbyte * 250)),
+        }),
+        satoshi: Some(cln::AmountOrAll {
+            value: Some(cln::amount_or_all::Value::All(true)),
+        }),
+        destination: to_address,
+        minconf: Some(0),
+        utxos: utxos.iter().map(cln_outpoint).collect(),
+    }
+}
+
+fn cln_outpoint(utxo: &OutPoint) -> cln::Outpoint {
+    cln::Outpoint {
+        // The txid bytes are in the order the txid is displayed
+        txid: utxo.txid.as_ref().iter().rev().cloned().collect(),
+        outnum: utxo.vout,
+    }
+}
+
+/// Adds the output receiving the whole excess of the funded PSBT
+pub(crate) fn with_redeem_output(
+    utxo_psbt: cln::UtxopsbtResponse,
+    script_pubkey: Script,
+) -> NodeResult<String> {
+    let psbt_bytes = base64::decode(utxo_psbt.psbt)
+        .map_err(|e| NodeError::Generic(format!("Invalid PSBT encoding: {e}")))?;
+    let mut psbt = <PartiallySignedTransaction as PsbtDeserialize>::deserialize(&psbt_bytes)
+        .map_err(|e| NodeError::Generic(format!("Invalid PSBT: {e}")))?;
+    let excess_sat = utxo_psbt.excess_msat.map(|a| a.msat).unwrap_or_default() / 1_000;
+    if excess_sat == 0 {
+        return Err(NodeError::InsufficientFunds(
+            "Insufficient funds to pay fees".to_string(),
+        ));
+    }
+    psbt.unsigned_tx.output.push(TxOut {
+        value: excess_sat,
+        script_pubkey,
+    });
+    psbt.outputs.push(Default::default());
+    Ok(base64::encode(PsbtSerialize::serialize(&psbt)))
+}
+
+/// The transaction of a PSBT signed by the node, spending outputs of its onchain wallet
+pub(crate) fn signed_onchain_tx(signed_psbt: String, weight: u64) -> NodeResult<SignedOnchainTx> {
+    let psbt_bytes = base64::decode(&signed_psbt)
+        .map_err(|e| NodeError::Generic(format!("Invalid PSBT encoding: {e}")))?;
+    let psbt = <PartiallySignedTransaction as PsbtDeserialize>::deserialize(&psbt_bytes)
+        .map_err(|e| NodeError::Generic(format!("Invalid PSBT: {e}")))?;
+    let mut input_value = 0;
+    for input in &psbt.inputs {
+        input_value += input
+            .witness_utxo
+            .as_ref()
+            .map(|utxo| utxo.value)
+            .ok_or(NodeError::generic("PSBT input without its spent output"))?;
+    }
+    let output_value: u64 = psbt.unsigned_tx.output.iter().map(|o| o.value).sum();
+    Ok(SignedOnchainTx {
+        fee_sat: input_value.saturating_sub(output_value),
+        weight,
+        psbt: signed_psbt,
+    })
+}
+
+pub(crate) fn cln_invoice_request(
+    request: CreateInvoiceRequest,
+) -> NodeResult<cln::InvoiceRequest> {
+    let label = serde_json::to_string(&InvoiceLabel {
+        unix_milli: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis(),
+        payer_amount_msat: request.payer_amount_msat,
+    })?;
+    Ok(cln::InvoiceRequest {
+        amount_msat: Some(cln::AmountOrAny {
+            value: Some(cln::amount_or_any::Value::Amount(cln::Amount {
+                msat: request.amount_msat,
+            })),
+        }),
+        label,
+        description: request.description,
+        preimage: request.preimage,
+        deschashonly: request.use_description_hash,
+        expiry: request.expiry.map(|e| e as u64),
+        fallbacks: vec![],
+        cltv: request.cltv,
+    })
+}
+
+pub(crate) fn fetch_bolt11_result(invoice: cln::ListinvoicesInvoices) -> Option<FetchBolt11Result> {
+    invoice.bolt11.map(|bolt11| FetchBolt11Result {
+        bolt11,
+        payer_amount_msat: serde_json::from_str::<InvoiceLabel>(&invoice.label)
+            .map(|label| label.payer_amount_msat)
+            .ok()
+            .flatten(),
+    })
+}
+
+pub(crate) fn cln_pay_request(
+    config: &Config,
+    bolt11: String,
+    amount_msat: Option<u64>,
+    label: Option<String>,
+    max_fee_msat: Option<u64>,
+) -> NodeResult<cln::PayRequest> {
+    let mut description = None;
+    if !bolt11.is_empty() {
+        let invoice = parse_invoice(&bolt11)?;
+        validate_network(invoice.clone(), config.network)?;
+        description = invoice.description;
+    }
+
+    // CLN doesn't accept an absolute max fee together with the relative limits
+    let (maxfee, maxfeepercent, exemptfee) = match max_fee_msat {
+        Some(msat) => (Some(cln::Amount { msat }), None, None),
+        None => (
+            None,
+            Some(config.maxfee_percent),
+            Some(cln::Amount {
+                msat: config.exemptfee_msat,
+            }),
+        ),
+    };
+
+    Ok(cln::PayRequest {
+        bolt11,
+        amount_msat: amount_msat.map(|amt| cln::Amount { msat: amt }),
+        maxfeepercent,
+        retry_for: Some(config.payment_timeout_sec),
+        label,
+        maxdelay: None,
+        riskfactor: None,
+        localinvreqid: None,
+        exclude: vec![],
+        maxfee,
+        description,
+        exemptfee,
+    })
+}
+
+/// The fee limit of `pay` when no max fee is set: `maxfee_percent` of the amount, or
+/// `exemptfee_msat` if higher
+pub(crate) fn default_fee_limit_msat(config: &Config, amount_msat: u64) -> u64 {
+    let relative_limit_msat = (amount_msat as f64 * config.maxfee_percent / 100.0) as u64;
+    max(relative_limit_msat, config.exemptfee_msat)
+}
+
+/// A `getroute` request for the cheapest route to pay the amount to the payee. With a last hop
+/// hint, the route leads to the hint source node, with the amount and delay it needs to forward.
+pub(crate) fn route_request(
+    payee_node_id: Vec<u8>,
+    amount_msat: u64,
+    final_cltv_delta: u64,
+    max_hops: u32,
+    last_hop: Option<&RouteHintHop>,
+) -> NodeResult<cln::GetrouteRequest> {
+    let (id, amount_msat, cltv, max_hops) = match last_hop {
+        Some(hop) => {
+            let hint_edge = PaymentPathEdge {
+                node_id: payee_node_id,
+                short_channel_id: format_short_channel_id(hop.short_channel_id),
+                channel_delay: hop.cltv_expiry_delta,
+                base_fee_msat: hop.fees_base_msat as u64,
+                fee_per_millionth: hop.fees_proportional_millionths as u64,
+            };
+            (
+                hex::decode(&hop.src_node_id)?,
+                hint_edge.amount_from_forward(amount_msat),
+                final_cltv_delta + hop.cltv_expiry_delta,
+                max_hops - 1,
+            )
+        }
+        None => (payee_node_id, amount_msat, final_cltv_delta, max_hops),
+    };
+    Ok(cln::GetrouteRequest {
+        id,
+        amount_msat: Some(Amount { msat: amount_msat }),
+        riskfactor: 0,
+        cltv: Some(cltv as u32),
+        fromid: None,
+        fuzzpercent: Some(0),
+        exclude: vec![],
+        maxhops: Some(max_hops),
+    })
+}
+
+/// The total fee of a route for the amount received by the payee. The amount of the first hop
+/// is what we send, including the fees of all the hops.
+pub(crate) fn route_fee_msat(route: &[GetrouteRoute], amount_msat: u64) -> u64 {
+    route
+        .first()
+        .and_then(|hop| hop.amount_msat.as_ref())
+        .map(|sent| sent.msat.saturating_sub(amount_msat))
+        .unwrap_or_default()
+}
+
+/// A `sendpay` request probing the `getroute` route, extended with the last hop hint if any. Its
+/// payment hash is random, so the payee can only fail it.
+pub(crate) fn probe_request(
+    route: Vec<GetrouteRoute>,
+    payee_node_id: Vec<u8>,
+    amount_msat: u64,
+    final_cltv_delta: u64,
+    last_hop: Option<&RouteHintHop>,
+) -> SendpayRequest {
+    let mut route: Vec<SendpayRoute> = route
+        .into_iter()
+        .map(|hop| SendpayRoute {
+            amount_msat: hop.amount_msat,
+            id: hop.id,
+            delay: hop.delay,
+            channel: hop.channel,
+        })
+        .collect();
+    if let Some(hop) = last_hop {
+        route.push(SendpayRoute {
+            amount_msat: Some(Amount { msat: amount_msat }),
+            id: payee_node_id,
+            delay: final_cltv_delta as u32,
+            channel: format_short_channel_id(hop.short_channel_id),
+        });
+    }
+    SendpayRequest {
+        route,
+        payment_hash: rand::random::<[u8; 32]>().to_vec(),
+        label: None,
+        amount_msat: Some(Amount { msat: amount_msat }),
+        bolt11: None,
+        payment_secret: None,
+        partid: None,
+        localinvreqid: None,
+        groupid: None,
+    }
+}
+
+/// Whether the probe failed at the payee, which rejects its unknown payment hash. A probe failing
+/// before reaching the payee means the route can't carry the amount.
+pub(crate) fn probe_reached_payee(status: tonic::Status) -> bool {
+    matches!(
+        parse_cln_error(status),
+        Ok(JsonRpcErrCode::PayDestinationPermFail)
+    )
+}
+
+pub(crate) fn cln_keysend_request(
+    config: &Config,
+    node_id: String,
+    amount_msat: u64,
+    extra_tlvs: Option<Vec<TlvEntry>>,
+    label: Option<String>,
+) -> NodeResult<cln::KeysendRequest> {
+    Ok(cln::KeysendRequest {
+        destination: hex::decode(node_id)?,
+        amount_msat: Some(cln::Amount { msat: amount_msat }),
+        label: label.or(Some(format!(
+            "breez-{}",
+            SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis()
+        ))),
+        extratlvs: extra_tlvs.map(|tlvs| cln::TlvStream {
+            entries: tlvs
+                .into_iter()
+                .map(|tlv| cln::TlvEntry {
+                    r#type: tlv.field_number,
+                    value: tlv.value,
+                })
+                .collect(),
+        }),
+        routehints: None,
+        maxfeepercent: Some(config.maxfee_percent),
+        exemptfee: None,
+        retry_for: Some(config.payment_timeout_sec),
+        maxdelay: None,
+    })
+}
+
+/// Whether the channel is in a state where it can still be closed
+pub(crate) fn is_closeable(channel: &cln::ListpeerchannelsChannels) -> bool {
+    matches!(
+        channel.state.and_then(cln::ChannelState::from_i32),
+        Some(
+            cln::ChannelState::Openingd
+                | cln::ChannelState::ChanneldAwaitingLockin
+                | cln::ChannelState::ChanneldNormal
+                | cln::ChannelState::ChanneldShuttingDown
+                | cln::ChannelState::FundingSpendSeen
+                | cln::ChannelState::DualopendOpenInit
+                | cln::ChannelState::DualopendAwaitingLockin
+        )
+    )
+}
+
+/// The open peer channels (private and public), indexed by peer pubkey
+pub(crate) fn open_peer_channels(
+    channels: Vec<cln::ListpeerchannelsChannels>,
+) -> HashMap<Vec<u8>, cln::ListpeerchannelsChannels> {
+    channels
+        .into_iter()
+        .filter(|c| {
+            c.state == Some(cln::ChannelState::ChanneldNormal as i32) && c.peer_id.is_some()
+        })
+        .map(|c| (c.peer_id.clone().unwrap(), c))
+        .collect()
+}
+
+/// Builds the routing hints of the private open peer channels, using the remote fee policy found
+/// in `channels` (the channels where our node is the destination), and whether the node has any
+/// public channel
+pub(crate) fn routing_hints(
+    open_peer_channels: HashMap<Vec<u8>, cln::ListpeerchannelsChannels>,
+    channels: Vec<cln::ListchannelsChannels>,
+    lsp_info: &LspInformation,
+) -> NodeResult<(Vec<RouteHint>, bool)> {
+    let (open_peer_channels_private, open_peer_channels_public): (
+        HashMap<Vec<u8>, ListpeerchannelsChannels>,
+        HashMap<Vec<u8>, ListpeerchannelsChannels>,
+    ) = open_peer_channels
+        .into_iter()
+        .partition(|(_, c)| c.private.unwrap_or_default());
+    let has_public_channel = !open_peer_channels_public.is_empty();
+
+    let mut hints: Vec<RouteHint> = vec![];
+    let channels: HashMap<Vec<u8>, cln::ListchannelsChannels> = channels
+        .into_iter()
+        .map(|c| (c.source.clone(), c))
+        .collect();
+
+    // Create a routing hint from each private channel.
+    for (peer_id, peer_channel) in open_peer_channels_private {
+        let peer_id_str = hex::encode(&peer_id);
+        let optional_channel_id = peer_channel
+            .alias
+            .and_then(|a| a.remote)
+            .or(peer_channel.short_channel_id);
+
+        if let Some(channel_id) = optional_channel_id {
+            // The remote fee policy
+            let maybe_policy = match channels.get(&peer_id) {
+                Some(channel) => Some((
+                    channel.base_fee_millisatoshi,
+                    channel.fee_per_millionth,
+                    channel.delay,
+                )),
+                None if peer_id_str == lsp_info.pubkey => Some((
+                    lsp_info.base_fee_msat as u32,
+                    (lsp_info.fee_rate * 1000000.0) as u32,
+                    lsp_info.time_lock_delta,
+                )),
+                _ => None,
+            };
+            match maybe_policy {
+                Some((fees_base_msat, fees_proportional_millionths, cltv_delta)) => {
+                    debug!(
+                        "For peer {}: remote base {} proportional {} cltv_delta {}",
+                        peer_id_str, fees_base_msat, fees_proportional_millionths, cltv_delta,
+                    );
+                    let scid = parse_short_channel_id(&channel_id)?;
+                    let hint = RouteHint {
+                        hops: vec![RouteHintHop {
+                            src_node_id: peer_id_str,
+                            short_channel_id: scid,
+                            fees_base_msat,
+                            fees_proportional_millionths,
+                            cltv_expiry_delta: cltv_delta as u64,
+                            htlc_minimum_msat: Some(
+                                peer_channel
+                                    .minimum_htlc_in_msat
+                                    .clone()
+                                    .unwrap_or_default()
+                                    .msat,
+                            ),
+                            htlc_maximum_msat: None,
+                        }],
+                    };
+                    info!("Generating hint hop as routing hint: {:?}", hint);
+                    hints.push(hint);
+                }
+                _ => debug!("No source channel found for peer: {:?}", peer_id_str),
+            };
+        }
+    }
+    Ok((hints, has_public_channel))
+}
+
 // pulls transactions from greenlight based on last sync timestamp.
 // greenlight gives us the payments via API and for received payments we are looking for settled invoices.
 async fn pull_transactions(
@@ -1829,9 +2236,27 @@ async fn pull_transactions(
         .list_invoices(cln::ListinvoicesRequest::default())
         .await?
         .into_inner();
+
+    // fetch payments from greenlight
+    let payments = c
+        .list_pays(cln::ListpaysRequest::default())
+        .await?
+        .into_inner();
+    trace!("list payments (unfiltered): {:?}", payments);
+
+    transactions_since(since_timestamp, invoices.invoices, payments.pays, htlc_list)
+}
+
+/// Constructs the received transactions from the invoices paid after the given timestamp, and the
+/// payment transactions (pending and complete) from the pays created or completed after it
+pub(crate) fn transactions_since(
+    since_timestamp: u64,
+    invoices: Vec<cln::ListinvoicesInvoices>,
+    pays: Vec<ListpaysPays>,
+    htlc_list: Vec<Htlc>,
+) -> NodeResult<Vec<Payment>> {
     // construct the received transactions by filtering the invoices to those paid and beyond the filter timestamp
     let received_transactions: NodeResult<Vec<Payment>> = invoices
-        .invoices
         .into_iter()
         .filter(|i| {
             i.paid_at.unwrap_or_default() > since_timestamp
@@ -1840,15 +2265,8 @@ async fn pull_transactions(
         .map(TryInto::try_into)
         .collect();
 
-    // fetch payments from greenlight
-    let payments = c
-        .list_pays(cln::ListpaysRequest::default())
-        .await?
-        .into_inner();
-    trace!("list payments (unfiltered): {:?}", payments);
     // construct the payment transactions (pending and complete)
-    let outbound_transactions: NodeResult<Vec<Payment>> = payments
-        .pays
+    let outbound_transactions: NodeResult<Vec<Payment>> = pays
         .into_iter()
         .filter(|p| {
             p.created_at > since_timestamp
@@ -1906,35 +2324,36 @@ impl TryFrom<OffChainPayment> for Payment {
     fn try_from(p: OffChainPayment) -> std::result::Result<Self, Self::Error> {
         let ln_invoice = parse_invoice(&p.bolt11)?;
         Ok(Payment {
-            id: hex::encode(p.payment_hash.clone()),
-            payment_type: PaymentType::Received,
-            payment_time: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
-            amount_msat: amount_to_msat(&p.amount.unwrap_or_default()),
-            fee_msat: 0,
-            status: PaymentStatus::Complete,
-            error: None,
             description: ln_invoice.description,
-            details: PaymentDetails::Ln {
-                data: LnPaymentDetails {
-                    payment_hash: hex::encode(p.payment_hash),
-                    label: p.label,
-                    destination_pubkey: ln_invoice.payee_pubkey,
-                    payment_preimage: hex::encode(p.preimage),
-                    keysend: false,
-                    bolt11: p.bolt11,
-                    lnurl_success_action: None, // For received payments, this is None
-                    lnurl_pay_domain: None,     // For received payments, this is None
-                    lnurl_pay_comment: None,    // For received payments, this is None
-                    lnurl_metadata: None,       // For received payments, this is None
-                    ln_address: None,
-                    lnurl_withdraw_endpoint: None,
-                    swap_info: None,
-                    reverse_swap_info: None,
-                    pending_expiration_block: None,
-                    open_channel_bolt11: None,
+            ..Payment::new(
+                hex::encode(p.payment_hash.clone()),
+                PaymentType::Received,
+                SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+                amount_to_msat(&p.amount.unwrap_or_default()),
+                0,
+                PaymentStatus::Complete,
+                PaymentDetails::Ln {
+                    data: LnPaymentDetails {
+                        payment_hash: hex::encode(p.payment_hash),
+                        label: p.label,
+                        destination_pubkey: ln_invoice.payee_pubkey,
+                        payment_preimage: hex::encode(p.preimage),
+                        keysend: false,
+                        bolt11: p.bolt11,
+                        lnurl_success_action: None, // For received payments, this is None
+                        lnurl_pay_domain: None,     // For received payments, this is None
+                        lnurl_pay_comment: None,    // For received payments, this is None
+                        lnurl_metadata: None,       // For received payments, this is None
+                        ln_address: None,
+                        lnurl_withdraw_endpoint: None,
+                        lnurl_verify_url: None,
+                        swap_info: None,
+                        reverse_swap_info: None,
+                        pending_expiration_block: None,
+                        open_channel_bolt11: None,
+                    },
                 },
-            },
-            metadata: None,
+            )
         })
     }
     // fn from(p: OffChainPayment) -> Self {
@@ -1951,35 +2370,36 @@ impl TryFrom<gl_client::signer::model::greenlight::Invoice> for Payment {
     ) -> std::result::Result<Self, Self::Error> {
         let ln_invoice = parse_invoice(&invoice.bolt11)?;
         Ok(Payment {
-            id: hex::encode(invoice.payment_hash.clone()),
-            payment_type: PaymentType::Received,
-            payment_time: invoice.payment_time as i64,
-            amount_msat: amount_to_msat(&invoice.received.or(invoice.amount).unwrap_or_default()),
-            fee_msat: 0,
-            status: PaymentStatus::Complete,
-            error: None,
             description: ln_invoice.description,
-            details: PaymentDetails::Ln {
-                data: LnPaymentDetails {
-                    payment_hash: hex::encode(invoice.payment_hash),
-                    label: invoice.label,
-                    destination_pubkey: ln_invoice.payee_pubkey,
-                    payment_preimage: hex::encode(invoice.payment_preimage),
-                    keysend: false,
-                    bolt11: invoice.bolt11,
-                    lnurl_success_action: None, // For received payments, this is None
-                    lnurl_pay_domain: None,     // For received payments, this is None
-                    lnurl_pay_comment: None,    // For received payments, this is None
-                    lnurl_metadata: None,       // For received payments, this is None
-                    ln_address: None,
-                    lnurl_withdraw_endpoint: None,
-                    swap_info: None,
-                    reverse_swap_info: None,
-                    pending_expiration_block: None,
-                    open_channel_bolt11: None,
+            ..Payment::new(
+                hex::encode(invoice.payment_hash.clone()),
+                PaymentType::Received,
+                invoice.payment_time as i64,
+                amount_to_msat(&invoice.received.or(invoice.amount).unwrap_or_default()),
+                0,
+                PaymentStatus::Complete,
+                PaymentDetails::Ln {
+                    data: LnPaymentDetails {
+                        payment_hash: hex::encode(invoice.payment_hash),
+                        label: invoice.label,
+                        destination_pubkey: ln_invoice.payee_pubkey,
+                        payment_preimage: hex::encode(invoice.payment_preimage),
+                        keysend: false,
+                        bolt11: invoice.bolt11,
+                        lnurl_success_action: None, // For received payments, this is None
+                        lnurl_pay_domain: None,     // For received payments, this is None
+                        lnurl_pay_comment: None,    // For received payments, this is None
+                        lnurl_metadata: None,       // For received payments, this is None
+                        ln_address: None,
+                        lnurl_withdraw_endpoint: None,
+                        lnurl_verify_url: None,
+                        swap_info: None,
+                        reverse_swap_info: None,
+                        pending_expiration_block: None,
+                        open_channel_bolt11: None,
+                    },
                 },
-            },
-            metadata: None,
+            )
         })
     }
 }
@@ -2011,35 +2431,36 @@ impl TryFrom<gl_client::signer::model::greenlight::Payment> for Payment {
         let status = payment.status().into();
 
         Ok(Payment {
-            id: hex::encode(payment.payment_hash.clone()),
-            payment_type: PaymentType::Sent,
-            payment_time: payment.created_at as i64,
-            amount_msat: payment_amount,
-            fee_msat: payment_amount_sent - payment_amount,
-            status,
-            error: None,
             description,
-            details: PaymentDetails::Ln {
-                data: LnPaymentDetails {
-                    payment_hash: hex::encode(payment.payment_hash),
-                    label: "".to_string(),
-                    destination_pubkey: hex::encode(payment.destination),
-                    payment_preimage: hex::encode(payment.payment_preimage),
-                    keysend: payment.bolt11.is_empty(),
-                    bolt11: payment.bolt11,
-                    lnurl_success_action: None,
-                    lnurl_pay_domain: None,
-                    lnurl_pay_comment: None,
-                    lnurl_metadata: None,
-                    ln_address: None,
-                    lnurl_withdraw_endpoint: None,
-                    swap_info: None,
-                    reverse_swap_info: None,
-                    pending_expiration_block: None,
-                    open_channel_bolt11: None,
+            ..Payment::new(
+                hex::encode(payment.payment_hash.clone()),
+                PaymentType::Sent,
+                payment.created_at as i64,
+                payment_amount,
+                payment_amount_sent - payment_amount,
+                status,
+                PaymentDetails::Ln {
+                    data: LnPaymentDetails {
+                        payment_hash: hex::encode(payment.payment_hash),
+                        label: "".to_string(),
+                        destination_pubkey: hex::encode(payment.destination),
+                        payment_preimage: hex::encode(payment.payment_preimage),
+                        keysend: payment.bolt11.is_empty(),
+                        bolt11: payment.bolt11,
+                        lnurl_success_action: None,
+                        lnurl_pay_domain: None,
+                        lnurl_pay_comment: None,
+                        lnurl_metadata: None,
+                        ln_address: None,
+                        lnurl_withdraw_endpoint: None,
+                        lnurl_verify_url: None,
+                        swap_info: None,
+                        reverse_swap_info: None,
+                        pending_expiration_block: None,
+                        open_channel_bolt11: None,
+                    },
                 },
-            },
-            metadata: None,
+            )
         })
     }
 }
@@ -2055,42 +2476,43 @@ impl TryFrom<cln::ListinvoicesInvoices> for Payment {
             .ok_or(InvoiceError::generic("No bolt11 invoice"))
             .and_then(|b| parse_invoice(b))?;
         Ok(Payment {
-            id: hex::encode(invoice.payment_hash.clone()),
-            payment_type: PaymentType::Received,
-            payment_time: invoice.paid_at.map(|i| i as i64).unwrap_or_default(),
-            amount_msat: invoice
-                .amount_received_msat
-                .or(invoice.amount_msat)
-                .map(|a| a.msat)
-                .unwrap_or_default(),
-            fee_msat: 0,
-            status: PaymentStatus::Complete,
-            error: None,
             description: ln_invoice.description,
-            details: PaymentDetails::Ln {
-                data: LnPaymentDetails {
-                    payment_hash: hex::encode(invoice.payment_hash),
-                    label: invoice.label,
-                    destination_pubkey: ln_invoice.payee_pubkey,
-                    payment_preimage: invoice
-                        .payment_preimage
-                        .map(hex::encode)
-                        .unwrap_or_default(),
-                    keysend: false,
-                    bolt11: invoice.bolt11.unwrap_or_default(),
-                    lnurl_success_action: None, // For received payments, this is None
-                    lnurl_pay_domain: None,     // For received payments, this is None
-                    lnurl_pay_comment: None,    // For received payments, this is None
-                    lnurl_metadata: None,       // For received payments, this is None
-                    ln_address: None,
-                    lnurl_withdraw_endpoint: None,
-                    swap_info: None,
-                    reverse_swap_info: None,
-                    pending_expiration_block: None,
-                    open_channel_bolt11: None,
+            ..Payment::new(
+                hex::encode(invoice.payment_hash.clone()),
+                PaymentType::Received,
+                invoice.paid_at.map(|i| i as i64).unwrap_or_default(),
+                invoice
+                    .amount_received_msat
+                    .or(invoice.amount_msat)
+                    .map(|a| a.msat)
+                    .unwrap_or_default(),
+                0,
+                PaymentStatus::Complete,
+                PaymentDetails::Ln {
+                    data: LnPaymentDetails {
+                        payment_hash: hex::encode(invoice.payment_hash),
+                        label: invoice.label,
+                        destination_pubkey: ln_invoice.payee_pubkey,
+                        payment_preimage: invoice
+                            .payment_preimage
+                            .map(hex::encode)
+                            .unwrap_or_default(),
+                        keysend: false,
+                        bolt11: invoice.bolt11.unwrap_or_default(),
+                        lnurl_success_action: None, // For received payments, this is None
+                        lnurl_pay_domain: None,     // For received payments, this is None
+                        lnurl_pay_comment: None,    // For received payments, this is None
+                        lnurl_metadata: None,       // For received payments, this is None
+                        ln_address: None,
+                        lnurl_withdraw_endpoint: None,
+                        lnurl_verify_url: None,
+                        swap_info: None,
+                        reverse_swap_info: None,
+                        pending_expiration_block: None,
+                        open_channel_bolt11: None,
+                    },
                 },
-            },
-            metadata: None,
+            )
         })
     }
 }
@@ -2109,6 +2531,8 @@ impl TryFrom<cln::ListpaysPays> for Payment {
     type Error = NodeError;
 
     fn try_from(payment: cln::ListpaysPays) -> NodeResult<Self, Self::Error> {
+        // Payments to BOLT12 offers carry the paid invoice in place of the bolt11 one
+        let invoice = payment.bolt11.clone().or(payment.bolt12.clone());
         let ln_invoice = payment
             .bolt11
             .as_ref()
@@ -2126,41 +2550,46 @@ impl TryFrom<cln::ListpaysPays> for Payment {
             .unwrap_or_default();
         let status = payment.status().into();
 
+        let amount_msat = match status {
+            PaymentStatus::Complete => payment_amount,
+            _ => ln_invoice
+                .as_ref()
+                .map_or(0, |i| i.amount_msat.unwrap_or_default()),
+        };
         Ok(Payment {
-            id: hex::encode(payment.payment_hash.clone()),
-            payment_type: PaymentType::Sent,
-            payment_time: payment.completed_at.unwrap_or(payment.created_at) as i64,
-            amount_msat: match status {
-                PaymentStatus::Complete => payment_amount,
-                _ => ln_invoice
-                    .as_ref()
-                    .map_or(0, |i| i.amount_msat.unwrap_or_default()),
-            },
-            fee_msat: payment_amount_sent - payment_amount,
-            status,
-            error: None,
             description: ln_invoice.map(|i| i.description).unwrap_or_default(),
-            details: PaymentDetails::Ln {
-                data: LnPaymentDetails {
-                    payment_hash: hex::encode(payment.payment_hash),
-                    label: payment.label.unwrap_or_default(),
-                    destination_pubkey: payment.destination.map(hex::encode).unwrap_or_default(),
-                    payment_preimage: payment.preimage.map(hex::encode).unwrap_or_default(),
-                    keysend: payment.bolt11.is_none(),
-                    bolt11: payment.bolt11.unwrap_or_default(),
-                    lnurl_success_action: None,
-                    lnurl_pay_domain: None,
-                    lnurl_pay_comment: None,
-                    lnurl_metadata: None,
-                    ln_address: None,
-                    lnurl_withdraw_endpoint: None,
-                    swap_info: None,
-                    reverse_swap_info: None,
-                    pending_expiration_block: None,
-                    open_channel_bolt11: None,
+            ..Payment::new(
+                hex::encode(payment.payment_hash.clone()),
+                PaymentType::Sent,
+                payment.completed_at.unwrap_or(payment.created_at) as i64,
+                amount_msat,
+                payment_amount_sent - payment_amount,
+                status,
+                PaymentDetails::Ln {
+                    data: LnPaymentDetails {
+                        payment_hash: hex::encode(payment.payment_hash),
+                        label: payment.label.unwrap_or_default(),
+                        destination_pubkey: payment
+                            .destination
+                            .map(hex::encode)
+                            .unwrap_or_default(),
+                        payment_preimage: payment.preimage.map(hex::encode).unwrap_or_default(),
+                        keysend: invoice.is_none(),
+                        bolt11: invoice.unwrap_or_default(),
+                        lnurl_success_action: None,
+                        lnurl_pay_domain: None,
+                        lnurl_pay_comment: None,
+                        lnurl_metadata: None,
+                        ln_address: None,
+                        lnurl_withdraw_endpoint: None,
+                        lnurl_verify_url: None,
+                        swap_info: None,
+                        reverse_swap_info: None,
+                        pending_expiration_block: None,
+                        open_channel_bolt11: None,
+                    },
                 },
-            },
-            metadata: None,
+            )
         })
     }
 }
@@ -2330,7 +2759,12 @@ mod tests {
     use gl_client::pb::cln::Amount;
     use gl_client::pb::{self, cln};
 
-    use crate::greenlight::node_api::convert_to_send_pay_route;
+    use crate::greenlight::node_api::{
+        cln_pay_request, convert_to_send_pay_route, default_fee_limit_msat, is_closeable,
+        open_peer_channels, probe_request, route_request,
+    };
+    use crate::test_utils::create_test_config;
+    use crate::RouteHintHop;
     use crate::{models, PaymentPath, PaymentPathEdge};
 
     #[test]
@@ -2469,6 +2903,99 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_closeable_and_open_peer_channels() {
+        for s in &[
+            Openingd,
+            ChanneldAwaitingLockin,
+            ChanneldNormal,
+            FundingSpendSeen,
+        ] {
+            assert!(is_closeable(&cln_channel(s)));
+        }
+        for s in &[ClosingdComplete, AwaitingUnilateral, Onchain] {
+            assert!(!is_closeable(&cln_channel(s)));
+        }
+
+        let mut opened = cln_channel(&ChanneldNormal);
+        opened.peer_id = Some(vec![1]);
+        let mut pending = cln_channel(&Openingd);
+        pending.peer_id = Some(vec![2]);
+        // Channels without a peer id are skipped
+        let no_peer = cln_channel(&ChanneldNormal);
+        let open_channels = open_peer_channels(vec![opened, pending, no_peer]);
+        assert_eq!(open_channels.len(), 1);
+        assert!(open_channels.contains_key(&vec![1]));
+    }
+
+    #[test]
+    fn test_pay_request_fee_limit() -> Result<()> {
+        let config = create_test_config();
+
+        // Without a max fee the relative limits of the config are used
+        let request = cln_pay_request(&config, "".into(), Some(10_000_000), None, None)?;
+        assert_eq!(request.maxfeepercent, Some(config.maxfee_percent));
+        assert_eq!(
+            request.exemptfee.map(|a| a.msat),
+            Some(config.exemptfee_msat)
+        );
+        assert!(request.maxfee.is_none());
+
+        // A max fee replaces them
+        let request = cln_pay_request(&config, "".into(), Some(10_000_000), None, Some(5_000))?;
+        assert_eq!(request.maxfee.map(|a| a.msat), Some(5_000));
+        assert!(request.maxfeepercent.is_none());
+        assert!(request.exemptfee.is_none());
+
+        // The exempt fee applies to small amounts
+        assert_eq!(
+            default_fee_limit_msat(&config, 10_000),
+            config.exemptfee_msat
+        );
+        assert_eq!(default_fee_limit_msat(&config, 100_000_000), 1_000_000);
+        Ok(())
+    }
+
+    #[test]
+    fn test_probe_through_last_hop() -> Result<()> {
+        let last_hop = RouteHintHop {
+            src_node_id: "02".repeat(33),
+            short_channel_id: 1,
+            fees_base_msat: 1_000,
+            fees_proportional_millionths: 0,
+            cltv_expiry_delta: 40,
+            ..Default::default()
+        };
+
+        // The route leads to the hint source node, which forwards the amount to the payee
+        let request = route_request(vec![3], 10_000, 18, 3, Some(&last_hop))?;
+        assert_eq!(request.id, hex::decode(&last_hop.src_node_id)?);
+        assert_eq!(request.amount_msat.map(|a| a.msat), Some(11_000));
+        assert_eq!(request.cltv, Some(58));
+        assert_eq!(request.maxhops, Some(2));
+
+        let route = vec![cln::GetrouteRoute {
+            id: request.id.clone(),
+            channel: "2x2x2".into(),
+            direction: 0,
+            amount_msat: request.amount_msat.clone(),
+            delay: 58,
+            style: 0,
+        }];
+        let probe = probe_request(route, vec![3], 10_000, 18, Some(&last_hop));
+        assert_eq!(probe.route.len(), 2);
+        assert_eq!(probe.route[1].id, vec![3]);
+        assert_eq!(probe.route[1].channel, "0x0x1");
+        assert_eq!(
+            probe.route[1].amount_msat.as_ref().map(|a| a.msat),
+            Some(10_000)
+        );
+        assert_eq!(probe.route[1].delay, 18);
+        assert_eq!(probe.payment_hash.len(), 32);
+        assert!(probe.payment_secret.is_none());
+        Ok(())
+    }
+
     fn cln_channel(state: &ListpeerchannelsChannelsState) -> cln::ListpeerchannelsChannels {
         cln::ListpeerchannelsChannels {
             state: Some((*state).into()),
@@ -167,8 +167,10 @@ mod breez_services;
 mod chain;
 mod crypt;
 pub mod error;
+mod export;
 #[rustfmt::skip]
 mod node_api; // flutter_rust_bridge_codegen: has to be defined before greenlight; greenlight::node_api
+mod cln;
 mod greenlight;
 #[rustfmt::skip]
 pub mod lnurl;
@@ -177,6 +179,7 @@ mod lsp;
 mod lsps0;
 mod lsps2;
 mod models;
+pub mod nwc;
 mod persist;
 mod support;
 mod swap_in;
@@ -186,11 +189,12 @@ mod swap_out;
 #[allow(dead_code)]
 mod test_utils;
 mod tonic_wrap;
+pub mod wallets;
 
 pub use breez_services::{
     mnemonic_to_seed, BackupFailedData, BreezEvent, BreezServices, CheckMessageRequest,
-    CheckMessageResponse, EventListener, InvoicePaidDetails, LogStream, PaymentFailedData,
-    SignMessageRequest, SignMessageResponse,
+    CheckMessageResponse, EventListener, InvoicePaidDetails, LogStream, PaymentApprover,
+    PaymentFailedData, SignMessageRequest, SignMessageResponse, SweepDetails, SweepFailedData,
 };
 pub use chain::RecommendedFees;
 pub use lsp::LspInformation;
@@ -1,4 +1,5 @@
 pub mod pay;
+pub mod server;
 
 #[cfg(test)]
 mod tests {
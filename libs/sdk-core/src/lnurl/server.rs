@@ -0,0 +1,195 @@
+use sdk_common::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Parameters of a LNURL-pay endpoint (LUD-06) or Lightning Address (LUD-16) served by this node.
+///
+/// The app is responsible for exposing the endpoint over HTTP (or any other transport that ends up
+/// as an HTTP response for the payer), and for forwarding the incoming requests to
+/// [crate::BreezServices::handle_lnurl_pay_server_request].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LnUrlPayServerConfig {
+    /// The URL the payer's wallet calls to get an invoice. Requests to this URL should be forwarded
+    /// to the handler along with their query string.
+    pub callback: String,
+    /// The minimum amount, in millisats, that this endpoint accepts
+    pub min_sendable: u64,
+    /// The maximum amount, in millisats, that this endpoint accepts
+    pub max_sendable: u64,
+    /// The description shown to the payer
+    pub description: String,
+    /// The maximum length of the comment the payer can attach to the payment. Zero disables comments.
+    pub comment_allowed: u16,
+    /// If set, the endpoint is served as this Lightning Address (e.g. `satoshi@example.com`)
+    pub ln_address: Option<String>,
+}
+
+impl LnUrlPayServerConfig {
+    /// The metadata of the endpoint, as a JSON string. Its hash is the description hash of the
+    /// invoices created for this endpoint.
+    pub fn metadata_str(&self) -> String {
+        let mut metadata = vec![json!(["text/plain", self.description])];
+        if let Some(ln_address) = &self.ln_address {
+            metadata.push(json!(["text/identifier", ln_address]));
+        }
+        Value::Array(metadata).to_string()
+    }
+
+    pub(crate) fn pay_request(&self) -> Value {
+        json!({
+            "tag": "payRequest",
+            "callback": self.callback,
+            "minSendable": self.min_sendable,
+            "maxSendable": self.max_sendable,
+            "metadata": self.metadata_str(),
+            "commentAllowed": self.comment_allowed,
+        })
+    }
+}
+
+/// A request received by the LNURL-pay endpoint, as parsed from the URL it was made to
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum LnUrlPayServerRequest {
+    /// First step of LNURL-pay: the payer's wallet fetches the endpoint parameters
+    PayRequest,
+    /// Second step of LNURL-pay: the payer's wallet asks for an invoice
+    Callback {
+        amount_msat: u64,
+        comment: Option<String>,
+    },
+}
+
+impl LnUrlPayServerRequest {
+    /// Callbacks are recognized by the `amount` query parameter, so the app can serve the endpoint
+    /// and its callback from the same URL.
+    pub(crate) fn parse(url: &str) -> Result<Self, String> {
+        let url = reqwest::Url::parse(url).map_err(|e| format!("Invalid URL: {e}"))?;
+        let mut amount = None;
+        let mut comment = None;
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "amount" => amount = Some(value.to_string()),
+                "comment" if !value.is_empty() => comment = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        match amount {
+            None => Ok(Self::PayRequest),
+            Some(amount) => Ok(Self::Callback {
+                amount_msat: amount
+                    .parse()
+                    .map_err(|_| format!("Invalid amount: {amount}"))?,
+                comment,
+            }),
+        }
+    }
+}
+
+/// The response to return to the payer's wallet, regardless of the transport
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LnUrlPayServerResponse {
+    /// The HTTP status code
+    pub status_code: u16,
+    /// The JSON body
+    pub body: String,
+}
+
+impl LnUrlPayServerResponse {
+    pub(crate) fn ok(body: Value) -> Self {
+        Self {
+            status_code: 200,
+            body: body.to_string(),
+        }
+    }
+
+    /// Errors are reported with the LUD-06 error payload
+    pub(crate) fn error(status_code: u16, reason: &str) -> Self {
+        Self {
+            status_code,
+            body: json!({ "status": "ERROR", "reason": reason }).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::Value;
+
+    use crate::breez_services::tests::breez_services;
+    use crate::lnurl::server::*;
+
+    fn get_test_config() -> LnUrlPayServerConfig {
+        LnUrlPayServerConfig {
+            callback: "https://example.com/lnurlp/satoshi/callback".into(),
+            min_sendable: 1_000,
+            max_sendable: 2_000,
+            description: "Pay satoshi".into(),
+            comment_allowed: 10,
+            ln_address: Some("satoshi@example.com".into()),
+        }
+    }
+
+    #[test]
+    fn test_parse_lnurl_pay_server_request() -> Result<()> {
+        assert_eq!(
+            LnUrlPayServerRequest::parse("https://example.com/.well-known/lnurlp/satoshi")?,
+            LnUrlPayServerRequest::PayRequest
+        );
+        assert_eq!(
+            LnUrlPayServerRequest::parse(
+                "https://example.com/lnurlp/satoshi/callback?amount=1000&comment=hi"
+            )?,
+            LnUrlPayServerRequest::Callback {
+                amount_msat: 1_000,
+                comment: Some("hi".into())
+            }
+        );
+        assert!(LnUrlPayServerRequest::parse("https://example.com/callback?amount=abc").is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_lnurl_pay_server() -> Result<()> {
+        let breez_services = breez_services().await?;
+        let config = get_test_config();
+
+        // The pay request can be parsed by the LNURL-pay client
+        let res = breez_services
+            .handle_lnurl_pay_server_request(
+                config.clone(),
+                "https://example.com/.well-known/lnurlp/satoshi".into(),
+            )
+            .await;
+        assert_eq!(res.status_code, 200);
+        let pay_request: LnUrlPayRequestData = serde_json::from_str(&res.body)?;
+        assert_eq!(pay_request.callback, config.callback);
+        assert_eq!(pay_request.min_sendable, 1_000);
+        assert_eq!(pay_request.max_sendable, 2_000);
+        assert_eq!(pay_request.metadata_str, config.metadata_str());
+
+        let res = breez_services
+            .handle_lnurl_pay_server_request(
+                config.clone(),
+                format!("{}?amount=2000&comment=thanks", config.callback),
+            )
+            .await;
+        assert_eq!(res.status_code, 200);
+        let body: Value = serde_json::from_str(&res.body)?;
+        let invoice = parse_invoice(body["pr"].as_str().unwrap())?;
+        assert_eq!(invoice.amount_msat, Some(2_000));
+
+        let res = breez_services
+            .handle_lnurl_pay_server_request(
+                config.clone(),
+                format!("{}?amount=3000", config.callback),
+            )
+            .await;
+        assert_eq!(res.status_code, 400);
+        let body: LnUrlErrorData = serde_json::from_str(&res.body)?;
+        assert_eq!(body.reason, "Amount is bigger than the maximum allowed");
+
+        Ok(())
+    }
+}
@@ -1,6 +1,13 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use crate::crypt::encrypt;
 use crate::error::{SdkError, SdkResult};
-use crate::models::{LspAPI, OpeningFeeParams, OpeningFeeParamsMenu};
+use crate::models::{CustomLsp, LspAPI, OpeningFeeParams, OpeningFeeParamsMenu};
+use crate::node_api::NodeAPI;
+use crate::persist::db::SqliteStorage;
+use crate::{lsps0, lsps2};
 
 use anyhow::{anyhow, Result};
 use prost::Message;
@@ -210,13 +217,223 @@ impl LspAPI for BreezServer {
     }
 }
 
+/// [LspAPI] that merges the [CustomLsp]s registered in the [crate::Config] with the LSPs provided
+/// by another [LspAPI], typically the Breez server.
+///
+/// The opening fee params of the custom LSPs are fetched from the LSPs themselves using LSPS2.
+/// Since this means connecting to the LSP and waiting for its replies, only the selected LSP is
+/// probed and the fetched params are cached for [CUSTOM_LSP_FEE_PARAMS_TTL].
+pub(crate) struct LspRegistry {
+    inner: Arc<dyn LspAPI>,
+    custom_lsps: Vec<CustomLsp>,
+    node_api: Arc<dyn NodeAPI>,
+    /// Unset if the node can't exchange LSPS0 messages
+    transport: Option<Arc<lsps0::Transport>>,
+    persister: Arc<SqliteStorage>,
+    fee_params_cache: Mutex<HashMap<String, (Instant, OpeningFeeParamsMenu)>>,
+}
+
+/// How long the opening fee params fetched from a custom LSP are reused
+const CUSTOM_LSP_FEE_PARAMS_TTL: Duration = Duration::from_secs(600);
+
+impl LspRegistry {
+    pub(crate) fn new(
+        inner: Arc<dyn LspAPI>,
+        custom_lsps: Vec<CustomLsp>,
+        node_api: Arc<dyn NodeAPI>,
+        transport: Option<Arc<lsps0::Transport>>,
+        persister: Arc<SqliteStorage>,
+    ) -> Self {
+        Self {
+            inner,
+            custom_lsps,
+            node_api,
+            transport,
+            persister,
+            fee_params_cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn ensure_not_custom(&self, lsp_id: &str) -> SdkResult<()> {
+        match self.custom_lsps.iter().any(|lsp| lsp.id == lsp_id) {
+            true => Err(SdkError::Generic {
+                err: format!("(LSP {lsp_id}) Operation not supported by custom LSPs"),
+            }),
+            false => Ok(()),
+        }
+    }
+
+    /// Builds the [LspInformation] of a custom LSP.
+    ///
+    /// The fee params are only fetched if `probe` is set and there are no cached ones. If they
+    /// are not available, the LSP is still returned, but with an empty menu.
+    async fn custom_lsp_info(&self, lsp: &CustomLsp, probe: bool) -> LspInformation {
+        let opening_fee_params_list = match self.cached_opening_fee_params(&lsp.id) {
+            Some(menu) => menu,
+            None if probe => match self.fetch_opening_fee_params(lsp).await {
+                Ok(menu) => {
+                    self.fee_params_cache
+                        .lock()
+                        .unwrap()
+                        .insert(lsp.id.clone(), (Instant::now(), menu.clone()));
+                    menu
+                }
+                Err(e) => {
+                    warn!("(LSP {}) Failed to fetch opening fee params: {e}", lsp.id);
+                    OpeningFeeParamsMenu { values: vec![] }
+                }
+            },
+            None => OpeningFeeParamsMenu { values: vec![] },
+        };
+
+        LspInformation {
+            id: lsp.id.clone(),
+            name: lsp.name.clone(),
+            widget_url: "".to_string(),
+            pubkey: lsp.pubkey.clone(),
+            host: lsp.host.clone(),
+            base_fee_msat: 0,
+            fee_rate: 0.0,
+            time_lock_delta: 0,
+            min_htlc_msat: 0,
+            lsp_pubkey: vec![],
+            opening_fee_params_list,
+        }
+    }
+
+    fn cached_opening_fee_params(&self, lsp_id: &str) -> Option<OpeningFeeParamsMenu> {
+        let mut cache = self.fee_params_cache.lock().unwrap();
+        match cache.get(lsp_id) {
+            Some((fetched_at, menu)) if fetched_at.elapsed() < CUSTOM_LSP_FEE_PARAMS_TTL => {
+                Some(menu.clone())
+            }
+            Some(_) => {
+                cache.remove(lsp_id);
+                None
+            }
+            None => None,
+        }
+    }
+
+    async fn fetch_opening_fee_params(&self, lsp: &CustomLsp) -> Result<OpeningFeeParamsMenu> {
+        let transport = self
+            .transport
+            .clone()
+            .ok_or_else(|| anyhow!("LSPS0 is not supported by the node"))?;
+        // LSPS0 messages can only be exchanged with connected peers
+        self.node_api
+            .connect_peer(lsp.pubkey.clone(), lsp.host.clone())
+            .await?;
+
+        let client = lsps0::Client::new(
+            transport,
+            hex::decode(&lsp.pubkey)?,
+            lsps0::LSPS0_REQUEST_TIMEOUT,
+        );
+        let protocols = match lsp.protocols.is_empty() {
+            true => client.list_protocols().await?.protocols,
+            false => lsp.protocols.clone(),
+        };
+        if !protocols.contains(&2) {
+            return Err(anyhow!("LSPS2 is not supported, protocols: {protocols:?}"));
+        }
+
+        let info = lsps2::Client::new(client)
+            .get_info(lsps2::client::GetInfoRequest {
+                version: lsps2::LSPS2_VERSION,
+                token: lsp.token.clone(),
+            })
+            .await?;
+        OpeningFeeParamsMenu::try_from(
+            info.opening_fee_params_menu
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        )
+    }
+}
+
+#[tonic::async_trait]
+impl LspAPI for LspRegistry {
+    async fn list_lsps(&self, node_pubkey: String) -> SdkResult<Vec<LspInformation>> {
+        let mut lsps = self.inner.list_lsps(node_pubkey).await?;
+        let selected_lsp_id = self.persister.get_lsp_id()?;
+        for custom_lsp in &self.custom_lsps {
+            if lsps.iter().any(|lsp| lsp.id == custom_lsp.id) {
+                warn!("(LSP {}) Custom LSP ID is already in use", custom_lsp.id);
+                continue;
+            }
+            let selected = selected_lsp_id.as_ref() == Some(&custom_lsp.id);
+            lsps.push(self.custom_lsp_info(custom_lsp, selected).await);
+        }
+        Ok(lsps)
+    }
+
+    async fn list_used_lsps(&self, node_pubkey: String) -> SdkResult<Vec<LspInformation>> {
+        self.inner.list_used_lsps(node_pubkey).await
+    }
+
+    async fn register_payment_notifications(
+        &self,
+        lsp_id: String,
+        lsp_pubkey: Vec<u8>,
+        webhook_url: String,
+        webhook_url_signature: String,
+    ) -> SdkResult<RegisterPaymentNotificationResponse> {
+        self.ensure_not_custom(&lsp_id)?;
+        self.inner
+            .register_payment_notifications(lsp_id, lsp_pubkey, webhook_url, webhook_url_signature)
+            .await
+    }
+
+    async fn unregister_payment_notifications(
+        &self,
+        lsp_id: String,
+        lsp_pubkey: Vec<u8>,
+        webhook_url: String,
+        webhook_url_signature: String,
+    ) -> SdkResult<RemovePaymentNotificationResponse> {
+        self.ensure_not_custom(&lsp_id)?;
+        self.inner
+            .unregister_payment_notifications(
+                lsp_id,
+                lsp_pubkey,
+                webhook_url,
+                webhook_url_signature,
+            )
+            .await
+    }
+
+    async fn register_payment(
+        &self,
+        lsp_id: String,
+        lsp_pubkey: Vec<u8>,
+        payment_info: PaymentInformation,
+    ) -> SdkResult<RegisterPaymentReply> {
+        self.ensure_not_custom(&lsp_id)?;
+        self.inner
+            .register_payment(lsp_id, lsp_pubkey, payment_info)
+            .await
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{LspInformation, OpeningFeeParams};
+    use std::sync::Arc;
 
-    use super::OpeningFeeParamsMenu;
+    use crate::breez_services::tests::get_dummy_node_state;
+    use crate::lsps0;
+    use crate::models::LspAPI;
+    use crate::persist::db::SqliteStorage;
+    use crate::persist::test_utils;
+    use crate::test_utils::{get_test_ofp_48h, MockBreezServer, MockNodeAPI};
+    use crate::{CustomLsp, LspInformation, OpeningFeeParams};
+
+    use super::{LspRegistry, OpeningFeeParamsMenu};
     use anyhow::Result;
     use chrono::{Duration, Utc};
+    use sdk_common::grpc;
+    use tokio::sync::watch;
 
     #[test]
     fn test_cheapest_open_channel_fee() -> Result<()> {
@@ -267,4 +484,64 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_lsp_registry_merges_custom_lsps() -> Result<()> {
+        let mut node_api = MockNodeAPI::new(get_dummy_node_state());
+        node_api
+            .set_lsps2_responses(get_test_ofp_48h(2000, 1000), "1x2x3")
+            .await;
+        let node_api = Arc::new(node_api);
+        let transport = Arc::new(lsps0::Transport::new(node_api.clone()));
+        let (stop, shutdown) = watch::channel(());
+        transport.start(shutdown);
+
+        let custom_lsp = CustomLsp {
+            id: "custom".to_string(),
+            name: "custom lsp".to_string(),
+            pubkey: "02d4e7e420d9dcf6f0206c27ecc69c400cc269b1f5f5ec856d8c9d1fc7e6d910d6"
+                .to_string(),
+            host: "localhost:9735".to_string(),
+            protocols: vec![],
+            token: None,
+        };
+        let persister = Arc::new(SqliteStorage::new(test_utils::create_test_sql_dir()));
+        persister.init()?;
+        let registry = LspRegistry::new(
+            Arc::new(MockBreezServer {}),
+            vec![custom_lsp.clone()],
+            node_api,
+            Some(transport),
+            persister.clone(),
+        );
+
+        // The custom LSP is not probed until it is selected
+        let lsps = registry.list_lsps("node".to_string()).await?;
+        assert_eq!(lsps.len(), 2);
+        let lsp = lsps.iter().find(|lsp| lsp.id == custom_lsp.id).unwrap();
+        assert_eq!(lsp.pubkey, custom_lsp.pubkey);
+        assert_eq!(lsp.host, custom_lsp.host);
+        assert!(lsp.opening_fee_params_list.values.is_empty());
+
+        persister.set_lsp_id(custom_lsp.id.clone())?;
+        registry.list_lsps("node".to_string()).await?;
+        let _ = stop.send(());
+
+        // The fetched fee params are cached, so they are returned after the transport stopped
+        let lsps = registry.list_lsps("node".to_string()).await?;
+        let lsp = lsps.iter().find(|lsp| lsp.id == custom_lsp.id).unwrap();
+        let ofp = lsp
+            .opening_fee_params_list
+            .get_cheapest_opening_fee_params()?;
+        assert_eq!(ofp.min_msat, 2000);
+        assert_eq!(ofp.proportional, 1000);
+
+        // The Breez specific calls are not supported by custom LSPs
+        let res = registry
+            .register_payment(custom_lsp.id, vec![], grpc::PaymentInformation::default())
+            .await;
+        assert!(res.is_err());
+
+        Ok(())
+    }
 }
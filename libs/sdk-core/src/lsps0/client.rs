@@ -9,7 +9,6 @@ pub struct ListProtocolsRequest {}
 
 #[derive(Debug, Deserialize)]
 pub struct ListProtocolsResponse {
-    #[allow(dead_code)]
     pub protocols: Vec<i32>,
 }
 
@@ -20,7 +19,6 @@ pub struct Client {
 }
 
 impl Client {
-    #[allow(dead_code)]
     pub fn new(transport: Arc<Transport>, peer_id: Vec<u8>, timeout: Duration) -> Self {
         Self {
             transport,
@@ -56,7 +54,6 @@ impl Client {
             .await
     }
 
-    #[allow(dead_code)]
     pub async fn list_protocols(&self) -> Result<ListProtocolsResponse, Error> {
         self.call(
             String::from("lsps0.list_protocols"),
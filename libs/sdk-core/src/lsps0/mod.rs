@@ -3,8 +3,12 @@ pub(crate) mod error;
 pub(crate) mod jsonrpc;
 pub(crate) mod transport;
 
+use std::time::Duration;
+
 pub(crate) use client::Client;
 
 pub(crate) use error::Error;
-#[allow(unused_imports)]
 pub(crate) use transport::Transport;
+
+/// Timeout for LSPS0 requests to the LSP
+pub(crate) const LSPS0_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use crate::node_api::NodeAPI;
+use crate::wallets;
 use crate::CustomMessage;
 use anyhow::{anyhow, Result};
 use rand::distributions::Alphanumeric;
@@ -17,7 +18,7 @@ use super::error::Error;
 use super::jsonrpc::RpcServerMessageBody;
 use super::jsonrpc::{RpcError, RpcRequest, RpcServerMessage};
 
-const LSPS0_MESSAGE_TYPE: u16 = 37913;
+pub(crate) const LSPS0_MESSAGE_TYPE: u16 = 37913;
 const JSONRPC_VERSION: &str = "2.0";
 
 #[tonic::async_trait]
@@ -64,7 +65,6 @@ pub struct Transport {
 }
 
 impl Transport {
-    #[allow(dead_code)]
     pub fn new(node: Arc<dyn NodeAPI>) -> Transport {
         Transport {
             node,
@@ -74,11 +74,10 @@ impl Transport {
         }
     }
 
-    #[allow(dead_code)]
     pub fn start(self: &Arc<Transport>, cancel: watch::Receiver<()>) {
         debug!("starting lsps0 transport.");
         let cloned = self.clone();
-        tokio::spawn(async move {
+        wallets::spawn(async move {
             loop {
                 let mut cancel = cancel.clone();
                 if cancel.has_changed().unwrap_or(true) {
@@ -1,7 +1,9 @@
+use sdk_common::grpc;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 
 use crate::lsps0;
+use crate::models;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct GetVersionsRequest {}
@@ -32,7 +34,7 @@ pub struct GetInfoResponse {
 }
 
 #[serde_as]
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct OpeningFeeParams {
     #[serde_as(as = "DisplayFromStr")]
     pub min_fee_msat: u64,
@@ -43,6 +45,32 @@ pub struct OpeningFeeParams {
     pub promise: String,
 }
 
+impl From<OpeningFeeParams> for grpc::OpeningFeeParams {
+    fn from(ofp: OpeningFeeParams) -> Self {
+        Self {
+            min_msat: ofp.min_fee_msat,
+            proportional: ofp.proportional,
+            valid_until: ofp.valid_until,
+            max_idle_time: ofp.min_lifetime,
+            max_client_to_self_delay: ofp.max_client_to_self_delay,
+            promise: ofp.promise,
+        }
+    }
+}
+
+impl From<models::OpeningFeeParams> for OpeningFeeParams {
+    fn from(ofp: models::OpeningFeeParams) -> Self {
+        Self {
+            min_fee_msat: ofp.min_msat,
+            proportional: ofp.proportional,
+            valid_until: ofp.valid_until,
+            min_lifetime: ofp.max_idle_time,
+            max_client_to_self_delay: ofp.max_client_to_self_delay,
+            promise: ofp.promise,
+        }
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BuyRequest {
@@ -123,7 +151,6 @@ pub struct Client {
 }
 
 impl Client {
-    #[allow(dead_code)]
     pub fn new(client: lsps0::Client) -> Self {
         Self { client }
     }
@@ -135,7 +162,6 @@ impl Client {
             .await
     }
 
-    #[allow(dead_code)]
     pub async fn get_info(&self, req: GetInfoRequest) -> Result<GetInfoResponse, GetInfoError> {
         match self.client.call(String::from("lsps2.get_info"), req).await {
             Ok(v) => Ok(v),
@@ -143,7 +169,6 @@ impl Client {
         }
     }
 
-    #[allow(dead_code)]
     pub async fn buy(&self, req: BuyRequest) -> Result<BuyResponse, BuyError> {
         match self.client.call(String::from("lsps2.buy"), req).await {
             Ok(v) => Ok(v),
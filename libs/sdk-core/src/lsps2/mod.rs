@@ -1,4 +1,6 @@
 pub(crate) mod client;
 
-#[allow(unused_imports)]
 pub(crate) use client::Client;
+
+/// The LSPS2 version used when talking to the LSP
+pub(crate) const LSPS2_VERSION: i32 = 1;
@@ -1,4 +1,4 @@
-use std::cmp::max;
+use std::cmp::{max, min, Reverse};
 use std::ops::Add;
 use std::str::FromStr;
 
@@ -29,6 +29,7 @@ use crate::swap_out::error::{ReverseSwapError, ReverseSwapResult};
 
 pub const SWAP_PAYMENT_FEE_EXPIRY_SECONDS: u32 = 60 * 60 * 24 * 2; // 2 days
 pub const INVOICE_PAYMENT_FEE_EXPIRY_SECONDS: u32 = 60 * 60; // 60 minutes
+pub const FIAT_RATE_MAX_AGE_SECONDS: i64 = 60 * 10; // 10 minutes
 
 /// Different types of supported payments
 #[derive(Clone, PartialEq, Eq, Debug, EnumString, Display, Deserialize, Serialize, Hash)]
@@ -92,6 +93,26 @@ pub struct Swap {
     pub swapper_max_payable: i64,
 }
 
+/// The swapper's key-path claim of a paid Taproot swap, to be co-signed with MuSig2
+pub struct CooperativeClaim {
+    /// The unsigned claim transaction
+    pub claim_tx: Vec<u8>,
+    /// The swapper's public nonce for signing each input of the claim transaction
+    pub swapper_pub_nonces: Vec<Vec<u8>>,
+}
+
+/// The payer's MuSig2 signature share for an input of the cooperative claim transaction
+pub struct CooperativeClaimSignature {
+    pub pub_nonce: Vec<u8>,
+    pub partial_signature: Vec<u8>,
+}
+
+/// The swapper's MuSig2 signature share for an input of a cooperative refund transaction
+pub struct CooperativeRefundSignature {
+    pub signature: Vec<u8>,
+    pub pub_nonce: Vec<u8>,
+}
+
 /// Trait covering functionality involving swaps
 #[tonic::async_trait]
 pub trait SwapperAPI: Send + Sync {
@@ -100,9 +121,29 @@ pub trait SwapperAPI: Send + Sync {
         hash: Vec<u8>,
         payer_pubkey: Vec<u8>,
         node_pubkey: String,
+        script_version: SwapScriptVersion,
     ) -> SwapResult<Swap>;
 
     async fn complete_swap(&self, bolt11: String) -> Result<()>;
+
+    /// Fetches the swapper's unsigned key-path claim transaction of a Taproot swap
+    async fn get_cooperative_claim(&self, bitcoin_address: String) -> Result<CooperativeClaim>;
+
+    /// Sends our signature shares for the swapper's key-path claim of a Taproot swap
+    async fn sign_cooperative_claim(
+        &self,
+        bitcoin_address: String,
+        signatures: Vec<CooperativeClaimSignature>,
+    ) -> Result<()>;
+
+    /// Asks the swapper to co-sign the key-path refund of an unpaid Taproot swap, before its lock
+    /// height has passed. `pub_nonces` holds our MuSig2 nonce for each input.
+    async fn request_cooperative_refund(
+        &self,
+        bitcoin_address: String,
+        refund_tx: Vec<u8>,
+        pub_nonces: Vec<Vec<u8>>,
+    ) -> Result<Vec<CooperativeRefundSignature>>;
 }
 
 /// Details about the reverse swap fees and parameters, at this point in time
@@ -461,7 +502,14 @@ pub struct Config {
     /// to the first URL fail, then the call will be repeated to the next URL, and so on.
     ///
     /// Note that, if specified, the URL has to be in the format: `https://mempool.space/api`
+    ///
+    /// Ignored if [Config::chain_backends] is not empty.
     pub mempoolspace_url: Option<String>,
+    /// If not empty, these are the chain backends that will be used, instead of mempool.space.
+    ///
+    /// Like the mempool.space URLs, they are used in order: if calls to a backend fail, then the
+    /// call will be repeated to the next one, and so on.
+    pub chain_backends: Vec<ChainBackend>,
     /// Directory in which all SDK files (DB, log) are stored. Defaults to ".", otherwise if it's customized,
     /// the folder should exist before starting the SDK.
     pub working_dir: String,
@@ -474,6 +522,10 @@ pub struct Config {
     /// Maps to the CLN `exemptfee` config when paying invoices (`lightning-pay`)
     pub exemptfee_msat: u64,
     pub node_config: NodeConfig,
+    /// Additional LSPs, not operated by Breez, that are merged into the list of available LSPs.
+    ///
+    /// Their fee parameters are fetched from the LSP itself using LSPS2.
+    pub custom_lsps: Vec<CustomLsp>,
 }
 
 impl Config {
@@ -482,6 +534,7 @@ impl Config {
             breezserver: PRODUCTION_BREEZSERVER_URL.to_string(),
             chainnotifier_url: "https://chainnotifier.breez.technology".to_string(),
             mempoolspace_url: None,
+            chain_backends: vec![],
             working_dir: ".".to_string(),
             network: Bitcoin,
             payment_timeout_sec: 60,
@@ -490,6 +543,7 @@ impl Config {
             maxfee_percent: 1.0,
             exemptfee_msat: 20000,
             node_config,
+            custom_lsps: vec![],
         }
     }
 
@@ -498,6 +552,7 @@ impl Config {
             breezserver: STAGING_BREEZSERVER_URL.to_string(),
             chainnotifier_url: "https://chainnotifier.breez.technology".to_string(),
             mempoolspace_url: None,
+            chain_backends: vec![],
             working_dir: ".".to_string(),
             network: Bitcoin,
             payment_timeout_sec: 60,
@@ -506,13 +561,66 @@ impl Config {
             maxfee_percent: 0.5,
             exemptfee_msat: 20000,
             node_config,
+            custom_lsps: vec![],
         }
     }
 }
 
+/// A service used to monitor the chain and broadcast transactions
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChainBackend {
+    /// A mempool.space instance, e.g. `https://mempool.space/api`
+    MempoolSpace { url: String },
+    /// An Esplora instance, e.g. `https://blockstream.info/api`
+    Esplora { url: String },
+    /// An Electrum server, e.g. `ssl://electrum.blockstream.info:50002` or
+    /// `tcp://127.0.0.1:50001`
+    Electrum { url: String },
+    /// A Bitcoin Core node, reached through its JSON-RPC interface, e.g. `http://127.0.0.1:18443`.
+    ///
+    /// Meant for regtest and self-hosted nodes: the queried addresses are imported in a
+    /// watch-only wallet of the node, and the node has to run with `-txindex`.
+    Bitcoind {
+        url: String,
+        rpc_user: String,
+        rpc_password: String,
+    },
+}
+
+/// An LSP that is registered directly in the [Config], rather than discovered through the
+/// Breez server.
+#[derive(Clone, Debug)]
+pub struct CustomLsp {
+    /// Unique ID of the LSP, used when selecting it with [crate::BreezServices::connect_lsp]
+    pub id: String,
+    pub name: String,
+    /// The identity pubkey of the Lightning node
+    pub pubkey: String,
+    /// The network location of the lightning node, e.g. `12.34.56.78:9012`
+    pub host: String,
+    /// The LSPS protocols supported by the LSP. If empty, they are queried from the LSP using
+    /// LSPS0.
+    pub protocols: Vec<i32>,
+    /// Optional token passed to the LSP when requesting LSPS2 fee parameters
+    pub token: Option<String>,
+}
+
 #[derive(Clone)]
 pub enum NodeConfig {
-    Greenlight { config: GreenlightNodeConfig },
+    Greenlight {
+        config: GreenlightNodeConfig,
+    },
+    /// A self-hosted Core Lightning node, reached through its `cln-grpc` plugin
+    Cln {
+        /// The URL of the gRPC interface, e.g. `https://127.0.0.1:9736`
+        grpc_url: String,
+        /// The PEM encoded CA certificate generated by the plugin (`ca.pem`)
+        ca_cert: Vec<u8>,
+        /// The PEM encoded client certificate generated by the plugin (`client.pem`)
+        client_cert: Vec<u8>,
+        /// The PEM encoded client key generated by the plugin (`client-key.pem`)
+        client_key: Vec<u8>,
+    },
 }
 
 #[derive(Clone, Serialize)]
@@ -645,7 +753,8 @@ pub struct SyncResponse {
 }
 
 /// The status of a payment
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, EnumString, Serialize, Deserialize)]
+#[strum(ascii_case_insensitive)]
 pub enum PaymentStatus {
     Pending = 0,
     Complete = 1,
@@ -666,6 +775,135 @@ pub struct Payment {
     pub description: Option<String>,
     pub details: PaymentDetails,
     pub metadata: Option<String>,
+    /// A free-form note set via [crate::BreezServices::set_payment_annotations]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub category: Option<String>,
+    /// The fiat amount the payment was requested or sent for, with the rate it was converted at
+    pub fiat_amount: Option<FiatAmount>,
+}
+
+impl Payment {
+    /// A payment without error, description, metadata, annotations or fiat amount, which can be
+    /// set with the struct update syntax
+    pub(crate) fn new(
+        id: String,
+        payment_type: PaymentType,
+        payment_time: i64,
+        amount_msat: u64,
+        fee_msat: u64,
+        status: PaymentStatus,
+        details: PaymentDetails,
+    ) -> Self {
+        Payment {
+            id,
+            payment_type,
+            payment_time,
+            amount_msat,
+            fee_msat,
+            status,
+            error: None,
+            description: None,
+            details,
+            metadata: None,
+            note: None,
+            tags: vec![],
+            category: None,
+            fiat_amount: None,
+        }
+    }
+}
+
+/// An exchange rate locked with [crate::BreezServices::lock_fiat_rate], so an amount is
+/// converted at the rate it was shown at. Only the rates locked by the SDK are accepted.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedFiatRate {
+    pub id: String,
+    /// The currency code, as in [Rate::coin]
+    pub currency: String,
+    /// The price of one bitcoin in the currency, as a decimal number
+    pub value: String,
+    /// Epoch time, in seconds
+    pub locked_at: i64,
+}
+
+/// An amount in a fiat currency, converted to bitcoin at a [LockedFiatRate]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FiatAmount {
+    /// The amount in the currency, as a decimal number like "25.50"
+    pub amount: String,
+    pub rate: LockedFiatRate,
+}
+
+impl FiatAmount {
+    /// Checks the amount can be converted, at a rate locked less than
+    /// [FIAT_RATE_MAX_AGE_SECONDS] ago
+    pub(crate) fn validate(&self, now: i64) -> Result<()> {
+        ensure!(
+            now - self.rate.locked_at <= FIAT_RATE_MAX_AGE_SECONDS,
+            "The {} rate locked at {} is stale, lock a new one",
+            self.rate.currency,
+            self.rate.locked_at
+        );
+        ensure!(
+            self.amount_msat()? > 0,
+            "The fiat amount is less than a millisatoshi"
+        );
+        Ok(())
+    }
+
+    /// The amount converted at the rate, rounded to the millisatoshi
+    pub(crate) fn amount_msat(&self) -> Result<u64> {
+        let (amount, amount_scale) = parse_decimal(&self.amount)
+            .map_err(|e| anyhow!("Invalid fiat amount {}: {e}", self.amount))?;
+        let (rate, rate_scale) = parse_decimal(&self.rate.value).map_err(|e| {
+            anyhow!(
+                "Invalid {} rate {}: {e}",
+                self.rate.currency,
+                self.rate.value
+            )
+        })?;
+        ensure!(
+            rate > 0,
+            "The {} rate has to be positive",
+            self.rate.currency
+        );
+
+        // amount / rate BTC, with 10^11 msat per BTC
+        let too_large = || anyhow!("The fiat amount {} is too large", self.amount);
+        let numerator = 10u128
+            .checked_pow(rate_scale + 11)
+            .and_then(|p| amount.checked_mul(p))
+            .ok_or_else(too_large)?;
+        let denominator = 10u128
+            .checked_pow(amount_scale)
+            .and_then(|p| rate.checked_mul(p))
+            .ok_or_else(too_large)?;
+        let amount_msat = numerator
+            .checked_add(denominator / 2)
+            .ok_or_else(too_large)?
+            / denominator;
+        u64::try_from(amount_msat).map_err(|_| too_large())
+    }
+}
+
+/// Parses a non-negative decimal number, returning its digits and how many of them are decimals
+fn parse_decimal(value: &str) -> Result<(u128, u32)> {
+    let (integer, decimals) = value.split_once('.').unwrap_or((value, ""));
+    ensure!(
+        !(integer.is_empty() && decimals.is_empty())
+            && integer
+                .chars()
+                .chain(decimals.chars())
+                .all(|c| c.is_ascii_digit()),
+        "not a decimal number"
+    );
+    ensure!(integer.len() + decimals.len() <= 30, "too many digits");
+    Ok((
+        format!("{integer}{decimals}").parse()?,
+        decimals.len() as u32,
+    ))
 }
 
 /// Represents a payments external information.
@@ -679,6 +917,9 @@ pub struct PaymentExternalInfo {
     pub lnurl_withdraw_endpoint: Option<String>,
     pub attempted_amount_msat: Option<u64>,
     pub attempted_error: Option<String>,
+    pub bolt12_offer: Option<String>,
+    pub bolt12_payer_note: Option<String>,
+    pub lnurl_verify_url: Option<String>,
 }
 
 /// Represents a list payments request.
@@ -691,10 +932,79 @@ pub struct ListPaymentsRequest {
     /// Epoch time, in seconds
     pub to_timestamp: Option<i64>,
     pub include_failures: Option<bool>,
+    /// Only payments with one of these statuses. Takes precedence over `include_failures`.
+    pub statuses: Option<Vec<PaymentStatus>>,
+    pub min_amount_msat: Option<u64>,
+    pub max_amount_msat: Option<u64>,
+    /// Only payments sent to this node
+    pub counterparty_node_id: Option<String>,
+    /// Only payments carrying all of these tags
+    pub tags: Option<Vec<String>>,
+    pub category: Option<String>,
+    /// Full-text search over the description, label, LNURL metadata, lightning address,
+    /// note, tags and category. Every word must match the start of a word of the payment.
+    pub search: Option<String>,
+    /// The order of the payments by time, [PaymentSortOrder::Descending] if not set
+    pub sort_order: Option<PaymentSortOrder>,
     pub offset: Option<u32>,
     pub limit: Option<u32>,
 }
 
+/// The order in which payments are listed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EnumString, Serialize, Deserialize)]
+#[strum(ascii_case_insensitive)]
+pub enum PaymentSortOrder {
+    /// Newest first
+    #[default]
+    Descending,
+    /// Oldest first
+    Ascending,
+}
+
+/// Represents a set payment annotations request.
+///
+/// The note, tags and category replace any previously set on the payment.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetPaymentAnnotationsRequest {
+    pub payment_hash: String,
+    pub note: Option<String>,
+    pub tags: Vec<String>,
+    pub category: Option<String>,
+}
+
+/// The file formats supported by [crate::BreezServices::export_payments]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumString, Serialize, Deserialize)]
+#[strum(ascii_case_insensitive)]
+pub enum ExportFormat {
+    /// All the payments matching the filters, with their fees, swap and channel close details
+    Csv,
+    /// The Koinly universal CSV format
+    Koinly,
+    /// The CoinTracking CSV import format
+    CoinTracking,
+}
+
+/// Represents an export payments request.
+pub struct ExportPaymentsRequest {
+    pub format: ExportFormat,
+    /// The payments to export
+    pub list_payments_request: ListPaymentsRequest,
+    /// If set, the fiat value of each payment is included, using the BTC rate of this currency
+    /// (e.g. `USD`) at the time of the payment
+    pub fiat_currency: Option<String>,
+    /// The historical BTC rates aren't served by Breez but by CoinGecko, which is sent the fiat
+    /// currency and the time range of the payments. This has to be set to agree to it when
+    /// `fiat_currency` is set.
+    pub use_coingecko_rates: bool,
+}
+
+/// Represents an export payments response.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ExportPaymentsResponse {
+    /// The exported payments, in the requested format
+    pub content: String,
+}
+
 /// Represents a payment response.
 #[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub struct PaymentResponse {
@@ -719,16 +1029,43 @@ pub enum PaymentDetails {
         #[serde(flatten)]
         data: ClosedChannelPaymentDetails,
     },
+    Bolt12 {
+        #[serde(flatten)]
+        data: Bolt12PaymentDetails,
+    },
 }
 
 impl PaymentDetails {
     pub fn add_pending_expiration_block(&mut self, htlc: Htlc) {
-        if let PaymentDetails::Ln { data } = self {
-            data.pending_expiration_block = Some(htlc.expiry)
+        match self {
+            PaymentDetails::Ln { data } => data.pending_expiration_block = Some(htlc.expiry),
+            PaymentDetails::Bolt12 { data } => data.pending_expiration_block = Some(htlc.expiry),
+            PaymentDetails::ClosedChannel { .. } => {}
         }
     }
 }
 
+/// Details of a payment made to a BOLT12 offer, as included in a [Payment]
+#[derive(PartialEq, Eq, Debug, Clone, Deserialize, Serialize)]
+pub struct Bolt12PaymentDetails {
+    pub payment_hash: String,
+    pub label: String,
+    pub destination_pubkey: String,
+    pub payment_preimage: String,
+
+    /// The offer this payment was made for
+    pub offer: String,
+
+    /// The BOLT12 invoice fetched from the offer issuer and paid
+    pub invoice: String,
+
+    /// The note sent to the offer issuer along with the invoice request, if any
+    pub payer_note: Option<String>,
+
+    /// Only set for [PaymentStatus::Pending] payments that are inflight.
+    pub pending_expiration_block: Option<u32>,
+}
+
 /// Details of a LN payment, as included in a [Payment]
 #[derive(PartialEq, Eq, Debug, Clone, Deserialize, Serialize)]
 pub struct LnPaymentDetails {
@@ -750,18 +1087,25 @@ pub struct LnPaymentDetails {
     /// Only set for [PaymentType::Sent] payments if it is not a payment to a Lightning Address
     pub lnurl_pay_domain: Option<String>,
 
-    /// Only set for [PaymentType::Sent] payments if the user sent the comment using LNURL-pay
+    /// Only set for [PaymentType::Sent] payments if the user sent the comment using LNURL-pay,
+    /// or for [PaymentType::Received] payments to this node's LNURL-pay endpoint where the payer sent a comment
     pub lnurl_pay_comment: Option<String>,
 
-    /// Only set for [PaymentType::Sent] payments that are sent to a Lightning Address
+    /// Only set for [PaymentType::Sent] payments that are sent to a Lightning Address,
+    /// or for [PaymentType::Received] payments to this node's Lightning Address
     pub ln_address: Option<String>,
 
-    /// Only set for [PaymentType::Sent] payments where the receiver endpoint returned LNURL metadata
+    /// Only set for [PaymentType::Sent] payments where the receiver endpoint returned LNURL metadata,
+    /// or for [PaymentType::Received] payments to this node's LNURL-pay endpoint
     pub lnurl_metadata: Option<String>,
 
     /// Only set for [PaymentType::Received] payments that were received as part of LNURL-withdraw
     pub lnurl_withdraw_endpoint: Option<String>,
 
+    /// Only set for [PaymentType::Sent] payments that are part of a LNURL-pay workflow where
+    /// the endpoint returns a LUD-21 verify URL
+    pub lnurl_verify_url: Option<String>,
+
     /// Only set for [PaymentType::Received] payments that were received in the context of a swap
     pub swap_info: Option<SwapInfo>,
 
@@ -806,6 +1150,33 @@ pub struct MaxChannelAmount {
     pub path: PaymentPath,
 }
 
+/// Splits the amount to pay over the channels, draining the ones that can send the most first.
+///
+/// Returns each channel to use with the amount it should deliver to the recipient, or `None`
+/// if all the channels together cannot pay the amount.
+pub(crate) fn split_payment(
+    mut max_amounts: Vec<MaxChannelAmount>,
+    amount_msat: u64,
+) -> Option<Vec<(MaxChannelAmount, u64)>> {
+    let total_msat: u64 = max_amounts.iter().map(|m| m.amount_msat).sum();
+    if amount_msat > total_msat {
+        return None;
+    }
+
+    max_amounts.sort_by_key(|m| Reverse(m.amount_msat));
+    let mut parts = vec![];
+    let mut left_to_pay_msat = amount_msat;
+    for max_amount in max_amounts {
+        if left_to_pay_msat == 0 {
+            break;
+        }
+        let to_pay_msat = min(left_to_pay_msat, max_amount.amount_msat);
+        left_to_pay_msat -= to_pay_msat;
+        parts.push((max_amount, to_pay_msat));
+    }
+    Some(parts)
+}
+
 /// Represents a receive payment request.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ReceivePaymentRequest {
@@ -825,6 +1196,9 @@ pub struct ReceivePaymentRequest {
     pub expiry: Option<u32>,
     /// if specified, sets the min_final_cltv_expiry for the invoice
     pub cltv: Option<u32>,
+    /// If set, the amount to receive instead of `amount_msat`, which should be 0. The amount and
+    /// its rate are stored on the received [Payment].
+    pub fiat_amount: Option<FiatAmount>,
 }
 
 /// Represents a receive payment response.
@@ -842,6 +1216,48 @@ pub struct ReceivePaymentResponse {
     pub opening_fee_msat: Option<u64>,
 }
 
+/// The status of a [PaymentRequest]
+#[derive(Clone, Copy, Debug, Display, EnumString, PartialEq, Eq, Serialize, Deserialize)]
+#[strum(ascii_case_insensitive)]
+pub enum PaymentRequestStatus {
+    Open,
+    Paid,
+    Expired,
+    Cancelled,
+}
+
+/// An invoice created with [crate::BreezServices::receive_payment], tracked until it is paid or
+/// expires. A paid request has the same payment hash as the id of its received [Payment].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PaymentRequest {
+    pub payment_hash: String,
+    pub bolt11: String,
+    pub description: String,
+    /// The amount the payer is asked to pay, including any channel opening fee
+    pub amount_msat: u64,
+    /// If set, these are the [OpeningFeeParams] of the channel opened when the invoice is paid
+    pub opening_fee_params: Option<OpeningFeeParams>,
+    /// If set, this is the channel opening fee deduced from the received amount
+    pub opening_fee_msat: Option<u64>,
+    pub status: PaymentRequestStatus,
+    pub created_at: i64,
+    pub expires_at: i64,
+    /// The time of the received payment, once paid
+    pub paid_at: Option<i64>,
+}
+
+/// Represents a request to list the tracked [PaymentRequest]s, the latest first
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ListPaymentRequestsRequest {
+    pub statuses: Option<Vec<PaymentRequestStatus>>,
+    /// Epoch time, in seconds, compared to the creation time
+    pub from_timestamp: Option<i64>,
+    /// Epoch time, in seconds, compared to the creation time
+    pub to_timestamp: Option<i64>,
+    pub offset: Option<u32>,
+    pub limit: Option<u32>,
+}
+
 /// Represents a send payment request.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SendPaymentRequest {
@@ -851,6 +1267,73 @@ pub struct SendPaymentRequest {
     pub amount_msat: Option<u64>,
     /// The external label or identifier of the [Payment]
     pub label: Option<String>,
+    /// The max fee to pay in millisatoshis. If not set, the limit is derived from
+    /// [Config::maxfee_percent] and [Config::exemptfee_msat].
+    pub max_fee_msat: Option<u64>,
+    /// The amount to pay in a fiat currency, in place of `amount_msat`. The amount and its rate
+    /// are stored on the sent [Payment].
+    pub fiat_amount: Option<FiatAmount>,
+}
+
+/// Represents a request to quote the payment of a bolt11 invoice before sending it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PrepareSendPaymentRequest {
+    /// The bolt11 invoice
+    pub bolt11: String,
+    /// The amount to pay in millisatoshis. Should only be set when `bolt11` is a zero-amount invoice.
+    pub amount_msat: Option<u64>,
+}
+
+/// The quote of a bolt11 invoice payment, as returned by [crate::BreezServices::prepare_send_payment]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrepareSendPaymentResponse {
+    /// The amount the recipient receives, in millisatoshis
+    pub amount_msat: u64,
+    /// Whether the amount can be sent to the recipient, either over the probed route or over the
+    /// routes found in the network graph
+    pub payable: bool,
+    /// Whether the probe of the cheapest route reached the recipient. If so, the payment is
+    /// expected to take this route in one part. Otherwise the quote is estimated from the network
+    /// graph.
+    pub probed: bool,
+    /// The most that can be sent to the recipient over the routes found, in millisatoshis
+    pub max_sendable_msat: u64,
+    /// The expected number of parts the payment is split into. Zero if not payable.
+    pub parts: u32,
+    /// The lower end of the estimated routing fee, in millisatoshis
+    pub min_fee_msat: u64,
+    /// The upper end of the estimated routing fee, in millisatoshis
+    pub max_fee_msat: u64,
+    /// The max fee accepted when paying if [SendPaymentRequest::max_fee_msat] isn't set
+    pub fee_limit_msat: u64,
+}
+
+/// Represents a request to pay a BOLT12 offer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PayOfferRequest {
+    /// The BOLT12 offer
+    pub offer: String,
+    /// The amount to pay in millisatoshis. Should only be set when `offer` has no amount.
+    pub amount_msat: Option<u64>,
+    /// An optional note sent to the offer issuer along with the invoice request
+    pub payer_note: Option<String>,
+    /// The external label or identifier of the [Payment]
+    pub label: Option<String>,
+}
+
+/// Represents a request to create a reusable BOLT12 offer.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CreateOfferRequest {
+    /// The description of the offer
+    pub description: String,
+    /// The amount in millisatoshis the offer asks for. If not set, the payer chooses the amount.
+    pub amount_msat: Option<u64>,
+}
+
+/// Represents a create offer response.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreateOfferResponse {
+    pub offer: LNOffer,
 }
 
 /// Represents a TLV entry for a keysend payment.
@@ -951,6 +1434,8 @@ pub struct OpenChannelFeeResponse {
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ReceiveOnchainRequest {
     pub opening_fee_params: Option<OpeningFeeParams>,
+    /// The type of swap address to create. Defaults to [SwapScriptVersion::P2wsh].
+    pub script_version: Option<SwapScriptVersion>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -1063,6 +1548,276 @@ pub struct RefundResponse {
     pub refund_tx_id: String,
 }
 
+pub struct PrepareRefundAllRequest {
+    pub to_address: String,
+    pub sat_per_vbyte: u32,
+    /// The swaps to refund. If not set, all refundable swaps are refunded.
+    pub swap_addresses: Option<Vec<String>>,
+}
+
+pub struct RefundAllRequest {
+    pub to_address: String,
+    pub sat_per_vbyte: u32,
+    /// The swaps to refund. If not set, all refundable swaps are refunded.
+    pub swap_addresses: Option<Vec<String>>,
+}
+
+/// Represents a request to replace an unconfirmed transaction with one paying a higher fee (RBF)
+pub struct BumpFeeRequest {
+    /// The txid of a refund, reverse swap claim or onchain funds redeem transaction
+    pub txid: String,
+    /// The fee rate of the replacement, which has to be higher than the current one
+    pub sat_per_vbyte: u32,
+}
+
+pub struct BumpFeeResponse {
+    /// The txid of the replacement transaction
+    pub txid: String,
+}
+
+/// The funds spent by a transaction accelerating the unconfirmed deposits of a swap (CPFP)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwapDepositAccelerationSource {
+    /// Outputs of the deposits paying to the node wallet, like the change of a deposit made from
+    /// it, topped up with other onchain funds of the node when needed
+    NodeFunds,
+    /// The deposits of a [SwapScriptVersion::Taproot] swap, refunded to the given address. The
+    /// timelocked refund can only start once they confirm, so they are refunded through the key
+    /// path, co-signed by the swapper.
+    SwapOutput,
+}
+
+/// Represents a request to accelerate the unconfirmed deposits of a swap with a child transaction
+/// paying for them (CPFP)
+pub struct PrepareAccelerateSwapDepositRequest {
+    pub swap_address: String,
+    /// The fee rate of the deposits and the child transaction together
+    pub target_sat_per_vbyte: u32,
+    /// Where to refund the deposits if the node has no funds in them
+    pub refund_address: Option<String>,
+}
+
+pub struct PrepareAccelerateSwapDepositResponse {
+    pub source: SwapDepositAccelerationSource,
+    pub child_tx_weight: u32,
+    pub child_tx_fee_sat: u64,
+}
+
+pub struct AccelerateSwapDepositRequest {
+    pub swap_address: String,
+    /// The fee rate of the deposits and the child transaction together
+    pub target_sat_per_vbyte: u32,
+    /// Where to refund the deposits if the node has no funds in them
+    pub refund_address: Option<String>,
+}
+
+pub struct AccelerateSwapDepositResponse {
+    /// The txid of the child transaction
+    pub txid: String,
+}
+
+/// Automatic sweeps of the node funds, evaluated on every new block and sync. A sweep is skipped
+/// while the recommended fee rate is above its cap.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SweepPolicy {
+    pub onchain: Option<OnchainSweepPolicy>,
+    pub lightning: Option<LightningSweepPolicy>,
+}
+
+/// Redeems the whole onchain balance, like the funds of closed channels, once it exceeds
+/// `max_onchain_balance_sat`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OnchainSweepPolicy {
+    pub to_address: String,
+    pub max_onchain_balance_sat: u64,
+    pub max_sat_per_vbyte: u32,
+}
+
+/// Sends the channels balance above `max_channels_balance_sat` onchain with a reverse swap
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LightningSweepPolicy {
+    pub to_address: String,
+    pub max_channels_balance_sat: u64,
+    pub max_sat_per_vbyte: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SweepKind {
+    /// A redeem of the onchain funds, see [OnchainSweepPolicy]
+    Onchain,
+    /// A reverse swap of the channels balance, see [LightningSweepPolicy]
+    Lightning,
+}
+
+impl SweepPolicy {
+    pub(crate) fn validate(&self, network: Network) -> Result<()> {
+        let destinations = [
+            self.onchain
+                .as_ref()
+                .map(|p| (&p.to_address, p.max_sat_per_vbyte)),
+            self.lightning
+                .as_ref()
+                .map(|p| (&p.to_address, p.max_sat_per_vbyte)),
+        ];
+        for (to_address, max_sat_per_vbyte) in destinations.into_iter().flatten() {
+            let address = Address::from_str(to_address)
+                .map_err(|e| anyhow!("Invalid sweep address {to_address}: {e}"))?;
+            ensure!(
+                address.is_valid_for_network(network.into()),
+                "Sweep address {to_address} is not valid for network {network}"
+            );
+            ensure!(
+                max_sat_per_vbyte > 0,
+                "The sweep fee rate cap has to be positive"
+            );
+        }
+        Ok(())
+    }
+
+    /// The sweeps due for the node balances, at the given fee rate
+    pub(crate) fn due_sweeps(&self, node_state: &NodeState, sat_per_vbyte: u32) -> Vec<SweepKind> {
+        let mut sweeps = vec![];
+        if let Some(onchain) = &self.onchain {
+            if node_state.onchain_balance_msat / 1_000 > onchain.max_onchain_balance_sat
+                && sat_per_vbyte <= onchain.max_sat_per_vbyte
+            {
+                sweeps.push(SweepKind::Onchain);
+            }
+        }
+        if let Some(lightning) = &self.lightning {
+            if node_state.channels_balance_msat / 1_000 > lightning.max_channels_balance_sat
+                && sat_per_vbyte <= lightning.max_sat_per_vbyte
+            {
+                sweeps.push(SweepKind::Lightning);
+            }
+        }
+        sweeps
+    }
+}
+
+/// Limits on the outgoing payments, checked before paying. The amounts include the maximum fee
+/// of a payment.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendingBudget {
+    /// The maximum amount of a single payment
+    pub max_payment_msat: Option<u64>,
+    /// The maximum amounts spent in rolling periods
+    pub limits: Vec<SpendingLimit>,
+    /// Payments above this amount have to be approved by the [crate::PaymentApprover]
+    pub approval_threshold_msat: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendingLimit {
+    pub period: BudgetPeriod,
+    pub max_amount_msat: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Display)]
+pub enum BudgetPeriod {
+    Daily,
+    Weekly,
+    /// A rolling period of 30 days
+    Monthly,
+}
+
+impl BudgetPeriod {
+    pub(crate) fn seconds(&self) -> i64 {
+        match self {
+            BudgetPeriod::Daily => 24 * 3600,
+            BudgetPeriod::Weekly => 7 * 24 * 3600,
+            BudgetPeriod::Monthly => 30 * 24 * 3600,
+        }
+    }
+}
+
+/// A [SpendingBudget] that loosens the current one, applied once its delay has passed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingSpendingBudget {
+    pub budget: SpendingBudget,
+    /// The time the budget applies from, in seconds since epoch
+    pub applies_at: i64,
+}
+
+/// A payment above the [SpendingBudget] approval threshold, waiting for the app to approve it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PaymentApprovalRequest {
+    pub amount_msat: u64,
+    /// The node id or the onchain address the payment is sent to
+    pub destination: String,
+}
+
+/// A [PaymentApprovalRequest] of one of the wallets, sent to the Flutter approval stream. The
+/// app answers it with its id.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaymentApprovalEvent {
+    pub id: u64,
+    pub wallet_id: String,
+    pub request: PaymentApprovalRequest,
+}
+
+impl SpendingBudget {
+    pub(crate) fn validate(&self) -> Result<()> {
+        for (i, limit) in self.limits.iter().enumerate() {
+            ensure!(
+                !self.limits[..i].iter().any(|l| l.period == limit.period),
+                "There can only be one {} spending limit",
+                limit.period
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks a payment against the budget, given the amounts already spent in the period of
+    /// each of its `limits`. Returns why the payment exceeds the budget, if it does.
+    pub(crate) fn exceeded_by(&self, amount_msat: u64, spent_msat: &[u64]) -> Option<String> {
+        if let Some(max_payment_msat) = self.max_payment_msat {
+            if amount_msat > max_payment_msat {
+                return Some(format!(
+                    "The payment of {amount_msat} msat is above the maximum of {max_payment_msat} msat"
+                ));
+            }
+        }
+        for (limit, spent_msat) in self.limits.iter().zip(spent_msat) {
+            if spent_msat.saturating_add(amount_msat) > limit.max_amount_msat {
+                return Some(format!(
+                    "The payment of {amount_msat} msat exceeds the {} limit of {} msat, of which {spent_msat} msat were spent",
+                    limit.period, limit.max_amount_msat
+                ));
+            }
+        }
+        None
+    }
+
+    pub(crate) fn needs_approval(&self, amount_msat: u64) -> bool {
+        self.approval_threshold_msat
+            .is_some_and(|threshold_msat| amount_msat > threshold_msat)
+    }
+
+    /// Whether the budget allows a payment the `current` one doesn't, by raising or removing
+    /// any of its caps
+    pub(crate) fn loosens(&self, current: &SpendingBudget) -> bool {
+        let raised = |cap: Option<u64>, current_cap: Option<u64>| match (cap, current_cap) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(cap), Some(current_cap)) => cap > current_cap,
+        };
+        raised(self.max_payment_msat, current.max_payment_msat)
+            || raised(
+                self.approval_threshold_msat,
+                current.approval_threshold_msat,
+            )
+            || current.limits.iter().any(|current_limit| {
+                let max_amount_msat = self
+                    .limits
+                    .iter()
+                    .find(|l| l.period == current_limit.period)
+                    .map(|l| l.max_amount_msat);
+                raised(max_amount_msat, Some(current_limit.max_amount_msat))
+            })
+    }
+}
+
 /// Dynamic fee parameters offered by the LSP for opening a new channel.
 ///
 /// After they are received, the client shouldn't change them when calling LSP methods,
@@ -1308,6 +2063,30 @@ impl TryFrom<i32> for SwapStatus {
     }
 }
 
+/// The script type of a swap address
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum SwapScriptVersion {
+    /// A P2WSH address, spent through the swap script by both the swapper and the refund.
+    #[default]
+    P2wsh = 0,
+
+    /// A Taproot address. Once paid, the swapper spends it together with us through the MuSig2
+    /// key path, which looks like any other Taproot spend. Refunds use the timelocked script path.
+    Taproot = 1,
+}
+
+impl TryFrom<i32> for SwapScriptVersion {
+    type Error = anyhow::Error;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(SwapScriptVersion::P2wsh),
+            1 => Ok(SwapScriptVersion::Taproot),
+            _ => Err(anyhow!("illegal value")),
+        }
+    }
+}
+
 /// Represents the details of an on-going swap.
 ///
 /// Once this SwapInfo is created it will be monitored on-chain and its state is
@@ -1335,7 +2114,11 @@ pub struct SwapInfo {
     /// The public key in binary format from the swapping service. Received from [SwapperAPI::create_swap].
     pub swapper_public_key: Vec<u8>,
     /// The locking script for the generated bitcoin address. Received from [SwapperAPI::create_swap].
+    ///
+    /// For [SwapScriptVersion::Taproot] swaps, this is the refund leaf of the script tree.
     pub script: Vec<u8>,
+    /// The script type of the swap address.
+    pub script_version: SwapScriptVersion,
 
     /// bolt11 invoice to claim the sent funds.
     pub bolt11: Option<String>,
@@ -1596,8 +2379,14 @@ mod tests {
     use rand::random;
     use sdk_common::grpc;
 
+    use crate::breez_services::tests::get_dummy_node_state;
+    use crate::models::split_payment;
     use crate::test_utils::{get_test_ofp, rand_vec_u8};
-    use crate::{OpeningFeeParams, PaymentPath, PaymentPathEdge};
+    use crate::{
+        BudgetPeriod, FiatAmount, LightningSweepPolicy, LockedFiatRate, MaxChannelAmount, Network,
+        OnchainSweepPolicy, OpeningFeeParams, PaymentPath, PaymentPathEdge, SpendingBudget,
+        SpendingLimit, SweepKind, SweepPolicy, FIAT_RATE_MAX_AGE_SECONDS,
+    };
 
     #[test]
     fn test_route_fees() -> Result<()> {
@@ -1757,4 +2546,174 @@ mod tests {
         ofp.valid_until = "2023-08-03T00:30:35.117Z".to_string();
         ofp.valid_until_date().map(|_| ())
     }
+
+    #[test]
+    fn test_sweep_policy() -> Result<()> {
+        let mut node_state = get_dummy_node_state();
+        node_state.onchain_balance_msat = 200_000_000;
+        node_state.channels_balance_msat = 1_000_000_000;
+
+        let mut policy = SweepPolicy {
+            onchain: Some(OnchainSweepPolicy {
+                to_address: "bc1q7r32q47suczprtpawgvw9xlefzd9nhccyatxvu".into(),
+                max_onchain_balance_sat: 100_000,
+                max_sat_per_vbyte: 10,
+            }),
+            lightning: Some(LightningSweepPolicy {
+                to_address: "34RQERthXaruAXtW6q1bvrGTeUbqi2Sm1i".into(),
+                max_channels_balance_sat: 1_000_000,
+                max_sat_per_vbyte: 20,
+            }),
+        };
+        policy.validate(Network::Bitcoin)?;
+        assert!(policy.validate(Network::Testnet).is_err());
+
+        // The channels balance doesn't exceed its ceiling
+        assert_eq!(policy.due_sweeps(&node_state, 5), vec![SweepKind::Onchain]);
+        node_state.channels_balance_msat = 1_500_000_000;
+        assert_eq!(
+            policy.due_sweeps(&node_state, 5),
+            vec![SweepKind::Onchain, SweepKind::Lightning]
+        );
+        // The sweeps wait for lower fees
+        assert_eq!(
+            policy.due_sweeps(&node_state, 15),
+            vec![SweepKind::Lightning]
+        );
+        assert!(policy.due_sweeps(&node_state, 25).is_empty());
+        assert!(SweepPolicy::default().due_sweeps(&node_state, 1).is_empty());
+
+        policy.onchain.as_mut().unwrap().to_address = "not an address".into();
+        assert!(policy.validate().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_spending_budget() -> Result<()> {
+        let mut budget = SpendingBudget {
+            max_payment_msat: Some(10_000),
+            limits: vec![
+                SpendingLimit {
+                    period: BudgetPeriod::Daily,
+                    max_amount_msat: 20_000,
+                },
+                SpendingLimit {
+                    period: BudgetPeriod::Weekly,
+                    max_amount_msat: 50_000,
+                },
+            ],
+            approval_threshold_msat: Some(5_000),
+        };
+        budget.validate()?;
+
+        assert_eq!(budget.exceeded_by(10_000, &[10_000, 40_000]), None);
+        assert!(budget.exceeded_by(10_001, &[0, 0]).is_some());
+        assert!(budget.exceeded_by(10_000, &[10_001, 10_001]).is_some());
+        assert!(budget.exceeded_by(1_000, &[0, 49_001]).is_some());
+        assert_eq!(SpendingBudget::default().exceeded_by(u64::MAX, &[]), None);
+
+        assert!(!budget.needs_approval(5_000));
+        assert!(budget.needs_approval(5_001));
+        assert!(!SpendingBudget::default().needs_approval(u64::MAX));
+
+        // Adding or lowering caps tightens the budget, raising or removing them loosens it
+        assert!(!budget.loosens(&budget));
+        assert!(!budget.loosens(&SpendingBudget::default()));
+        assert!(SpendingBudget::default().loosens(&budget));
+        let tightened = SpendingBudget {
+            max_payment_msat: Some(9_000),
+            approval_threshold_msat: Some(1_000),
+            ..budget.clone()
+        };
+        assert!(!tightened.loosens(&budget));
+        assert!(budget.loosens(&tightened));
+        let without_weekly_limit = SpendingBudget {
+            limits: budget.limits[..1].to_vec(),
+            ..budget.clone()
+        };
+        assert!(without_weekly_limit.loosens(&budget));
+        assert!(!budget.loosens(&without_weekly_limit));
+
+        budget.limits.push(SpendingLimit {
+            period: BudgetPeriod::Daily,
+            max_amount_msat: 30_000,
+        });
+        assert!(budget.validate().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fiat_amount() -> Result<()> {
+        let now = 1_700_000_000;
+        let mut fiat_amount = FiatAmount {
+            amount: "25.5".into(),
+            rate: LockedFiatRate {
+                id: "lock".into(),
+                currency: "USD".into(),
+                value: "51000".into(),
+                locked_at: now - 60,
+            },
+        };
+        fiat_amount.validate(now)?;
+        assert_eq!(fiat_amount.amount_msat()?, 50_000_000);
+        fiat_amount.rate.value = "51000.00".into();
+        assert_eq!(fiat_amount.amount_msat()?, 50_000_000);
+        // Rounded to the nearest millisatoshi
+        fiat_amount.amount = "0.01".into();
+        fiat_amount.rate.value = "30000".into();
+        assert_eq!(fiat_amount.amount_msat()?, 33_333);
+        fiat_amount.rate.value = "60000".into();
+        assert_eq!(fiat_amount.amount_msat()?, 16_667);
+
+        // A rate is only used for a while after it is locked
+        fiat_amount.rate.locked_at = now - FIAT_RATE_MAX_AGE_SECONDS - 1;
+        assert!(fiat_amount.validate(now).is_err());
+        fiat_amount.rate.locked_at = now;
+        for amount in ["0", "0.000000001", "", ".", "-1", "1e3", "1,5"] {
+            fiat_amount.amount = amount.into();
+            assert!(fiat_amount.validate(now).is_err());
+        }
+        fiat_amount.amount = "1".into();
+        fiat_amount.rate.value = "0".into();
+        assert!(fiat_amount.validate(now).is_err());
+        fiat_amount.rate.value = "0.000000000001".into();
+        fiat_amount.amount = "100000000000000".into();
+        assert!(fiat_amount.amount_msat().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_payment() {
+        let channel = |id: &str, amount_msat: u64| MaxChannelAmount {
+            channel_id: id.into(),
+            amount_msat,
+            path: PaymentPath { edges: vec![] },
+        };
+        let channels = vec![
+            channel("a", 1_000),
+            channel("b", 5_000),
+            channel("c", 3_000),
+        ];
+
+        let parts = split_payment(channels.clone(), 4_000).unwrap();
+        assert_eq!(
+            parts
+                .iter()
+                .map(|(c, amount)| (c.channel_id.as_str(), *amount))
+                .collect::<Vec<_>>(),
+            vec![("b", 4_000)]
+        );
+
+        let parts = split_payment(channels.clone(), 8_500).unwrap();
+        assert_eq!(
+            parts
+                .iter()
+                .map(|(c, amount)| (c.channel_id.as_str(), *amount))
+                .collect::<Vec<_>>(),
+            vec![("b", 5_000), ("c", 3_000), ("a", 500)]
+        );
+
+        assert!(split_payment(channels, 9_001).is_none());
+        assert!(split_payment(vec![], 1).is_none());
+    }
 }
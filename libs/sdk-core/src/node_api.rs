@@ -10,11 +10,12 @@ use sdk_common::prelude::*;
 
 use crate::{
     bitcoin::util::bip32::{ChildNumber, ExtendedPrivKey},
+    bitcoin::OutPoint,
     lightning_invoice::RawBolt11Invoice,
     persist::error::PersistError,
-    CustomMessage, LnUrlAuthError, LspInformation, MaxChannelAmount, NodeCredentials, Payment,
-    PaymentResponse, PrepareRedeemOnchainFundsRequest, PrepareRedeemOnchainFundsResponse,
-    RouteHint, RouteHintHop, SyncResponse, TlvEntry,
+    CreateOfferRequest, CustomMessage, LnUrlAuthError, LspInformation, MaxChannelAmount,
+    NodeCredentials, Payment, PaymentResponse, PrepareRedeemOnchainFundsRequest,
+    PrepareRedeemOnchainFundsResponse, RouteHint, RouteHintHop, SyncResponse, TlvEntry,
 };
 
 pub type NodeResult<T, E = NodeError> = Result<T, E>;
@@ -110,9 +111,43 @@ pub struct FetchBolt11Result {
     pub payer_amount_msat: Option<u64>,
 }
 
+/// The result of [NodeAPI::probe_route]
+pub struct RouteProbe {
+    /// The total fee of the route, in millisatoshis
+    pub fee_msat: u64,
+    /// Whether the probe reached the payee, which means the route can carry the amount
+    pub reached_payee: bool,
+}
+
+/// A transaction of the onchain wallet signed by the node, which isn't broadcast yet
+pub struct SignedOnchainTx {
+    pub fee_sat: u64,
+    /// The estimated weight of the finalized transaction
+    pub weight: u64,
+    /// The signed PSBT, to broadcast with [NodeAPI::send_signed_psbt]
+    pub psbt: String,
+}
+
+/// The incoming payments streamed by [NodeAPI::stream_incoming_payments]
+pub type IncomingPaymentStream =
+    Pin<Box<dyn Stream<Item = Result<gl_client::signer::model::greenlight::IncomingPayment>> + Send>>;
+
+/// Optional features that not every node backend can provide, see [NodeAPI::unsupported_features]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeFeature {
+    /// Exchanging custom peer messages, needed to talk LSPS0 with the LSP
+    CustomMessages,
+    /// Streaming the logs of the node
+    LogStream,
+    /// Creating and paying BOLT12 offers
+    Offers,
+}
+
 /// Trait covering functions affecting the LN node
 #[tonic::async_trait]
 pub trait NodeAPI: Send + Sync {
+    /// The [NodeFeature]s the node can't provide. The methods of these features return an error.
+    fn unsupported_features(&self) -> Vec<NodeFeature>;
     fn node_credentials(&self) -> NodeResult<Option<NodeCredentials>>;
     async fn configure_node(&self, close_to_address: Option<String>) -> NodeResult<()>;
     async fn create_invoice(&self, request: CreateInvoiceRequest) -> NodeResult<String>;
@@ -123,12 +158,14 @@ pub trait NodeAPI: Send + Sync {
         since_timestamp: u64,
         match_local_balance: bool,
     ) -> NodeResult<SyncResponse>;
-    /// As per the `pb::PayRequest` docs, `amount_msat` is only needed when the invoice doesn't specify an amount
+    /// As per the `pb::PayRequest` docs, `amount_msat` is only needed when the invoice doesn't specify an amount.
+    /// If `max_fee_msat` is not set, the fee limits of the [crate::Config] apply.
     async fn send_payment(
         &self,
         bolt11: String,
         amount_msat: Option<u64>,
         label: Option<String>,
+        max_fee_msat: Option<u64>,
     ) -> NodeResult<Payment>;
     async fn send_spontaneous_payment(
         &self,
@@ -137,6 +174,17 @@ pub trait NodeAPI: Send + Sync {
         extra_tlvs: Option<Vec<TlvEntry>>,
         label: Option<String>,
     ) -> NodeResult<Payment>;
+    /// Creates a reusable BOLT12 offer, returning its encoded string
+    async fn create_offer(&self, req: CreateOfferRequest) -> NodeResult<String>;
+    /// Fetches an invoice for the BOLT12 offer and pays it. As with [NodeAPI::send_payment],
+    /// `amount_msat` is only needed when the offer doesn't specify an amount
+    async fn pay_offer(
+        &self,
+        offer: String,
+        amount_msat: Option<u64>,
+        payer_note: Option<String>,
+        label: Option<String>,
+    ) -> NodeResult<Payment>;
     async fn start(&self) -> NodeResult<String>;
 
     /// Attempts to find a payment path "manually" and send the htlcs in a way that will drain
@@ -151,6 +199,18 @@ pub trait NodeAPI: Send + Sync {
         max_hops: u32,
         last_hop: Option<&RouteHintHop>,
     ) -> NodeResult<Vec<MaxChannelAmount>>;
+
+    /// Probes the cheapest route from the node to pay the amount to the payee, through the last
+    /// hop hint if any, with an HTLC the payee can't claim, as its payment hash is random. `None`
+    /// if no route was found in the network graph of the node.
+    async fn probe_route(
+        &self,
+        payee_node_id: Vec<u8>,
+        amount_msat: u64,
+        final_cltv_delta: u64,
+        max_hops: u32,
+        last_hop: Option<&RouteHintHop>,
+    ) -> NodeResult<Option<RouteProbe>>;
     async fn redeem_onchain_funds(
         &self,
         to_address: String,
@@ -160,14 +220,31 @@ pub trait NodeAPI: Send + Sync {
         &self,
         req: PrepareRedeemOnchainFundsRequest,
     ) -> NodeResult<PrepareRedeemOnchainFundsResponse>;
+    /// Signs a transaction spending the given outputs of the onchain wallet to the address, to
+    /// replace the unconfirmed transaction that already spends them (RBF). It is broadcast by
+    /// [NodeAPI::send_signed_psbt].
+    async fn sign_replacement_redeem(
+        &self,
+        utxos: Vec<OutPoint>,
+        to_address: String,
+        sat_per_vbyte: u32,
+    ) -> NodeResult<SignedOnchainTx>;
+    /// Broadcasts a transaction signed by the node, returning its txid
+    async fn send_signed_psbt(&self, psbt: String) -> NodeResult<Vec<u8>>;
+    /// Spends the given outputs of the onchain wallet to the address, even if they are still
+    /// unconfirmed, so the spending transaction pays for their parents (CPFP).
+    async fn cpfp_onchain_funds(
+        &self,
+        utxos: Vec<OutPoint>,
+        to_address: String,
+        sat_per_vbyte: u32,
+    ) -> NodeResult<Vec<u8>>;
     async fn start_signer(&self, shutdown: mpsc::Receiver<()>);
     async fn start_keep_alive(&self, shutdown: watch::Receiver<()>);
     async fn connect_peer(&self, node_id: String, addr: String) -> NodeResult<()>;
     fn sign_invoice(&self, invoice: RawBolt11Invoice) -> NodeResult<String>;
     async fn close_peer_channels(&self, node_id: String) -> NodeResult<Vec<String>>;
-    async fn stream_incoming_payments(
-        &self,
-    ) -> NodeResult<Streaming<gl_client::signer::model::greenlight::IncomingPayment>>;
+    async fn stream_incoming_payments(&self) -> NodeResult<IncomingPaymentStream>;
     async fn stream_log_messages(
         &self,
     ) -> NodeResult<Streaming<gl_client::signer::model::greenlight::LogEntry>>;
@@ -0,0 +1,172 @@
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use anyhow::{anyhow, ensure, Result};
+use sdk_common::prelude::{Aes256CbcDec, Aes256CbcEnc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::bitcoin::hashes::{hex::ToHex, sha256, Hash};
+use crate::bitcoin::secp256k1::{
+    ecdh, schnorr, KeyPair, Message, PublicKey, Secp256k1, SecretKey, XOnlyPublicKey,
+};
+
+/// A signed Nostr event, as defined in NIP-01
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NostrEvent {
+    /// Hex-encoded sha256 of the serialized event
+    pub id: String,
+    /// Hex-encoded x-only public key of the event creator
+    pub pubkey: String,
+    /// Epoch time, in seconds
+    pub created_at: u64,
+    pub kind: u32,
+    pub tags: Vec<Vec<String>>,
+    pub content: String,
+    /// Hex-encoded schnorr signature of the event id
+    pub sig: String,
+}
+
+impl NostrEvent {
+    pub(crate) fn new_signed(
+        keys: &KeyPair,
+        created_at: u64,
+        kind: u32,
+        tags: Vec<Vec<String>>,
+        content: String,
+    ) -> Result<Self> {
+        let mut event = NostrEvent {
+            id: String::new(),
+            pubkey: keys.x_only_public_key().0.to_hex(),
+            created_at,
+            kind,
+            tags,
+            content,
+            sig: String::new(),
+        };
+        event.id = event.compute_id();
+        let msg = Message::from_slice(&hex::decode(&event.id)?)?;
+        event.sig = Secp256k1::new()
+            .sign_schnorr_no_aux_rand(&msg, keys)
+            .to_hex();
+        Ok(event)
+    }
+
+    fn compute_id(&self) -> String {
+        let serialized = json!([
+            0,
+            self.pubkey,
+            self.created_at,
+            self.kind,
+            self.tags,
+            self.content
+        ])
+        .to_string();
+        sha256::Hash::hash(serialized.as_bytes()).to_hex()
+    }
+
+    /// Checks that the id matches the event contents and that it is signed by `pubkey`
+    pub(crate) fn verify(&self) -> Result<()> {
+        ensure!(self.id == self.compute_id(), "Invalid event id");
+        let msg = Message::from_slice(&hex::decode(&self.id)?)?;
+        let sig = schnorr::Signature::from_slice(&hex::decode(&self.sig)?)?;
+        let pubkey = XOnlyPublicKey::from_slice(&hex::decode(&self.pubkey)?)?;
+        Secp256k1::verification_only()
+            .verify_schnorr(&sig, &msg, &pubkey)
+            .map_err(|e| anyhow!("Invalid event signature: {e}"))
+    }
+
+    /// The first value of the first tag with the given name
+    pub(crate) fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|tag| tag.first().map(String::as_str) == Some(name))
+            .and_then(|tag| tag.get(1))
+            .map(String::as_str)
+    }
+}
+
+/// The NIP-04 shared secret is the unhashed x coordinate of the ECDH point
+fn nip04_shared_secret(secret_key: &SecretKey, pubkey: &str) -> Result<[u8; 32]> {
+    let xonly = XOnlyPublicKey::from_slice(&hex::decode(pubkey)?)?;
+    let pubkey = PublicKey::from_slice(&[&[0x02], &xonly.serialize()[..]].concat())?;
+    let point = ecdh::shared_secret_point(&pubkey, secret_key);
+    let mut shared_secret = [0u8; 32];
+    shared_secret.copy_from_slice(&point[..32]);
+    Ok(shared_secret)
+}
+
+/// Encrypts the plaintext for `pubkey`, as defined in NIP-04
+pub(crate) fn nip04_encrypt(
+    secret_key: &SecretKey,
+    pubkey: &str,
+    plaintext: &str,
+) -> Result<String> {
+    let key = nip04_shared_secret(secret_key, pubkey)?;
+    let iv: [u8; 16] = rand::random();
+    let ciphertext = Aes256CbcEnc::new_from_slices(&key, &iv)?
+        .encrypt_padded_vec_mut::<Pkcs7>(plaintext.as_bytes());
+    Ok(format!(
+        "{}?iv={}",
+        base64::encode(ciphertext),
+        base64::encode(iv)
+    ))
+}
+
+/// Decrypts content sent by `pubkey`, as defined in NIP-04
+pub(crate) fn nip04_decrypt(secret_key: &SecretKey, pubkey: &str, content: &str) -> Result<String> {
+    let (ciphertext, iv) = content
+        .split_once("?iv=")
+        .ok_or_else(|| anyhow!("Invalid NIP-04 content"))?;
+    let key = nip04_shared_secret(secret_key, pubkey)?;
+    let plaintext = Aes256CbcDec::new_from_slices(&key, &base64::decode(iv)?)?
+        .decrypt_padded_vec_mut::<Pkcs7>(&base64::decode(ciphertext)?)
+        .map_err(|e| anyhow!("Failed to decrypt NIP-04 content: {e}"))?;
+    Ok(String::from_utf8(plaintext)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::bitcoin::secp256k1::{KeyPair, Secp256k1};
+    use crate::nwc::event::*;
+
+    #[test]
+    fn test_event_sign_and_verify() -> Result<()> {
+        let keys = KeyPair::new(&Secp256k1::new(), &mut rand::thread_rng());
+        let event = NostrEvent::new_signed(
+            &keys,
+            1_700_000_000,
+            23194,
+            vec![vec!["p".into(), "abcd".into()]],
+            "content".into(),
+        )?;
+        event.verify()?;
+        assert_eq!(event.tag("p"), Some("abcd"));
+        assert_eq!(event.tag("e"), None);
+
+        let mut tampered = event.clone();
+        tampered.content = "other content".into();
+        assert!(tampered.verify().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_nip04_encrypt_decrypt() -> Result<()> {
+        let secp = Secp256k1::new();
+        let alice = KeyPair::new(&secp, &mut rand::thread_rng());
+        let bob = KeyPair::new(&secp, &mut rand::thread_rng());
+
+        let encrypted = nip04_encrypt(
+            &alice.secret_key(),
+            &bob.x_only_public_key().0.to_hex(),
+            "hello bob",
+        )?;
+        let decrypted = nip04_decrypt(
+            &bob.secret_key(),
+            &alice.x_only_public_key().0.to_hex(),
+            &encrypted,
+        )?;
+        assert_eq!(decrypted, "hello bob");
+        Ok(())
+    }
+}
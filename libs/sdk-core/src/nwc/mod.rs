@@ -0,0 +1,911 @@
+//! A Nostr Wallet Connect (NIP-47) wallet service, as described in
+//! <https://github.com/nostr-protocol/nips/blob/master/47.md>
+//!
+//! Each connection is identified by the public key its client signs requests with, and is only
+//! allowed to use the granted [NwcMethod]s, spending at most its [NwcBudget]. The client secret is
+//! only shared in the connection URI and never stored by the SDK.
+
+pub mod event;
+pub mod relay;
+
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, ensure, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use strum_macros::{Display, EnumString};
+use tokio::sync::{watch, Mutex};
+
+use self::event::{nip04_decrypt, nip04_encrypt, NostrEvent};
+use self::relay::{NostrFilter, NostrRelay};
+use crate::bitcoin::hashes::hex::ToHex;
+use crate::bitcoin::secp256k1::{KeyPair, Secp256k1};
+use crate::breez_services::BreezServices;
+use crate::error::{SdkError, SdkResult, SendPaymentError};
+use crate::greenlight::node_api::default_fee_limit_msat;
+use crate::persist::db::SqliteStorage;
+use crate::persist::error::PersistError;
+use crate::wallets;
+use crate::*;
+
+const INFO_EVENT_KIND: u32 = 13194;
+const REQUEST_EVENT_KIND: u32 = 23194;
+const RESPONSE_EVENT_KIND: u32 = 23195;
+/// Requests created longer ago, or further in the future, are ignored. The ids of the handled
+/// requests are kept for this long, so they can't be replayed.
+const MAX_REQUEST_AGE_SEC: i64 = 600;
+
+/// A NIP-47 command that can be granted to a connection
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, EnumString, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum NwcMethod {
+    PayInvoice,
+    MakeInvoice,
+    LookupInvoice,
+    ListTransactions,
+    GetBalance,
+}
+
+/// The spending budget of a connection
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NwcBudget {
+    /// The maximum amount, in millisats, the client can spend in a budget period
+    pub max_amount_msat: u64,
+    /// If set, the budget is renewed after this many seconds. Otherwise it never renews.
+    pub renewal_period_sec: Option<u64>,
+    /// The amount, including fees, spent in the current budget period
+    pub used_amount_msat: u64,
+    /// Epoch time, in seconds, at which the current budget period started
+    pub period_start: i64,
+}
+
+impl NwcBudget {
+    /// Starts a new budget period if the current one elapsed. Returns whether it did.
+    fn renew(&mut self, now: i64) -> bool {
+        match self.renewal_period_sec {
+            Some(period) if now >= self.period_start + period as i64 => {
+                self.used_amount_msat = 0;
+                self.period_start = now;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn remaining_msat(&self) -> u64 {
+        self.max_amount_msat.saturating_sub(self.used_amount_msat)
+    }
+}
+
+/// A connection between a Nostr client and the wallet service
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NwcConnection {
+    /// Hex-encoded x-only public key the client signs its requests with
+    pub client_pubkey: String,
+    /// A name to identify the client
+    pub name: String,
+    pub permissions: Vec<NwcMethod>,
+    /// If not set, the client can spend the whole balance
+    pub budget: Option<NwcBudget>,
+    /// Epoch time, in seconds
+    pub created_at: i64,
+}
+
+/// Represents a request to create a Nostr Wallet Connect connection
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreateNwcConnectionRequest {
+    /// A name to identify the client
+    pub name: String,
+    /// The URL of the relay the wallet service is started with, see
+    /// [BreezServices::start_nwc_service]
+    pub relay_url: String,
+    pub permissions: Vec<NwcMethod>,
+    /// If set, the maximum amount, in millisats, the client can spend
+    pub max_amount_msat: Option<u64>,
+    /// If set along with `max_amount_msat`, the budget is renewed after this many seconds
+    pub renewal_period_sec: Option<u64>,
+}
+
+/// Represents a create Nostr Wallet Connect connection response
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreateNwcConnectionResponse {
+    pub connection: NwcConnection,
+    /// The `nostr+walletconnect://` URI to share with the client. It contains the client secret,
+    /// so it can't be retrieved later.
+    pub connection_uri: String,
+}
+
+/// Error codes returned to the client, as defined in NIP-47
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum NwcErrorCode {
+    NotImplemented,
+    InsufficientBalance,
+    QuotaExceeded,
+    Restricted,
+    Internal,
+    Other,
+    PaymentFailed,
+    NotFound,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NwcError {
+    code: NwcErrorCode,
+    message: String,
+}
+
+impl NwcError {
+    fn new(code: NwcErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn other(err: impl ToString) -> Self {
+        Self::new(NwcErrorCode::Other, err.to_string())
+    }
+}
+
+impl From<SdkError> for NwcError {
+    fn from(err: SdkError) -> Self {
+        Self::new(NwcErrorCode::Internal, err.to_string())
+    }
+}
+
+impl From<PersistError> for NwcError {
+    fn from(err: PersistError) -> Self {
+        Self::new(NwcErrorCode::Internal, err.to_string())
+    }
+}
+
+impl From<SendPaymentError> for NwcError {
+    fn from(err: SendPaymentError) -> Self {
+        let code = match err {
+            SendPaymentError::InvalidAmount { .. }
+            | SendPaymentError::InvalidInvoice { .. }
+            | SendPaymentError::InvalidNetwork { .. }
+            | SendPaymentError::InvoiceExpired { .. }
+            | SendPaymentError::AlreadyPaid => NwcErrorCode::Other,
+            SendPaymentError::BudgetExceeded { .. } => NwcErrorCode::QuotaExceeded,
+            _ => NwcErrorCode::PaymentFailed,
+        };
+        Self::new(code, err.to_string())
+    }
+}
+
+type NwcResult<T> = Result<T, NwcError>;
+
+#[derive(Deserialize)]
+struct NwcRequest {
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct PayInvoiceParams {
+    invoice: String,
+    /// Only used for zero-amount invoices
+    amount: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct MakeInvoiceParams {
+    amount: u64,
+    description: Option<String>,
+    expiry: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct LookupInvoiceParams {
+    payment_hash: Option<String>,
+    invoice: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ListTransactionsParams {
+    from: Option<i64>,
+    until: Option<i64>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+    #[serde(rename = "type")]
+    transaction_type: Option<String>,
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> NwcResult<T> {
+    let params = match params {
+        Value::Null => json!({}),
+        params => params,
+    };
+    serde_json::from_value(params).map_err(|e| NwcError::other(format!("Invalid params: {e}")))
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// Checks that the request is recent enough and that it didn't expire, as set by its NIP-40
+/// `expiration` tag
+fn ensure_fresh(event: &NostrEvent, now: i64) -> Result<()> {
+    let created_at = event.created_at as i64;
+    ensure!(
+        (now - created_at).abs() <= MAX_REQUEST_AGE_SEC,
+        "Request created at {created_at} is too old or too far in the future"
+    );
+    if let Some(expiration) = event.tag("expiration") {
+        let expiration: i64 = expiration.parse()?;
+        ensure!(now < expiration, "Request expired at {expiration}");
+    }
+    Ok(())
+}
+
+/// Loads the keys of the wallet service, creating them on first use
+fn service_keys(persister: &SqliteStorage) -> SdkResult<KeyPair> {
+    let secret_key = match persister.get_nwc_service_key()? {
+        Some(secret_key) => secret_key,
+        None => {
+            let secret_key = rand::random::<[u8; 32]>().to_vec();
+            persister.set_nwc_service_key(secret_key.clone())?;
+            secret_key
+        }
+    };
+    Ok(KeyPair::from_seckey_slice(&Secp256k1::new(), &secret_key)
+        .map_err(|e| anyhow!("Invalid NWC service key: {e}"))?)
+}
+
+pub(crate) fn create_connection(
+    persister: &SqliteStorage,
+    req: CreateNwcConnectionRequest,
+) -> SdkResult<CreateNwcConnectionResponse> {
+    ensure_sdk!(
+        !req.permissions.is_empty(),
+        SdkError::generic("At least one permission is required")
+    );
+
+    let service_keys = service_keys(persister)?;
+    let client_keys = KeyPair::new(&Secp256k1::new(), &mut rand::thread_rng());
+    let created_at = now();
+    let connection = NwcConnection {
+        client_pubkey: client_keys.x_only_public_key().0.to_hex(),
+        name: req.name,
+        permissions: req.permissions,
+        budget: req.max_amount_msat.map(|max_amount_msat| NwcBudget {
+            max_amount_msat,
+            renewal_period_sec: req.renewal_period_sec,
+            used_amount_msat: 0,
+            period_start: created_at,
+        }),
+        created_at,
+    };
+
+    let mut connection_uri = reqwest::Url::parse(&format!(
+        "nostr+walletconnect://{}",
+        service_keys.x_only_public_key().0.to_hex()
+    ))
+    .map_err(|e| anyhow!("Failed to create connection URI: {e}"))?;
+    connection_uri
+        .query_pairs_mut()
+        .append_pair("relay", &req.relay_url)
+        .append_pair(
+            "secret",
+            &hex::encode(client_keys.secret_key().secret_bytes()),
+        );
+
+    persister.insert_nwc_connection(&connection)?;
+    Ok(CreateNwcConnectionResponse {
+        connection,
+        connection_uri: connection_uri.to_string(),
+    })
+}
+
+fn payment_to_transaction(payment: &Payment) -> Value {
+    let (invoice, payment_hash, preimage) = match &payment.details {
+        PaymentDetails::Ln { data } => (
+            Some(data.bolt11.clone()),
+            Some(data.payment_hash.clone()),
+            data.payment_preimage.clone(),
+        ),
+        PaymentDetails::Bolt12 { data } => (
+            Some(data.invoice.clone()),
+            Some(data.payment_hash.clone()),
+            data.payment_preimage.clone(),
+        ),
+        PaymentDetails::ClosedChannel { .. } => (None, None, String::new()),
+    };
+    let transaction_type = match payment.payment_type {
+        PaymentType::Received => "incoming",
+        _ => "outgoing",
+    };
+
+    json!({
+        "type": transaction_type,
+        "invoice": invoice,
+        "description": payment.description,
+        "payment_hash": payment_hash,
+        "preimage": Some(preimage).filter(|preimage| !preimage.is_empty()),
+        "amount": payment.amount_msat,
+        "fees_paid": payment.fee_msat,
+        "created_at": payment.payment_time,
+        "settled_at": (payment.status == PaymentStatus::Complete).then_some(payment.payment_time),
+    })
+}
+
+/// The part of the budget of a connection held while its payment is in flight
+struct BudgetReservation {
+    amount_msat: u64,
+    period_start: i64,
+}
+
+/// Handles the requests of the clients, received through the relay
+pub(crate) struct NwcService {
+    breez_services: Arc<BreezServices>,
+    persister: Arc<SqliteStorage>,
+    relay: Arc<dyn NostrRelay>,
+    config: Config,
+    keys: KeyPair,
+    budget_lock: Mutex<()>,
+}
+
+impl NwcService {
+    pub(crate) fn new(
+        breez_services: Arc<BreezServices>,
+        persister: Arc<SqliteStorage>,
+        relay: Arc<dyn NostrRelay>,
+        config: Config,
+    ) -> SdkResult<Self> {
+        let keys = service_keys(&persister)?;
+        Ok(Self {
+            breez_services,
+            persister,
+            relay,
+            config,
+            keys,
+            budget_lock: Mutex::new(()),
+        })
+    }
+
+    /// Publishes the info event and handles the requests until shutdown
+    pub(crate) async fn start(self, mut shutdown_receiver: watch::Receiver<()>) -> SdkResult<()> {
+        let methods = [
+            NwcMethod::PayInvoice,
+            NwcMethod::MakeInvoice,
+            NwcMethod::LookupInvoice,
+            NwcMethod::ListTransactions,
+            NwcMethod::GetBalance,
+        ]
+        .map(|method| method.to_string())
+        .join(" ");
+        let info =
+            NostrEvent::new_signed(&self.keys, now() as u64, INFO_EVENT_KIND, vec![], methods)?;
+        self.relay.publish(info).await?;
+
+        let mut requests = self
+            .relay
+            .subscribe(NostrFilter {
+                kinds: vec![REQUEST_EVENT_KIND],
+                p_tags: vec![self.keys.x_only_public_key().0.to_hex()],
+                since: Some(now() as u64),
+            })
+            .await?;
+        let service = Arc::new(self);
+        wallets::spawn(async move {
+            loop {
+                tokio::select! {
+                    request = requests.recv() => {
+                        let Some(request) = request else {
+                            warn!("NWC relay subscription closed");
+                            break;
+                        };
+                        // Each request has its own task, so a slow payment doesn't hold up the
+                        // requests received after it
+                        let service = service.clone();
+                        wallets::spawn(async move {
+                            if let Some(response) = service.handle_event(request).await {
+                                if let Err(e) = service.relay.publish(response).await {
+                                    error!("Failed to publish NWC response: {e}");
+                                }
+                            }
+                        });
+                    },
+                    _ = shutdown_receiver.changed() => {
+                        debug!("NWC service task completed");
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Returns the response event to publish, if the request comes from a known client and it
+    /// wasn't handled before
+    async fn handle_event(&self, event: NostrEvent) -> Option<NostrEvent> {
+        if let Err(e) = event.verify() {
+            warn!("Ignoring invalid NWC request {}: {e}", event.id);
+            return None;
+        }
+        let now = now();
+        if let Err(e) = ensure_fresh(&event, now) {
+            warn!("Ignoring NWC request {}: {e}", event.id);
+            return None;
+        }
+        let connection = match self.persister.get_nwc_connection(&event.pubkey) {
+            Ok(Some(connection)) => connection,
+            Ok(None) => {
+                warn!("Ignoring NWC request from unknown client {}", event.pubkey);
+                return None;
+            }
+            Err(e) => {
+                error!("Failed to get NWC connection: {e}");
+                return None;
+            }
+        };
+        match self.persister.insert_nwc_request(
+            &event.id,
+            event.created_at as i64,
+            now - MAX_REQUEST_AGE_SEC,
+        ) {
+            Ok(true) => {}
+            Ok(false) => {
+                warn!("Ignoring replayed NWC request {}", event.id);
+                return None;
+            }
+            Err(e) => {
+                error!("Failed to record NWC request: {e}");
+                return None;
+            }
+        }
+        let request: NwcRequest =
+            match nip04_decrypt(&self.keys.secret_key(), &event.pubkey, &event.content)
+                .and_then(|content| Ok(serde_json::from_str(&content)?))
+            {
+                Ok(request) => request,
+                Err(e) => {
+                    warn!("Failed to decode NWC request {}: {e}", event.id);
+                    return None;
+                }
+            };
+
+        let content = match self
+            .handle_request(connection, request.params, &request.method)
+            .await
+        {
+            Ok(result) => json!({ "result_type": request.method, "result": result }),
+            Err(error) => json!({ "result_type": request.method, "error": error }),
+        };
+        match self.create_response(&event, content.to_string()) {
+            Ok(response) => Some(response),
+            Err(e) => {
+                error!("Failed to create NWC response: {e}");
+                None
+            }
+        }
+    }
+
+    fn create_response(&self, request: &NostrEvent, content: String) -> Result<NostrEvent> {
+        NostrEvent::new_signed(
+            &self.keys,
+            now() as u64,
+            RESPONSE_EVENT_KIND,
+            vec![
+                vec!["p".into(), request.pubkey.clone()],
+                vec!["e".into(), request.id.clone()],
+            ],
+            nip04_encrypt(&self.keys.secret_key(), &request.pubkey, &content)?,
+        )
+    }
+
+    async fn handle_request(
+        &self,
+        connection: NwcConnection,
+        params: Value,
+        method: &str,
+    ) -> NwcResult<Value> {
+        let method = NwcMethod::from_str(method).map_err(|_| {
+            NwcError::new(
+                NwcErrorCode::NotImplemented,
+                format!("Unknown method {method}"),
+            )
+        })?;
+        if !connection.permissions.contains(&method) {
+            return Err(NwcError::new(
+                NwcErrorCode::Restricted,
+                format!("This connection is not allowed to {method}"),
+            ));
+        }
+
+        match method {
+            NwcMethod::PayInvoice => self.pay_invoice(connection, parse_params(params)?).await,
+            NwcMethod::MakeInvoice => self.make_invoice(parse_params(params)?).await,
+            NwcMethod::LookupInvoice => self.lookup_invoice(parse_params(params)?).await,
+            NwcMethod::ListTransactions => self.list_transactions(parse_params(params)?).await,
+            NwcMethod::GetBalance => self.get_balance(),
+        }
+    }
+
+    async fn pay_invoice(
+        &self,
+        connection: NwcConnection,
+        params: PayInvoiceParams,
+    ) -> NwcResult<Value> {
+        let invoice = parse_invoice(&params.invoice).map_err(NwcError::other)?;
+        let amount_msat = invoice
+            .amount_msat
+            .or(params.amount)
+            .ok_or_else(|| NwcError::other("Amount is required for zero-amount invoices"))?;
+
+        if amount_msat > self.breez_services.node_info()?.max_payable_msat {
+            return Err(NwcError::new(
+                NwcErrorCode::InsufficientBalance,
+                "Insufficient balance",
+            ));
+        }
+
+        let fee_limit_msat = default_fee_limit_msat(&self.config, amount_msat);
+        let reservation = self
+            .reserve_budget(&connection.client_pubkey, amount_msat + fee_limit_msat)
+            .await?;
+        let payment_res = self
+            .breez_services
+            .send_payment(SendPaymentRequest {
+                bolt11: params.invoice,
+                amount_msat: invoice.amount_msat.is_none().then_some(amount_msat),
+                label: None,
+                max_fee_msat: Some(fee_limit_msat),
+                fiat_amount: None,
+            })
+            .await
+            .map(|res| res.payment);
+        if let Some(reservation) = reservation {
+            self.settle_budget(&connection.client_pubkey, reservation, &payment_res)
+                .await?;
+        }
+
+        let preimage = match payment_res?.details {
+            PaymentDetails::Ln { data } => data.payment_preimage,
+            _ => String::new(),
+        };
+        Ok(json!({ "preimage": preimage }))
+    }
+
+    /// Holds the amount, including the fee limit, from the budget of the connection, if it has
+    /// one. The budget is read and updated under the lock, so concurrent payments can't spend the
+    /// same amount.
+    async fn reserve_budget(
+        &self,
+        client_pubkey: &str,
+        amount_msat: u64,
+    ) -> NwcResult<Option<BudgetReservation>> {
+        let _budget_guard = self.budget_lock.lock().await;
+        let Some(mut budget) = self.get_budget(client_pubkey)? else {
+            return Ok(None);
+        };
+        budget.renew(now());
+        if amount_msat > budget.remaining_msat() {
+            return Err(NwcError::new(
+                NwcErrorCode::QuotaExceeded,
+                "The payment, including its fee limit, exceeds the budget of this connection",
+            ));
+        }
+        budget.used_amount_msat += amount_msat;
+        self.persister.update_nwc_budget(client_pubkey, &budget)?;
+        Ok(Some(BudgetReservation {
+            amount_msat,
+            period_start: budget.period_start,
+        }))
+    }
+
+    /// Replaces the reservation with the amount paid, fees included. A failed payment releases it,
+    /// while a timed out payment may still complete, so its reservation is kept. If the budget
+    /// was renewed since, the reservation was already released.
+    async fn settle_budget(
+        &self,
+        client_pubkey: &str,
+        reservation: BudgetReservation,
+        payment_res: &Result<Payment, SendPaymentError>,
+    ) -> NwcResult<()> {
+        let spent_msat = match payment_res {
+            Ok(payment) => payment.amount_msat + payment.fee_msat,
+            Err(SendPaymentError::PaymentTimeout { .. }) => return Ok(()),
+            Err(_) => 0,
+        };
+        let _budget_guard = self.budget_lock.lock().await;
+        let connection = self.persister.get_nwc_connection(client_pubkey)?;
+        let Some(mut budget) = connection.and_then(|connection| connection.budget) else {
+            return Ok(());
+        };
+        if budget.period_start == reservation.period_start {
+            budget.used_amount_msat = budget
+                .used_amount_msat
+                .saturating_sub(reservation.amount_msat)
+                + spent_msat;
+            self.persister.update_nwc_budget(client_pubkey, &budget)?;
+        }
+        Ok(())
+    }
+
+    fn get_budget(&self, client_pubkey: &str) -> NwcResult<Option<NwcBudget>> {
+        match self.persister.get_nwc_connection(client_pubkey)? {
+            Some(connection) => Ok(connection.budget),
+            None => Err(NwcError::new(
+                NwcErrorCode::Restricted,
+                "This connection was deleted",
+            )),
+        }
+    }
+
+    async fn make_invoice(&self, params: MakeInvoiceParams) -> NwcResult<Value> {
+        let invoice = self
+            .breez_services
+            .receive_payment(ReceivePaymentRequest {
+                amount_msat: params.amount,
+                description: params.description.unwrap_or_default(),
+                expiry: params.expiry,
+                ..Default::default()
+            })
+            .await
+            .map_err(NwcError::other)?
+            .ln_invoice;
+
+        Ok(json!({
+            "type": "incoming",
+            "invoice": invoice.bolt11,
+            "description": invoice.description,
+            "payment_hash": invoice.payment_hash,
+            "amount": invoice.amount_msat,
+            "fees_paid": 0,
+            "created_at": invoice.timestamp,
+            "expires_at": invoice.timestamp + invoice.expiry,
+        }))
+    }
+
+    /// Only finds paid or pending invoices, as unpaid invoices are not stored as payments
+    async fn lookup_invoice(&self, params: LookupInvoiceParams) -> NwcResult<Value> {
+        let payment_hash = match (params.payment_hash, params.invoice) {
+            (Some(payment_hash), _) => payment_hash,
+            (None, Some(invoice)) => {
+                parse_invoice(&invoice)
+                    .map_err(NwcError::other)?
+                    .payment_hash
+            }
+            (None, None) => {
+                return Err(NwcError::other(
+                    "Either payment_hash or invoice is required",
+                ))
+            }
+        };
+
+        match self.breez_services.payment_by_hash(payment_hash).await? {
+            Some(payment) => Ok(payment_to_transaction(&payment)),
+            None => Err(NwcError::new(NwcErrorCode::NotFound, "Invoice not found")),
+        }
+    }
+
+    async fn list_transactions(&self, params: ListTransactionsParams) -> NwcResult<Value> {
+        let filters = match params.transaction_type.as_deref() {
+            Some("incoming") => vec![PaymentTypeFilter::Received],
+            Some("outgoing") => vec![PaymentTypeFilter::Sent],
+            _ => vec![PaymentTypeFilter::Sent, PaymentTypeFilter::Received],
+        };
+        let payments = self
+            .breez_services
+            .list_payments(ListPaymentsRequest {
+                filters: Some(filters),
+                from_timestamp: params.from,
+                to_timestamp: params.until,
+                offset: params.offset,
+                limit: params.limit,
+                ..Default::default()
+            })
+            .await?;
+
+        Ok(json!({
+            "transactions": payments.iter().map(payment_to_transaction).collect::<Vec<Value>>()
+        }))
+    }
+
+    fn get_balance(&self) -> NwcResult<Value> {
+        let node_state = self.breez_services.node_info()?;
+        Ok(json!({ "balance": node_state.channels_balance_msat }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use reqwest::Url;
+    use serde_json::{json, Value};
+
+    use crate::bitcoin::hashes::hex::ToHex;
+    use crate::bitcoin::secp256k1::{KeyPair, Secp256k1};
+    use crate::breez_services::tests::breez_services;
+    use crate::nwc::event::{nip04_decrypt, nip04_encrypt, NostrEvent};
+    use crate::nwc::relay::{NostrFilter, NostrRelay};
+    use crate::nwc::*;
+    use crate::test_utils::{create_invoice, MockNostrRelay};
+
+    #[test]
+    fn test_budget_renewal() {
+        let mut budget = NwcBudget {
+            max_amount_msat: 10_000,
+            renewal_period_sec: Some(60),
+            used_amount_msat: 4_000,
+            period_start: 1_000,
+        };
+        assert!(!budget.renew(1_059));
+        assert_eq!(budget.remaining_msat(), 6_000);
+        assert!(budget.renew(1_060));
+        assert_eq!(budget.remaining_msat(), 10_000);
+        assert_eq!(budget.period_start, 1_060);
+
+        let mut budget = NwcBudget {
+            renewal_period_sec: None,
+            ..budget
+        };
+        assert!(!budget.renew(i64::MAX));
+    }
+
+    #[test]
+    fn test_ensure_fresh() -> Result<()> {
+        let keys = KeyPair::new(&Secp256k1::new(), &mut rand::thread_rng());
+        let request =
+            |tags| NostrEvent::new_signed(&keys, 1_000, REQUEST_EVENT_KIND, tags, "".into());
+
+        assert!(ensure_fresh(&request(vec![])?, 1_000 + MAX_REQUEST_AGE_SEC).is_ok());
+        assert!(ensure_fresh(&request(vec![])?, 1_001 + MAX_REQUEST_AGE_SEC).is_err());
+        assert!(ensure_fresh(&request(vec![])?, 999 - MAX_REQUEST_AGE_SEC).is_err());
+
+        let expiring = request(vec![vec!["expiration".into(), "1060".into()]])?;
+        assert!(ensure_fresh(&expiring, 1_059).is_ok());
+        assert!(ensure_fresh(&expiring, 1_060).is_err());
+        Ok(())
+    }
+
+    struct TestClient {
+        keys: KeyPair,
+        service_pubkey: String,
+        relay: Arc<MockNostrRelay>,
+    }
+
+    impl TestClient {
+        fn from_uri(connection_uri: &str, relay: Arc<MockNostrRelay>) -> Result<Self> {
+            let uri = Url::parse(connection_uri)?;
+            let secret = uri
+                .query_pairs()
+                .find(|(key, _)| key == "secret")
+                .unwrap()
+                .1
+                .to_string();
+            Ok(Self {
+                keys: KeyPair::from_seckey_slice(&Secp256k1::new(), &hex::decode(secret)?)?,
+                service_pubkey: uri.host_str().unwrap().to_string(),
+                relay,
+            })
+        }
+
+        async fn request(&self, method: &str, params: Value) -> Result<Value> {
+            let mut responses = self
+                .relay
+                .subscribe(NostrFilter {
+                    kinds: vec![RESPONSE_EVENT_KIND],
+                    p_tags: vec![self.keys.x_only_public_key().0.to_hex()],
+                    since: None,
+                })
+                .await?;
+            let content = json!({ "method": method, "params": params }).to_string();
+            let request = NostrEvent::new_signed(
+                &self.keys,
+                now() as u64,
+                REQUEST_EVENT_KIND,
+                vec![vec!["p".into(), self.service_pubkey.clone()]],
+                nip04_encrypt(&self.keys.secret_key(), &self.service_pubkey, &content)?,
+            )?;
+            self.relay.publish(request.clone()).await?;
+
+            let response = tokio::time::timeout(Duration::from_secs(5), responses.recv())
+                .await?
+                .unwrap();
+            response.verify()?;
+            assert_eq!(response.pubkey, self.service_pubkey);
+            assert_eq!(response.tag("e"), Some(request.id.as_str()));
+            let content = nip04_decrypt(
+                &self.keys.secret_key(),
+                &self.service_pubkey,
+                &response.content,
+            )?;
+            Ok(serde_json::from_str(&content)?)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nwc_service() -> Result<()> {
+        let breez_services = breez_services().await?;
+        breez_services.sync().await?;
+        let relay = Arc::new(MockNostrRelay::new());
+        breez_services.start_nwc_service(relay.clone()).await?;
+
+        let res = breez_services
+            .create_nwc_connection(CreateNwcConnectionRequest {
+                name: "test client".into(),
+                relay_url: relay.url(),
+                permissions: vec![
+                    NwcMethod::PayInvoice,
+                    NwcMethod::MakeInvoice,
+                    NwcMethod::GetBalance,
+                ],
+                // Enough for one payment of 90 msat with the default fee limit of 20 sat
+                max_amount_msat: Some(20_100),
+                renewal_period_sec: None,
+            })
+            .await?;
+        assert!(res.connection_uri.starts_with("nostr+walletconnect://"));
+        assert_eq!(
+            breez_services.list_nwc_connections().await?,
+            vec![res.connection.clone()]
+        );
+        let client = TestClient::from_uri(&res.connection_uri, relay.clone())?;
+        assert_eq!(
+            client.keys.x_only_public_key().0.to_hex(),
+            res.connection.client_pubkey
+        );
+
+        let response = client.request("get_balance", json!({})).await?;
+        assert_eq!(response["result_type"], "get_balance");
+        assert_eq!(
+            response["result"]["balance"],
+            breez_services.node_info()?.channels_balance_msat
+        );
+
+        let response = client
+            .request(
+                "make_invoice",
+                json!({ "amount": 1_000, "description": "nwc" }),
+            )
+            .await?;
+        let invoice = parse_invoice(response["result"]["invoice"].as_str().unwrap())?;
+        assert_eq!(invoice.amount_msat, Some(1_000));
+        assert_eq!(invoice.description, Some("nwc".into()));
+
+        let response = client.request("list_transactions", json!({})).await?;
+        assert_eq!(response["error"]["code"], "RESTRICTED");
+
+        let response = client
+            .request(
+                "pay_invoice",
+                json!({ "invoice": create_invoice("nwc".into(), 90, vec![], None).bolt11 }),
+            )
+            .await?;
+        assert!(response["result"]["preimage"].is_string());
+        let budget = breez_services.list_nwc_connections().await?[0]
+            .budget
+            .clone()
+            .unwrap();
+        // The fee limit held during the payment is released
+        assert_eq!(budget.used_amount_msat, 90);
+
+        let response = client
+            .request(
+                "pay_invoice",
+                json!({ "invoice": create_invoice("nwc".into(), 90, vec![], None).bolt11 }),
+            )
+            .await?;
+        assert_eq!(response["error"]["code"], "QUOTA_EXCEEDED");
+
+        breez_services
+            .delete_nwc_connection(res.connection.client_pubkey)
+            .await?;
+        assert!(breez_services.list_nwc_connections().await?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use super::event::NostrEvent;
+use crate::error::SdkResult;
+
+/// The subset of a NIP-01 filter used by the wallet service
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NostrFilter {
+    /// If not empty, only events of these kinds match
+    pub kinds: Vec<u32>,
+    /// If not empty, only events tagging (`p` tag) one of these hex-encoded public keys match
+    pub p_tags: Vec<String>,
+    /// If set, only events created at or after this epoch time, in seconds, match
+    pub since: Option<u64>,
+}
+
+impl NostrFilter {
+    pub fn matches(&self, event: &NostrEvent) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&event.kind))
+            && (self.p_tags.is_empty()
+                || event
+                    .tag("p")
+                    .is_some_and(|p| self.p_tags.iter().any(|tag| tag == p)))
+            && !matches!(self.since, Some(since) if event.created_at < since)
+    }
+}
+
+/// A connection to a Nostr relay, provided by the app.
+///
+/// The wallet service publishes its responses and receives the client requests through this
+/// connection. Implementations are expected to handle reconnections on their own.
+#[tonic::async_trait]
+pub trait NostrRelay: Send + Sync {
+    /// The URL of the relay, as shared with the clients in the connection URI
+    fn url(&self) -> String;
+
+    /// Publishes the event to the relay
+    async fn publish(&self, event: NostrEvent) -> SdkResult<()>;
+
+    /// Subscribes to the events matching the filter. Events are delivered on the returned channel
+    /// until the receiver is dropped.
+    async fn subscribe(&self, filter: NostrFilter) -> SdkResult<mpsc::Receiver<NostrEvent>>;
+}
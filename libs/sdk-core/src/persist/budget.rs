@@ -0,0 +1,116 @@
+use rusqlite::named_params;
+
+use super::{db::SqliteStorage, error::PersistResult};
+
+impl SqliteStorage {
+    /// Records an outgoing payment against the spending budget, returning the id of the spend
+    pub(crate) fn insert_budget_spend(
+        &self,
+        amount_msat: u64,
+        destination: &str,
+        created_at: i64,
+    ) -> PersistResult<i64> {
+        let con = self.get_connection()?;
+        con.execute(
+            "INSERT INTO budget_spends (amount_msat, destination, created_at)\
+            VALUES (:amount_msat, :destination, :created_at)",
+            named_params! {
+                ":amount_msat": amount_msat,
+                ":destination": destination,
+                ":created_at": created_at,
+            },
+        )?;
+        Ok(con.last_insert_rowid())
+    }
+
+    /// Sets the payment of a spend that completes after the call that made it, so the spend is
+    /// settled on sync
+    pub(crate) fn set_budget_spend_pending_payment(
+        &self,
+        id: i64,
+        payment_hash: &str,
+    ) -> PersistResult<()> {
+        self.get_connection()?.execute(
+            "UPDATE budget_spends SET pending_payment_hash = :payment_hash WHERE id = :id",
+            named_params! {
+                ":id": id,
+                ":payment_hash": payment_hash,
+            },
+        )?;
+        Ok(())
+    }
+
+    /// The ids of the spends waiting for their payment, along with its hash
+    pub(crate) fn list_pending_budget_spends(&self) -> PersistResult<Vec<(i64, String)>> {
+        let con = self.get_connection()?;
+        let mut stmt = con.prepare(
+            "SELECT id, pending_payment_hash FROM budget_spends WHERE pending_payment_hash IS NOT NULL",
+        )?;
+        let vec = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<(i64, String)>, _>>()?;
+        Ok(vec)
+    }
+
+    /// Sets the amount of a spend to the one actually paid, once the payment completed
+    pub(crate) fn update_budget_spend(&self, id: i64, amount_msat: u64) -> PersistResult<()> {
+        self.get_connection()?.execute(
+            "UPDATE budget_spends SET amount_msat = :amount_msat, pending_payment_hash = NULL WHERE id = :id",
+            named_params! {
+                ":id": id,
+                ":amount_msat": amount_msat,
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Releases a spend whose payment failed
+    pub(crate) fn delete_budget_spend(&self, id: i64) -> PersistResult<()> {
+        self.get_connection()?
+            .execute("DELETE FROM budget_spends WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    /// The total amount spent since the given timestamp
+    pub(crate) fn get_budget_spent_since(&self, from_timestamp: i64) -> PersistResult<u64> {
+        Ok(self.get_connection()?.query_row(
+            "SELECT COALESCE(SUM(amount_msat), 0) FROM budget_spends WHERE created_at >= ?1",
+            [from_timestamp],
+            |row| row.get(0),
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::persist::db::SqliteStorage;
+    use crate::persist::error::PersistResult;
+    use crate::persist::test_utils;
+
+    #[test]
+    fn test_budget_spends() -> PersistResult<()> {
+        let storage = SqliteStorage::new(test_utils::create_test_sql_dir());
+        storage.init()?;
+        assert_eq!(storage.get_budget_spent_since(0)?, 0);
+
+        storage.insert_budget_spend(1_000, "bc1qaddress", 1_700_000_000)?;
+        let id = storage.insert_budget_spend(2_000, "02aa", 1_700_000_100)?;
+        let failed_id = storage.insert_budget_spend(4_000, "02bb", 1_700_000_200)?;
+        assert_eq!(storage.get_budget_spent_since(1_700_000_000)?, 7_000);
+        assert_eq!(storage.get_budget_spent_since(1_700_000_100)?, 6_000);
+
+        storage.set_budget_spend_pending_payment(id, &"aa".repeat(32))?;
+        assert_eq!(
+            storage.list_pending_budget_spends()?,
+            vec![(id, "aa".repeat(32))]
+        );
+
+        storage.update_budget_spend(id, 1_500)?;
+        storage.delete_budget_spend(failed_id)?;
+        assert_eq!(storage.get_budget_spent_since(0)?, 2_500);
+        assert_eq!(storage.get_budget_spent_since(1_700_000_101)?, 0);
+        // A settled spend no longer waits for its payment
+        assert!(storage.list_pending_budget_spends()?.is_empty());
+        Ok(())
+    }
+}
@@ -48,6 +48,7 @@ impl SqliteStorage {
     pub(crate) fn init(&self) -> PersistResult<()> {
         self.migrate_main_db()?;
         Self::migrate_sync_db(self.sync_db_file.clone())?;
+        self.init_payments_search_index()?;
         Ok(())
     }
 
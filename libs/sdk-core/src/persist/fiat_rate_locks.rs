@@ -0,0 +1,81 @@
+use rusqlite::{named_params, OptionalExtension, Row};
+
+use super::{db::SqliteStorage, error::PersistResult};
+use crate::LockedFiatRate;
+
+impl SqliteStorage {
+    pub(crate) fn insert_fiat_rate_lock(&self, rate: &LockedFiatRate) -> PersistResult<()> {
+        self.get_connection()?.execute(
+            "INSERT INTO fiat_rate_locks (id, currency, value, locked_at)\
+            VALUES (:id, :currency, :value, :locked_at)",
+            named_params! {
+                ":id": rate.id,
+                ":currency": rate.currency,
+                ":value": rate.value,
+                ":locked_at": rate.locked_at,
+            },
+        )?;
+        Ok(())
+    }
+
+    pub(crate) fn get_fiat_rate_lock(&self, id: &str) -> PersistResult<Option<LockedFiatRate>> {
+        Ok(self
+            .get_connection()?
+            .query_row("SELECT * FROM fiat_rate_locks WHERE id = ?1", [id], |row| {
+                self.sql_row_to_fiat_rate_lock(row)
+            })
+            .optional()?)
+    }
+
+    /// Deletes the rates locked before the given timestamp, which can no longer be used
+    pub(crate) fn delete_fiat_rate_locks_before(&self, locked_at: i64) -> PersistResult<()> {
+        self.get_connection()?.execute(
+            "DELETE FROM fiat_rate_locks WHERE locked_at < ?1",
+            [locked_at],
+        )?;
+        Ok(())
+    }
+
+    fn sql_row_to_fiat_rate_lock(&self, row: &Row) -> Result<LockedFiatRate, rusqlite::Error> {
+        Ok(LockedFiatRate {
+            id: row.get("id")?,
+            currency: row.get("currency")?,
+            value: row.get("value")?,
+            locked_at: row.get("locked_at")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::persist::db::SqliteStorage;
+    use crate::persist::error::PersistResult;
+    use crate::persist::test_utils;
+    use crate::LockedFiatRate;
+
+    #[test]
+    fn test_fiat_rate_locks() -> PersistResult<()> {
+        let storage = SqliteStorage::new(test_utils::create_test_sql_dir());
+        storage.init()?;
+
+        let rate = LockedFiatRate {
+            id: "aa".repeat(16),
+            currency: "USD".into(),
+            value: "51000.5".into(),
+            locked_at: 1_700_000_000,
+        };
+        storage.insert_fiat_rate_lock(&rate)?;
+        storage.insert_fiat_rate_lock(&LockedFiatRate {
+            id: "bb".repeat(16),
+            locked_at: 1_700_000_600,
+            ..rate.clone()
+        })?;
+        assert_eq!(storage.get_fiat_rate_lock(&rate.id)?, Some(rate.clone()));
+        assert_eq!(storage.get_fiat_rate_lock(&"cc".repeat(16))?, None);
+
+        storage.delete_fiat_rate_locks_before(1_700_000_600)?;
+        assert_eq!(storage.get_fiat_rate_lock(&rate.id)?, None);
+        assert!(storage.get_fiat_rate_lock(&"bb".repeat(16))?.is_some());
+        Ok(())
+    }
+}
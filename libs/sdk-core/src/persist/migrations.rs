@@ -434,7 +434,82 @@ pub(crate) fn current_migrations() -> Vec<&'static str> {
        ",
        "DELETE FROM cached_items WHERE key = 'gl_credentials'",
        "DELETE FROM cached_items WHERE key = 'last_sync_time'",
-       "DELETE FROM cached_items WHERE key = 'node_state'"
+       "DELETE FROM cached_items WHERE key = 'node_state'",
+       "
+       CREATE TABLE IF NOT EXISTS nwc_connections (
+        client_pubkey TEXT NOT NULL PRIMARY KEY,
+        name TEXT NOT NULL,
+        permissions TEXT NOT NULL,
+        max_amount_msat INTEGER,
+        renewal_period_sec INTEGER,
+        used_amount_msat INTEGER NOT NULL DEFAULT 0,
+        period_start INTEGER NOT NULL,
+        created_at INTEGER NOT NULL
+       ) STRICT;
+
+       CREATE TABLE IF NOT EXISTS nwc_requests (
+        event_id TEXT NOT NULL PRIMARY KEY,
+        created_at INTEGER NOT NULL
+       ) STRICT;
+       ",
+       "
+       CREATE TABLE IF NOT EXISTS redeem_txs (
+        txid TEXT NOT NULL PRIMARY KEY,
+        to_address TEXT NOT NULL,
+        created_at INTEGER NOT NULL
+       ) STRICT;
+
+       CREATE TABLE IF NOT EXISTS tx_replacements (
+        txid TEXT NOT NULL PRIMARY KEY,
+        replaced_txid TEXT NOT NULL UNIQUE,
+        sat_per_vbyte INTEGER NOT NULL,
+        created_at INTEGER NOT NULL
+       ) STRICT;
+       ",
+       // Full-text index of payments, which is populated on init as it includes data from the sync DB
+       "
+       CREATE VIRTUAL TABLE IF NOT EXISTS payments_search USING fts5(
+        payment_id UNINDEXED,
+        description,
+        label,
+        lnurl_metadata,
+        ln_address,
+        note,
+        tags,
+        category
+       );
+       ",
+       "
+       CREATE TABLE IF NOT EXISTS payment_requests (
+        payment_hash TEXT NOT NULL PRIMARY KEY,
+        bolt11 TEXT NOT NULL,
+        description TEXT NOT NULL,
+        amount_msat INTEGER NOT NULL,
+        opening_fee_params TEXT,
+        opening_fee_msat INTEGER,
+        status TEXT NOT NULL,
+        created_at INTEGER NOT NULL,
+        expires_at INTEGER NOT NULL,
+        paid_at INTEGER
+       ) STRICT;
+       ",
+       "
+       CREATE TABLE IF NOT EXISTS budget_spends (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        amount_msat INTEGER NOT NULL,
+        destination TEXT NOT NULL,
+        created_at INTEGER NOT NULL,
+        pending_payment_hash TEXT
+       ) STRICT;
+       ",
+       "
+       CREATE TABLE IF NOT EXISTS fiat_rate_locks (
+        id TEXT NOT NULL PRIMARY KEY,
+        currency TEXT NOT NULL,
+        value TEXT NOT NULL,
+        locked_at INTEGER NOT NULL
+       ) STRICT;
+       ",
     ]
 }
 
@@ -620,5 +695,52 @@ pub(crate) fn current_sync_migrations() -> Vec<&'static str> {
         UPDATE swaps SET max_swapper_payable = max_allowed_deposit;
         ",
         "ALTER TABLE payments_external_info ADD COLUMN lnurl_pay_comment TEXT;",
+        "
+        ALTER TABLE payments_external_info ADD COLUMN bolt12_offer TEXT;
+        ALTER TABLE payments_external_info ADD COLUMN bolt12_payer_note TEXT;
+        ",
+        "ALTER TABLE payments_external_info ADD COLUMN lnurl_verify_url TEXT;",
+        // Swaps are P2WSH (0) unless created as Taproot (1)
+        "ALTER TABLE swaps ADD COLUMN script_version INTEGER NOT NULL DEFAULT 0;",
+        // Notes, tags (a JSON array) and a category attached to a payment by the user
+        "
+        CREATE TABLE IF NOT EXISTS payments_annotations (
+         payment_id TEXT NOT NULL PRIMARY KEY,
+         note TEXT,
+         tags TEXT NOT NULL DEFAULT '[]',
+         category TEXT,
+         updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        ) STRICT;
+
+        CREATE TRIGGER IF NOT EXISTS sync_payments_annotations
+         AFTER INSERT ON payments_annotations
+        BEGIN
+         INSERT INTO sync_requests(changed_table) VALUES('payments_annotations');
+        END;
+        ",
+        // The fiat amount a payment was requested or sent for, and the rate it was converted at.
+        // Amounts and rates are decimal strings, to keep their exact value.
+        "
+        CREATE TABLE IF NOT EXISTS payments_fiat_amounts (
+         payment_id TEXT NOT NULL PRIMARY KEY,
+         amount TEXT NOT NULL,
+         currency TEXT NOT NULL,
+         rate TEXT NOT NULL,
+         rate_locked_at INTEGER NOT NULL,
+         rate_lock_id TEXT NOT NULL
+        ) STRICT;
+
+        CREATE TRIGGER IF NOT EXISTS sync_payments_fiat_amounts
+         AFTER INSERT ON payments_fiat_amounts
+        BEGIN
+         INSERT INTO sync_requests(changed_table) VALUES('payments_fiat_amounts');
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS sync_payments_fiat_amounts_update
+         AFTER UPDATE ON payments_fiat_amounts
+        BEGIN
+         INSERT INTO sync_requests(changed_table) VALUES('payments_fiat_amounts');
+        END;
+        ",
 	]
 }
@@ -1,8 +1,13 @@
+pub(crate) mod budget;
 pub(crate) mod cache;
 pub(crate) mod channels;
 pub(crate) mod db;
 pub(crate) mod error;
+pub(crate) mod fiat_rate_locks;
 pub(crate) mod migrations;
+pub(crate) mod nwc;
+pub(crate) mod payment_requests;
+pub(crate) mod replacements;
 pub(crate) mod reverseswap;
 pub(crate) mod settings;
 pub(crate) mod swap;
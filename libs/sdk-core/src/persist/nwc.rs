@@ -0,0 +1,209 @@
+use std::str::FromStr;
+
+use rusqlite::{named_params, OptionalExtension, Row};
+
+use super::{db::SqliteStorage, error::PersistResult};
+use crate::nwc::{NwcBudget, NwcConnection, NwcMethod};
+
+const KEY_NWC_SERVICE_KEY: &str = "nwc_service_key";
+
+impl SqliteStorage {
+    pub(crate) fn set_nwc_service_key(&self, secret_key: Vec<u8>) -> PersistResult<()> {
+        self.update_cached_item(KEY_NWC_SERVICE_KEY, hex::encode(secret_key))
+    }
+
+    pub(crate) fn get_nwc_service_key(&self) -> PersistResult<Option<Vec<u8>>> {
+        match self.get_cached_item(KEY_NWC_SERVICE_KEY)? {
+            Some(str) => Ok(Some(hex::decode(str)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn insert_nwc_connection(&self, connection: &NwcConnection) -> PersistResult<()> {
+        self.get_connection()?.execute(
+            "INSERT INTO nwc_connections (client_pubkey, name, permissions, max_amount_msat, renewal_period_sec, used_amount_msat, period_start, created_at)\
+            VALUES (:client_pubkey, :name, :permissions, :max_amount_msat, :renewal_period_sec, :used_amount_msat, :period_start, :created_at)",
+            named_params! {
+                ":client_pubkey": connection.client_pubkey,
+                ":name": connection.name,
+                ":permissions": connection
+                    .permissions
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<String>>()
+                    .join(","),
+                ":max_amount_msat": connection.budget.as_ref().map(|b| b.max_amount_msat),
+                ":renewal_period_sec": connection.budget.as_ref().and_then(|b| b.renewal_period_sec),
+                ":used_amount_msat": connection.budget.as_ref().map_or(0, |b| b.used_amount_msat),
+                ":period_start": connection.budget.as_ref().map_or(connection.created_at, |b| b.period_start),
+                ":created_at": connection.created_at,
+            },
+        )?;
+        Ok(())
+    }
+
+    pub(crate) fn update_nwc_budget(
+        &self,
+        client_pubkey: &str,
+        budget: &NwcBudget,
+    ) -> PersistResult<()> {
+        self.get_connection()?.execute(
+            "UPDATE nwc_connections SET used_amount_msat = :used_amount_msat, period_start = :period_start WHERE client_pubkey = :client_pubkey",
+            named_params! {
+                ":client_pubkey": client_pubkey,
+                ":used_amount_msat": budget.used_amount_msat,
+                ":period_start": budget.period_start,
+            },
+        )?;
+        Ok(())
+    }
+
+    pub(crate) fn delete_nwc_connection(&self, client_pubkey: &str) -> PersistResult<()> {
+        self.get_connection()?.execute(
+            "DELETE FROM nwc_connections WHERE client_pubkey = ?1",
+            [client_pubkey],
+        )?;
+        Ok(())
+    }
+
+    /// Records a handled request and forgets the ones created before `prune_before`. Returns
+    /// false if the request was already recorded.
+    pub(crate) fn insert_nwc_request(
+        &self,
+        event_id: &str,
+        created_at: i64,
+        prune_before: i64,
+    ) -> PersistResult<bool> {
+        let con = self.get_connection()?;
+        con.execute(
+            "DELETE FROM nwc_requests WHERE created_at < ?1",
+            [prune_before],
+        )?;
+        let inserted = con.execute(
+            "INSERT OR IGNORE INTO nwc_requests (event_id, created_at) VALUES (:event_id, :created_at)",
+            named_params! {
+                ":event_id": event_id,
+                ":created_at": created_at,
+            },
+        )?;
+        Ok(inserted == 1)
+    }
+
+    pub(crate) fn get_nwc_connection(
+        &self,
+        client_pubkey: &str,
+    ) -> PersistResult<Option<NwcConnection>> {
+        Ok(self
+            .get_connection()?
+            .query_row(
+                "SELECT * FROM nwc_connections WHERE client_pubkey = ?1",
+                [client_pubkey],
+                |row| self.sql_row_to_nwc_connection(row),
+            )
+            .optional()?)
+    }
+
+    pub(crate) fn list_nwc_connections(&self) -> PersistResult<Vec<NwcConnection>> {
+        let con = self.get_connection()?;
+        let mut stmt = con.prepare("SELECT * FROM nwc_connections ORDER BY created_at")?;
+        let vec = stmt
+            .query_map([], |row| self.sql_row_to_nwc_connection(row))?
+            .collect::<Result<Vec<NwcConnection>, _>>()?;
+        Ok(vec)
+    }
+
+    fn sql_row_to_nwc_connection(&self, row: &Row) -> Result<NwcConnection, rusqlite::Error> {
+        let permissions: String = row.get("permissions")?;
+        let max_amount_msat: Option<u64> = row.get("max_amount_msat")?;
+        Ok(NwcConnection {
+            client_pubkey: row.get("client_pubkey")?,
+            name: row.get("name")?,
+            permissions: permissions
+                .split(',')
+                .filter_map(|method| NwcMethod::from_str(method).ok())
+                .collect(),
+            budget: match max_amount_msat {
+                Some(max_amount_msat) => Some(NwcBudget {
+                    max_amount_msat,
+                    renewal_period_sec: row.get("renewal_period_sec")?,
+                    used_amount_msat: row.get("used_amount_msat")?,
+                    period_start: row.get("period_start")?,
+                }),
+                None => None,
+            },
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nwc::{NwcBudget, NwcConnection, NwcMethod};
+    use crate::persist::db::SqliteStorage;
+    use crate::persist::error::PersistResult;
+    use crate::persist::test_utils;
+
+    #[test]
+    fn test_nwc_connections() -> PersistResult<()> {
+        let storage = SqliteStorage::new(test_utils::create_test_sql_dir());
+        storage.init()?;
+
+        let connection = NwcConnection {
+            client_pubkey: "aa".repeat(32),
+            name: "test client".into(),
+            permissions: vec![NwcMethod::PayInvoice, NwcMethod::GetBalance],
+            budget: Some(NwcBudget {
+                max_amount_msat: 10_000,
+                renewal_period_sec: Some(3600),
+                used_amount_msat: 0,
+                period_start: 1_700_000_000,
+            }),
+            created_at: 1_700_000_000,
+        };
+        storage.insert_nwc_connection(&connection)?;
+        storage.insert_nwc_connection(&NwcConnection {
+            client_pubkey: "bb".repeat(32),
+            name: "unlimited client".into(),
+            permissions: vec![NwcMethod::ListTransactions],
+            budget: None,
+            created_at: 1_700_000_001,
+        })?;
+        assert_eq!(
+            storage.get_nwc_connection(&connection.client_pubkey)?,
+            Some(connection.clone())
+        );
+        assert_eq!(storage.list_nwc_connections()?.len(), 2);
+
+        let budget = NwcBudget {
+            used_amount_msat: 5_000,
+            ..connection.budget.clone().unwrap()
+        };
+        storage.update_nwc_budget(&connection.client_pubkey, &budget)?;
+        assert_eq!(
+            storage
+                .get_nwc_connection(&connection.client_pubkey)?
+                .unwrap()
+                .budget,
+            Some(budget)
+        );
+
+        storage.delete_nwc_connection(&connection.client_pubkey)?;
+        assert_eq!(storage.get_nwc_connection(&connection.client_pubkey)?, None);
+        assert_eq!(storage.list_nwc_connections()?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_nwc_requests() -> PersistResult<()> {
+        let storage = SqliteStorage::new(test_utils::create_test_sql_dir());
+        storage.init()?;
+
+        assert!(storage.insert_nwc_request("aa", 1_000, 0)?);
+        assert!(storage.insert_nwc_request("bb", 1_100, 0)?);
+        assert!(!storage.insert_nwc_request("aa", 1_000, 0)?);
+        // Once pruned, a request is recorded again
+        assert!(storage.insert_nwc_request("aa", 1_000, 1_050)?);
+        assert!(!storage.insert_nwc_request("bb", 1_100, 1_050)?);
+        Ok(())
+    }
+}
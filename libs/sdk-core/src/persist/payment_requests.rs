@@ -0,0 +1,248 @@
+use std::str::FromStr;
+
+use rusqlite::{named_params, OptionalExtension, Row};
+
+use super::{db::SqliteStorage, error::PersistResult};
+use crate::{ListPaymentRequestsRequest, PaymentRequest, PaymentRequestStatus};
+
+impl SqliteStorage {
+    pub(crate) fn insert_payment_request(&self, request: &PaymentRequest) -> PersistResult<()> {
+        self.get_connection()?.execute(
+            "INSERT OR REPLACE INTO payment_requests (payment_hash, bolt11, description, amount_msat, opening_fee_params, opening_fee_msat, status, created_at, expires_at, paid_at)\
+            VALUES (:payment_hash, :bolt11, :description, :amount_msat, :opening_fee_params, :opening_fee_msat, :status, :created_at, :expires_at, :paid_at)",
+            named_params! {
+                ":payment_hash": request.payment_hash,
+                ":bolt11": request.bolt11,
+                ":description": request.description,
+                ":amount_msat": request.amount_msat,
+                ":opening_fee_params": request.opening_fee_params,
+                ":opening_fee_msat": request.opening_fee_msat,
+                ":status": request.status.to_string(),
+                ":created_at": request.created_at,
+                ":expires_at": request.expires_at,
+                ":paid_at": request.paid_at,
+            },
+        )?;
+        Ok(())
+    }
+
+    pub(crate) fn update_payment_request_status(
+        &self,
+        payment_hash: &str,
+        status: PaymentRequestStatus,
+        paid_at: Option<i64>,
+    ) -> PersistResult<()> {
+        self.get_connection()?.execute(
+            "UPDATE payment_requests SET status = :status, paid_at = :paid_at WHERE payment_hash = :payment_hash",
+            named_params! {
+                ":payment_hash": payment_hash,
+                ":status": status.to_string(),
+                ":paid_at": paid_at,
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Marks an open payment request as expired, returning false if it was no longer open
+    pub(crate) fn expire_payment_request(&self, payment_hash: &str) -> PersistResult<bool> {
+        let updated = self.get_connection()?.execute(
+            "UPDATE payment_requests SET status = :status WHERE payment_hash = :payment_hash AND status = :open",
+            named_params! {
+                ":payment_hash": payment_hash,
+                ":status": PaymentRequestStatus::Expired.to_string(),
+                ":open": PaymentRequestStatus::Open.to_string(),
+            },
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Lists the payment requests that can still be paid: the open ones and the cancelled or
+    /// expired ones that expire after `expired_since`
+    pub(crate) fn list_unsettled_payment_requests(
+        &self,
+        expired_since: i64,
+    ) -> PersistResult<Vec<PaymentRequest>> {
+        let con = self.get_connection()?;
+        let mut stmt = con.prepare(
+            "SELECT * FROM payment_requests \
+            WHERE status = :open OR (status IN (:cancelled, :expired) AND expires_at > :expired_since)",
+        )?;
+        let vec = stmt
+            .query_map(
+                named_params! {
+                    ":open": PaymentRequestStatus::Open.to_string(),
+                    ":cancelled": PaymentRequestStatus::Cancelled.to_string(),
+                    ":expired": PaymentRequestStatus::Expired.to_string(),
+                    ":expired_since": expired_since,
+                },
+                |row| self.sql_row_to_payment_request(row),
+            )?
+            .collect::<Result<Vec<PaymentRequest>, _>>()?;
+        Ok(vec)
+    }
+
+    /// The time the next open payment request expires at, if any
+    pub(crate) fn get_next_payment_request_expiry(&self) -> PersistResult<Option<i64>> {
+        Ok(self.get_connection()?.query_row(
+            "SELECT MIN(expires_at) FROM payment_requests WHERE status = ?1",
+            [PaymentRequestStatus::Open.to_string()],
+            |row| row.get(0),
+        )?)
+    }
+
+    pub(crate) fn get_payment_request(
+        &self,
+        payment_hash: &str,
+    ) -> PersistResult<Option<PaymentRequest>> {
+        Ok(self
+            .get_connection()?
+            .query_row(
+                "SELECT * FROM payment_requests WHERE payment_hash = ?1",
+                [payment_hash],
+                |row| self.sql_row_to_payment_request(row),
+            )
+            .optional()?)
+    }
+
+    pub(crate) fn list_payment_requests(
+        &self,
+        req: &ListPaymentRequestsRequest,
+    ) -> PersistResult<Vec<PaymentRequest>> {
+        let mut where_clause = vec!["true".to_string()];
+        if let Some(statuses) = &req.statuses {
+            where_clause.push(format!(
+                "status IN ({})",
+                statuses
+                    .iter()
+                    .map(|s| format!("'{s}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        if let Some(t) = req.from_timestamp {
+            where_clause.push(format!("created_at >= {t}"));
+        }
+        if let Some(t) = req.to_timestamp {
+            where_clause.push(format!("created_at <= {t}"));
+        }
+
+        let con = self.get_connection()?;
+        let mut stmt = con.prepare(&format!(
+            "SELECT * FROM payment_requests WHERE {} ORDER BY created_at DESC LIMIT {} OFFSET {}",
+            where_clause.join(" AND "),
+            req.limit.unwrap_or(u32::MAX),
+            req.offset.unwrap_or(0),
+        ))?;
+        let vec = stmt
+            .query_map([], |row| self.sql_row_to_payment_request(row))?
+            .collect::<Result<Vec<PaymentRequest>, _>>()?;
+        Ok(vec)
+    }
+
+    fn sql_row_to_payment_request(&self, row: &Row) -> Result<PaymentRequest, rusqlite::Error> {
+        let status: String = row.get("status")?;
+        Ok(PaymentRequest {
+            payment_hash: row.get("payment_hash")?,
+            bolt11: row.get("bolt11")?,
+            description: row.get("description")?,
+            amount_msat: row.get("amount_msat")?,
+            opening_fee_params: row.get("opening_fee_params")?,
+            opening_fee_msat: row.get("opening_fee_msat")?,
+            status: PaymentRequestStatus::from_str(&status)
+                .unwrap_or(PaymentRequestStatus::Expired),
+            created_at: row.get("created_at")?,
+            expires_at: row.get("expires_at")?,
+            paid_at: row.get("paid_at")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::persist::db::SqliteStorage;
+    use crate::persist::error::PersistResult;
+    use crate::persist::test_utils;
+    use crate::test_utils::get_test_ofp;
+    use crate::{ListPaymentRequestsRequest, PaymentRequest, PaymentRequestStatus};
+
+    #[test]
+    fn test_payment_requests() -> PersistResult<()> {
+        let storage = SqliteStorage::new(test_utils::create_test_sql_dir());
+        storage.init()?;
+
+        let request = PaymentRequest {
+            payment_hash: "aa".repeat(32),
+            bolt11: "lnbc1".into(),
+            description: "order #1".into(),
+            amount_msat: 10_000,
+            opening_fee_params: Some(get_test_ofp(1_000, 10, true).into()),
+            opening_fee_msat: Some(1_000),
+            status: PaymentRequestStatus::Open,
+            created_at: 1_700_000_000,
+            expires_at: 1_700_003_600,
+            paid_at: None,
+        };
+        storage.insert_payment_request(&request)?;
+        storage.insert_payment_request(&PaymentRequest {
+            payment_hash: "bb".repeat(32),
+            description: "order #2".into(),
+            opening_fee_params: None,
+            opening_fee_msat: None,
+            created_at: 1_700_000_100,
+            ..request.clone()
+        })?;
+        assert_eq!(
+            storage.get_payment_request(&request.payment_hash)?,
+            Some(request.clone())
+        );
+        assert_eq!(storage.get_payment_request(&"cc".repeat(32))?, None);
+
+        storage.update_payment_request_status(
+            &request.payment_hash,
+            PaymentRequestStatus::Paid,
+            Some(1_700_000_200),
+        )?;
+        let requests = storage.list_payment_requests(&ListPaymentRequestsRequest::default())?;
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].payment_hash, "bb".repeat(32));
+        assert_eq!(requests[1].status, PaymentRequestStatus::Paid);
+        assert_eq!(requests[1].paid_at, Some(1_700_000_200));
+
+        let open = storage.list_payment_requests(&ListPaymentRequestsRequest {
+            statuses: Some(vec![
+                PaymentRequestStatus::Open,
+                PaymentRequestStatus::Expired,
+            ]),
+            ..Default::default()
+        })?;
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].description, "order #2");
+        let paged = storage.list_payment_requests(&ListPaymentRequestsRequest {
+            from_timestamp: Some(1_700_000_000),
+            to_timestamp: Some(1_700_000_050),
+            limit: Some(1),
+            ..Default::default()
+        })?;
+        assert_eq!(paged.len(), 1);
+        assert_eq!(paged[0].payment_hash, request.payment_hash);
+
+        assert_eq!(
+            storage.get_next_payment_request_expiry()?,
+            Some(1_700_003_600)
+        );
+        assert!(storage.expire_payment_request(&"bb".repeat(32))?);
+        // Only open requests expire, and only once
+        assert!(!storage.expire_payment_request(&"bb".repeat(32))?);
+        assert!(!storage.expire_payment_request(&request.payment_hash)?);
+        assert_eq!(storage.get_next_payment_request_expiry()?, None);
+
+        // Expired requests are reconciled until the window passes, paid ones never
+        let unsettled = storage.list_unsettled_payment_requests(1_700_003_000)?;
+        assert_eq!(unsettled.len(), 1);
+        assert_eq!(unsettled[0].status, PaymentRequestStatus::Expired);
+        assert!(storage
+            .list_unsettled_payment_requests(1_700_003_600)?
+            .is_empty());
+        Ok(())
+    }
+}
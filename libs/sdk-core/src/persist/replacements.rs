@@ -0,0 +1,147 @@
+use chrono::Utc;
+use rusqlite::{named_params, OptionalExtension};
+
+use super::{db::SqliteStorage, error::PersistResult};
+
+/// A transaction broadcast to replace an unconfirmed one with a higher fee (RBF)
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TxReplacement {
+    pub(crate) txid: String,
+    pub(crate) replaced_txid: String,
+    pub(crate) sat_per_vbyte: u32,
+    pub(crate) created_at: i64,
+}
+
+impl TxReplacement {
+    pub(crate) fn new(txid: String, replaced_txid: &str, sat_per_vbyte: u32) -> Self {
+        Self {
+            txid,
+            replaced_txid: replaced_txid.to_string(),
+            sat_per_vbyte,
+            created_at: Utc::now().timestamp(),
+        }
+    }
+}
+
+impl SqliteStorage {
+    pub(crate) fn insert_tx_replacement(&self, replacement: &TxReplacement) -> PersistResult<()> {
+        self.get_connection()?.execute(
+            "INSERT INTO tx_replacements (txid, replaced_txid, sat_per_vbyte, created_at)\
+            VALUES (:txid, :replaced_txid, :sat_per_vbyte, :created_at)",
+            named_params! {
+                ":txid": replacement.txid,
+                ":replaced_txid": replacement.replaced_txid,
+                ":sat_per_vbyte": replacement.sat_per_vbyte,
+                ":created_at": replacement.created_at,
+            },
+        )?;
+        Ok(())
+    }
+
+    /// The replacement record of a transaction, if it replaced another one
+    pub(crate) fn get_tx_replacement(&self, txid: &str) -> PersistResult<Option<TxReplacement>> {
+        Ok(self
+            .get_connection()?
+            .query_row(
+                "SELECT txid, replaced_txid, sat_per_vbyte, created_at FROM tx_replacements WHERE txid = ?1",
+                [txid],
+                |row| {
+                    Ok(TxReplacement {
+                        txid: row.get(0)?,
+                        replaced_txid: row.get(1)?,
+                        sat_per_vbyte: row.get(2)?,
+                        created_at: row.get(3)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// The txid of the transaction that replaced the given one, if any
+    pub(crate) fn get_replacing_txid(&self, replaced_txid: &str) -> PersistResult<Option<String>> {
+        Ok(self
+            .get_connection()?
+            .query_row(
+                "SELECT txid FROM tx_replacements WHERE replaced_txid = ?1",
+                [replaced_txid],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub(crate) fn insert_redeem_tx(
+        &self,
+        txid: &str,
+        to_address: &str,
+        created_at: i64,
+    ) -> PersistResult<()> {
+        self.get_connection()?.execute(
+            "INSERT OR REPLACE INTO redeem_txs (txid, to_address, created_at) VALUES (?1, ?2, ?3)",
+            (txid, to_address, created_at),
+        )?;
+        Ok(())
+    }
+
+    /// The destination address of a transaction redeeming the onchain funds of the node
+    pub(crate) fn get_redeem_tx_address(&self, txid: &str) -> PersistResult<Option<String>> {
+        Ok(self
+            .get_connection()?
+            .query_row(
+                "SELECT to_address FROM redeem_txs WHERE txid = ?1",
+                [txid],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::persist::db::SqliteStorage;
+    use crate::persist::error::PersistResult;
+    use crate::persist::replacements::TxReplacement;
+    use crate::persist::test_utils;
+
+    #[test]
+    fn test_tx_replacements() -> PersistResult<()> {
+        let storage = SqliteStorage::new(test_utils::create_test_sql_dir());
+        storage.init()?;
+
+        let first_replacement = TxReplacement {
+            txid: "bb".repeat(32),
+            replaced_txid: "aa".repeat(32),
+            sat_per_vbyte: 10,
+            created_at: 1_700_000_000,
+        };
+        let second_replacement = TxReplacement {
+            txid: "cc".repeat(32),
+            replaced_txid: "bb".repeat(32),
+            sat_per_vbyte: 20,
+            created_at: 1_700_000_100,
+        };
+        storage.insert_tx_replacement(&first_replacement)?;
+        storage.insert_tx_replacement(&second_replacement)?;
+
+        assert_eq!(
+            storage.get_tx_replacement(&"cc".repeat(32))?,
+            Some(second_replacement)
+        );
+        assert_eq!(storage.get_tx_replacement(&"aa".repeat(32))?, None);
+        assert_eq!(
+            storage.get_replacing_txid(&"aa".repeat(32))?,
+            Some("bb".repeat(32))
+        );
+        assert_eq!(storage.get_replacing_txid(&"cc".repeat(32))?, None);
+
+        // A transaction can only be replaced once
+        assert!(storage.insert_tx_replacement(&first_replacement).is_err());
+
+        storage.insert_redeem_tx(&"dd".repeat(32), "bc1qaddress", 1_700_000_000)?;
+        assert_eq!(
+            storage.get_redeem_tx_address(&"dd".repeat(32))?,
+            Some("bc1qaddress".to_string())
+        );
+        assert_eq!(storage.get_redeem_tx_address(&"ee".repeat(32))?, None);
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use serde::Serialize;
 
 use super::{db::SqliteStorage, error::PersistResult};
+use crate::models::{PendingSpendingBudget, SpendingBudget, SweepPolicy};
 
 #[allow(dead_code)]
 #[derive(Serialize)]
@@ -58,6 +59,67 @@ impl SqliteStorage {
     pub fn get_lsp_id(&self) -> PersistResult<Option<String>> {
         self.get_setting("lsp".to_string())
     }
+
+    pub fn set_sweep_policy(&self, policy: &SweepPolicy) -> PersistResult<()> {
+        self.update_setting("sweep_policy".to_string(), serde_json::to_string(policy)?)
+    }
+
+    pub fn get_sweep_policy(&self) -> PersistResult<Option<SweepPolicy>> {
+        Ok(match self.get_setting("sweep_policy".to_string())? {
+            Some(policy) => Some(serde_json::from_str(&policy)?),
+            None => None,
+        })
+    }
+
+    /// Records the redeem tx of the last onchain sweep, until it confirms
+    pub fn set_onchain_sweep_txid(&self, txid: &str) -> PersistResult<()> {
+        self.update_setting("onchain_sweep_txid".to_string(), txid.to_string())
+    }
+
+    pub fn get_onchain_sweep_txid(&self) -> PersistResult<Option<String>> {
+        self.get_setting("onchain_sweep_txid".to_string())
+    }
+
+    pub fn delete_onchain_sweep_txid(&self) -> PersistResult<()> {
+        self.delete_setting("onchain_sweep_txid".to_string())
+    }
+
+    pub fn set_spending_budget(&self, budget: &SpendingBudget) -> PersistResult<()> {
+        self.update_setting(
+            "spending_budget".to_string(),
+            serde_json::to_string(budget)?,
+        )
+    }
+
+    pub fn get_spending_budget(&self) -> PersistResult<Option<SpendingBudget>> {
+        Ok(match self.get_setting("spending_budget".to_string())? {
+            Some(budget) => Some(serde_json::from_str(&budget)?),
+            None => None,
+        })
+    }
+
+    pub fn set_pending_spending_budget(
+        &self,
+        pending: &PendingSpendingBudget,
+    ) -> PersistResult<()> {
+        self.update_setting(
+            "pending_spending_budget".to_string(),
+            serde_json::to_string(pending)?,
+        )
+    }
+
+    pub fn get_pending_spending_budget(&self) -> PersistResult<Option<PendingSpendingBudget>> {
+        Ok(
+            match self.get_setting("pending_spending_budget".to_string())? {
+                Some(pending) => Some(serde_json::from_str(&pending)?),
+                None => None,
+            },
+        )
+    }
+
+    pub fn delete_pending_spending_budget(&self) -> PersistResult<()> {
+        self.delete_setting("pending_spending_budget".to_string())
+    }
 }
 
 #[test]
@@ -89,3 +151,33 @@ fn test_settings() {
     assert_eq!(settings[1].key, "key2");
     assert_eq!(settings[1].value, "val3");
 }
+
+#[test]
+fn test_sweep_policy() {
+    use crate::models::OnchainSweepPolicy;
+    use crate::persist::test_utils;
+
+    let storage = SqliteStorage::new(test_utils::create_test_sql_dir());
+    storage.init().unwrap();
+    assert_eq!(storage.get_sweep_policy().unwrap(), None);
+
+    let policy = SweepPolicy {
+        onchain: Some(OnchainSweepPolicy {
+            to_address: "bc1q7r32q47suczprtpawgvw9xlefzd9nhccyatxvu".to_string(),
+            max_onchain_balance_sat: 100_000,
+            max_sat_per_vbyte: 10,
+        }),
+        lightning: None,
+    };
+    storage.set_sweep_policy(&policy).unwrap();
+    assert_eq!(storage.get_sweep_policy().unwrap(), Some(policy));
+
+    assert_eq!(storage.get_onchain_sweep_txid().unwrap(), None);
+    storage.set_onchain_sweep_txid("txid").unwrap();
+    assert_eq!(
+        storage.get_onchain_sweep_txid().unwrap(),
+        Some("txid".to_string())
+    );
+    storage.delete_onchain_sweep_txid().unwrap();
+    assert_eq!(storage.get_onchain_sweep_txid().unwrap(), None);
+}
@@ -1,6 +1,6 @@
 use rusqlite::{named_params, OptionalExtension, Params, Row, Transaction, TransactionBehavior};
 
-use crate::models::{OpeningFeeParams, SwapInfo, SwapStatus};
+use crate::models::{OpeningFeeParams, SwapInfo, SwapScriptVersion, SwapStatus};
 
 use super::{
     db::{SqliteStorage, StringArray},
@@ -36,9 +36,10 @@ impl SqliteStorage {
            script,
            min_allowed_deposit, 
            max_allowed_deposit,
-           max_swapper_payable
+           max_swapper_payable,
+           script_version
          )
-         VALUES (:bitcoin_address, :created_at, :lock_height, :payment_hash, :preimage, :private_key, :public_key, :swapper_public_key, :script, :min_allowed_deposit, :max_allowed_deposit, :max_swapper_payable)",
+         VALUES (:bitcoin_address, :created_at, :lock_height, :payment_hash, :preimage, :private_key, :public_key, :swapper_public_key, :script, :min_allowed_deposit, :max_allowed_deposit, :max_swapper_payable, :script_version)",
          named_params! {
              ":bitcoin_address": swap_info.bitcoin_address,
              ":created_at": swap_info.created_at,
@@ -52,6 +53,7 @@ impl SqliteStorage {
              ":min_allowed_deposit": swap_info.min_allowed_deposit,
              ":max_allowed_deposit": swap_info.max_allowed_deposit,
              ":max_swapper_payable": swap_info.max_swapper_payable,
+             ":script_version": swap_info.script_version as i32,
          },
         )?;
 
@@ -240,6 +242,7 @@ impl SqliteStorage {
           public_key as {prefix}public_key,
           swapper_public_key as {prefix}swapper_public_key,
           script as {prefix}script,
+          script_version as {prefix}script_version,
           min_allowed_deposit as {prefix}min_allowed_deposit,
           max_allowed_deposit as {prefix}max_allowed_deposit,
           max_swapper_payable as {prefix}max_swapper_payable,
@@ -283,6 +286,7 @@ impl SqliteStorage {
           {prefix}public_key,
           {prefix}swapper_public_key,
           {prefix}script,
+          {prefix}script_version,
           {prefix}min_allowed_deposit,
           {prefix}max_allowed_deposit,
           {prefix}max_swapper_payable,
@@ -378,6 +382,11 @@ impl SqliteStorage {
         let confirmed_txs_raw: StringArray = row
             .get::<&str, Option<StringArray>>(format!("{prefix}confirmed_tx_ids").as_str())?
             .unwrap_or(StringArray(vec![]));
+        let script_version: SwapScriptVersion = row
+            .get::<&str, Option<i32>>(format!("{prefix}script_version").as_str())?
+            .unwrap_or_default()
+            .try_into()
+            .unwrap_or_default();
         let bitcoin_address = row.get(format!("{prefix}bitcoin_address").as_str())?;
         Ok(SwapInfo {
             bitcoin_address,
@@ -389,6 +398,7 @@ impl SqliteStorage {
             public_key: row.get(format!("{prefix}public_key").as_str())?,
             swapper_public_key: row.get(format!("{prefix}swapper_public_key").as_str())?,
             script: row.get(format!("{prefix}script").as_str())?,
+            script_version,
             bolt11: row.get(format!("{prefix}bolt11").as_str())?,
             paid_msat: row
                 .get::<&str, Option<u64>>(format!("{prefix}paid_msat").as_str())?
@@ -422,7 +432,7 @@ mod tests {
     use crate::persist::error::PersistResult;
     use crate::persist::swap::SwapChainInfo;
     use crate::test_utils::get_test_ofp_48h;
-    use crate::{OpeningFeeParams, SwapInfo, SwapStatus};
+    use crate::{OpeningFeeParams, SwapInfo, SwapScriptVersion, SwapStatus};
     use rusqlite::{named_params, Connection};
 
     #[test]
@@ -449,6 +459,7 @@ mod tests {
             public_key: vec![4],
             swapper_public_key: vec![5],
             script: vec![5],
+            script_version: SwapScriptVersion::P2wsh,
             bolt11: None,
             paid_msat: 0,
             unconfirmed_sats: 0,
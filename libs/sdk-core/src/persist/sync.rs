@@ -142,7 +142,8 @@ impl SqliteStorage {
            script,
            min_allowed_deposit,
            max_allowed_deposit,
-           max_swapper_payable
+           max_swapper_payable,
+           script_version
           FROM remote_sync.swaps
           WHERE bitcoin_address NOT IN (SELECT bitcoin_address FROM sync.swaps);",
             [],
@@ -173,12 +174,31 @@ impl SqliteStorage {
               attempted_amount_msat,
               attempted_error,
               lnurl_pay_domain,
-              lnurl_pay_comment
+              lnurl_pay_comment,
+              bolt12_offer,
+              bolt12_payer_note,
+              lnurl_verify_url
              FROM remote_sync.payments_external_info
              WHERE payment_id NOT IN (SELECT payment_id FROM sync.payments_external_info);",
             [],
         )?;
 
+        // sync remote payments_fiat_amounts table
+        tx.execute(
+            "
+             INSERT INTO sync.payments_fiat_amounts
+             SELECT
+              payment_id,
+              amount,
+              currency,
+              rate,
+              rate_locked_at,
+              rate_lock_id
+             FROM remote_sync.payments_fiat_amounts
+             WHERE payment_id NOT IN (SELECT payment_id FROM sync.payments_fiat_amounts);",
+            [],
+        )?;
+
         // sync remote payments_metadata table
         tx.execute(
             "
@@ -195,6 +215,25 @@ impl SqliteStorage {
             [],
         )?;
 
+        // sync remote payments_annotations table
+        tx.execute(
+            "
+             INSERT OR REPLACE INTO sync.payments_annotations
+             SELECT
+              remote_sync.payments_annotations.payment_id,
+              remote_sync.payments_annotations.note,
+              remote_sync.payments_annotations.tags,
+              remote_sync.payments_annotations.category,
+              remote_sync.payments_annotations.updated_at
+             FROM remote_sync.payments_annotations
+             LEFT JOIN sync.payments_annotations
+             ON sync.payments_annotations.payment_id = remote_sync.payments_annotations.payment_id
+             WHERE
+              sync.payments_annotations.updated_at IS NULL
+              OR remote_sync.payments_annotations.updated_at > sync.payments_annotations.updated_at;",
+            [],
+        )?;
+
         // sync remote reverse_swaps table
         tx.execute(
             "
@@ -236,6 +275,11 @@ impl SqliteStorage {
         tx.commit()?;
         con.execute("DETACH DATABASE remote_sync", [])?;
 
+        // Make the imported payment info, notes and tags searchable
+        if to_local {
+            self.rebuild_payments_search_index()?;
+        }
+
         Ok(())
     }
 
@@ -460,6 +504,7 @@ mod tests {
             public_key: rand_vec_u8(10),
             swapper_public_key: rand_vec_u8(10),
             script: rand_vec_u8(10),
+            script_version: crate::models::SwapScriptVersion::P2wsh,
             bolt11: None,
             paid_msat: 0,
             unconfirmed_sats: 0,
@@ -3,7 +3,7 @@ use std::str::FromStr;
 
 use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, Type, ValueRef};
 use rusqlite::Row;
-use rusqlite::{named_params, params, OptionalExtension};
+use rusqlite::{named_params, params, Connection, OptionalExtension};
 use sdk_common::prelude::*;
 use serde_json::{Map, Value};
 
@@ -13,6 +13,26 @@ use crate::models::*;
 
 const METADATA_MAX_LEN: usize = 1000;
 
+/// Selects the searchable fields of payments, in the column order of the `payments_search` table.
+/// Only the text entries of the LNURL metadata are indexed, not the images or the mime types.
+const PAYMENTS_SEARCH_FIELDS_QUERY: &str = "
+ SELECT
+  p.id,
+  p.description,
+  json_extract(p.details, '$.label'),
+  (SELECT group_concat(json_extract(value, '$[1]'), ' ')
+   FROM json_each(CASE WHEN json_valid(e.lnurl_metadata) THEN e.lnurl_metadata ELSE '[]' END)
+   WHERE json_extract(value, '$[0]') LIKE 'text/%'),
+  e.ln_address,
+  a.note,
+  (SELECT group_concat(value, ' ') FROM json_each(a.tags)),
+  a.category
+ FROM payments p
+ LEFT JOIN sync.payments_external_info e
+  ON p.id = e.payment_id
+ LEFT JOIN sync.payments_annotations a
+  ON p.id = a.payment_id";
+
 impl SqliteStorage {
     /// Inserts payments into the payments table. These can be pending, completed and failed payments. Before
     /// persisting, it automatically deletes previously pending payments
@@ -59,15 +79,65 @@ impl SqliteStorage {
                 &ln_tx.details,
             ))?;
         }
+        let ids: Vec<&str> = transactions.iter().map(|p| p.id.as_str()).collect();
+        Self::index_payments_for_search(&con, &ids)?;
+        Ok(())
+    }
+
+    /// Populates the full-text index of payments, if it wasn't yet
+    pub(crate) fn init_payments_search_index(&self) -> PersistResult<()> {
+        let indexed = self
+            .get_connection()?
+            .prepare("SELECT 1 FROM payments_search LIMIT 1")?
+            .exists([])?;
+        if !indexed {
+            self.rebuild_payments_search_index()?;
+        }
         Ok(())
     }
 
-    /// Deletes any pending sent payments and returns the deleted count
+    /// Re-indexes all payments for full-text search, e.g. after importing changes from the sync DB
+    pub(crate) fn rebuild_payments_search_index(&self) -> PersistResult<()> {
+        self.get_connection()?.execute_batch(&format!(
+            "
+             DELETE FROM payments_search;
+             INSERT INTO payments_search {PAYMENTS_SEARCH_FIELDS_QUERY};"
+        ))?;
+        Ok(())
+    }
+
+    fn index_payments_for_search(con: &Connection, payment_ids: &[&str]) -> PersistResult<()> {
+        if payment_ids.is_empty() {
+            return Ok(());
+        }
+        let ids = serde_json::to_string(payment_ids)?;
+        con.execute(
+            "DELETE FROM payments_search WHERE payment_id IN (SELECT value FROM json_each(?1))",
+            [&ids],
+        )?;
+        con.execute(
+            &format!(
+                "INSERT INTO payments_search {PAYMENTS_SEARCH_FIELDS_QUERY} \
+                 WHERE p.id IN (SELECT value FROM json_each(?1))"
+            ),
+            [&ids],
+        )?;
+        Ok(())
+    }
+
+    /// Deletes any pending sent payments, with their search index entries, and returns the deleted
+    /// count
     fn delete_pending_lightning_payments(&self) -> PersistResult<usize> {
-        Ok(self.get_connection()?.execute(
+        let con = self.get_connection()?;
+        let deleted = con.execute(
             "DELETE FROM payments WHERE payment_type = ?1 AND status = ?2",
             params![PaymentType::Sent.to_string(), PaymentStatus::Pending],
-        )?)
+        )?;
+        con.execute(
+            "DELETE FROM payments_search WHERE payment_id NOT IN (SELECT id FROM payments)",
+            [],
+        )?;
+        Ok(deleted)
     }
 
     /// Inserts metadata associated with this payment
@@ -88,9 +158,12 @@ impl SqliteStorage {
            ln_address,
            lnurl_withdraw_endpoint,
            attempted_amount_msat,
-           attempted_error
+           attempted_error,
+           bolt12_offer,
+           bolt12_payer_note,
+           lnurl_verify_url
          )
-         VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9)
+         VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12)
         ",
         )?;
 
@@ -104,11 +177,44 @@ impl SqliteStorage {
             payment_external_info.lnurl_withdraw_endpoint,
             payment_external_info.attempted_amount_msat,
             payment_external_info.attempted_error,
+            payment_external_info.bolt12_offer,
+            payment_external_info.bolt12_payer_note,
+            payment_external_info.lnurl_verify_url,
         ))?;
+        Self::index_payments_for_search(&con, &[payment_hash])?;
 
         Ok(())
     }
 
+    /// Stores the fiat amount a payment is requested or sent for
+    pub(crate) fn insert_payment_fiat_amount(
+        &self,
+        payment_hash: &str,
+        fiat_amount: &FiatAmount,
+    ) -> PersistResult<()> {
+        self.get_connection()?.execute(
+            "
+             INSERT OR REPLACE INTO sync.payments_fiat_amounts (
+               payment_id,
+               amount,
+               currency,
+               rate,
+               rate_locked_at,
+               rate_lock_id
+             )
+             VALUES (:payment_id, :amount, :currency, :rate, :rate_locked_at, :rate_lock_id)",
+            named_params! {
+                ":payment_id": payment_hash,
+                ":amount": fiat_amount.amount,
+                ":currency": fiat_amount.rate.currency,
+                ":rate": fiat_amount.rate.value,
+                ":rate_locked_at": fiat_amount.rate.locked_at,
+                ":rate_lock_id": fiat_amount.rate.id,
+            },
+        )?;
+        Ok(())
+    }
+
     /// Updates the metadata object associated to a payment
     pub fn set_payment_external_metadata(
         &self,
@@ -202,40 +308,97 @@ impl SqliteStorage {
         )?)
     }
 
+    /// Sets the note, tags and category of a payment, replacing any previously set
+    ///
+    /// Blank values are dropped and tags are deduplicated.
+    pub fn set_payment_annotations(&self, req: &SetPaymentAnnotationsRequest) -> PersistResult<()> {
+        let note = req.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+        let category = req
+            .category
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty());
+        let mut tags: Vec<&str> = Vec::new();
+        for tag in req.tags.iter().map(|t| t.trim()) {
+            if !tag.is_empty() && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        let tags = serde_json::to_string(&tags)?;
+
+        ensure_sdk!(
+            note.map_or(0, str::len) + tags.len() + category.map_or(0, str::len)
+                <= METADATA_MAX_LEN,
+            PersistError::Generic(format!(
+                "Max annotations size ({} characters) has been exceeded",
+                METADATA_MAX_LEN
+            ))
+        );
+
+        let payment_exists = self
+            .get_connection()?
+            .prepare("SELECT 1 FROM payments WHERE id = ?1;")?
+            .exists(params![req.payment_hash])?;
+        if !payment_exists {
+            return Err(PersistError::generic("Payment not found"));
+        }
+
+        let con = self.get_connection()?;
+        con.execute(
+            "
+             INSERT OR REPLACE INTO sync.payments_annotations(
+                payment_id,
+                note,
+                tags,
+                category,
+                updated_at
+             )
+             VALUES (
+                ?1,
+                ?2,
+                json(?3),
+                ?4,
+                CURRENT_TIMESTAMP
+             );",
+            params![req.payment_hash, note, tags, category],
+        )?;
+        Self::index_payments_for_search(&con, &[&req.payment_hash])?;
+
+        Ok(())
+    }
+
+    /// All the distinct tags set on payments, in alphabetical order
+    pub(crate) fn list_payment_tags(&self) -> PersistResult<Vec<String>> {
+        let con = self.get_connection()?;
+        let mut stmt = con.prepare(
+            "
+             SELECT DISTINCT t.value
+             FROM sync.payments_annotations a, json_each(a.tags) t
+             ORDER BY t.value",
+        )?;
+        let tags = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(tags)
+    }
+
     /// Constructs [Payment] by joining data in the `payment` and `payments_external_info` tables
     ///
     /// This queries all payments. To query a single payment, see [Self::get_payment_by_hash]
     /// or [Self::get_completed_payment_by_hash]
     pub fn list_payments(&self, req: ListPaymentsRequest) -> PersistResult<Vec<Payment>> {
-        let where_clause = filter_to_where_clause(
-            req.filters,
-            &req.metadata_filters,
-            req.from_timestamp,
-            req.to_timestamp,
-            req.include_failures,
-        );
+        let where_clause = filter_to_where_clause(&req);
         let offset = req.offset.unwrap_or(0u32);
         let limit = req.limit.unwrap_or(u32::MAX);
         let con = self.get_connection()?;
-        let query = self.select_payments_query(where_clause.as_str(), offset, limit)?;
+        let query = self.select_payments_query(
+            where_clause.as_str(),
+            req.sort_order.unwrap_or_default(),
+            offset,
+            limit,
+        )?;
         let mut stmt = con.prepare(query.as_str())?;
-
-        let mut params: HashMap<String, String> = HashMap::new();
-
-        if let Some(metadata_filters) = &req.metadata_filters {
-            metadata_filters.iter().enumerate().for_each(
-                |(
-                    i,
-                    MetadataFilter {
-                        json_path,
-                        json_value,
-                    },
-                )| {
-                    params.insert(format!(":json_path_{i}"), format!("$.{json_path}"));
-                    params.insert(format!(":json_value_{i}"), json_value.clone());
-                },
-            )
-        }
+        let params = filter_to_params(&req);
 
         let vec: Vec<Payment> = stmt
             .query_map(
@@ -251,12 +414,47 @@ impl SqliteStorage {
         Ok(vec)
     }
 
+    /// Counts the payments matching the filters of the request, ignoring its offset and limit
+    pub fn count_payments(&self, req: &ListPaymentsRequest) -> PersistResult<u64> {
+        let where_clause = filter_to_where_clause(req);
+        let params = filter_to_params(req);
+        let count = self.get_connection()?.query_row(
+            &format!(
+                "
+          SELECT COUNT(*)
+          FROM payments p
+          LEFT JOIN sync.payments_external_info e
+          ON
+           p.id = e.payment_id
+          LEFT JOIN sync.payments_metadata m
+          ON
+            p.id = m.payment_id
+          LEFT JOIN sync.payments_annotations a
+          ON
+            p.id = a.payment_id
+          {where_clause}"
+            ),
+            params
+                .iter()
+                .map(|(k, v)| (k.as_str(), v as &dyn ToSql))
+                .collect::<Vec<(&str, &dyn ToSql)>>()
+                .as_slice(),
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
     pub fn select_payments_query(
         &self,
         where_clause: &str,
+        sort_order: PaymentSortOrder,
         offset: u32,
         limit: u32,
     ) -> PersistResult<String> {
+        let order = match sort_order {
+            PaymentSortOrder::Descending => "DESC",
+            PaymentSortOrder::Ascending => "ASC",
+        };
         let swap_fields = self.select_swap_fields("swaps_");
         let swap_query = self.select_swap_query("true", "swaps_");
         let rev_swap_fields = self.select_reverse_swap_fields("revswaps_");
@@ -283,6 +481,17 @@ impl SqliteStorage {
            m.metadata,
            e.lnurl_pay_domain,
            e.lnurl_pay_comment,
+           e.bolt12_offer,
+           e.bolt12_payer_note,
+           e.lnurl_verify_url,
+           a.note AS annotation_note,
+           a.tags AS annotation_tags,
+           a.category AS annotation_category,
+           f.amount AS fiat_amount,
+           f.currency AS fiat_currency,
+           f.rate AS fiat_rate,
+           f.rate_locked_at AS fiat_rate_locked_at,
+           f.rate_lock_id AS fiat_rate_lock_id,
            {swap_fields},
            {rev_swap_fields}
           FROM payments p
@@ -292,6 +501,12 @@ impl SqliteStorage {
           LEFT JOIN sync.payments_metadata m
           ON
             p.id = m.payment_id
+          LEFT JOIN sync.payments_annotations a
+          ON
+            p.id = a.payment_id
+          LEFT JOIN sync.payments_fiat_amounts f
+          ON
+            p.id = f.payment_id
           LEFT JOIN sync.open_channel_payment_info o
            ON
             p.id = o.payment_hash
@@ -302,7 +517,7 @@ impl SqliteStorage {
            ON
             json_extract(p.details, '$.payment_preimage') = hex(revswaps_preimage) COLLATE NOCASE
           {where_clause}
-          ORDER BY payment_time DESC
+          ORDER BY payment_time {order}
           LIMIT {limit}
           OFFSET {offset}
         "
@@ -317,7 +532,8 @@ impl SqliteStorage {
     ///
     /// To query all payments, see [Self::list_payments]
     pub(crate) fn get_payment_by_hash(&self, hash: &String) -> PersistResult<Option<Payment>> {
-        let query = self.select_payments_query("where id = ?1", 0, 1)?;
+        let query =
+            self.select_payments_query("where id = ?1", PaymentSortOrder::Descending, 0, 1)?;
         Ok(self
             .get_connection()?
             .query_row(query.as_str(), [hash], |row| self.sql_row_to_payment(row))
@@ -362,6 +578,8 @@ impl SqliteStorage {
         let amount_msat = row.get(3)?;
         let status: PaymentStatus = row.get(5)?;
         let attempted_amount_msat: Option<u64> = row.get(12)?;
+        let tags_index = row.as_ref().column_index("annotation_tags")?;
+        let tags: Option<String> = row.get(tags_index)?;
         let mut payment = Payment {
             id: row.get(0)?,
             payment_type: PaymentType::from_str(payment_type_str.as_str()).unwrap(),
@@ -376,7 +594,28 @@ impl SqliteStorage {
             details: row.get(7)?,
             error: row.get(13)?,
             metadata: row.get(16)?,
+            note: row.get("annotation_note")?,
+            tags: match tags {
+                None => vec![],
+                Some(t) => serde_json::from_str(&t).map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(tags_index, Type::Text, Box::new(e))
+                })?,
+            },
+            category: row.get("annotation_category")?,
+            fiat_amount: None,
         };
+        let fiat_amount: Option<String> = row.get("fiat_amount")?;
+        if let Some(amount) = fiat_amount {
+            payment.fiat_amount = Some(FiatAmount {
+                amount,
+                rate: LockedFiatRate {
+                    id: row.get("fiat_rate_lock_id")?,
+                    currency: row.get("fiat_currency")?,
+                    value: row.get("fiat_rate")?,
+                    locked_at: row.get("fiat_rate_locked_at")?,
+                },
+            });
+        }
 
         if let PaymentDetails::Ln { ref mut data } = payment.details {
             let lnurl_success_action_str: Option<String> = row.get(8)?;
@@ -392,6 +631,7 @@ impl SqliteStorage {
             data.lnurl_metadata = row.get(9)?;
             data.ln_address = row.get(10)?;
             data.lnurl_withdraw_endpoint = row.get(11)?;
+            data.lnurl_verify_url = row.get(21)?;
             data.swap_info = self.sql_row_to_swap(row, "swaps_").ok();
             if let Ok(fr) = self.sql_row_to_reverse_swap(row, "revswaps_") {
                 data.reverse_swap_info = Some(fr.get_reverse_swap_info_using_cached_values());
@@ -411,31 +651,73 @@ impl SqliteStorage {
             }
         }
 
+        // The node reports payments to offers as regular LN payments, with the paid BOLT12
+        // invoice in place of the bolt11. If we know the offer, expose it as such.
+        let bolt12_offer: Option<String> = row.get(19)?;
+        if let (Some(offer), PaymentDetails::Ln { data }) = (bolt12_offer, &payment.details) {
+            payment.details = PaymentDetails::Bolt12 {
+                data: Bolt12PaymentDetails {
+                    payment_hash: data.payment_hash.clone(),
+                    label: data.label.clone(),
+                    destination_pubkey: data.destination_pubkey.clone(),
+                    payment_preimage: data.payment_preimage.clone(),
+                    offer,
+                    invoice: data.bolt11.clone(),
+                    payer_note: row.get(20)?,
+                    pending_expiration_block: data.pending_expiration_block,
+                },
+            };
+        }
+
         Ok(payment)
     }
 }
 
-fn filter_to_where_clause(
-    type_filters: Option<Vec<PaymentTypeFilter>>,
-    metadata_filters: &Option<Vec<MetadataFilter>>,
-    from_timestamp: Option<i64>,
-    to_timestamp: Option<i64>,
-    include_failures: Option<bool>,
-) -> String {
+fn filter_to_where_clause(req: &ListPaymentsRequest) -> String {
     let mut where_clause: Vec<String> = Vec::new();
-    let with_failures = include_failures.unwrap_or(false);
+    let with_failures = req.include_failures.unwrap_or(false);
 
-    if let Some(t) = from_timestamp {
+    if let Some(t) = req.from_timestamp {
         where_clause.push(format!("payment_time >= {t}"));
     };
-    if let Some(t) = to_timestamp {
+    if let Some(t) = req.to_timestamp {
         where_clause.push(format!("payment_time <= {t}"));
     };
-    if !with_failures {
-        where_clause.push(format!("status != {}", PaymentStatus::Failed as i64));
+    match &req.statuses {
+        Some(statuses) => where_clause.push(format!(
+            "status in ({})",
+            statuses
+                .iter()
+                .map(|s| (*s as i64).to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        None => {
+            if !with_failures {
+                where_clause.push(format!("status != {}", PaymentStatus::Failed as i64));
+            }
+        }
     };
 
-    if let Some(filters) = type_filters {
+    // Match the amount as listed, which for pending and failed payments is the attempted amount
+    let listed_amount = format!(
+        "(CASE WHEN status = {} THEN amount_msat ELSE coalesce(e.attempted_amount_msat, amount_msat) END)",
+        PaymentStatus::Complete as i64
+    );
+    if let Some(a) = req.min_amount_msat {
+        where_clause.push(format!("{listed_amount} >= {a}"));
+    }
+    if let Some(a) = req.max_amount_msat {
+        where_clause.push(format!("{listed_amount} <= {a}"));
+    }
+    if req.counterparty_node_id.is_some() {
+        where_clause.push(format!(
+            "payment_type = '{}' and json_extract(details, '$.destination_pubkey') = :counterparty_node_id",
+            PaymentType::Sent
+        ));
+    }
+
+    if let Some(filters) = &req.filters {
         if !filters.is_empty() {
             let mut type_filter_clause: HashSet<PaymentType> = HashSet::new();
             for type_filter in filters {
@@ -463,12 +745,29 @@ fn filter_to_where_clause(
         }
     }
 
-    if let Some(filters) = metadata_filters {
+    if let Some(filters) = &req.metadata_filters {
         filters.iter().enumerate().for_each(|(i, _)| {
             where_clause.push(format!("metadata->:json_path_{i} = :json_value_{i}"));
         });
     }
 
+    if let Some(tags) = &req.tags {
+        tags.iter().enumerate().for_each(|(i, _)| {
+            where_clause.push(format!(
+                "EXISTS (SELECT 1 FROM json_each(a.tags) WHERE value = :tag_{i})"
+            ));
+        });
+    }
+    if req.category.is_some() {
+        where_clause.push("a.category = :category".to_string());
+    }
+    if search_to_fts_query(&req.search).is_some() {
+        where_clause.push(
+            "p.id IN (SELECT payment_id FROM payments_search WHERE payments_search MATCH :search)"
+                .to_string(),
+        );
+    }
+
     let mut where_clause_str = String::new();
     if !where_clause.is_empty() {
         where_clause_str = String::from("where ");
@@ -477,6 +776,55 @@ fn filter_to_where_clause(
     where_clause_str
 }
 
+fn filter_to_params(req: &ListPaymentsRequest) -> HashMap<String, String> {
+    let mut params: HashMap<String, String> = HashMap::new();
+
+    if let Some(metadata_filters) = &req.metadata_filters {
+        metadata_filters.iter().enumerate().for_each(
+            |(
+                i,
+                MetadataFilter {
+                    json_path,
+                    json_value,
+                },
+            )| {
+                params.insert(format!(":json_path_{i}"), format!("$.{json_path}"));
+                params.insert(format!(":json_value_{i}"), json_value.clone());
+            },
+        )
+    }
+    if let Some(node_id) = &req.counterparty_node_id {
+        params.insert(":counterparty_node_id".to_string(), node_id.clone());
+    }
+    if let Some(tags) = &req.tags {
+        for (i, tag) in tags.iter().enumerate() {
+            params.insert(format!(":tag_{i}"), tag.trim().to_string());
+        }
+    }
+    if let Some(category) = &req.category {
+        params.insert(":category".to_string(), category.trim().to_string());
+    }
+    if let Some(query) = search_to_fts_query(&req.search) {
+        params.insert(":search".to_string(), query);
+    }
+    params
+}
+
+/// Turns free text into an FTS5 query matching every word as a prefix, so that it can't
+/// be interpreted as FTS5 query syntax
+fn search_to_fts_query(search: &Option<String>) -> Option<String> {
+    let terms: Vec<String> = search
+        .as_deref()
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(|t| format!("\"{}\"*", t.replace('"', "\"\"")))
+        .collect();
+    match terms.is_empty() {
+        true => None,
+        false => Some(terms.join(" ")),
+    }
+}
+
 impl FromSql for PaymentDetails {
     fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
         serde_json::from_str(value.as_str()?).map_err(|_| FromSqlError::InvalidType)
@@ -521,6 +869,7 @@ fn test_ln_transactions() -> PersistResult<(), Box<dyn std::error::Error>> {
     let lnurl_metadata = "{'key': 'sample-metadata-val'}";
     let test_ln_address = "test@ln.adddress.com";
     let test_lnurl_pay_domain = "example.com";
+    let test_lnurl_verify_url = "https://example.com/lnurlp/verify/1";
     let test_lnurl_pay_comment = "Thank you Satoshi!";
     let sa = SuccessActionProcessed::Message {
         data: MessageSuccessActionData {
@@ -544,6 +893,7 @@ fn test_ln_transactions() -> PersistResult<(), Box<dyn std::error::Error>> {
         public_key: vec![1, 3, 2],
         swapper_public_key: vec![2, 1, 3],
         script: vec![2, 3, 1],
+        script_version: SwapScriptVersion::P2wsh,
         bolt11: Some("swap_bolt11".into()),
         paid_msat: 50_000,
         confirmed_sats: 50,
@@ -595,16 +945,14 @@ fn test_ln_transactions() -> PersistResult<(), Box<dyn std::error::Error>> {
         status: ReverseSwapStatus::CompletedConfirmed,
     };
     let txs = [
-        Payment {
-            id: payment_hash_with_lnurl_success_action.to_string(),
-            payment_type: PaymentType::Sent,
-            payment_time: 1001,
-            amount_msat: 100,
-            fee_msat: 20,
-            status: PaymentStatus::Complete,
-            error: None,
-            description: None,
-            details: PaymentDetails::Ln {
+        Payment::new(
+            payment_hash_with_lnurl_success_action.to_string(),
+            PaymentType::Sent,
+            1001,
+            100,
+            20,
+            PaymentStatus::Complete,
+            PaymentDetails::Ln {
                 data: LnPaymentDetails {
                     payment_hash: payment_hash_with_lnurl_success_action.to_string(),
                     label: "label".to_string(),
@@ -618,117 +966,118 @@ fn test_ln_transactions() -> PersistResult<(), Box<dyn std::error::Error>> {
                     lnurl_metadata: Some(lnurl_metadata.to_string()),
                     ln_address: Some(test_ln_address.to_string()),
                     lnurl_withdraw_endpoint: None,
+                    lnurl_verify_url: Some(test_lnurl_verify_url.to_string()),
                     swap_info: None,
                     reverse_swap_info: None,
                     pending_expiration_block: None,
                     open_channel_bolt11: None,
                 },
             },
-            metadata: None,
-        },
+        ),
         Payment {
-            id: payment_hash_with_lnurl_withdraw.to_string(),
-            payment_type: PaymentType::Received,
-            payment_time: 1000,
-            amount_msat: 100,
-            fee_msat: 20,
-            status: PaymentStatus::Complete,
-            error: None,
             description: Some("desc".to_string()),
-            details: PaymentDetails::Ln {
-                data: LnPaymentDetails {
-                    payment_hash: payment_hash_with_lnurl_withdraw.to_string(),
-                    label: "label".to_string(),
-                    destination_pubkey: "pubey".to_string(),
-                    payment_preimage: "2222".to_string(),
-                    keysend: true,
-                    bolt11: "bolt11".to_string(),
-                    lnurl_success_action: None,
-                    lnurl_pay_domain: None,
-                    lnurl_pay_comment: None,
-                    lnurl_metadata: None,
-                    ln_address: None,
-                    lnurl_withdraw_endpoint: Some(lnurl_withdraw_url.to_string()),
-                    swap_info: None,
-                    reverse_swap_info: None,
-                    pending_expiration_block: None,
-                    open_channel_bolt11: None,
+            ..Payment::new(
+                payment_hash_with_lnurl_withdraw.to_string(),
+                PaymentType::Received,
+                1000,
+                100,
+                20,
+                PaymentStatus::Complete,
+                PaymentDetails::Ln {
+                    data: LnPaymentDetails {
+                        payment_hash: payment_hash_with_lnurl_withdraw.to_string(),
+                        label: "label".to_string(),
+                        destination_pubkey: "pubey".to_string(),
+                        payment_preimage: "2222".to_string(),
+                        keysend: true,
+                        bolt11: "bolt11".to_string(),
+                        lnurl_success_action: None,
+                        lnurl_pay_domain: None,
+                        lnurl_pay_comment: None,
+                        lnurl_metadata: None,
+                        ln_address: None,
+                        lnurl_withdraw_endpoint: Some(lnurl_withdraw_url.to_string()),
+                        lnurl_verify_url: None,
+                        swap_info: None,
+                        reverse_swap_info: None,
+                        pending_expiration_block: None,
+                        open_channel_bolt11: None,
+                    },
                 },
-            },
-            metadata: None,
+            )
         },
         Payment {
-            id: hex::encode(payment_hash_with_swap_info.clone()),
-            payment_type: PaymentType::Received,
-            payment_time: 999,
-            amount_msat: 50_000,
-            fee_msat: 20,
-            status: PaymentStatus::Complete,
-            error: None,
             description: Some("desc".to_string()),
-            details: PaymentDetails::Ln {
-                data: LnPaymentDetails {
-                    payment_hash: hex::encode(payment_hash_with_swap_info),
-                    label: "label".to_string(),
-                    destination_pubkey: "pubkey".to_string(),
-                    payment_preimage: "3333".to_string(),
-                    keysend: false,
-                    bolt11: "swap_bolt11".to_string(),
-                    lnurl_success_action: None,
-                    lnurl_pay_domain: None,
-                    lnurl_pay_comment: None,
-                    lnurl_metadata: None,
-                    ln_address: None,
-                    lnurl_withdraw_endpoint: None,
-                    swap_info: Some(swap_info.clone()),
-                    reverse_swap_info: None,
-                    pending_expiration_block: None,
-                    open_channel_bolt11: None,
+            ..Payment::new(
+                hex::encode(payment_hash_with_swap_info.clone()),
+                PaymentType::Received,
+                999,
+                50_000,
+                20,
+                PaymentStatus::Complete,
+                PaymentDetails::Ln {
+                    data: LnPaymentDetails {
+                        payment_hash: hex::encode(payment_hash_with_swap_info),
+                        label: "label".to_string(),
+                        destination_pubkey: "pubkey".to_string(),
+                        payment_preimage: "3333".to_string(),
+                        keysend: false,
+                        bolt11: "swap_bolt11".to_string(),
+                        lnurl_success_action: None,
+                        lnurl_pay_domain: None,
+                        lnurl_pay_comment: None,
+                        lnurl_metadata: None,
+                        ln_address: None,
+                        lnurl_withdraw_endpoint: None,
+                        lnurl_verify_url: None,
+                        swap_info: Some(swap_info.clone()),
+                        reverse_swap_info: None,
+                        pending_expiration_block: None,
+                        open_channel_bolt11: None,
+                    },
                 },
-            },
-            metadata: None,
+            )
         },
         Payment {
-            id: hex::encode(payment_hash_with_rev_swap_info.clone()),
-            payment_type: PaymentType::Sent,
-            payment_time: 998,
-            amount_msat: 100_000,
-            fee_msat: 200,
-            status: PaymentStatus::Complete,
-            error: None,
             description: Some("desc".to_string()),
-            details: PaymentDetails::Ln {
-                data: LnPaymentDetails {
-                    payment_hash: hex::encode(payment_hash_with_rev_swap_info),
-                    label: "label".to_string(),
-                    destination_pubkey: "pubkey".to_string(),
-                    payment_preimage: hex::encode(rev_swap_preimage),
-                    keysend: false,
-                    bolt11: "swap_bolt11".to_string(),
-                    lnurl_success_action: None,
-                    lnurl_metadata: None,
-                    lnurl_pay_domain: None,
-                    lnurl_pay_comment: None,
-                    ln_address: None,
-                    lnurl_withdraw_endpoint: None,
-                    swap_info: None,
-                    reverse_swap_info: Some(rev_swap_info.clone()),
-                    pending_expiration_block: None,
-                    open_channel_bolt11: None,
+            ..Payment::new(
+                hex::encode(payment_hash_with_rev_swap_info.clone()),
+                PaymentType::Sent,
+                998,
+                100_000,
+                200,
+                PaymentStatus::Complete,
+                PaymentDetails::Ln {
+                    data: LnPaymentDetails {
+                        payment_hash: hex::encode(payment_hash_with_rev_swap_info),
+                        label: "label".to_string(),
+                        destination_pubkey: "pubkey".to_string(),
+                        payment_preimage: hex::encode(rev_swap_preimage),
+                        keysend: false,
+                        bolt11: "swap_bolt11".to_string(),
+                        lnurl_success_action: None,
+                        lnurl_metadata: None,
+                        lnurl_pay_domain: None,
+                        lnurl_pay_comment: None,
+                        ln_address: None,
+                        lnurl_withdraw_endpoint: None,
+                        lnurl_verify_url: None,
+                        swap_info: None,
+                        reverse_swap_info: Some(rev_swap_info.clone()),
+                        pending_expiration_block: None,
+                        open_channel_bolt11: None,
+                    },
                 },
-            },
-            metadata: None,
+            )
         },
-        Payment {
-            id: payment_hash_with_lnurl_domain.to_string(),
-            payment_type: PaymentType::Sent,
-            payment_time: 998,
-            amount_msat: 100,
-            fee_msat: 20,
-            status: PaymentStatus::Complete,
-            error: None,
-            description: None,
-            details: PaymentDetails::Ln {
+        Payment::new(
+            payment_hash_with_lnurl_domain.to_string(),
+            PaymentType::Sent,
+            998,
+            100,
+            20,
+            PaymentStatus::Complete,
+            PaymentDetails::Ln {
                 data: LnPaymentDetails {
                     payment_hash: payment_hash_with_lnurl_domain.to_string(),
                     label: "label".to_string(),
@@ -742,45 +1091,46 @@ fn test_ln_transactions() -> PersistResult<(), Box<dyn std::error::Error>> {
                     lnurl_metadata: Some(lnurl_metadata.to_string()),
                     ln_address: None,
                     lnurl_withdraw_endpoint: None,
+                    lnurl_verify_url: None,
                     swap_info: None,
                     reverse_swap_info: None,
                     pending_expiration_block: None,
                     open_channel_bolt11: None,
                 },
             },
-            metadata: None,
-        },
+        ),
     ];
     let failed_txs = [Payment {
-        id: "125".to_string(),
-        payment_type: PaymentType::Sent,
-        payment_time: 2000,
-        amount_msat: 1000,
-        fee_msat: 0,
-        status: PaymentStatus::Failed,
-        error: None,
         description: Some("desc".to_string()),
-        details: PaymentDetails::Ln {
-            data: LnPaymentDetails {
-                payment_hash: "125".to_string(),
-                label: "label".to_string(),
-                destination_pubkey: "pubey".to_string(),
-                payment_preimage: "4444".to_string(),
-                keysend: true,
-                bolt11: "bolt11".to_string(),
-                lnurl_success_action: None,
-                lnurl_pay_domain: None,
-                lnurl_pay_comment: None,
-                lnurl_metadata: None,
-                ln_address: None,
-                lnurl_withdraw_endpoint: None,
-                swap_info: None,
-                reverse_swap_info: None,
-                pending_expiration_block: None,
-                open_channel_bolt11: None,
+        ..Payment::new(
+            "125".to_string(),
+            PaymentType::Sent,
+            2000,
+            1000,
+            0,
+            PaymentStatus::Failed,
+            PaymentDetails::Ln {
+                data: LnPaymentDetails {
+                    payment_hash: "125".to_string(),
+                    label: "label".to_string(),
+                    destination_pubkey: "pubey".to_string(),
+                    payment_preimage: "4444".to_string(),
+                    keysend: true,
+                    bolt11: "bolt11".to_string(),
+                    lnurl_success_action: None,
+                    lnurl_pay_domain: None,
+                    lnurl_pay_comment: None,
+                    lnurl_metadata: None,
+                    ln_address: None,
+                    lnurl_withdraw_endpoint: None,
+                    lnurl_verify_url: None,
+                    swap_info: None,
+                    reverse_swap_info: None,
+                    pending_expiration_block: None,
+                    open_channel_bolt11: None,
+                },
             },
-        },
-        metadata: None,
+        )
     }];
     let storage = SqliteStorage::new(test_utils::create_test_sql_dir());
     storage.init()?;
@@ -797,6 +1147,9 @@ fn test_ln_transactions() -> PersistResult<(), Box<dyn std::error::Error>> {
             lnurl_withdraw_endpoint: None,
             attempted_amount_msat: None,
             attempted_error: None,
+            bolt12_offer: None,
+            bolt12_payer_note: None,
+            lnurl_verify_url: Some(test_lnurl_verify_url.to_string()),
         },
     )?;
     storage.insert_payment_external_info(
@@ -810,6 +1163,9 @@ fn test_ln_transactions() -> PersistResult<(), Box<dyn std::error::Error>> {
             lnurl_withdraw_endpoint: Some(lnurl_withdraw_url.to_string()),
             attempted_amount_msat: None,
             attempted_error: None,
+            bolt12_offer: None,
+            bolt12_payer_note: None,
+            lnurl_verify_url: None,
         },
     )?;
     storage.insert_swap(swap_info.clone())?;
@@ -828,6 +1184,9 @@ fn test_ln_transactions() -> PersistResult<(), Box<dyn std::error::Error>> {
             lnurl_withdraw_endpoint: None,
             attempted_amount_msat: None,
             attempted_error: None,
+            bolt12_offer: None,
+            bolt12_payer_note: None,
+            lnurl_verify_url: None,
         },
     )?;
     storage.insert_reverse_swap(&full_ref_swap_info)?;
@@ -924,6 +1283,67 @@ fn test_ln_transactions() -> PersistResult<(), Box<dyn std::error::Error>> {
     assert_eq!(retrieve_txs.len(), 1);
     assert_eq!(retrieve_txs[0].id, payment_hash_with_lnurl_withdraw);
 
+    // test status, amount and counterparty filters
+    let retrieve_txs = storage.list_payments(ListPaymentsRequest {
+        statuses: Some(vec![PaymentStatus::Failed]),
+        ..Default::default()
+    })?;
+    assert_eq!(retrieve_txs.len(), 1);
+    assert_eq!(retrieve_txs[0].id, "125");
+    let retrieve_txs = storage.list_payments(ListPaymentsRequest {
+        min_amount_msat: Some(50_000),
+        ..Default::default()
+    })?;
+    assert_eq!(retrieve_txs, vec![txs[2].clone(), txs[3].clone()]);
+    let retrieve_txs = storage.list_payments(ListPaymentsRequest {
+        counterparty_node_id: Some("pubey".to_string()),
+        max_amount_msat: Some(100),
+        include_failures: Some(true),
+        ..Default::default()
+    })?;
+    assert_eq!(retrieve_txs.len(), 2);
+    assert_eq!(retrieve_txs[0].id, payment_hash_with_lnurl_success_action);
+    assert_eq!(retrieve_txs[1].id, payment_hash_with_lnurl_domain);
+
+    // test sort order
+    let retrieve_txs = storage.list_payments(ListPaymentsRequest {
+        sort_order: Some(PaymentSortOrder::Ascending),
+        ..Default::default()
+    })?;
+    assert_eq!(retrieve_txs.len(), 5);
+    assert_eq!(retrieve_txs[4].id, payment_hash_with_lnurl_success_action);
+
+    // test full-text search
+    let retrieve_txs = storage.list_payments(ListPaymentsRequest {
+        search: Some("TEST@ln".to_string()),
+        ..Default::default()
+    })?;
+    assert_eq!(retrieve_txs.len(), 1);
+    assert_eq!(retrieve_txs[0].id, payment_hash_with_lnurl_success_action);
+    let retrieve_txs = storage.list_payments(ListPaymentsRequest {
+        search: Some("lab \"NOT".to_string()),
+        ..Default::default()
+    })?;
+    assert!(retrieve_txs.is_empty());
+
+    // test count
+    assert_eq!(
+        storage.count_payments(&ListPaymentsRequest {
+            search: Some("lab".to_string()),
+            offset: Some(1),
+            limit: Some(1),
+            ..Default::default()
+        })?,
+        5
+    );
+    assert_eq!(
+        storage.count_payments(&ListPaymentsRequest {
+            include_failures: Some(true),
+            ..Default::default()
+        })?,
+        6
+    );
+
     // test json metadata validation
     assert!(storage
         .set_payment_external_metadata(
@@ -966,6 +1386,121 @@ fn test_ln_transactions() -> PersistResult<(), Box<dyn std::error::Error>> {
     assert_eq!(retrieve_txs[0].id, payment_hash_with_lnurl_withdraw);
     assert_eq!(retrieve_txs[0].metadata, Some(test_json.to_string()),);
 
+    // test annotations set and filter
+    assert!(storage
+        .set_payment_annotations(&SetPaymentAnnotationsRequest {
+            payment_hash: "non existing hash".to_string(),
+            ..Default::default()
+        })
+        .is_err());
+    storage.set_payment_annotations(&SetPaymentAnnotationsRequest {
+        payment_hash: payment_hash_with_lnurl_withdraw.to_string(),
+        note: Some(" Lunch with Bob ".to_string()),
+        tags: vec![
+            "food".to_string(),
+            " team ".to_string(),
+            "food".to_string(),
+            "".to_string(),
+        ],
+        category: Some("Expenses".to_string()),
+    })?;
+    storage.set_payment_annotations(&SetPaymentAnnotationsRequest {
+        payment_hash: payment_hash_with_lnurl_success_action.to_string(),
+        tags: vec!["team".to_string()],
+        ..Default::default()
+    })?;
+
+    let annotated = storage
+        .get_payment_by_hash(&payment_hash_with_lnurl_withdraw.to_string())?
+        .unwrap();
+    assert_eq!(annotated.note, Some("Lunch with Bob".to_string()));
+    assert_eq!(annotated.tags, vec!["food".to_string(), "team".to_string()]);
+    assert_eq!(annotated.category, Some("Expenses".to_string()));
+    assert_eq!(annotated.fiat_amount, None);
+    assert_eq!(
+        storage.list_payment_tags()?,
+        vec!["food".to_string(), "team".to_string()]
+    );
+
+    let fiat_amount = FiatAmount {
+        amount: "12.50".to_string(),
+        rate: LockedFiatRate {
+            id: "aa".repeat(16),
+            currency: "EUR".to_string(),
+            value: "50000.25".to_string(),
+            locked_at: 1_700_000_000,
+        },
+    };
+    storage.insert_payment_fiat_amount(payment_hash_with_lnurl_withdraw, &fiat_amount)?;
+    assert_eq!(
+        storage
+            .get_payment_by_hash(&payment_hash_with_lnurl_withdraw.to_string())?
+            .unwrap()
+            .fiat_amount,
+        Some(fiat_amount)
+    );
+
+    let filter_ids = |req: ListPaymentsRequest| -> PersistResult<Vec<String>> {
+        Ok(storage
+            .list_payments(req)?
+            .into_iter()
+            .map(|p| p.id)
+            .collect())
+    };
+    assert_eq!(
+        filter_ids(ListPaymentsRequest {
+            tags: Some(vec!["team".to_string()]),
+            ..Default::default()
+        })?,
+        vec![
+            payment_hash_with_lnurl_success_action.to_string(),
+            payment_hash_with_lnurl_withdraw.to_string()
+        ]
+    );
+    assert_eq!(
+        filter_ids(ListPaymentsRequest {
+            tags: Some(vec!["team".to_string(), "food".to_string()]),
+            ..Default::default()
+        })?,
+        vec![payment_hash_with_lnurl_withdraw.to_string()]
+    );
+    assert_eq!(
+        filter_ids(ListPaymentsRequest {
+            category: Some("Expenses".to_string()),
+            ..Default::default()
+        })?,
+        vec![payment_hash_with_lnurl_withdraw.to_string()]
+    );
+    for search in ["LUNCH", "expens", "foo"] {
+        assert_eq!(
+            filter_ids(ListPaymentsRequest {
+                search: Some(search.to_string()),
+                ..Default::default()
+            })?,
+            vec![payment_hash_with_lnurl_withdraw.to_string()]
+        );
+    }
+    assert!(filter_ids(ListPaymentsRequest {
+        search: Some("dinner".to_string()),
+        ..Default::default()
+    })?
+    .is_empty());
+
+    // Setting the annotations again replaces them
+    storage.set_payment_annotations(&SetPaymentAnnotationsRequest {
+        payment_hash: payment_hash_with_lnurl_success_action.to_string(),
+        ..Default::default()
+    })?;
+    assert_eq!(
+        storage.list_payment_tags()?,
+        vec!["food".to_string(), "team".to_string()]
+    );
+    storage.set_payment_annotations(&SetPaymentAnnotationsRequest {
+        payment_hash: payment_hash_with_lnurl_withdraw.to_string(),
+        ..Default::default()
+    })?;
+    assert!(storage.list_payment_tags()?.is_empty());
+
     // test open_channel_bolt11
     storage.insert_open_channel_payment_info(
         payment_hash_with_lnurl_withdraw,
@@ -1008,3 +1543,85 @@ fn test_ln_transactions() -> PersistResult<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_payments_search() -> PersistResult<(), Box<dyn std::error::Error>> {
+    use crate::models::{LnPaymentDetails, Payment, PaymentDetails};
+    use crate::persist::test_utils;
+
+    let ln_payment = |id: &str, status: PaymentStatus| {
+        Payment::new(
+            id.to_string(),
+            PaymentType::Sent,
+            1001,
+            100,
+            0,
+            status,
+            PaymentDetails::Ln {
+                data: LnPaymentDetails {
+                    payment_hash: id.to_string(),
+                    label: String::new(),
+                    destination_pubkey: "pubkey".to_string(),
+                    payment_preimage: String::new(),
+                    keysend: false,
+                    bolt11: "bolt11".to_string(),
+                    lnurl_success_action: None,
+                    lnurl_pay_domain: None,
+                    lnurl_pay_comment: None,
+                    lnurl_metadata: None,
+                    ln_address: None,
+                    lnurl_withdraw_endpoint: None,
+                    lnurl_verify_url: None,
+                    swap_info: None,
+                    reverse_swap_info: None,
+                    pending_expiration_block: None,
+                    open_channel_bolt11: None,
+                },
+            },
+        )
+    };
+    let storage = SqliteStorage::new(test_utils::create_test_sql_dir());
+    storage.init()?;
+    storage.insert_or_update_payments(&[ln_payment("123", PaymentStatus::Complete)], false)?;
+    storage.insert_payment_external_info(
+        "123",
+        PaymentExternalInfo {
+            lnurl_pay_success_action: None,
+            lnurl_pay_domain: None,
+            lnurl_pay_comment: None,
+            lnurl_metadata: Some(
+                r#"[["text/plain","Pizza for two"],["image/png;base64","iVBORw0KGgo"]]"#
+                    .to_string(),
+            ),
+            ln_address: None,
+            lnurl_withdraw_endpoint: None,
+            attempted_amount_msat: None,
+            attempted_error: None,
+            bolt12_offer: None,
+            bolt12_payer_note: None,
+            lnurl_verify_url: None,
+        },
+    )?;
+    let search_count = |search: &str| {
+        storage.count_payments(&ListPaymentsRequest {
+            search: Some(search.to_string()),
+            ..Default::default()
+        })
+    };
+    // Only the text entries of the LNURL metadata are searchable
+    assert_eq!(search_count("pizza")?, 1);
+    assert_eq!(search_count("iVBORw0KGgo")?, 0);
+    assert_eq!(search_count("text")?, 0);
+
+    // The pending payments deleted on sync are removed from the search index
+    storage.insert_or_update_payments(&[ln_payment("456", PaymentStatus::Pending)], false)?;
+    storage.insert_or_update_payments(&[], true)?;
+    let indexed: u32 = storage.get_connection()?.query_row(
+        "SELECT COUNT(*) FROM payments_search WHERE payment_id = '456'",
+        [],
+        |row| row.get(0),
+    )?;
+    assert_eq!(indexed, 0);
+    assert_eq!(search_count("pizza")?, 1);
+    Ok(())
+}
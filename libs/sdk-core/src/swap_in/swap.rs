@@ -1,3 +1,4 @@
+use std::cmp::Reverse;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -5,32 +6,46 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use anyhow::{anyhow, Result};
 use rand::Rng;
 use ripemd::{Digest, Ripemd160};
-use sdk_common::grpc::{AddFundInitRequest, GetSwapPaymentRequest};
-use sdk_common::prelude::BreezServer;
+use sdk_common::grpc::{
+    AddFundInitRequest, CooperativeRefundRequest, GetCooperativeClaimRequest,
+    GetSwapPaymentRequest, SignCooperativeClaimRequest,
+};
+use sdk_common::prelude::{parse_invoice, BreezServer};
 use tokio::sync::broadcast;
 
 use crate::bitcoin::blockdata::constants::WITNESS_SCALE_FACTOR;
 use crate::bitcoin::blockdata::opcodes;
 use crate::bitcoin::blockdata::script::Builder;
 use crate::bitcoin::consensus::encode;
-use crate::bitcoin::hashes::sha256;
+use crate::bitcoin::hashes::{sha256, Hash};
 use crate::bitcoin::psbt::serialize::Serialize;
-use crate::bitcoin::secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
-use crate::bitcoin::util::sighash::SighashCache;
+use crate::bitcoin::secp256k1::{KeyPair, Message, PublicKey, Secp256k1, SecretKey};
+use crate::bitcoin::util::sighash::{Prevouts, SchnorrSighashType, SighashCache};
 use crate::bitcoin::{
-    Address, EcdsaSighashType, Script, Sequence, Transaction, TxIn, TxOut, Witness,
+    Address, EcdsaSighashType, OutPoint, Script, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
 };
 use crate::breez_services::{BreezEvent, OpenChannelParams, Receiver};
-use crate::chain::{get_total_incoming_txs, get_utxos, AddressUtxos, ChainService};
+use crate::chain::{
+    cpfp_child_fee_rate, get_total_incoming_txs, get_utxos, replacement_fee, validate_replacement,
+    AddressUtxos, ChainService, OnchainTx, Utxo,
+};
 use crate::error::ReceivePaymentError;
-use crate::models::{Swap, SwapInfo, SwapStatus, SwapperAPI};
+use crate::models::{
+    CooperativeClaim, CooperativeClaimSignature, CooperativeRefundSignature, Swap, SwapInfo,
+    SwapScriptVersion, SwapStatus, SwapperAPI,
+};
 use crate::node_api::NodeAPI;
 use crate::persist::error::PersistResult;
+use crate::persist::replacements::TxReplacement;
 use crate::persist::swap::SwapChainInfo;
 use crate::swap_in::error::SwapError;
+use crate::swap_in::taproot::TaprootSwap;
 use crate::{
-    models::OpeningFeeParams, PrepareRefundRequest, PrepareRefundResponse, ReceivePaymentRequest,
-    RefundRequest, RefundResponse, SWAP_PAYMENT_FEE_EXPIRY_SECONDS,
+    models::OpeningFeeParams, AccelerateSwapDepositRequest, AccelerateSwapDepositResponse,
+    PrepareAccelerateSwapDepositRequest, PrepareAccelerateSwapDepositResponse,
+    PrepareRefundAllRequest, PrepareRefundRequest, PrepareRefundResponse, ReceivePaymentRequest,
+    RefundAllRequest, RefundRequest, RefundResponse, SwapDepositAccelerationSource,
+    SWAP_PAYMENT_FEE_EXPIRY_SECONDS,
 };
 
 use super::error::SwapResult;
@@ -42,13 +57,19 @@ impl SwapperAPI for BreezServer {
         hash: Vec<u8>,
         payer_pubkey: Vec<u8>,
         node_id: String,
+        script_version: SwapScriptVersion,
     ) -> SwapResult<Swap> {
         let mut fund_client = self.get_swapper_client().await;
+        let script_version = match script_version {
+            SwapScriptVersion::P2wsh => crate::grpc::SwapScriptVersion::P2wsh,
+            SwapScriptVersion::Taproot => crate::grpc::SwapScriptVersion::Taproot,
+        };
         let req = AddFundInitRequest {
             hash: hash.clone(),
             pubkey: payer_pubkey.clone(),
             node_id,
             notification_token: "".to_string(),
+            script_version: script_version as i32,
         };
 
         let result = fund_client.add_fund_init(req).await?.into_inner();
@@ -79,8 +100,80 @@ impl SwapperAPI for BreezServer {
             err => Err(anyhow!("Failed to complete swap: {}", err.as_str_name())),
         }
     }
+
+    async fn get_cooperative_claim(&self, bitcoin_address: String) -> Result<CooperativeClaim> {
+        let req = GetCooperativeClaimRequest {
+            address: bitcoin_address,
+        };
+        let resp = self
+            .get_swapper_client()
+            .await
+            .get_cooperative_claim(req)
+            .await?
+            .into_inner();
+
+        Ok(CooperativeClaim {
+            claim_tx: resp.transaction,
+            swapper_pub_nonces: resp.pub_nonces,
+        })
+    }
+
+    async fn sign_cooperative_claim(
+        &self,
+        bitcoin_address: String,
+        signatures: Vec<CooperativeClaimSignature>,
+    ) -> Result<()> {
+        let req = SignCooperativeClaimRequest {
+            address: bitcoin_address,
+            signatures: signatures
+                .into_iter()
+                .map(
+                    |signature| crate::grpc::sign_cooperative_claim_request::InputSignature {
+                        pub_nonce: signature.pub_nonce,
+                        partial_signature: signature.partial_signature,
+                    },
+                )
+                .collect(),
+        };
+        self.get_swapper_client()
+            .await
+            .sign_cooperative_claim(req)
+            .await?;
+        Ok(())
+    }
+
+    async fn request_cooperative_refund(
+        &self,
+        bitcoin_address: String,
+        refund_tx: Vec<u8>,
+        pub_nonces: Vec<Vec<u8>>,
+    ) -> Result<Vec<CooperativeRefundSignature>> {
+        let req = CooperativeRefundRequest {
+            address: bitcoin_address,
+            transaction: refund_tx,
+            pub_nonces,
+        };
+        let resp = self
+            .get_swapper_client()
+            .await
+            .cooperative_refund(req)
+            .await?
+            .into_inner();
+
+        Ok(resp
+            .signatures
+            .into_iter()
+            .map(|signature| CooperativeRefundSignature {
+                signature: signature.signature,
+                pub_nonce: signature.pub_nonce,
+            })
+            .collect())
+    }
 }
 
+/// The smallest output the node wallet is left with after accelerating a deposit
+const DUST_LIMIT_SAT: u64 = 546;
+
 /// This struct is responsible for handling on-chain funds with lightning payments.
 /// It uses internally an implementation of SwapperAPI that represents the actually swapper service.
 pub(crate) struct BTCReceiveSwap {
@@ -163,6 +256,18 @@ impl BTCReceiveSwap {
                             new_status,
                         )?;
                         self.emit_swap_updated(&swap_info.bitcoin_address)?;
+
+                        // The payment is settled, so we let the swapper claim the funds through
+                        // the key path. If this fails, it can still claim them through the
+                        // script path with the preimage.
+                        if swap_info.script_version == SwapScriptVersion::Taproot {
+                            if let Err(err) = self.sign_cooperative_claim(&swap_info).await {
+                                warn!(
+                                    "failed to sign cooperative claim for swap {}: {err}",
+                                    swap_info.bitcoin_address
+                                );
+                            }
+                        }
                     }
                 }
             }
@@ -176,6 +281,7 @@ impl BTCReceiveSwap {
     pub(crate) async fn create_swap_address(
         &self,
         channel_opening_fees: OpeningFeeParams,
+        script_version: SwapScriptVersion,
     ) -> SwapResult<SwapInfo> {
         let node_state = self
             .persister
@@ -190,8 +296,12 @@ impl BTCReceiveSwap {
             )
         };
 
-        // check first that we don't already have an unused swap
-        if let Some(unused_swap) = self.list_unused()?.first().cloned() {
+        // check first that we don't already have an unused swap of the same type
+        if let Some(unused_swap) = self
+            .list_unused()?
+            .into_iter()
+            .find(|s| s.script_version == script_version)
+        {
             info!("Found unused swap when trying to create new swap address");
             let bitcoin_address = unused_swap.bitcoin_address.clone();
 
@@ -224,18 +334,37 @@ impl BTCReceiveSwap {
         // use swap API to fetch a new swap address
         let swap_reply = self
             .swapper_api
-            .create_swap(hash.clone(), pubkey.clone(), node_state.id.clone())
+            .create_swap(
+                hash.clone(),
+                pubkey.clone(),
+                node_state.id.clone(),
+                script_version,
+            )
             .await?;
         info!("created swap address {}", swap_reply.bitcoin_address);
         // calculate the submarine swap script
-        let our_script = create_submarine_swap_script(
-            hash.clone(),
-            swap_reply.swapper_pubkey.clone(),
-            pubkey.clone(),
-            swap_reply.lock_height,
-        )?;
-
-        let address = Address::p2wsh(&our_script, self.network);
+        let (our_script, address) = match script_version {
+            SwapScriptVersion::P2wsh => {
+                let script = create_submarine_swap_script(
+                    hash.clone(),
+                    swap_reply.swapper_pubkey.clone(),
+                    pubkey.clone(),
+                    swap_reply.lock_height,
+                )?;
+                let address = Address::p2wsh(&script, self.network);
+                (script, address)
+            }
+            SwapScriptVersion::Taproot => {
+                let taproot_swap = TaprootSwap::new(
+                    &hash,
+                    &swap_reply.swapper_pubkey,
+                    &pubkey,
+                    swap_reply.lock_height,
+                )?;
+                let address = taproot_swap.address(self.network);
+                (taproot_swap.refund_script, address)
+            }
+        };
         let address_str = address.to_string();
 
         // Ensure our address generation match the service
@@ -256,6 +385,7 @@ impl BTCReceiveSwap {
             public_key: pubkey.clone(),
             swapper_public_key: swap_reply.swapper_pubkey.clone(),
             script: our_script.as_bytes().to_vec(),
+            script_version,
             bolt11: None,
             paid_msat: 0,
             unconfirmed_sats: 0,
@@ -474,6 +604,7 @@ impl BTCReceiveSwap {
                         use_description_hash: Some(false),
                         expiry: Some(SWAP_PAYMENT_FEE_EXPIRY_SECONDS),
                         cltv: None,
+                        fiat_amount: None,
                     })
                     .await;
 
@@ -496,7 +627,7 @@ impl BTCReceiveSwap {
                             None => {
                                 let res = self
                                     .node_api
-                                    .fetch_bolt11(swap_info.payment_hash)
+                                    .fetch_bolt11(swap_info.payment_hash.clone())
                                     .await?
                                     .ok_or(anyhow!(
                                         "Preimage already known, but invoice not found"
@@ -526,7 +657,7 @@ impl BTCReceiveSwap {
 
                 // If we have a new invoice, created or fetched from GL, associate it with the swap
                 self.persister
-                    .update_swap_bolt11(bitcoin_address, new_bolt11.clone())?;
+                    .update_swap_bolt11(bitcoin_address.clone(), new_bolt11.clone())?;
                 self.emit_swap_updated(&swap_info.bitcoin_address)?;
                 new_bolt11
             }
@@ -536,6 +667,46 @@ impl BTCReceiveSwap {
         self.swapper_api.complete_swap(bolt11).await
     }
 
+    /// Co-signs the swapper's key-path claim of a Taproot swap, once its payment is settled.
+    ///
+    /// The claim transaction comes from the swapper, so it's only signed if it spends deposits
+    /// of the swap worth no more than the paid invoice. The sighashes are computed from it.
+    async fn sign_cooperative_claim(&self, swap_info: &SwapInfo) -> Result<()> {
+        let bolt11 = swap_info
+            .bolt11
+            .as_ref()
+            .ok_or(anyhow!("Swap {} has no invoice", swap_info.bitcoin_address))?;
+        let paid_msat = parse_invoice(bolt11)?
+            .amount_msat
+            .ok_or(anyhow!("Swap invoice has no amount"))?;
+        let taproot_swap = TaprootSwap::new(
+            &swap_info.payment_hash,
+            &swap_info.swapper_public_key,
+            &swap_info.public_key,
+            swap_info.lock_height,
+        )?;
+
+        let claim = self
+            .swapper_api
+            .get_cooperative_claim(swap_info.bitcoin_address.clone())
+            .await?;
+        let claim_tx: Transaction = encode::deserialize(&claim.claim_tx)?;
+        let utxos = self
+            .get_address_utxos(swap_info.bitcoin_address.clone())
+            .await?;
+        let prevouts = cooperative_claim_prevouts(&claim_tx, &utxos, &taproot_swap, paid_msat)?;
+        let sighashes = taproot_key_spend_sighashes(&claim_tx, &prevouts)?;
+
+        let signatures = taproot_swap.sign_cooperative_claim(
+            &swap_info.private_key,
+            &sighashes,
+            claim.swapper_pub_nonces,
+        )?;
+        self.swapper_api
+            .sign_cooperative_claim(swap_info.bitcoin_address.clone(), signatures)
+            .await
+    }
+
     pub(crate) async fn prepare_refund_swap(
         &self,
         req: PrepareRefundRequest,
@@ -546,7 +717,7 @@ impl BTCReceiveSwap {
 
         let refund_tx = prepare_refund_tx(&utxos, req.to_address, swap_info.lock_height as u32)?;
 
-        let refund_tx_weight = compute_refund_tx_weight(&refund_tx);
+        let refund_tx_weight = compute_refund_tx_weight(&refund_tx, swap_info.script_version);
         let refund_tx_fee_sat = compute_tx_fee(refund_tx_weight, req.sat_per_vbyte);
         Ok(PrepareRefundResponse {
             refund_tx_weight,
@@ -560,26 +731,514 @@ impl BTCReceiveSwap {
 
         let utxos = self.get_address_utxos(req.swap_address.clone()).await?;
 
-        let script = create_submarine_swap_script(
-            swap_info.payment_hash,
-            swap_info.swapper_public_key,
-            swap_info.public_key,
+        let refund_tx = match swap_info.script_version {
+            SwapScriptVersion::P2wsh => {
+                let script = create_submarine_swap_script(
+                    swap_info.payment_hash,
+                    swap_info.swapper_public_key,
+                    swap_info.public_key,
+                    swap_info.lock_height,
+                )?;
+                create_refund_tx(
+                    utxos.clone(),
+                    swap_info.private_key,
+                    req.to_address,
+                    swap_info.lock_height as u32,
+                    &script,
+                    req.sat_per_vbyte,
+                )?
+            }
+            SwapScriptVersion::Taproot => {
+                let taproot_swap = TaprootSwap::new(
+                    &swap_info.payment_hash,
+                    &swap_info.swapper_public_key,
+                    &swap_info.public_key,
+                    swap_info.lock_height,
+                )?;
+                create_taproot_refund_tx(
+                    utxos.clone(),
+                    swap_info.private_key,
+                    req.to_address,
+                    swap_info.lock_height as u32,
+                    &taproot_swap,
+                    req.sat_per_vbyte,
+                )?
+            }
+        };
+        self.broadcast_refund_tx(req.swap_address, refund_tx).await
+    }
+
+    pub(crate) async fn prepare_refund_all_swaps(
+        &self,
+        req: PrepareRefundAllRequest,
+    ) -> Result<PrepareRefundResponse> {
+        let refunds = self.get_swap_refunds(req.swap_addresses).await?;
+
+        let refund_tx = prepare_batch_refund_tx(&refunds, req.to_address)?;
+
+        let refund_tx_weight = compute_batch_refund_tx_weight(&refund_tx, &refunds);
+        let refund_tx_fee_sat = compute_tx_fee(refund_tx_weight, req.sat_per_vbyte);
+        Ok(PrepareRefundResponse {
+            refund_tx_weight,
+            refund_tx_fee_sat,
+        })
+    }
+
+    /// Refunds several swaps in a single transaction, so the fees are only paid once.
+    pub(crate) async fn refund_all_swaps(&self, req: RefundAllRequest) -> Result<RefundResponse> {
+        let refunds = self.get_swap_refunds(req.swap_addresses).await?;
+
+        let refund_tx = create_batch_refund_tx(&refunds, req.to_address, req.sat_per_vbyte)?;
+        let tx_id = self.broadcast_batch_refund_tx(refunds, refund_tx).await?;
+        Ok(RefundResponse {
+            refund_tx_id: tx_id,
+        })
+    }
+
+    /// Replaces an unconfirmed refund transaction by one paying the given fee rate (RBF).
+    ///
+    /// Returns the txid of the replacement, or `None` if the transaction doesn't refund any swap.
+    pub(crate) async fn bump_refund_tx(
+        &self,
+        txid: &str,
+        sat_per_vbyte: u32,
+    ) -> Result<Option<String>> {
+        let swaps: Vec<SwapInfo> = self
+            .persister
+            .list_swaps()?
+            .into_iter()
+            .filter(|swap| swap.refund_tx_ids.iter().any(|id| id == txid))
+            .collect();
+        if swaps.is_empty() {
+            return Ok(None);
+        }
+
+        let replaced_tx = self
+            .chain_service
+            .address_transactions(swaps[0].bitcoin_address.clone())
+            .await?
+            .into_iter()
+            .find(|tx| tx.txid == txid)
+            .ok_or(anyhow!("Refund tx {txid} was not found"))?;
+        let to_address = replaced_tx
+            .vout
+            .first()
+            .map(|vout| vout.scriptpubkey_address.clone())
+            .ok_or(anyhow!("Refund tx {txid} has no outputs"))?;
+
+        // The refunded utxos are still unspent, as the replaced tx isn't confirmed
+        let mut refunds = Vec::new();
+        for swap_info in swaps {
+            let utxos = self
+                .get_address_utxos(swap_info.bitcoin_address.clone())
+                .await?;
+            refunds.push(SwapRefund { swap_info, utxos });
+        }
+
+        // Cooperative refunds are Taproot key-path spends, which don't wait for the lock height
+        // of the swap. Any other refund is replaced by a timelocked one.
+        let cooperative = replaced_tx.vin.iter().all(|vin| vin.sequence == 0);
+        let refund_tx = match (cooperative, refunds.as_slice()) {
+            (true, [refund]) if refund.swap_info.script_version == SwapScriptVersion::Taproot => {
+                self.create_cooperative_refund_tx(
+                    refund.swap_info.clone(),
+                    &refund.utxos,
+                    to_address,
+                    sat_per_vbyte,
+                )
+                .await?
+            }
+            (true, _) => {
+                return Err(anyhow!(
+                    "Refund tx {txid} is not the key-path refund of a single Taproot swap"
+                ))
+            }
+            (false, _) => create_batch_refund_tx(&refunds, to_address, sat_per_vbyte)?,
+        };
+        let replacement: Transaction = encode::deserialize(&refund_tx)?;
+        validate_replacement(
+            &replaced_tx,
+            replacement_fee(&replaced_tx, &replacement)?,
+            replacement.weight() as u64,
+        )?;
+
+        info!("broadcasting refund tx {:?}", hex::encode(&refund_tx));
+        let tx_id = self.chain_service.broadcast_transaction(refund_tx).await?;
+        self.persister.insert_tx_replacement(&TxReplacement::new(
+            tx_id.clone(),
+            txid,
+            sat_per_vbyte,
+        ))?;
+        self.persist_refund_tx(refunds, &tx_id)?;
+        Ok(Some(tx_id))
+    }
+
+    /// Broadcasts a refund transaction spending the funds of the given swaps
+    async fn broadcast_batch_refund_tx(
+        &self,
+        refunds: Vec<SwapRefund>,
+        refund_tx: Vec<u8>,
+    ) -> Result<String> {
+        info!("broadcasting batch refund tx {:?}", hex::encode(&refund_tx));
+        let tx_id = self.chain_service.broadcast_transaction(refund_tx).await?;
+        self.persist_refund_tx(refunds, &tx_id)?;
+        Ok(tx_id)
+    }
+
+    fn persist_refund_tx(&self, refunds: Vec<SwapRefund>, tx_id: &str) -> Result<()> {
+        for refund in refunds {
+            let bitcoin_address = refund.swap_info.bitcoin_address;
+            self.persister
+                .insert_swap_refund_tx_ids(bitcoin_address.clone(), tx_id.to_string())?;
+            self.emit_swap_updated(&bitcoin_address)?;
+        }
+        Ok(())
+    }
+
+    /// Fetches the confirmed funds of the given swaps, or of all the refundable swaps if none are
+    /// given. Swaps without confirmed funds are skipped.
+    async fn get_swap_refunds(
+        &self,
+        swap_addresses: Option<Vec<String>>,
+    ) -> Result<Vec<SwapRefund>> {
+        let swaps = match swap_addresses {
+            Some(swap_addresses) => {
+                let mut swaps = Vec::new();
+                for swap_address in swap_addresses {
+                    let swap_info = self.get_swap_info_ok(swap_address.clone())?;
+                    if !swap_info.refundable() {
+                        return Err(anyhow!("Swap address {swap_address} is not refundable"));
+                    }
+                    swaps.push(swap_info);
+                }
+                swaps
+            }
+            None => self.list_refundables()?,
+        };
+
+        let mut refunds = Vec::new();
+        for swap_info in swaps {
+            let utxos = self
+                .get_address_utxos(swap_info.bitcoin_address.clone())
+                .await?;
+            if !utxos.confirmed.is_empty() {
+                refunds.push(SwapRefund { swap_info, utxos });
+            }
+        }
+        if refunds.is_empty() {
+            return Err(anyhow!("No confirmed funds to refund"));
+        }
+        Ok(refunds)
+    }
+
+    pub(crate) async fn prepare_accelerate_swap_deposit(
+        &self,
+        req: PrepareAccelerateSwapDepositRequest,
+    ) -> Result<PrepareAccelerateSwapDepositResponse> {
+        let acceleration = self
+            .plan_deposit_acceleration(
+                req.swap_address,
+                req.target_sat_per_vbyte,
+                req.refund_address,
+            )
+            .await?;
+        Ok(PrepareAccelerateSwapDepositResponse {
+            source: acceleration.source(),
+            child_tx_weight: acceleration.child_tx_weight,
+            child_tx_fee_sat: acceleration.child_tx_fee_sat,
+        })
+    }
+
+    /// Broadcasts a transaction spending the unconfirmed deposits of a swap, paying enough fees
+    /// for the deposits to confirm at the target fee rate (CPFP)
+    pub(crate) async fn accelerate_swap_deposit(
+        &self,
+        req: AccelerateSwapDepositRequest,
+    ) -> Result<AccelerateSwapDepositResponse> {
+        let acceleration = self
+            .plan_deposit_acceleration(
+                req.swap_address.clone(),
+                req.target_sat_per_vbyte,
+                req.refund_address,
+            )
+            .await?;
+        let txid = match acceleration.funding {
+            DepositAccelerationFunding::NodeFunds { utxos, to_address } => hex::encode(
+                self.node_api
+                    .cpfp_onchain_funds(utxos, to_address, acceleration.child_sat_per_vbyte)
+                    .await?,
+            ),
+            DepositAccelerationFunding::SwapOutput {
+                swap_info,
+                utxos,
+                refund_address,
+            } => {
+                let refund_tx = self
+                    .create_cooperative_refund_tx(
+                        swap_info,
+                        &utxos,
+                        refund_address,
+                        acceleration.child_sat_per_vbyte,
+                    )
+                    .await?;
+                self.broadcast_refund_tx(req.swap_address, refund_tx)
+                    .await?
+                    .refund_tx_id
+            }
+        };
+        Ok(AccelerateSwapDepositResponse { txid })
+    }
+
+    /// Picks the funds of the child transaction, preferring the node funds in the deposits over
+    /// refunding them
+    async fn plan_deposit_acceleration(
+        &self,
+        swap_address: String,
+        target_sat_per_vbyte: u32,
+        refund_address: Option<String>,
+    ) -> Result<DepositAcceleration> {
+        let swap_info = self.get_swap_info_ok(swap_address.clone())?;
+        let deposits: Vec<OnchainTx> = self
+            .chain_service
+            .address_transactions(swap_address.clone())
+            .await?
+            .into_iter()
+            .filter(|tx| {
+                !tx.status.confirmed
+                    && tx
+                        .vout
+                        .iter()
+                        .any(|vout| vout.scriptpubkey_address == swap_address)
+            })
+            .collect();
+        if deposits.is_empty() {
+            return Err(anyhow!("Swap {swap_address} has no unconfirmed deposits"));
+        }
+
+        if let Some(acceleration) =
+            self.plan_node_funded_acceleration(&deposits, target_sat_per_vbyte)?
+        {
+            return Ok(acceleration);
+        }
+
+        // Refunding the deposits before they confirm is only possible through the key path of a
+        // Taproot swap, with the swapper co-signing
+        if swap_info.script_version != SwapScriptVersion::Taproot {
+            return Err(anyhow!(
+                "Swap {swap_address} has no node funds to accelerate it"
+            ));
+        }
+        let refund_address = refund_address.ok_or_else(|| {
+            anyhow!("Swap {swap_address} has no node funds to accelerate it, a refund address is needed")
+        })?;
+        if swap_info.paid_msat > 0 {
+            return Err(anyhow!("Swap {swap_address} was already paid"));
+        }
+        let unconfirmed = self.get_address_utxos(swap_address).await?.unconfirmed;
+        // The key-path refund isn't timelocked, so the deposits are spent as they are
+        let utxos = AddressUtxos {
+            confirmed: unconfirmed
+                .into_iter()
+                .map(|utxo| Utxo {
+                    block_height: Some(0),
+                    ..utxo
+                })
+                .collect(),
+            unconfirmed: vec![],
+        };
+        let child_tx = prepare_refund_tx(&utxos, refund_address.clone(), 0)?;
+        let child_tx_weight = compute_cooperative_refund_tx_weight(&child_tx);
+        let child_sat_per_vbyte =
+            cpfp_child_fee_rate(&deposits, child_tx_weight, target_sat_per_vbyte)?;
+        let child_tx_fee_sat = compute_tx_fee(child_tx_weight, child_sat_per_vbyte);
+        if child_tx_fee_sat >= utxos.confirmed_sats() {
+            return Err(anyhow!("Insufficient funds to pay fees"));
+        }
+
+        Ok(DepositAcceleration {
+            funding: DepositAccelerationFunding::SwapOutput {
+                swap_info,
+                utxos,
+                refund_address,
+            },
+            child_tx_weight,
+            child_tx_fee_sat,
+            child_sat_per_vbyte,
+        })
+    }
+
+    /// Plans a child transaction spending the node outputs of every deposit, adding other onchain
+    /// funds of the node if those can't pay the fees
+    fn plan_node_funded_acceleration(
+        &self,
+        deposits: &[OnchainTx],
+        target_sat_per_vbyte: u32,
+    ) -> Result<Option<DepositAcceleration>> {
+        let node_utxos = match self.persister.get_node_state()? {
+            Some(node_state) => node_state.utxos,
+            None => return Ok(None),
+        };
+        let (mut inputs, mut others): (Vec<_>, Vec<_>) = node_utxos
+            .into_iter()
+            .filter(|utxo| !utxo.reserved)
+            .partition(|utxo| {
+                let txid = hex::encode(&utxo.txid);
+                deposits.iter().any(|tx| tx.txid == txid)
+            });
+        let spends_all_deposits = deposits
+            .iter()
+            .all(|tx| inputs.iter().any(|utxo| hex::encode(&utxo.txid) == tx.txid));
+        if !spends_all_deposits {
+            return Ok(None);
+        }
+
+        // The funds stay in the node wallet
+        let to_address = inputs[0].address.clone();
+        others.sort_by_key(|utxo| Reverse(utxo.amount_millisatoshi));
+        let mut others = others.into_iter();
+        loop {
+            let child_tx_weight = compute_node_cpfp_tx_weight(inputs.len(), &to_address)?;
+            let child_sat_per_vbyte =
+                cpfp_child_fee_rate(deposits, child_tx_weight, target_sat_per_vbyte)?;
+            let child_tx_fee_sat = compute_tx_fee(child_tx_weight, child_sat_per_vbyte);
+            let inputs_sat: u64 = inputs
+                .iter()
+                .map(|utxo| utxo.amount_millisatoshi / 1_000)
+                .sum();
+            if inputs_sat >= child_tx_fee_sat + DUST_LIMIT_SAT {
+                let utxos = inputs
+                    .iter()
+                    .map(|utxo| {
+                        Ok(OutPoint {
+                            txid: Txid::from_str(&hex::encode(&utxo.txid))?,
+                            vout: utxo.outnum,
+                        })
+                    })
+                    .collect::<Result<Vec<OutPoint>>>()?;
+                return Ok(Some(DepositAcceleration {
+                    funding: DepositAccelerationFunding::NodeFunds { utxos, to_address },
+                    child_tx_weight,
+                    child_tx_fee_sat,
+                    child_sat_per_vbyte,
+                }));
+            }
+
+            match others.next() {
+                Some(utxo) => inputs.push(utxo),
+                None => return Err(anyhow!("Insufficient node funds to pay fees")),
+            }
+        }
+    }
+
+    /// Refunds an unpaid Taproot swap right away, with the swapper co-signing the refund
+    /// transaction.
+    ///
+    /// If the swapper doesn't cooperate and the swap lock height has already passed, this falls
+    /// back to the timelocked refund of [BTCReceiveSwap::refund_swap].
+    pub(crate) async fn request_cooperative_refund(
+        &self,
+        req: RefundRequest,
+    ) -> Result<RefundResponse> {
+        match self.cooperative_refund_swap(&req).await {
+            Ok(res) => Ok(res),
+            Err(err) => {
+                let swap_info = self.get_swap_info_ok(req.swap_address.clone())?;
+                match swap_info.refundable() {
+                    true => {
+                        warn!(
+                            "cooperative refund of swap {} failed, using the timelocked refund: {err}",
+                            req.swap_address
+                        );
+                        self.refund_swap(req).await
+                    }
+                    false => Err(err),
+                }
+            }
+        }
+    }
+
+    async fn cooperative_refund_swap(&self, req: &RefundRequest) -> Result<RefundResponse> {
+        let swap_info = self.get_swap_info_ok(req.swap_address.clone())?;
+        if swap_info.paid_msat > 0 {
+            return Err(anyhow!("Swap {} was already paid", req.swap_address));
+        }
+
+        let utxos = self.get_address_utxos(req.swap_address.clone()).await?;
+        let refund_tx = self
+            .create_cooperative_refund_tx(
+                swap_info,
+                &utxos,
+                req.to_address.clone(),
+                req.sat_per_vbyte,
+            )
+            .await?;
+        self.broadcast_refund_tx(req.swap_address.clone(), refund_tx)
+            .await
+    }
+
+    /// Creates a key-path refund transaction of a Taproot swap, co-signed by the swapper.
+    ///
+    /// P2WSH swaps can't be refunded cooperatively: the only branch of their script that isn't
+    /// timelocked is the claim branch, which would reveal the preimage of an unpaid swap.
+    async fn create_cooperative_refund_tx(
+        &self,
+        swap_info: SwapInfo,
+        utxos: &AddressUtxos,
+        to_address: String,
+        sat_per_vbyte: u32,
+    ) -> Result<Vec<u8>> {
+        if swap_info.script_version != SwapScriptVersion::Taproot {
+            return Err(anyhow!(
+                "Swap {} can only be refunded after its lock height",
+                swap_info.bitcoin_address
+            ));
+        }
+        let mut tx = prepare_cooperative_refund_tx(utxos, to_address, sat_per_vbyte)?;
+
+        let taproot_swap = TaprootSwap::new(
+            &swap_info.payment_hash,
+            &swap_info.swapper_public_key,
+            &swap_info.public_key,
             swap_info.lock_height,
         )?;
-        let refund_tx = create_refund_tx(
-            utxos.clone(),
-            swap_info.private_key,
-            req.to_address,
-            swap_info.lock_height as u32,
-            &script,
-            req.sat_per_vbyte,
+        let prevouts = taproot_prevouts(utxos, &taproot_swap);
+        let sighashes = taproot_key_spend_sighashes(&tx, &prevouts)?;
+        let secnonces = taproot_swap.refund_nonces(&swap_info.private_key, &sighashes)?;
+        let pub_nonces = secnonces
+            .iter()
+            .map(|secnonce| secnonce.public_nonce().serialize().to_vec())
+            .collect();
+        let swapper_signatures = self
+            .swapper_api
+            .request_cooperative_refund(
+                swap_info.bitcoin_address.clone(),
+                encode::serialize(&tx),
+                pub_nonces,
+            )
+            .await?;
+        let signatures = taproot_swap.aggregate_refund_signatures(
+            &swap_info.private_key,
+            &sighashes,
+            secnonces,
+            swapper_signatures,
         )?;
+        for (input, signature) in tx.input.iter_mut().zip(signatures) {
+            input.witness = Witness::from_vec(vec![signature]);
+        }
+        Ok(encode::serialize(&tx))
+    }
+
+    async fn broadcast_refund_tx(
+        &self,
+        bitcoin_address: String,
+        refund_tx: Vec<u8>,
+    ) -> Result<RefundResponse> {
         info!("broadcasting refund tx {:?}", hex::encode(&refund_tx));
         let tx_id = self.chain_service.broadcast_transaction(refund_tx).await?;
 
         self.persister
-            .insert_swap_refund_tx_ids(swap_info.bitcoin_address, tx_id.clone())?;
-        self.emit_swap_updated(&req.swap_address)?;
+            .insert_swap_refund_tx_ids(bitcoin_address.clone(), tx_id.clone())?;
+        self.emit_swap_updated(&bitcoin_address)?;
 
         Ok(RefundResponse {
             refund_tx_id: tx_id,
@@ -665,17 +1324,90 @@ pub(crate) fn create_submarine_swap_script(
         .into_script())
 }
 
-fn compute_refund_tx_weight(tx: &Transaction) -> u32 {
-    #[allow(clippy::identity_op)] // Allow "+ 0" term in sum below for clarity
-    let refund_witness_input_size: u32 = 1 + 1 + 73 + 1 + 0 + 1 + 100;
+#[allow(clippy::identity_op)] // Allow "+ 0" term in sum below for clarity
+fn refund_witness_input_size(script_version: SwapScriptVersion) -> u32 {
+    match script_version {
+        SwapScriptVersion::P2wsh => 1 + 1 + 73 + 1 + 0 + 1 + 100,
+        // Schnorr signature, refund leaf and the control block of a depth 1 leaf
+        SwapScriptVersion::Taproot => 1 + 1 + 64 + 1 + 41 + 1 + 65,
+    }
+}
+
+fn compute_refund_tx_weight(tx: &Transaction, script_version: SwapScriptVersion) -> u32 {
+    tx.strippedsize() as u32 * WITNESS_SCALE_FACTOR as u32
+        + refund_witness_input_size(script_version) * tx.input.len() as u32
+}
+
+fn compute_batch_refund_tx_weight(tx: &Transaction, refunds: &[SwapRefund]) -> u32 {
+    let witness_size: u32 = refunds
+        .iter()
+        .map(|refund| {
+            refund_witness_input_size(refund.swap_info.script_version)
+                * refund.utxos.confirmed.len() as u32
+        })
+        .sum();
+    tx.strippedsize() as u32 * WITNESS_SCALE_FACTOR as u32 + witness_size
+}
+
+fn compute_cooperative_refund_tx_weight(tx: &Transaction) -> u32 {
+    // Aggregated key-path signature
+    let refund_witness_input_size: u32 = 1 + 1 + 64;
     tx.strippedsize() as u32 * WITNESS_SCALE_FACTOR as u32
         + refund_witness_input_size * tx.input.len() as u32
 }
 
+/// The weight of a transaction spending outputs of the node wallet to one of its addresses
+fn compute_node_cpfp_tx_weight(inputs: usize, to_address: &str) -> Result<u32> {
+    let tx = Transaction {
+        version: 2,
+        lock_time: crate::bitcoin::PackedLockTime(0),
+        input: vec![TxIn::default(); inputs],
+        output: vec![TxOut {
+            value: 0,
+            script_pubkey: Address::from_str(to_address)?.script_pubkey(),
+        }],
+    };
+    // Same witness size of the wallet inputs as when redeeming the onchain funds
+    Ok(tx.strippedsize() as u32 * WITNESS_SCALE_FACTOR as u32 + 110 * inputs as u32)
+}
+
 fn compute_tx_fee(tx_weight: u32, sat_per_vbyte: u32) -> u64 {
     (tx_weight * sat_per_vbyte / WITNESS_SCALE_FACTOR as u32) as u64
 }
 
+/// A child transaction accelerating the unconfirmed deposits of a swap (CPFP)
+struct DepositAcceleration {
+    funding: DepositAccelerationFunding,
+    child_tx_weight: u32,
+    child_tx_fee_sat: u64,
+    child_sat_per_vbyte: u32,
+}
+
+enum DepositAccelerationFunding {
+    NodeFunds {
+        utxos: Vec<OutPoint>,
+        to_address: String,
+    },
+    SwapOutput {
+        swap_info: SwapInfo,
+        utxos: AddressUtxos,
+        refund_address: String,
+    },
+}
+
+impl DepositAcceleration {
+    fn source(&self) -> SwapDepositAccelerationSource {
+        match self.funding {
+            DepositAccelerationFunding::NodeFunds { .. } => {
+                SwapDepositAccelerationSource::NodeFunds
+            }
+            DepositAccelerationFunding::SwapOutput { .. } => {
+                SwapDepositAccelerationSource::SwapOutput
+            }
+        }
+    }
+}
+
 /// Prepare the refund transaction that is to be used by the user in case where the swap has
 /// expired
 fn prepare_refund_tx(
@@ -745,7 +1477,7 @@ fn create_refund_tx(
 
     let mut tx = prepare_refund_tx(&utxos, to_address, lock_delay)?;
 
-    let tx_weight = compute_refund_tx_weight(&tx);
+    let tx_weight = compute_refund_tx_weight(&tx, SwapScriptVersion::P2wsh);
     let fees = compute_tx_fee(tx_weight, sat_per_vbyte);
 
     if fees >= tx.output[0].value {
@@ -785,6 +1517,321 @@ fn create_refund_tx(
     Ok(encode::serialize(&tx))
 }
 
+/// Creating the refund transaction of a Taproot swap, spending through the refund leaf.
+fn create_taproot_refund_tx(
+    utxos: AddressUtxos,
+    private_key: Vec<u8>,
+    to_address: String,
+    lock_delay: u32,
+    taproot_swap: &TaprootSwap,
+    sat_per_vbyte: u32,
+) -> Result<Vec<u8>> {
+    info!("creating taproot refund tx sat_per_vbyte {}", sat_per_vbyte);
+
+    let mut tx = prepare_refund_tx(&utxos, to_address, lock_delay)?;
+
+    let tx_weight = compute_refund_tx_weight(&tx, SwapScriptVersion::Taproot);
+    let fees = compute_tx_fee(tx_weight, sat_per_vbyte);
+
+    if fees >= tx.output[0].value {
+        return Err(anyhow!("Insufficient funds to pay fees"));
+    }
+    tx.output[0].value -= fees;
+
+    let prevouts = taproot_prevouts(&utxos, taproot_swap);
+
+    let scpt = Secp256k1::new();
+    let key_pair = KeyPair::from_seckey_slice(&scpt, &private_key)?;
+    let leaf_hash = taproot_swap.refund_leaf_hash();
+    let control_block = taproot_swap.refund_control_block()?.serialize();
+
+    // go over all inputs and sign them
+    let mut witnesses: Vec<Witness> = Vec::new();
+    let mut signer = SighashCache::new(&tx);
+    for index in 0..tx.input.len() {
+        let sighash = signer.taproot_script_spend_signature_hash(
+            index,
+            &Prevouts::All(&prevouts),
+            leaf_hash,
+            SchnorrSighashType::Default,
+        )?;
+        let msg = Message::from_slice(&sighash[..])?;
+        let sig = scpt.sign_schnorr_no_aux_rand(&msg, &key_pair);
+
+        let witness: Vec<Vec<u8>> = vec![
+            sig.as_ref().to_vec(),
+            taproot_swap.refund_script.to_bytes(),
+            control_block.clone(),
+        ];
+        witnesses.push(Witness::from_vec(witness));
+    }
+    for (input, witness) in tx.input.iter_mut().zip(witnesses) {
+        input.witness = witness;
+    }
+
+    Ok(encode::serialize(&tx))
+}
+
+/// A swap refunded by a batch refund transaction, with its confirmed funds
+struct SwapRefund {
+    swap_info: SwapInfo,
+    utxos: AddressUtxos,
+}
+
+/// The script spent by the refund of a swap
+enum RefundScript {
+    P2wsh(Script),
+    Taproot(TaprootSwap),
+}
+
+impl RefundScript {
+    fn new(swap_info: &SwapInfo) -> Result<Self> {
+        match swap_info.script_version {
+            SwapScriptVersion::P2wsh => Ok(RefundScript::P2wsh(create_submarine_swap_script(
+                swap_info.payment_hash.clone(),
+                swap_info.swapper_public_key.clone(),
+                swap_info.public_key.clone(),
+                swap_info.lock_height,
+            )?)),
+            SwapScriptVersion::Taproot => Ok(RefundScript::Taproot(TaprootSwap::new(
+                &swap_info.payment_hash,
+                &swap_info.swapper_public_key,
+                &swap_info.public_key,
+                swap_info.lock_height,
+            )?)),
+        }
+    }
+
+    fn script_pubkey(&self) -> Script {
+        match self {
+            RefundScript::P2wsh(script) => script.to_v0_p2wsh(),
+            RefundScript::Taproot(taproot_swap) => taproot_swap.script_pubkey(),
+        }
+    }
+}
+
+/// Prepare a refund transaction spending the confirmed funds of several expired swaps. Each
+/// input waits for the lock height of its own swap.
+fn prepare_batch_refund_tx(refunds: &[SwapRefund], to_address: String) -> Result<Transaction> {
+    let mut lock_time = 0;
+    let mut confirmed_amount: u64 = 0;
+    let mut txins: Vec<TxIn> = Vec::new();
+    for refund in refunds {
+        let lock_delay = refund.swap_info.lock_height as u32;
+        for utxo in &refund.utxos.confirmed {
+            let confirmed_height = utxo.block_height.unwrap_or_default();
+            lock_time = std::cmp::max(lock_time, confirmed_height + lock_delay);
+            confirmed_amount += utxo.value;
+            txins.push(TxIn {
+                previous_output: utxo.out,
+                script_sig: Script::new(),
+                sequence: Sequence(lock_delay),
+                witness: Witness::default(),
+            });
+        }
+    }
+    if txins.is_empty() {
+        return Err(anyhow!("Must have at least one input"));
+    }
+
+    let btc_address = Address::from_str(&to_address)?;
+    let tx_out: Vec<TxOut> = vec![TxOut {
+        value: confirmed_amount,
+        script_pubkey: btc_address.payload.script_pubkey(),
+    }];
+
+    Ok(Transaction {
+        version: 2,
+        lock_time: crate::bitcoin::PackedLockTime(lock_time),
+        input: txins,
+        output: tx_out,
+    })
+}
+
+/// Creating the refund transaction of several expired swaps, signing each input through the
+/// refund path of its swap script.
+fn create_batch_refund_tx(
+    refunds: &[SwapRefund],
+    to_address: String,
+    sat_per_vbyte: u32,
+) -> Result<Vec<u8>> {
+    info!("creating batch refund tx sat_per_vbyte {}", sat_per_vbyte);
+
+    let mut tx = prepare_batch_refund_tx(refunds, to_address)?;
+
+    let tx_weight = compute_batch_refund_tx_weight(&tx, refunds);
+    let fees = compute_tx_fee(tx_weight, sat_per_vbyte);
+
+    if fees >= tx.output[0].value {
+        return Err(anyhow!("Insufficient funds to pay fees"));
+    }
+    tx.output[0].value -= fees;
+
+    let scripts = refunds
+        .iter()
+        .map(|refund| RefundScript::new(&refund.swap_info))
+        .collect::<Result<Vec<RefundScript>>>()?;
+
+    // The spent outputs and the signing key and script of each input, in the inputs order
+    let mut prevouts: Vec<TxOut> = Vec::new();
+    let mut input_signers: Vec<(&[u8], &RefundScript)> = Vec::new();
+    for (refund, script) in refunds.iter().zip(scripts.iter()) {
+        for utxo in &refund.utxos.confirmed {
+            prevouts.push(TxOut {
+                value: utxo.value,
+                script_pubkey: script.script_pubkey(),
+            });
+            input_signers.push((&refund.swap_info.private_key, script));
+        }
+    }
+
+    let scpt = Secp256k1::new();
+
+    // go over all inputs and sign them
+    let mut witnesses: Vec<Witness> = Vec::new();
+    let mut signer = SighashCache::new(&tx);
+    for (index, (private_key, script)) in input_signers.into_iter().enumerate() {
+        let witness: Vec<Vec<u8>> = match script {
+            RefundScript::P2wsh(input_script) => {
+                let sig = signer.segwit_signature_hash(
+                    index,
+                    input_script,
+                    prevouts[index].value,
+                    EcdsaSighashType::All,
+                )?;
+                let msg = Message::from_slice(&sig[..])?;
+                let secret_key = SecretKey::from_slice(private_key)?;
+                let sig = scpt.sign_ecdsa(&msg, &secret_key);
+
+                let mut sigvec = sig.serialize_der().to_vec();
+                sigvec.push(EcdsaSighashType::All as u8);
+                vec![sigvec, vec![], input_script.serialize()]
+            }
+            RefundScript::Taproot(taproot_swap) => {
+                let sighash = signer.taproot_script_spend_signature_hash(
+                    index,
+                    &Prevouts::All(&prevouts),
+                    taproot_swap.refund_leaf_hash(),
+                    SchnorrSighashType::Default,
+                )?;
+                let msg = Message::from_slice(&sighash[..])?;
+                let key_pair = KeyPair::from_seckey_slice(&scpt, private_key)?;
+                let sig = scpt.sign_schnorr_no_aux_rand(&msg, &key_pair);
+                vec![
+                    sig.as_ref().to_vec(),
+                    taproot_swap.refund_script.to_bytes(),
+                    taproot_swap.refund_control_block()?.serialize(),
+                ]
+            }
+        };
+        witnesses.push(Witness::from_vec(witness));
+    }
+    for (input, witness) in tx.input.iter_mut().zip(witnesses) {
+        input.witness = witness;
+    }
+
+    Ok(encode::serialize(&tx))
+}
+
+/// Prepare a Taproot key-path refund transaction that is co-signed by the swapper, so it doesn't
+/// have to wait for the swap lock height.
+fn prepare_cooperative_refund_tx(
+    utxos: &AddressUtxos,
+    to_address: String,
+    sat_per_vbyte: u32,
+) -> Result<Transaction> {
+    // The key path isn't timelocked
+    let mut tx = prepare_refund_tx(utxos, to_address, 0)?;
+
+    let tx_weight = compute_cooperative_refund_tx_weight(&tx);
+    let fees = compute_tx_fee(tx_weight, sat_per_vbyte);
+
+    if fees >= tx.output[0].value {
+        return Err(anyhow!("Insufficient funds to pay fees"));
+    }
+    tx.output[0].value -= fees;
+    Ok(tx)
+}
+
+/// Taproot sighashes commit to all the spent outputs
+fn taproot_prevouts(utxos: &AddressUtxos, taproot_swap: &TaprootSwap) -> Vec<TxOut> {
+    let swap_script_pubkey = taproot_swap.script_pubkey();
+    utxos
+        .confirmed
+        .iter()
+        .map(|utxo| TxOut {
+            value: utxo.value,
+            script_pubkey: swap_script_pubkey.clone(),
+        })
+        .collect()
+}
+
+/// The outputs spent by the swapper's cooperative claim transaction, in the inputs order.
+///
+/// Fails unless every input spends a distinct confirmed deposit of the swap and the claimed
+/// deposits are worth no more than the paid invoice, so later deposits aren't swept.
+fn cooperative_claim_prevouts(
+    claim_tx: &Transaction,
+    utxos: &AddressUtxos,
+    taproot_swap: &TaprootSwap,
+    paid_msat: u64,
+) -> Result<Vec<TxOut>> {
+    if claim_tx.input.is_empty() || claim_tx.output.is_empty() {
+        return Err(anyhow!("Invalid cooperative claim tx"));
+    }
+
+    let mut prevouts: Vec<TxOut> = Vec::new();
+    for (index, input) in claim_tx.input.iter().enumerate() {
+        if claim_tx.input[..index]
+            .iter()
+            .any(|other| other.previous_output == input.previous_output)
+        {
+            return Err(anyhow!(
+                "Cooperative claim tx spends {} twice",
+                input.previous_output
+            ));
+        }
+        let utxo = utxos
+            .confirmed
+            .iter()
+            .find(|utxo| utxo.out == input.previous_output)
+            .ok_or(anyhow!(
+                "Cooperative claim tx spends {} which isn't a deposit of the swap",
+                input.previous_output
+            ))?;
+        prevouts.push(TxOut {
+            value: utxo.value,
+            script_pubkey: taproot_swap.script_pubkey(),
+        });
+    }
+
+    let claimed_sat: u64 = prevouts.iter().map(|prevout| prevout.value).sum();
+    let output_sat: u64 = claim_tx.output.iter().map(|output| output.value).sum();
+    if claimed_sat * 1_000 > paid_msat {
+        return Err(anyhow!(
+            "Cooperative claim tx spends {claimed_sat} sats, more than the paid invoice"
+        ));
+    }
+    if output_sat > claimed_sat {
+        return Err(anyhow!("Cooperative claim tx spends more than its inputs"));
+    }
+    Ok(prevouts)
+}
+
+fn taproot_key_spend_sighashes(tx: &Transaction, prevouts: &[TxOut]) -> Result<Vec<[u8; 32]>> {
+    let mut signer = SighashCache::new(tx);
+    let mut sighashes = Vec::new();
+    for index in 0..tx.input.len() {
+        let sighash = signer.taproot_key_spend_signature_hash(
+            index,
+            &Prevouts::All(prevouts),
+            SchnorrSighashType::Default,
+        )?;
+        sighashes.push(sighash.into_inner());
+    }
+    Ok(sighashes)
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -804,7 +1851,7 @@ mod tests {
             OutPoint, Transaction, Txid,
         },
         breez_services::tests::get_dummy_node_state,
-        chain::{ChainService, OnchainTx},
+        chain::{ChainService, OnchainTx, TxStatus},
         models::*,
         persist::db::SqliteStorage,
         test_utils::{
@@ -814,7 +1861,11 @@ mod tests {
         BreezEvent,
     };
 
-    use super::{create_refund_tx, create_submarine_swap_script, get_utxos, BTCReceiveSwap};
+    use super::{
+        compute_cooperative_refund_tx_weight, cooperative_claim_prevouts, create_batch_refund_tx,
+        create_refund_tx, create_submarine_swap_script, create_taproot_refund_tx, get_utxos,
+        prepare_cooperative_refund_tx, BTCReceiveSwap, SwapRefund, TaprootSwap,
+    };
 
     #[test]
     fn test_build_swap_script() -> Result<()> {
@@ -891,7 +1942,7 @@ mod tests {
         let chain_service = Arc::new(MockChainService::default());
         let (swapper, persister) = create_swapper(chain_service.clone())?;
         let swap_info = swapper
-            .create_swap_address(get_test_ofp(10, 10, true).into())
+            .create_swap_address(get_test_ofp(10, 10, true).into(), SwapScriptVersion::P2wsh)
             .await?;
 
         assert_eq!(swap_info.max_swapper_payable, 4_000_000);
@@ -904,7 +1955,7 @@ mod tests {
         persister.set_node_state(&dummy_node_state)?;
 
         let swap_info = swapper
-            .create_swap_address(get_test_ofp(10, 10, true).into())
+            .create_swap_address(get_test_ofp(10, 10, true).into(), SwapScriptVersion::P2wsh)
             .await?;
         assert_eq!(swap_info.max_swapper_payable, 4_000_000);
         assert_eq!(swap_info.max_allowed_deposit, custom_max_receivable as i64);
@@ -918,7 +1969,7 @@ mod tests {
         let chain_service = Arc::new(MockChainService::default());
         let (swapper, persister) = create_swapper(chain_service.clone())?;
         let mut swap_info = swapper
-            .create_swap_address(get_test_ofp(10, 10, true).into())
+            .create_swap_address(get_test_ofp(10, 10, true).into(), SwapScriptVersion::P2wsh)
             .await?;
 
         // test initial status
@@ -1065,7 +2116,7 @@ mod tests {
         let chain_service = Arc::new(MockChainService::default());
         let (mut swapper, _) = create_swapper(chain_service.clone())?;
         let swap_info = swapper
-            .create_swap_address(get_test_ofp(10, 10, true).into())
+            .create_swap_address(get_test_ofp(10, 10, true).into(), SwapScriptVersion::P2wsh)
             .await?;
         assert_eq!(swap_info.confirmed_at, None);
         // We test the case that a confirmed transaction was detected on chain that
@@ -1119,6 +2170,180 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_bump_refund_tx() -> Result<()> {
+        let chain_service = Arc::new(MockChainService::default());
+        let (mut swapper, persister) = create_swapper(chain_service.clone())?;
+        let swap_info = swapper
+            .create_swap_address(get_test_ofp(10, 10, true).into(), SwapScriptVersion::P2wsh)
+            .await?;
+        let address = swap_info.bitcoin_address.clone();
+        swapper.chain_service = chain_service_with_confirmed_txs(address.clone());
+        swapper
+            .on_event(BreezEvent::NewBlock {
+                block: chain_service.tip + 145,
+            })
+            .await?;
+
+        let refund_response = swapper
+            .refund_swap(RefundRequest {
+                swap_address: address.clone(),
+                to_address: String::from("34RQERthXaruAXtW6q1bvrGTeUbqi2Sm1i"),
+                sat_per_vbyte: 1,
+            })
+            .await?;
+
+        // The refund tx is in the mempool, paying 1 sat/vbyte
+        let refund_tx_raw = r#"{"txid":"<txid>","version":2,"locktime":767925,"vin":[{"txid":"ec901bcab07df7d475d98fff2933dcb56d57bbdaa029c4142aed93462b6928fe","vout":0,"prevout":{"scriptpubkey":"","scriptpubkey_asm":"","scriptpubkey_type":"v0_p2wsh","scriptpubkey_address":"<address>","value":50000},"scriptsig":"","scriptsig_asm":"","witness":[],"is_coinbase":false,"sequence":288}],"vout":[{"scriptpubkey":"a9141df45814863edfd6d87457e8f8bd79607a116a8f87","scriptpubkey_asm":"","scriptpubkey_type":"p2sh","scriptpubkey_address":"34RQERthXaruAXtW6q1bvrGTeUbqi2Sm1i","value":49857}],"size":230,"weight":572,"fee":143,"status":{"confirmed":false}}"#
+            .replace("<txid>", &refund_response.refund_tx_id)
+            .replace("<address>", &address);
+        let mut transactions = swapper
+            .chain_service
+            .address_transactions(address.clone())
+            .await?;
+        transactions.push(serde_json::from_str(&refund_tx_raw)?);
+        let mut mempool_chain_service = MockChainService::default();
+        mempool_chain_service
+            .address_to_transactions
+            .insert(address.clone(), transactions);
+        swapper.chain_service = Arc::new(mempool_chain_service);
+
+        // Only transactions refunding a swap are bumped
+        assert_eq!(
+            swapper
+                .bump_refund_tx(
+                    "a418e856bb22b6345868dc0b1ac1dd7a6b7fae1d231b275b74172f9584fa0bdf",
+                    5
+                )
+                .await?,
+            None
+        );
+        // The replacement has to pay a higher fee rate
+        assert!(swapper
+            .bump_refund_tx(&refund_response.refund_tx_id, 1)
+            .await
+            .is_err());
+
+        let bumped_txid = swapper
+            .bump_refund_tx(&refund_response.refund_tx_id, 5)
+            .await?
+            .unwrap();
+        let swap = swapper.get_swap_info(address)?.unwrap();
+        assert_eq!(swap.refund_tx_ids.len(), 2);
+        assert!(swap.refund_tx_ids.contains(&bumped_txid));
+        assert_eq!(
+            persister.get_replacing_txid(&refund_response.refund_tx_id)?,
+            Some(bumped_txid)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_accelerate_swap_deposit() -> Result<()> {
+        let chain_service = Arc::new(MockChainService::default());
+        let (mut swapper, persister) = create_swapper(chain_service.clone())?;
+        let swap_info = swapper
+            .create_swap_address(get_test_ofp(10, 10, true).into(), SwapScriptVersion::P2wsh)
+            .await?;
+        let address = swap_info.bitcoin_address.clone();
+        let taproot_swap_info = swapper
+            .create_swap_address(
+                get_test_ofp(10, 10, true).into(),
+                SwapScriptVersion::Taproot,
+            )
+            .await?;
+        let taproot_address = taproot_swap_info.bitcoin_address.clone();
+
+        // The deposits are in the mempool, paying 2 sat/vbyte
+        let mut mempool_chain_service = MockChainService::default();
+        for address in [address.clone(), taproot_address.clone()] {
+            let mut transactions = chain_service_with_confirmed_txs(address.clone())
+                .address_transactions(address.clone())
+                .await?;
+            transactions[0].fee = 282;
+            transactions[0].status = TxStatus {
+                confirmed: false,
+                block_height: None,
+                block_hash: None,
+                block_time: None,
+            };
+            mempool_chain_service
+                .address_to_transactions
+                .insert(address, transactions);
+        }
+        swapper.chain_service = Arc::new(mempool_chain_service);
+
+        // Without node funds in the deposit, it can only be refunded through the key path of a
+        // Taproot swap
+        assert!(swapper
+            .prepare_accelerate_swap_deposit(PrepareAccelerateSwapDepositRequest {
+                swap_address: taproot_address.clone(),
+                target_sat_per_vbyte: 10,
+                refund_address: None,
+            })
+            .await
+            .is_err());
+        assert!(swapper
+            .prepare_accelerate_swap_deposit(PrepareAccelerateSwapDepositRequest {
+                swap_address: address.clone(),
+                target_sat_per_vbyte: 10,
+                refund_address: Some(String::from("34RQERthXaruAXtW6q1bvrGTeUbqi2Sm1i")),
+            })
+            .await
+            .is_err());
+        let prepared = swapper
+            .prepare_accelerate_swap_deposit(PrepareAccelerateSwapDepositRequest {
+                swap_address: taproot_address,
+                target_sat_per_vbyte: 10,
+                refund_address: Some(String::from("34RQERthXaruAXtW6q1bvrGTeUbqi2Sm1i")),
+            })
+            .await?;
+        assert_eq!(prepared.source, SwapDepositAccelerationSource::SwapOutput);
+        assert_eq!(prepared.child_tx_weight, 398);
+        assert_eq!(prepared.child_tx_fee_sat, 2189);
+
+        // The change of the deposit goes to the node wallet
+        let mut node_state = get_dummy_node_state();
+        node_state.utxos = vec![UnspentTransactionOutput {
+            txid: hex::decode("ec901bcab07df7d475d98fff2933dcb56d57bbdaa029c4142aed93462b6928fe")?,
+            outnum: 1,
+            amount_millisatoshi: 12_140_465_000,
+            address: String::from("bc1q7r32q47suczprtpawgvw9xlefzd9nhccyatxvu"),
+            reserved: false,
+        }];
+        persister.set_node_state(&node_state)?;
+
+        let prepared = swapper
+            .prepare_accelerate_swap_deposit(PrepareAccelerateSwapDepositRequest {
+                swap_address: address.clone(),
+                target_sat_per_vbyte: 10,
+                refund_address: None,
+            })
+            .await?;
+        assert_eq!(prepared.source, SwapDepositAccelerationSource::NodeFunds);
+        assert_eq!(prepared.child_tx_weight, 438);
+        assert_eq!(prepared.child_tx_fee_sat, 2299);
+
+        // The deposit already pays the target fee rate
+        assert!(swapper
+            .accelerate_swap_deposit(AccelerateSwapDepositRequest {
+                swap_address: address.clone(),
+                target_sat_per_vbyte: 2,
+                refund_address: None,
+            })
+            .await
+            .is_err());
+        let accelerated = swapper
+            .accelerate_swap_deposit(AccelerateSwapDepositRequest {
+                swap_address: address,
+                target_sat_per_vbyte: 10,
+                refund_address: None,
+            })
+            .await?;
+        assert_eq!(accelerated.txid.len(), 64);
+        Ok(())
+    }
+
     // 1. User sent funds to swap address
     // 2. Funds are redeemed in lightning transaction
     // Swap paid amount is updated and no longer redeemable.
@@ -1131,41 +2356,42 @@ mod tests {
             let _ = receiver.recv().await;
         });
         let swap_info = swapper
-            .create_swap_address(get_test_ofp(10, 10, true).into())
+            .create_swap_address(get_test_ofp(10, 10, true).into(), SwapScriptVersion::P2wsh)
             .await?;
 
         // add a payment with the same hash and test that the swapper updates the paid_amount for
         // the swap.
         let payment = Payment {
-            id: hex::encode(swap_info.payment_hash.clone()),
-            payment_type: PaymentType::Received,
-            payment_time: 0,
-            amount_msat: 5_000,
-            fee_msat: 0,
-            status: PaymentStatus::Complete,
-            error: None,
             description: Some("desc".to_string()),
-            details: PaymentDetails::Ln {
-                data: LnPaymentDetails {
-                    payment_hash: hex::encode(swap_info.payment_hash.clone()),
-                    label: "".to_string(),
-                    destination_pubkey: "".to_string(),
-                    payment_preimage: "111".to_string(),
-                    keysend: false,
-                    bolt11: "".to_string(),
-                    lnurl_success_action: None,
-                    lnurl_pay_domain: None,
-                    lnurl_pay_comment: None,
-                    lnurl_metadata: None,
-                    ln_address: None,
-                    lnurl_withdraw_endpoint: None,
-                    swap_info: None,
-                    reverse_swap_info: None,
-                    pending_expiration_block: None,
-                    open_channel_bolt11: None,
+            ..Payment::new(
+                hex::encode(swap_info.payment_hash.clone()),
+                PaymentType::Received,
+                0,
+                5_000,
+                0,
+                PaymentStatus::Complete,
+                PaymentDetails::Ln {
+                    data: LnPaymentDetails {
+                        payment_hash: hex::encode(swap_info.payment_hash.clone()),
+                        label: "".to_string(),
+                        destination_pubkey: "".to_string(),
+                        payment_preimage: "111".to_string(),
+                        keysend: false,
+                        bolt11: "".to_string(),
+                        lnurl_success_action: None,
+                        lnurl_pay_domain: None,
+                        lnurl_pay_comment: None,
+                        lnurl_metadata: None,
+                        ln_address: None,
+                        lnurl_withdraw_endpoint: None,
+                        lnurl_verify_url: None,
+                        swap_info: None,
+                        reverse_swap_info: None,
+                        pending_expiration_block: None,
+                        open_channel_bolt11: None,
+                    },
                 },
-            },
-            metadata: None,
+            )
         };
         persister.insert_or_update_payments(&vec![payment.clone()], false)?;
 
@@ -1224,7 +2450,7 @@ mod tests {
         let chain_service = Arc::new(MockChainService::default());
         let (mut swapper, _) = create_swapper(chain_service.clone())?;
         let swap_info = swapper
-            .create_swap_address(get_test_ofp(10, 10, true).into())
+            .create_swap_address(get_test_ofp(10, 10, true).into(), SwapScriptVersion::P2wsh)
             .await?;
 
         // Once swap is spent on-chain the confirmed_sats would be set to zero again.
@@ -1289,7 +2515,8 @@ mod tests {
         let tx: Transaction = deserialize(&raw_tx_bytes).unwrap();
         let weight = Transaction::weight(&tx) as u64;
 
-        let refund_tx_weight = compute_refund_tx_weight(&prepared_refund_tx);
+        let refund_tx_weight =
+            compute_refund_tx_weight(&prepared_refund_tx, SwapScriptVersion::P2wsh);
         assert_eq!(refund_tx_weight, weight as u32);
 
         let refund_tx_fee_sat = compute_tx_fee(refund_tx_weight, 0);
@@ -1405,6 +2632,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_prepare_cooperative_refund() -> Result<()> {
+        let utxos = AddressUtxos {
+            confirmed: vec![Utxo {
+                out: OutPoint {
+                    txid: Txid::from_hex(
+                        "1ab3fe9f94ff1332d6f198484c3677832d1162781f86ce85f6d7587fa97f0330",
+                    )?,
+                    vout: 0,
+                },
+                value: 20000,
+                block_height: Some(700000),
+            }],
+            unconfirmed: vec![],
+        };
+        let tx = prepare_cooperative_refund_tx(
+            &utxos,
+            String::from("bc1qvhykeqcpdzu0pdvy99xnh9ckhwzcfskct6h6l2"),
+            2,
+        )?;
+
+        // The key-path refund doesn't wait for the lock height
+        assert_eq!(tx.lock_time.0, 0);
+        assert_eq!(tx.input[0].sequence.0, 0);
+        let fees = compute_tx_fee(compute_cooperative_refund_tx_weight(&tx), 2);
+        assert_eq!(tx.output[0].value, 20000 - fees);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_create_swap_address_uses_the_current_time() -> Result<()> {
         let current_time = SystemTime::now()
@@ -1414,12 +2670,250 @@ mod tests {
         let chain_service = Arc::new(MockChainService::default());
         let (swapper, _) = create_swapper(chain_service.clone())?;
         let swap_info = swapper
-            .create_swap_address(get_test_ofp(10, 10, true).into())
+            .create_swap_address(get_test_ofp(10, 10, true).into(), SwapScriptVersion::P2wsh)
             .await?;
         assert!(swap_info.created_at >= current_time);
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_create_taproot_swap_address() -> Result<()> {
+        let chain_service = Arc::new(MockChainService::default());
+        let (swapper, persister) = create_swapper(chain_service.clone())?;
+        let p2wsh_swap = swapper
+            .create_swap_address(get_test_ofp(10, 10, true).into(), SwapScriptVersion::P2wsh)
+            .await?;
+        let taproot_swap = swapper
+            .create_swap_address(
+                get_test_ofp(10, 10, true).into(),
+                SwapScriptVersion::Taproot,
+            )
+            .await?;
+
+        // An unused swap is only reused for the same script version
+        assert_ne!(p2wsh_swap.bitcoin_address, taproot_swap.bitcoin_address);
+        assert!(taproot_swap.bitcoin_address.starts_with("bc1p"));
+        let stored_swap = persister
+            .get_swap_info_by_address(taproot_swap.bitcoin_address.clone())?
+            .unwrap();
+        assert_eq!(stored_swap.script_version, SwapScriptVersion::Taproot);
+
+        let reused_swap = swapper
+            .create_swap_address(
+                get_test_ofp(10, 10, true).into(),
+                SwapScriptVersion::Taproot,
+            )
+            .await?;
+        assert_eq!(reused_swap.bitcoin_address, taproot_swap.bitcoin_address);
+        Ok(())
+    }
+
+    #[test]
+    fn test_taproot_refund() -> Result<()> {
+        let payer_priv_key_raw = [1; 32].to_vec();
+        let secp = Secp256k1::new();
+        let payer_pub_key =
+            PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&payer_priv_key_raw)?)
+                .serialize();
+        let swapper_pub_key =
+            PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&[2; 32])?).serialize();
+        let taproot_swap = TaprootSwap::new(&[3; 32], &swapper_pub_key, &payer_pub_key, 288)?;
+
+        let utxos = AddressUtxos {
+            confirmed: vec![Utxo {
+                out: OutPoint {
+                    txid: Txid::from_hex(
+                        "1ab3fe9f94ff1332d6f198484c3677832d1162781f86ce85f6d7587fa97f0330",
+                    )?,
+                    vout: 0,
+                },
+                value: 20000,
+                block_height: Some(700000),
+            }],
+            unconfirmed: vec![],
+        };
+        let refund_tx = create_taproot_refund_tx(
+            utxos,
+            payer_priv_key_raw,
+            String::from("bc1qvhykeqcpdzu0pdvy99xnh9ckhwzcfskct6h6l2"),
+            288,
+            &taproot_swap,
+            1,
+        )?;
+
+        let tx: Transaction = deserialize(&refund_tx)?;
+        assert_eq!(tx.lock_time.0, 700288);
+        assert_eq!(tx.input[0].sequence.0, 288);
+        let witness = tx.input[0].witness.to_vec();
+        assert_eq!(witness.len(), 3);
+        assert_eq!(witness[0].len(), 64);
+        assert_eq!(witness[1], taproot_swap.refund_script.to_bytes());
+        assert_eq!(witness[2].len(), 65);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cooperative_claim_prevouts() -> Result<()> {
+        let secp = Secp256k1::new();
+        let payer_pub_key =
+            PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&[1; 32])?).serialize();
+        let swapper_pub_key =
+            PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&[2; 32])?).serialize();
+        let taproot_swap = TaprootSwap::new(&[3; 32], &swapper_pub_key, &payer_pub_key, 288)?;
+
+        let deposit = |vout: u32, value: u64| -> Result<Utxo> {
+            Ok(Utxo {
+                out: OutPoint {
+                    txid: Txid::from_hex(
+                        "1ab3fe9f94ff1332d6f198484c3677832d1162781f86ce85f6d7587fa97f0330",
+                    )?,
+                    vout,
+                },
+                value,
+                block_height: Some(700000),
+            })
+        };
+        let paid = AddressUtxos {
+            confirmed: vec![deposit(0, 20000)?],
+            unconfirmed: vec![],
+        };
+        let claim_tx = prepare_refund_tx(
+            &paid,
+            String::from("bc1qvhykeqcpdzu0pdvy99xnh9ckhwzcfskct6h6l2"),
+            0,
+        )?;
+
+        // A later deposit to the swap address isn't part of the paid invoice
+        let utxos = AddressUtxos {
+            confirmed: vec![deposit(0, 20000)?, deposit(1, 30000)?],
+            unconfirmed: vec![],
+        };
+        let prevouts = cooperative_claim_prevouts(&claim_tx, &utxos, &taproot_swap, 20_000_000)?;
+        assert_eq!(prevouts.len(), 1);
+        assert_eq!(prevouts[0].value, 20000);
+        assert_eq!(prevouts[0].script_pubkey, taproot_swap.script_pubkey());
+
+        let sweeping_tx = prepare_refund_tx(
+            &utxos,
+            String::from("bc1qvhykeqcpdzu0pdvy99xnh9ckhwzcfskct6h6l2"),
+            0,
+        )?;
+        assert!(
+            cooperative_claim_prevouts(&sweeping_tx, &utxos, &taproot_swap, 20_000_000).is_err()
+        );
+
+        let mut foreign_tx = claim_tx.clone();
+        foreign_tx.input[0].previous_output.vout = 2;
+        assert!(
+            cooperative_claim_prevouts(&foreign_tx, &utxos, &taproot_swap, 20_000_000).is_err()
+        );
+
+        let mut double_spending_tx = claim_tx.clone();
+        double_spending_tx
+            .input
+            .push(double_spending_tx.input[0].clone());
+        assert!(
+            cooperative_claim_prevouts(&double_spending_tx, &utxos, &taproot_swap, 40_000_000)
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_batch_refund() -> Result<()> {
+        let chain_service = Arc::new(MockChainService::default());
+        let (swapper, _) = create_swapper(chain_service.clone())?;
+        let p2wsh_swap = swapper
+            .create_swap_address(get_test_ofp(10, 10, true).into(), SwapScriptVersion::P2wsh)
+            .await?;
+        let taproot_swap = swapper
+            .create_swap_address(
+                get_test_ofp(10, 10, true).into(),
+                SwapScriptVersion::Taproot,
+            )
+            .await?;
+        let to_address = String::from("bc1qvhykeqcpdzu0pdvy99xnh9ckhwzcfskct6h6l2");
+        let utxos = |txid: &str, block_height: u32| -> Result<AddressUtxos> {
+            Ok(AddressUtxos {
+                confirmed: vec![Utxo {
+                    out: OutPoint {
+                        txid: Txid::from_hex(txid)?,
+                        vout: 0,
+                    },
+                    value: 20000,
+                    block_height: Some(block_height),
+                }],
+                unconfirmed: vec![],
+            })
+        };
+
+        // A batch of a single P2WSH swap is the same as its own refund
+        let p2wsh_utxos = utxos(
+            "1ab3fe9f94ff1332d6f198484c3677832d1162781f86ce85f6d7587fa97f0330",
+            700000,
+        )?;
+        let p2wsh_script = create_submarine_swap_script(
+            p2wsh_swap.payment_hash.clone(),
+            p2wsh_swap.swapper_public_key.clone(),
+            p2wsh_swap.public_key.clone(),
+            p2wsh_swap.lock_height,
+        )?;
+        let refund_tx = create_refund_tx(
+            p2wsh_utxos.clone(),
+            p2wsh_swap.private_key.clone(),
+            to_address.clone(),
+            p2wsh_swap.lock_height as u32,
+            &p2wsh_script,
+            1,
+        )?;
+        let p2wsh_refund = SwapRefund {
+            swap_info: p2wsh_swap.clone(),
+            utxos: p2wsh_utxos,
+        };
+        let batch_tx = create_batch_refund_tx(&[p2wsh_refund], to_address.clone(), 1)?;
+        assert_eq!(batch_tx, refund_tx);
+
+        // A batch of both swaps waits for the latest lock of its inputs
+        let refunds = vec![
+            SwapRefund {
+                swap_info: p2wsh_swap.clone(),
+                utxos: utxos(
+                    "1ab3fe9f94ff1332d6f198484c3677832d1162781f86ce85f6d7587fa97f0330",
+                    700000,
+                )?,
+            },
+            SwapRefund {
+                swap_info: taproot_swap.clone(),
+                utxos: utxos(
+                    "ec901bcab07df7d475d98fff2933dcb56d57bbdaa029c4142aed93462b6928fe",
+                    700100,
+                )?,
+            },
+        ];
+        let batch_tx: Transaction = deserialize(&create_batch_refund_tx(&refunds, to_address, 1)?)?;
+        assert_eq!(batch_tx.input.len(), 2);
+        assert_eq!(batch_tx.output.len(), 1);
+        assert_eq!(batch_tx.input[0].sequence.0, p2wsh_swap.lock_height as u32);
+        assert_eq!(
+            batch_tx.input[1].sequence.0,
+            taproot_swap.lock_height as u32
+        );
+        assert_eq!(
+            batch_tx.lock_time.0,
+            std::cmp::max(
+                700000 + p2wsh_swap.lock_height as u32,
+                700100 + taproot_swap.lock_height as u32
+            )
+        );
+        let p2wsh_witness = batch_tx.input[0].witness.to_vec();
+        assert_eq!(p2wsh_witness.len(), 3);
+        assert!(p2wsh_witness[1].is_empty());
+        let taproot_witness = batch_tx.input[1].witness.to_vec();
+        assert_eq!(taproot_witness.len(), 3);
+        assert_eq!(taproot_witness[0].len(), 64);
+        Ok(())
+    }
+
     fn create_swapper(
         chain_service: Arc<dyn ChainService>,
     ) -> Result<(BTCReceiveSwap, Arc<SqliteStorage>)> {
@@ -0,0 +1,353 @@
+use anyhow::{anyhow, Result};
+use musig2::{AggNonce, KeyAggContext, LiftedSignature, PartialSignature, PubNonce, SecNonce};
+use rand::Rng;
+use ripemd::{Digest, Ripemd160};
+use secp::{Point, Scalar};
+
+use crate::bitcoin::blockdata::opcodes;
+use crate::bitcoin::blockdata::script::Builder;
+use crate::bitcoin::hashes::Hash;
+use crate::bitcoin::secp256k1::{PublicKey, Secp256k1, XOnlyPublicKey};
+use crate::bitcoin::util::taproot::{
+    ControlBlock, LeafVersion, TapLeafHash, TaprootBuilder, TaprootSpendInfo,
+};
+use crate::bitcoin::{Address, Network, Script};
+use crate::models::{CooperativeClaimSignature, CooperativeRefundSignature};
+
+/// The scripts and keys of a Taproot swap address.
+///
+/// The internal key is the MuSig2 aggregate of the payer and swapper keys, sorted as in BIP327.
+/// Once the swapper has paid the invoice, both parties spend the funds through the key path,
+/// which looks like any single-sig Taproot spend. The script tree is only revealed when one of
+/// them has to act alone:
+/// * The claim leaf lets the swapper spend with the preimage.
+/// * The refund leaf lets the payer spend once the relative lock height has passed.
+pub(crate) struct TaprootSwap {
+    pub(crate) refund_script: Script,
+    spend_info: TaprootSpendInfo,
+    key_agg_ctx: KeyAggContext,
+}
+
+impl TaprootSwap {
+    pub(crate) fn new(
+        invoice_hash: &[u8],
+        swapper_pub_key: &[u8],
+        payer_pub_key: &[u8],
+        lock_height: i64,
+    ) -> Result<Self> {
+        let swapper_key = PublicKey::from_slice(swapper_pub_key)?;
+        let payer_key = PublicKey::from_slice(payer_pub_key)?;
+
+        let mut hasher = Ripemd160::new();
+        hasher.update(invoice_hash);
+        let result = hasher.finalize();
+
+        let claim_script = Builder::new()
+            .push_opcode(opcodes::all::OP_HASH160)
+            .push_slice(&result[..])
+            .push_opcode(opcodes::all::OP_EQUALVERIFY)
+            .push_slice(&swapper_key.x_only_public_key().0.serialize())
+            .push_opcode(opcodes::all::OP_CHECKSIG)
+            .into_script();
+        let refund_script = Builder::new()
+            .push_slice(&payer_key.x_only_public_key().0.serialize())
+            .push_opcode(opcodes::all::OP_CHECKSIGVERIFY)
+            .push_int(lock_height)
+            .push_opcode(opcodes::all::OP_CSV)
+            .into_script();
+
+        let mut keys = vec![swapper_key.serialize(), payer_key.serialize()];
+        keys.sort();
+        let points = keys
+            .iter()
+            .map(|key| Point::from_slice(key).map_err(|_| anyhow!("Invalid public key")))
+            .collect::<Result<Vec<Point>>>()?;
+        let key_agg_ctx =
+            KeyAggContext::new(points).map_err(|e| anyhow!("Failed to aggregate keys: {e}"))?;
+        let internal_key = XOnlyPublicKey::from_slice(
+            &key_agg_ctx
+                .aggregated_pubkey_untweaked::<Point>()
+                .serialize_xonly(),
+        )?;
+
+        let spend_info = TaprootBuilder::new()
+            .add_leaf(1, claim_script)?
+            .add_leaf(1, refund_script.clone())?
+            .finalize(&Secp256k1::verification_only(), internal_key)
+            .map_err(|_| anyhow!("Failed to build the swap script tree"))?;
+        let merkle_root = spend_info
+            .merkle_root()
+            .ok_or(anyhow!("Missing swap script tree root"))?;
+        let key_agg_ctx = key_agg_ctx
+            .with_taproot_tweak(&merkle_root.into_inner())
+            .map_err(|e| anyhow!("Failed to tweak the aggregated key: {e}"))?;
+
+        // Both tweaks must agree, otherwise the key-path signatures would be invalid
+        let tweaked_key = key_agg_ctx.aggregated_pubkey::<Point>().serialize_xonly();
+        if tweaked_key != spend_info.output_key().to_inner().serialize() {
+            return Err(anyhow!("Mismatching tweaked swap key"));
+        }
+
+        Ok(Self {
+            refund_script,
+            spend_info,
+            key_agg_ctx,
+        })
+    }
+
+    pub(crate) fn address(&self, network: Network) -> Address {
+        Address::p2tr_tweaked(self.spend_info.output_key(), network)
+    }
+
+    pub(crate) fn script_pubkey(&self) -> Script {
+        Script::new_v1_p2tr_tweaked(self.spend_info.output_key())
+    }
+
+    pub(crate) fn refund_leaf_hash(&self) -> TapLeafHash {
+        TapLeafHash::from_script(&self.refund_script, LeafVersion::TapScript)
+    }
+
+    pub(crate) fn refund_control_block(&self) -> Result<ControlBlock> {
+        self.spend_info
+            .control_block(&(self.refund_script.clone(), LeafVersion::TapScript))
+            .ok_or(anyhow!("Missing refund leaf in the swap script tree"))
+    }
+
+    /// Creates the payer's partial signatures for the swapper's key-path claim of each input,
+    /// given the sighashes we computed from the claim transaction.
+    ///
+    /// This must only be called once the swapper paid the swap invoice, as the signatures let
+    /// the swapper spend the funds without revealing anything on-chain.
+    pub(crate) fn sign_cooperative_claim(
+        &self,
+        private_key: &[u8],
+        sighashes: &[[u8; 32]],
+        swapper_pub_nonces: Vec<Vec<u8>>,
+    ) -> Result<Vec<CooperativeClaimSignature>> {
+        if sighashes.len() != swapper_pub_nonces.len() {
+            return Err(anyhow!("Missing swapper nonces for the claim inputs"));
+        }
+        let seckey = to_scalar(private_key)?;
+
+        sighashes
+            .iter()
+            .zip(swapper_pub_nonces)
+            .map(|(sighash, swapper_pub_nonce)| {
+                let swapper_nonce = PubNonce::from_bytes(&swapper_pub_nonce)
+                    .map_err(|e| anyhow!("Invalid swapper nonce: {e}"))?;
+                let secnonce = self.new_secnonce(seckey, sighash);
+                let pub_nonce = secnonce.public_nonce();
+                let agg_nonce = AggNonce::sum([&pub_nonce, &swapper_nonce]);
+                let partial_signature: PartialSignature =
+                    musig2::sign_partial(&self.key_agg_ctx, seckey, secnonce, &agg_nonce, sighash)
+                        .map_err(|e| anyhow!("Failed to sign cooperative claim: {e}"))?;
+
+                Ok(CooperativeClaimSignature {
+                    pub_nonce: pub_nonce.serialize().to_vec(),
+                    partial_signature: partial_signature.serialize().to_vec(),
+                })
+            })
+            .collect()
+    }
+
+    /// Creates our nonces for the key-path refund of each input, to be sent to the swapper
+    /// along with the refund transaction.
+    pub(crate) fn refund_nonces(
+        &self,
+        private_key: &[u8],
+        sighashes: &[[u8; 32]],
+    ) -> Result<Vec<SecNonce>> {
+        let seckey = to_scalar(private_key)?;
+        Ok(sighashes
+            .iter()
+            .map(|sighash| self.new_secnonce(seckey, sighash))
+            .collect())
+    }
+
+    /// Combines our partial signatures with the swapper's into the key-path signature of each
+    /// refund input. Fails if the swapper's signatures don't result in valid signatures.
+    pub(crate) fn aggregate_refund_signatures(
+        &self,
+        private_key: &[u8],
+        sighashes: &[[u8; 32]],
+        secnonces: Vec<SecNonce>,
+        swapper_signatures: Vec<CooperativeRefundSignature>,
+    ) -> Result<Vec<Vec<u8>>> {
+        if sighashes.len() != secnonces.len() || sighashes.len() != swapper_signatures.len() {
+            return Err(anyhow!("Missing signatures for the refund inputs"));
+        }
+        let seckey = to_scalar(private_key)?;
+        let aggregated_pubkey = self.key_agg_ctx.aggregated_pubkey::<Point>();
+
+        sighashes
+            .iter()
+            .zip(secnonces)
+            .zip(swapper_signatures)
+            .map(|((sighash, secnonce), swapper_signature)| {
+                let swapper_nonce = PubNonce::from_bytes(&swapper_signature.pub_nonce)
+                    .map_err(|e| anyhow!("Invalid swapper nonce: {e}"))?;
+                let swapper_partial_signature =
+                    PartialSignature::from_slice(&swapper_signature.signature)
+                        .map_err(|_| anyhow!("Invalid swapper partial signature"))?;
+                let agg_nonce = AggNonce::sum([&secnonce.public_nonce(), &swapper_nonce]);
+                let partial_signature: PartialSignature =
+                    musig2::sign_partial(&self.key_agg_ctx, seckey, secnonce, &agg_nonce, sighash)
+                        .map_err(|e| anyhow!("Failed to sign cooperative refund: {e}"))?;
+
+                let signature: LiftedSignature = musig2::aggregate_partial_signatures(
+                    &self.key_agg_ctx,
+                    &agg_nonce,
+                    [partial_signature, swapper_partial_signature],
+                    sighash,
+                )
+                .map_err(|e| anyhow!("Invalid cooperative refund signature: {e}"))?;
+                musig2::verify_single(aggregated_pubkey, signature, sighash)
+                    .map_err(|e| anyhow!("Invalid cooperative refund signature: {e}"))?;
+                Ok(signature.serialize().to_vec())
+            })
+            .collect()
+    }
+
+    fn new_secnonce(&self, seckey: Scalar, message: &[u8]) -> SecNonce {
+        SecNonce::build(rand::thread_rng().gen::<[u8; 32]>())
+            .with_seckey(seckey)
+            .with_message(&message)
+            .with_aggregated_pubkey(self.key_agg_ctx.aggregated_pubkey::<Point>())
+            .build()
+    }
+}
+
+fn to_scalar(private_key: &[u8]) -> Result<Scalar> {
+    Scalar::from_slice(private_key).map_err(|_| anyhow!("Invalid private key"))
+}
+
+#[cfg(test)]
+mod tests {
+    use musig2::{AggNonce, LiftedSignature, PartialSignature, PubNonce, SecNonce};
+    use secp::{Point, Scalar};
+
+    use crate::bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+    use crate::bitcoin::Network;
+    use crate::swap_in::taproot::*;
+
+    #[test]
+    fn test_cooperative_claim() -> Result<()> {
+        let secp = Secp256k1::new();
+        let payer_key = SecretKey::from_slice(&[1; 32])?;
+        let swapper_key = SecretKey::from_slice(&[2; 32])?;
+        let payer_pub_key = PublicKey::from_secret_key(&secp, &payer_key).serialize();
+        let swapper_pub_key = PublicKey::from_secret_key(&secp, &swapper_key).serialize();
+
+        let swap = TaprootSwap::new(&[3; 32], &swapper_pub_key, &payer_pub_key, 288)?;
+        assert!(swap
+            .address(Network::Bitcoin)
+            .to_string()
+            .starts_with("bc1p"));
+        assert_eq!(swap.refund_control_block()?.serialize().len(), 65);
+
+        // The swapper's side of the signing session
+        let sighash = [4; 32];
+        let swapper_seckey = Scalar::from_slice(&swapper_key.secret_bytes()).unwrap();
+        let swapper_secnonce = SecNonce::build([5; 32])
+            .with_seckey(swapper_seckey)
+            .with_message(&sighash)
+            .build();
+        let swapper_nonce = swapper_secnonce.public_nonce();
+
+        let signatures = swap.sign_cooperative_claim(
+            &payer_key.secret_bytes(),
+            &[sighash],
+            vec![swapper_nonce.serialize().to_vec()],
+        )?;
+        assert_eq!(signatures.len(), 1);
+
+        let payer_nonce = PubNonce::from_bytes(&signatures[0].pub_nonce).unwrap();
+        let payer_signature =
+            PartialSignature::from_slice(&signatures[0].partial_signature).unwrap();
+        let agg_nonce = AggNonce::sum([&payer_nonce, &swapper_nonce]);
+        let swapper_signature: PartialSignature = musig2::sign_partial(
+            &swap.key_agg_ctx,
+            swapper_seckey,
+            swapper_secnonce,
+            &agg_nonce,
+            sighash,
+        )
+        .unwrap();
+
+        // The aggregated signature is a valid key-path signature for the swap output key
+        let signature: LiftedSignature = musig2::aggregate_partial_signatures(
+            &swap.key_agg_ctx,
+            &agg_nonce,
+            [payer_signature, swapper_signature],
+            sighash,
+        )
+        .unwrap();
+        let output_key: Point = swap.key_agg_ctx.aggregated_pubkey();
+        assert!(musig2::verify_single(output_key, signature, sighash).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cooperative_refund() -> Result<()> {
+        let secp = Secp256k1::new();
+        let payer_key = SecretKey::from_slice(&[1; 32])?;
+        let swapper_key = SecretKey::from_slice(&[2; 32])?;
+        let payer_pub_key = PublicKey::from_secret_key(&secp, &payer_key).serialize();
+        let swapper_pub_key = PublicKey::from_secret_key(&secp, &swapper_key).serialize();
+        let swap = TaprootSwap::new(&[3; 32], &swapper_pub_key, &payer_pub_key, 288)?;
+
+        let sighashes = [[4; 32], [5; 32]];
+        let secnonces = swap.refund_nonces(&payer_key.secret_bytes(), &sighashes)?;
+
+        // The swapper signs each input with our nonces
+        let swapper_seckey = Scalar::from_slice(&swapper_key.secret_bytes()).unwrap();
+        let swapper_signatures = |tamper: bool| -> Vec<CooperativeRefundSignature> {
+            sighashes
+                .iter()
+                .zip(secnonces.iter())
+                .map(|(sighash, secnonce)| {
+                    let swapper_secnonce = SecNonce::build(*sighash).build();
+                    let swapper_nonce = swapper_secnonce.public_nonce();
+                    let agg_nonce = AggNonce::sum([&secnonce.public_nonce(), &swapper_nonce]);
+                    let message: &[u8] = match tamper {
+                        true => &[7; 32],
+                        false => sighash,
+                    };
+                    let signature: PartialSignature = musig2::sign_partial(
+                        &swap.key_agg_ctx,
+                        swapper_seckey,
+                        swapper_secnonce,
+                        &agg_nonce,
+                        message,
+                    )
+                    .unwrap();
+                    CooperativeRefundSignature {
+                        signature: signature.serialize().to_vec(),
+                        pub_nonce: swapper_nonce.serialize().to_vec(),
+                    }
+                })
+                .collect()
+        };
+
+        let bad_signatures = swapper_signatures(true);
+        let good_signatures = swapper_signatures(false);
+        let signatures = swap.aggregate_refund_signatures(
+            &payer_key.secret_bytes(),
+            &sighashes,
+            secnonces.clone(),
+            good_signatures,
+        )?;
+        assert_eq!(signatures.len(), 2);
+        assert!(signatures.iter().all(|s| s.len() == 64));
+
+        assert!(swap
+            .aggregate_refund_signatures(
+                &payer_key.secret_bytes(),
+                &sighashes,
+                secnonces,
+                bad_signatures
+            )
+            .is_err());
+        Ok(())
+    }
+}
@@ -20,10 +20,13 @@ use crate::bitcoin::{
     Address, AddressType, EcdsaSighashType, KeyPair, Network, OutPoint, Script, Sequence,
     Transaction, TxIn, TxOut, Txid, Witness,
 };
-use crate::chain::{get_utxos, AddressUtxos, ChainService, OnchainTx, Utxo};
+use crate::chain::{
+    get_utxos, replacement_fee, validate_replacement, AddressUtxos, ChainService, OnchainTx, Utxo,
+};
 use crate::error::SdkResult;
 use crate::models::{ReverseSwapServiceAPI, ReverseSwapperRoutingAPI};
 use crate::node_api::{NodeAPI, NodeError};
+use crate::persist::replacements::TxReplacement;
 use crate::swap_in::swap::create_swap_keys;
 use crate::{
     ensure_sdk, BreezEvent, Config, FullReverseSwapInfo, PayOnchainRequest, PaymentStatus,
@@ -396,7 +399,14 @@ impl BTCSendSwap {
     }
 
     /// Builds and signs claim tx
-    async fn create_claim_tx(&self, rs: &FullReverseSwapInfo) -> Result<Transaction> {
+    ///
+    /// The claim tx pays the fee rate chosen when creating the reverse swap, unless a higher fee
+    /// rate is given to replace an unconfirmed claim tx.
+    async fn create_claim_tx(
+        &self,
+        rs: &FullReverseSwapInfo,
+        claim_tx_feerate: Option<u32>,
+    ) -> Result<Transaction> {
         let lockup_addr = rs.get_lockup_address(self.config.network)?;
         let claim_addr = Address::from_str(&rs.claim_pubkey)?;
         let redeem_script = Script::from_hex(&rs.redeem_script)?;
@@ -430,9 +440,14 @@ impl BTCSendSwap {
                 debug!("Claim tx amount: {claim_amount_sat} sat");
 
                 // Calculate amount sent in a backward compatible way
-                let tx_out_value = match rs.sat_per_vbyte {
+                let tx_out_value = match claim_tx_feerate.or(rs.sat_per_vbyte) {
                     Some(claim_tx_feerate) => {
-                        claim_amount_sat - Self::calculate_claim_tx_fee(claim_tx_feerate)?
+                        let claim_tx_fee = Self::calculate_claim_tx_fee(claim_tx_feerate)?;
+                        ensure!(
+                            claim_tx_fee < claim_amount_sat,
+                            "Cannot create claim tx: the fee exceeds the locked amount"
+                        );
+                        claim_amount_sat - claim_tx_fee
                     }
                     None => rs.receive_amount_sat.ok_or(anyhow!(
                         "Cannot create claim tx: no claim feerate or receive amount found"
@@ -659,7 +674,8 @@ impl BTCSendSwap {
             // (Re-)Broadcast the claim tx for monitored reverse swaps that have a confirmed lockup tx
             let broadcasted_claim_tx = if matches!(lock_tx_status, TxStatus::Confirmed) {
                 info!("Lock tx is confirmed, preparing claim tx");
-                let claim_tx = self.create_claim_tx(&rsi).await?;
+                let claim_tx_feerate = self.bumped_claim_tx_feerate(&rsi)?;
+                let claim_tx = self.create_claim_tx(&rsi, claim_tx_feerate).await?;
                 let claim_tx_broadcast_res = self
                     .chain_service
                     .broadcast_transaction(serialize(&claim_tx))
@@ -679,13 +695,15 @@ impl BTCSendSwap {
                     .update_reverse_swap_lockup_txid(&rsi.id, lockup_tx.map(|tx| tx.txid))?;
                 self.emit_reverse_swap_updated(&rsi.id).await?;
             }
-            if rsi.cache.claim_txid.is_none() {
-                self.persister.update_reverse_swap_claim_txid(
-                    &rsi.id,
-                    claim_tx
-                        .map(|tx| tx.txid)
-                        .or(broadcasted_claim_tx.map(|tx| tx.txid().to_string())),
-                )?;
+            // The cached claim txid is also updated when the claim tx was replaced
+            let claim_txid = claim_tx
+                .map(|tx| tx.txid)
+                .or(broadcasted_claim_tx.map(|tx| tx.txid().to_string()));
+            if rsi.cache.claim_txid.is_none()
+                || (claim_txid.is_some() && claim_txid != rsi.cache.claim_txid)
+            {
+                self.persister
+                    .update_reverse_swap_claim_txid(&rsi.id, claim_txid)?;
                 self.emit_reverse_swap_updated(&rsi.id).await?;
             }
         }
@@ -693,6 +711,66 @@ impl BTCSendSwap {
         Ok(())
     }
 
+    /// The fee rate of the claim tx of the reverse swap, if it replaced a claim tx with a lower fee
+    fn bumped_claim_tx_feerate(&self, rsi: &FullReverseSwapInfo) -> Result<Option<u32>> {
+        Ok(match &rsi.cache.claim_txid {
+            Some(claim_txid) => self
+                .persister
+                .get_tx_replacement(claim_txid)?
+                .map(|replacement| replacement.sat_per_vbyte),
+            None => None,
+        })
+    }
+
+    /// Replaces the unconfirmed claim tx of a reverse swap by one paying the given fee rate (RBF).
+    ///
+    /// Returns the txid of the replacement, or `None` if the transaction doesn't claim any
+    /// reverse swap.
+    pub(crate) async fn bump_claim_tx(
+        &self,
+        txid: &str,
+        sat_per_vbyte: u32,
+    ) -> Result<Option<String>> {
+        let rsi = match self
+            .list_monitored()
+            .await?
+            .into_iter()
+            .find(|rsi| rsi.cache.claim_txid.as_deref() == Some(txid))
+        {
+            Some(rsi) => rsi,
+            None => return Ok(None),
+        };
+
+        let replaced_tx = self
+            .get_claim_tx(&rsi)
+            .await?
+            .filter(|tx| tx.txid == txid)
+            .ok_or_else(|| anyhow!("Claim tx {txid} was not found"))?;
+
+        let claim_tx = self.create_claim_tx(&rsi, Some(sat_per_vbyte)).await?;
+        validate_replacement(
+            &replaced_tx,
+            replacement_fee(&replaced_tx, &claim_tx)?,
+            claim_tx.weight() as u64,
+        )?;
+
+        let claim_txid = self
+            .chain_service
+            .broadcast_transaction(serialize(&claim_tx))
+            .await?;
+        info!("Claim tx {txid} was replaced by {claim_txid}");
+        self.persister.insert_tx_replacement(&TxReplacement::new(
+            claim_txid.clone(),
+            txid,
+            sat_per_vbyte,
+        ))?;
+
+        self.persister
+            .update_reverse_swap_claim_txid(&rsi.id, Some(claim_txid.clone()))?;
+        self.emit_reverse_swap_updated(&rsi.id).await?;
+        Ok(Some(claim_txid))
+    }
+
     pub async fn claim_reverse_swap(&self, lockup_address: String) -> ReverseSwapResult<()> {
         let rsis: Vec<FullReverseSwapInfo> = self
             .list_monitored()
@@ -760,7 +838,7 @@ impl BTCSendSwap {
                 .map(|lockup_tx| lockup_tx.txid),
             claim_txid: match full_rsi.cache.status {
                 CompletedSeen | CompletedConfirmed => self
-                    .create_claim_tx(&full_rsi)
+                    .create_claim_tx(&full_rsi, self.bumped_claim_tx_feerate(&full_rsi)?)
                     .await
                     .ok()
                     .map(|claim_tx| claim_tx.txid().to_hex()),
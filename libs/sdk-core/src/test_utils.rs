@@ -1,9 +1,10 @@
 use std::collections::{HashMap, HashSet};
 use std::pin::Pin;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use std::{mem, vec};
 
-use anyhow::{Error, Result};
+use anyhow::{anyhow, Error, Result};
 use chrono::{SecondsFormat, Utc};
 use gl_client::signer::model::greenlight::amount::Unit;
 use gl_client::signer::model::greenlight::Amount;
@@ -13,7 +14,8 @@ use rand::distributions::{Alphanumeric, DistString, Standard};
 use rand::rngs::OsRng;
 use rand::{random, Rng};
 use sdk_common::grpc;
-use sdk_common::prelude::{FiatAPI, FiatCurrency, Rate};
+use sdk_common::prelude::{FiatAPI, FiatCurrency, HistoricalRate, Rate};
+use serde_json::json;
 use tokio::sync::{mpsc, watch, Mutex};
 use tokio::time::sleep;
 use tokio_stream::Stream;
@@ -33,14 +35,25 @@ use crate::chain::{ChainService, OnchainTx, Outspend, RecommendedFees, TxStatus}
 use crate::error::{ReceivePaymentError, SdkError, SdkResult};
 use crate::invoice::{InvoiceError, InvoiceResult};
 use crate::lightning::ln::PaymentSecret;
+use crate::lightning::offers::offer::OfferBuilder;
 use crate::lightning_invoice::{Currency, InvoiceBuilder, RawBolt11Invoice};
 use crate::lsp::LspInformation;
+use crate::lsps0::jsonrpc::{RpcRequest, RpcServerMessage, RpcServerMessageBody};
+use crate::lsps0::transport::LSPS0_MESSAGE_TYPE;
 use crate::models::{
-    LspAPI, NodeState, Payment, ReverseSwapServiceAPI, Swap, SwapperAPI, SyncResponse, TlvEntry,
+    CooperativeClaim, CooperativeClaimSignature, CooperativeRefundSignature, CreateOfferRequest,
+    LspAPI, NodeState, Payment, ReverseSwapServiceAPI, Swap, SwapScriptVersion, SwapperAPI,
+    SyncResponse, TlvEntry,
 };
-use crate::node_api::{CreateInvoiceRequest, FetchBolt11Result, NodeAPI, NodeError, NodeResult};
+use crate::node_api::{
+    CreateInvoiceRequest, FetchBolt11Result, IncomingPaymentStream, NodeAPI, NodeError,
+    NodeFeature, NodeResult, RouteProbe, SignedOnchainTx,
+};
+use crate::nwc::event::NostrEvent;
+use crate::nwc::relay::{NostrFilter, NostrRelay};
 use crate::swap_in::error::SwapResult;
 use crate::swap_in::swap::create_submarine_swap_script;
+use crate::swap_in::taproot::TaprootSwap;
 use crate::swap_out::boltzswap::{BoltzApiCreateReverseSwapResponse, BoltzApiReverseSwapStatus};
 use crate::swap_out::error::{ReverseSwapError, ReverseSwapResult};
 use crate::{
@@ -113,6 +126,43 @@ impl BackupTransport for MockBackupTransport {
     }
 }
 
+/// An in-memory Nostr relay, forwarding the published events to the matching subscriptions
+pub struct MockNostrRelay {
+    subscriptions: std::sync::Mutex<Vec<(NostrFilter, mpsc::Sender<NostrEvent>)>>,
+}
+
+impl MockNostrRelay {
+    pub fn new() -> Self {
+        MockNostrRelay {
+            subscriptions: std::sync::Mutex::new(vec![]),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl NostrRelay for MockNostrRelay {
+    fn url(&self) -> String {
+        "wss://relay.example.com".into()
+    }
+
+    async fn publish(&self, event: NostrEvent) -> SdkResult<()> {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        subscriptions.retain(|(_, sender)| !sender.is_closed());
+        for (filter, sender) in subscriptions.iter() {
+            if filter.matches(&event) {
+                _ = sender.try_send(event.clone());
+            }
+        }
+        Ok(())
+    }
+
+    async fn subscribe(&self, filter: NostrFilter) -> SdkResult<mpsc::Receiver<NostrEvent>> {
+        let (sender, receiver) = mpsc::channel(10);
+        self.subscriptions.lock().unwrap().push((filter, sender));
+        Ok(receiver)
+    }
+}
+
 pub struct MockSwapperAPI {}
 
 #[tonic::async_trait]
@@ -122,6 +172,7 @@ impl SwapperAPI for MockSwapperAPI {
         hash: Vec<u8>,
         payer_pubkey: Vec<u8>,
         _node_pubkey: String,
+        script_version: SwapScriptVersion,
     ) -> SwapResult<Swap> {
         let mut swapper_priv_key_raw = [2; 32];
         rand::thread_rng().fill(&mut swapper_priv_key_raw);
@@ -133,9 +184,19 @@ impl SwapperAPI for MockSwapperAPI {
             .serialize()
             .to_vec();
 
-        let script =
-            create_submarine_swap_script(hash, swapper_pub_key.clone(), payer_pubkey, 144).unwrap();
-        let address = crate::bitcoin::Address::p2wsh(&script, crate::bitcoin::Network::Bitcoin);
+        let address = match script_version {
+            SwapScriptVersion::P2wsh => {
+                let script =
+                    create_submarine_swap_script(hash, swapper_pub_key.clone(), payer_pubkey, 144)
+                        .unwrap();
+                crate::bitcoin::Address::p2wsh(&script, crate::bitcoin::Network::Bitcoin)
+            }
+            SwapScriptVersion::Taproot => {
+                TaprootSwap::new(&hash, &swapper_pub_key, &payer_pubkey, 144)
+                    .unwrap()
+                    .address(crate::bitcoin::Network::Bitcoin)
+            }
+        };
 
         Ok(Swap {
             bitcoin_address: address.to_string(),
@@ -151,6 +212,27 @@ impl SwapperAPI for MockSwapperAPI {
     async fn complete_swap(&self, _bolt11: String) -> Result<()> {
         Ok(())
     }
+
+    async fn get_cooperative_claim(&self, _bitcoin_address: String) -> Result<CooperativeClaim> {
+        Err(anyhow!("Cooperative claim not supported"))
+    }
+
+    async fn sign_cooperative_claim(
+        &self,
+        _bitcoin_address: String,
+        _signatures: Vec<CooperativeClaimSignature>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn request_cooperative_refund(
+        &self,
+        _bitcoin_address: String,
+        _refund_tx: Vec<u8>,
+        _pub_nonces: Vec<Vec<u8>>,
+    ) -> Result<Vec<CooperativeRefundSignature>> {
+        Err(anyhow!("Cooperative refund not supported"))
+    }
 }
 
 pub struct MockReverseSwapperAPI {}
@@ -330,6 +412,10 @@ pub struct MockNodeAPI {
 
 #[tonic::async_trait]
 impl NodeAPI for MockNodeAPI {
+    fn unsupported_features(&self) -> Vec<NodeFeature> {
+        vec![]
+    }
+
     fn node_credentials(&self) -> NodeResult<Option<NodeCredentials>> {
         Err(NodeError::Generic("Not implemented".to_string()))
     }
@@ -376,6 +462,7 @@ impl NodeAPI for MockNodeAPI {
         bolt11: String,
         _amount_msat: Option<u64>,
         _label: Option<String>,
+        _max_fee_msat: Option<u64>,
     ) -> NodeResult<Payment> {
         let payment = self.add_dummy_payment_for(bolt11, None, None).await?;
         Ok(payment)
@@ -392,6 +479,31 @@ impl NodeAPI for MockNodeAPI {
         Ok(payment)
     }
 
+    async fn create_offer(&self, req: CreateOfferRequest) -> NodeResult<String> {
+        let secret_key = SecretKey::from_slice(&rand_vec_u8(32))
+            .map_err(|e| NodeError::Generic(e.to_string()))?;
+        let signing_pubkey = PublicKey::from_secret_key(&Secp256k1::new(), &secret_key);
+        let mut builder = OfferBuilder::new(req.description, signing_pubkey);
+        if let Some(amount_msat) = req.amount_msat {
+            builder = builder.amount_msats(amount_msat);
+        }
+        let offer = builder
+            .build()
+            .map_err(|e| NodeError::Generic(format!("{e:?}")))?;
+        Ok(offer.to_string())
+    }
+
+    async fn pay_offer(
+        &self,
+        _offer: String,
+        _amount_msat: Option<u64>,
+        _payer_note: Option<String>,
+        _label: Option<String>,
+    ) -> NodeResult<Payment> {
+        let payment = self.add_dummy_payment_rand().await?;
+        Ok(payment)
+    }
+
     async fn start(&self) -> NodeResult<String> {
         Ok("".to_string())
     }
@@ -404,6 +516,28 @@ impl NodeAPI for MockNodeAPI {
         Ok(rand_vec_u8(32))
     }
 
+    async fn sign_replacement_redeem(
+        &self,
+        _utxos: Vec<crate::bitcoin::OutPoint>,
+        _to_address: String,
+        _sat_per_vbyte: u32,
+    ) -> NodeResult<SignedOnchainTx> {
+        Err(NodeError::Generic("Not implemented".to_string()))
+    }
+
+    async fn send_signed_psbt(&self, _psbt: String) -> NodeResult<Vec<u8>> {
+        Ok(rand_vec_u8(32))
+    }
+
+    async fn cpfp_onchain_funds(
+        &self,
+        _utxos: Vec<crate::bitcoin::OutPoint>,
+        _to_address: String,
+        _sat_per_vbyte: u32,
+    ) -> NodeResult<Vec<u8>> {
+        Ok(rand_vec_u8(32))
+    }
+
     async fn prepare_redeem_onchain_funds(
         &self,
         _req: PrepareRedeemOnchainFundsRequest,
@@ -439,9 +573,7 @@ impl NodeAPI for MockNodeAPI {
     async fn close_peer_channels(&self, _node_id: String) -> NodeResult<Vec<String>> {
         Ok(vec![])
     }
-    async fn stream_incoming_payments(
-        &self,
-    ) -> NodeResult<Streaming<gl_client::signer::model::greenlight::IncomingPayment>> {
+    async fn stream_incoming_payments(&self) -> NodeResult<IncomingPaymentStream> {
         Err(NodeError::Generic("Not implemented".to_string()))
     }
 
@@ -472,6 +604,17 @@ impl NodeAPI for MockNodeAPI {
         Err(NodeError::Generic("Not implemented".to_string()))
     }
 
+    async fn probe_route(
+        &self,
+        _payee_node_id: Vec<u8>,
+        _amount_msat: u64,
+        _final_cltv_delta: u64,
+        _max_hops: u32,
+        _last_hop: Option<&RouteHintHop>,
+    ) -> NodeResult<Option<RouteProbe>> {
+        Ok(None)
+    }
+
     fn derive_bip32_key(&self, _path: Vec<ChildNumber>) -> NodeResult<ExtendedPrivKey> {
         Ok(ExtendedPrivKey::new_master(Network::Bitcoin, &[])?)
     }
@@ -625,6 +768,58 @@ impl MockNodeAPI {
     pub async fn set_on_stream_custom_messages(&mut self, f: mpsc::Receiver<CustomMessage>) {
         *self.on_stream_custom_messages.lock().await = f;
     }
+
+    /// Makes every peer answer LSPS0 requests like an LSPS2-compliant LSP, offering the given
+    /// fee params and returning `jit_channel_scid` when a channel is bought.
+    pub async fn set_lsps2_responses(
+        &mut self,
+        opening_fee_params: grpc::OpeningFeeParams,
+        jit_channel_scid: &str,
+    ) {
+        let (tx, rx) = mpsc::channel(1);
+        let tx = Arc::new(tx);
+        let jit_channel_scid = jit_channel_scid.to_string();
+        let on_send_request = move |message: CustomMessage| {
+            let req = serde_json::from_slice::<RpcRequest<serde_json::Value>>(&message.payload)?;
+            let result = match req.method.as_str() {
+                "lsps0.list_protocols" => json!({ "protocols": [2] }),
+                "lsps2.get_info" => json!({
+                    "opening_fee_params_menu": [{
+                        "min_fee_msat": opening_fee_params.min_msat.to_string(),
+                        "proportional": opening_fee_params.proportional,
+                        "valid_until": opening_fee_params.valid_until,
+                        "min_lifetime": opening_fee_params.max_idle_time,
+                        "max_client_to_self_delay": opening_fee_params.max_client_to_self_delay,
+                        "promise": opening_fee_params.promise,
+                    }],
+                    "min_payment_size_msat": "1000",
+                    "max_payment_size_msat": "1000000000",
+                }),
+                "lsps2.buy" => json!({
+                    "jit_channel_scid": jit_channel_scid,
+                    "lsp_cltv_expiry_delta": 144,
+                    "client_trusts_lsp": false,
+                }),
+                method => return Err(NodeError::Generic(format!("Unexpected method {method}"))),
+            };
+            let resp = RpcServerMessage {
+                jsonrpc: req.jsonrpc,
+                body: RpcServerMessageBody::Response { id: req.id, result },
+            };
+            let msg = CustomMessage {
+                peer_id: message.peer_id,
+                message_type: LSPS0_MESSAGE_TYPE,
+                payload: serde_json::to_vec(&resp)?,
+            };
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let _ = tx.send(msg).await;
+            });
+            Ok(())
+        };
+        self.set_on_send_custom_message(Box::new(on_send_request));
+        self.set_on_stream_custom_messages(rx).await;
+    }
 }
 
 pub struct MockBreezServer {}
@@ -707,6 +902,24 @@ impl FiatAPI for MockBreezServer {
             value: 20_000.00,
         }])
     }
+
+    async fn fetch_historical_rates(
+        &self,
+        _currency: &str,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<HistoricalRate>> {
+        Ok(vec![
+            HistoricalRate {
+                timestamp: from,
+                value: 20_000.00,
+            },
+            HistoricalRate {
+                timestamp: to,
+                value: 30_000.00,
+            },
+        ])
+    }
 }
 
 pub struct MockBuyBitcoinService {}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
+
+use log::Record;
+use once_cell::sync::Lazy;
+use tokio::task::JoinHandle;
+
+use crate::breez_services::BreezServicesResult;
+use crate::error::{ConnectError, SdkError, SdkResult};
+use crate::*;
+
+tokio::task_local! {
+    /// The id of the wallet the current task runs for, used to route log statements
+    static WALLET_ID: String;
+}
+
+/// The log streams of the wallets. Logging is process-wide, so the streams can't be owned by a
+/// [WalletManager].
+static WALLET_LOG_STREAMS: Lazy<RwLock<HashMap<String, Box<dyn LogStream>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Runs the future in the context of the wallet, so that its log statements are sent to the
+/// wallet log stream. Tasks spawned by the SDK from this future inherit the context.
+pub async fn with_wallet_context<F: Future>(wallet_id: String, future: F) -> F::Output {
+    WALLET_ID.scope(wallet_id, future).await
+}
+
+/// The id of the wallet the current task runs for, if any
+pub(crate) fn current_wallet_id() -> Option<String> {
+    WALLET_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Runs the future in the context of the wallet if any, like the one of the task that started
+/// the thread running it
+pub(crate) async fn with_optional_wallet_context<F: Future>(
+    wallet_id: Option<String>,
+    future: F,
+) -> F::Output {
+    match wallet_id {
+        Some(wallet_id) => WALLET_ID.scope(wallet_id, future).await,
+        None => future.await,
+    }
+}
+
+/// Spawns the future as a new task that keeps the wallet context of the current task
+pub(crate) fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    match current_wallet_id() {
+        Some(wallet_id) => tokio::spawn(WALLET_ID.scope(wallet_id, future)),
+        None => tokio::spawn(future),
+    }
+}
+
+/// Sends the record to the log stream of the wallet it was emitted for, if any.
+///
+/// This is done by the logger set by [BreezServices::init_logging]. Apps that set their own
+/// global logger should call it from their logger to support per-wallet log streams.
+pub fn log_to_wallet_stream(record: &Record) {
+    if let Some(wallet_id) = current_wallet_id() {
+        if let Some(log_stream) = WALLET_LOG_STREAMS.read().unwrap().get(&wallet_id) {
+            log_stream.log(LogEntry {
+                line: record.args().to_string(),
+                level: record.level().as_str().to_string(),
+            });
+        }
+    }
+}
+
+/// An event emitted by one of the wallets hosted in the process
+#[derive(Clone, Debug, PartialEq)]
+pub struct WalletEvent {
+    pub wallet_id: String,
+    pub event: BreezEvent,
+}
+
+/// Hosts several independent wallets in the same process.
+///
+/// Each wallet is a [BreezServices] instance with its own working dir, storage, event listener
+/// and log stream, identified by an app-defined wallet id. The calls to a wallet are made on the instance
+/// returned by [WalletManager::get_wallet].
+#[derive(Default)]
+pub struct WalletManager {
+    wallets: Mutex<HashMap<String, Wallet>>,
+}
+
+struct Wallet {
+    breez_services: Arc<BreezServices>,
+    working_dir: String,
+}
+
+impl WalletManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connects the wallet, as in [BreezServices::connect], with the approver of its payments if
+    /// any.
+    ///
+    /// The wallet id must not be in use, and the working dir in the request config must not be
+    /// used by another connected wallet.
+    pub async fn connect(
+        &self,
+        wallet_id: String,
+        req: ConnectRequest,
+        event_listener: Box<dyn EventListener>,
+        payment_approver: Option<Box<dyn PaymentApprover>>,
+    ) -> BreezServicesResult<Arc<BreezServices>> {
+        let working_dir = req.config.working_dir.clone();
+        self.ensure_available(&wallet_id, &working_dir)?;
+        let breez_services = with_wallet_context(wallet_id.clone(), async move {
+            match payment_approver {
+                Some(payment_approver) => {
+                    BreezServices::connect_with_payment_approver(
+                        req,
+                        event_listener,
+                        payment_approver,
+                    )
+                    .await
+                }
+                None => BreezServices::connect(req, event_listener).await,
+            }
+        })
+        .await?;
+
+        // Another wallet may have been connected in the meantime
+        if let Err(e) = self.ensure_available(&wallet_id, &working_dir) {
+            _ = breez_services.disconnect().await;
+            return Err(e);
+        }
+        self.wallets.lock().unwrap().insert(
+            wallet_id,
+            Wallet {
+                breez_services: breez_services.clone(),
+                working_dir,
+            },
+        );
+        Ok(breez_services)
+    }
+
+    fn ensure_available(&self, wallet_id: &str, working_dir: &str) -> BreezServicesResult<()> {
+        let wallets = self.wallets.lock().unwrap();
+        ensure_sdk!(
+            !wallets.contains_key(wallet_id),
+            ConnectError::Generic {
+                err: format!("Wallet {wallet_id} is already connected"),
+            }
+        );
+        ensure_sdk!(
+            wallets
+                .values()
+                .all(|wallet| wallet.working_dir != working_dir),
+            ConnectError::Generic {
+                err: format!("Working dir {working_dir} is used by another wallet"),
+            }
+        );
+        Ok(())
+    }
+
+    /// Disconnects the wallet and removes it from the manager
+    pub async fn disconnect(&self, wallet_id: &str) -> SdkResult<()> {
+        let breez_services = self.get_wallet(wallet_id)?;
+        with_wallet_context(wallet_id.to_string(), breez_services.disconnect()).await?;
+
+        self.wallets.lock().unwrap().remove(wallet_id);
+        Ok(())
+    }
+
+    /// The ids of the connected wallets, sorted
+    pub fn list_wallets(&self) -> Vec<String> {
+        let mut wallet_ids: Vec<String> = self.wallets.lock().unwrap().keys().cloned().collect();
+        wallet_ids.sort();
+        wallet_ids
+    }
+
+    pub fn get_wallet(&self, wallet_id: &str) -> SdkResult<Arc<BreezServices>> {
+        self.wallets
+            .lock()
+            .unwrap()
+            .get(wallet_id)
+            .map(|wallet| wallet.breez_services.clone())
+            .ok_or(SdkError::Generic {
+                err: format!("Wallet {wallet_id} is not connected"),
+            })
+    }
+
+    /// Sets the stream receiving the log statements of the wallet, replacing any previous one.
+    ///
+    /// The stream receives the statements emitted while connecting the wallet, by its background
+    /// tasks and by the calls made within [with_wallet_context]. A global logger forwarding to
+    /// [log_to_wallet_stream], like the one set by [BreezServices::init_logging], is required.
+    pub fn set_log_stream(wallet_id: &str, log_stream: Box<dyn LogStream>) {
+        WALLET_LOG_STREAMS
+            .write()
+            .unwrap()
+            .insert(wallet_id.to_string(), log_stream);
+    }
+
+    pub fn remove_log_stream(wallet_id: &str) {
+        WALLET_LOG_STREAMS.write().unwrap().remove(wallet_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::wallets::*;
+
+    #[tokio::test]
+    async fn test_wallet_context_is_inherited() -> Result<()> {
+        assert_eq!(current_wallet_id(), None);
+        let spawned_wallet_id = with_wallet_context("alice".into(), async {
+            spawn(async { current_wallet_id() }).await
+        })
+        .await?;
+        assert_eq!(spawned_wallet_id, Some("alice".into()));
+        assert_eq!(spawn(async { current_wallet_id() }).await?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_wallet() {
+        let manager = WalletManager::new();
+        assert!(manager.list_wallets().is_empty());
+        assert!(manager.get_wallet("alice").is_err());
+    }
+}
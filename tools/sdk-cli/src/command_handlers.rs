@@ -4,13 +4,19 @@ use std::sync::Arc;
 use anyhow::{anyhow, Context, Error, Result};
 use breez_sdk_core::InputType::{LnUrlAuth, LnUrlPay, LnUrlWithdraw};
 use breez_sdk_core::{
-    parse, BreezEvent, BreezServices, BuyBitcoinRequest, CheckMessageRequest, ConnectRequest,
-    EventListener, GreenlightCredentials, ListPaymentsRequest, LnUrlPayRequest,
-    LnUrlWithdrawRequest, MetadataFilter, PayOnchainRequest, PrepareOnchainPaymentRequest,
-    PrepareRedeemOnchainFundsRequest, PrepareRefundRequest, ReceiveOnchainRequest,
-    ReceivePaymentRequest, RedeemOnchainFundsRequest, RefundRequest, ReportIssueRequest,
+    parse, AccelerateSwapDepositRequest, BreezEvent, BreezServices, BudgetPeriod, BumpFeeRequest,
+    BuyBitcoinRequest, CheckMessageRequest, ConnectRequest, CreateOfferRequest, EventListener,
+    ExportPaymentsRequest, FiatAmount, GreenlightCredentials, LightningSweepPolicy,
+    ListPaymentRequestsRequest, ListPaymentsRequest, LnUrlPayRequest, LnUrlWithdrawRequest,
+    MetadataFilter, OnchainSweepPolicy, PayOfferRequest, PayOnchainRequest,
+    PrepareAccelerateSwapDepositRequest, PrepareOnchainPaymentRequest,
+    PrepareRedeemOnchainFundsRequest, PrepareRefundAllRequest, PrepareRefundRequest,
+    PrepareSendPaymentRequest, ReceiveOnchainRequest, ReceivePaymentRequest,
+    RedeemOnchainFundsRequest, RefundAllRequest, RefundRequest, ReportIssueRequest,
     ReportPaymentFailureDetails, ReverseSwapFeesRequest, SendOnchainRequest, SendPaymentRequest,
-    SendSpontaneousPaymentRequest, SignMessageRequest, StaticBackupRequest, SwapAmountType,
+    SendSpontaneousPaymentRequest, SetPaymentAnnotationsRequest, SignMessageRequest,
+    SpendingBudget, SpendingLimit, StaticBackupRequest, SwapAmountType, SwapScriptVersion,
+    SweepPolicy,
 };
 use breez_sdk_core::{GreenlightNodeConfig, NodeConfig};
 use once_cell::sync::OnceCell;
@@ -119,6 +125,28 @@ pub(crate) async fn handle_command(
             .await?;
             Ok("Node was connected successfully".to_string())
         }
+        Commands::ConnectCln {
+            grpc_url,
+            certs_dir,
+        } => {
+            let mut config = persistence
+                .get_or_create_config()?
+                .to_sdk_config(&persistence.data_dir);
+            config.node_config = NodeConfig::Cln {
+                grpc_url,
+                ca_cert: fs::read(certs_dir.join("ca.pem"))?,
+                client_cert: fs::read(certs_dir.join("client.pem"))?,
+                client_key: fs::read(certs_dir.join("client-key.pem"))?,
+            };
+
+            connect(ConnectRequest {
+                config,
+                seed: persistence.get_or_create_seed(),
+                restore_only: None,
+            })
+            .await?;
+            Ok("Node was connected successfully".to_string())
+        }
         Commands::Sync {} => {
             sdk()?.sync().await?;
             Ok("Sync finished successfully".to_string())
@@ -133,6 +161,8 @@ pub(crate) async fn handle_command(
             use_description_hash,
             expiry,
             cltv,
+            fiat_amount,
+            currency,
         } => {
             let recv_payment_response = sdk()?
                 .receive_payment(ReceivePaymentRequest {
@@ -141,6 +171,7 @@ pub(crate) async fn handle_command(
                     use_description_hash,
                     expiry,
                     cltv,
+                    fiat_amount: lock_fiat_amount(fiat_amount, currency).await?,
                     ..Default::default()
                 })
                 .await?;
@@ -149,6 +180,28 @@ pub(crate) async fn handle_command(
             result.push_str(&build_qr_text(&recv_payment_response.ln_invoice.bolt11));
             Ok(result)
         }
+        Commands::ListPaymentRequests {
+            statuses,
+            from_timestamp,
+            to_timestamp,
+            limit,
+            offset,
+        } => {
+            let requests = sdk()?
+                .list_payment_requests(ListPaymentRequestsRequest {
+                    statuses,
+                    from_timestamp,
+                    to_timestamp,
+                    offset,
+                    limit,
+                })
+                .await?;
+            serde_json::to_string_pretty(&requests).map_err(|e| e.into())
+        }
+        Commands::CancelPaymentRequest { payment_hash } => {
+            sdk()?.cancel_payment_request(payment_hash).await?;
+            Ok("Payment request was cancelled successfully".to_string())
+        }
         Commands::SendOnchain {
             amount_sat,
             onchain_recipient_address,
@@ -245,16 +298,33 @@ pub(crate) async fn handle_command(
             bolt11,
             amount_msat,
             label,
+            max_fee_msat,
+            fiat_amount,
+            currency,
         } => {
             let payment = sdk()?
                 .send_payment(SendPaymentRequest {
                     bolt11,
                     amount_msat,
                     label,
+                    max_fee_msat,
+                    fiat_amount: lock_fiat_amount(fiat_amount, currency).await?,
                 })
                 .await?;
             serde_json::to_string_pretty(&payment).map_err(|e| e.into())
         }
+        Commands::PrepareSendPayment {
+            bolt11,
+            amount_msat,
+        } => {
+            let res = sdk()?
+                .prepare_send_payment(PrepareSendPaymentRequest {
+                    bolt11,
+                    amount_msat,
+                })
+                .await?;
+            serde_json::to_string_pretty(&res).map_err(|e| e.into())
+        }
         Commands::SendSpontaneousPayment {
             node_id,
             amount_msat,
@@ -270,6 +340,37 @@ pub(crate) async fn handle_command(
                 .await?;
             serde_json::to_string_pretty(&response.payment).map_err(|e| e.into())
         }
+        Commands::PayOffer {
+            offer,
+            amount_msat,
+            payer_note,
+            label,
+        } => {
+            let response = sdk()?
+                .pay_offer(PayOfferRequest {
+                    offer,
+                    amount_msat,
+                    payer_note,
+                    label,
+                })
+                .await?;
+            serde_json::to_string_pretty(&response.payment).map_err(|e| e.into())
+        }
+        Commands::CreateOffer {
+            description,
+            amount_msat,
+        } => {
+            let response = sdk()?
+                .create_offer(CreateOfferRequest {
+                    description,
+                    amount_msat,
+                })
+                .await?;
+            let mut result = serde_json::to_string_pretty(&response.offer)?;
+            result.push('\n');
+            result.push_str(&build_qr_text(&response.offer.offer));
+            Ok(result)
+        }
         Commands::ListPayments {
             from_timestamp,
             to_timestamp,
@@ -277,6 +378,15 @@ pub(crate) async fn handle_command(
             limit,
             offset,
             metadata_filters: metadata_filters_raw,
+            statuses,
+            min_amount_msat,
+            max_amount_msat,
+            counterparty_node_id,
+            tags,
+            category,
+            search,
+            sort_order,
+            count,
         } => {
             let metadata_filters = match metadata_filters_raw {
                 Some(raw_filters) => {
@@ -297,18 +407,55 @@ pub(crate) async fn handle_command(
                 None => None,
             };
 
-            let payments = sdk()?
-                .list_payments(ListPaymentsRequest {
-                    filters: None,
-                    metadata_filters,
-                    from_timestamp,
-                    to_timestamp,
-                    include_failures: Some(include_failures),
-                    limit,
-                    offset,
+            let req = ListPaymentsRequest {
+                filters: None,
+                metadata_filters,
+                from_timestamp,
+                to_timestamp,
+                include_failures: Some(include_failures),
+                statuses,
+                min_amount_msat,
+                max_amount_msat,
+                counterparty_node_id,
+                tags,
+                category,
+                search,
+                sort_order,
+                limit,
+                offset,
+            };
+            match count {
+                true => Ok(sdk()?.count_payments(req).await?.to_string()),
+                false => {
+                    let payments = sdk()?.list_payments(req).await?;
+                    serde_json::to_string_pretty(&payments).map_err(|e| e.into())
+                }
+            }
+        }
+        Commands::ExportPayments {
+            format,
+            file,
+            from_timestamp,
+            to_timestamp,
+            include_failures,
+            fiat_currency,
+            use_coingecko_rates,
+        } => {
+            let res = sdk()?
+                .export_payments(ExportPaymentsRequest {
+                    format,
+                    list_payments_request: ListPaymentsRequest {
+                        from_timestamp,
+                        to_timestamp,
+                        include_failures: Some(include_failures),
+                        ..Default::default()
+                    },
+                    fiat_currency,
+                    use_coingecko_rates,
                 })
                 .await?;
-            serde_json::to_string_pretty(&payments).map_err(|e| e.into())
+            fs::write(&file, res.content)?;
+            Ok(format!("Payments exported to {}", file.display()))
         }
         Commands::SetPaymentMetadata {
             payment_hash,
@@ -318,6 +465,27 @@ pub(crate) async fn handle_command(
 
             Ok("Payment metadata was set successfully".to_string())
         }
+        Commands::SetPaymentAnnotations {
+            payment_hash,
+            note,
+            tags,
+            category,
+        } => {
+            sdk()?
+                .set_payment_annotations(SetPaymentAnnotationsRequest {
+                    payment_hash,
+                    note,
+                    tags: tags.unwrap_or_default(),
+                    category,
+                })
+                .await?;
+
+            Ok("Payment annotations were set successfully".to_string())
+        }
+        Commands::ListTags {} => {
+            let tags = sdk()?.list_tags().await?;
+            serde_json::to_string_pretty(&tags).map_err(|e| e.into())
+        }
         Commands::PaymentByHash { hash } => {
             let payment = sdk()?.payment_by_hash(hash).await?;
             serde_json::to_string_pretty(&payment).map_err(|e| e.into())
@@ -401,12 +569,21 @@ pub(crate) async fn handle_command(
         Commands::RecommendedFees {} => {
             serde_json::to_string_pretty(&sdk()?.recommended_fees().await?).map_err(|e| e.into())
         }
-        Commands::ReceiveOnchain {} => serde_json::to_string_pretty(
-            &sdk()?
-                .receive_onchain(ReceiveOnchainRequest::default())
-                .await?,
-        )
-        .map_err(|e| e.into()),
+        Commands::ReceiveOnchain { taproot } => {
+            let script_version = match taproot {
+                true => Some(SwapScriptVersion::Taproot),
+                false => None,
+            };
+            serde_json::to_string_pretty(
+                &sdk()?
+                    .receive_onchain(ReceiveOnchainRequest {
+                        script_version,
+                        ..Default::default()
+                    })
+                    .await?,
+            )
+            .map_err(|e| e.into())
+        }
         Commands::InProgressSwap {} => {
             serde_json::to_string_pretty(&sdk()?.in_progress_swap().await?).map_err(|e| e.into())
         }
@@ -448,6 +625,160 @@ pub(crate) async fn handle_command(
                 .await?;
             Ok(format!("Refund tx: {}", res.refund_tx_id))
         }
+        Commands::CooperativeRefund {
+            swap_address,
+            to_address,
+            sat_per_vbyte,
+        } => {
+            let res = sdk()?
+                .request_cooperative_refund(RefundRequest {
+                    swap_address,
+                    to_address,
+                    sat_per_vbyte,
+                })
+                .await?;
+            Ok(format!("Refund tx: {}", res.refund_tx_id))
+        }
+        Commands::PrepareRefundAll {
+            to_address,
+            sat_per_vbyte,
+            swap_addresses,
+        } => {
+            let res = sdk()?
+                .prepare_refund_all(PrepareRefundAllRequest {
+                    to_address,
+                    sat_per_vbyte,
+                    swap_addresses: (!swap_addresses.is_empty()).then_some(swap_addresses),
+                })
+                .await?;
+            Ok(format!(
+                "Prepared refund tx - weight: {} - fees: {} sat",
+                res.refund_tx_weight, res.refund_tx_fee_sat
+            ))
+        }
+        Commands::RefundAll {
+            to_address,
+            sat_per_vbyte,
+            swap_addresses,
+        } => {
+            let res = sdk()?
+                .refund_all(RefundAllRequest {
+                    to_address,
+                    sat_per_vbyte,
+                    swap_addresses: (!swap_addresses.is_empty()).then_some(swap_addresses),
+                })
+                .await?;
+            Ok(format!("Refund tx: {}", res.refund_tx_id))
+        }
+        Commands::BumpFee {
+            txid,
+            sat_per_vbyte,
+        } => {
+            let res = sdk()?
+                .bump_fee(BumpFeeRequest {
+                    txid,
+                    sat_per_vbyte,
+                })
+                .await?;
+            Ok(format!("Replacement tx: {}", res.txid))
+        }
+        Commands::PrepareAccelerateSwapDeposit {
+            swap_address,
+            target_sat_per_vbyte,
+            refund_address,
+        } => {
+            let res = sdk()?
+                .prepare_accelerate_swap_deposit(PrepareAccelerateSwapDepositRequest {
+                    swap_address,
+                    target_sat_per_vbyte,
+                    refund_address,
+                })
+                .await?;
+            Ok(format!(
+                "Prepared child tx - source: {:?} - weight: {} - fees: {} sat",
+                res.source, res.child_tx_weight, res.child_tx_fee_sat
+            ))
+        }
+        Commands::AccelerateSwapDeposit {
+            swap_address,
+            target_sat_per_vbyte,
+            refund_address,
+        } => {
+            let res = sdk()?
+                .accelerate_swap_deposit(AccelerateSwapDepositRequest {
+                    swap_address,
+                    target_sat_per_vbyte,
+                    refund_address,
+                })
+                .await?;
+            Ok(format!("Child tx: {}", res.txid))
+        }
+        Commands::SetSweepPolicy {
+            max_sat_per_vbyte,
+            onchain_address,
+            max_onchain_balance_sat,
+            lightning_address,
+            max_channels_balance_sat,
+        } => {
+            let policy = SweepPolicy {
+                onchain: onchain_address.map(|to_address| OnchainSweepPolicy {
+                    to_address,
+                    max_onchain_balance_sat: max_onchain_balance_sat.unwrap_or_default(),
+                    max_sat_per_vbyte,
+                }),
+                lightning: lightning_address.map(|to_address| LightningSweepPolicy {
+                    to_address,
+                    max_channels_balance_sat: max_channels_balance_sat.unwrap_or_default(),
+                    max_sat_per_vbyte,
+                }),
+            };
+            sdk()?.set_sweep_policy(policy).await?;
+            Ok("Sweep policy was set".to_string())
+        }
+        Commands::SweepPolicy {} => {
+            serde_json::to_string_pretty(&sdk()?.sweep_policy().await?).map_err(|e| e.into())
+        }
+        Commands::SetSpendingBudget {
+            max_payment_msat,
+            daily_msat,
+            weekly_msat,
+            monthly_msat,
+            approval_threshold_msat,
+        } => {
+            let limits = [
+                (BudgetPeriod::Daily, daily_msat),
+                (BudgetPeriod::Weekly, weekly_msat),
+                (BudgetPeriod::Monthly, monthly_msat),
+            ]
+            .into_iter()
+            .filter_map(|(period, max_amount_msat)| {
+                max_amount_msat.map(|max_amount_msat| SpendingLimit {
+                    period,
+                    max_amount_msat,
+                })
+            })
+            .collect();
+            let budget = SpendingBudget {
+                max_payment_msat,
+                limits,
+                approval_threshold_msat,
+            };
+            sdk()?.set_spending_budget(budget).await?;
+            match sdk()?.pending_spending_budget().await? {
+                Some(pending) => Ok(format!(
+                    "Spending budget loosens the current one, it applies at {}",
+                    pending.applies_at
+                )),
+                None => Ok("Spending budget was set".to_string()),
+            }
+        }
+        Commands::SpendingBudget {} => {
+            let res = serde_json::json!({
+                "budget": sdk()?.spending_budget().await?,
+                "pending": sdk()?.pending_spending_budget().await?,
+            });
+            serde_json::to_string_pretty(&res).map_err(|e| e.into())
+        }
         Commands::SignMessage { message } => {
             let req = SignMessageRequest { message };
             let res = sdk()?.sign_message(req).await?;
@@ -609,6 +940,20 @@ pub(crate) async fn handle_command(
     }
 }
 
+/// Converts a fiat amount at the live rate of its currency
+async fn lock_fiat_amount(
+    amount: Option<String>,
+    currency: Option<String>,
+) -> Result<Option<FiatAmount>> {
+    match (amount, currency) {
+        (Some(amount), Some(currency)) => Ok(Some(FiatAmount {
+            amount,
+            rate: sdk()?.lock_fiat_rate(currency).await?,
+        })),
+        _ => Ok(None),
+    }
+}
+
 fn build_qr_text(text: &str) -> String {
     QrCode::with_error_correction_level(text, EcLevel::L)
         .unwrap()
@@ -1,4 +1,7 @@
-use breez_sdk_core::{BuyBitcoinProvider, EnvironmentType};
+use breez_sdk_core::{
+    BuyBitcoinProvider, EnvironmentType, ExportFormat, PaymentRequestStatus, PaymentSortOrder,
+    PaymentStatus,
+};
 use clap::Parser;
 
 #[derive(Parser, Debug)]
@@ -40,6 +43,15 @@ pub(crate) enum Commands {
         restore_only: bool,
     },
 
+    /// [init] Connect to the sdk services using a self-hosted Core Lightning node
+    ConnectCln {
+        /// The URL of the node's cln-grpc interface
+        grpc_url: String,
+
+        /// The directory containing the ca.pem, client.pem and client-key.pem files generated by cln-grpc
+        certs_dir: std::path::PathBuf,
+    },
+
     /// [pay] Send a lightning payment
     SendPayment {
         bolt11: String,
@@ -50,6 +62,31 @@ pub(crate) enum Commands {
         /// The external label or identifier of the payment
         #[clap(name = "label", short = 'l', long = "label")]
         label: Option<String>,
+
+        /// The max fee to pay, in msat
+        #[clap(name = "max_fee_msat", short = 'm', long = "max_fee")]
+        max_fee_msat: Option<u64>,
+
+        /// The amount to pay in a fiat currency, like 12.50, converted at its live rate
+        #[clap(
+            name = "fiat_amount",
+            short = 'f',
+            long = "fiat",
+            requires = "currency"
+        )]
+        fiat_amount: Option<String>,
+
+        /// The currency of the fiat amount, like USD
+        #[clap(name = "currency", short = 'c', long = "currency")]
+        currency: Option<String>,
+    },
+
+    /// [pay] Quote a lightning payment: whether it is payable and its estimated fee
+    PrepareSendPayment {
+        bolt11: String,
+
+        #[clap(name = "amount_msat", short = 'a', long = "amt")]
+        amount_msat: Option<u64>,
     },
 
     /// [pay] Send a spontaneous (keysend) payment
@@ -62,6 +99,30 @@ pub(crate) enum Commands {
         label: Option<String>,
     },
 
+    /// [pay] Pay a BOLT12 offer
+    PayOffer {
+        offer: String,
+
+        #[clap(name = "amount_msat", short = 'a', long = "amt")]
+        amount_msat: Option<u64>,
+
+        /// A note for the offer issuer
+        #[clap(name = "payer_note", short = 'n', long = "note")]
+        payer_note: Option<String>,
+
+        /// The external label or identifier of the payment
+        #[clap(name = "label", short = 'l', long = "label")]
+        label: Option<String>,
+    },
+
+    /// [pay] Create a reusable BOLT12 offer
+    CreateOffer {
+        description: String,
+
+        #[clap(name = "amount_msat", short = 'a', long = "amt")]
+        amount_msat: Option<u64>,
+    },
+
     /// [pay] Generate a bolt11 invoice
     ReceivePayment {
         amount_msat: u64,
@@ -72,8 +133,48 @@ pub(crate) enum Commands {
         expiry: Option<u32>,
         #[clap(name = "cltv", short = 'c', long = "cltv")]
         cltv: Option<u32>,
+
+        /// The amount to receive in a fiat currency, in place of the amount in msat, which
+        /// should then be 0
+        #[clap(
+            name = "fiat_amount",
+            short = 'f',
+            long = "fiat",
+            requires = "currency"
+        )]
+        fiat_amount: Option<String>,
+
+        /// The currency of the fiat amount, like USD
+        #[clap(name = "currency", long = "currency")]
+        currency: Option<String>,
     },
 
+    /// [pay] List the invoices generated to receive payments, with their status
+    ListPaymentRequests {
+        /// Optional statuses of the requests: open, paid, expired or cancelled
+        #[clap(long = "status", num_args = 1..)]
+        statuses: Option<Vec<PaymentRequestStatus>>,
+
+        /// The optional from unix timestamp
+        #[clap(name = "from_timestamp", short = 'f', long = "from")]
+        from_timestamp: Option<i64>,
+
+        /// The optional to unix timestamp
+        #[clap(name = "to_timestamp", short = 't', long = "to")]
+        to_timestamp: Option<i64>,
+
+        /// Optional limit of listed requests
+        #[clap(short = 'l', long = "limit")]
+        limit: Option<u32>,
+
+        /// Optional offset in requests
+        #[clap(short = 'o', long = "offset")]
+        offset: Option<u32>,
+    },
+
+    /// [pay] Cancel an open payment request
+    CancelPaymentRequest { payment_hash: String },
+
     /// [pay] List recommended fees based on the mempool
     RecommendedFees {},
 
@@ -106,7 +207,11 @@ pub(crate) enum Commands {
     LnurlAuth { lnurl: String },
 
     /// [swap-in] Generate address to receive onchain
-    ReceiveOnchain {},
+    ReceiveOnchain {
+        /// Create a Taproot swap address
+        #[clap(short = 't', long = "taproot")]
+        taproot: bool,
+    },
 
     /// [swap-in] Get the current in-progress swap if exists
     InProgressSwap {},
@@ -131,6 +236,105 @@ pub(crate) enum Commands {
         sat_per_vbyte: u32,
     },
 
+    /// [swap-in] Refund an incomplete Taproot swap right away, co-signed by the swapper
+    CooperativeRefund {
+        swap_address: String,
+        to_address: String,
+        sat_per_vbyte: u32,
+    },
+
+    /// [swap-in] Prepare a single refund transaction for several incomplete swaps
+    PrepareRefundAll {
+        to_address: String,
+        sat_per_vbyte: u32,
+        /// The swap addresses to refund, all refundable swaps if none are given
+        swap_addresses: Vec<String>,
+    },
+
+    /// [swap-in] Broadcast a single refund transaction for several incomplete swaps
+    RefundAll {
+        to_address: String,
+        sat_per_vbyte: u32,
+        /// The swap addresses to refund, all refundable swaps if none are given
+        swap_addresses: Vec<String>,
+    },
+
+    /// Replace an unconfirmed refund, claim or redeem transaction with a higher fee one
+    BumpFee { txid: String, sat_per_vbyte: u32 },
+
+    /// [swap-in] Prepare a child transaction paying for the unconfirmed deposits of a swap
+    PrepareAccelerateSwapDeposit {
+        swap_address: String,
+        target_sat_per_vbyte: u32,
+
+        /// Where to refund the deposits if the node has no funds in them
+        #[clap(name = "refund_address", short = 'r', long = "refund_address")]
+        refund_address: Option<String>,
+    },
+
+    /// [swap-in] Broadcast a child transaction paying for the unconfirmed deposits of a swap
+    AccelerateSwapDeposit {
+        swap_address: String,
+        target_sat_per_vbyte: u32,
+
+        /// Where to refund the deposits if the node has no funds in them
+        #[clap(name = "refund_address", short = 'r', long = "refund_address")]
+        refund_address: Option<String>,
+    },
+
+    /// Set the automatic sweeps of the node funds, turning them off if no address is given
+    SetSweepPolicy {
+        /// The highest fee rate to sweep at
+        max_sat_per_vbyte: u32,
+
+        /// Where to redeem the onchain funds once they exceed the max onchain balance
+        #[clap(name = "onchain_address", short = 'o', long = "onchain_address")]
+        onchain_address: Option<String>,
+
+        #[clap(name = "max_onchain_balance_sat", long = "max_onchain_balance")]
+        max_onchain_balance_sat: Option<u64>,
+
+        /// Where to send the channels balance above the max channels balance
+        #[clap(name = "lightning_address", short = 'l', long = "lightning_address")]
+        lightning_address: Option<String>,
+
+        #[clap(name = "max_channels_balance_sat", long = "max_channels_balance")]
+        max_channels_balance_sat: Option<u64>,
+    },
+
+    /// Show the automatic sweeps of the node funds
+    SweepPolicy {},
+
+    /// Set the limits of the outgoing payments, removing them if none is given
+    SetSpendingBudget {
+        /// The maximum amount of a single payment
+        #[clap(name = "max_payment_msat", short = 'p', long = "max_payment")]
+        max_payment_msat: Option<u64>,
+
+        /// The maximum amount spent in the last 24 hours
+        #[clap(name = "daily_msat", short = 'd', long = "daily")]
+        daily_msat: Option<u64>,
+
+        /// The maximum amount spent in the last 7 days
+        #[clap(name = "weekly_msat", short = 'w', long = "weekly")]
+        weekly_msat: Option<u64>,
+
+        /// The maximum amount spent in the last 30 days
+        #[clap(name = "monthly_msat", short = 'm', long = "monthly")]
+        monthly_msat: Option<u64>,
+
+        /// Payments above this amount need an approval, which the CLI doesn't give
+        #[clap(
+            name = "approval_threshold_msat",
+            short = 'a',
+            long = "approval_threshold"
+        )]
+        approval_threshold_msat: Option<u64>,
+    },
+
+    /// Show the limits of the outgoing payments, and the ones waiting to apply
+    SpendingBudget {},
+
     /// [swap-out] Send on-chain using a reverse swap
     SendOnchain {
         amount_sat: u64,
@@ -281,6 +485,73 @@ pub(crate) enum Commands {
         /// Optional metadata filter, in the form of json_path:json_value
         #[clap(short = 'm', long = "metadata", num_args = 1..)]
         metadata_filters: Option<Vec<String>>,
+
+        /// Optional statuses of the payments: pending, complete or failed
+        #[clap(long = "status", num_args = 1..)]
+        statuses: Option<Vec<PaymentStatus>>,
+
+        /// Optional minimum amount, in msat
+        #[clap(long = "min_amount")]
+        min_amount_msat: Option<u64>,
+
+        /// Optional maximum amount, in msat
+        #[clap(long = "max_amount")]
+        max_amount_msat: Option<u64>,
+
+        /// Optional node id the payments were sent to
+        #[clap(long = "counterparty")]
+        counterparty_node_id: Option<String>,
+
+        /// Optional tags the payments must all carry
+        #[clap(long = "tag", num_args = 1..)]
+        tags: Option<Vec<String>>,
+
+        /// Optional category of the payments
+        #[clap(short = 'c', long = "category")]
+        category: Option<String>,
+
+        /// Optional text to search in the description, label, lnurl metadata, lightning address,
+        /// note, tags and category
+        #[clap(short = 's', long = "search")]
+        search: Option<String>,
+
+        /// Optional sort order by time: descending (default) or ascending
+        #[clap(long = "sort")]
+        sort_order: Option<PaymentSortOrder>,
+
+        /// Only print the number of matching payments, ignoring limit and offset
+        #[clap(long = "count")]
+        count: bool,
+    },
+
+    /// [node-mgmt] Export the payment history to a file
+    ExportPayments {
+        /// The export format: csv, koinly or cointracking
+        format: ExportFormat,
+
+        /// The file to write the export to
+        file: std::path::PathBuf,
+
+        /// The optional from unix timestamp
+        #[clap(name = "from_timestamp", short = 'f', long = "from")]
+        from_timestamp: Option<i64>,
+
+        /// The optional to unix timestamp
+        #[clap(name = "to_timestamp", short = 't', long = "to")]
+        to_timestamp: Option<i64>,
+
+        /// Include failed payments
+        #[clap(short = 'i', long = "include_failures")]
+        include_failures: bool,
+
+        /// Optional fiat currency, e.g. USD, to include the fiat value of the payments
+        #[clap(short = 'c', long = "fiat_currency")]
+        fiat_currency: Option<String>,
+
+        /// Fetch the rates of the fiat currency from CoinGecko, sharing the currency and the
+        /// time range of the payments with it
+        #[clap(long = "use_coingecko_rates")]
+        use_coingecko_rates: bool,
     },
 
     /// [node-mgmt] Set the metadata for a given payment
@@ -289,6 +560,26 @@ pub(crate) enum Commands {
         metadata: String,
     },
 
+    /// [node-mgmt] Set the note, tags and category of a given payment
+    SetPaymentAnnotations {
+        payment_hash: String,
+
+        /// Optional free-form note
+        #[clap(short = 'n', long = "note")]
+        note: Option<String>,
+
+        /// Optional tags
+        #[clap(short = 't', long = "tag", num_args = 1..)]
+        tags: Option<Vec<String>>,
+
+        /// Optional category
+        #[clap(short = 'c', long = "category")]
+        category: Option<String>,
+    },
+
+    /// [node-mgmt] List all the tags set on payments
+    ListTags {},
+
     /// [node-mgmt] Retrieve a payment by its hash
     PaymentByHash { hash: String },
 